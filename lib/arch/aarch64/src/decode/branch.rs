@@ -0,0 +1,329 @@
+//! Decoding of the branches, exception generating, and system instructions encoding group.
+
+use crate::{
+    decode::{
+        Condition, DecodeError, Instruction, Mnemonic, field,
+        operand::{BarrierOption, BranchTarget, Operand, PStateField, Register, SystemOperation},
+        register, signed_field,
+    },
+    esr::SystemRegisterEncoding,
+};
+
+/// Decodes an instruction in the branches, exception generating, and system instructions
+/// encoding group.
+pub(super) fn decode(word: u32) -> Result<Instruction, DecodeError> {
+    match field(word, 29, 3) {
+        0b000 | 0b100 => Ok(immediate(word)),
+        0b001 | 0b101 => Ok(compare_or_test(word)),
+        0b010 if field(word, 25, 1) == 0 => conditional(word),
+        0b110 => match field(word, 24, 2) {
+            0b00 => exception(word),
+            0b01 => system(word),
+            _ => register_branch(word),
+        },
+        _ => Err(DecodeError::Unallocated),
+    }
+}
+
+/// Decodes the unconditional branch (immediate) class.
+fn immediate(word: u32) -> Instruction {
+    let mnemonic = if field(word, 31, 1) == 1 {
+        Mnemonic::Bl
+    } else {
+        Mnemonic::B
+    };
+
+    Instruction::new(mnemonic).with(Operand::Label(signed_field(word, 0, 26) * 4))
+}
+
+/// Decodes the compare and branch (immediate) and test and branch (immediate) classes.
+fn compare_or_test(word: u32) -> Instruction {
+    let nonzero = field(word, 24, 1) == 1;
+    let rt = register(word, 0);
+
+    if field(word, 25, 1) == 0 {
+        let mnemonic = if nonzero {
+            Mnemonic::Cbnz
+        } else {
+            Mnemonic::Cbz
+        };
+
+        Instruction::new(mnemonic)
+            .with_register(Register::zr(rt, field(word, 31, 1) == 1))
+            .with(Operand::Label(signed_field(word, 5, 19) * 4))
+    } else {
+        let mnemonic = if nonzero {
+            Mnemonic::Tbnz
+        } else {
+            Mnemonic::Tbz
+        };
+        let bit = (field(word, 31, 1) << 5) | field(word, 19, 5);
+
+        Instruction::new(mnemonic)
+            .with_register(Register::zr(rt, bit >= 32))
+            .with(Operand::Immediate(u64::from(bit)))
+            .with(Operand::Label(signed_field(word, 5, 14) * 4))
+    }
+}
+
+/// Decodes the conditional branch (immediate) class.
+fn conditional(word: u32) -> Result<Instruction, DecodeError> {
+    if field(word, 24, 1) != 0 {
+        return Err(DecodeError::Unallocated);
+    }
+
+    let condition = Condition::from_bits((word & 0xF) as u8);
+    let mnemonic = if field(word, 4, 1) == 1 {
+        Mnemonic::BcCond(condition)
+    } else {
+        Mnemonic::BCond(condition)
+    };
+
+    Ok(Instruction::new(mnemonic).with(Operand::Label(signed_field(word, 5, 19) * 4)))
+}
+
+/// Decodes the exception generation class.
+fn exception(word: u32) -> Result<Instruction, DecodeError> {
+    let immediate = Operand::Immediate(u64::from(field(word, 5, 16)));
+
+    let mnemonic = match (field(word, 21, 3), field(word, 2, 3), field(word, 0, 2)) {
+        (0b000, 0b000, 0b01) => Mnemonic::Svc,
+        (0b000, 0b000, 0b10) => Mnemonic::Hvc,
+        (0b000, 0b000, 0b11) => Mnemonic::Smc,
+        (0b001, 0b000, 0b00) => Mnemonic::Brk,
+        (0b010, 0b000, 0b00) => Mnemonic::Hlt,
+        (0b101, 0b000, 0b01) => Mnemonic::Dcps1,
+        (0b101, 0b000, 0b10) => Mnemonic::Dcps2,
+        (0b101, 0b000, 0b11) => Mnemonic::Dcps3,
+        (0b011, 0b000, 0b00) => return Err(DecodeError::Unsupported),
+        _ => return Err(DecodeError::Unallocated),
+    };
+
+    let instruction = Instruction::new(mnemonic);
+    if matches!(
+        mnemonic,
+        Mnemonic::Dcps1 | Mnemonic::Dcps2 | Mnemonic::Dcps3
+    ) && field(word, 5, 16) == 0
+    {
+        return Ok(instruction);
+    }
+
+    Ok(instruction.with(immediate))
+}
+
+/// Decodes the system instruction classes: hints, barriers, process state writes, system
+/// instructions, and system register moves.
+fn system(word: u32) -> Result<Instruction, DecodeError> {
+    if field(word, 22, 2) != 0b00 {
+        return Err(DecodeError::Unallocated);
+    }
+
+    let read = field(word, 21, 1) == 1;
+    let op0 = field(word, 19, 2);
+    let op1 = (field(word, 16, 3) & 0b111) as u8;
+    let crn = (field(word, 12, 4) & 0xF) as u8;
+    let crm = (field(word, 8, 4) & 0xF) as u8;
+    let op2 = (field(word, 5, 3) & 0b111) as u8;
+    let rt = register(word, 0);
+
+    match (read, op0) {
+        (false, 0b00) => {
+            if rt != 31 {
+                if (op1, crn, crm) == (3, 1, 0) && op2 <= 1 {
+                    let mnemonic = if op2 == 0 {
+                        Mnemonic::Wfet
+                    } else {
+                        Mnemonic::Wfit
+                    };
+                    return Ok(Instruction::new(mnemonic).with_register(Register::zr(rt, true)));
+                }
+
+                return Err(DecodeError::Unallocated);
+            }
+
+            match (op1, crn) {
+                (3, 2) => Ok(hint((crm << 3) | op2)),
+                (3, 3) => barrier(crm, op2),
+                (_, 4) => Ok(Instruction::new(Mnemonic::Msr)
+                    .with(Operand::PStateField(PStateField { op1, op2 }))
+                    .with(Operand::Immediate(u64::from(crm)))),
+                _ => Err(DecodeError::Unallocated),
+            }
+        }
+        (_, 0b00) => Err(DecodeError::Unallocated),
+        (_, 0b01) => Ok(system_instruction(
+            read,
+            SystemOperation { op1, crn, crm, op2 },
+            rt,
+        )),
+        (read, op0) => {
+            let system_register = Operand::SystemRegister(SystemRegisterEncoding {
+                op0: (op0 & 0b11) as u8,
+                op1,
+                crn,
+                crm,
+                op2,
+            });
+
+            let instruction = if read {
+                Instruction::new(Mnemonic::Mrs)
+                    .with_register(Register::zr(rt, true))
+                    .with(system_register)
+            } else {
+                Instruction::new(Mnemonic::Msr)
+                    .with(system_register)
+                    .with_register(Register::zr(rt, true))
+            };
+            Ok(instruction)
+        }
+    }
+}
+
+/// Decodes a hint instruction with the 7-bit hint number `hint`.
+fn hint(hint: u8) -> Instruction {
+    let mnemonic = match hint {
+        0 => Mnemonic::Nop,
+        1 => Mnemonic::Yield,
+        2 => Mnemonic::Wfe,
+        3 => Mnemonic::Wfi,
+        4 => Mnemonic::Sev,
+        5 => Mnemonic::Sevl,
+        6 => Mnemonic::Dgh,
+        7 => Mnemonic::Xpaclri,
+        8 => Mnemonic::Pacia1716,
+        10 => Mnemonic::Pacib1716,
+        12 => Mnemonic::Autia1716,
+        14 => Mnemonic::Autib1716,
+        16 => Mnemonic::Esb,
+        17 => Mnemonic::PsbCsync,
+        18 => Mnemonic::TsbCsync,
+        20 => Mnemonic::Csdb,
+        24 => Mnemonic::Paciaz,
+        25 => Mnemonic::Paciasp,
+        26 => Mnemonic::Pacibz,
+        27 => Mnemonic::Pacibsp,
+        28 => Mnemonic::Autiaz,
+        29 => Mnemonic::Autiasp,
+        30 => Mnemonic::Autibz,
+        31 => Mnemonic::Autibsp,
+        32 => return Instruction::new(Mnemonic::Bti),
+        34 => {
+            return Instruction::new(Mnemonic::Bti).with(Operand::BranchTarget(BranchTarget::Call));
+        }
+        36 => {
+            return Instruction::new(Mnemonic::Bti).with(Operand::BranchTarget(BranchTarget::Jump));
+        }
+        38 => {
+            return Instruction::new(Mnemonic::Bti)
+                .with(Operand::BranchTarget(BranchTarget::JumpCall));
+        }
+        hint => return Instruction::new(Mnemonic::Hint).with(Operand::Immediate(u64::from(hint))),
+    };
+
+    Instruction::new(mnemonic)
+}
+
+/// Decodes a barrier instruction.
+fn barrier(crm: u8, op2: u8) -> Result<Instruction, DecodeError> {
+    let instruction = match op2 {
+        0b010 if crm == 0b1111 => Instruction::new(Mnemonic::Clrex),
+        0b010 => Instruction::new(Mnemonic::Clrex).with(Operand::Immediate(u64::from(crm))),
+        0b100 => Instruction::new(Mnemonic::Dsb).with(Operand::Barrier(BarrierOption(crm))),
+        0b101 => Instruction::new(Mnemonic::Dmb).with(Operand::Barrier(BarrierOption(crm))),
+        0b110 if crm == 0b1111 => Instruction::new(Mnemonic::Isb),
+        0b110 => Instruction::new(Mnemonic::Isb).with(Operand::Immediate(u64::from(crm))),
+        0b111 if crm == 0b0000 => Instruction::new(Mnemonic::Sb),
+        0b001 | 0b011 => return Err(DecodeError::Unsupported),
+        _ => return Err(DecodeError::Unallocated),
+    };
+
+    Ok(instruction)
+}
+
+/// Decodes a `SYS` or `SYSL` instruction, using the `AT`, `DC`, `IC`, or `TLBI` alias when the
+/// operation is known.
+fn system_instruction(read: bool, operation: SystemOperation, rt: u8) -> Instruction {
+    if read {
+        return Instruction::new(Mnemonic::Sysl)
+            .with_register(Register::zr(rt, true))
+            .with(Operand::SystemOperation(operation));
+    }
+
+    let alias = match (operation.crn, operation.name()) {
+        (7, Some(_)) if operation.crm == 8 => Some(Mnemonic::At),
+        (7, Some(_)) if matches!(operation.crm, 1 | 5) => Some(Mnemonic::Ic),
+        (7, Some(_)) => Some(Mnemonic::Dc),
+        (8, Some(_)) => Some(Mnemonic::Tlbi),
+        _ => None,
+    };
+
+    match alias {
+        Some(mnemonic) if rt == 31 => {
+            Instruction::new(mnemonic).with(Operand::SystemOperation(operation))
+        }
+        Some(mnemonic) => Instruction::new(mnemonic)
+            .with(Operand::SystemOperation(operation))
+            .with_register(Register::zr(rt, true)),
+        None => Instruction::new(Mnemonic::Sys)
+            .with(Operand::SystemOperation(operation))
+            .with_register(Register::zr(rt, true)),
+    }
+}
+
+/// Decodes the unconditional branch (register) class.
+fn register_branch(word: u32) -> Result<Instruction, DecodeError> {
+    if field(word, 25, 1) != 1 || field(word, 16, 5) != 0b11111 {
+        return Err(DecodeError::Unallocated);
+    }
+
+    let opc = field(word, 21, 4);
+    let op3 = field(word, 10, 6);
+    let rn = register(word, 5);
+    let op4 = register(word, 0);
+
+    let instruction = match (opc, op3, rn, op4) {
+        (0b0000, 0b000000, rn, 0) => {
+            Instruction::new(Mnemonic::Br).with_register(Register::zr(rn, true))
+        }
+        (0b0001, 0b000000, rn, 0) => {
+            Instruction::new(Mnemonic::Blr).with_register(Register::zr(rn, true))
+        }
+        (0b0010, 0b000000, 30, 0) => Instruction::new(Mnemonic::Ret),
+        (0b0010, 0b000000, rn, 0) => {
+            Instruction::new(Mnemonic::Ret).with_register(Register::zr(rn, true))
+        }
+        (0b0100, 0b000000, 31, 0) => Instruction::new(Mnemonic::Eret),
+        (0b0101, 0b000000, 31, 0) => Instruction::new(Mnemonic::Drps),
+        (0b0000, 0b000010, rn, 31) => {
+            Instruction::new(Mnemonic::Braaz).with_register(Register::zr(rn, true))
+        }
+        (0b0000, 0b000011, rn, 31) => {
+            Instruction::new(Mnemonic::Brabz).with_register(Register::zr(rn, true))
+        }
+        (0b0001, 0b000010, rn, 31) => {
+            Instruction::new(Mnemonic::Blraaz).with_register(Register::zr(rn, true))
+        }
+        (0b0001, 0b000011, rn, 31) => {
+            Instruction::new(Mnemonic::Blrabz).with_register(Register::zr(rn, true))
+        }
+        (0b0010, 0b000010, 31, 31) => Instruction::new(Mnemonic::Retaa),
+        (0b0010, 0b000011, 31, 31) => Instruction::new(Mnemonic::Retab),
+        (0b0100, 0b000010, 31, 31) => Instruction::new(Mnemonic::Eretaa),
+        (0b0100, 0b000011, 31, 31) => Instruction::new(Mnemonic::Eretab),
+        (0b1000 | 0b1001, 0b000010 | 0b000011, rn, rm) => {
+            let mnemonic = match (opc, op3) {
+                (0b1000, 0b000010) => Mnemonic::Braa,
+                (0b1000, _) => Mnemonic::Brab,
+                (_, 0b000010) => Mnemonic::Blraa,
+                (_, _) => Mnemonic::Blrab,
+            };
+
+            Instruction::new(mnemonic)
+                .with_register(Register::zr(rn, true))
+                .with_register(Register::sp(rm, true))
+        }
+        _ => return Err(DecodeError::Unallocated),
+    };
+
+    Ok(instruction)
+}
@@ -0,0 +1,748 @@
+//! Decoding of the loads and stores encoding group.
+
+use crate::decode::{
+    AccessKind, DecodeError, Instruction, MemoryAccess, Mnemonic, field,
+    operand::{
+        Address, Arrangement, Base, Extend, Offset, Operand, PrefetchOperation, Register,
+        VectorLayout, VectorList, Writeback,
+    },
+    register, signed_field,
+};
+
+/// The structure loads that transfer 1 to 4 elements per structure.
+const STRUCTURE_LOADS: [Mnemonic; 4] = [Mnemonic::Ld1, Mnemonic::Ld2, Mnemonic::Ld3, Mnemonic::Ld4];
+
+/// The structure stores that transfer 1 to 4 elements per structure.
+const STRUCTURE_STORES: [Mnemonic; 4] =
+    [Mnemonic::St1, Mnemonic::St2, Mnemonic::St3, Mnemonic::St4];
+
+/// The replicating structure loads that transfer 1 to 4 elements per structure.
+const REPLICATING_LOADS: [Mnemonic; 4] = [
+    Mnemonic::Ld1r,
+    Mnemonic::Ld2r,
+    Mnemonic::Ld3r,
+    Mnemonic::Ld4r,
+];
+
+/// The atomic memory operations, indexed by `opc`, that return the original value.
+const ATOMIC_LOADS: [Mnemonic; 8] = [
+    Mnemonic::Ldadd,
+    Mnemonic::Ldclr,
+    Mnemonic::Ldeor,
+    Mnemonic::Ldset,
+    Mnemonic::Ldsmax,
+    Mnemonic::Ldsmin,
+    Mnemonic::Ldumax,
+    Mnemonic::Ldumin,
+];
+
+/// The atomic memory operations, indexed by `opc`, that discard the original value.
+const ATOMIC_STORES: [Mnemonic; 8] = [
+    Mnemonic::Stadd,
+    Mnemonic::Stclr,
+    Mnemonic::Steor,
+    Mnemonic::Stset,
+    Mnemonic::Stsmax,
+    Mnemonic::Stsmin,
+    Mnemonic::Stumax,
+    Mnemonic::Stumin,
+];
+
+/// Decodes an instruction in the loads and stores encoding group.
+pub(super) fn decode(word: u32) -> Result<Instruction, DecodeError> {
+    let op0 = field(word, 28, 4);
+    let vector = field(word, 26, 1) == 1;
+    let op2 = field(word, 23, 2);
+    let bit_21 = field(word, 21, 1) == 1;
+    let op4 = field(word, 10, 2);
+
+    match (op0 & 0b11, op2) {
+        (0b00, _) if vector => {
+            if op0 & 0b1011 != 0 {
+                return Err(DecodeError::Unsupported);
+            }
+
+            match op2 {
+                0b00 | 0b01 => multiple_structures(word, op2 == 0b01),
+                _ => single_structure(word, op2 == 0b11),
+            }
+        }
+        (0b00, 0b00 | 0b01) => exclusive(word),
+        (0b00, _) => Err(DecodeError::Unsupported),
+        (0b01, 0b00 | 0b01) => literal(word, vector),
+        (0b01, _) if !vector && !bit_21 && op4 == 0b00 => ordered_unscaled(word),
+        (0b01, _) => Err(DecodeError::Unsupported),
+        (0b10, mode) => pair(word, vector, mode),
+        (0b11, 0b00 | 0b01) => match (bit_21, op4) {
+            (false, _) => immediate(word, vector, op4),
+            (true, 0b00) => atomic(word, vector),
+            (true, 0b10) => register_offset(word, vector),
+            (true, _) => authenticated(word, vector),
+        },
+        (_, _) => unsigned_immediate(word, vector),
+    }
+}
+
+/// Returns an [`Operand::Memory`] that addresses `offset` from the base register `rn`.
+const fn memory(rn: u8, offset: Offset, writeback: Writeback) -> Operand {
+    Operand::Memory(Address {
+        base: Base::Register(Register::sp(rn, true)),
+        offset,
+        writeback,
+    })
+}
+
+/// Returns an [`Operand::Memory`] that addresses the base register `rn` without an offset.
+const fn base_only(rn: u8) -> Operand {
+    memory(rn, Offset::Immediate(0), Writeback::None)
+}
+
+/// Decodes the load/store exclusive, load-acquire/store-release, and compare and swap classes.
+fn exclusive(word: u32) -> Result<Instruction, DecodeError> {
+    let size = field(word, 30, 2);
+    let o2 = field(word, 23, 1) == 1;
+    let load = field(word, 22, 1) == 1;
+    let o1 = field(word, 21, 1) == 1;
+    let o0 = field(word, 15, 1) == 1;
+    let (rs, rt2, rn, rt) = (
+        register(word, 16),
+        register(word, 10),
+        register(word, 5),
+        register(word, 0),
+    );
+
+    let is_64 = size == 0b11;
+    let bytes = 1 << size;
+    let kind = if load {
+        AccessKind::Load
+    } else {
+        AccessKind::Store
+    };
+
+    let instruction = match (o2, o1) {
+        (false, false) => {
+            let mnemonic = match (load, o0) {
+                (false, false) => Mnemonic::Stxr,
+                (false, true) => Mnemonic::Stlxr,
+                (true, false) => Mnemonic::Ldxr,
+                (true, true) => Mnemonic::Ldaxr,
+            };
+
+            let mut instruction = Instruction::new(mnemonic);
+            if !load {
+                instruction = instruction.with_register(Register::zr(rs, false));
+            }
+
+            let mut access = MemoryAccess::new(kind, bytes);
+            access.exclusive = true;
+            access.acquire = load && o0;
+            access.release = !load && o0;
+            instruction
+                .with_register(Register::zr(rt, is_64))
+                .with(base_only(rn))
+                .with_access(access)
+        }
+        (false, true) if size >= 0b10 => {
+            let mnemonic = match (load, o0) {
+                (false, false) => Mnemonic::Stxp,
+                (false, true) => Mnemonic::Stlxp,
+                (true, false) => Mnemonic::Ldxp,
+                (true, true) => Mnemonic::Ldaxp,
+            };
+
+            let mut instruction = Instruction::new(mnemonic);
+            if !load {
+                instruction = instruction.with_register(Register::zr(rs, false));
+            }
+
+            let mut access = MemoryAccess::new(kind, bytes);
+            access.count = 2;
+            access.exclusive = true;
+            access.acquire = load && o0;
+            access.release = !load && o0;
+            instruction
+                .with_register(Register::zr(rt, is_64))
+                .with_register(Register::zr(rt2, is_64))
+                .with(base_only(rn))
+                .with_access(access)
+        }
+        (false, true) => {
+            if rs % 2 != 0 || rt % 2 != 0 || rt2 != 31 {
+                return Err(DecodeError::Unallocated);
+            }
+
+            let is_64 = size == 0b01;
+            let mut access =
+                MemoryAccess::new(AccessKind::ReadModifyWrite, if is_64 { 8 } else { 4 });
+            access.count = 2;
+            access.acquire = load;
+            access.release = o0;
+            Instruction::new(Mnemonic::Casp)
+                .with_register(Register::zr(rs, is_64))
+                .with_register(Register::zr(rs + 1, is_64))
+                .with_register(Register::zr(rt, is_64))
+                .with_register(Register::zr(rt + 1, is_64))
+                .with(base_only(rn))
+                .with_access(access)
+        }
+        (true, false) => {
+            let mnemonic = match (load, o0) {
+                (false, false) => Mnemonic::Stllr,
+                (false, true) => Mnemonic::Stlr,
+                (true, false) => Mnemonic::Ldlar,
+                (true, true) => Mnemonic::Ldar,
+            };
+
+            let mut access = MemoryAccess::new(kind, bytes);
+            access.acquire = load;
+            access.release = !load;
+            Instruction::new(mnemonic)
+                .with_register(Register::zr(rt, is_64))
+                .with(base_only(rn))
+                .with_access(access)
+        }
+        (true, true) => {
+            let mut access = MemoryAccess::new(AccessKind::ReadModifyWrite, bytes);
+            access.acquire = load;
+            access.release = o0;
+            Instruction::new(Mnemonic::Cas)
+                .with_register(Register::zr(rs, is_64))
+                .with_register(Register::zr(rt, is_64))
+                .with(base_only(rn))
+                .with_access(access)
+        }
+    };
+
+    Ok(instruction)
+}
+
+/// Decodes the load register (literal) class.
+fn literal(word: u32, vector: bool) -> Result<Instruction, DecodeError> {
+    let opc = field(word, 30, 2);
+    let rt = register(word, 0);
+    let operand = Operand::Memory(Address {
+        base: Base::Pc,
+        offset: Offset::Immediate(signed_field(word, 5, 19) * 4),
+        writeback: Writeback::None,
+    });
+
+    let (mnemonic, register, access) = match (vector, opc) {
+        (false, 0b11) => {
+            let access = MemoryAccess::new(AccessKind::Prefetch, 8);
+            return Ok(Instruction::new(Mnemonic::Prfm)
+                .with(Operand::Prefetch(PrefetchOperation(rt)))
+                .with(operand)
+                .with_access(access));
+        }
+        (false, 0b10) => {
+            let mut access = MemoryAccess::new(AccessKind::Load, 4);
+            access.sign_extend = true;
+            (Mnemonic::Ldr, Register::zr(rt, true), access)
+        }
+        (false, opc) => {
+            let size = 4 << opc;
+            let access = MemoryAccess::new(AccessKind::Load, size);
+            (Mnemonic::Ldr, Register::zr(rt, opc == 1), access)
+        }
+        (true, 0b11) => return Err(DecodeError::Unallocated),
+        (true, opc) => {
+            let size = 4 << opc;
+            let mut access = MemoryAccess::new(AccessKind::Load, size);
+            access.vector = true;
+            (Mnemonic::Ldr, Register::vector(rt, size), access)
+        }
+    };
+
+    Ok(Instruction::new(mnemonic)
+        .with_register(register)
+        .with(operand)
+        .with_access(access))
+}
+
+/// Decodes the load-acquire RCpc and store-release register (unscaled) class.
+fn ordered_unscaled(word: u32) -> Result<Instruction, DecodeError> {
+    let size = field(word, 30, 2);
+    let opc = field(word, 22, 2);
+    let (rn, rt) = (register(word, 5), register(word, 0));
+    let bytes = 1 << size;
+
+    let (mnemonic, register, mut access) = match (size, opc) {
+        (_, 0b00) => (
+            Mnemonic::Stlur,
+            Register::zr(rt, size == 0b11),
+            MemoryAccess::new(AccessKind::Store, bytes),
+        ),
+        (_, 0b01) => (
+            Mnemonic::Ldapur,
+            Register::zr(rt, size == 0b11),
+            MemoryAccess::new(AccessKind::Load, bytes),
+        ),
+        (0b11, _) | (0b10, 0b11) => return Err(DecodeError::Unallocated),
+        (_, opc) => {
+            let mut access = MemoryAccess::new(AccessKind::Load, bytes);
+            access.sign_extend = true;
+            (Mnemonic::Ldapur, Register::zr(rt, opc == 0b10), access)
+        }
+    };
+    access.acquire = access.kind == AccessKind::Load;
+    access.release = access.kind == AccessKind::Store;
+
+    Ok(Instruction::new(mnemonic)
+        .with_register(register)
+        .with(memory(
+            rn,
+            Offset::Immediate(signed_field(word, 12, 9)),
+            Writeback::None,
+        ))
+        .with_access(access))
+}
+
+/// Decodes the load/store register pair classes, where `mode` selects the addressing mode.
+fn pair(word: u32, vector: bool, mode: u32) -> Result<Instruction, DecodeError> {
+    let opc = field(word, 30, 2);
+    let load = field(word, 22, 1) == 1;
+    let (rt2, rn, rt) = (register(word, 10), register(word, 5), register(word, 0));
+
+    let (size, sign_extend) = match (vector, opc) {
+        (false, 0b00) => (4, false),
+        (false, 0b10) => (8, false),
+        (false, 0b01) if load && mode != 0b00 => (4, true),
+        (false, 0b01) => return Err(DecodeError::Unsupported),
+        (true, 0b11) | (false, _) => return Err(DecodeError::Unallocated),
+        (true, opc) => (4 << opc, false),
+    };
+
+    let (mnemonic, writeback) = match (mode, load) {
+        (0b00, false) => (Mnemonic::Stnp, Writeback::None),
+        (0b00, true) => (Mnemonic::Ldnp, Writeback::None),
+        (0b01, false) => (Mnemonic::Stp, Writeback::PostIndex),
+        (0b01, true) => (Mnemonic::Ldp, Writeback::PostIndex),
+        (0b10, false) => (Mnemonic::Stp, Writeback::None),
+        (0b10, true) => (Mnemonic::Ldp, Writeback::None),
+        (_, false) => (Mnemonic::Stp, Writeback::PreIndex),
+        (_, true) => (Mnemonic::Ldp, Writeback::PreIndex),
+    };
+
+    let register = |index| {
+        if vector {
+            Register::vector(index, size)
+        } else {
+            Register::zr(index, size == 8 || sign_extend)
+        }
+    };
+
+    let kind = if load {
+        AccessKind::Load
+    } else {
+        AccessKind::Store
+    };
+    let mut access = MemoryAccess::new(kind, size);
+    access.count = 2;
+    access.sign_extend = sign_extend;
+    access.vector = vector;
+
+    let offset = signed_field(word, 15, 7) * i64::from(size);
+    Ok(Instruction::new(mnemonic)
+        .with_register(register(rt))
+        .with_register(register(rt2))
+        .with(memory(rn, Offset::Immediate(offset), writeback))
+        .with_access(access))
+}
+
+/// The register transferred by a single register load or store.
+struct Transfer {
+    /// The [`MemoryAccess`] performed by the instruction.
+    access: MemoryAccess,
+    /// The register that is transferred, or [`None`] for prefetches.
+    register: Option<Register>,
+}
+
+/// Decodes the `size`, `V`, and `opc` fields shared by the single register load and store
+/// classes.
+fn transfer(word: u32, vector: bool) -> Result<Transfer, DecodeError> {
+    let size = field(word, 30, 2);
+    let opc = field(word, 22, 2);
+    let rt = register(word, 0);
+
+    if vector {
+        let (load, bytes) = match (size, opc) {
+            (0b00, 0b10 | 0b11) => (opc == 0b11, 16),
+            (size, 0b00 | 0b01) => (opc == 0b01, 1 << size),
+            _ => return Err(DecodeError::Unallocated),
+        };
+
+        let kind = if load {
+            AccessKind::Load
+        } else {
+            AccessKind::Store
+        };
+        let mut access = MemoryAccess::new(kind, bytes);
+        access.vector = true;
+        return Ok(Transfer {
+            access,
+            register: Some(Register::vector(rt, bytes)),
+        });
+    }
+
+    let bytes = 1 << size;
+    let transfer = match (size, opc) {
+        (_, 0b00) => Transfer {
+            access: MemoryAccess::new(AccessKind::Store, bytes),
+            register: Some(Register::zr(rt, size == 0b11)),
+        },
+        (_, 0b01) => Transfer {
+            access: MemoryAccess::new(AccessKind::Load, bytes),
+            register: Some(Register::zr(rt, size == 0b11)),
+        },
+        (0b11, 0b10) => Transfer {
+            access: MemoryAccess::new(AccessKind::Prefetch, bytes),
+            register: None,
+        },
+        (0b11, _) | (0b10, 0b11) => return Err(DecodeError::Unallocated),
+        (_, opc) => {
+            let mut access = MemoryAccess::new(AccessKind::Load, bytes);
+            access.sign_extend = true;
+            Transfer {
+                access,
+                register: Some(Register::zr(rt, opc == 0b10)),
+            }
+        }
+    };
+
+    Ok(transfer)
+}
+
+/// Builds a single register load, store, or prefetch from its [`Transfer`] and address.
+fn single(
+    mnemonic: Mnemonic,
+    prefetch: Mnemonic,
+    word: u32,
+    transfer: Transfer,
+    address: Operand,
+) -> Instruction {
+    let (mnemonic, first) = match transfer.register {
+        Some(register) => (mnemonic, Operand::Register(register)),
+        None => (
+            prefetch,
+            Operand::Prefetch(PrefetchOperation(register(word, 0))),
+        ),
+    };
+
+    Instruction::new(mnemonic)
+        .with(first)
+        .with(address)
+        .with_access(transfer.access)
+}
+
+/// Decodes the load/store register classes with a 9-bit signed immediate, where `op4` selects
+/// the unscaled, post-indexed, unprivileged, or pre-indexed class.
+fn immediate(word: u32, vector: bool, op4: u32) -> Result<Instruction, DecodeError> {
+    let mut transfer = transfer(word, vector)?;
+    let offset = Offset::Immediate(signed_field(word, 12, 9));
+    let rn = register(word, 5);
+
+    let prefetch = transfer.register.is_none();
+    let (mnemonic, writeback) = match (op4, transfer.access.kind) {
+        (0b00, AccessKind::Store) => (Mnemonic::Stur, Writeback::None),
+        (0b00, _) => (Mnemonic::Ldur, Writeback::None),
+        _ if prefetch => return Err(DecodeError::Unallocated),
+        (0b10, _) if vector => return Err(DecodeError::Unallocated),
+        (0b10, kind) => {
+            transfer.access.unprivileged = true;
+            if kind == AccessKind::Store {
+                (Mnemonic::Sttr, Writeback::None)
+            } else {
+                (Mnemonic::Ldtr, Writeback::None)
+            }
+        }
+        (0b01, AccessKind::Store) => (Mnemonic::Str, Writeback::PostIndex),
+        (0b01, _) => (Mnemonic::Ldr, Writeback::PostIndex),
+        (_, AccessKind::Store) => (Mnemonic::Str, Writeback::PreIndex),
+        (_, _) => (Mnemonic::Ldr, Writeback::PreIndex),
+    };
+
+    Ok(single(
+        mnemonic,
+        Mnemonic::Prfum,
+        word,
+        transfer,
+        memory(rn, offset, writeback),
+    ))
+}
+
+/// Decodes the load/store register (unsigned immediate) class.
+fn unsigned_immediate(word: u32, vector: bool) -> Result<Instruction, DecodeError> {
+    let transfer = transfer(word, vector)?;
+    let offset = i64::from(field(word, 10, 12) << scale(transfer.access.size));
+    let mnemonic = if transfer.access.kind == AccessKind::Store {
+        Mnemonic::Str
+    } else {
+        Mnemonic::Ldr
+    };
+
+    Ok(single(
+        mnemonic,
+        Mnemonic::Prfm,
+        word,
+        transfer,
+        memory(
+            register(word, 5),
+            Offset::Immediate(offset),
+            Writeback::None,
+        ),
+    ))
+}
+
+/// Returns the base 2 logarithm of the access size `size`, by which offsets are scaled.
+const fn scale(size: u8) -> u8 {
+    match size {
+        1 => 0,
+        2 => 1,
+        4 => 2,
+        8 => 3,
+        _ => 4,
+    }
+}
+
+/// Decodes the load/store register (register offset) class.
+fn register_offset(word: u32, vector: bool) -> Result<Instruction, DecodeError> {
+    let transfer = transfer(word, vector)?;
+    let rm = register(word, 16);
+    let shift = field(word, 12, 1) == 1;
+
+    let (extend, is_64) = match field(word, 13, 3) {
+        0b010 => (Extend::Uxtw, false),
+        0b011 => (Extend::Lsl, true),
+        0b110 => (Extend::Sxtw, false),
+        0b111 => (Extend::Sxtx, true),
+        _ => return Err(DecodeError::Unallocated),
+    };
+
+    let offset = Offset::Register {
+        register: Register::zr(rm, is_64),
+        extend,
+        shift: if shift {
+            Some(scale(transfer.access.size))
+        } else {
+            None
+        },
+    };
+    let mnemonic = if transfer.access.kind == AccessKind::Store {
+        Mnemonic::Str
+    } else {
+        Mnemonic::Ldr
+    };
+
+    Ok(single(
+        mnemonic,
+        Mnemonic::Prfm,
+        word,
+        transfer,
+        memory(register(word, 5), offset, Writeback::None),
+    ))
+}
+
+/// Decodes the atomic memory operations class.
+fn atomic(word: u32, vector: bool) -> Result<Instruction, DecodeError> {
+    if vector {
+        return Err(DecodeError::Unallocated);
+    }
+
+    let size = field(word, 30, 2);
+    let acquire = field(word, 23, 1) == 1;
+    let release = field(word, 22, 1) == 1;
+    let o3 = field(word, 15, 1) == 1;
+    let opc = field(word, 12, 3);
+    let (rs, rn, rt) = (register(word, 16), register(word, 5), register(word, 0));
+
+    let is_64 = size == 0b11;
+    let bytes = 1 << size;
+    let mut access = MemoryAccess::new(AccessKind::ReadModifyWrite, bytes);
+    access.acquire = acquire;
+    access.release = release;
+
+    let instruction = match (o3, opc) {
+        (false, opc) if rt == 31 && !acquire => {
+            Instruction::new(ATOMIC_STORES[opc as usize]).with_register(Register::zr(rs, is_64))
+        }
+        (false, opc) => Instruction::new(ATOMIC_LOADS[opc as usize])
+            .with_register(Register::zr(rs, is_64))
+            .with_register(Register::zr(rt, is_64)),
+        (true, 0b000) => Instruction::new(Mnemonic::Swp)
+            .with_register(Register::zr(rs, is_64))
+            .with_register(Register::zr(rt, is_64)),
+        (true, 0b100) if acquire && !release && rs == 31 => {
+            access.kind = AccessKind::Load;
+            Instruction::new(Mnemonic::Ldapr).with_register(Register::zr(rt, is_64))
+        }
+        _ => return Err(DecodeError::Unsupported),
+    };
+
+    Ok(instruction.with(base_only(rn)).with_access(access))
+}
+
+/// Decodes the load register with pointer authentication class.
+fn authenticated(word: u32, vector: bool) -> Result<Instruction, DecodeError> {
+    if vector || field(word, 30, 2) != 0b11 {
+        return Err(DecodeError::Unallocated);
+    }
+
+    let mnemonic = if field(word, 23, 1) == 1 {
+        Mnemonic::Ldrab
+    } else {
+        Mnemonic::Ldraa
+    };
+    let writeback = if field(word, 11, 1) == 1 {
+        Writeback::PreIndex
+    } else {
+        Writeback::None
+    };
+    let offset = (field(word, 22, 1) << 9) | field(word, 12, 9);
+
+    Ok(Instruction::new(mnemonic)
+        .with_register(Register::zr(register(word, 0), true))
+        .with(memory(
+            register(word, 5),
+            Offset::Immediate(signed_field(offset, 0, 10) * 8),
+            writeback,
+        ))
+        .with_access(MemoryAccess::new(AccessKind::Load, 8)))
+}
+
+/// Returns the memory operand of a structure load or store, which is post-indexed by `Rm`, or
+/// by `immediate` when `Rm` is 31, if `post_index` is `true`.
+fn structure_address(word: u32, post_index: bool, immediate: u8) -> Operand {
+    let rn = register(word, 5);
+    if !post_index {
+        return base_only(rn);
+    }
+
+    let offset = match register(word, 16) {
+        31 => Offset::Immediate(i64::from(immediate)),
+        rm => Offset::Register {
+            register: Register::X(rm),
+            extend: Extend::Lsl,
+            shift: None,
+        },
+    };
+    memory(rn, offset, Writeback::PostIndex)
+}
+
+/// Decodes the SIMD load/store multiple structures classes.
+fn multiple_structures(word: u32, post_index: bool) -> Result<Instruction, DecodeError> {
+    let q = field(word, 30, 1) == 1;
+    let load = field(word, 22, 1) == 1;
+    let size = field(word, 10, 2);
+
+    if field(word, 21, 1) != 0 || (!post_index && field(word, 16, 5) != 0) {
+        return Err(DecodeError::Unallocated);
+    }
+
+    let (elements, registers): (usize, u8) = match field(word, 12, 4) {
+        0b0000 => (4, 4),
+        0b0010 => (1, 4),
+        0b0100 => (3, 3),
+        0b0110 => (1, 3),
+        0b0111 => (1, 1),
+        0b1000 => (2, 2),
+        0b1010 => (1, 2),
+        _ => return Err(DecodeError::Unallocated),
+    };
+    if size == 0b11 && !q && elements != 1 {
+        return Err(DecodeError::Unallocated);
+    }
+
+    let arrangement = Arrangement::from_bits(size, q);
+    let (mnemonic, kind) = if load {
+        (STRUCTURE_LOADS[elements - 1], AccessKind::Load)
+    } else {
+        (STRUCTURE_STORES[elements - 1], AccessKind::Store)
+    };
+
+    let mut access = MemoryAccess::new(kind, arrangement.size());
+    access.count = registers;
+    access.vector = true;
+
+    Ok(Instruction::new(mnemonic)
+        .with(Operand::VectorList(VectorList {
+            first: register(word, 0),
+            count: registers,
+            layout: VectorLayout::Arrangement(arrangement),
+        }))
+        .with(structure_address(
+            word,
+            post_index,
+            registers * arrangement.size(),
+        ))
+        .with_access(access))
+}
+
+/// Decodes the SIMD load/store single structure classes.
+fn single_structure(word: u32, post_index: bool) -> Result<Instruction, DecodeError> {
+    let q = field(word, 30, 1);
+    let load = field(word, 22, 1) == 1;
+    let opcode = field(word, 13, 3);
+    let s = field(word, 12, 1);
+    let size = field(word, 10, 2);
+
+    if !post_index && field(word, 16, 5) != 0 {
+        return Err(DecodeError::Unallocated);
+    }
+
+    let elements = ((((opcode & 1) << 1) | field(word, 21, 1)) & 0b11) as u8 + 1;
+    let (layout, element_size, mnemonic) = match opcode >> 1 {
+        0b11 => {
+            if !load || s != 0 {
+                return Err(DecodeError::Unallocated);
+            }
+
+            let arrangement = Arrangement::from_bits(size, q == 1);
+            (
+                VectorLayout::Arrangement(arrangement),
+                arrangement.element_size(),
+                REPLICATING_LOADS[usize::from(elements - 1)],
+            )
+        }
+        scale => {
+            let (element_size, index) = match (scale, size) {
+                (0b00, _) => (1, (q << 3) | (s << 2) | size),
+                (0b01, 0b00 | 0b10) => (2, (q << 2) | (s << 1) | (size >> 1)),
+                (0b10, 0b00) => (4, (q << 1) | s),
+                (0b10, 0b01) if s == 0 => (8, q),
+                _ => return Err(DecodeError::Unallocated),
+            };
+
+            let mnemonic = if load {
+                STRUCTURE_LOADS[usize::from(elements - 1)]
+            } else {
+                STRUCTURE_STORES[usize::from(elements - 1)]
+            };
+            (
+                VectorLayout::Element {
+                    size: element_size,
+                    index: (index & 0xF) as u8,
+                },
+                element_size,
+                mnemonic,
+            )
+        }
+    };
+
+    let kind = if load {
+        AccessKind::Load
+    } else {
+        AccessKind::Store
+    };
+    let mut access = MemoryAccess::new(kind, element_size);
+    access.count = elements;
+    access.vector = true;
+
+    Ok(Instruction::new(mnemonic)
+        .with(Operand::VectorList(VectorList {
+            first: register(word, 0),
+            count: elements,
+            layout,
+        }))
+        .with(structure_address(word, post_index, elements * element_size))
+        .with_access(access))
+}
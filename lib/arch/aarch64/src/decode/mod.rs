@@ -0,0 +1,1086 @@
+//! Decoder for A64 instructions.
+//!
+//! [`decode()`] decodes a single 32-bit instruction into an [`Instruction`], which records the
+//! [`Mnemonic`], the [`Operand`]s, and, for loads, stores, and atomics, the [`MemoryAccess`] it
+//! performs. The decoder covers every load and store encoding class except memory tagging and
+//! memory copy and set, along with branches, exception generating instructions, and system
+//! instructions. This is enough to emulate data aborts that do not report a valid instruction
+//! syndrome and to print disassembly in traces. Data processing instructions are not decoded.
+
+use core::{error, fmt};
+
+use crate::decode::operand::{Address, Operand, Register};
+
+mod branch;
+mod load_store;
+pub mod operand;
+
+/// The maximum number of operands of an [`Instruction`].
+pub const MAX_OPERANDS: usize = 5;
+
+/// Decodes the A64 instruction `word`.
+///
+/// # Errors
+///
+/// - [`DecodeError::Unallocated`]: `word` is an unallocated or reserved encoding.
+/// - [`DecodeError::Unsupported`]: `word` is not supported by this decoder.
+pub fn decode(word: u32) -> Result<Instruction, DecodeError> {
+    match (word >> 25) & 0xF {
+        0b0100 | 0b0110 | 0b1100 | 0b1110 => load_store::decode(word),
+        0b1010 | 0b1011 => branch::decode(word),
+        _ => Err(DecodeError::Unsupported),
+    }
+}
+
+/// A decoded instruction.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Instruction {
+    /// The mnemonic of the instruction.
+    mnemonic: Mnemonic,
+    /// The operands of the instruction, of which the first `operand_count` are valid.
+    operands: [Operand; MAX_OPERANDS],
+    /// The number of valid operands.
+    operand_count: usize,
+    /// The memory access performed by the instruction, if any.
+    access: Option<MemoryAccess>,
+}
+
+impl Instruction {
+    /// The value of operand slots that are not used by an [`Instruction`].
+    const UNUSED_OPERAND: Operand = Operand::Immediate(0);
+
+    /// Creates an [`Instruction`] without operands.
+    const fn new(mnemonic: Mnemonic) -> Self {
+        Self {
+            mnemonic,
+            operands: [Self::UNUSED_OPERAND; MAX_OPERANDS],
+            operand_count: 0,
+            access: None,
+        }
+    }
+
+    /// Appends `operand` to the operands of the [`Instruction`].
+    const fn with(mut self, operand: Operand) -> Self {
+        self.operands[self.operand_count] = operand;
+        self.operand_count += 1;
+        self
+    }
+
+    /// Appends the register `register` to the operands of the [`Instruction`].
+    const fn with_register(self, register: Register) -> Self {
+        self.with(Operand::Register(register))
+    }
+
+    /// Records the [`MemoryAccess`] performed by the [`Instruction`].
+    const fn with_access(mut self, access: MemoryAccess) -> Self {
+        self.access = Some(access);
+        self
+    }
+
+    /// Returns the [`Mnemonic`] of the instruction.
+    pub const fn mnemonic(&self) -> Mnemonic {
+        self.mnemonic
+    }
+
+    /// Returns the operands of the instruction, in assembly order.
+    pub fn operands(&self) -> &[Operand] {
+        &self.operands[..self.operand_count]
+    }
+
+    /// Returns the memory operand of the instruction, if any.
+    pub fn memory_operand(&self) -> Option<&Address> {
+        self.operands().iter().find_map(|operand| match operand {
+            Operand::Memory(address) => Some(address),
+            _ => None,
+        })
+    }
+
+    /// Returns the [`MemoryAccess`] performed by the instruction, if any.
+    pub const fn memory_access(&self) -> Option<&MemoryAccess> {
+        self.access.as_ref()
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.mnemonic, f)?;
+        if let Some(access) = self.access {
+            if access.sign_extend {
+                f.write_str("s")?;
+            }
+            if self.mnemonic.has_ordering_suffix() {
+                f.write_str(match (access.acquire, access.release) {
+                    (false, false) => "",
+                    (true, false) => "a",
+                    (false, true) => "l",
+                    (true, true) => "al",
+                })?;
+            }
+            if !access.vector {
+                f.write_str(match (access.size, access.sign_extend) {
+                    (1, _) => "b",
+                    (2, _) => "h",
+                    (4, true) => "w",
+                    _ => "",
+                })?;
+            }
+        }
+
+        for (index, operand) in self.operands().iter().enumerate() {
+            f.write_str(if index == 0 { " " } else { ", " })?;
+            fmt::Display::fmt(operand, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The memory access performed by a load, store, prefetch, or atomic [`Instruction`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct MemoryAccess {
+    /// The kind of access.
+    pub kind: AccessKind,
+    /// The size, in bytes, of the data transferred for each register.
+    ///
+    /// For structure loads and stores, this is the size of the part of each register that is
+    /// transferred.
+    pub size: u8,
+    /// The number of registers transferred.
+    pub count: u8,
+    /// If `true`, loaded data is sign-extended to the size of the destination register.
+    pub sign_extend: bool,
+    /// If `true`, the access has acquire semantics.
+    pub acquire: bool,
+    /// If `true`, the access has release semantics.
+    pub release: bool,
+    /// If `true`, the access is exclusive.
+    pub exclusive: bool,
+    /// If `true`, the access is checked as if it were made from EL0.
+    pub unprivileged: bool,
+    /// If `true`, the transferred registers are SIMD&FP registers.
+    pub vector: bool,
+}
+
+impl MemoryAccess {
+    /// Creates a plain [`MemoryAccess`] of `kind` that transfers a single register of `size`
+    /// bytes.
+    const fn new(kind: AccessKind, size: u8) -> Self {
+        Self {
+            kind,
+            size,
+            count: 1,
+            sign_extend: false,
+            acquire: false,
+            release: false,
+            exclusive: false,
+            unprivileged: false,
+            vector: false,
+        }
+    }
+
+    /// Returns the total number of bytes accessed.
+    pub const fn total_size(&self) -> usize {
+        self.size as usize * self.count as usize
+    }
+}
+
+/// The kind of a [`MemoryAccess`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum AccessKind {
+    /// Memory is read.
+    Load,
+    /// Memory is written.
+    Store,
+    /// Memory is read, modified, and conditionally or unconditionally written, as a single
+    /// atomic operation.
+    ReadModifyWrite,
+    /// Memory is prefetched, which does not fault.
+    Prefetch,
+}
+
+/// A condition, as tested by conditional branches.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Condition {
+    /// Equal (`Z == 1`).
+    Eq,
+    /// Not equal (`Z == 0`).
+    Ne,
+    /// Carry set, or unsigned higher or same (`C == 1`).
+    Cs,
+    /// Carry clear, or unsigned lower (`C == 0`).
+    Cc,
+    /// Negative (`N == 1`).
+    Mi,
+    /// Positive or zero (`N == 0`).
+    Pl,
+    /// Overflow (`V == 1`).
+    Vs,
+    /// No overflow (`V == 0`).
+    Vc,
+    /// Unsigned higher (`C == 1 && Z == 0`).
+    Hi,
+    /// Unsigned lower or same (`C == 0 || Z == 1`).
+    Ls,
+    /// Signed greater than or equal (`N == V`).
+    Ge,
+    /// Signed less than (`N != V`).
+    Lt,
+    /// Signed greater than (`Z == 0 && N == V`).
+    Gt,
+    /// Signed less than or equal (`Z == 1 || N != V`).
+    Le,
+    /// Always.
+    Al,
+    /// Always, encoded as `0b1111`.
+    Nv,
+}
+
+impl Condition {
+    /// Returns the [`Condition`] encoded as `bits`.
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits & 0xF {
+            0x0 => Self::Eq,
+            0x1 => Self::Ne,
+            0x2 => Self::Cs,
+            0x3 => Self::Cc,
+            0x4 => Self::Mi,
+            0x5 => Self::Pl,
+            0x6 => Self::Vs,
+            0x7 => Self::Vc,
+            0x8 => Self::Hi,
+            0x9 => Self::Ls,
+            0xA => Self::Ge,
+            0xB => Self::Lt,
+            0xC => Self::Gt,
+            0xD => Self::Le,
+            0xE => Self::Al,
+            _ => Self::Nv,
+        }
+    }
+
+    /// Returns the encoding of the [`Condition`].
+    pub const fn to_bits(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns `true` if the condition holds for the `NZCV` flags in `nzcv`, which holds `N` in
+    /// bit 3 and `V` in bit 0.
+    pub const fn holds(self, nzcv: u8) -> bool {
+        let n = nzcv & 0b1000 != 0;
+        let z = nzcv & 0b0100 != 0;
+        let c = nzcv & 0b0010 != 0;
+        let v = nzcv & 0b0001 != 0;
+
+        match self {
+            Self::Eq => z,
+            Self::Ne => !z,
+            Self::Cs => c,
+            Self::Cc => !c,
+            Self::Mi => n,
+            Self::Pl => !n,
+            Self::Vs => v,
+            Self::Vc => !v,
+            Self::Hi => c && !z,
+            Self::Ls => !c || z,
+            Self::Ge => n == v,
+            Self::Lt => n != v,
+            Self::Gt => !z && n == v,
+            Self::Le => z || n != v,
+            Self::Al | Self::Nv => true,
+        }
+    }
+
+    /// Returns the name of the [`Condition`], as used in assembly.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Eq => "eq",
+            Self::Ne => "ne",
+            Self::Cs => "hs",
+            Self::Cc => "lo",
+            Self::Mi => "mi",
+            Self::Pl => "pl",
+            Self::Vs => "vs",
+            Self::Vc => "vc",
+            Self::Hi => "hi",
+            Self::Ls => "ls",
+            Self::Ge => "ge",
+            Self::Lt => "lt",
+            Self::Gt => "gt",
+            Self::Le => "le",
+            Self::Al => "al",
+            Self::Nv => "nv",
+        }
+    }
+}
+
+/// The operation performed by an [`Instruction`].
+///
+/// Loads, stores, and atomics that encode their access size, signedness, or memory ordering in a
+/// suffix are represented by their base mnemonic; [`Instruction`] adds the suffix when displayed.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Mnemonic {
+    /// Load register.
+    Ldr,
+    /// Store register.
+    Str,
+    /// Load register with an unscaled offset.
+    Ldur,
+    /// Store register with an unscaled offset.
+    Stur,
+    /// Load register with unprivileged access.
+    Ldtr,
+    /// Store register with unprivileged access.
+    Sttr,
+    /// Load pair of registers.
+    Ldp,
+    /// Store pair of registers.
+    Stp,
+    /// Load pair of registers with a non-temporal hint.
+    Ldnp,
+    /// Store pair of registers with a non-temporal hint.
+    Stnp,
+    /// Prefetch memory.
+    Prfm,
+    /// Prefetch memory with an unscaled offset.
+    Prfum,
+    /// Load exclusive register.
+    Ldxr,
+    /// Load-acquire exclusive register.
+    Ldaxr,
+    /// Store exclusive register.
+    Stxr,
+    /// Store-release exclusive register.
+    Stlxr,
+    /// Load exclusive pair of registers.
+    Ldxp,
+    /// Load-acquire exclusive pair of registers.
+    Ldaxp,
+    /// Store exclusive pair of registers.
+    Stxp,
+    /// Store-release exclusive pair of registers.
+    Stlxp,
+    /// Load-acquire register.
+    Ldar,
+    /// Store-release register.
+    Stlr,
+    /// Load LOAcquire register.
+    Ldlar,
+    /// Store LORelease register.
+    Stllr,
+    /// Load-acquire RCpc register.
+    Ldapr,
+    /// Load-acquire RCpc register with an unscaled offset.
+    Ldapur,
+    /// Store-release register with an unscaled offset.
+    Stlur,
+    /// Compare and swap.
+    Cas,
+    /// Compare and swap pair of registers.
+    Casp,
+    /// Atomic add.
+    Ldadd,
+    /// Atomic bit clear.
+    Ldclr,
+    /// Atomic exclusive OR.
+    Ldeor,
+    /// Atomic bit set.
+    Ldset,
+    /// Atomic signed maximum.
+    Ldsmax,
+    /// Atomic signed minimum.
+    Ldsmin,
+    /// Atomic unsigned maximum.
+    Ldumax,
+    /// Atomic unsigned minimum.
+    Ldumin,
+    /// Swap.
+    Swp,
+    /// Atomic add without return.
+    Stadd,
+    /// Atomic bit clear without return.
+    Stclr,
+    /// Atomic exclusive OR without return.
+    Steor,
+    /// Atomic bit set without return.
+    Stset,
+    /// Atomic signed maximum without return.
+    Stsmax,
+    /// Atomic signed minimum without return.
+    Stsmin,
+    /// Atomic unsigned maximum without return.
+    Stumax,
+    /// Atomic unsigned minimum without return.
+    Stumin,
+    /// Load register, with pointer authentication using key A.
+    Ldraa,
+    /// Load register, with pointer authentication using key B.
+    Ldrab,
+    /// Load multiple single-element structures.
+    Ld1,
+    /// Load multiple 2-element structures.
+    Ld2,
+    /// Load multiple 3-element structures.
+    Ld3,
+    /// Load multiple 4-element structures.
+    Ld4,
+    /// Store multiple single-element structures.
+    St1,
+    /// Store multiple 2-element structures.
+    St2,
+    /// Store multiple 3-element structures.
+    St3,
+    /// Store multiple 4-element structures.
+    St4,
+    /// Load one single-element structure and replicate to all lanes.
+    Ld1r,
+    /// Load one 2-element structure and replicate to all lanes.
+    Ld2r,
+    /// Load one 3-element structure and replicate to all lanes.
+    Ld3r,
+    /// Load one 4-element structure and replicate to all lanes.
+    Ld4r,
+    /// Branch.
+    B,
+    /// Branch with link.
+    Bl,
+    /// Branch conditionally.
+    BCond(Condition),
+    /// Branch consistent conditionally.
+    BcCond(Condition),
+    /// Compare and branch on zero.
+    Cbz,
+    /// Compare and branch on nonzero.
+    Cbnz,
+    /// Test bit and branch if zero.
+    Tbz,
+    /// Test bit and branch if nonzero.
+    Tbnz,
+    /// Branch to register.
+    Br,
+    /// Branch with link to register.
+    Blr,
+    /// Return from subroutine.
+    Ret,
+    /// Branch to register, with pointer authentication using key A.
+    Braa,
+    /// Branch to register, with pointer authentication using key B.
+    Brab,
+    /// Branch to register, with pointer authentication using key A and a zero modifier.
+    Braaz,
+    /// Branch to register, with pointer authentication using key B and a zero modifier.
+    Brabz,
+    /// Branch with link to register, with pointer authentication using key A.
+    Blraa,
+    /// Branch with link to register, with pointer authentication using key B.
+    Blrab,
+    /// Branch with link to register, with pointer authentication using key A and a zero modifier.
+    Blraaz,
+    /// Branch with link to register, with pointer authentication using key B and a zero modifier.
+    Blrabz,
+    /// Return from subroutine, with pointer authentication using key A.
+    Retaa,
+    /// Return from subroutine, with pointer authentication using key B.
+    Retab,
+    /// Exception return.
+    Eret,
+    /// Exception return, with pointer authentication using key A.
+    Eretaa,
+    /// Exception return, with pointer authentication using key B.
+    Eretab,
+    /// Debug restore process state.
+    Drps,
+    /// Supervisor call.
+    Svc,
+    /// Hypervisor call.
+    Hvc,
+    /// Secure monitor call.
+    Smc,
+    /// Breakpoint.
+    Brk,
+    /// Halt.
+    Hlt,
+    /// Debug change PE state to EL1.
+    Dcps1,
+    /// Debug change PE state to EL2.
+    Dcps2,
+    /// Debug change PE state to EL3.
+    Dcps3,
+    /// Move to a system register or process state field.
+    Msr,
+    /// Move from a system register.
+    Mrs,
+    /// System instruction.
+    Sys,
+    /// System instruction with result.
+    Sysl,
+    /// Address translate.
+    At,
+    /// Data cache operation.
+    Dc,
+    /// Instruction cache operation.
+    Ic,
+    /// TLB invalidate operation.
+    Tlbi,
+    /// Hint instruction without an assigned alias.
+    Hint,
+    /// No operation.
+    Nop,
+    /// Yield.
+    Yield,
+    /// Wait for event.
+    Wfe,
+    /// Wait for interrupt.
+    Wfi,
+    /// Send event.
+    Sev,
+    /// Send event local.
+    Sevl,
+    /// Data gathering hint.
+    Dgh,
+    /// Strip pointer authentication code from the link register.
+    Xpaclri,
+    /// Pointer authentication code for an instruction address in `x17`, using key A and the modifier in `x16`.
+    Pacia1716,
+    /// Pointer authentication code for an instruction address in `x17`, using key B and the modifier in `x16`.
+    Pacib1716,
+    /// Authenticate an instruction address in `x17`, using key A and the modifier in `x16`.
+    Autia1716,
+    /// Authenticate an instruction address in `x17`, using key B and the modifier in `x16`.
+    Autib1716,
+    /// Error synchronization barrier.
+    Esb,
+    /// Profiling synchronization barrier.
+    PsbCsync,
+    /// Trace synchronization barrier.
+    TsbCsync,
+    /// Consumption of speculative data barrier.
+    Csdb,
+    /// Pointer authentication code for the link register, using key A and a zero modifier.
+    Paciaz,
+    /// Pointer authentication code for the link register, using key A and the stack pointer as modifier.
+    Paciasp,
+    /// Pointer authentication code for the link register, using key B and a zero modifier.
+    Pacibz,
+    /// Pointer authentication code for the link register, using key B and the stack pointer as modifier.
+    Pacibsp,
+    /// Authenticate the link register, using key A and a zero modifier.
+    Autiaz,
+    /// Authenticate the link register, using key A and the stack pointer as modifier.
+    Autiasp,
+    /// Authenticate the link register, using key B and a zero modifier.
+    Autibz,
+    /// Authenticate the link register, using key B and the stack pointer as modifier.
+    Autibsp,
+    /// Branch target identification.
+    Bti,
+    /// Clear exclusive monitor.
+    Clrex,
+    /// Data synchronization barrier.
+    Dsb,
+    /// Data memory barrier.
+    Dmb,
+    /// Instruction synchronization barrier.
+    Isb,
+    /// Speculation barrier.
+    Sb,
+    /// Wait for event with timeout.
+    Wfet,
+    /// Wait for interrupt with timeout.
+    Wfit,
+}
+
+impl Mnemonic {
+    /// Returns the base name of the [`Mnemonic`].
+    ///
+    /// The name of [`Mnemonic::BCond`] and [`Mnemonic::BcCond`] excludes the condition.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Ldr => "ldr",
+            Self::Str => "str",
+            Self::Ldur => "ldur",
+            Self::Stur => "stur",
+            Self::Ldtr => "ldtr",
+            Self::Sttr => "sttr",
+            Self::Ldp => "ldp",
+            Self::Stp => "stp",
+            Self::Ldnp => "ldnp",
+            Self::Stnp => "stnp",
+            Self::Prfm => "prfm",
+            Self::Prfum => "prfum",
+            Self::Ldxr => "ldxr",
+            Self::Ldaxr => "ldaxr",
+            Self::Stxr => "stxr",
+            Self::Stlxr => "stlxr",
+            Self::Ldxp => "ldxp",
+            Self::Ldaxp => "ldaxp",
+            Self::Stxp => "stxp",
+            Self::Stlxp => "stlxp",
+            Self::Ldar => "ldar",
+            Self::Stlr => "stlr",
+            Self::Ldlar => "ldlar",
+            Self::Stllr => "stllr",
+            Self::Ldapr => "ldapr",
+            Self::Ldapur => "ldapur",
+            Self::Stlur => "stlur",
+            Self::Cas => "cas",
+            Self::Casp => "casp",
+            Self::Ldadd => "ldadd",
+            Self::Ldclr => "ldclr",
+            Self::Ldeor => "ldeor",
+            Self::Ldset => "ldset",
+            Self::Ldsmax => "ldsmax",
+            Self::Ldsmin => "ldsmin",
+            Self::Ldumax => "ldumax",
+            Self::Ldumin => "ldumin",
+            Self::Swp => "swp",
+            Self::Stadd => "stadd",
+            Self::Stclr => "stclr",
+            Self::Steor => "steor",
+            Self::Stset => "stset",
+            Self::Stsmax => "stsmax",
+            Self::Stsmin => "stsmin",
+            Self::Stumax => "stumax",
+            Self::Stumin => "stumin",
+            Self::Ldraa => "ldraa",
+            Self::Ldrab => "ldrab",
+            Self::Ld1 => "ld1",
+            Self::Ld2 => "ld2",
+            Self::Ld3 => "ld3",
+            Self::Ld4 => "ld4",
+            Self::St1 => "st1",
+            Self::St2 => "st2",
+            Self::St3 => "st3",
+            Self::St4 => "st4",
+            Self::Ld1r => "ld1r",
+            Self::Ld2r => "ld2r",
+            Self::Ld3r => "ld3r",
+            Self::Ld4r => "ld4r",
+            Self::B => "b",
+            Self::Bl => "bl",
+            Self::BCond(_) => "b.",
+            Self::BcCond(_) => "bc.",
+            Self::Cbz => "cbz",
+            Self::Cbnz => "cbnz",
+            Self::Tbz => "tbz",
+            Self::Tbnz => "tbnz",
+            Self::Br => "br",
+            Self::Blr => "blr",
+            Self::Ret => "ret",
+            Self::Braa => "braa",
+            Self::Brab => "brab",
+            Self::Braaz => "braaz",
+            Self::Brabz => "brabz",
+            Self::Blraa => "blraa",
+            Self::Blrab => "blrab",
+            Self::Blraaz => "blraaz",
+            Self::Blrabz => "blrabz",
+            Self::Retaa => "retaa",
+            Self::Retab => "retab",
+            Self::Eret => "eret",
+            Self::Eretaa => "eretaa",
+            Self::Eretab => "eretab",
+            Self::Drps => "drps",
+            Self::Svc => "svc",
+            Self::Hvc => "hvc",
+            Self::Smc => "smc",
+            Self::Brk => "brk",
+            Self::Hlt => "hlt",
+            Self::Dcps1 => "dcps1",
+            Self::Dcps2 => "dcps2",
+            Self::Dcps3 => "dcps3",
+            Self::Msr => "msr",
+            Self::Mrs => "mrs",
+            Self::Sys => "sys",
+            Self::Sysl => "sysl",
+            Self::At => "at",
+            Self::Dc => "dc",
+            Self::Ic => "ic",
+            Self::Tlbi => "tlbi",
+            Self::Hint => "hint",
+            Self::Nop => "nop",
+            Self::Yield => "yield",
+            Self::Wfe => "wfe",
+            Self::Wfi => "wfi",
+            Self::Sev => "sev",
+            Self::Sevl => "sevl",
+            Self::Dgh => "dgh",
+            Self::Xpaclri => "xpaclri",
+            Self::Pacia1716 => "pacia1716",
+            Self::Pacib1716 => "pacib1716",
+            Self::Autia1716 => "autia1716",
+            Self::Autib1716 => "autib1716",
+            Self::Esb => "esb",
+            Self::PsbCsync => "psb csync",
+            Self::TsbCsync => "tsb csync",
+            Self::Csdb => "csdb",
+            Self::Paciaz => "paciaz",
+            Self::Paciasp => "paciasp",
+            Self::Pacibz => "pacibz",
+            Self::Pacibsp => "pacibsp",
+            Self::Autiaz => "autiaz",
+            Self::Autiasp => "autiasp",
+            Self::Autibz => "autibz",
+            Self::Autibsp => "autibsp",
+            Self::Bti => "bti",
+            Self::Clrex => "clrex",
+            Self::Dsb => "dsb",
+            Self::Dmb => "dmb",
+            Self::Isb => "isb",
+            Self::Sb => "sb",
+            Self::Wfet => "wfet",
+            Self::Wfit => "wfit",
+        }
+    }
+
+    /// Returns `true` if the memory ordering of the instruction is shown as a mnemonic suffix.
+    const fn has_ordering_suffix(self) -> bool {
+        matches!(
+            self,
+            Self::Cas
+                | Self::Casp
+                | Self::Ldadd
+                | Self::Ldclr
+                | Self::Ldeor
+                | Self::Ldset
+                | Self::Ldsmax
+                | Self::Ldsmin
+                | Self::Ldumax
+                | Self::Ldumin
+                | Self::Swp
+                | Self::Stadd
+                | Self::Stclr
+                | Self::Steor
+                | Self::Stset
+                | Self::Stsmax
+                | Self::Stsmin
+                | Self::Stumax
+                | Self::Stumin
+        )
+    }
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())?;
+        match self {
+            Self::BCond(condition) | Self::BcCond(condition) => f.write_str(condition.name()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Various errors that can occur when decoding an [`Instruction`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The instruction is an unallocated or reserved encoding.
+    Unallocated,
+    /// The instruction is not supported by the decoder.
+    Unsupported,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unallocated => write!(f, "unallocated instruction encoding"),
+            Self::Unsupported => write!(f, "unsupported instruction"),
+        }
+    }
+}
+
+impl error::Error for DecodeError {}
+
+/// Returns the `width`-bit field of `word` that starts at bit `shift`.
+const fn field(word: u32, shift: u32, width: u32) -> u32 {
+    (word >> shift) & ((1 << width) - 1)
+}
+
+/// Returns the 5-bit register number of `word` that starts at bit `shift`.
+const fn register(word: u32, shift: u32) -> u8 {
+    ((word >> shift) & 0x1F) as u8
+}
+
+/// Returns the `width`-bit field of `word` that starts at bit `shift`, sign-extended.
+const fn signed_field(word: u32, shift: u32, width: u32) -> i64 {
+    let value = (word >> shift) << (32 - width);
+    (value.cast_signed() >> (32 - width)) as i64
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use std::string::ToString;
+
+    use super::*;
+    use crate::decode::operand::{Base, Offset, Writeback};
+
+    #[test]
+    fn disassembly() {
+        let cases = [
+            (0xF9400020, "ldr x0, [x1]"),
+            (0xB9400BE2, "ldr w2, [sp, #8]"),
+            (0x397FFC83, "ldrb w3, [x4, #4095]"),
+            (0x798004C5, "ldrsh x5, [x6, #2]"),
+            (0xB89FCD07, "ldrsw x7, [x8, #-4]!"),
+            (0xF8010549, "str x9, [x10], #16"),
+            (0x781FED8B, "strh w11, [x12, #-2]!"),
+            (0xF85FF1CD, "ldur x13, [x14, #-1]"),
+            (0x3800320F, "sturb w15, [x16, #3]"),
+            (0xF8408A51, "ldtr x17, [x18, #8]"),
+            (0x78000A93, "sttrh w19, [x20]"),
+            (0xB89F8AD5, "ldtrsw x21, [x22, #-8]"),
+            (0xF8626820, "ldr x0, [x1, x2]"),
+            (0xF8627820, "ldr x0, [x1, x2, lsl #3]"),
+            (0x38627820, "ldrb w0, [x1, x2, lsl #0]"),
+            (0x38E2C820, "ldrsb w0, [x1, w2, sxtw]"),
+            (0xB8625820, "ldr w0, [x1, w2, uxtw #2]"),
+            (0x3CA2F820, "str q0, [x1, x2, sxtx #4]"),
+            (0x3DC00841, "ldr q1, [x2, #32]"),
+            (0xFC1F8FE3, "str d3, [sp, #-8]!"),
+            (0xBC4044A4, "ldr s4, [x5], #4"),
+            (0x7C5FE0E6, "ldur h6, [x7, #-2]"),
+            (0x3D400528, "ldr b8, [x9, #1]"),
+            (0xF9802000, "prfm pldl1keep, [x0, #64]"),
+            (0xF8A16813, "prfm pstl2strm, [x0, x1]"),
+            (0xF89FD00C, "prfum plil3keep, [x0, #-3]"),
+            (0x58000080, "ldr x0, #0x10"),
+            (0x18FFFFC1, "ldr w1, #-0x8"),
+            (0x98000022, "ldrsw x2, #0x4"),
+            (0x9C000103, "ldr q3, #0x20"),
+            (0xD8000001, "prfm pldl1strm, #0x0"),
+            (0xA94107E0, "ldp x0, x1, [sp, #16]"),
+            (0xA9BF7BFD, "stp x29, x30, [sp, #-16]!"),
+            (0x28C10C82, "ldp w2, w3, [x4], #8"),
+            (0x697F18E5, "ldpsw x5, x6, [x7, #-8]"),
+            (0xAC410440, "ldnp q0, q1, [x2, #32]"),
+            (0x6C000C82, "stnp d2, d3, [x4]"),
+            (0x2D8104A0, "stp s0, s1, [x5, #8]!"),
+            (0xC85F7C20, "ldxr x0, [x1]"),
+            (0x085FFC62, "ldaxrb w2, [x3]"),
+            (0xC8047CC5, "stxr w4, x5, [x6]"),
+            (0x4807FFE8, "stlxrh w7, w8, [sp]"),
+            (0xC87F0440, "ldxp x0, x1, [x2]"),
+            (0x882394C4, "stlxp w3, w4, w5, [x6]"),
+            (0xC8DFFC20, "ldar x0, [x1]"),
+            (0x089FFC62, "stlrb w2, [x3]"),
+            (0x48DF7CA4, "ldlarh w4, [x5]"),
+            (0xC89F7CE6, "stllr x6, [x7]"),
+            (0xC8A07C41, "cas x0, x1, [x2]"),
+            (0x08E3FCA4, "casalb w3, w4, [x5]"),
+            (0x4860FC82, "caspal x0, x1, x2, x3, [x4]"),
+            (0x08267FE8, "casp w6, w7, w8, w9, [sp]"),
+            (0xF8200041, "ldadd x0, x1, [x2]"),
+            (0x78E300A4, "ldaddalh w3, w4, [x5]"),
+            (0xF8E01041, "ldclral x0, x1, [x2]"),
+            (0x38204041, "ldsmaxb w0, w1, [x2]"),
+            (0xF820003F, "stadd x0, [x1]"),
+            (0x3862207F, "steorlb w2, [x3]"),
+            (0xF8E08041, "swpal x0, x1, [x2]"),
+            (0x382380A4, "swpb w3, w4, [x5]"),
+            (0x38BFC020, "ldaprb w0, [x1]"),
+            (0xF8BFC062, "ldapr x2, [x3]"),
+            (0xD91F8020, "stlur x0, [x1, #-8]"),
+            (0x19C01062, "ldapursb w2, [x3, #1]"),
+            (0x998000A4, "ldapursw x4, [x5]"),
+            (0xF8201420, "ldraa x0, [x1, #8]"),
+            (0xF8E00C62, "ldrab x2, [x3, #-4096]!"),
+            (0x4C407000, "ld1 {v0.16b}, [x0]"),
+            (0x4CDFA820, "ld1 {v0.4s, v1.4s}, [x1], #32"),
+            (0x4CC30440, "ld4 {v0.8h, v1.8h, v2.8h, v3.8h}, [x2], x3"),
+            (0x4C008C9E, "st2 {v30.2d, v31.2d}, [x4]"),
+            (0x0C9F40A1, "st3 {v1.8b, v2.8b, v3.8b}, [x5], #24"),
+            (0x0C406CC0, "ld1 {v0.1d, v1.1d, v2.1d}, [x6]"),
+            (0x0D409000, "ld1 {v0.s}[1], [x0]"),
+            (0x4D9F8421, "st1 {v1.d}[1], [x1], #8"),
+            (0x4DE45842, "ld2 {v2.h, v3.h}[7], [x2], x4"),
+            (0x4D203C64, "st4 {v4.b, v5.b, v6.b, v7.b}[15], [x3]"),
+            (0x4D40C800, "ld1r {v0.4s}, [x0]"),
+            (0x4DDFEC21, "ld3r {v1.2d, v2.2d, v3.2d}, [x1], #24"),
+            (0x14000040, "b #0x100"),
+            (0x97FFFFFF, "bl #-0x4"),
+            (0x54000040, "b.eq #0x8"),
+            (0x54FFFF81, "b.ne #-0x10"),
+            (0xB4000100, "cbz x0, #0x20"),
+            (0x35FFFF01, "cbnz w1, #-0x20"),
+            (0x36180202, "tbz w2, #0x3, #0x40"),
+            (0xB7FFFFE3, "tbnz x3, #0x3f, #-0x4"),
+            (0xD61F0000, "br x0"),
+            (0xD63F0020, "blr x1"),
+            (0xD65F03C0, "ret"),
+            (0xD65F0040, "ret x2"),
+            (0xD69F03E0, "eret"),
+            (0xD71F0801, "braa x0, x1"),
+            (0xD63F0C5F, "blrabz x2"),
+            (0xD65F0BFF, "retaa"),
+            (0xD69F0FFF, "eretab"),
+            (0xD4000001, "svc #0x0"),
+            (0xD4000022, "hvc #0x1"),
+            (0xD4000043, "smc #0x2"),
+            (0xD4207D00, "brk #0x3e8"),
+            (0xD45E0000, "hlt #0xf000"),
+            (0xD5380000, "mrs x0, midr_el1"),
+            (0xD51C1101, "msr hcr_el2, x1"),
+            (0xD53FF202, "mrs x2, s3_7_c15_c2_0"),
+            (0xD50342DF, "msr daifset, #0x2"),
+            (0xD50041BF, "msr spsel, #0x1"),
+            (0xD503201F, "nop"),
+            (0xD503207F, "wfi"),
+            (0xD503205F, "wfe"),
+            (0xD503203F, "yield"),
+            (0xD5032FFF, "hint #0x7f"),
+            (0xD503245F, "bti c"),
+            (0xD503233F, "paciasp"),
+            (0xD50323FF, "autibsp"),
+            (0xD5033F9F, "dsb sy"),
+            (0xD50339BF, "dmb ishld"),
+            (0xD5033FDF, "isb"),
+            (0xD5033F5F, "clrex"),
+            (0xD50330FF, "sb"),
+            (0xD50B7E20, "dc civac, x0"),
+            (0xD508751F, "ic iallu"),
+            (0xD508831F, "tlbi vmalle1is"),
+            (0xD50C8723, "tlbi vae2, x3"),
+            (0xD5087804, "at s1e1r, x4"),
+            (0xD5092385, "sys #1, c2, c3, #4, x5"),
+            (0xD52A34A6, "sysl x6, #2, c3, c4, #5"),
+        ];
+
+        for (word, expected) in cases {
+            let instruction = decode(word).unwrap_or_else(|error| panic!("{word:#010x}: {error}"));
+            assert_eq!(instruction.to_string(), expected, "{word:#010x}");
+        }
+    }
+
+    #[test]
+    fn decode_errors() {
+        let cases = [
+            // add x0, x1, x2
+            (0x8B02_0020, DecodeError::Unsupported),
+            // stg x0, [x1]
+            (0xD920_0820, DecodeError::Unsupported),
+            // ldr literal with a reserved SIMD&FP size
+            (0xDC00_0000, DecodeError::Unallocated),
+            // ldp with a reserved opc
+            (0xE940_0000, DecodeError::Unallocated),
+            // ldr (register) with a reserved extend option
+            (0xF862_0820, DecodeError::Unallocated),
+            // casp with an odd first register
+            (0x0827_7FE8, DecodeError::Unallocated),
+            // ldtr to a SIMD&FP register
+            (0xFC40_0800, DecodeError::Unallocated),
+            // exception generation with a reserved opc
+            (0xD4E0_0000, DecodeError::Unallocated),
+            // br with a nonzero op4
+            (0xD61F_0001, DecodeError::Unallocated),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(decode(word), Err(expected), "{word:#010x}");
+        }
+    }
+
+    #[test]
+    fn memory_access() {
+        // stp x29, x30, [sp, #-16]!
+        let instruction = decode(0xA9BF_7BFD).unwrap();
+        let access = instruction.memory_access().unwrap();
+        assert_eq!(access.kind, AccessKind::Store);
+        assert_eq!((access.size, access.count), (8, 2));
+        assert_eq!(access.total_size(), 16);
+        assert_eq!(
+            instruction.operands()[..2],
+            [
+                Operand::Register(Register::X(29)),
+                Operand::Register(Register::X(30))
+            ]
+        );
+        let address = instruction.memory_operand().unwrap();
+        assert_eq!(address.base, Base::Register(Register::Sp));
+        assert_eq!(address.offset, Offset::Immediate(-16));
+        assert_eq!(address.writeback, Writeback::PreIndex);
+        let read = |register| {
+            assert_eq!(register, Register::Sp);
+            0x1000
+        };
+        assert_eq!(address.effective_address(0, read), 0xFF0);
+        assert_eq!(address.written_back_address(0, read), Some(0xFF0));
+
+        // str x9, [x10], #16
+        let address = *decode(0xF801_0549).unwrap().memory_operand().unwrap();
+        assert_eq!(address.effective_address(0, |_| 0x2000), 0x2000);
+        assert_eq!(address.written_back_address(0, |_| 0x2000), Some(0x2010));
+
+        // ldrsw x7, [x8, #-4]!
+        let access = *decode(0xB89F_CD07).unwrap().memory_access().unwrap();
+        assert_eq!(access.kind, AccessKind::Load);
+        assert_eq!(access.size, 4);
+        assert!(access.sign_extend);
+
+        // ldr w0, [x1, w2, uxtw #2]
+        let address = *decode(0xB862_5820).unwrap().memory_operand().unwrap();
+        let read = |register| match register {
+            Register::X(1) => 0x1000,
+            Register::W(2) => 0xFFFF_FFFF_0000_0001,
+            register => panic!("unexpected register {register}"),
+        };
+        assert_eq!(address.effective_address(0, read), 0x1004);
+        assert_eq!(address.written_back_address(0, read), None);
+
+        // ldrsb w0, [x1, w2, sxtw]
+        let address = *decode(0x38E2_C820).unwrap().memory_operand().unwrap();
+        let read = |register| match register {
+            Register::X(1) => 0x1000,
+            Register::W(2) => 0xFFFF_FFFF,
+            register => panic!("unexpected register {register}"),
+        };
+        assert_eq!(address.effective_address(0, read), 0xFFF);
+
+        // ldr x0, #16
+        let address = *decode(0x5800_0080).unwrap().memory_operand().unwrap();
+        assert_eq!(address.base, Base::Pc);
+        assert_eq!(
+            address.effective_address(0x4000, |_| unreachable!()),
+            0x4010
+        );
+
+        // ldaxrb w2, [x3]
+        let access = *decode(0x085F_FC62).unwrap().memory_access().unwrap();
+        assert_eq!(access.size, 1);
+        assert!(access.exclusive && access.acquire && !access.release);
+
+        // casalb w3, w4, [x5]
+        let access = *decode(0x08E3_FCA4).unwrap().memory_access().unwrap();
+        assert_eq!(access.kind, AccessKind::ReadModifyWrite);
+        assert!(access.acquire && access.release && !access.exclusive);
+
+        // ldtr x17, [x18, #8]
+        assert!(
+            decode(0xF840_8A51)
+                .unwrap()
+                .memory_access()
+                .unwrap()
+                .unprivileged
+        );
+
+        // ld4 {v0.8h, v1.8h, v2.8h, v3.8h}, [x2], x3
+        let instruction = decode(0x4CC3_0440).unwrap();
+        let access = instruction.memory_access().unwrap();
+        assert!(access.vector);
+        assert_eq!((access.size, access.count), (16, 4));
+        let address = instruction.memory_operand().unwrap();
+        assert_eq!(address.writeback, Writeback::PostIndex);
+        assert_eq!(
+            address.written_back_address(0, |register| match register {
+                Register::X(2) => 0x8000,
+                Register::X(3) => 0x40,
+                register => panic!("unexpected register {register}"),
+            }),
+            Some(0x8040)
+        );
+
+        // b.eq #0x8, svc #0x0, and mrs x0, midr_el1 do not access memory
+        for word in [0x5400_0040, 0xD400_0001, 0xD538_0000] {
+            assert_eq!(decode(word).unwrap().memory_access(), None, "{word:#010x}");
+        }
+    }
+}
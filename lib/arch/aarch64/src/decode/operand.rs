@@ -0,0 +1,652 @@
+//! Registers, addresses, and immediates that make up the operands of an instruction.
+
+use core::fmt;
+
+use crate::esr::SystemRegisterEncoding;
+
+/// A general purpose or SIMD&FP register.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Register {
+    /// The 64-bit general purpose register `Xn`, where `n` is in `0..=30`.
+    X(u8),
+    /// The 32-bit general purpose register `Wn`, where `n` is in `0..=30`.
+    W(u8),
+    /// The 64-bit zero register.
+    Xzr,
+    /// The 32-bit zero register.
+    Wzr,
+    /// The 64-bit stack pointer.
+    Sp,
+    /// The 32-bit stack pointer.
+    Wsp,
+    /// The 8-bit SIMD&FP register `Bn`.
+    B(u8),
+    /// The 16-bit SIMD&FP register `Hn`.
+    H(u8),
+    /// The 32-bit SIMD&FP register `Sn`.
+    S(u8),
+    /// The 64-bit SIMD&FP register `Dn`.
+    D(u8),
+    /// The 128-bit SIMD&FP register `Qn`.
+    Q(u8),
+}
+
+impl Register {
+    /// Returns the general purpose register encoded as `index`, where index 31 selects the zero
+    /// register.
+    pub(crate) const fn zr(index: u8, is_64: bool) -> Self {
+        match (index, is_64) {
+            (31, true) => Self::Xzr,
+            (31, false) => Self::Wzr,
+            (index, true) => Self::X(index),
+            (index, false) => Self::W(index),
+        }
+    }
+
+    /// Returns the general purpose register encoded as `index`, where index 31 selects the stack
+    /// pointer.
+    pub(crate) const fn sp(index: u8, is_64: bool) -> Self {
+        match (index, is_64) {
+            (31, true) => Self::Sp,
+            (31, false) => Self::Wsp,
+            (index, true) => Self::X(index),
+            (index, false) => Self::W(index),
+        }
+    }
+
+    /// Returns the SIMD&FP register encoded as `index` that holds `size` bytes.
+    pub(crate) const fn vector(index: u8, size: u8) -> Self {
+        match size {
+            1 => Self::B(index),
+            2 => Self::H(index),
+            4 => Self::S(index),
+            8 => Self::D(index),
+            _ => Self::Q(index),
+        }
+    }
+
+    /// Returns the register number as encoded in an instruction.
+    ///
+    /// The zero register and the stack pointer are both register number 31.
+    pub const fn index(self) -> u8 {
+        match self {
+            Self::X(index)
+            | Self::W(index)
+            | Self::B(index)
+            | Self::H(index)
+            | Self::S(index)
+            | Self::D(index)
+            | Self::Q(index) => index,
+            Self::Xzr | Self::Wzr | Self::Sp | Self::Wsp => 31,
+        }
+    }
+
+    /// Returns the size, in bytes, of the register.
+    pub const fn size(self) -> u8 {
+        match self {
+            Self::B(_) => 1,
+            Self::H(_) => 2,
+            Self::W(_) | Self::Wzr | Self::Wsp | Self::S(_) => 4,
+            Self::X(_) | Self::Xzr | Self::Sp | Self::D(_) => 8,
+            Self::Q(_) => 16,
+        }
+    }
+
+    /// Returns `true` if the register is a SIMD&FP register.
+    pub const fn is_vector(self) -> bool {
+        matches!(
+            self,
+            Self::B(_) | Self::H(_) | Self::S(_) | Self::D(_) | Self::Q(_)
+        )
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::X(index) => write!(f, "x{index}"),
+            Self::W(index) => write!(f, "w{index}"),
+            Self::Xzr => f.write_str("xzr"),
+            Self::Wzr => f.write_str("wzr"),
+            Self::Sp => f.write_str("sp"),
+            Self::Wsp => f.write_str("wsp"),
+            Self::B(index) => write!(f, "b{index}"),
+            Self::H(index) => write!(f, "h{index}"),
+            Self::S(index) => write!(f, "s{index}"),
+            Self::D(index) => write!(f, "d{index}"),
+            Self::Q(index) => write!(f, "q{index}"),
+        }
+    }
+}
+
+/// The arrangement of elements in a SIMD&FP vector register.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Arrangement {
+    /// Eight 8-bit elements in a 64-bit vector.
+    B8,
+    /// Sixteen 8-bit elements in a 128-bit vector.
+    B16,
+    /// Four 16-bit elements in a 64-bit vector.
+    H4,
+    /// Eight 16-bit elements in a 128-bit vector.
+    H8,
+    /// Two 32-bit elements in a 64-bit vector.
+    S2,
+    /// Four 32-bit elements in a 128-bit vector.
+    S4,
+    /// One 64-bit element in a 64-bit vector.
+    D1,
+    /// Two 64-bit elements in a 128-bit vector.
+    D2,
+}
+
+impl Arrangement {
+    /// Returns the [`Arrangement`] encoded by the `size` and `Q` fields of an instruction.
+    pub(crate) const fn from_bits(size: u32, q: bool) -> Self {
+        match (size, q) {
+            (0, false) => Self::B8,
+            (0, true) => Self::B16,
+            (1, false) => Self::H4,
+            (1, true) => Self::H8,
+            (2, false) => Self::S2,
+            (2, true) => Self::S4,
+            (_, false) => Self::D1,
+            (_, true) => Self::D2,
+        }
+    }
+
+    /// Returns the size, in bytes, of each element.
+    pub const fn element_size(self) -> u8 {
+        match self {
+            Self::B8 | Self::B16 => 1,
+            Self::H4 | Self::H8 => 2,
+            Self::S2 | Self::S4 => 4,
+            Self::D1 | Self::D2 => 8,
+        }
+    }
+
+    /// Returns the size, in bytes, of the vector.
+    pub const fn size(self) -> u8 {
+        match self {
+            Self::B8 | Self::H4 | Self::S2 | Self::D1 => 8,
+            Self::B16 | Self::H8 | Self::S4 | Self::D2 => 16,
+        }
+    }
+
+    /// Returns the name of the arrangement, as used in assembly.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::B8 => "8b",
+            Self::B16 => "16b",
+            Self::H4 => "4h",
+            Self::H8 => "8h",
+            Self::S2 => "2s",
+            Self::S4 => "4s",
+            Self::D1 => "1d",
+            Self::D2 => "2d",
+        }
+    }
+}
+
+/// The part of each SIMD&FP register in a [`VectorList`] that is accessed.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum VectorLayout {
+    /// All elements of each register, in the given [`Arrangement`].
+    Arrangement(Arrangement),
+    /// A single element of each register.
+    Element {
+        /// The size, in bytes, of the element.
+        size: u8,
+        /// The index of the element.
+        index: u8,
+    },
+}
+
+/// A list of consecutively numbered SIMD&FP registers, as used by structure loads and stores.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct VectorList {
+    /// The number of the first register in the list.
+    pub first: u8,
+    /// The number of registers in the list.
+    pub count: u8,
+    /// The part of each register that is accessed.
+    pub layout: VectorLayout,
+}
+
+impl fmt::Display for VectorList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("{")?;
+        for offset in 0..self.count {
+            if offset != 0 {
+                f.write_str(", ")?;
+            }
+
+            let register = (self.first + offset) % 32;
+            match self.layout {
+                VectorLayout::Arrangement(arrangement) => {
+                    write!(f, "v{register}.{}", arrangement.name())?
+                }
+                VectorLayout::Element { size, .. } => {
+                    let suffix = match size {
+                        1 => "b",
+                        2 => "h",
+                        4 => "s",
+                        _ => "d",
+                    };
+                    write!(f, "v{register}.{suffix}")?
+                }
+            }
+        }
+        f.write_str("}")?;
+
+        if let VectorLayout::Element { index, .. } = self.layout {
+            write!(f, "[{index}]")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The extension applied to an offset register before it is added to the base address.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Extend {
+    /// The low 32 bits of the register are zero-extended.
+    Uxtw,
+    /// The full 64-bit register is used.
+    Lsl,
+    /// The low 32 bits of the register are sign-extended.
+    Sxtw,
+    /// The full 64-bit register is used.
+    Sxtx,
+}
+
+impl Extend {
+    /// Returns the result of extending `value` and shifting it left by `shift`.
+    pub const fn apply(self, value: u64, shift: u8) -> u64 {
+        let extended = match self {
+            Self::Uxtw => value & 0xFFFF_FFFF,
+            Self::Sxtw => (((value & 0xFFFF_FFFF) as u32).cast_signed() as i64).cast_unsigned(),
+            Self::Lsl | Self::Sxtx => value,
+        };
+
+        extended << shift
+    }
+
+    /// Returns the name of the extension, as used in assembly.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Uxtw => "uxtw",
+            Self::Lsl => "lsl",
+            Self::Sxtw => "sxtw",
+            Self::Sxtx => "sxtx",
+        }
+    }
+}
+
+/// The base of an [`Address`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Base {
+    /// A general purpose register or the stack pointer.
+    Register(Register),
+    /// The address of the instruction itself, as used by literal loads.
+    Pc,
+}
+
+/// The offset of an [`Address`] from its [`Base`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Offset {
+    /// A constant offset.
+    Immediate(i64),
+    /// A register, extended and then shifted left.
+    Register {
+        /// The register that holds the offset.
+        register: Register,
+        /// The extension applied to the register.
+        extend: Extend,
+        /// The amount by which the extended register is shifted, if an amount is encoded.
+        shift: Option<u8>,
+    },
+}
+
+/// Whether, and when, the base register of an [`Address`] is updated.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Writeback {
+    /// The base register is not updated.
+    None,
+    /// The offset is added to the base register before the access, which uses the updated base.
+    PreIndex,
+    /// The access uses the original base register, which is then updated by the offset.
+    PostIndex,
+}
+
+/// The address accessed by a load, store, or atomic instruction.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Address {
+    /// The base of the address.
+    pub base: Base,
+    /// The offset from the base.
+    pub offset: Offset,
+    /// How the base register is updated.
+    pub writeback: Writeback,
+}
+
+impl Address {
+    /// Returns the address of the first byte accessed.
+    ///
+    /// `pc` is the address of the instruction and `read` returns the value of the given general
+    /// purpose register. [`Register::Sp`] is passed to `read` when the base is the stack pointer.
+    pub fn effective_address(&self, pc: u64, mut read: impl FnMut(Register) -> u64) -> u64 {
+        let base = self.base_value(pc, &mut read);
+        match self.writeback {
+            Writeback::PostIndex => base,
+            Writeback::None | Writeback::PreIndex => {
+                base.wrapping_add(self.offset_value(&mut read))
+            }
+        }
+    }
+
+    /// Returns the value written back to the base register, if the address has writeback.
+    ///
+    /// `pc` and `read` have the same meaning as for [`Address::effective_address()`].
+    pub fn written_back_address(
+        &self,
+        pc: u64,
+        mut read: impl FnMut(Register) -> u64,
+    ) -> Option<u64> {
+        match self.writeback {
+            Writeback::None => None,
+            Writeback::PreIndex | Writeback::PostIndex => Some(
+                self.base_value(pc, &mut read)
+                    .wrapping_add(self.offset_value(&mut read)),
+            ),
+        }
+    }
+
+    /// Returns the value of the [`Base`].
+    fn base_value(&self, pc: u64, read: &mut impl FnMut(Register) -> u64) -> u64 {
+        match self.base {
+            Base::Register(register) => read(register),
+            Base::Pc => pc,
+        }
+    }
+
+    /// Returns the value of the [`Offset`].
+    fn offset_value(&self, read: &mut impl FnMut(Register) -> u64) -> u64 {
+        match self.offset {
+            Offset::Immediate(offset) => offset.cast_unsigned(),
+            Offset::Register {
+                register: Register::Xzr | Register::Wzr,
+                ..
+            } => 0,
+            Offset::Register {
+                register,
+                extend,
+                shift,
+            } => extend.apply(read(register), shift.unwrap_or(0)),
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let base = match self.base {
+            Base::Register(register) => register,
+            Base::Pc => {
+                let Offset::Immediate(offset) = self.offset else {
+                    return Err(fmt::Error);
+                };
+                return fmt::Display::fmt(&Operand::Label(offset), f);
+            }
+        };
+
+        match (self.writeback, self.offset) {
+            (Writeback::PostIndex, Offset::Immediate(offset)) => write!(f, "[{base}], #{offset}"),
+            (Writeback::PostIndex, Offset::Register { register, .. }) => {
+                write!(f, "[{base}], {register}")
+            }
+            (_, Offset::Immediate(0)) if self.writeback == Writeback::None => write!(f, "[{base}]"),
+            (writeback, offset) => {
+                write!(f, "[{base}, ")?;
+                match offset {
+                    Offset::Immediate(offset) => write!(f, "#{offset}")?,
+                    Offset::Register {
+                        register,
+                        extend,
+                        shift,
+                    } => {
+                        write!(f, "{register}")?;
+                        match (extend, shift) {
+                            (Extend::Lsl, None) => {}
+                            (extend, None) => write!(f, ", {}", extend.name())?,
+                            (extend, Some(shift)) => write!(f, ", {} #{shift}", extend.name())?,
+                        }
+                    }
+                }
+                f.write_str("]")?;
+                if writeback == Writeback::PreIndex {
+                    f.write_str("!")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A prefetch operation, as encoded in the `Rt` field of `PRFM` and `PRFUM`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PrefetchOperation(pub u8);
+
+impl fmt::Display for PrefetchOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.0 >> 3 {
+            0b00 => "pld",
+            0b01 => "pli",
+            0b10 => "pst",
+            _ => return write!(f, "#{:#x}", self.0),
+        };
+        let target = match (self.0 >> 1) & 0b11 {
+            0b00 => "l1",
+            0b01 => "l2",
+            0b10 => "l3",
+            _ => return write!(f, "#{:#x}", self.0),
+        };
+        let policy = if self.0 & 1 == 0 { "keep" } else { "strm" };
+
+        write!(f, "{kind}{target}{policy}")
+    }
+}
+
+/// The domain and access types ordered by a `DMB` or `DSB` barrier, as encoded in `CRm`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct BarrierOption(pub u8);
+
+impl fmt::Display for BarrierOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.0 {
+            0b0001 => "oshld",
+            0b0010 => "oshst",
+            0b0011 => "osh",
+            0b0101 => "nshld",
+            0b0110 => "nshst",
+            0b0111 => "nsh",
+            0b1001 => "ishld",
+            0b1010 => "ishst",
+            0b1011 => "ish",
+            0b1101 => "ld",
+            0b1110 => "st",
+            0b1111 => "sy",
+            option => return write!(f, "#{option:#x}"),
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// A process state field written by `MSR (immediate)`, as encoded in `op1` and `op2`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PStateField {
+    /// The `op1` field of the encoding.
+    pub op1: u8,
+    /// The `op2` field of the encoding.
+    pub op2: u8,
+}
+
+impl fmt::Display for PStateField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match (self.op1, self.op2) {
+            (0, 3) => "uao",
+            (0, 4) => "pan",
+            (0, 5) => "spsel",
+            (3, 1) => "ssbs",
+            (3, 2) => "dit",
+            (3, 4) => "tco",
+            (3, 6) => "daifset",
+            (3, 7) => "daifclr",
+            (op1, op2) => return write!(f, "s0_{op1}_c4_c0_{op2}"),
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// The operation performed by a `SYS` or `SYSL` instruction.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct SystemOperation {
+    /// The `op1` field of the encoding.
+    pub op1: u8,
+    /// The `CRn` field of the encoding.
+    pub crn: u8,
+    /// The `CRm` field of the encoding.
+    pub crm: u8,
+    /// The `op2` field of the encoding.
+    pub op2: u8,
+}
+
+impl SystemOperation {
+    /// Returns the name of the `AT`, `DC`, `IC`, or `TLBI` operation, if it is a commonly used
+    /// operation known to this crate.
+    pub const fn name(self) -> Option<&'static str> {
+        let name = match (self.op1, self.crn, self.crm, self.op2) {
+            (0, 7, 1, 0) => "ialluis",
+            (0, 7, 5, 0) => "iallu",
+            (3, 7, 5, 1) => "ivau",
+            (0, 7, 6, 1) => "ivac",
+            (0, 7, 6, 2) => "isw",
+            (0, 7, 10, 2) => "csw",
+            (0, 7, 14, 2) => "cisw",
+            (3, 7, 4, 1) => "zva",
+            (3, 7, 10, 1) => "cvac",
+            (3, 7, 11, 1) => "cvau",
+            (3, 7, 12, 1) => "cvap",
+            (3, 7, 14, 1) => "civac",
+            (0, 7, 8, 0) => "s1e1r",
+            (0, 7, 8, 1) => "s1e1w",
+            (0, 7, 8, 2) => "s1e0r",
+            (0, 7, 8, 3) => "s1e0w",
+            (4, 7, 8, 0) => "s1e2r",
+            (4, 7, 8, 1) => "s1e2w",
+            (4, 7, 8, 4) => "s12e1r",
+            (4, 7, 8, 5) => "s12e1w",
+            (0, 8, 3, 0) => "vmalle1is",
+            (0, 8, 3, 1) => "vae1is",
+            (0, 8, 3, 2) => "aside1is",
+            (0, 8, 7, 0) => "vmalle1",
+            (0, 8, 7, 1) => "vae1",
+            (0, 8, 7, 2) => "aside1",
+            (0, 8, 7, 3) => "vaae1",
+            (4, 8, 0, 1) => "ipas2e1is",
+            (4, 8, 4, 1) => "ipas2e1",
+            (4, 8, 3, 0) => "alle2is",
+            (4, 8, 3, 1) => "vae2is",
+            (4, 8, 3, 4) => "alle1is",
+            (4, 8, 3, 6) => "vmalls12e1is",
+            (4, 8, 7, 0) => "alle2",
+            (4, 8, 7, 1) => "vae2",
+            (4, 8, 7, 4) => "alle1",
+            (4, 8, 7, 6) => "vmalls12e1",
+            _ => return None,
+        };
+
+        Some(name)
+    }
+}
+
+impl fmt::Display for SystemOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => f.write_str(name),
+            None => write!(
+                f,
+                "#{}, c{}, c{}, #{}",
+                self.op1, self.crn, self.crm, self.op2
+            ),
+        }
+    }
+}
+
+/// The kinds of indirect branch accepted by a `BTI` landing pad.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum BranchTarget {
+    /// Indirect calls (`BTI c`).
+    Call,
+    /// Indirect jumps (`BTI j`).
+    Jump,
+    /// Indirect calls and jumps (`BTI jc`).
+    JumpCall,
+}
+
+impl fmt::Display for BranchTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Call => "c",
+            Self::Jump => "j",
+            Self::JumpCall => "jc",
+        })
+    }
+}
+
+/// An operand of an instruction.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Operand {
+    /// A general purpose or SIMD&FP register.
+    Register(Register),
+    /// A list of SIMD&FP registers.
+    VectorList(VectorList),
+    /// A memory address.
+    Memory(Address),
+    /// An unsigned immediate.
+    Immediate(u64),
+    /// A branch or literal target, as a byte offset from the address of the instruction.
+    Label(i64),
+    /// A system register accessed by `MSR` or `MRS`.
+    SystemRegister(SystemRegisterEncoding),
+    /// A process state field written by `MSR (immediate)`.
+    PStateField(PStateField),
+    /// The operation performed by a system instruction.
+    SystemOperation(SystemOperation),
+    /// A prefetch operation.
+    Prefetch(PrefetchOperation),
+    /// A barrier option.
+    Barrier(BarrierOption),
+    /// The kinds of branch accepted by a `BTI` landing pad.
+    BranchTarget(BranchTarget),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Register(register) => fmt::Display::fmt(register, f),
+            Self::VectorList(list) => fmt::Display::fmt(list, f),
+            Self::Memory(address) => fmt::Display::fmt(address, f),
+            Self::Immediate(value) => write!(f, "#{value:#x}"),
+            Self::Label(offset) if *offset < 0 => write!(f, "#-{:#x}", offset.unsigned_abs()),
+            Self::Label(offset) => write!(f, "#{offset:#x}"),
+            Self::SystemRegister(register) => fmt::Display::fmt(register, f),
+            Self::PStateField(field) => fmt::Display::fmt(field, f),
+            Self::SystemOperation(operation) => fmt::Display::fmt(operation, f),
+            Self::Prefetch(operation) => fmt::Display::fmt(operation, f),
+            Self::Barrier(option) => fmt::Display::fmt(option, f),
+            Self::BranchTarget(target) => fmt::Display::fmt(target, f),
+        }
+    }
+}
@@ -0,0 +1,1258 @@
+//! Decoding of the exception syndrome reported in the `ESR_ELx` registers.
+//!
+//! An [`ExceptionSyndrome`] wraps the raw value of an `ESR_ELx` register and can be decoded into
+//! a [`Syndrome`], which exposes the instruction specific syndrome (`ISS`) fields relevant to the
+//! [`ExceptionClass`] of the exception.
+
+use core::fmt;
+
+/// The value of an `ESR_ELx` register.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct ExceptionSyndrome(u64);
+
+impl ExceptionSyndrome {
+    /// Creates an [`ExceptionSyndrome`] from the raw value of an `ESR_ELx` register.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`ExceptionSyndrome`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the [`ExceptionClass`] of the exception (`EC`).
+    pub const fn exception_class(self) -> ExceptionClass {
+        ExceptionClass::from_bits(((self.0 >> 26) & 0x3F) as u8)
+    }
+
+    /// Returns `true` if the trapped instruction was 32 bits long (`IL`).
+    pub const fn instruction_length_32(self) -> bool {
+        ((self.0 >> 25) & 0b1) == 0b1
+    }
+
+    /// Returns the instruction specific syndrome (`ISS`).
+    pub const fn instruction_specific_syndrome(self) -> u32 {
+        (self.0 & 0x1FF_FFFF) as u32
+    }
+
+    /// Returns the additional instruction specific syndrome (`ISS2`).
+    pub const fn instruction_specific_syndrome_2(self) -> u32 {
+        ((self.0 >> 32) & 0xFF_FFFF) as u32
+    }
+
+    /// Decodes the instruction specific syndrome according to the [`ExceptionClass`].
+    pub const fn decode(self) -> Syndrome {
+        let iss = self.instruction_specific_syndrome();
+        let immediate = (iss & 0xFFFF) as u16;
+
+        match self.exception_class() {
+            ExceptionClass::TrappedWfx => Syndrome::Wfx(WfxSyndrome(iss)),
+            ExceptionClass::SvcAarch64 => Syndrome::Svc(immediate),
+            ExceptionClass::HvcAarch64 => Syndrome::Hvc(immediate),
+            ExceptionClass::SmcAarch64 => Syndrome::Smc(immediate),
+            ExceptionClass::TrappedSystemRegister => {
+                Syndrome::SystemRegister(SystemRegisterSyndrome(iss))
+            }
+            ExceptionClass::InstructionAbortLowerEl => Syndrome::InstructionAbort {
+                lower_el: true,
+                syndrome: InstructionAbortSyndrome(iss),
+            },
+            ExceptionClass::InstructionAbortCurrentEl => Syndrome::InstructionAbort {
+                lower_el: false,
+                syndrome: InstructionAbortSyndrome(iss),
+            },
+            ExceptionClass::DataAbortLowerEl => Syndrome::DataAbort {
+                lower_el: true,
+                syndrome: DataAbortSyndrome(iss),
+            },
+            ExceptionClass::DataAbortCurrentEl => Syndrome::DataAbort {
+                lower_el: false,
+                syndrome: DataAbortSyndrome(iss),
+            },
+            ExceptionClass::BreakpointLowerEl => Syndrome::Breakpoint { lower_el: true },
+            ExceptionClass::BreakpointCurrentEl => Syndrome::Breakpoint { lower_el: false },
+            ExceptionClass::SoftwareStepLowerEl => Syndrome::SoftwareStep {
+                lower_el: true,
+                syndrome: SoftwareStepSyndrome(iss),
+            },
+            ExceptionClass::SoftwareStepCurrentEl => Syndrome::SoftwareStep {
+                lower_el: false,
+                syndrome: SoftwareStepSyndrome(iss),
+            },
+            ExceptionClass::WatchpointLowerEl => Syndrome::Watchpoint {
+                lower_el: true,
+                syndrome: WatchpointSyndrome(iss),
+            },
+            ExceptionClass::WatchpointCurrentEl => Syndrome::Watchpoint {
+                lower_el: false,
+                syndrome: WatchpointSyndrome(iss),
+            },
+            ExceptionClass::BrkAarch64 => Syndrome::Brk(immediate),
+            class => Syndrome::Other { class, iss },
+        }
+    }
+}
+
+impl fmt::Debug for ExceptionSyndrome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExceptionSyndrome")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("exception_class", &self.exception_class())
+            .field("instruction_length_32", &self.instruction_length_32())
+            .field("syndrome", &self.decode())
+            .finish()
+    }
+}
+
+/// The class of an exception, as reported in `ESR_ELx.EC`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ExceptionClass {
+    /// The exception was taken for an unknown reason.
+    Unknown,
+    /// A trapped `WFI`, `WFE`, `WFIT`, or `WFET` instruction.
+    TrappedWfx,
+    /// A trapped `MCR` or `MRC` access to coprocessor 15.
+    TrappedMcrMrcCp15,
+    /// A trapped `MCRR` or `MRRC` access to coprocessor 15.
+    TrappedMcrrMrrcCp15,
+    /// A trapped `MCR` or `MRC` access to coprocessor 14.
+    TrappedMcrMrcCp14,
+    /// A trapped `LDC` or `STC` access.
+    TrappedLdcStc,
+    /// A trapped access to SVE, Advanced SIMD, or floating-point functionality.
+    TrappedFloatingPoint,
+    /// A trapped `VMRS` access.
+    TrappedVmrs,
+    /// A trapped pointer authentication instruction.
+    TrappedPointerAuthentication,
+    /// A trapped `LD64B`, `ST64B`, `ST64BV`, or `ST64BV0` instruction.
+    TrappedLd64bSt64b,
+    /// A trapped `MRRC` access to coprocessor 14.
+    TrappedMrrcCp14,
+    /// A branch target identification exception.
+    BranchTarget,
+    /// An illegal execution state exception.
+    IllegalExecutionState,
+    /// An `SVC` instruction executed in AArch32 state.
+    SvcAarch32,
+    /// An `HVC` instruction executed in AArch32 state.
+    HvcAarch32,
+    /// An `SMC` instruction executed in AArch32 state.
+    SmcAarch32,
+    /// An `SVC` instruction executed in AArch64 state.
+    SvcAarch64,
+    /// An `HVC` instruction executed in AArch64 state.
+    HvcAarch64,
+    /// An `SMC` instruction executed in AArch64 state.
+    SmcAarch64,
+    /// A trapped `MSR`, `MRS`, or system instruction executed in AArch64 state.
+    TrappedSystemRegister,
+    /// A trapped access to SVE functionality.
+    TrappedSve,
+    /// A trapped `ERET`, `ERETAA`, or `ERETAB` instruction.
+    TrappedEret,
+    /// A trapped `TSTART` instruction.
+    TrappedTstart,
+    /// A pointer authentication failure.
+    PointerAuthenticationFailure,
+    /// A trapped access to SME functionality.
+    TrappedSme,
+    /// A granule protection check exception.
+    GranuleProtectionCheck,
+    /// An implementation defined exception taken to EL3.
+    ImplementationDefinedEl3,
+    /// An instruction abort taken from a lower exception level.
+    InstructionAbortLowerEl,
+    /// An instruction abort taken without a change in exception level.
+    InstructionAbortCurrentEl,
+    /// A PC alignment fault.
+    PcAlignment,
+    /// A data abort taken from a lower exception level.
+    DataAbortLowerEl,
+    /// A data abort taken without a change in exception level.
+    DataAbortCurrentEl,
+    /// An SP alignment fault.
+    SpAlignment,
+    /// A memory copy or memory set exception.
+    MemoryOperation,
+    /// A trapped floating-point exception taken from AArch32 state.
+    FloatingPointAarch32,
+    /// A trapped floating-point exception taken from AArch64 state.
+    FloatingPointAarch64,
+    /// An SError exception.
+    SError,
+    /// A breakpoint exception taken from a lower exception level.
+    BreakpointLowerEl,
+    /// A breakpoint exception taken without a change in exception level.
+    BreakpointCurrentEl,
+    /// A software step exception taken from a lower exception level.
+    SoftwareStepLowerEl,
+    /// A software step exception taken without a change in exception level.
+    SoftwareStepCurrentEl,
+    /// A watchpoint exception taken from a lower exception level.
+    WatchpointLowerEl,
+    /// A watchpoint exception taken without a change in exception level.
+    WatchpointCurrentEl,
+    /// A `BKPT` instruction executed in AArch32 state.
+    BkptAarch32,
+    /// A vector catch exception taken from AArch32 state.
+    VectorCatchAarch32,
+    /// A `BRK` instruction executed in AArch64 state.
+    BrkAarch64,
+    /// A reserved or unallocated exception class.
+    Reserved(u8),
+}
+
+impl ExceptionClass {
+    /// Decodes an [`ExceptionClass`] from the value of `ESR_ELx.EC`.
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits {
+            0x00 => Self::Unknown,
+            0x01 => Self::TrappedWfx,
+            0x03 => Self::TrappedMcrMrcCp15,
+            0x04 => Self::TrappedMcrrMrrcCp15,
+            0x05 => Self::TrappedMcrMrcCp14,
+            0x06 => Self::TrappedLdcStc,
+            0x07 => Self::TrappedFloatingPoint,
+            0x08 => Self::TrappedVmrs,
+            0x09 => Self::TrappedPointerAuthentication,
+            0x0A => Self::TrappedLd64bSt64b,
+            0x0C => Self::TrappedMrrcCp14,
+            0x0D => Self::BranchTarget,
+            0x0E => Self::IllegalExecutionState,
+            0x11 => Self::SvcAarch32,
+            0x12 => Self::HvcAarch32,
+            0x13 => Self::SmcAarch32,
+            0x15 => Self::SvcAarch64,
+            0x16 => Self::HvcAarch64,
+            0x17 => Self::SmcAarch64,
+            0x18 => Self::TrappedSystemRegister,
+            0x19 => Self::TrappedSve,
+            0x1A => Self::TrappedEret,
+            0x1B => Self::TrappedTstart,
+            0x1C => Self::PointerAuthenticationFailure,
+            0x1D => Self::TrappedSme,
+            0x1E => Self::GranuleProtectionCheck,
+            0x1F => Self::ImplementationDefinedEl3,
+            0x20 => Self::InstructionAbortLowerEl,
+            0x21 => Self::InstructionAbortCurrentEl,
+            0x22 => Self::PcAlignment,
+            0x24 => Self::DataAbortLowerEl,
+            0x25 => Self::DataAbortCurrentEl,
+            0x26 => Self::SpAlignment,
+            0x27 => Self::MemoryOperation,
+            0x28 => Self::FloatingPointAarch32,
+            0x2C => Self::FloatingPointAarch64,
+            0x2F => Self::SError,
+            0x30 => Self::BreakpointLowerEl,
+            0x31 => Self::BreakpointCurrentEl,
+            0x32 => Self::SoftwareStepLowerEl,
+            0x33 => Self::SoftwareStepCurrentEl,
+            0x34 => Self::WatchpointLowerEl,
+            0x35 => Self::WatchpointCurrentEl,
+            0x38 => Self::BkptAarch32,
+            0x3A => Self::VectorCatchAarch32,
+            0x3C => Self::BrkAarch64,
+            bits => Self::Reserved(bits & 0x3F),
+        }
+    }
+
+    /// Returns the value of `ESR_ELx.EC` that corresponds to this [`ExceptionClass`].
+    pub const fn to_bits(self) -> u8 {
+        match self {
+            Self::Unknown => 0x00,
+            Self::TrappedWfx => 0x01,
+            Self::TrappedMcrMrcCp15 => 0x03,
+            Self::TrappedMcrrMrrcCp15 => 0x04,
+            Self::TrappedMcrMrcCp14 => 0x05,
+            Self::TrappedLdcStc => 0x06,
+            Self::TrappedFloatingPoint => 0x07,
+            Self::TrappedVmrs => 0x08,
+            Self::TrappedPointerAuthentication => 0x09,
+            Self::TrappedLd64bSt64b => 0x0A,
+            Self::TrappedMrrcCp14 => 0x0C,
+            Self::BranchTarget => 0x0D,
+            Self::IllegalExecutionState => 0x0E,
+            Self::SvcAarch32 => 0x11,
+            Self::HvcAarch32 => 0x12,
+            Self::SmcAarch32 => 0x13,
+            Self::SvcAarch64 => 0x15,
+            Self::HvcAarch64 => 0x16,
+            Self::SmcAarch64 => 0x17,
+            Self::TrappedSystemRegister => 0x18,
+            Self::TrappedSve => 0x19,
+            Self::TrappedEret => 0x1A,
+            Self::TrappedTstart => 0x1B,
+            Self::PointerAuthenticationFailure => 0x1C,
+            Self::TrappedSme => 0x1D,
+            Self::GranuleProtectionCheck => 0x1E,
+            Self::ImplementationDefinedEl3 => 0x1F,
+            Self::InstructionAbortLowerEl => 0x20,
+            Self::InstructionAbortCurrentEl => 0x21,
+            Self::PcAlignment => 0x22,
+            Self::DataAbortLowerEl => 0x24,
+            Self::DataAbortCurrentEl => 0x25,
+            Self::SpAlignment => 0x26,
+            Self::MemoryOperation => 0x27,
+            Self::FloatingPointAarch32 => 0x28,
+            Self::FloatingPointAarch64 => 0x2C,
+            Self::SError => 0x2F,
+            Self::BreakpointLowerEl => 0x30,
+            Self::BreakpointCurrentEl => 0x31,
+            Self::SoftwareStepLowerEl => 0x32,
+            Self::SoftwareStepCurrentEl => 0x33,
+            Self::WatchpointLowerEl => 0x34,
+            Self::WatchpointCurrentEl => 0x35,
+            Self::BkptAarch32 => 0x38,
+            Self::VectorCatchAarch32 => 0x3A,
+            Self::BrkAarch64 => 0x3C,
+            Self::Reserved(bits) => bits,
+        }
+    }
+
+    /// Returns a human readable description of the [`ExceptionClass`].
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::Unknown => "unknown reason",
+            Self::TrappedWfx => "trapped WFI or WFE",
+            Self::TrappedMcrMrcCp15 => "trapped MCR or MRC access to CP15",
+            Self::TrappedMcrrMrrcCp15 => "trapped MCRR or MRRC access to CP15",
+            Self::TrappedMcrMrcCp14 => "trapped MCR or MRC access to CP14",
+            Self::TrappedLdcStc => "trapped LDC or STC access",
+            Self::TrappedFloatingPoint => "trapped SVE, SIMD, or floating-point access",
+            Self::TrappedVmrs => "trapped VMRS access",
+            Self::TrappedPointerAuthentication => "trapped pointer authentication instruction",
+            Self::TrappedLd64bSt64b => "trapped LD64B or ST64B instruction",
+            Self::TrappedMrrcCp14 => "trapped MRRC access to CP14",
+            Self::BranchTarget => "branch target exception",
+            Self::IllegalExecutionState => "illegal execution state",
+            Self::SvcAarch32 => "SVC in AArch32 state",
+            Self::HvcAarch32 => "HVC in AArch32 state",
+            Self::SmcAarch32 => "SMC in AArch32 state",
+            Self::SvcAarch64 => "SVC in AArch64 state",
+            Self::HvcAarch64 => "HVC in AArch64 state",
+            Self::SmcAarch64 => "SMC in AArch64 state",
+            Self::TrappedSystemRegister => "trapped MSR, MRS, or system instruction",
+            Self::TrappedSve => "trapped SVE access",
+            Self::TrappedEret => "trapped ERET",
+            Self::TrappedTstart => "trapped TSTART",
+            Self::PointerAuthenticationFailure => "pointer authentication failure",
+            Self::TrappedSme => "trapped SME access",
+            Self::GranuleProtectionCheck => "granule protection check",
+            Self::ImplementationDefinedEl3 => "implementation defined exception to EL3",
+            Self::InstructionAbortLowerEl => "instruction abort from a lower EL",
+            Self::InstructionAbortCurrentEl => "instruction abort from the current EL",
+            Self::PcAlignment => "PC alignment fault",
+            Self::DataAbortLowerEl => "data abort from a lower EL",
+            Self::DataAbortCurrentEl => "data abort from the current EL",
+            Self::SpAlignment => "SP alignment fault",
+            Self::MemoryOperation => "memory copy or set exception",
+            Self::FloatingPointAarch32 => "trapped floating-point exception in AArch32 state",
+            Self::FloatingPointAarch64 => "trapped floating-point exception",
+            Self::SError => "SError",
+            Self::BreakpointLowerEl | Self::BreakpointCurrentEl => "breakpoint",
+            Self::SoftwareStepLowerEl | Self::SoftwareStepCurrentEl => "software step",
+            Self::WatchpointLowerEl | Self::WatchpointCurrentEl => "watchpoint",
+            Self::BkptAarch32 => "BKPT instruction",
+            Self::VectorCatchAarch32 => "vector catch",
+            Self::BrkAarch64 => "BRK instruction",
+            Self::Reserved(_) => "reserved or unrecognized",
+        }
+    }
+}
+
+/// The decoded instruction specific syndrome of an exception.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Syndrome {
+    /// A trapped `WFI`, `WFE`, `WFIT`, or `WFET` instruction.
+    Wfx(WfxSyndrome),
+    /// An `SVC` instruction executed in AArch64 state, with its immediate.
+    Svc(u16),
+    /// An `HVC` instruction executed in AArch64 state, with its immediate.
+    Hvc(u16),
+    /// An `SMC` instruction executed in AArch64 state, with its immediate.
+    Smc(u16),
+    /// A trapped `MSR`, `MRS`, or system instruction.
+    SystemRegister(SystemRegisterSyndrome),
+    /// An instruction abort.
+    InstructionAbort {
+        /// If `true`, the exception was taken from a lower exception level.
+        lower_el: bool,
+        /// The syndrome of the instruction abort.
+        syndrome: InstructionAbortSyndrome,
+    },
+    /// A data abort.
+    DataAbort {
+        /// If `true`, the exception was taken from a lower exception level.
+        lower_el: bool,
+        /// The syndrome of the data abort.
+        syndrome: DataAbortSyndrome,
+    },
+    /// A breakpoint exception.
+    Breakpoint {
+        /// If `true`, the exception was taken from a lower exception level.
+        lower_el: bool,
+    },
+    /// A software step exception.
+    SoftwareStep {
+        /// If `true`, the exception was taken from a lower exception level.
+        lower_el: bool,
+        /// The syndrome of the software step exception.
+        syndrome: SoftwareStepSyndrome,
+    },
+    /// A watchpoint exception.
+    Watchpoint {
+        /// If `true`, the exception was taken from a lower exception level.
+        lower_el: bool,
+        /// The syndrome of the watchpoint exception.
+        syndrome: WatchpointSyndrome,
+    },
+    /// A `BRK` instruction executed in AArch64 state, with its immediate.
+    Brk(u16),
+    /// An exception whose instruction specific syndrome is not decoded.
+    Other {
+        /// The [`ExceptionClass`] of the exception.
+        class: ExceptionClass,
+        /// The raw instruction specific syndrome.
+        iss: u32,
+    },
+}
+
+/// The instruction specific syndrome of a trapped `WFI`, `WFE`, `WFIT`, or `WFET` instruction.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct WfxSyndrome(u32);
+
+impl WfxSyndrome {
+    /// Creates a [`WfxSyndrome`] from the raw instruction specific syndrome.
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw instruction specific syndrome.
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the [`WaitInstruction`] that was trapped (`TI`).
+    pub const fn instruction(self) -> WaitInstruction {
+        match self.0 & 0b11 {
+            0b00 => WaitInstruction::Wfi,
+            0b01 => WaitInstruction::Wfe,
+            0b10 => WaitInstruction::Wfit,
+            _ => WaitInstruction::Wfet,
+        }
+    }
+
+    /// Returns the general purpose register holding the timeout of a `WFIT` or `WFET`
+    /// instruction (`RN`), or [`None`] if the register is not valid (`RV`).
+    pub const fn register(self) -> Option<u8> {
+        if (self.0 >> 2) & 0b1 == 0b1 {
+            Some(((self.0 >> 5) & 0x1F) as u8)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the condition code of the trapped instruction (`COND`), or [`None`] if the
+    /// condition code is not valid (`CV`).
+    pub const fn condition(self) -> Option<u8> {
+        if (self.0 >> 24) & 0b1 == 0b1 {
+            Some(((self.0 >> 20) & 0xF) as u8)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Debug for WfxSyndrome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WfxSyndrome")
+            .field("instruction", &self.instruction())
+            .field("register", &self.register())
+            .field("condition", &self.condition())
+            .finish()
+    }
+}
+
+/// A wait instruction that can be trapped.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum WaitInstruction {
+    /// Wait for interrupt.
+    Wfi,
+    /// Wait for event.
+    Wfe,
+    /// Wait for interrupt with timeout.
+    Wfit,
+    /// Wait for event with timeout.
+    Wfet,
+}
+
+/// The instruction specific syndrome of a trapped `MSR`, `MRS`, or system instruction.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct SystemRegisterSyndrome(u32);
+
+impl SystemRegisterSyndrome {
+    /// Creates a [`SystemRegisterSyndrome`] from the raw instruction specific syndrome.
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw instruction specific syndrome.
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the [`SystemRegisterEncoding`] of the accessed system register.
+    pub const fn system_register(self) -> SystemRegisterEncoding {
+        SystemRegisterEncoding {
+            op0: ((self.0 >> 20) & 0b11) as u8,
+            op1: ((self.0 >> 14) & 0b111) as u8,
+            crn: ((self.0 >> 10) & 0xF) as u8,
+            crm: ((self.0 >> 1) & 0xF) as u8,
+            op2: ((self.0 >> 17) & 0b111) as u8,
+        }
+    }
+
+    /// Returns the general purpose register used for the transfer (`Rt`).
+    pub const fn register(self) -> u8 {
+        ((self.0 >> 5) & 0x1F) as u8
+    }
+
+    /// Returns `true` if the trapped instruction reads from the system register (`Direction`).
+    pub const fn is_read(self) -> bool {
+        self.0 & 0b1 == 0b1
+    }
+}
+
+impl fmt::Debug for SystemRegisterSyndrome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SystemRegisterSyndrome")
+            .field("system_register", &self.system_register())
+            .field("register", &self.register())
+            .field("is_read", &self.is_read())
+            .finish()
+    }
+}
+
+/// The encoding of a system register accessed through `MSR` or `MRS`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct SystemRegisterEncoding {
+    /// The `op0` field of the encoding.
+    pub op0: u8,
+    /// The `op1` field of the encoding.
+    pub op1: u8,
+    /// The `CRn` field of the encoding.
+    pub crn: u8,
+    /// The `CRm` field of the encoding.
+    pub crm: u8,
+    /// The `op2` field of the encoding.
+    pub op2: u8,
+}
+
+impl SystemRegisterEncoding {
+    /// Returns the architectural name of the system register, if it is a commonly used register
+    /// known to this crate.
+    pub const fn name(self) -> Option<&'static str> {
+        let name = match (self.op0, self.op1, self.crn, self.crm, self.op2) {
+            (3, 0, 0, 0, 0) => "midr_el1",
+            (3, 0, 0, 0, 5) => "mpidr_el1",
+            (3, 0, 0, 4, 0) => "id_aa64pfr0_el1",
+            (3, 0, 0, 4, 1) => "id_aa64pfr1_el1",
+            (3, 0, 0, 5, 0) => "id_aa64dfr0_el1",
+            (3, 0, 0, 6, 0) => "id_aa64isar0_el1",
+            (3, 0, 0, 6, 1) => "id_aa64isar1_el1",
+            (3, 0, 0, 6, 2) => "id_aa64isar2_el1",
+            (3, 0, 0, 7, 0) => "id_aa64mmfr0_el1",
+            (3, 0, 0, 7, 1) => "id_aa64mmfr1_el1",
+            (3, 0, 0, 7, 2) => "id_aa64mmfr2_el1",
+            (3, 0, 1, 0, 0) => "sctlr_el1",
+            (3, 0, 1, 0, 2) => "cpacr_el1",
+            (3, 0, 2, 0, 0) => "ttbr0_el1",
+            (3, 0, 2, 0, 1) => "ttbr1_el1",
+            (3, 0, 2, 0, 2) => "tcr_el1",
+            (3, 0, 4, 0, 0) => "spsr_el1",
+            (3, 0, 4, 0, 1) => "elr_el1",
+            (3, 0, 4, 1, 0) => "sp_el0",
+            (3, 0, 4, 2, 2) => "currentel",
+            (3, 0, 5, 2, 0) => "esr_el1",
+            (3, 0, 6, 0, 0) => "far_el1",
+            (3, 0, 7, 4, 0) => "par_el1",
+            (3, 0, 10, 2, 0) => "mair_el1",
+            (3, 0, 12, 0, 0) => "vbar_el1",
+            (3, 0, 13, 0, 1) => "contextidr_el1",
+            (3, 0, 13, 0, 4) => "tpidr_el1",
+            (3, 3, 4, 2, 1) => "daif",
+            (3, 3, 4, 2, 0) => "nzcv",
+            (3, 3, 13, 0, 2) => "tpidr_el0",
+            (3, 3, 14, 0, 0) => "cntfrq_el0",
+            (3, 3, 14, 0, 1) => "cntpct_el0",
+            (3, 3, 14, 0, 2) => "cntvct_el0",
+            (3, 3, 14, 2, 0) => "cntp_tval_el0",
+            (3, 3, 14, 2, 1) => "cntp_ctl_el0",
+            (3, 3, 14, 2, 2) => "cntp_cval_el0",
+            (3, 3, 14, 3, 0) => "cntv_tval_el0",
+            (3, 3, 14, 3, 1) => "cntv_ctl_el0",
+            (3, 3, 14, 3, 2) => "cntv_cval_el0",
+            (3, 4, 0, 0, 5) => "vmpidr_el2",
+            (3, 4, 1, 0, 0) => "sctlr_el2",
+            (3, 4, 1, 1, 0) => "hcr_el2",
+            (3, 4, 1, 1, 1) => "mdcr_el2",
+            (3, 4, 1, 1, 2) => "cptr_el2",
+            (3, 4, 2, 0, 0) => "ttbr0_el2",
+            (3, 4, 2, 0, 2) => "tcr_el2",
+            (3, 4, 2, 1, 0) => "vttbr_el2",
+            (3, 4, 2, 1, 2) => "vtcr_el2",
+            (3, 4, 4, 0, 0) => "spsr_el2",
+            (3, 4, 4, 0, 1) => "elr_el2",
+            (3, 4, 5, 2, 0) => "esr_el2",
+            (3, 4, 6, 0, 0) => "far_el2",
+            (3, 4, 6, 0, 4) => "hpfar_el2",
+            (3, 4, 10, 2, 0) => "mair_el2",
+            (3, 4, 12, 0, 0) => "vbar_el2",
+            (3, 4, 14, 1, 0) => "cnthctl_el2",
+            (3, 4, 14, 0, 3) => "cntvoff_el2",
+            _ => return None,
+        };
+
+        Some(name)
+    }
+}
+
+impl fmt::Display for SystemRegisterEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => f.write_str(name),
+            None => write!(
+                f,
+                "s{}_{}_c{}_c{}_{}",
+                self.op0, self.op1, self.crn, self.crm, self.op2
+            ),
+        }
+    }
+}
+
+/// The instruction specific syndrome of an instruction abort.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct InstructionAbortSyndrome(u32);
+
+impl InstructionAbortSyndrome {
+    /// Creates an [`InstructionAbortSyndrome`] from the raw instruction specific syndrome.
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw instruction specific syndrome.
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns `true` if the value of `FAR_ELx` is not valid (`FnV`).
+    pub const fn far_not_valid(self) -> bool {
+        (self.0 >> 10) & 0b1 == 0b1
+    }
+
+    /// Returns `true` if the abort was caused by an external abort (`EA`).
+    pub const fn external_abort(self) -> bool {
+        (self.0 >> 9) & 0b1 == 0b1
+    }
+
+    /// Returns `true` if the abort occurred during a stage 2 translation of a stage 1
+    /// translation table walk (`S1PTW`).
+    pub const fn stage_1_translation_walk(self) -> bool {
+        (self.0 >> 7) & 0b1 == 0b1
+    }
+
+    /// Returns the [`FaultStatusCode`] of the abort (`IFSC`).
+    pub const fn fault_status(self) -> FaultStatusCode {
+        FaultStatusCode::from_bits((self.0 & 0x3F) as u8)
+    }
+}
+
+impl fmt::Debug for InstructionAbortSyndrome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InstructionAbortSyndrome")
+            .field("far_not_valid", &self.far_not_valid())
+            .field("external_abort", &self.external_abort())
+            .field("stage_1_translation_walk", &self.stage_1_translation_walk())
+            .field("fault_status", &self.fault_status())
+            .finish()
+    }
+}
+
+/// The instruction specific syndrome of a data abort.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct DataAbortSyndrome(u32);
+
+impl DataAbortSyndrome {
+    /// Creates a [`DataAbortSyndrome`] from the raw instruction specific syndrome.
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw instruction specific syndrome.
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the [`DataAccess`] that caused the abort, or [`None`] if the instruction syndrome
+    /// is not valid (`ISV`).
+    pub const fn access(self) -> Option<DataAccess> {
+        if (self.0 >> 24) & 0b1 == 0b0 {
+            return None;
+        }
+
+        let size = match (self.0 >> 22) & 0b11 {
+            0b00 => AccessSize::Byte,
+            0b01 => AccessSize::Halfword,
+            0b10 => AccessSize::Word,
+            _ => AccessSize::Doubleword,
+        };
+
+        Some(DataAccess {
+            size,
+            sign_extend: (self.0 >> 21) & 0b1 == 0b1,
+            register: ((self.0 >> 16) & 0x1F) as u8,
+            register_64: (self.0 >> 15) & 0b1 == 0b1,
+            acquire_release: (self.0 >> 14) & 0b1 == 0b1,
+        })
+    }
+
+    /// Returns `true` if the value of `FAR_ELx` is not valid (`FnV`).
+    pub const fn far_not_valid(self) -> bool {
+        (self.0 >> 10) & 0b1 == 0b1
+    }
+
+    /// Returns `true` if the abort was caused by an external abort (`EA`).
+    pub const fn external_abort(self) -> bool {
+        (self.0 >> 9) & 0b1 == 0b1
+    }
+
+    /// Returns `true` if the abort was caused by a cache maintenance or address translation
+    /// instruction (`CM`).
+    pub const fn cache_maintenance(self) -> bool {
+        (self.0 >> 8) & 0b1 == 0b1
+    }
+
+    /// Returns `true` if the abort occurred during a stage 2 translation of a stage 1
+    /// translation table walk (`S1PTW`).
+    pub const fn stage_1_translation_walk(self) -> bool {
+        (self.0 >> 7) & 0b1 == 0b1
+    }
+
+    /// Returns `true` if the abort was caused by an instruction writing to memory (`WnR`).
+    pub const fn is_write(self) -> bool {
+        (self.0 >> 6) & 0b1 == 0b1
+    }
+
+    /// Returns the [`FaultStatusCode`] of the abort (`DFSC`).
+    pub const fn fault_status(self) -> FaultStatusCode {
+        FaultStatusCode::from_bits((self.0 & 0x3F) as u8)
+    }
+}
+
+impl fmt::Debug for DataAbortSyndrome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataAbortSyndrome")
+            .field("access", &self.access())
+            .field("far_not_valid", &self.far_not_valid())
+            .field("external_abort", &self.external_abort())
+            .field("cache_maintenance", &self.cache_maintenance())
+            .field("stage_1_translation_walk", &self.stage_1_translation_walk())
+            .field("is_write", &self.is_write())
+            .field("fault_status", &self.fault_status())
+            .finish()
+    }
+}
+
+/// A description of the load or store that caused a data abort.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct DataAccess {
+    /// The size of the access (`SAS`).
+    pub size: AccessSize,
+    /// If `true`, the loaded value is sign extended (`SSE`).
+    pub sign_extend: bool,
+    /// The general purpose register used for the transfer (`SRT`).
+    pub register: u8,
+    /// If `true`, the transfer register is 64 bits wide (`SF`).
+    pub register_64: bool,
+    /// If `true`, the instruction has acquire or release semantics (`AR`).
+    pub acquire_release: bool,
+}
+
+/// The size of a data access.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum AccessSize {
+    /// A single byte.
+    Byte,
+    /// A 16-bit halfword.
+    Halfword,
+    /// A 32-bit word.
+    Word,
+    /// A 64-bit doubleword.
+    Doubleword,
+}
+
+impl AccessSize {
+    /// Returns the size, in bytes, of the access.
+    pub const fn bytes(self) -> u8 {
+        match self {
+            Self::Byte => 1,
+            Self::Halfword => 2,
+            Self::Word => 4,
+            Self::Doubleword => 8,
+        }
+    }
+}
+
+/// The instruction specific syndrome of a software step exception.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct SoftwareStepSyndrome(u32);
+
+impl SoftwareStepSyndrome {
+    /// Creates a [`SoftwareStepSyndrome`] from the raw instruction specific syndrome.
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw instruction specific syndrome.
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns `true` if the stepped instruction was a load-exclusive instruction (`EX`), or
+    /// [`None`] if the syndrome is not valid (`ISV`).
+    pub const fn exclusive(self) -> Option<bool> {
+        if (self.0 >> 24) & 0b1 == 0b1 {
+            Some((self.0 >> 6) & 0b1 == 0b1)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Debug for SoftwareStepSyndrome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SoftwareStepSyndrome")
+            .field("exclusive", &self.exclusive())
+            .finish()
+    }
+}
+
+/// The instruction specific syndrome of a watchpoint exception.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct WatchpointSyndrome(u32);
+
+impl WatchpointSyndrome {
+    /// Creates a [`WatchpointSyndrome`] from the raw instruction specific syndrome.
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw instruction specific syndrome.
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns `true` if the watchpoint was hit by a cache maintenance instruction (`CM`).
+    pub const fn cache_maintenance(self) -> bool {
+        (self.0 >> 8) & 0b1 == 0b1
+    }
+
+    /// Returns `true` if the watchpoint was hit by an instruction writing to memory (`WnR`).
+    pub const fn is_write(self) -> bool {
+        (self.0 >> 6) & 0b1 == 0b1
+    }
+}
+
+impl fmt::Debug for WatchpointSyndrome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchpointSyndrome")
+            .field("cache_maintenance", &self.cache_maintenance())
+            .field("is_write", &self.is_write())
+            .finish()
+    }
+}
+
+/// The cause of an instruction or data abort, as reported in `IFSC` or `DFSC`.
+///
+/// Translation table levels are reported as an [`i8`] since level -1 exists when 52-bit
+/// addresses are used with the 4 KiB and 16 KiB granules.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum FaultStatusCode {
+    /// An address size fault at the given translation table level.
+    AddressSize {
+        /// The level of the translation table walk at which the fault occurred.
+        level: i8,
+    },
+    /// A translation fault at the given translation table level.
+    Translation {
+        /// The level of the translation table walk at which the fault occurred.
+        level: i8,
+    },
+    /// An access flag fault at the given translation table level.
+    AccessFlag {
+        /// The level of the translation table walk at which the fault occurred.
+        level: i8,
+    },
+    /// A permission fault at the given translation table level.
+    Permission {
+        /// The level of the translation table walk at which the fault occurred.
+        level: i8,
+    },
+    /// A synchronous external abort not on a translation table walk.
+    SynchronousExternalAbort,
+    /// A synchronous tag check fault.
+    TagCheck,
+    /// A synchronous external abort on a translation table walk.
+    SynchronousExternalAbortOnWalk {
+        /// The level of the translation table walk at which the abort occurred.
+        level: i8,
+    },
+    /// A synchronous parity or ECC error not on a translation table walk.
+    Parity,
+    /// A synchronous parity or ECC error on a translation table walk.
+    ParityOnWalk {
+        /// The level of the translation table walk at which the error occurred.
+        level: i8,
+    },
+    /// An alignment fault.
+    Alignment,
+    /// A debug exception.
+    Debug,
+    /// A granule protection fault not on a translation table walk.
+    GranuleProtection,
+    /// A granule protection fault on a translation table walk.
+    GranuleProtectionOnWalk {
+        /// The level of the translation table walk at which the fault occurred.
+        level: i8,
+    },
+    /// A TLB conflict abort.
+    TlbConflict,
+    /// An unsupported atomic hardware update fault.
+    UnsupportedAtomicUpdate,
+    /// An implementation defined lockdown fault.
+    Lockdown,
+    /// An implementation defined unsupported exclusive or atomic access fault.
+    UnsupportedExclusiveOrAtomic,
+    /// A reserved or unrecognized fault status code.
+    Reserved(u8),
+}
+
+impl FaultStatusCode {
+    /// Decodes a [`FaultStatusCode`] from the value of `IFSC` or `DFSC`.
+    pub const fn from_bits(bits: u8) -> Self {
+        let level = (bits & 0b11).cast_signed();
+        match bits & 0x3F {
+            0b000000..=0b000011 => Self::AddressSize { level },
+            0b000100..=0b000111 => Self::Translation { level },
+            0b001000..=0b001011 => Self::AccessFlag { level },
+            0b001100..=0b001111 => Self::Permission { level },
+            0b010000 => Self::SynchronousExternalAbort,
+            0b010001 => Self::TagCheck,
+            0b010011 => Self::SynchronousExternalAbortOnWalk { level: -1 },
+            0b010100..=0b010111 => Self::SynchronousExternalAbortOnWalk { level },
+            0b011000 => Self::Parity,
+            0b011011 => Self::ParityOnWalk { level: -1 },
+            0b011100..=0b011111 => Self::ParityOnWalk { level },
+            0b100001 => Self::Alignment,
+            0b100010 => Self::Debug,
+            0b100011 => Self::GranuleProtection,
+            0b100100..=0b100111 => Self::GranuleProtectionOnWalk { level },
+            0b101000 => Self::GranuleProtectionOnWalk { level: -1 },
+            0b101001 => Self::AddressSize { level: -1 },
+            0b101011 => Self::Translation { level: -1 },
+            0b110000 => Self::TlbConflict,
+            0b110001 => Self::UnsupportedAtomicUpdate,
+            0b110100 => Self::Lockdown,
+            0b110101 => Self::UnsupportedExclusiveOrAtomic,
+            bits => Self::Reserved(bits),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn exception_class_round_trip() {
+        for bits in 0..0x40 {
+            assert_eq!(ExceptionClass::from_bits(bits).to_bits(), bits);
+        }
+    }
+
+    #[test]
+    fn decode_syndrome() {
+        let cases = [
+            (
+                0x0000_0000,
+                Syndrome::Other {
+                    class: ExceptionClass::Unknown,
+                    iss: 0,
+                },
+            ),
+            (0x07E0_0000, Syndrome::Wfx(WfxSyndrome(0x1E0_0000))),
+            (0x5600_1234, Syndrome::Svc(0x1234)),
+            (0x5A00_0000, Syndrome::Hvc(0)),
+            (0x5E00_0001, Syndrome::Smc(1)),
+            (
+                0x6230_0041,
+                Syndrome::SystemRegister(SystemRegisterSyndrome(0x30_0041)),
+            ),
+            (
+                0x8200_000F,
+                Syndrome::InstructionAbort {
+                    lower_el: true,
+                    syndrome: InstructionAbortSyndrome(0xF),
+                },
+            ),
+            (
+                0x8600_0010,
+                Syndrome::InstructionAbort {
+                    lower_el: false,
+                    syndrome: InstructionAbortSyndrome(0x10),
+                },
+            ),
+            (
+                0x9383_0047,
+                Syndrome::DataAbort {
+                    lower_el: true,
+                    syndrome: DataAbortSyndrome(0x183_0047),
+                },
+            ),
+            (
+                0x9600_0021,
+                Syndrome::DataAbort {
+                    lower_el: false,
+                    syndrome: DataAbortSyndrome(0x21),
+                },
+            ),
+            (
+                0xBE00_0000,
+                Syndrome::Other {
+                    class: ExceptionClass::SError,
+                    iss: 0,
+                },
+            ),
+            (0xC200_0022, Syndrome::Breakpoint { lower_el: true }),
+            (0xC600_0022, Syndrome::Breakpoint { lower_el: false }),
+            (
+                0xCF00_0062,
+                Syndrome::SoftwareStep {
+                    lower_el: false,
+                    syndrome: SoftwareStepSyndrome(0x100_0062),
+                },
+            ),
+            (
+                0xD200_0062,
+                Syndrome::Watchpoint {
+                    lower_el: true,
+                    syndrome: WatchpointSyndrome(0x62),
+                },
+            ),
+            (0xF200_03E8, Syndrome::Brk(0x3E8)),
+            (
+                0xFE00_0000,
+                Syndrome::Other {
+                    class: ExceptionClass::Reserved(0x3F),
+                    iss: 0,
+                },
+            ),
+        ];
+
+        for (esr, expected) in cases {
+            assert_eq!(
+                ExceptionSyndrome::from_bits(esr).decode(),
+                expected,
+                "ESR {esr:#010x}"
+            );
+        }
+    }
+
+    #[test]
+    fn data_abort_fields() {
+        let cases = [
+            (
+                0x9383_0047,
+                Some(DataAccess {
+                    size: AccessSize::Word,
+                    sign_extend: false,
+                    register: 3,
+                    register_64: false,
+                    acquire_release: false,
+                }),
+                true,
+                FaultStatusCode::Translation { level: 3 },
+            ),
+            (
+                0x93DE_C005,
+                Some(DataAccess {
+                    size: AccessSize::Doubleword,
+                    sign_extend: false,
+                    register: 30,
+                    register_64: true,
+                    acquire_release: true,
+                }),
+                false,
+                FaultStatusCode::Translation { level: 1 },
+            ),
+            (
+                0x9321_0006,
+                Some(DataAccess {
+                    size: AccessSize::Byte,
+                    sign_extend: true,
+                    register: 1,
+                    register_64: false,
+                    acquire_release: false,
+                }),
+                false,
+                FaultStatusCode::Translation { level: 2 },
+            ),
+            (0x9600_0021, None, false, FaultStatusCode::Alignment),
+            (
+                0x9600_004F,
+                None,
+                true,
+                FaultStatusCode::Permission { level: 3 },
+            ),
+            (
+                0x9600_002B,
+                None,
+                false,
+                FaultStatusCode::Translation { level: -1 },
+            ),
+        ];
+
+        for (esr, access, is_write, fault_status) in cases {
+            let Syndrome::DataAbort { syndrome, .. } = ExceptionSyndrome::from_bits(esr).decode()
+            else {
+                panic!("ESR {esr:#010x} is not a data abort");
+            };
+
+            assert_eq!(syndrome.access(), access, "ESR {esr:#010x}");
+            assert_eq!(syndrome.is_write(), is_write, "ESR {esr:#010x}");
+            assert_eq!(syndrome.fault_status(), fault_status, "ESR {esr:#010x}");
+        }
+    }
+
+    #[test]
+    fn system_register_fields() {
+        let cases = [
+            // MRS x2, MIDR_EL1
+            (
+                0x6230_0041,
+                SystemRegisterEncoding {
+                    op0: 3,
+                    op1: 0,
+                    crn: 0,
+                    crm: 0,
+                    op2: 0,
+                },
+                2,
+                true,
+            ),
+            // MSR SCTLR_EL1, x5
+            (
+                0x6230_04A0,
+                SystemRegisterEncoding {
+                    op0: 3,
+                    op1: 0,
+                    crn: 1,
+                    crm: 0,
+                    op2: 0,
+                },
+                5,
+                false,
+            ),
+            // MRS x30, CNTVCT_EL0
+            (
+                0x6234_FBC1,
+                SystemRegisterEncoding {
+                    op0: 3,
+                    op1: 3,
+                    crn: 14,
+                    crm: 0,
+                    op2: 2,
+                },
+                30,
+                true,
+            ),
+        ];
+
+        for (esr, system_register, register, is_read) in cases {
+            let Syndrome::SystemRegister(syndrome) = ExceptionSyndrome::from_bits(esr).decode()
+            else {
+                panic!("ESR {esr:#010x} is not a trapped system register access");
+            };
+
+            assert_eq!(syndrome.system_register(), system_register);
+            assert_eq!(syndrome.register(), register, "ESR {esr:#010x}");
+            assert_eq!(syndrome.is_read(), is_read, "ESR {esr:#010x}");
+        }
+    }
+
+    #[test]
+    fn wfx_fields() {
+        let cases = [
+            (0x07E0_0000, WaitInstruction::Wfi, None, Some(0xE)),
+            (0x07E0_0001, WaitInstruction::Wfe, None, Some(0xE)),
+            (0x07E0_0066, WaitInstruction::Wfit, Some(3), Some(0xE)),
+            (0x0600_0027, WaitInstruction::Wfet, Some(1), None),
+        ];
+
+        for (esr, instruction, register, condition) in cases {
+            let Syndrome::Wfx(syndrome) = ExceptionSyndrome::from_bits(esr).decode() else {
+                panic!("ESR {esr:#010x} is not a trapped wait instruction");
+            };
+
+            assert_eq!(syndrome.instruction(), instruction, "ESR {esr:#010x}");
+            assert_eq!(syndrome.register(), register, "ESR {esr:#010x}");
+            assert_eq!(syndrome.condition(), condition, "ESR {esr:#010x}");
+        }
+    }
+
+    #[test]
+    fn fault_status_codes() {
+        let cases = [
+            (0b000010, FaultStatusCode::AddressSize { level: 2 }),
+            (0b101001, FaultStatusCode::AddressSize { level: -1 }),
+            (0b000100, FaultStatusCode::Translation { level: 0 }),
+            (0b001011, FaultStatusCode::AccessFlag { level: 3 }),
+            (0b001101, FaultStatusCode::Permission { level: 1 }),
+            (0b010000, FaultStatusCode::SynchronousExternalAbort),
+            (0b010001, FaultStatusCode::TagCheck),
+            (
+                0b010011,
+                FaultStatusCode::SynchronousExternalAbortOnWalk { level: -1 },
+            ),
+            (
+                0b010110,
+                FaultStatusCode::SynchronousExternalAbortOnWalk { level: 2 },
+            ),
+            (0b011000, FaultStatusCode::Parity),
+            (0b011011, FaultStatusCode::ParityOnWalk { level: -1 }),
+            (0b011101, FaultStatusCode::ParityOnWalk { level: 1 }),
+            (0b100001, FaultStatusCode::Alignment),
+            (0b100010, FaultStatusCode::Debug),
+            (0b100011, FaultStatusCode::GranuleProtection),
+            (
+                0b100101,
+                FaultStatusCode::GranuleProtectionOnWalk { level: 1 },
+            ),
+            (
+                0b101000,
+                FaultStatusCode::GranuleProtectionOnWalk { level: -1 },
+            ),
+            (0b110000, FaultStatusCode::TlbConflict),
+            (0b110001, FaultStatusCode::UnsupportedAtomicUpdate),
+            (0b110100, FaultStatusCode::Lockdown),
+            (0b110101, FaultStatusCode::UnsupportedExclusiveOrAtomic),
+            (0b111111, FaultStatusCode::Reserved(0b111111)),
+        ];
+
+        for (bits, expected) in cases {
+            assert_eq!(FaultStatusCode::from_bits(bits), expected, "{bits:#08b}");
+        }
+    }
+}
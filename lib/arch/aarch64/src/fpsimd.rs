@@ -0,0 +1,386 @@
+//! Saving and restoring of SIMD, floating-point, and SVE register state.
+//!
+//! Code built for this crate's targets does not use the SIMD and floating-point registers, so the
+//! state saved here belongs entirely to whatever context (typically a guest) last ran. Accesses
+//! must be enabled with [`enable_el1()`] or [`enable_el2()`] before any state can be saved or
+//! restored.
+
+#[cfg(target_arch = "aarch64")]
+use crate::msr::{AccessControl, CpacrEL1, CptrEL2};
+
+/// The largest SVE vector length permitted by the architecture, in bytes.
+pub const MAX_VECTOR_LENGTH: usize = 256;
+
+/// The largest SVE predicate length permitted by the architecture, in bytes.
+pub const MAX_PREDICATE_LENGTH: usize = MAX_VECTOR_LENGTH / 8;
+
+/// The number of SVE predicate registers, not counting the first-fault register.
+const PREDICATE_REGISTERS: usize = 16;
+
+/// Enables SIMD and floating-point accesses, and SVE accesses if `sve` is `true`, at EL1 and EL0.
+///
+/// # Safety
+///
+/// This must be executed at EL1 or higher, and SVE must be implemented if `sve` is `true`.
+#[cfg(target_arch = "aarch64")]
+pub unsafe fn enable_el1(sve: bool) {
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `CPACR_EL1` is accessible.
+    let mut cpacr = unsafe { CpacrEL1::get() }.set_fp_access(AccessControl::TrapNone);
+    if sve {
+        cpacr = cpacr.set_sve_access(AccessControl::TrapNone);
+    }
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `CPACR_EL1` is accessible and that enabling
+    // SVE accesses is valid.
+    unsafe { cpacr.set() }
+    isb();
+}
+
+/// Stops trapping SIMD and floating-point accesses, and SVE accesses if `sve` is `true`, to EL2.
+///
+/// This uses the layout of `CPTR_EL2` for `HCR_EL2.E2H` clear; [`enable_el1()`] controls the
+/// trapping of EL2 accesses when `HCR_EL2.E2H` is set.
+///
+/// # Safety
+///
+/// This must be executed at EL2 with `HCR_EL2.E2H` clear, and SVE must be implemented if `sve` is
+/// `true`.
+#[cfg(target_arch = "aarch64")]
+pub unsafe fn enable_el2(sve: bool) {
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `CPTR_EL2` is accessible.
+    let mut cptr = unsafe { CptrEL2::get() }.set_trap_fp(false);
+    if sve {
+        cptr = cptr.set_trap_sve(false);
+    }
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `CPTR_EL2` is accessible and that enabling SVE
+    // accesses is valid.
+    unsafe { cptr.set() }
+    isb();
+}
+
+/// Returns the current SVE vector length, in bytes.
+///
+/// # Safety
+///
+/// SVE must be implemented and SVE accesses must not be trapped at the current exception level.
+#[cfg(target_arch = "aarch64")]
+pub unsafe fn vector_length() -> usize {
+    let length: usize;
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `RDVL` does not trap.
+    unsafe {
+        core::arch::asm!(
+            ".arch_extension sve",
+            "rdvl {length}, #1",
+            length = out(reg) length,
+            options(nomem, nostack, preserves_flags)
+        )
+    }
+
+    length
+}
+
+/// The SIMD and floating-point register state: `V0` to `V31`, `FPSR`, and `FPCR`.
+#[repr(C, align(16))]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct FpsimdState {
+    /// The contents of the `V0` to `V31` registers.
+    pub v: [u128; 32],
+    /// The contents of the `FPSR` register.
+    pub fpsr: u64,
+    /// The contents of the `FPCR` register.
+    pub fpcr: u64,
+}
+
+impl FpsimdState {
+    /// Creates an [`FpsimdState`] in which every register is zero.
+    pub const fn new() -> Self {
+        Self {
+            v: [0; 32],
+            fpsr: 0,
+            fpcr: 0,
+        }
+    }
+
+    /// Saves the current SIMD and floating-point register state.
+    ///
+    /// # Safety
+    ///
+    /// SIMD and floating-point accesses must not be trapped at the current exception level.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn save(&mut self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that the accesses do not trap, and `self` is
+        // large enough and suitably aligned to hold the state.
+        unsafe {
+            core::arch::asm!(
+                ".arch_extension fp",
+                "stp q0, q1, [{state}, #0]",
+                "stp q2, q3, [{state}, #32]",
+                "stp q4, q5, [{state}, #64]",
+                "stp q6, q7, [{state}, #96]",
+                "stp q8, q9, [{state}, #128]",
+                "stp q10, q11, [{state}, #160]",
+                "stp q12, q13, [{state}, #192]",
+                "stp q14, q15, [{state}, #224]",
+                "stp q16, q17, [{state}, #256]",
+                "stp q18, q19, [{state}, #288]",
+                "stp q20, q21, [{state}, #320]",
+                "stp q22, q23, [{state}, #352]",
+                "stp q24, q25, [{state}, #384]",
+                "stp q26, q27, [{state}, #416]",
+                "stp q28, q29, [{state}, #448]",
+                "stp q30, q31, [{state}, #480]",
+                "mrs {tmp}, fpsr",
+                "str {tmp}, [{state}, #512]",
+                "mrs {tmp}, fpcr",
+                "str {tmp}, [{state}, #520]",
+                state = in(reg) core::ptr::from_mut(self),
+                tmp = out(reg) _,
+                options(nostack, preserves_flags)
+            )
+        }
+    }
+
+    /// Loads the SIMD and floating-point register state.
+    ///
+    /// # Safety
+    ///
+    /// SIMD and floating-point accesses must not be trapped at the current exception level. If
+    /// SVE is enabled, the bits of the `Z` registers beyond the low 128 are set to zero.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn restore(&self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that the accesses do not trap, and `self` is
+        // suitably aligned.
+        unsafe {
+            core::arch::asm!(
+                ".arch_extension fp",
+                "ldp q0, q1, [{state}, #0]",
+                "ldp q2, q3, [{state}, #32]",
+                "ldp q4, q5, [{state}, #64]",
+                "ldp q6, q7, [{state}, #96]",
+                "ldp q8, q9, [{state}, #128]",
+                "ldp q10, q11, [{state}, #160]",
+                "ldp q12, q13, [{state}, #192]",
+                "ldp q14, q15, [{state}, #224]",
+                "ldp q16, q17, [{state}, #256]",
+                "ldp q18, q19, [{state}, #288]",
+                "ldp q20, q21, [{state}, #320]",
+                "ldp q22, q23, [{state}, #352]",
+                "ldp q24, q25, [{state}, #384]",
+                "ldp q26, q27, [{state}, #416]",
+                "ldp q28, q29, [{state}, #448]",
+                "ldp q30, q31, [{state}, #480]",
+                "ldr {tmp}, [{state}, #512]",
+                "msr fpsr, {tmp}",
+                "ldr {tmp}, [{state}, #520]",
+                "msr fpcr, {tmp}",
+                state = in(reg) core::ptr::from_ref(self),
+                tmp = out(reg) _,
+                options(nostack, preserves_flags, readonly)
+            )
+        }
+    }
+}
+
+/// Expands to an SVE load or store of each listed register, at the same multiple of the vector
+/// length from `$base`.
+#[cfg(target_arch = "aarch64")]
+macro_rules! sve_transfer {
+    ($op:literal, $register:literal, $base:literal; $($index:literal),+) => {
+        concat!($($op, " ", $register, $index, ", [", $base, ", #", $index, ", mul vl]\n"),+)
+    };
+}
+
+/// The SVE register state: `Z0` to `Z31`, `P0` to `P15`, `FFR`, `FPSR`, and `FPCR`.
+///
+/// The state is sized for [`MAX_VECTOR_LENGTH`], but only the part covered by the vector length
+/// in effect when the state was saved is used.
+#[repr(C, align(16))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SveState {
+    /// The contents of the `Z` registers, each occupying [`SveState::vector_length()`] bytes.
+    z: [u8; 32 * MAX_VECTOR_LENGTH],
+    /// The contents of the `P` registers followed by `FFR`, each occupying one eighth of
+    /// [`SveState::vector_length()`] bytes.
+    p: [u8; (PREDICATE_REGISTERS + 1) * MAX_PREDICATE_LENGTH],
+    /// The vector length, in bytes, in effect when the state was saved, or zero if it was never
+    /// saved.
+    vector_length: usize,
+    /// The contents of the `FPSR` register.
+    fpsr: u64,
+    /// The contents of the `FPCR` register.
+    fpcr: u64,
+}
+
+impl SveState {
+    /// Creates an [`SveState`] that has never been saved.
+    pub const fn new() -> Self {
+        Self {
+            z: [0; 32 * MAX_VECTOR_LENGTH],
+            p: [0; (PREDICATE_REGISTERS + 1) * MAX_PREDICATE_LENGTH],
+            vector_length: 0,
+            fpsr: 0,
+            fpcr: 0,
+        }
+    }
+
+    /// Returns the vector length, in bytes, in effect when the state was saved, or zero if it
+    /// was never saved.
+    pub const fn vector_length(&self) -> usize {
+        self.vector_length
+    }
+
+    /// Returns the saved contents of the register `Z<index>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than 32.
+    pub fn z(&self, index: usize) -> &[u8] {
+        assert!(index < 32, "invalid Z register {index}");
+        &self.z[index * self.vector_length..][..self.vector_length]
+    }
+
+    /// Returns the saved contents of the register `P<index>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than 16.
+    pub fn p(&self, index: usize) -> &[u8] {
+        assert!(index < PREDICATE_REGISTERS, "invalid P register {index}");
+        self.predicate(index)
+    }
+
+    /// Returns the saved contents of the first-fault register.
+    pub fn ffr(&self) -> &[u8] {
+        self.predicate(PREDICATE_REGISTERS)
+    }
+
+    /// Returns the saved value of `FPSR`.
+    pub const fn fpsr(&self) -> u64 {
+        self.fpsr
+    }
+
+    /// Returns the saved value of `FPCR`.
+    pub const fn fpcr(&self) -> u64 {
+        self.fpcr
+    }
+
+    /// Returns the saved contents of the predicate slot `index`.
+    fn predicate(&self, index: usize) -> &[u8] {
+        let length = self.vector_length / 8;
+        &self.p[index * length..][..length]
+    }
+
+    /// Saves the current SVE register state.
+    ///
+    /// # Safety
+    ///
+    /// SVE, SIMD, and floating-point accesses must not be trapped at the current exception level
+    /// and the processor must not be in streaming mode.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn save(&mut self) {
+        let vector_length: usize;
+
+        // SAFETY:
+        //
+        // The invariants of this function ensure that the accesses do not trap, and the buffers
+        // are sized for the largest vector length permitted by the architecture. `P0` is
+        // reloaded after it is used to read `FFR`.
+        unsafe {
+            core::arch::asm!(
+                ".arch_extension fp",
+                ".arch_extension sve",
+                sve_transfer!("str", "z", "{z}";
+                    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+                    16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31),
+                sve_transfer!("str", "p", "{p}";
+                    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15),
+                "rdffr p0.b",
+                "str p0, [{p}, #16, mul vl]",
+                "ldr p0, [{p}, #0, mul vl]",
+                "mrs {fpsr}, fpsr",
+                "mrs {fpcr}, fpcr",
+                "rdvl {vl}, #1",
+                z = in(reg) self.z.as_mut_ptr(),
+                p = in(reg) self.p.as_mut_ptr(),
+                fpsr = out(reg) self.fpsr,
+                fpcr = out(reg) self.fpcr,
+                vl = out(reg) vector_length,
+                options(nostack, preserves_flags)
+            )
+        }
+
+        self.vector_length = vector_length;
+    }
+
+    /// Loads the SVE register state.
+    ///
+    /// # Safety
+    ///
+    /// SVE, SIMD, and floating-point accesses must not be trapped at the current exception
+    /// level, the processor must not be in streaming mode, and the current vector length must be
+    /// the one in effect when the state was saved.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn restore(&self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that `vector_length()` does not trap.
+        debug_assert_eq!(self.vector_length, unsafe { vector_length() });
+
+        // SAFETY:
+        //
+        // The invariants of this function ensure that the accesses do not trap, and that the
+        // state was saved with the current vector length. `FFR` is restored through `P0` before
+        // `P0` itself is restored.
+        unsafe {
+            core::arch::asm!(
+                ".arch_extension fp",
+                ".arch_extension sve",
+                "ldr p0, [{p}, #16, mul vl]",
+                "wrffr p0.b",
+                sve_transfer!("ldr", "p", "{p}";
+                    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15),
+                sve_transfer!("ldr", "z", "{z}";
+                    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+                    16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31),
+                "msr fpsr, {fpsr}",
+                "msr fpcr, {fpcr}",
+                z = in(reg) self.z.as_ptr(),
+                p = in(reg) self.p.as_ptr(),
+                fpsr = in(reg) self.fpsr,
+                fpcr = in(reg) self.fpcr,
+                options(nostack, preserves_flags, readonly)
+            )
+        }
+    }
+}
+
+impl Default for SveState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Synchronizes the context after a write to a system register.
+#[cfg(target_arch = "aarch64")]
+fn isb() {
+    // SAFETY:
+    //
+    // `isb` has no effect other than synchronizing the context.
+    unsafe { core::arch::asm!("isb", options(nomem, nostack, preserves_flags)) }
+}
@@ -2,6 +2,9 @@
 
 #![no_std]
 
+pub mod decode;
+pub mod esr;
+pub mod fpsimd;
 pub mod msr;
 pub mod paging;
 
@@ -0,0 +1,2247 @@
+//! Typed wrappers around the system registers that control virtualization at EL2.
+//!
+//! Where the layout of a register depends on `HCR_EL2.E2H`, the wrapper describes the layout used
+//! when `HCR_EL2.E2H` is clear.
+
+use core::fmt;
+
+#[cfg(target_arch = "aarch64")]
+use crate::msr::raw;
+use crate::{
+    Granule, PhysicalAddressSpaceSize,
+    esr::ExceptionSyndrome,
+    msr::{MairEL1, MemoryAttribute, ZcrEL1},
+};
+
+/// The state of the `HCR_EL2` register.
+///
+/// Controls virtualization: stage 2 translation, the routing of exceptions to EL2, and the trapping
+/// of EL1 and EL0 operations to EL2.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct HcrEL2(u64);
+
+impl HcrEL2 {
+    /// Returns the value of the [`HcrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `HCR_EL2` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `HCR_EL2` is safe.
+        let val = unsafe { raw::read_hcr_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`HcrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `HCR_EL2` register and the new configuration of the
+    /// `HCR_EL2` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `HCR_EL2` is safe.
+        unsafe { raw::write_hcr_el2(self.0) }
+    }
+
+    /// Creates a [`HcrEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`HcrEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns `true` if stage 2 translation is enabled for the EL1&0 translation regime (`VM`).
+    pub const fn virtualization_enable(self) -> bool {
+        (self.0 & 0b1) == 0b1
+    }
+
+    /// Sets whether stage 2 translation is enabled for the EL1&0 translation regime (`VM`).
+    pub const fn set_virtualization_enable(self, value: bool) -> Self {
+        Self((self.0 & !0b1) | (value as u64))
+    }
+
+    /// Returns `true` if data cache invalidate by set/way instructions at EL1 perform a clean and
+    /// invalidate (`SWIO`).
+    pub const fn invalidate_as_clean(self) -> bool {
+        ((self.0 >> 1) & 0b1) == 0b1
+    }
+
+    /// Sets whether data cache invalidate by set/way instructions at EL1 perform a clean and
+    /// invalidate (`SWIO`).
+    pub const fn set_invalidate_as_clean(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 1)) | ((value as u64) << 1))
+    }
+
+    /// Returns `true` if stage 1 translation table walks that access device memory at stage 2
+    /// generate stage 2 permission faults (`PTW`).
+    pub const fn protected_table_walk(self) -> bool {
+        ((self.0 >> 2) & 0b1) == 0b1
+    }
+
+    /// Sets whether stage 1 translation table walks that access device memory at stage 2 generate
+    /// stage 2 permission faults (`PTW`).
+    pub const fn set_protected_table_walk(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 2)) | ((value as u64) << 2))
+    }
+
+    /// Returns `true` if physical FIQ interrupts are routed to EL2 (`FMO`).
+    pub const fn fiq_mask_override(self) -> bool {
+        ((self.0 >> 3) & 0b1) == 0b1
+    }
+
+    /// Sets whether physical FIQ interrupts are routed to EL2 (`FMO`).
+    pub const fn set_fiq_mask_override(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 3)) | ((value as u64) << 3))
+    }
+
+    /// Returns `true` if physical IRQ interrupts are routed to EL2 (`IMO`).
+    pub const fn irq_mask_override(self) -> bool {
+        ((self.0 >> 4) & 0b1) == 0b1
+    }
+
+    /// Sets whether physical IRQ interrupts are routed to EL2 (`IMO`).
+    pub const fn set_irq_mask_override(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 4)) | ((value as u64) << 4))
+    }
+
+    /// Returns `true` if physical SError interrupts are routed to EL2 (`AMO`).
+    pub const fn serror_mask_override(self) -> bool {
+        ((self.0 >> 5) & 0b1) == 0b1
+    }
+
+    /// Sets whether physical SError interrupts are routed to EL2 (`AMO`).
+    pub const fn set_serror_mask_override(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 5)) | ((value as u64) << 5))
+    }
+
+    /// Returns `true` if a virtual FIQ interrupt is pending (`VF`).
+    pub const fn virtual_fiq(self) -> bool {
+        ((self.0 >> 6) & 0b1) == 0b1
+    }
+
+    /// Sets whether a virtual FIQ interrupt is pending (`VF`).
+    pub const fn set_virtual_fiq(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 6)) | ((value as u64) << 6))
+    }
+
+    /// Returns `true` if a virtual IRQ interrupt is pending (`VI`).
+    pub const fn virtual_irq(self) -> bool {
+        ((self.0 >> 7) & 0b1) == 0b1
+    }
+
+    /// Sets whether a virtual IRQ interrupt is pending (`VI`).
+    pub const fn set_virtual_irq(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 7)) | ((value as u64) << 7))
+    }
+
+    /// Returns `true` if a virtual SError interrupt is pending (`VSE`).
+    pub const fn virtual_serror(self) -> bool {
+        ((self.0 >> 8) & 0b1) == 0b1
+    }
+
+    /// Sets whether a virtual SError interrupt is pending (`VSE`).
+    pub const fn set_virtual_serror(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 8)) | ((value as u64) << 8))
+    }
+
+    /// Returns `true` if TLB and cache maintenance instructions executed at EL1 are broadcast
+    /// within the Inner Shareable domain (`FB`).
+    pub const fn force_broadcast(self) -> bool {
+        ((self.0 >> 9) & 0b1) == 0b1
+    }
+
+    /// Sets whether TLB and cache maintenance instructions executed at EL1 are broadcast within the
+    /// Inner Shareable domain (`FB`).
+    pub const fn set_force_broadcast(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 9)) | ((value as u64) << 9))
+    }
+
+    /// Returns the minimum shareability domain applied to barrier instructions executed at EL1 or
+    /// EL0 (`BSU`).
+    pub const fn barrier_shareability_upgrade(self) -> u8 {
+        ((self.0 >> 10) & 0b11) as u8
+    }
+
+    /// Sets the minimum shareability domain applied to barrier instructions executed at EL1 or EL0
+    /// (`BSU`).
+    pub const fn set_barrier_shareability_upgrade(self, value: u8) -> Self {
+        Self((self.0 & !(0b11 << 10)) | ((value as u64 & 0b11) << 10))
+    }
+
+    /// Returns `true` if stage 1 translation is treated as disabled and memory accesses as Normal
+    /// Write-Back when the EL1&0 stage 1 MMU is disabled (`DC`).
+    pub const fn default_cacheability(self) -> bool {
+        ((self.0 >> 12) & 0b1) == 0b1
+    }
+
+    /// Sets whether stage 1 translation is treated as disabled and memory accesses as Normal
+    /// Write-Back when the EL1&0 stage 1 MMU is disabled (`DC`).
+    pub const fn set_default_cacheability(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 12)) | ((value as u64) << 12))
+    }
+
+    /// Returns `true` if WFI instructions executed at EL1 or EL0 are trapped to EL2 (`TWI`).
+    pub const fn trap_wfi(self) -> bool {
+        ((self.0 >> 13) & 0b1) == 0b1
+    }
+
+    /// Sets whether WFI instructions executed at EL1 or EL0 are trapped to EL2 (`TWI`).
+    pub const fn set_trap_wfi(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 13)) | ((value as u64) << 13))
+    }
+
+    /// Returns `true` if WFE instructions executed at EL1 or EL0 are trapped to EL2 (`TWE`).
+    pub const fn trap_wfe(self) -> bool {
+        ((self.0 >> 14) & 0b1) == 0b1
+    }
+
+    /// Sets whether WFE instructions executed at EL1 or EL0 are trapped to EL2 (`TWE`).
+    pub const fn set_trap_wfe(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 14)) | ((value as u64) << 14))
+    }
+
+    /// Returns `true` if reads of the ID group 0 registers are trapped to EL2 (`TID0`).
+    pub const fn trap_id_group_0(self) -> bool {
+        ((self.0 >> 15) & 0b1) == 0b1
+    }
+
+    /// Sets whether reads of the ID group 0 registers are trapped to EL2 (`TID0`).
+    pub const fn set_trap_id_group_0(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 15)) | ((value as u64) << 15))
+    }
+
+    /// Returns `true` if reads of the ID group 1 registers are trapped to EL2 (`TID1`).
+    pub const fn trap_id_group_1(self) -> bool {
+        ((self.0 >> 16) & 0b1) == 0b1
+    }
+
+    /// Sets whether reads of the ID group 1 registers are trapped to EL2 (`TID1`).
+    pub const fn set_trap_id_group_1(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 16)) | ((value as u64) << 16))
+    }
+
+    /// Returns `true` if reads of the ID group 2 registers, which describe the caches, are trapped
+    /// to EL2 (`TID2`).
+    pub const fn trap_id_group_2(self) -> bool {
+        ((self.0 >> 17) & 0b1) == 0b1
+    }
+
+    /// Sets whether reads of the ID group 2 registers, which describe the caches, are trapped to
+    /// EL2 (`TID2`).
+    pub const fn set_trap_id_group_2(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 17)) | ((value as u64) << 17))
+    }
+
+    /// Returns `true` if reads of the ID group 3 registers, which describe the processor features,
+    /// are trapped to EL2 (`TID3`).
+    pub const fn trap_id_group_3(self) -> bool {
+        ((self.0 >> 18) & 0b1) == 0b1
+    }
+
+    /// Sets whether reads of the ID group 3 registers, which describe the processor features, are
+    /// trapped to EL2 (`TID3`).
+    pub const fn set_trap_id_group_3(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 18)) | ((value as u64) << 18))
+    }
+
+    /// Returns `true` if SMC instructions executed at EL1 are trapped to EL2 (`TSC`).
+    pub const fn trap_smc(self) -> bool {
+        ((self.0 >> 19) & 0b1) == 0b1
+    }
+
+    /// Sets whether SMC instructions executed at EL1 are trapped to EL2 (`TSC`).
+    pub const fn set_trap_smc(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 19)) | ((value as u64) << 19))
+    }
+
+    /// Returns `true` if accesses to implementation defined system registers at EL1 are trapped to
+    /// EL2 (`TIDCP`).
+    pub const fn trap_implementation_defined(self) -> bool {
+        ((self.0 >> 20) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to implementation defined system registers at EL1 are trapped to EL2
+    /// (`TIDCP`).
+    pub const fn set_trap_implementation_defined(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 20)) | ((value as u64) << 20))
+    }
+
+    /// Returns `true` if accesses to the auxiliary control registers at EL1 are trapped to EL2
+    /// (`TACR`).
+    pub const fn trap_auxiliary_control(self) -> bool {
+        ((self.0 >> 21) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the auxiliary control registers at EL1 are trapped to EL2 (`TACR`).
+    pub const fn set_trap_auxiliary_control(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 21)) | ((value as u64) << 21))
+    }
+
+    /// Returns `true` if data cache maintenance by set/way instructions are trapped to EL2 (`TSW`).
+    pub const fn trap_set_way(self) -> bool {
+        ((self.0 >> 22) & 0b1) == 0b1
+    }
+
+    /// Sets whether data cache maintenance by set/way instructions are trapped to EL2 (`TSW`).
+    pub const fn set_trap_set_way(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 22)) | ((value as u64) << 22))
+    }
+
+    /// Returns `true` if data cache maintenance to the point of coherency or persistence is trapped
+    /// to EL2 (`TPCP`).
+    pub const fn trap_point_of_coherency(self) -> bool {
+        ((self.0 >> 23) & 0b1) == 0b1
+    }
+
+    /// Sets whether data cache maintenance to the point of coherency or persistence is trapped to
+    /// EL2 (`TPCP`).
+    pub const fn set_trap_point_of_coherency(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 23)) | ((value as u64) << 23))
+    }
+
+    /// Returns `true` if cache maintenance to the point of unification is trapped to EL2 (`TPU`).
+    pub const fn trap_point_of_unification(self) -> bool {
+        ((self.0 >> 24) & 0b1) == 0b1
+    }
+
+    /// Sets whether cache maintenance to the point of unification is trapped to EL2 (`TPU`).
+    pub const fn set_trap_point_of_unification(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 24)) | ((value as u64) << 24))
+    }
+
+    /// Returns `true` if TLB maintenance instructions executed at EL1 are trapped to EL2 (`TTLB`).
+    pub const fn trap_tlb_maintenance(self) -> bool {
+        ((self.0 >> 25) & 0b1) == 0b1
+    }
+
+    /// Sets whether TLB maintenance instructions executed at EL1 are trapped to EL2 (`TTLB`).
+    pub const fn set_trap_tlb_maintenance(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 25)) | ((value as u64) << 25))
+    }
+
+    /// Returns `true` if writes to the EL1 virtual memory control registers are trapped to EL2
+    /// (`TVM`).
+    pub const fn trap_virtual_memory(self) -> bool {
+        ((self.0 >> 26) & 0b1) == 0b1
+    }
+
+    /// Sets whether writes to the EL1 virtual memory control registers are trapped to EL2 (`TVM`).
+    pub const fn set_trap_virtual_memory(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 26)) | ((value as u64) << 26))
+    }
+
+    /// Returns `true` if exceptions that would be routed to EL1 are routed to EL2 (`TGE`).
+    pub const fn trap_general_exceptions(self) -> bool {
+        ((self.0 >> 27) & 0b1) == 0b1
+    }
+
+    /// Sets whether exceptions that would be routed to EL1 are routed to EL2 (`TGE`).
+    pub const fn set_trap_general_exceptions(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 27)) | ((value as u64) << 27))
+    }
+
+    /// Returns `true` if DC ZVA instructions executed at EL1 or EL0 are trapped to EL2 (`TDZ`).
+    pub const fn trap_dc_zva(self) -> bool {
+        ((self.0 >> 28) & 0b1) == 0b1
+    }
+
+    /// Sets whether DC ZVA instructions executed at EL1 or EL0 are trapped to EL2 (`TDZ`).
+    pub const fn set_trap_dc_zva(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 28)) | ((value as u64) << 28))
+    }
+
+    /// Returns `true` if HVC instructions are undefined (`HCD`).
+    pub const fn hvc_disable(self) -> bool {
+        ((self.0 >> 29) & 0b1) == 0b1
+    }
+
+    /// Sets whether HVC instructions are undefined (`HCD`).
+    pub const fn set_hvc_disable(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 29)) | ((value as u64) << 29))
+    }
+
+    /// Returns `true` if reads of the EL1 virtual memory control registers are trapped to EL2
+    /// (`TRVM`).
+    pub const fn trap_virtual_memory_reads(self) -> bool {
+        ((self.0 >> 30) & 0b1) == 0b1
+    }
+
+    /// Sets whether reads of the EL1 virtual memory control registers are trapped to EL2 (`TRVM`).
+    pub const fn set_trap_virtual_memory_reads(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 30)) | ((value as u64) << 30))
+    }
+
+    /// Returns `true` if EL1 executes in AArch64 state (`RW`).
+    pub const fn el1_aarch64(self) -> bool {
+        ((self.0 >> 31) & 0b1) == 0b1
+    }
+
+    /// Sets whether EL1 executes in AArch64 state (`RW`).
+    pub const fn set_el1_aarch64(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 31)) | ((value as u64) << 31))
+    }
+
+    /// Returns `true` if stage 2 data accesses and translation table walks are treated as
+    /// Non-cacheable (`CD`).
+    pub const fn stage_2_data_cache_disable(self) -> bool {
+        ((self.0 >> 32) & 0b1) == 0b1
+    }
+
+    /// Sets whether stage 2 data accesses and translation table walks are treated as Non-cacheable
+    /// (`CD`).
+    pub const fn set_stage_2_data_cache_disable(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 32)) | ((value as u64) << 32))
+    }
+
+    /// Returns `true` if stage 2 instruction accesses are treated as Non-cacheable (`ID`).
+    pub const fn stage_2_instruction_cache_disable(self) -> bool {
+        ((self.0 >> 33) & 0b1) == 0b1
+    }
+
+    /// Sets whether stage 2 instruction accesses are treated as Non-cacheable (`ID`).
+    pub const fn set_stage_2_instruction_cache_disable(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 33)) | ((value as u64) << 33))
+    }
+
+    /// Returns `true` if the host operating system runs at EL2 (the Virtualization Host Extensions
+    /// are enabled) (`E2H`).
+    pub const fn el2_host(self) -> bool {
+        ((self.0 >> 34) & 0b1) == 0b1
+    }
+
+    /// Sets whether the host operating system runs at EL2 (the Virtualization Host Extensions are
+    /// enabled) (`E2H`).
+    pub const fn set_el2_host(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 34)) | ((value as u64) << 34))
+    }
+
+    /// Returns `true` if accesses to the LORegion registers at EL1 are trapped to EL2 (`TLOR`).
+    pub const fn trap_lor(self) -> bool {
+        ((self.0 >> 35) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the LORegion registers at EL1 are trapped to EL2 (`TLOR`).
+    pub const fn set_trap_lor(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 35)) | ((value as u64) << 35))
+    }
+
+    /// Returns `true` if accesses to the RAS error record registers at EL1 are trapped to EL2
+    /// (`TERR`).
+    pub const fn trap_error_records(self) -> bool {
+        ((self.0 >> 36) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the RAS error record registers at EL1 are trapped to EL2 (`TERR`).
+    pub const fn set_trap_error_records(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 36)) | ((value as u64) << 36))
+    }
+
+    /// Returns `true` if synchronous external aborts taken from EL1 or EL0 are routed to EL2
+    /// (`TEA`).
+    pub const fn route_external_aborts(self) -> bool {
+        ((self.0 >> 37) & 0b1) == 0b1
+    }
+
+    /// Sets whether synchronous external aborts taken from EL1 or EL0 are routed to EL2 (`TEA`).
+    pub const fn set_route_external_aborts(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 37)) | ((value as u64) << 37))
+    }
+
+    /// Returns `true` if accesses to the pointer authentication key registers at EL1 are not
+    /// trapped to EL2 (`APK`).
+    pub const fn pointer_authentication_key_access(self) -> bool {
+        ((self.0 >> 40) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the pointer authentication key registers at EL1 are not trapped to
+    /// EL2 (`APK`).
+    pub const fn set_pointer_authentication_key_access(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 40)) | ((value as u64) << 40))
+    }
+
+    /// Returns `true` if pointer authentication instructions executed at EL1 or EL0 are not trapped
+    /// to EL2 (`API`).
+    pub const fn pointer_authentication_instructions(self) -> bool {
+        ((self.0 >> 41) & 0b1) == 0b1
+    }
+
+    /// Sets whether pointer authentication instructions executed at EL1 or EL0 are not trapped to
+    /// EL2 (`API`).
+    pub const fn set_pointer_authentication_instructions(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 41)) | ((value as u64) << 41))
+    }
+
+    /// Returns `true` if nested virtualization traps are enabled (`NV`).
+    pub const fn nested_virtualization(self) -> bool {
+        ((self.0 >> 42) & 0b1) == 0b1
+    }
+
+    /// Sets whether nested virtualization traps are enabled (`NV`).
+    pub const fn set_nested_virtualization(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 42)) | ((value as u64) << 42))
+    }
+
+    /// Returns `true` if EL1 accesses to the EL1 registers affected by nested virtualization are
+    /// trapped to EL2 (`NV1`).
+    pub const fn nested_virtualization_1(self) -> bool {
+        ((self.0 >> 43) & 0b1) == 0b1
+    }
+
+    /// Sets whether EL1 accesses to the EL1 registers affected by nested virtualization are trapped
+    /// to EL2 (`NV1`).
+    pub const fn set_nested_virtualization_1(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 43)) | ((value as u64) << 43))
+    }
+
+    /// Returns `true` if AT S1E1* instructions executed at EL1 are trapped to EL2 (`AT`).
+    pub const fn trap_address_translation(self) -> bool {
+        ((self.0 >> 44) & 0b1) == 0b1
+    }
+
+    /// Sets whether AT S1E1* instructions executed at EL1 are trapped to EL2 (`AT`).
+    pub const fn set_trap_address_translation(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 44)) | ((value as u64) << 44))
+    }
+
+    /// Returns `true` if stage 2 can force the resulting memory type and cacheability to Normal
+    /// Write-Back (`FWB`).
+    pub const fn forced_write_back(self) -> bool {
+        ((self.0 >> 46) & 0b1) == 0b1
+    }
+
+    /// Sets whether stage 2 can force the resulting memory type and cacheability to Normal
+    /// Write-Back (`FWB`).
+    pub const fn set_forced_write_back(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 46)) | ((value as u64) << 46))
+    }
+
+    /// Returns `true` if reads of the cache identification registers and writes to CSSELR_EL1 are
+    /// trapped to EL2 (`TID4`).
+    pub const fn trap_id_group_4(self) -> bool {
+        ((self.0 >> 49) & 0b1) == 0b1
+    }
+
+    /// Sets whether reads of the cache identification registers and writes to CSSELR_EL1 are
+    /// trapped to EL2 (`TID4`).
+    pub const fn set_trap_id_group_4(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 49)) | ((value as u64) << 49))
+    }
+}
+
+impl fmt::Debug for HcrEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HcrEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("virtualization_enable", &self.virtualization_enable())
+            .field("invalidate_as_clean", &self.invalidate_as_clean())
+            .field("protected_table_walk", &self.protected_table_walk())
+            .field("fiq_mask_override", &self.fiq_mask_override())
+            .field("irq_mask_override", &self.irq_mask_override())
+            .field("serror_mask_override", &self.serror_mask_override())
+            .field("virtual_fiq", &self.virtual_fiq())
+            .field("virtual_irq", &self.virtual_irq())
+            .field("virtual_serror", &self.virtual_serror())
+            .field("force_broadcast", &self.force_broadcast())
+            .field(
+                "barrier_shareability_upgrade",
+                &self.barrier_shareability_upgrade(),
+            )
+            .field("default_cacheability", &self.default_cacheability())
+            .field("trap_wfi", &self.trap_wfi())
+            .field("trap_wfe", &self.trap_wfe())
+            .field("trap_id_group_0", &self.trap_id_group_0())
+            .field("trap_id_group_1", &self.trap_id_group_1())
+            .field("trap_id_group_2", &self.trap_id_group_2())
+            .field("trap_id_group_3", &self.trap_id_group_3())
+            .field("trap_smc", &self.trap_smc())
+            .field(
+                "trap_implementation_defined",
+                &self.trap_implementation_defined(),
+            )
+            .field("trap_auxiliary_control", &self.trap_auxiliary_control())
+            .field("trap_set_way", &self.trap_set_way())
+            .field("trap_point_of_coherency", &self.trap_point_of_coherency())
+            .field(
+                "trap_point_of_unification",
+                &self.trap_point_of_unification(),
+            )
+            .field("trap_tlb_maintenance", &self.trap_tlb_maintenance())
+            .field("trap_virtual_memory", &self.trap_virtual_memory())
+            .field("trap_general_exceptions", &self.trap_general_exceptions())
+            .field("trap_dc_zva", &self.trap_dc_zva())
+            .field("hvc_disable", &self.hvc_disable())
+            .field(
+                "trap_virtual_memory_reads",
+                &self.trap_virtual_memory_reads(),
+            )
+            .field("el1_aarch64", &self.el1_aarch64())
+            .field(
+                "stage_2_data_cache_disable",
+                &self.stage_2_data_cache_disable(),
+            )
+            .field(
+                "stage_2_instruction_cache_disable",
+                &self.stage_2_instruction_cache_disable(),
+            )
+            .field("el2_host", &self.el2_host())
+            .field("trap_lor", &self.trap_lor())
+            .field("trap_error_records", &self.trap_error_records())
+            .field("route_external_aborts", &self.route_external_aborts())
+            .field(
+                "pointer_authentication_key_access",
+                &self.pointer_authentication_key_access(),
+            )
+            .field(
+                "pointer_authentication_instructions",
+                &self.pointer_authentication_instructions(),
+            )
+            .field("nested_virtualization", &self.nested_virtualization())
+            .field("nested_virtualization_1", &self.nested_virtualization_1())
+            .field("trap_address_translation", &self.trap_address_translation())
+            .field("forced_write_back", &self.forced_write_back())
+            .field("trap_id_group_4", &self.trap_id_group_4())
+            .finish()
+    }
+}
+
+/// The state of the `VTCR_EL2` register.
+///
+/// Controls the stage 2 translation of the EL1&0 translation regime.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct VtcrEL2(u64);
+
+impl VtcrEL2 {
+    /// The bits of the [`VtcrEL2`] register that are reserved and must be written as one.
+    pub const RES1: u64 = 1 << 31;
+
+    /// Returns the value of the [`VtcrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `VTCR_EL2` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `VTCR_EL2` is safe.
+        let val = unsafe { raw::read_vtcr_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`VtcrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `VTCR_EL2` register and the new configuration of the
+    /// `VTCR_EL2` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `VTCR_EL2` is safe.
+        unsafe { raw::write_vtcr_el2(self.0) }
+    }
+
+    /// Returns a [`VtcrEL2`] with every field cleared and every reserved bit set to its required
+    /// value.
+    pub const fn new() -> Self {
+        Self(Self::RES1)
+    }
+
+    /// Creates a [`VtcrEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`VtcrEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the size offset of the memory region addressed by `VTTBR_EL2` (`T0SZ`).
+    pub const fn size_offset(self) -> u8 {
+        (self.0 & 0x3F) as u8
+    }
+
+    /// Sets the size offset of the memory region addressed by `VTTBR_EL2` (`T0SZ`).
+    pub const fn set_size_offset(self, value: u8) -> Self {
+        Self((self.0 & !0x3F) | (value as u64 & 0x3F))
+    }
+
+    /// Returns the encoded starting level of the stage 2 translation table walk (`SL0`).
+    pub const fn starting_level(self) -> u8 {
+        ((self.0 >> 6) & 0b11) as u8
+    }
+
+    /// Sets the encoded starting level of the stage 2 translation table walk (`SL0`).
+    pub const fn set_starting_level(self, value: u8) -> Self {
+        Self((self.0 & !(0b11 << 6)) | ((value as u64 & 0b11) << 6))
+    }
+
+    /// Returns the inner cacheability attribute for stage 2 translation table walks (`IRGN0`).
+    pub const fn inner_cacheability_attr(self) -> u8 {
+        ((self.0 >> 8) & 0b11) as u8
+    }
+
+    /// Sets the inner cacheability attribute for stage 2 translation table walks (`IRGN0`).
+    pub const fn set_inner_cacheability_attr(self, value: u8) -> Self {
+        Self((self.0 & !(0b11 << 8)) | ((value as u64 & 0b11) << 8))
+    }
+
+    /// Returns the outer cacheability attribute for stage 2 translation table walks (`ORGN0`).
+    pub const fn outer_cacheability_attr(self) -> u8 {
+        ((self.0 >> 10) & 0b11) as u8
+    }
+
+    /// Sets the outer cacheability attribute for stage 2 translation table walks (`ORGN0`).
+    pub const fn set_outer_cacheability_attr(self, value: u8) -> Self {
+        Self((self.0 & !(0b11 << 10)) | ((value as u64 & 0b11) << 10))
+    }
+
+    /// Returns the shareability attribute for stage 2 translation table walks (`SH0`).
+    pub const fn shareability_attr(self) -> u8 {
+        ((self.0 >> 12) & 0b11) as u8
+    }
+
+    /// Sets the shareability attribute for stage 2 translation table walks (`SH0`).
+    pub const fn set_shareability_attr(self, value: u8) -> Self {
+        Self((self.0 & !(0b11 << 12)) | ((value as u64 & 0b11) << 12))
+    }
+
+    /// Returns the stage 2 translation [`Granule`] (`TG0`).
+    pub const fn translation_granule(self) -> Granule {
+        match (self.0 >> 14) & 0b11 {
+            0b00 => Granule::Page4KiB,
+            0b01 => Granule::Page64KiB,
+            0b10 => Granule::Page16KiB,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Sets the stage 2 translation [`Granule`] (`TG0`).
+    pub const fn set_translation_granule(self, granule: Granule) -> Self {
+        let value = match granule {
+            Granule::Page4KiB => 0b00,
+            Granule::Page16KiB => 0b10,
+            Granule::Page64KiB => 0b01,
+        };
+
+        Self((self.0 & !(0b11 << 14)) | (value << 14))
+    }
+
+    /// Returns the physical address size of the stage 2 output (`PS`).
+    pub const fn physical_address_size(self) -> PhysicalAddressSpaceSize {
+        PhysicalAddressSpaceSize::from_bits(((self.0 >> 16) & 0b111) as u8)
+    }
+
+    /// Sets the physical address size of the stage 2 output (`PS`).
+    pub const fn set_physical_address_size(self, size: PhysicalAddressSpaceSize) -> Self {
+        Self((self.0 & !(0b111 << 16)) | ((size.to_bits() as u64 & 0b111) << 16))
+    }
+
+    /// Returns `true` if 16-bit VMIDs are used (`VS`).
+    pub const fn vmid_16(self) -> bool {
+        ((self.0 >> 19) & 0b1) == 0b1
+    }
+
+    /// Sets whether 16-bit VMIDs are used (`VS`).
+    pub const fn set_vmid_16(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 19)) | ((value as u64) << 19))
+    }
+
+    /// Returns `true` if the hardware manages the stage 2 access flag (`HA`).
+    pub const fn hardware_access_flag(self) -> bool {
+        ((self.0 >> 21) & 0b1) == 0b1
+    }
+
+    /// Sets whether the hardware manages the stage 2 access flag (`HA`).
+    pub const fn set_hardware_access_flag(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 21)) | ((value as u64) << 21))
+    }
+
+    /// Returns `true` if the hardware manages the stage 2 dirty state (`HD`).
+    pub const fn hardware_dirty_state(self) -> bool {
+        ((self.0 >> 22) & 0b1) == 0b1
+    }
+
+    /// Sets whether the hardware manages the stage 2 dirty state (`HD`).
+    pub const fn set_hardware_dirty_state(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 22)) | ((value as u64) << 22))
+    }
+}
+
+impl Default for VtcrEL2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for VtcrEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VtcrEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("size_offset", &self.size_offset())
+            .field("starting_level", &self.starting_level())
+            .field("inner_cacheability_attr", &self.inner_cacheability_attr())
+            .field("outer_cacheability_attr", &self.outer_cacheability_attr())
+            .field("shareability_attr", &self.shareability_attr())
+            .field("translation_granule", &self.translation_granule())
+            .field("physical_address_size", &self.physical_address_size())
+            .field("vmid_16", &self.vmid_16())
+            .field("hardware_access_flag", &self.hardware_access_flag())
+            .field("hardware_dirty_state", &self.hardware_dirty_state())
+            .finish()
+    }
+}
+
+/// The state of the `VTTBR_EL2` register.
+///
+/// Holds the base address of the stage 2 translation table and the VMID of the current virtual
+/// machine.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct VttbrEL2(u64);
+
+impl VttbrEL2 {
+    /// Returns the value of the [`VttbrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `VTTBR_EL2` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `VTTBR_EL2` is safe.
+        let val = unsafe { raw::read_vttbr_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`VttbrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `VTTBR_EL2` register and the new configuration of the
+    /// `VTTBR_EL2` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `VTTBR_EL2` is safe.
+        unsafe { raw::write_vttbr_el2(self.0) }
+    }
+
+    /// Creates a [`VttbrEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`VttbrEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns `true` if the stage 2 translation table entries are shared with other processors in
+    /// the Inner Shareable domain (`CnP`).
+    pub const fn common_not_private(self) -> bool {
+        (self.0 & 0b1) == 0b1
+    }
+
+    /// Sets whether the stage 2 translation table entries are shared with other processors in the
+    /// Inner Shareable domain (`CnP`).
+    pub const fn set_common_not_private(self, value: bool) -> Self {
+        Self((self.0 & !0b1) | (value as u64))
+    }
+
+    /// Returns the physical address of the stage 2 translation table (`BADDR`).
+    pub const fn base_address(self) -> u64 {
+        self.0 & 0x0000_FFFF_FFFF_FFFE
+    }
+
+    /// Sets the physical address of the stage 2 translation table (`BADDR`).
+    pub const fn set_base_address(self, value: u64) -> Self {
+        Self((self.0 & !0x0000_FFFF_FFFF_FFFE) | (value & 0x0000_FFFF_FFFF_FFFE))
+    }
+
+    /// Returns the VMID of the current virtual machine (`VMID`).
+    pub const fn vmid(self) -> u16 {
+        ((self.0 >> 48) & 0xFFFF) as u16
+    }
+
+    /// Sets the VMID of the current virtual machine (`VMID`).
+    pub const fn set_vmid(self, value: u16) -> Self {
+        Self((self.0 & !(0xFFFF << 48)) | ((value as u64 & 0xFFFF) << 48))
+    }
+}
+
+impl fmt::Debug for VttbrEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VttbrEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("common_not_private", &self.common_not_private())
+            .field("base_address", &self.base_address())
+            .field("vmid", &self.vmid())
+            .finish()
+    }
+}
+
+/// The state of the `SCTLR_EL2` register.
+///
+/// Provides top-level control of the system at EL2, including its stage 1 MMU and caches.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct SctlrEL2(u64);
+
+impl SctlrEL2 {
+    /// The bits of the [`SctlrEL2`] register that are reserved and must be written as one.
+    pub const RES1: u64 = 0x30C5_0830;
+
+    /// Returns the value of the [`SctlrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `SCTLR_EL2` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `SCTLR_EL2` is safe.
+        let val = unsafe { raw::read_sctlr_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`SctlrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `SCTLR_EL2` register and the new configuration of the
+    /// `SCTLR_EL2` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `SCTLR_EL2` is safe.
+        unsafe { raw::write_sctlr_el2(self.0) }
+    }
+
+    /// Returns a [`SctlrEL2`] with every field cleared and every reserved bit set to its required
+    /// value.
+    pub const fn new() -> Self {
+        Self(Self::RES1)
+    }
+
+    /// Creates a [`SctlrEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`SctlrEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns `true` if the MMU is enabled for EL2 stage 1 address translation (`M`).
+    pub const fn mmu_enable(self) -> bool {
+        (self.0 & 0b1) == 0b1
+    }
+
+    /// Sets whether the MMU is enabled for EL2 stage 1 address translation (`M`).
+    pub const fn set_mmu_enable(self, value: bool) -> Self {
+        Self((self.0 & !0b1) | (value as u64))
+    }
+
+    /// Returns `true` if alignment fault checking is enabled at EL2 (`A`).
+    pub const fn alignment_check(self) -> bool {
+        ((self.0 >> 1) & 0b1) == 0b1
+    }
+
+    /// Sets whether alignment fault checking is enabled at EL2 (`A`).
+    pub const fn set_alignment_check(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 1)) | ((value as u64) << 1))
+    }
+
+    /// Returns `true` if data accesses and translation table walks at EL2 are cacheable (`C`).
+    pub const fn data_cache_enable(self) -> bool {
+        ((self.0 >> 2) & 0b1) == 0b1
+    }
+
+    /// Sets whether data accesses and translation table walks at EL2 are cacheable (`C`).
+    pub const fn set_data_cache_enable(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 2)) | ((value as u64) << 2))
+    }
+
+    /// Returns `true` if stack alignment checking is enabled at EL2 (`SA`).
+    pub const fn stack_alignment_check(self) -> bool {
+        ((self.0 >> 3) & 0b1) == 0b1
+    }
+
+    /// Sets whether stack alignment checking is enabled at EL2 (`SA`).
+    pub const fn set_stack_alignment_check(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 3)) | ((value as u64) << 3))
+    }
+
+    /// Returns `true` if instruction accesses at EL2 are cacheable (`I`).
+    pub const fn instruction_cache_enable(self) -> bool {
+        ((self.0 >> 12) & 0b1) == 0b1
+    }
+
+    /// Sets whether instruction accesses at EL2 are cacheable (`I`).
+    pub const fn set_instruction_cache_enable(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 12)) | ((value as u64) << 12))
+    }
+
+    /// Returns `true` if writable memory is treated as execute-never at EL2 (`WXN`).
+    pub const fn write_execute_never(self) -> bool {
+        ((self.0 >> 19) & 0b1) == 0b1
+    }
+
+    /// Sets whether writable memory is treated as execute-never at EL2 (`WXN`).
+    pub const fn set_write_execute_never(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 19)) | ((value as u64) << 19))
+    }
+
+    /// Returns `true` if data accesses and translation table walks at EL2 are big-endian (`EE`).
+    pub const fn big_endian(self) -> bool {
+        ((self.0 >> 25) & 0b1) == 0b1
+    }
+
+    /// Sets whether data accesses and translation table walks at EL2 are big-endian (`EE`).
+    pub const fn set_big_endian(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 25)) | ((value as u64) << 25))
+    }
+}
+
+impl Default for SctlrEL2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for SctlrEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SctlrEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("mmu_enable", &self.mmu_enable())
+            .field("alignment_check", &self.alignment_check())
+            .field("data_cache_enable", &self.data_cache_enable())
+            .field("stack_alignment_check", &self.stack_alignment_check())
+            .field("instruction_cache_enable", &self.instruction_cache_enable())
+            .field("write_execute_never", &self.write_execute_never())
+            .field("big_endian", &self.big_endian())
+            .finish()
+    }
+}
+
+/// The state of the `TCR_EL2` register.
+///
+/// Controls stage 1 translation of the EL2 translation regime, when `HCR_EL2.E2H` is clear.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct TcrEL2(u64);
+
+impl TcrEL2 {
+    /// The bits of the [`TcrEL2`] register that are reserved and must be written as one.
+    pub const RES1: u64 = (1 << 31) | (1 << 23);
+
+    /// Returns the value of the [`TcrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `TCR_EL2` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `TCR_EL2` is safe.
+        let val = unsafe { raw::read_tcr_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`TcrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `TCR_EL2` register and the new configuration of the
+    /// `TCR_EL2` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `TCR_EL2` is safe.
+        unsafe { raw::write_tcr_el2(self.0) }
+    }
+
+    /// Returns a [`TcrEL2`] with every field cleared and every reserved bit set to its required
+    /// value.
+    pub const fn new() -> Self {
+        Self(Self::RES1)
+    }
+
+    /// Creates a [`TcrEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`TcrEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the size offset of the memory region addressed by `TTBR0_EL2` (`T0SZ`).
+    pub const fn size_offset(self) -> u8 {
+        (self.0 & 0x3F) as u8
+    }
+
+    /// Sets the size offset of the memory region addressed by `TTBR0_EL2` (`T0SZ`).
+    pub const fn set_size_offset(self, value: u8) -> Self {
+        Self((self.0 & !0x3F) | (value as u64 & 0x3F))
+    }
+
+    /// Returns the inner cacheability attribute for translation table walks (`IRGN0`).
+    pub const fn inner_cacheability_attr(self) -> u8 {
+        ((self.0 >> 8) & 0b11) as u8
+    }
+
+    /// Sets the inner cacheability attribute for translation table walks (`IRGN0`).
+    pub const fn set_inner_cacheability_attr(self, value: u8) -> Self {
+        Self((self.0 & !(0b11 << 8)) | ((value as u64 & 0b11) << 8))
+    }
+
+    /// Returns the outer cacheability attribute for translation table walks (`ORGN0`).
+    pub const fn outer_cacheability_attr(self) -> u8 {
+        ((self.0 >> 10) & 0b11) as u8
+    }
+
+    /// Sets the outer cacheability attribute for translation table walks (`ORGN0`).
+    pub const fn set_outer_cacheability_attr(self, value: u8) -> Self {
+        Self((self.0 & !(0b11 << 10)) | ((value as u64 & 0b11) << 10))
+    }
+
+    /// Returns the shareability attribute for translation table walks (`SH0`).
+    pub const fn shareability_attr(self) -> u8 {
+        ((self.0 >> 12) & 0b11) as u8
+    }
+
+    /// Sets the shareability attribute for translation table walks (`SH0`).
+    pub const fn set_shareability_attr(self, value: u8) -> Self {
+        Self((self.0 & !(0b11 << 12)) | ((value as u64 & 0b11) << 12))
+    }
+
+    /// Returns the translation [`Granule`] for `TTBR0_EL2` (`TG0`).
+    pub const fn translation_granule(self) -> Granule {
+        match (self.0 >> 14) & 0b11 {
+            0b00 => Granule::Page4KiB,
+            0b01 => Granule::Page64KiB,
+            0b10 => Granule::Page16KiB,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Sets the translation [`Granule`] for `TTBR0_EL2` (`TG0`).
+    pub const fn set_translation_granule(self, granule: Granule) -> Self {
+        let value = match granule {
+            Granule::Page4KiB => 0b00,
+            Granule::Page16KiB => 0b10,
+            Granule::Page64KiB => 0b01,
+        };
+
+        Self((self.0 & !(0b11 << 14)) | (value << 14))
+    }
+
+    /// Returns the physical address size of the translation output (`PS`).
+    pub const fn physical_address_size(self) -> PhysicalAddressSpaceSize {
+        PhysicalAddressSpaceSize::from_bits(((self.0 >> 16) & 0b111) as u8)
+    }
+
+    /// Sets the physical address size of the translation output (`PS`).
+    pub const fn set_physical_address_size(self, size: PhysicalAddressSpaceSize) -> Self {
+        Self((self.0 & !(0b111 << 16)) | ((size.to_bits() as u64 & 0b111) << 16))
+    }
+
+    /// Returns `true` if the top byte of addresses is ignored during translation (`TBI`).
+    pub const fn top_byte_ignored(self) -> bool {
+        ((self.0 >> 20) & 0b1) == 0b1
+    }
+
+    /// Sets whether the top byte of addresses is ignored during translation (`TBI`).
+    pub const fn set_top_byte_ignored(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 20)) | ((value as u64) << 20))
+    }
+
+    /// Returns `true` if the hardware manages the access flag (`HA`).
+    pub const fn hardware_access_flag(self) -> bool {
+        ((self.0 >> 21) & 0b1) == 0b1
+    }
+
+    /// Sets whether the hardware manages the access flag (`HA`).
+    pub const fn set_hardware_access_flag(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 21)) | ((value as u64) << 21))
+    }
+
+    /// Returns `true` if the hardware manages the dirty state (`HD`).
+    pub const fn hardware_dirty_state(self) -> bool {
+        ((self.0 >> 22) & 0b1) == 0b1
+    }
+
+    /// Sets whether the hardware manages the dirty state (`HD`).
+    pub const fn set_hardware_dirty_state(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 22)) | ((value as u64) << 22))
+    }
+
+    /// Returns `true` if hierarchical permissions are disabled (`HPD`).
+    pub const fn hierarchical_permissions_disable(self) -> bool {
+        ((self.0 >> 24) & 0b1) == 0b1
+    }
+
+    /// Sets whether hierarchical permissions are disabled (`HPD`).
+    pub const fn set_hierarchical_permissions_disable(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 24)) | ((value as u64) << 24))
+    }
+}
+
+impl Default for TcrEL2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for TcrEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TcrEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("size_offset", &self.size_offset())
+            .field("inner_cacheability_attr", &self.inner_cacheability_attr())
+            .field("outer_cacheability_attr", &self.outer_cacheability_attr())
+            .field("shareability_attr", &self.shareability_attr())
+            .field("translation_granule", &self.translation_granule())
+            .field("physical_address_size", &self.physical_address_size())
+            .field("top_byte_ignored", &self.top_byte_ignored())
+            .field("hardware_access_flag", &self.hardware_access_flag())
+            .field("hardware_dirty_state", &self.hardware_dirty_state())
+            .field(
+                "hierarchical_permissions_disable",
+                &self.hierarchical_permissions_disable(),
+            )
+            .finish()
+    }
+}
+
+/// The state of the `ESR_EL2` register.
+///
+/// Holds syndrome information for an exception taken to EL2.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct EsrEL2(u64);
+
+impl EsrEL2 {
+    /// Returns the value of the [`EsrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `ESR_EL2` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `ESR_EL2` is safe.
+        let val = unsafe { raw::read_esr_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`EsrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `ESR_EL2` register and the new configuration of the
+    /// `ESR_EL2` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `ESR_EL2` is safe.
+        unsafe { raw::write_esr_el2(self.0) }
+    }
+
+    /// Creates a [`EsrEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`EsrEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the instruction specific syndrome, whose format depends on the exception class
+    /// (`ISS`).
+    pub const fn instruction_specific_syndrome(self) -> u32 {
+        (self.0 & 0x1FF_FFFF) as u32
+    }
+
+    /// Sets the instruction specific syndrome, whose format depends on the exception class (`ISS`).
+    pub const fn set_instruction_specific_syndrome(self, value: u32) -> Self {
+        Self((self.0 & !0x1FF_FFFF) | (value as u64 & 0x1FF_FFFF))
+    }
+
+    /// Returns `true` if the trapped instruction was 32 bits long (`IL`).
+    pub const fn instruction_length(self) -> bool {
+        ((self.0 >> 25) & 0b1) == 0b1
+    }
+
+    /// Sets whether the trapped instruction was 32 bits long (`IL`).
+    pub const fn set_instruction_length(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 25)) | ((value as u64) << 25))
+    }
+
+    /// Returns the class of the exception (`EC`).
+    pub const fn exception_class(self) -> u8 {
+        ((self.0 >> 26) & 0x3F) as u8
+    }
+
+    /// Sets the class of the exception (`EC`).
+    pub const fn set_exception_class(self, value: u8) -> Self {
+        Self((self.0 & !(0x3F << 26)) | ((value as u64 & 0x3F) << 26))
+    }
+
+    /// Returns the additional instruction specific syndrome (`ISS2`).
+    pub const fn instruction_specific_syndrome_2(self) -> u32 {
+        ((self.0 >> 32) & 0xFF_FFFF) as u32
+    }
+
+    /// Sets the additional instruction specific syndrome (`ISS2`).
+    pub const fn set_instruction_specific_syndrome_2(self, value: u32) -> Self {
+        Self((self.0 & !(0xFF_FFFF << 32)) | ((value as u64 & 0xFF_FFFF) << 32))
+    }
+
+    /// Returns the [`ExceptionSyndrome`] described by this [`EsrEL2`].
+    pub const fn syndrome(self) -> ExceptionSyndrome {
+        ExceptionSyndrome::from_bits(self.0)
+    }
+}
+
+impl fmt::Debug for EsrEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EsrEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field(
+                "instruction_specific_syndrome",
+                &self.instruction_specific_syndrome(),
+            )
+            .field("instruction_length", &self.instruction_length())
+            .field("exception_class", &self.exception_class())
+            .field(
+                "instruction_specific_syndrome_2",
+                &self.instruction_specific_syndrome_2(),
+            )
+            .finish()
+    }
+}
+
+/// The state of the `FAR_EL2` register.
+///
+/// Holds the faulting virtual address for synchronous instruction aborts, data aborts, PC alignment
+/// faults, and watchpoints taken to EL2.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct FarEL2(u64);
+
+impl FarEL2 {
+    /// Returns the value of the [`FarEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `FAR_EL2` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `FAR_EL2` is safe.
+        let val = unsafe { raw::read_far_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`FarEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `FAR_EL2` register and the new configuration of the
+    /// `FAR_EL2` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `FAR_EL2` is safe.
+        unsafe { raw::write_far_el2(self.0) }
+    }
+
+    /// Creates a [`FarEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`FarEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the faulting virtual address (`VA`).
+    pub const fn address(self) -> u64 {
+        self.0
+    }
+
+    /// Sets the faulting virtual address (`VA`).
+    pub const fn set_address(self, value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl fmt::Debug for FarEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FarEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("address", &self.address())
+            .finish()
+    }
+}
+
+/// The state of the `HPFAR_EL2` register.
+///
+/// Holds the faulting intermediate physical address for stage 2 translation faults and access flag
+/// faults.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct HpfarEL2(u64);
+
+impl HpfarEL2 {
+    /// Returns the value of the [`HpfarEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `HPFAR_EL2` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `HPFAR_EL2` is safe.
+        let val = unsafe { raw::read_hpfar_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`HpfarEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `HPFAR_EL2` register and the new configuration of the
+    /// `HPFAR_EL2` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `HPFAR_EL2` is safe.
+        unsafe { raw::write_hpfar_el2(self.0) }
+    }
+
+    /// Creates a [`HpfarEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`HpfarEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the address of the page containing the faulting intermediate physical address
+    /// (`FIPA`).
+    pub const fn faulting_page(self) -> u64 {
+        ((self.0 >> 4) & 0xFFF_FFFF_FFFF) << 12
+    }
+
+    /// Returns `true` if the faulting address is in the Non-secure IPA space (`NS`).
+    pub const fn non_secure(self) -> bool {
+        ((self.0 >> 63) & 0b1) == 0b1
+    }
+}
+
+impl fmt::Debug for HpfarEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HpfarEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("faulting_page", &self.faulting_page())
+            .field("non_secure", &self.non_secure())
+            .finish()
+    }
+}
+
+/// The state of the `VBAR_EL2` register.
+///
+/// Holds the base address of the exception vector table for exceptions taken to EL2.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct VbarEL2(u64);
+
+impl VbarEL2 {
+    /// Returns the value of the [`VbarEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `VBAR_EL2` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `VBAR_EL2` is safe.
+        let val = unsafe { raw::read_vbar_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`VbarEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `VBAR_EL2` register and the new configuration of the
+    /// `VBAR_EL2` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `VBAR_EL2` is safe.
+        unsafe { raw::write_vbar_el2(self.0) }
+    }
+
+    /// Creates a [`VbarEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`VbarEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the 2 KiB aligned base address of the exception vector table (`VBA`).
+    pub const fn base_address(self) -> u64 {
+        self.0 & !0x7FF
+    }
+
+    /// Sets the 2 KiB aligned base address of the exception vector table (`VBA`).
+    pub const fn set_base_address(self, value: u64) -> Self {
+        Self((self.0 & !!0x7FF) | (value & !0x7FF))
+    }
+}
+
+impl fmt::Debug for VbarEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VbarEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("base_address", &self.base_address())
+            .finish()
+    }
+}
+
+/// The state of the `CPTR_EL2` register.
+///
+/// Controls the trapping to EL2 of accesses to the SVE, SIMD, floating-point, trace, and activity
+/// monitor functionality, when `HCR_EL2.E2H` is clear.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct CptrEL2(u64);
+
+impl CptrEL2 {
+    /// The bits of the [`CptrEL2`] register that are reserved and must be written as one.
+    pub const RES1: u64 = 0x33FF;
+
+    /// Returns the value of the [`CptrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `CPTR_EL2` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `CPTR_EL2` is safe.
+        let val = unsafe { raw::read_cptr_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`CptrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `CPTR_EL2` register and the new configuration of the
+    /// `CPTR_EL2` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `CPTR_EL2` is safe.
+        unsafe { raw::write_cptr_el2(self.0) }
+    }
+
+    /// Returns a [`CptrEL2`] with every field cleared and every reserved bit set to its required
+    /// value.
+    pub const fn new() -> Self {
+        Self(Self::RES1)
+    }
+
+    /// Creates a [`CptrEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`CptrEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns `true` if SVE instructions and register accesses are trapped to EL2 (`TZ`).
+    pub const fn trap_sve(self) -> bool {
+        ((self.0 >> 8) & 0b1) == 0b1
+    }
+
+    /// Sets whether SVE instructions and register accesses are trapped to EL2 (`TZ`).
+    pub const fn set_trap_sve(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 8)) | ((value as u64) << 8))
+    }
+
+    /// Returns `true` if SIMD and floating-point instructions and register accesses are trapped to
+    /// EL2 (`TFP`).
+    pub const fn trap_fp(self) -> bool {
+        ((self.0 >> 10) & 0b1) == 0b1
+    }
+
+    /// Sets whether SIMD and floating-point instructions and register accesses are trapped to EL2
+    /// (`TFP`).
+    pub const fn set_trap_fp(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 10)) | ((value as u64) << 10))
+    }
+
+    /// Returns `true` if trace register accesses are trapped to EL2 (`TTA`).
+    pub const fn trap_trace(self) -> bool {
+        ((self.0 >> 20) & 0b1) == 0b1
+    }
+
+    /// Sets whether trace register accesses are trapped to EL2 (`TTA`).
+    pub const fn set_trap_trace(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 20)) | ((value as u64) << 20))
+    }
+
+    /// Returns `true` if activity monitor register accesses are trapped to EL2 (`TAM`).
+    pub const fn trap_activity_monitors(self) -> bool {
+        ((self.0 >> 30) & 0b1) == 0b1
+    }
+
+    /// Sets whether activity monitor register accesses are trapped to EL2 (`TAM`).
+    pub const fn set_trap_activity_monitors(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 30)) | ((value as u64) << 30))
+    }
+
+    /// Returns `true` if accesses to `CPACR_EL1` at EL1 are trapped to EL2 (`TCPAC`).
+    pub const fn trap_cpacr(self) -> bool {
+        ((self.0 >> 31) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to `CPACR_EL1` at EL1 are trapped to EL2 (`TCPAC`).
+    pub const fn set_trap_cpacr(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 31)) | ((value as u64) << 31))
+    }
+}
+
+impl Default for CptrEL2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for CptrEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CptrEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("trap_sve", &self.trap_sve())
+            .field("trap_fp", &self.trap_fp())
+            .field("trap_trace", &self.trap_trace())
+            .field("trap_activity_monitors", &self.trap_activity_monitors())
+            .field("trap_cpacr", &self.trap_cpacr())
+            .finish()
+    }
+}
+
+/// The state of the `ZCR_EL2` register.
+///
+/// Constrains the SVE vector length at EL2 and, when EL2 is enabled, at EL1 and EL0.
+#[derive(Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct ZcrEL2(u64);
+
+impl ZcrEL2 {
+    /// Returns the value of the [`ZcrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `ZCR_EL2` register, which requires SVE to be implemented
+    /// and not trapped.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `ZCR_EL2` is safe.
+        let val = unsafe { raw::read_zcr_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`ZcrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `ZCR_EL2` register, which requires SVE to be implemented
+    /// and not trapped. Shrinking the vector length discards the upper part of every SVE register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `ZCR_EL2` is safe.
+        unsafe { raw::write_zcr_el2(self.0) }
+    }
+
+    /// Creates a [`ZcrEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`ZcrEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the requested vector length, in bytes (`LEN`).
+    ///
+    /// The effective vector length is the largest supported length that does not exceed this.
+    pub const fn vector_length(self) -> usize {
+        ((self.0 & 0xF) as usize + 1) * 16
+    }
+
+    /// Sets the requested vector length, in bytes (`LEN`).
+    ///
+    /// `bytes` is rounded down to a multiple of 16 and clamped to the architectural range of 16
+    /// to 256 bytes.
+    pub const fn set_vector_length(self, bytes: usize) -> Self {
+        Self((self.0 & !0xF) | (ZcrEL1::from_bits(0).set_vector_length(bytes).to_bits()))
+    }
+}
+
+impl fmt::Debug for ZcrEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZcrEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("vector_length", &self.vector_length())
+            .finish()
+    }
+}
+
+/// The state of the `MAIR_EL2` register.
+///
+/// Holds the eight [`MemoryAttribute`]s selected by the `AttrIndx` field of stage 1 translation
+/// descriptors at EL2.
+#[derive(Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct MairEL2(u64);
+
+impl MairEL2 {
+    /// The number of [`MemoryAttribute`]s held by the [`MairEL2`] register.
+    pub const ATTRIBUTES: u8 = MairEL1::ATTRIBUTES;
+
+    /// Returns the value of the [`MairEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `MAIR_EL2` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `MAIR_EL2` is safe.
+        let val = unsafe { raw::read_mair_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`MairEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `MAIR_EL2` register and the new attributes must be
+    /// compatible with the mappings in use.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `MAIR_EL2` is safe.
+        unsafe { raw::write_mair_el2(self.0) }
+    }
+
+    /// Creates a [`MairEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`MairEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the [`MemoryAttribute`] at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`MairEL2::ATTRIBUTES`].
+    pub const fn attribute(self, index: u8) -> MemoryAttribute {
+        MairEL1::from_bits(self.0).attribute(index)
+    }
+
+    /// Sets the [`MemoryAttribute`] at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`MairEL2::ATTRIBUTES`].
+    pub const fn set_attribute(self, index: u8, attribute: MemoryAttribute) -> Self {
+        Self(
+            MairEL1::from_bits(self.0)
+                .set_attribute(index, attribute)
+                .to_bits(),
+        )
+    }
+}
+
+impl fmt::Debug for MairEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MairEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .finish()
+    }
+}
+
+/// The state of the `MDCR_EL2` register.
+///
+/// Controls the trapping to EL2 of debug, trace, and performance monitor accesses.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct MdcrEL2(u64);
+
+impl MdcrEL2 {
+    /// Returns the value of the [`MdcrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `MDCR_EL2` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `MDCR_EL2` is safe.
+        let val = unsafe { raw::read_mdcr_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`MdcrEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `MDCR_EL2` register and the new configuration of the
+    /// `MDCR_EL2` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `MDCR_EL2` is safe.
+        unsafe { raw::write_mdcr_el2(self.0) }
+    }
+
+    /// Creates a [`MdcrEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`MdcrEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the number of performance monitor event counters accessible at EL1 and EL0 (`HPMN`).
+    pub const fn hyp_pmu_counters(self) -> u8 {
+        (self.0 & 0x1F) as u8
+    }
+
+    /// Sets the number of performance monitor event counters accessible at EL1 and EL0 (`HPMN`).
+    pub const fn set_hyp_pmu_counters(self, value: u8) -> Self {
+        Self((self.0 & !0x1F) | (value as u64 & 0x1F))
+    }
+
+    /// Returns `true` if accesses to `PMCR_EL0` at EL1 or EL0 are trapped to EL2 (`TPMCR`).
+    pub const fn trap_pmcr(self) -> bool {
+        ((self.0 >> 5) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to `PMCR_EL0` at EL1 or EL0 are trapped to EL2 (`TPMCR`).
+    pub const fn set_trap_pmcr(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 5)) | ((value as u64) << 5))
+    }
+
+    /// Returns `true` if accesses to the performance monitor registers at EL1 or EL0 are trapped to
+    /// EL2 (`TPM`).
+    pub const fn trap_pmu(self) -> bool {
+        ((self.0 >> 6) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the performance monitor registers at EL1 or EL0 are trapped to EL2
+    /// (`TPM`).
+    pub const fn set_trap_pmu(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 6)) | ((value as u64) << 6))
+    }
+
+    /// Returns `true` if the performance monitor event counters reserved for EL2 are enabled
+    /// (`HPME`).
+    pub const fn hyp_pmu_enable(self) -> bool {
+        ((self.0 >> 7) & 0b1) == 0b1
+    }
+
+    /// Sets whether the performance monitor event counters reserved for EL2 are enabled (`HPME`).
+    pub const fn set_hyp_pmu_enable(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 7)) | ((value as u64) << 7))
+    }
+
+    /// Returns `true` if debug exceptions are routed to EL2 (`TDE`).
+    pub const fn trap_debug_exceptions(self) -> bool {
+        ((self.0 >> 8) & 0b1) == 0b1
+    }
+
+    /// Sets whether debug exceptions are routed to EL2 (`TDE`).
+    pub const fn set_trap_debug_exceptions(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 8)) | ((value as u64) << 8))
+    }
+
+    /// Returns `true` if accesses to the debug registers at EL1 or EL0 are trapped to EL2 (`TDA`).
+    pub const fn trap_debug_access(self) -> bool {
+        ((self.0 >> 9) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the debug registers at EL1 or EL0 are trapped to EL2 (`TDA`).
+    pub const fn set_trap_debug_access(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 9)) | ((value as u64) << 9))
+    }
+
+    /// Returns `true` if accesses to the OS-related debug registers at EL1 are trapped to EL2
+    /// (`TDOSA`).
+    pub const fn trap_debug_os(self) -> bool {
+        ((self.0 >> 10) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the OS-related debug registers at EL1 are trapped to EL2 (`TDOSA`).
+    pub const fn set_trap_debug_os(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 10)) | ((value as u64) << 10))
+    }
+
+    /// Returns `true` if accesses to the debug ROM registers at EL1 or EL0 are trapped to EL2
+    /// (`TDRA`).
+    pub const fn trap_debug_rom(self) -> bool {
+        ((self.0 >> 11) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the debug ROM registers at EL1 or EL0 are trapped to EL2 (`TDRA`).
+    pub const fn set_trap_debug_rom(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 11)) | ((value as u64) << 11))
+    }
+
+    /// Returns the owning translation regime and access controls of the profiling buffer (`E2PB`).
+    pub const fn profiling_buffer_owner(self) -> u8 {
+        ((self.0 >> 12) & 0b11) as u8
+    }
+
+    /// Sets the owning translation regime and access controls of the profiling buffer (`E2PB`).
+    pub const fn set_profiling_buffer_owner(self, value: u8) -> Self {
+        Self((self.0 & !(0b11 << 12)) | ((value as u64 & 0b11) << 12))
+    }
+
+    /// Returns `true` if accesses to the statistical profiling registers at EL1 are trapped to EL2
+    /// (`TPMS`).
+    pub const fn trap_profiling(self) -> bool {
+        ((self.0 >> 14) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the statistical profiling registers at EL1 are trapped to EL2
+    /// (`TPMS`).
+    pub const fn set_trap_profiling(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 14)) | ((value as u64) << 14))
+    }
+
+    /// Returns `true` if event counting by the performance monitors is prohibited at EL2 (`HPMD`).
+    pub const fn hyp_pmu_disable(self) -> bool {
+        ((self.0 >> 17) & 0b1) == 0b1
+    }
+
+    /// Sets whether event counting by the performance monitors is prohibited at EL2 (`HPMD`).
+    pub const fn set_hyp_pmu_disable(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 17)) | ((value as u64) << 17))
+    }
+
+    /// Returns `true` if accesses to the trace filter control registers at EL1 are trapped to EL2
+    /// (`TTRF`).
+    pub const fn trap_trace_filter(self) -> bool {
+        ((self.0 >> 19) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the trace filter control registers at EL1 are trapped to EL2
+    /// (`TTRF`).
+    pub const fn set_trap_trace_filter(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 19)) | ((value as u64) << 19))
+    }
+
+    /// Returns `true` if the cycle counter is prohibited from counting at EL2 (`HCCD`).
+    pub const fn hyp_cycle_counter_disable(self) -> bool {
+        ((self.0 >> 23) & 0b1) == 0b1
+    }
+
+    /// Sets whether the cycle counter is prohibited from counting at EL2 (`HCCD`).
+    pub const fn set_hyp_cycle_counter_disable(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 23)) | ((value as u64) << 23))
+    }
+
+    /// Returns `true` if accesses to the debug communication channel registers at EL1 or EL0 are
+    /// trapped to EL2 (`TDCC`).
+    pub const fn trap_debug_comms(self) -> bool {
+        ((self.0 >> 27) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the debug communication channel registers at EL1 or EL0 are trapped
+    /// to EL2 (`TDCC`).
+    pub const fn set_trap_debug_comms(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 27)) | ((value as u64) << 27))
+    }
+}
+
+impl fmt::Debug for MdcrEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MdcrEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("hyp_pmu_counters", &self.hyp_pmu_counters())
+            .field("trap_pmcr", &self.trap_pmcr())
+            .field("trap_pmu", &self.trap_pmu())
+            .field("hyp_pmu_enable", &self.hyp_pmu_enable())
+            .field("trap_debug_exceptions", &self.trap_debug_exceptions())
+            .field("trap_debug_access", &self.trap_debug_access())
+            .field("trap_debug_os", &self.trap_debug_os())
+            .field("trap_debug_rom", &self.trap_debug_rom())
+            .field("profiling_buffer_owner", &self.profiling_buffer_owner())
+            .field("trap_profiling", &self.trap_profiling())
+            .field("hyp_pmu_disable", &self.hyp_pmu_disable())
+            .field("trap_trace_filter", &self.trap_trace_filter())
+            .field(
+                "hyp_cycle_counter_disable",
+                &self.hyp_cycle_counter_disable(),
+            )
+            .field("trap_debug_comms", &self.trap_debug_comms())
+            .finish()
+    }
+}
+
+/// The state of the `CNTHCTL_EL2` register.
+///
+/// Controls the generation of an event stream from the physical counter and access from EL1 to the
+/// physical counter and timer, when `HCR_EL2.E2H` is clear.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct CnthctlEL2(u64);
+
+impl CnthctlEL2 {
+    /// Returns the value of the [`CnthctlEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `CNTHCTL_EL2` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `CNTHCTL_EL2` is safe.
+        let val = unsafe { raw::read_cnthctl_el2() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`CnthctlEL2`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `CNTHCTL_EL2` register and the new configuration of the
+    /// `CNTHCTL_EL2` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `CNTHCTL_EL2` is safe.
+        unsafe { raw::write_cnthctl_el2(self.0) }
+    }
+
+    /// Creates a [`CnthctlEL2`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`CnthctlEL2`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns `true` if accesses to the physical counter at EL1 and EL0 are not trapped to EL2
+    /// (`EL1PCTEN`).
+    pub const fn el1_physical_counter_access(self) -> bool {
+        (self.0 & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the physical counter at EL1 and EL0 are not trapped to EL2
+    /// (`EL1PCTEN`).
+    pub const fn set_el1_physical_counter_access(self, value: bool) -> Self {
+        Self((self.0 & !0b1) | (value as u64))
+    }
+
+    /// Returns `true` if accesses to the physical timer at EL1 and EL0 are not trapped to EL2
+    /// (`EL1PCEN`).
+    pub const fn el1_physical_timer_access(self) -> bool {
+        ((self.0 >> 1) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the physical timer at EL1 and EL0 are not trapped to EL2
+    /// (`EL1PCEN`).
+    pub const fn set_el1_physical_timer_access(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 1)) | ((value as u64) << 1))
+    }
+
+    /// Returns `true` if an event stream is generated from the physical counter (`EVNTEN`).
+    pub const fn event_stream_enable(self) -> bool {
+        ((self.0 >> 2) & 0b1) == 0b1
+    }
+
+    /// Sets whether an event stream is generated from the physical counter (`EVNTEN`).
+    pub const fn set_event_stream_enable(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 2)) | ((value as u64) << 2))
+    }
+
+    /// Returns `true` if the event stream is generated on a 1 to 0 transition of the trigger bit
+    /// (`EVNTDIR`).
+    pub const fn event_stream_direction(self) -> bool {
+        ((self.0 >> 3) & 0b1) == 0b1
+    }
+
+    /// Sets whether the event stream is generated on a 1 to 0 transition of the trigger bit
+    /// (`EVNTDIR`).
+    pub const fn set_event_stream_direction(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 3)) | ((value as u64) << 3))
+    }
+
+    /// Returns the bit of the physical counter that triggers the event stream (`EVNTI`).
+    pub const fn event_stream_trigger(self) -> u8 {
+        ((self.0 >> 4) & 0xF) as u8
+    }
+
+    /// Sets the bit of the physical counter that triggers the event stream (`EVNTI`).
+    pub const fn set_event_stream_trigger(self, value: u8) -> Self {
+        Self((self.0 & !(0xF << 4)) | ((value as u64 & 0xF) << 4))
+    }
+
+    /// Returns `true` if enhanced counter virtualization is enabled (`ECV`).
+    pub const fn enhanced_counter_virtualization(self) -> bool {
+        ((self.0 >> 12) & 0b1) == 0b1
+    }
+
+    /// Sets whether enhanced counter virtualization is enabled (`ECV`).
+    pub const fn set_enhanced_counter_virtualization(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 12)) | ((value as u64) << 12))
+    }
+
+    /// Returns `true` if accesses to the virtual timer at EL1 and EL0 are trapped to EL2
+    /// (`EL1TVT`).
+    pub const fn el1_trap_virtual_timer(self) -> bool {
+        ((self.0 >> 13) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the virtual timer at EL1 and EL0 are trapped to EL2 (`EL1TVT`).
+    pub const fn set_el1_trap_virtual_timer(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 13)) | ((value as u64) << 13))
+    }
+
+    /// Returns `true` if accesses to the virtual counter at EL1 and EL0 are trapped to EL2
+    /// (`EL1TVCT`).
+    pub const fn el1_trap_virtual_counter(self) -> bool {
+        ((self.0 >> 14) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the virtual counter at EL1 and EL0 are trapped to EL2 (`EL1TVCT`).
+    pub const fn set_el1_trap_virtual_counter(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 14)) | ((value as u64) << 14))
+    }
+
+    /// Returns `true` if accesses to the physical counter at EL1 are trapped to EL2 when
+    /// `HCR_EL2.NV` is set (`EL1NVPCT`).
+    pub const fn el1_trap_non_virtual_physical_counter(self) -> bool {
+        ((self.0 >> 15) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the physical counter at EL1 are trapped to EL2 when `HCR_EL2.NV` is
+    /// set (`EL1NVPCT`).
+    pub const fn set_el1_trap_non_virtual_physical_counter(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 15)) | ((value as u64) << 15))
+    }
+
+    /// Returns `true` if accesses to the virtual counter at EL1 are trapped to EL2 when
+    /// `HCR_EL2.NV` is set (`EL1NVVCT`).
+    pub const fn el1_trap_non_virtual_virtual_counter(self) -> bool {
+        ((self.0 >> 16) & 0b1) == 0b1
+    }
+
+    /// Sets whether accesses to the virtual counter at EL1 are trapped to EL2 when `HCR_EL2.NV` is
+    /// set (`EL1NVVCT`).
+    pub const fn set_el1_trap_non_virtual_virtual_counter(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 16)) | ((value as u64) << 16))
+    }
+
+    /// Returns `true` if the event stream trigger bit is selected from bits 8 to 23 of the counter
+    /// (`EVNTIS`).
+    pub const fn event_stream_scale(self) -> bool {
+        ((self.0 >> 17) & 0b1) == 0b1
+    }
+
+    /// Sets whether the event stream trigger bit is selected from bits 8 to 23 of the counter
+    /// (`EVNTIS`).
+    pub const fn set_event_stream_scale(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 17)) | ((value as u64) << 17))
+    }
+}
+
+impl fmt::Debug for CnthctlEL2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CnthctlEL2")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field(
+                "el1_physical_counter_access",
+                &self.el1_physical_counter_access(),
+            )
+            .field(
+                "el1_physical_timer_access",
+                &self.el1_physical_timer_access(),
+            )
+            .field("event_stream_enable", &self.event_stream_enable())
+            .field("event_stream_direction", &self.event_stream_direction())
+            .field("event_stream_trigger", &self.event_stream_trigger())
+            .field(
+                "enhanced_counter_virtualization",
+                &self.enhanced_counter_virtualization(),
+            )
+            .field("el1_trap_virtual_timer", &self.el1_trap_virtual_timer())
+            .field("el1_trap_virtual_counter", &self.el1_trap_virtual_counter())
+            .field(
+                "el1_trap_non_virtual_physical_counter",
+                &self.el1_trap_non_virtual_physical_counter(),
+            )
+            .field(
+                "el1_trap_non_virtual_virtual_counter",
+                &self.el1_trap_non_virtual_virtual_counter(),
+            )
+            .field("event_stream_scale", &self.event_stream_scale())
+            .finish()
+    }
+}
@@ -0,0 +1,813 @@
+//! Typed wrappers around the `ID_AA64PFR*_EL1`, `ID_AA64ISAR*_EL1`, and `ID_AA64DFR0_EL1`
+//! feature identification registers.
+
+use core::fmt;
+
+#[cfg(target_arch = "aarch64")]
+use crate::msr::raw;
+
+/// The execution states supported at an exception level.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ExecutionStateSupport {
+    /// The exception level is not implemented.
+    NotImplemented,
+    /// The exception level can only execute in AArch64 state.
+    Aarch64,
+    /// The exception level can execute in either AArch64 or AArch32 state.
+    Aarch64AndAarch32,
+}
+
+impl ExecutionStateSupport {
+    /// Decodes the [`ExecutionStateSupport`] from an `ID_AA64PFR0_EL1.ELx` field.
+    const fn from_bits(bits: u8) -> Self {
+        match bits {
+            0b0000 => Self::NotImplemented,
+            0b0001 => Self::Aarch64,
+            _ => Self::Aarch64AndAarch32,
+        }
+    }
+}
+
+/// The state of the `ID_AA64PFR0_EL1` register.
+///
+/// Describes the execution states, exception levels, and major optional features implemented by the
+/// processor.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Aarch64ProcessorFeatureRegister0EL1(u64);
+
+impl Aarch64ProcessorFeatureRegister0EL1 {
+    /// Returns the value of the [`Aarch64ProcessorFeatureRegister0EL1`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `ID_AA64PFR0_EL1` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `ID_AA64PFR0_EL1` is safe.
+        let val = unsafe { raw::read_id_aa64pfr0_el1() };
+        Self(val)
+    }
+
+    /// Creates a [`Aarch64ProcessorFeatureRegister0EL1`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`Aarch64ProcessorFeatureRegister0EL1`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the execution states supported at EL0 (`EL0`).
+    pub const fn el0(self) -> ExecutionStateSupport {
+        ExecutionStateSupport::from_bits((self.0 & 0xF) as u8)
+    }
+
+    /// Returns the execution states supported at EL1 (`EL1`).
+    pub const fn el1(self) -> ExecutionStateSupport {
+        ExecutionStateSupport::from_bits(((self.0 >> 4) & 0xF) as u8)
+    }
+
+    /// Returns the execution states supported at EL2 (`EL2`).
+    pub const fn el2(self) -> ExecutionStateSupport {
+        ExecutionStateSupport::from_bits(((self.0 >> 8) & 0xF) as u8)
+    }
+
+    /// Returns the execution states supported at EL3 (`EL3`).
+    pub const fn el3(self) -> ExecutionStateSupport {
+        ExecutionStateSupport::from_bits(((self.0 >> 12) & 0xF) as u8)
+    }
+
+    /// Returns `true` if floating-point is implemented (`FP`).
+    pub const fn fp(self) -> bool {
+        ((self.0 >> 16) & 0xF) != 0xF
+    }
+
+    /// Returns `true` if half-precision floating-point arithmetic is implemented (`FP`).
+    pub const fn fp16(self) -> bool {
+        ((self.0 >> 16) & 0xF) == 0b0001
+    }
+
+    /// Returns `true` if Advanced SIMD is implemented (`AdvSIMD`).
+    pub const fn advanced_simd(self) -> bool {
+        ((self.0 >> 20) & 0xF) != 0xF
+    }
+
+    /// Returns `true` if half-precision Advanced SIMD arithmetic is implemented (`AdvSIMD`).
+    pub const fn advanced_simd_fp16(self) -> bool {
+        ((self.0 >> 20) & 0xF) == 0b0001
+    }
+
+    /// Returns `true` if the GIC CPU interface system registers are implemented (`GIC`).
+    pub const fn gic_system_registers(self) -> bool {
+        ((self.0 >> 24) & 0xF) != 0
+    }
+
+    /// Returns the version of the RAS extension that is implemented, or zero (`RAS`).
+    pub const fn ras(self) -> u8 {
+        ((self.0 >> 28) & 0xF) as u8
+    }
+
+    /// Returns `true` if the Scalable Vector Extension is implemented (`SVE`).
+    pub const fn sve(self) -> bool {
+        ((self.0 >> 32) & 0xF) != 0
+    }
+
+    /// Returns `true` if Secure EL2 is implemented (`SEL2`).
+    pub const fn secure_el2(self) -> bool {
+        ((self.0 >> 36) & 0xF) != 0
+    }
+
+    /// Returns the major version of the Memory Partitioning and Monitoring extension, or zero
+    /// (`MPAM`).
+    pub const fn mpam(self) -> u8 {
+        ((self.0 >> 40) & 0xF) as u8
+    }
+
+    /// Returns the version of the Activity Monitors extension that is implemented, or zero (`AMU`).
+    pub const fn amu(self) -> u8 {
+        ((self.0 >> 44) & 0xF) as u8
+    }
+
+    /// Returns `true` if data independent timing is implemented (`DIT`).
+    pub const fn dit(self) -> bool {
+        ((self.0 >> 48) & 0xF) != 0
+    }
+
+    /// Returns the version of the Realm Management Extension that is implemented, or zero (`RME`).
+    pub const fn rme(self) -> u8 {
+        ((self.0 >> 52) & 0xF) as u8
+    }
+
+    /// Returns the level of protection against speculative use of branch targets from other
+    /// contexts (`CSV2`).
+    pub const fn csv2(self) -> u8 {
+        ((self.0 >> 56) & 0xF) as u8
+    }
+
+    /// Returns `true` if data loaded under speculation with a permission or domain fault cannot be
+    /// used to form an address or generate a condition code (`CSV3`).
+    pub const fn csv3(self) -> bool {
+        ((self.0 >> 60) & 0xF) != 0
+    }
+}
+
+impl fmt::Debug for Aarch64ProcessorFeatureRegister0EL1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Aarch64ProcessorFeatureRegister0EL1")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("el0", &self.el0())
+            .field("el1", &self.el1())
+            .field("el2", &self.el2())
+            .field("el3", &self.el3())
+            .field("fp", &self.fp())
+            .field("fp16", &self.fp16())
+            .field("advanced_simd", &self.advanced_simd())
+            .field("advanced_simd_fp16", &self.advanced_simd_fp16())
+            .field("gic_system_registers", &self.gic_system_registers())
+            .field("ras", &self.ras())
+            .field("sve", &self.sve())
+            .field("secure_el2", &self.secure_el2())
+            .field("mpam", &self.mpam())
+            .field("amu", &self.amu())
+            .field("dit", &self.dit())
+            .field("rme", &self.rme())
+            .field("csv2", &self.csv2())
+            .field("csv3", &self.csv3())
+            .finish()
+    }
+}
+
+/// The state of the `ID_AA64PFR1_EL1` register.
+///
+/// Describes additional optional features implemented by the processor.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Aarch64ProcessorFeatureRegister1EL1(u64);
+
+impl Aarch64ProcessorFeatureRegister1EL1 {
+    /// Returns the value of the [`Aarch64ProcessorFeatureRegister1EL1`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `ID_AA64PFR1_EL1` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `ID_AA64PFR1_EL1` is safe.
+        let val = unsafe { raw::read_id_aa64pfr1_el1() };
+        Self(val)
+    }
+
+    /// Creates a [`Aarch64ProcessorFeatureRegister1EL1`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`Aarch64ProcessorFeatureRegister1EL1`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns `true` if branch target identification is implemented (`BT`).
+    pub const fn branch_target_identification(self) -> bool {
+        (self.0 & 0xF) != 0
+    }
+
+    /// Returns the level of support for the speculative store bypass safe mechanism (`SSBS`).
+    pub const fn ssbs(self) -> u8 {
+        ((self.0 >> 4) & 0xF) as u8
+    }
+
+    /// Returns the level of support for the Memory Tagging Extension, or zero (`MTE`).
+    pub const fn mte(self) -> u8 {
+        ((self.0 >> 8) & 0xF) as u8
+    }
+
+    /// Returns the minor version of the RAS extension (`RAS_frac`).
+    pub const fn ras_fraction(self) -> u8 {
+        ((self.0 >> 12) & 0xF) as u8
+    }
+
+    /// Returns the minor version of the Memory Partitioning and Monitoring extension (`MPAM_frac`).
+    pub const fn mpam_fraction(self) -> u8 {
+        ((self.0 >> 16) & 0xF) as u8
+    }
+
+    /// Returns the version of the Scalable Matrix Extension that is implemented, or zero (`SME`).
+    pub const fn sme(self) -> u8 {
+        ((self.0 >> 24) & 0xF) as u8
+    }
+
+    /// Returns `true` if reads of the random number registers can be trapped to EL3 (`RNDR_trap`).
+    pub const fn rndr_trap(self) -> bool {
+        ((self.0 >> 28) & 0xF) != 0
+    }
+
+    /// Returns the minor level of protection against speculative use of branch targets
+    /// (`CSV2_frac`).
+    pub const fn csv2_fraction(self) -> u8 {
+        ((self.0 >> 32) & 0xF) as u8
+    }
+
+    /// Returns `true` if non-maskable interrupts are implemented (`NMI`).
+    pub const fn nmi(self) -> bool {
+        ((self.0 >> 36) & 0xF) != 0
+    }
+}
+
+impl fmt::Debug for Aarch64ProcessorFeatureRegister1EL1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Aarch64ProcessorFeatureRegister1EL1")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field(
+                "branch_target_identification",
+                &self.branch_target_identification(),
+            )
+            .field("ssbs", &self.ssbs())
+            .field("mte", &self.mte())
+            .field("ras_fraction", &self.ras_fraction())
+            .field("mpam_fraction", &self.mpam_fraction())
+            .field("sme", &self.sme())
+            .field("rndr_trap", &self.rndr_trap())
+            .field("csv2_fraction", &self.csv2_fraction())
+            .field("nmi", &self.nmi())
+            .finish()
+    }
+}
+
+/// The state of the `ID_AA64ISAR0_EL1` register.
+///
+/// Describes the cryptographic, atomic, and arithmetic instructions implemented by the processor.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Aarch64InstructionSetAttributeRegister0EL1(u64);
+
+impl Aarch64InstructionSetAttributeRegister0EL1 {
+    /// Returns the value of the [`Aarch64InstructionSetAttributeRegister0EL1`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `ID_AA64ISAR0_EL1` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `ID_AA64ISAR0_EL1` is safe.
+        let val = unsafe { raw::read_id_aa64isar0_el1() };
+        Self(val)
+    }
+
+    /// Creates a [`Aarch64InstructionSetAttributeRegister0EL1`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`Aarch64InstructionSetAttributeRegister0EL1`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns `true` if the AES instructions are implemented (`AES`).
+    pub const fn aes(self) -> bool {
+        ((self.0 >> 4) & 0xF) != 0
+    }
+
+    /// Returns `true` if the 64-bit polynomial multiply instructions are implemented (`AES`).
+    pub const fn pmull(self) -> bool {
+        ((self.0 >> 4) & 0xF) >= 0b0010
+    }
+
+    /// Returns `true` if the SHA1 instructions are implemented (`SHA1`).
+    pub const fn sha1(self) -> bool {
+        ((self.0 >> 8) & 0xF) != 0
+    }
+
+    /// Returns `true` if the SHA256 instructions are implemented (`SHA2`).
+    pub const fn sha256(self) -> bool {
+        ((self.0 >> 12) & 0xF) != 0
+    }
+
+    /// Returns `true` if the SHA512 instructions are implemented (`SHA2`).
+    pub const fn sha512(self) -> bool {
+        ((self.0 >> 12) & 0xF) >= 0b0010
+    }
+
+    /// Returns `true` if the CRC32 instructions are implemented (`CRC32`).
+    pub const fn crc32(self) -> bool {
+        ((self.0 >> 16) & 0xF) != 0
+    }
+
+    /// Returns `true` if the Large System Extension atomic instructions are implemented
+    /// (`Atomic`).
+    pub const fn atomics(self) -> bool {
+        ((self.0 >> 20) & 0xF) >= 0b0010
+    }
+
+    /// Returns `true` if the transactional memory extension instructions are implemented (`TME`).
+    pub const fn transactional_memory(self) -> bool {
+        ((self.0 >> 24) & 0xF) != 0
+    }
+
+    /// Returns `true` if the rounding double multiply accumulate instructions are implemented
+    /// (`RDM`).
+    pub const fn rdm(self) -> bool {
+        ((self.0 >> 28) & 0xF) != 0
+    }
+
+    /// Returns `true` if the SHA3 instructions are implemented (`SHA3`).
+    pub const fn sha3(self) -> bool {
+        ((self.0 >> 32) & 0xF) != 0
+    }
+
+    /// Returns `true` if the SM3 instructions are implemented (`SM3`).
+    pub const fn sm3(self) -> bool {
+        ((self.0 >> 36) & 0xF) != 0
+    }
+
+    /// Returns `true` if the SM4 instructions are implemented (`SM4`).
+    pub const fn sm4(self) -> bool {
+        ((self.0 >> 40) & 0xF) != 0
+    }
+
+    /// Returns `true` if the dot product instructions are implemented (`DP`).
+    pub const fn dot_product(self) -> bool {
+        ((self.0 >> 44) & 0xF) != 0
+    }
+
+    /// Returns `true` if the half-precision floating-point multiply long instructions are
+    /// implemented (`FHM`).
+    pub const fn fhm(self) -> bool {
+        ((self.0 >> 48) & 0xF) != 0
+    }
+
+    /// Returns the level of support for the flag manipulation instructions (`TS`).
+    pub const fn flag_manipulation(self) -> u8 {
+        ((self.0 >> 52) & 0xF) as u8
+    }
+
+    /// Returns the level of support for outer shareable and TLB range maintenance instructions
+    /// (`TLB`).
+    pub const fn tlb_maintenance(self) -> u8 {
+        ((self.0 >> 56) & 0xF) as u8
+    }
+
+    /// Returns `true` if the random number registers are implemented (`RNDR`).
+    pub const fn rndr(self) -> bool {
+        ((self.0 >> 60) & 0xF) != 0
+    }
+}
+
+impl fmt::Debug for Aarch64InstructionSetAttributeRegister0EL1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Aarch64InstructionSetAttributeRegister0EL1")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("aes", &self.aes())
+            .field("pmull", &self.pmull())
+            .field("sha1", &self.sha1())
+            .field("sha256", &self.sha256())
+            .field("sha512", &self.sha512())
+            .field("crc32", &self.crc32())
+            .field("atomics", &self.atomics())
+            .field("transactional_memory", &self.transactional_memory())
+            .field("rdm", &self.rdm())
+            .field("sha3", &self.sha3())
+            .field("sm3", &self.sm3())
+            .field("sm4", &self.sm4())
+            .field("dot_product", &self.dot_product())
+            .field("fhm", &self.fhm())
+            .field("flag_manipulation", &self.flag_manipulation())
+            .field("tlb_maintenance", &self.tlb_maintenance())
+            .field("rndr", &self.rndr())
+            .finish()
+    }
+}
+
+/// The state of the `ID_AA64ISAR1_EL1` register.
+///
+/// Describes the pointer authentication, memory ordering, and miscellaneous instructions
+/// implemented by the processor.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Aarch64InstructionSetAttributeRegister1EL1(u64);
+
+impl Aarch64InstructionSetAttributeRegister1EL1 {
+    /// Returns the value of the [`Aarch64InstructionSetAttributeRegister1EL1`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `ID_AA64ISAR1_EL1` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `ID_AA64ISAR1_EL1` is safe.
+        let val = unsafe { raw::read_id_aa64isar1_el1() };
+        Self(val)
+    }
+
+    /// Creates a [`Aarch64InstructionSetAttributeRegister1EL1`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`Aarch64InstructionSetAttributeRegister1EL1`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the level of support for the data cache clean to the point of persistence
+    /// instructions (`DPB`).
+    pub const fn data_persistence(self) -> u8 {
+        (self.0 & 0xF) as u8
+    }
+
+    /// Returns the level of support for address authentication using the QARMA5 algorithm (`APA`).
+    pub const fn pointer_authentication_qarma5(self) -> u8 {
+        ((self.0 >> 4) & 0xF) as u8
+    }
+
+    /// Returns the level of support for address authentication using an implementation defined
+    /// algorithm (`API`).
+    pub const fn pointer_authentication_implementation_defined(self) -> u8 {
+        ((self.0 >> 8) & 0xF) as u8
+    }
+
+    /// Returns `true` if the JavaScript conversion instruction is implemented (`JSCVT`).
+    pub const fn javascript_conversion(self) -> bool {
+        ((self.0 >> 12) & 0xF) != 0
+    }
+
+    /// Returns `true` if the floating-point complex number instructions are implemented (`FCMA`).
+    pub const fn complex_numbers(self) -> bool {
+        ((self.0 >> 16) & 0xF) != 0
+    }
+
+    /// Returns the level of support for the load-acquire RCpc instructions (`LRCPC`).
+    pub const fn rcpc(self) -> u8 {
+        ((self.0 >> 20) & 0xF) as u8
+    }
+
+    /// Returns `true` if generic authentication using the QARMA5 algorithm is implemented (`GPA`).
+    pub const fn generic_authentication_qarma5(self) -> bool {
+        ((self.0 >> 24) & 0xF) != 0
+    }
+
+    /// Returns `true` if generic authentication using an implementation defined algorithm is
+    /// implemented (`GPI`).
+    pub const fn generic_authentication_implementation_defined(self) -> bool {
+        ((self.0 >> 28) & 0xF) != 0
+    }
+
+    /// Returns `true` if the floating-point round to integer instructions are implemented
+    /// (`FRINTTS`).
+    pub const fn frint(self) -> bool {
+        ((self.0 >> 32) & 0xF) != 0
+    }
+
+    /// Returns `true` if the speculation barrier instruction is implemented (`SB`).
+    pub const fn speculation_barrier(self) -> bool {
+        ((self.0 >> 36) & 0xF) != 0
+    }
+
+    /// Returns `true` if the prediction restriction instructions are implemented (`SPECRES`).
+    pub const fn prediction_restriction(self) -> bool {
+        ((self.0 >> 40) & 0xF) != 0
+    }
+
+    /// Returns the level of support for the BFloat16 instructions (`BF16`).
+    pub const fn bf16(self) -> u8 {
+        ((self.0 >> 44) & 0xF) as u8
+    }
+
+    /// Returns `true` if the data gathering hint instruction is implemented (`DGH`).
+    pub const fn data_gathering_hint(self) -> bool {
+        ((self.0 >> 48) & 0xF) != 0
+    }
+
+    /// Returns `true` if the Int8 matrix multiply instructions are implemented (`I8MM`).
+    pub const fn int8_matrix_multiply(self) -> bool {
+        ((self.0 >> 52) & 0xF) != 0
+    }
+
+    /// Returns `true` if the XS attribute and the TLBI and DSB instructions with the nXS qualifier
+    /// are implemented (`XS`).
+    pub const fn xs(self) -> bool {
+        ((self.0 >> 56) & 0xF) != 0
+    }
+
+    /// Returns the level of support for the 64-byte load and store instructions (`LS64`).
+    pub const fn ls64(self) -> u8 {
+        ((self.0 >> 60) & 0xF) as u8
+    }
+}
+
+impl fmt::Debug for Aarch64InstructionSetAttributeRegister1EL1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Aarch64InstructionSetAttributeRegister1EL1")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("data_persistence", &self.data_persistence())
+            .field(
+                "pointer_authentication_qarma5",
+                &self.pointer_authentication_qarma5(),
+            )
+            .field(
+                "pointer_authentication_implementation_defined",
+                &self.pointer_authentication_implementation_defined(),
+            )
+            .field("javascript_conversion", &self.javascript_conversion())
+            .field("complex_numbers", &self.complex_numbers())
+            .field("rcpc", &self.rcpc())
+            .field(
+                "generic_authentication_qarma5",
+                &self.generic_authentication_qarma5(),
+            )
+            .field(
+                "generic_authentication_implementation_defined",
+                &self.generic_authentication_implementation_defined(),
+            )
+            .field("frint", &self.frint())
+            .field("speculation_barrier", &self.speculation_barrier())
+            .field("prediction_restriction", &self.prediction_restriction())
+            .field("bf16", &self.bf16())
+            .field("data_gathering_hint", &self.data_gathering_hint())
+            .field("int8_matrix_multiply", &self.int8_matrix_multiply())
+            .field("xs", &self.xs())
+            .field("ls64", &self.ls64())
+            .finish()
+    }
+}
+
+/// The state of the `ID_AA64ISAR2_EL1` register.
+///
+/// Describes additional instructions implemented by the processor.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Aarch64InstructionSetAttributeRegister2EL1(u64);
+
+impl Aarch64InstructionSetAttributeRegister2EL1 {
+    /// Returns the value of the [`Aarch64InstructionSetAttributeRegister2EL1`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `ID_AA64ISAR2_EL1` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `ID_AA64ISAR2_EL1` is safe.
+        let val = unsafe { raw::read_id_aa64isar2_el1() };
+        Self(val)
+    }
+
+    /// Creates a [`Aarch64InstructionSetAttributeRegister2EL1`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`Aarch64InstructionSetAttributeRegister2EL1`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns `true` if the WFET and WFIT instructions are implemented (`WFxT`).
+    pub const fn wait_with_timeout(self) -> bool {
+        (self.0 & 0xF) != 0
+    }
+
+    /// Returns `true` if the reciprocal estimate instructions produce results with increased
+    /// precision (`RPRES`).
+    pub const fn reciprocal_precision(self) -> bool {
+        ((self.0 >> 4) & 0xF) != 0
+    }
+
+    /// Returns `true` if generic authentication using the QARMA3 algorithm is implemented (`GPA3`).
+    pub const fn generic_authentication_qarma3(self) -> bool {
+        ((self.0 >> 8) & 0xF) != 0
+    }
+
+    /// Returns the level of support for address authentication using the QARMA3 algorithm (`APA3`).
+    pub const fn pointer_authentication_qarma3(self) -> u8 {
+        ((self.0 >> 12) & 0xF) as u8
+    }
+
+    /// Returns `true` if the memory copy and memory set instructions are implemented (`MOPS`).
+    pub const fn memory_operations(self) -> bool {
+        ((self.0 >> 16) & 0xF) != 0
+    }
+
+    /// Returns `true` if the BC.cond instruction is implemented (`BC`).
+    pub const fn branch_consistent(self) -> bool {
+        ((self.0 >> 20) & 0xF) != 0
+    }
+
+    /// Returns the level of support for the ConstPACField() pointer authentication behavior
+    /// (`PAC_frac`).
+    pub const fn pointer_authentication_fraction(self) -> u8 {
+        ((self.0 >> 24) & 0xF) as u8
+    }
+
+    /// Returns `true` if the clear branch history instruction is implemented (`CLRBHB`).
+    pub const fn clear_branch_history(self) -> bool {
+        ((self.0 >> 28) & 0xF) != 0
+    }
+}
+
+impl fmt::Debug for Aarch64InstructionSetAttributeRegister2EL1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Aarch64InstructionSetAttributeRegister2EL1")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("wait_with_timeout", &self.wait_with_timeout())
+            .field("reciprocal_precision", &self.reciprocal_precision())
+            .field(
+                "generic_authentication_qarma3",
+                &self.generic_authentication_qarma3(),
+            )
+            .field(
+                "pointer_authentication_qarma3",
+                &self.pointer_authentication_qarma3(),
+            )
+            .field("memory_operations", &self.memory_operations())
+            .field("branch_consistent", &self.branch_consistent())
+            .field(
+                "pointer_authentication_fraction",
+                &self.pointer_authentication_fraction(),
+            )
+            .field("clear_branch_history", &self.clear_branch_history())
+            .finish()
+    }
+}
+
+/// The state of the `ID_AA64DFR0_EL1` register.
+///
+/// Describes the debug, trace, and performance monitoring features implemented by the processor.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Aarch64DebugFeatureRegister0EL1(u64);
+
+impl Aarch64DebugFeatureRegister0EL1 {
+    /// Returns the value of the [`Aarch64DebugFeatureRegister0EL1`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `ID_AA64DFR0_EL1` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `ID_AA64DFR0_EL1` is safe.
+        let val = unsafe { raw::read_id_aa64dfr0_el1() };
+        Self(val)
+    }
+
+    /// Creates a [`Aarch64DebugFeatureRegister0EL1`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`Aarch64DebugFeatureRegister0EL1`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the version of the debug architecture (`DebugVer`).
+    pub const fn debug_version(self) -> u8 {
+        (self.0 & 0xF) as u8
+    }
+
+    /// Returns the version of the trace system registers interface, or zero (`TraceVer`).
+    pub const fn trace_version(self) -> u8 {
+        ((self.0 >> 4) & 0xF) as u8
+    }
+
+    /// Returns the version of the performance monitors extension, or [`None`] if the performance
+    /// monitors are not implemented or implementation defined (`PMUVer`).
+    pub const fn pmu_version(self) -> Option<u8> {
+        match ((self.0 >> 8) & 0xF) as u8 {
+            0b0000 | 0b1111 => None,
+            version => Some(version),
+        }
+    }
+
+    /// Returns the number of hardware breakpoints (`BRPs`).
+    pub const fn breakpoints(self) -> u8 {
+        ((self.0 >> 12) & 0xF) as u8 + 1
+    }
+
+    /// Returns the level of support for the PMU snapshot extension (`PMSS`).
+    pub const fn pmu_snapshot(self) -> u8 {
+        ((self.0 >> 16) & 0xF) as u8
+    }
+
+    /// Returns the number of hardware watchpoints (`WRPs`).
+    pub const fn watchpoints(self) -> u8 {
+        ((self.0 >> 20) & 0xF) as u8 + 1
+    }
+
+    /// Returns the number of context-aware breakpoints (`CTX_CMPs`).
+    pub const fn context_aware_breakpoints(self) -> u8 {
+        ((self.0 >> 28) & 0xF) as u8 + 1
+    }
+
+    /// Returns the version of the statistical profiling extension, or zero (`PMSVer`).
+    pub const fn statistical_profiling_version(self) -> u8 {
+        ((self.0 >> 32) & 0xF) as u8
+    }
+
+    /// Returns `true` if the OS double lock is implemented (`DoubleLock`).
+    pub const fn double_lock(self) -> bool {
+        ((self.0 >> 36) & 0xF) == 0b0000
+    }
+
+    /// Returns `true` if the self-hosted trace filter extension is implemented (`TraceFilt`).
+    pub const fn trace_filter(self) -> bool {
+        ((self.0 >> 40) & 0xF) != 0
+    }
+
+    /// Returns `true` if the trace buffer extension is implemented (`TraceBuffer`).
+    pub const fn trace_buffer(self) -> bool {
+        ((self.0 >> 44) & 0xF) != 0
+    }
+
+    /// Returns `true` if the multi-threaded PMU extension is implemented (`MTPMU`).
+    pub const fn multithreaded_pmu(self) -> bool {
+        ((self.0 >> 48) & 0xF) != 0
+    }
+
+    /// Returns `true` if the branch record buffer extension is implemented (`BRBE`).
+    pub const fn branch_record_buffer(self) -> bool {
+        ((self.0 >> 52) & 0xF) != 0
+    }
+
+    /// Returns `true` if EL2 can reserve every performance monitor event counter for itself
+    /// (`HPMN0`).
+    pub const fn zero_pmu_counters(self) -> bool {
+        ((self.0 >> 60) & 0xF) != 0
+    }
+}
+
+impl fmt::Debug for Aarch64DebugFeatureRegister0EL1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Aarch64DebugFeatureRegister0EL1")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("debug_version", &self.debug_version())
+            .field("trace_version", &self.trace_version())
+            .field("pmu_version", &self.pmu_version())
+            .field("breakpoints", &self.breakpoints())
+            .field("pmu_snapshot", &self.pmu_snapshot())
+            .field("watchpoints", &self.watchpoints())
+            .field(
+                "context_aware_breakpoints",
+                &self.context_aware_breakpoints(),
+            )
+            .field(
+                "statistical_profiling_version",
+                &self.statistical_profiling_version(),
+            )
+            .field("double_lock", &self.double_lock())
+            .field("trace_filter", &self.trace_filter())
+            .field("trace_buffer", &self.trace_buffer())
+            .field("multithreaded_pmu", &self.multithreaded_pmu())
+            .field("branch_record_buffer", &self.branch_record_buffer())
+            .field("zero_pmu_counters", &self.zero_pmu_counters())
+            .finish()
+    }
+}
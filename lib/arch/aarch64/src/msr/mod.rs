@@ -4,8 +4,21 @@ use core::fmt;
 
 use crate::{EL, Granule, PhysicalAddressSpaceSize};
 
+mod el2;
+mod id;
 pub mod raw;
 
+pub use el2::{
+    CnthctlEL2, CptrEL2, EsrEL2, FarEL2, HcrEL2, HpfarEL2, MairEL2, MdcrEL2, SctlrEL2, TcrEL2,
+    VbarEL2, VtcrEL2, VttbrEL2, ZcrEL2,
+};
+pub use id::{
+    Aarch64DebugFeatureRegister0EL1, Aarch64InstructionSetAttributeRegister0EL1,
+    Aarch64InstructionSetAttributeRegister1EL1, Aarch64InstructionSetAttributeRegister2EL1,
+    Aarch64ProcessorFeatureRegister0EL1, Aarch64ProcessorFeatureRegister1EL1,
+    ExecutionStateSupport,
+};
+
 /// The state of the `CurrentEL` register.
 #[derive(Clone, Copy, Debug, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CurrentEl(u64);
@@ -313,6 +326,333 @@ impl fmt::Debug for SctlrEL1 {
     }
 }
 
+/// The trapping of an extension's instructions and register accesses at EL1 and EL0, as
+/// configured by the `FPEN`, `ZEN`, and `SMEN` fields of `CPACR_EL1`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum AccessControl {
+    /// Accesses at EL1 and EL0 are trapped.
+    TrapAll,
+    /// Accesses at EL0 are trapped and accesses at EL1 are not.
+    TrapEl0,
+    /// No accesses are trapped.
+    TrapNone,
+}
+
+impl AccessControl {
+    /// Decodes an [`AccessControl`] from a two-bit field.
+    const fn from_bits(bits: u64) -> Self {
+        match bits & 0b11 {
+            0b01 => Self::TrapEl0,
+            0b11 => Self::TrapNone,
+            _ => Self::TrapAll,
+        }
+    }
+
+    /// Encodes the [`AccessControl`] as a two-bit field.
+    const fn to_bits(self) -> u64 {
+        match self {
+            Self::TrapAll => 0b00,
+            Self::TrapEl0 => 0b01,
+            Self::TrapNone => 0b11,
+        }
+    }
+}
+
+/// The state of the `CPACR_EL1` register.
+///
+/// Controls the trapping of SIMD, floating-point, and SVE accesses at EL1 and EL0.
+#[derive(Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct CpacrEL1(u64);
+
+impl CpacrEL1 {
+    /// Returns the value of the [`CpacrEL1`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `CPACR_EL1` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `CPACR_EL1` is safe.
+        let val = unsafe { raw::read_cpacr_el1() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`CpacrEL1`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `CPACR_EL1` register and the new configuration of the
+    /// `CPACR_EL1` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `CPACR_EL1` is safe.
+        unsafe { raw::write_cpacr_el1(self.0) }
+    }
+
+    /// Creates a [`CpacrEL1`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`CpacrEL1`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the trapping of SVE instructions and register accesses (`ZEN`).
+    pub const fn sve_access(self) -> AccessControl {
+        AccessControl::from_bits(self.0 >> 16)
+    }
+
+    /// Sets the trapping of SVE instructions and register accesses (`ZEN`).
+    pub const fn set_sve_access(self, access: AccessControl) -> Self {
+        Self((self.0 & !(0b11 << 16)) | (access.to_bits() << 16))
+    }
+
+    /// Returns the trapping of SIMD and floating-point instructions and register accesses
+    /// (`FPEN`).
+    pub const fn fp_access(self) -> AccessControl {
+        AccessControl::from_bits(self.0 >> 20)
+    }
+
+    /// Sets the trapping of SIMD and floating-point instructions and register accesses (`FPEN`).
+    pub const fn set_fp_access(self, access: AccessControl) -> Self {
+        Self((self.0 & !(0b11 << 20)) | (access.to_bits() << 20))
+    }
+
+    /// Returns `true` if trace register accesses are trapped to EL1 (`TTA`).
+    pub const fn trap_trace(self) -> bool {
+        ((self.0 >> 28) & 0b1) == 0b1
+    }
+
+    /// Sets whether trace register accesses are trapped to EL1 (`TTA`).
+    pub const fn set_trap_trace(self, value: bool) -> Self {
+        Self((self.0 & !(0b1 << 28)) | ((value as u64) << 28))
+    }
+}
+
+impl fmt::Debug for CpacrEL1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CpacrEL1")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("sve_access", &self.sve_access())
+            .field("fp_access", &self.fp_access())
+            .field("trap_trace", &self.trap_trace())
+            .finish()
+    }
+}
+
+/// The state of the `ZCR_EL1` register.
+///
+/// Constrains the SVE vector length at EL1 and EL0.
+#[derive(Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct ZcrEL1(u64);
+
+impl ZcrEL1 {
+    /// Returns the value of the [`ZcrEL1`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `ZCR_EL1` register, which requires SVE to be implemented
+    /// and not trapped.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `ZCR_EL1` is safe.
+        let val = unsafe { raw::read_zcr_el1() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`ZcrEL1`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `ZCR_EL1` register, which requires SVE to be implemented
+    /// and not trapped. Shrinking the vector length discards the upper part of every SVE register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `ZCR_EL1` is safe.
+        unsafe { raw::write_zcr_el1(self.0) }
+    }
+
+    /// Creates a [`ZcrEL1`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`ZcrEL1`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the requested vector length, in bytes (`LEN`).
+    ///
+    /// The effective vector length is the largest supported length that does not exceed this.
+    pub const fn vector_length(self) -> usize {
+        ((self.0 & 0xF) as usize + 1) * 16
+    }
+
+    /// Sets the requested vector length, in bytes (`LEN`).
+    ///
+    /// `bytes` is rounded down to a multiple of 16 and clamped to the architectural range of 16
+    /// to 256 bytes.
+    pub const fn set_vector_length(self, bytes: usize) -> Self {
+        let len = if bytes < 16 {
+            0
+        } else if bytes >= 256 {
+            0xF
+        } else {
+            (bytes / 16 - 1) as u64
+        };
+        Self((self.0 & !0xF) | len)
+    }
+}
+
+impl fmt::Debug for ZcrEL1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZcrEL1")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("vector_length", &self.vector_length())
+            .finish()
+    }
+}
+
+/// An 8-bit memory attribute encoding, as held by each field of `MAIR_ELx`.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct MemoryAttribute(pub u8);
+
+impl MemoryAttribute {
+    /// Device memory that permits neither gathering, reordering, nor early write acknowledgement.
+    pub const DEVICE_NGNRNE: Self = Self(0x00);
+    /// Device memory that permits early write acknowledgement, but neither gathering nor
+    /// reordering.
+    pub const DEVICE_NGNRE: Self = Self(0x04);
+    /// Normal memory that is inner and outer non-cacheable.
+    pub const NORMAL_NONCACHEABLE: Self = Self(0x44);
+    /// Normal memory that is inner and outer write-through cacheable, with read and write
+    /// allocation.
+    pub const NORMAL_WRITE_THROUGH: Self = Self(0xBB);
+    /// Normal memory that is inner and outer write-back cacheable, with read and write
+    /// allocation.
+    pub const NORMAL_WRITE_BACK: Self = Self(0xFF);
+
+    /// Returns `true` if the [`MemoryAttribute`] describes Device memory.
+    pub const fn is_device(self) -> bool {
+        self.0 & 0xF0 == 0
+    }
+
+    /// Returns `true` if the [`MemoryAttribute`] describes Normal memory that is inner and outer
+    /// non-cacheable.
+    pub const fn is_normal_noncacheable(self) -> bool {
+        self.0 == Self::NORMAL_NONCACHEABLE.0
+    }
+
+    /// Returns `true` if the [`MemoryAttribute`] describes Normal memory that is inner and outer
+    /// write-back cacheable.
+    pub const fn is_normal_write_back(self) -> bool {
+        const fn write_back(policy: u8) -> bool {
+            policy & 0b1100 == 0b1100 || (policy & 0b1100 == 0b0100 && policy & 0b0011 != 0)
+        }
+
+        write_back(self.0 >> 4) && write_back(self.0 & 0xF)
+    }
+}
+
+/// The state of the `MAIR_EL1` register.
+///
+/// Holds the eight [`MemoryAttribute`]s selected by the `AttrIndx` field of stage 1 translation
+/// descriptors at EL1 and EL0.
+#[derive(Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct MairEL1(u64);
+
+impl MairEL1 {
+    /// The number of [`MemoryAttribute`]s held by the [`MairEL1`] register.
+    pub const ATTRIBUTES: u8 = 8;
+
+    /// Returns the value of the [`MairEL1`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `MAIR_EL1` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `MAIR_EL1` is safe.
+        let val = unsafe { raw::read_mair_el1() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`MairEL1`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `MAIR_EL1` register and the new attributes must be
+    /// compatible with the mappings in use.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `MAIR_EL1` is safe.
+        unsafe { raw::write_mair_el1(self.0) }
+    }
+
+    /// Creates a [`MairEL1`] from its raw value.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`MairEL1`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the [`MemoryAttribute`] at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`MairEL1::ATTRIBUTES`].
+    #[expect(clippy::as_conversions)]
+    pub const fn attribute(self, index: u8) -> MemoryAttribute {
+        assert!(
+            index < Self::ATTRIBUTES,
+            "MAIR attribute index out of range"
+        );
+        MemoryAttribute(((self.0 >> (index * 8)) & 0xFF) as u8)
+    }
+
+    /// Sets the [`MemoryAttribute`] at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`MairEL1::ATTRIBUTES`].
+    #[expect(clippy::as_conversions)]
+    pub const fn set_attribute(self, index: u8, attribute: MemoryAttribute) -> Self {
+        assert!(
+            index < Self::ATTRIBUTES,
+            "MAIR attribute index out of range"
+        );
+        let shift = index * 8;
+        Self((self.0 & !(0xFF << shift)) | ((attribute.0 as u64) << shift))
+    }
+}
+
+impl fmt::Debug for MairEL1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MairEL1")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .finish()
+    }
+}
+
 /// The state of the `TCR_EL1` register.
 #[derive(Clone, Copy, Hash, PartialEq, Eq)]
 pub struct TcrEL1(u64);
@@ -544,3 +884,73 @@ impl fmt::Debug for TcrEL1 {
             .finish()
     }
 }
+
+/// The state of the `CNTV_CTL_EL0` register.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct CntvCtlEL0(u64);
+
+impl CntvCtlEL0 {
+    /// Returns the value of the [`CntvCtlEL0`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to read from the `CNTV_CTL_EL0` register.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that reading `CNTV_CTL_EL0` is safe.
+        let val = unsafe { raw::read_cntv_ctl_el0() };
+        Self(val)
+    }
+
+    /// Sets the value of the [`CntvCtlEL0`] register.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to write to the `CNTV_CTL_EL0` register and the new configuration of the
+    /// `CNTV_CTL_EL0` register must be compatible with the current state of the system.
+    #[cfg(target_arch = "aarch64")]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that writing `CNTV_CTL_EL0` is safe.
+        unsafe { raw::write_cntv_ctl_el0(self.0) }
+    }
+
+    /// Returns `true` if the virtual timer is enabled.
+    pub const fn enable(self) -> bool {
+        (self.0 & 0b1) == 0b1
+    }
+
+    /// Sets whether the virtual timer is enabled.
+    pub const fn set_enable(self, enable: bool) -> Self {
+        Self((self.0 & !0b1) | (enable as u64))
+    }
+
+    /// Returns `true` if the virtual timer interrupt is masked.
+    pub const fn interrupt_mask(self) -> bool {
+        ((self.0 >> 1) & 0b1) == 0b1
+    }
+
+    /// Sets whether the virtual timer interrupt is masked.
+    pub const fn set_interrupt_mask(self, mask: bool) -> Self {
+        Self((self.0 & !(0b1 << 1)) | ((mask as u64) << 1))
+    }
+
+    /// Returns `true` if the virtual timer condition is met.
+    pub const fn interrupt_status(self) -> bool {
+        ((self.0 >> 2) & 0b1) == 0b1
+    }
+}
+
+impl fmt::Debug for CntvCtlEL0 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CntvCtlEL0")
+            .field("raw", &format_args!("{:#018x}", self.0))
+            .field("enable", &self.enable())
+            .field("interrupt_mask", &self.interrupt_mask())
+            .field("interrupt_status", &self.interrupt_status())
+            .finish()
+    }
+}
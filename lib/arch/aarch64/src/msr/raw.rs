@@ -66,9 +66,63 @@ sysreg_read! {read_id_aa64mmfr1_el1, "ID_AA64MMFR1_EL1"}
 sysreg_read! {read_id_aa64mmfr2_el1, "ID_AA64MMFR2_EL1"}
 sysreg_read! {read_id_aa64mmfr3_el1, "ID_AA64MMFR3_EL1"}
 sysreg_read! {read_id_aa64mmfr4_el1, "ID_AA64MMFR4_EL1"}
+sysreg_read! {read_id_aa64pfr0_el1, "ID_AA64PFR0_EL1"}
+sysreg_read! {read_id_aa64pfr1_el1, "ID_AA64PFR1_EL1"}
+sysreg_read! {read_id_aa64isar0_el1, "ID_AA64ISAR0_EL1"}
+sysreg_read! {read_id_aa64isar1_el1, "ID_AA64ISAR1_EL1"}
+sysreg_read! {read_id_aa64isar2_el1, "ID_AA64ISAR2_EL1"}
+sysreg_read! {read_id_aa64dfr0_el1, "ID_AA64DFR0_EL1"}
 
 sysreg_rw! {read_sctlr_el1, write_sctlr_el1, "SCTLR_EL1"}
 sysreg_rw! {read_tcr_el1, write_tcr_el1, "TCR_EL1"}
+sysreg_rw! {read_mair_el1, write_mair_el1, "MAIR_EL1"}
+sysreg_rw! {read_cpacr_el1, write_cpacr_el1, "CPACR_EL1"}
+sysreg_rw! {read_sctlr_el2, write_sctlr_el2, "SCTLR_EL2"}
+sysreg_rw! {read_tcr_el2, write_tcr_el2, "TCR_EL2"}
+sysreg_rw! {read_mair_el2, write_mair_el2, "MAIR_EL2"}
+
+sysreg_rw! {read_hcr_el2, write_hcr_el2, "HCR_EL2"}
+sysreg_rw! {read_vtcr_el2, write_vtcr_el2, "VTCR_EL2"}
+sysreg_rw! {read_vttbr_el2, write_vttbr_el2, "VTTBR_EL2"}
+sysreg_rw! {read_cptr_el2, write_cptr_el2, "CPTR_EL2"}
+sysreg_rw! {read_mdcr_el2, write_mdcr_el2, "MDCR_EL2"}
+
+// `ZCR_EL1` and `ZCR_EL2` are named by encoding so that they assemble without the SVE extension.
+sysreg_rw! {read_zcr_el1, write_zcr_el1, "S3_0_C1_C2_0"}
+sysreg_rw! {read_zcr_el2, write_zcr_el2, "S3_4_C1_C2_0"}
 
 sysreg_rw! {read_ttbr0_el1, write_ttbr0_el1, "TTBR0_EL1"}
 sysreg_rw! {read_ttbr1_el1, write_ttbr1_el1, "TTBR1_EL1"}
+
+sysreg_read! {read_esr_el1, "ESR_EL1"}
+sysreg_rw! {read_esr_el2, write_esr_el2, "ESR_EL2"}
+sysreg_read! {read_far_el1, "FAR_EL1"}
+sysreg_rw! {read_far_el2, write_far_el2, "FAR_EL2"}
+sysreg_rw! {read_hpfar_el2, write_hpfar_el2, "HPFAR_EL2"}
+
+sysreg_rw! {read_elr_el1, write_elr_el1, "ELR_EL1"}
+sysreg_rw! {read_elr_el2, write_elr_el2, "ELR_EL2"}
+sysreg_rw! {read_spsr_el1, write_spsr_el1, "SPSR_EL1"}
+sysreg_rw! {read_spsr_el2, write_spsr_el2, "SPSR_EL2"}
+
+sysreg_rw! {read_vbar_el1, write_vbar_el1, "VBAR_EL1"}
+sysreg_rw! {read_vbar_el2, write_vbar_el2, "VBAR_EL2"}
+
+sysreg_read! {read_cntfrq_el0, "CNTFRQ_EL0"}
+sysreg_read! {read_cntpct_el0, "CNTPCT_EL0"}
+sysreg_read! {read_cntvct_el0, "CNTVCT_EL0"}
+sysreg_rw! {read_cntv_ctl_el0, write_cntv_ctl_el0, "CNTV_CTL_EL0"}
+sysreg_rw! {read_cntv_cval_el0, write_cntv_cval_el0, "CNTV_CVAL_EL0"}
+sysreg_rw! {read_cntv_tval_el0, write_cntv_tval_el0, "CNTV_TVAL_EL0"}
+sysreg_rw! {read_cnthctl_el2, write_cnthctl_el2, "CNTHCTL_EL2"}
+
+sysreg_rw! {read_icc_sre_el1, write_icc_sre_el1, "ICC_SRE_EL1"}
+sysreg_rw! {read_icc_sre_el2, write_icc_sre_el2, "ICC_SRE_EL2"}
+sysreg_rw! {read_icc_pmr_el1, write_icc_pmr_el1, "ICC_PMR_EL1"}
+sysreg_rw! {read_icc_bpr1_el1, write_icc_bpr1_el1, "ICC_BPR1_EL1"}
+sysreg_rw! {read_icc_ctlr_el1, write_icc_ctlr_el1, "ICC_CTLR_EL1"}
+sysreg_rw! {read_icc_igrpen1_el1, write_icc_igrpen1_el1, "ICC_IGRPEN1_EL1"}
+sysreg_read! {read_icc_iar1_el1, "ICC_IAR1_EL1"}
+sysreg_write! {write_icc_eoir1_el1, "ICC_EOIR1_EL1"}
+sysreg_write! {write_icc_dir_el1, "ICC_DIR_EL1"}
+sysreg_write! {write_icc_sgi1r_el1, "ICC_SGI1R_EL1"}
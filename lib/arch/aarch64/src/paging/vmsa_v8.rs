@@ -580,6 +580,24 @@ impl TranslationDescriptor {
     pub const fn set_page_block_accessed(self, accessed: bool) -> Self {
         Self((self.0 & !(1 << 10)) | (bool_as_u64(accessed) << 10))
     }
+
+    /// Returns the index of the `MAIR_ELx` field that holds the memory attributes.
+    ///
+    /// This should only be used on [`TranslationDescriptor`]s that are page or block descriptors.
+    #[expect(clippy::as_conversions)]
+    pub const fn page_block_attribute_index(self) -> u8 {
+        ((self.0 >> 2) & 0b111) as u8
+    }
+
+    /// Sets the index of the `MAIR_ELx` field that holds the memory attributes.
+    ///
+    /// Only the lowest three bits of `index` are used.
+    ///
+    /// This should only be used on [`TranslationDescriptor`]s that are page or block descriptors.
+    #[expect(clippy::as_conversions)]
+    pub const fn set_page_block_attribute_index(self, index: u8) -> Self {
+        Self((self.0 & !(0b111 << 2)) | (((index & 0b111) as u64) << 2))
+    }
 }
 
 /// Converts a boolean to its `u64` representation.
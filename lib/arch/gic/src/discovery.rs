@@ -0,0 +1,486 @@
+//! Discovery of the GIC through the device tree or the ACPI MADT.
+//!
+//! Both sources produce a [`GicDescription`], which records the physical address of every GIC
+//! register frame, including the GICH and GICV frames used for virtualization, which are not
+//! driven by this crate.
+
+use device_tree::{Node, Property};
+
+use crate::GicVersion;
+
+/// The maximum number of redistributor regions recorded in a [`GicDescription`].
+pub const MAX_REDISTRIBUTOR_REGIONS: usize = 8;
+
+/// The size of the GICv2 distributor register frame.
+const V2_DISTRIBUTOR_SIZE: u64 = 0x1000;
+/// The size of the GICv3 distributor register frame.
+const V3_DISTRIBUTOR_SIZE: u64 = 0x10000;
+/// The size of the GICv2 CPU interface, GICH and GICV register frames.
+const CPU_INTERFACE_SIZE: u64 = 0x2000;
+/// The size of the register frames of a single redistributor without virtual LPI support.
+const REDISTRIBUTOR_SIZE: u64 = 0x20000;
+
+/// A physical memory region containing GIC registers.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Region {
+    /// The physical address of the start of the region.
+    pub base: u64,
+    /// The size, in bytes, of the region.
+    pub size: u64,
+}
+
+/// The location of the GIC register frames of a platform.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GicDescription {
+    /// The version of the GIC.
+    pub version: GicVersion,
+    /// The distributor register frame.
+    pub distributor: Region,
+    /// The memory-mapped CPU interface, which is always present on GICv2 and optional on GICv3.
+    pub cpu_interface: Option<Region>,
+    /// The virtual interface control register frame (GICH).
+    pub hypervisor_interface: Option<Region>,
+    /// The virtual CPU interface register frame (GICV).
+    pub virtual_cpu_interface: Option<Region>,
+    /// The redistributor regions, of which the first `redistributor_count` are valid.
+    redistributors: [Region; MAX_REDISTRIBUTOR_REGIONS],
+    /// The number of valid entries in `redistributors`.
+    redistributor_count: usize,
+}
+
+impl GicDescription {
+    /// Creates a [`GicDescription`] with no redistributors or optional register frames.
+    const fn new(version: GicVersion, distributor: Region) -> Self {
+        Self {
+            version,
+            distributor,
+            cpu_interface: None,
+            hypervisor_interface: None,
+            virtual_cpu_interface: None,
+            redistributors: [Region { base: 0, size: 0 }; MAX_REDISTRIBUTOR_REGIONS],
+            redistributor_count: 0,
+        }
+    }
+
+    /// Returns the redistributor regions of a GICv3 or GICv4.
+    pub fn redistributors(&self) -> &[Region] {
+        &self.redistributors[..self.redistributor_count]
+    }
+
+    /// Records the redistributor `region`, merging it with the previous region if they are
+    /// contiguous.
+    ///
+    /// Returns [`None`] if there is no space for `region`.
+    fn push_redistributor(&mut self, region: Region) -> Option<()> {
+        if let Some(last) = self.redistributors[..self.redistributor_count].last_mut() {
+            if region.base >= last.base && region.base < last.base + last.size {
+                return Some(());
+            } else if last.base + last.size == region.base {
+                last.size += region.size;
+                return Some(());
+            }
+        }
+
+        *self.redistributors.get_mut(self.redistributor_count)? = region;
+        self.redistributor_count += 1;
+        Some(())
+    }
+}
+
+/// Returns the [`GicDescription`] of the first enabled GIC node in the device tree rooted at
+/// `root`.
+pub fn from_device_tree(root: Node) -> Option<GicDescription> {
+    find_device_tree_node(root, 2, 1)
+}
+
+/// Searches the children of `node` for a GIC node, where `address_cells` and `size_cells` are the
+/// `#address-cells` and `#size-cells` of `node`.
+fn find_device_tree_node(
+    node: Node,
+    address_cells: u32,
+    size_cells: u32,
+) -> Option<GicDescription> {
+    for child in node.nodes() {
+        let enabled = child
+            .find_property(c"status")
+            .and_then(|status| status.read_cstr(0))
+            .is_none_or(|status| status == c"okay" || status == c"ok");
+        if !enabled {
+            continue;
+        }
+
+        if let Some(version) = child
+            .find_property(c"compatible")
+            .and_then(compatible_version)
+        {
+            let reg = child.find_property(c"reg")?;
+            let reg = Reg {
+                property: reg,
+                address_cells,
+                size_cells,
+            };
+            return match version {
+                GicVersion::V2 => device_tree_v2(reg),
+                GicVersion::V3 | GicVersion::V4 => {
+                    let redistributor_regions = child
+                        .find_property(c"#redistributor-regions")
+                        .and_then(|property| property.read_u32_at(0))
+                        .unwrap_or(1);
+                    device_tree_v3(reg, conversion::u32_to_usize_strict(redistributor_regions))
+                }
+            };
+        }
+
+        let read_cells = |name, default| {
+            child
+                .find_property(name)
+                .and_then(|property| property.read_u32_at(0))
+                .unwrap_or(default)
+        };
+        let child_address_cells = read_cells(c"#address-cells", 2);
+        let child_size_cells = read_cells(c"#size-cells", 1);
+        if let Some(description) =
+            find_device_tree_node(child, child_address_cells, child_size_cells)
+        {
+            return Some(description);
+        }
+    }
+
+    None
+}
+
+/// Returns the [`GicVersion`] described by a `compatible` property, if any of its entries
+/// describe a GIC.
+fn compatible_version(compatible: Property) -> Option<GicVersion> {
+    compatible
+        .data()
+        .split(|&byte| byte == 0)
+        .find_map(|entry| match entry {
+            b"arm,gic-v3" => Some(GicVersion::V3),
+            b"arm,gic-400"
+            | b"arm,cortex-a15-gic"
+            | b"arm,cortex-a9-gic"
+            | b"arm,cortex-a7-gic"
+            | b"arm,gic-v2" => Some(GicVersion::V2),
+            _ => None,
+        })
+}
+
+/// Builds the [`GicDescription`] of a GICv2 from its `reg` property, which lists the GICD, GICC,
+/// GICH, and GICV frames in order.
+fn device_tree_v2(reg: Reg) -> Option<GicDescription> {
+    let mut description = GicDescription::new(GicVersion::V2, reg.get(0)?);
+    description.cpu_interface = Some(reg.get(1)?);
+    description.hypervisor_interface = reg.get(2);
+    description.virtual_cpu_interface = reg.get(3);
+    Some(description)
+}
+
+/// Builds the [`GicDescription`] of a GICv3 from its `reg` property, which lists the GICD frame,
+/// `redistributor_regions` GICR regions, and then the optional GICC, GICH, and GICV frames.
+fn device_tree_v3(reg: Reg, redistributor_regions: usize) -> Option<GicDescription> {
+    let mut description = GicDescription::new(GicVersion::V3, reg.get(0)?);
+    for index in 0..redistributor_regions {
+        description.push_redistributor(reg.get(1 + index)?)?;
+    }
+    description.cpu_interface = reg.get(1 + redistributor_regions);
+    description.hypervisor_interface = reg.get(2 + redistributor_regions);
+    description.virtual_cpu_interface = reg.get(3 + redistributor_regions);
+    Some(description)
+}
+
+/// A `reg` property and the cell sizes of its entries.
+struct Reg<'a> {
+    /// The `reg` property.
+    property: Property<'a>,
+    /// The number of cells in the address of each entry.
+    address_cells: u32,
+    /// The number of cells in the size of each entry.
+    size_cells: u32,
+}
+
+impl Reg<'_> {
+    /// Returns the [`Region`] described by entry `index`.
+    fn get(&self, index: usize) -> Option<Region> {
+        let address_size = conversion::u32_to_usize_strict(self.address_cells) * 4;
+        let size_size = conversion::u32_to_usize_strict(self.size_cells) * 4;
+        let offset = index.checked_mul(address_size + size_size)?;
+
+        let read = |offset, size| match size {
+            4 => self.property.read_u32_at(offset).map(u64::from),
+            8 => self.property.read_u64_at(offset),
+            _ => None,
+        };
+
+        Some(Region {
+            base: read(offset, address_size)?,
+            size: read(offset + address_size, size_size)?,
+        })
+    }
+}
+
+/// The offset of the interrupt controller structures in the MADT.
+const MADT_ENTRIES_OFFSET: usize = 44;
+
+/// The MADT entry type of a GIC CPU interface (GICC) structure.
+const MADT_GICC: u8 = 0xB;
+/// The MADT entry type of a GIC distributor (GICD) structure.
+const MADT_GICD: u8 = 0xC;
+/// The MADT entry type of a GIC redistributor (GICR) structure.
+const MADT_GICR: u8 = 0xE;
+
+/// Returns the [`GicDescription`] described by the ACPI MADT contained in `madt`.
+///
+/// If the MADT contains no GICR structures, the redistributors are instead taken from the GICR
+/// base addresses of the GICC structures.
+pub fn from_madt(madt: &[u8]) -> Option<GicDescription> {
+    if madt.get(..4)? != b"APIC" {
+        return None;
+    }
+    let length = conversion::u32_to_usize_strict(read_u32(madt, 4)?);
+    let madt = madt.get(..length)?;
+
+    let mut description = None;
+    let mut has_redistributors = false;
+    for (kind, entry) in madt_entries(madt) {
+        if kind == MADT_GICD {
+            let base = read_u64(entry, 8)?;
+            let version = match *entry.get(20)? {
+                0 => None,
+                1 | 2 => Some(GicVersion::V2),
+                3 => Some(GicVersion::V3),
+                4 => Some(GicVersion::V4),
+                _ => return None,
+            };
+            description = Some((base, version));
+        } else if kind == MADT_GICR {
+            has_redistributors = true;
+        }
+    }
+    let (distributor_base, version) = description?;
+
+    let has_gicc_redistributors = madt_entries(madt)
+        .filter(|&(kind, _)| kind == MADT_GICC)
+        .any(|(_, entry)| read_u64(entry, 60).is_some_and(|base| base != 0));
+    let version = version.unwrap_or(if has_redistributors || has_gicc_redistributors {
+        GicVersion::V3
+    } else {
+        GicVersion::V2
+    });
+    let distributor_size = match version {
+        GicVersion::V2 => V2_DISTRIBUTOR_SIZE,
+        GicVersion::V3 | GicVersion::V4 => V3_DISTRIBUTOR_SIZE,
+    };
+
+    let mut description = GicDescription::new(
+        version,
+        Region {
+            base: distributor_base,
+            size: distributor_size,
+        },
+    );
+    let frame = |base: u64, size| (base != 0).then_some(Region { base, size });
+
+    for (kind, entry) in madt_entries(madt) {
+        match kind {
+            MADT_GICC => {
+                description.cpu_interface = description
+                    .cpu_interface
+                    .or(frame(read_u64(entry, 32)?, CPU_INTERFACE_SIZE));
+                description.virtual_cpu_interface = description
+                    .virtual_cpu_interface
+                    .or(frame(read_u64(entry, 40)?, CPU_INTERFACE_SIZE));
+                description.hypervisor_interface = description
+                    .hypervisor_interface
+                    .or(frame(read_u64(entry, 48)?, CPU_INTERFACE_SIZE));
+
+                if !has_redistributors
+                    && let Some(region) = frame(read_u64(entry, 60)?, REDISTRIBUTOR_SIZE)
+                {
+                    description.push_redistributor(region)?;
+                }
+            }
+            MADT_GICR => {
+                let region = Region {
+                    base: read_u64(entry, 4)?,
+                    size: u64::from(read_u32(entry, 12)?),
+                };
+                description.push_redistributor(region)?;
+            }
+            _ => {}
+        }
+    }
+
+    Some(description)
+}
+
+/// Returns an iterator over the type and contents of each interrupt controller structure in
+/// `madt`.
+fn madt_entries(madt: &[u8]) -> impl Iterator<Item = (u8, &[u8])> {
+    let mut remaining = madt.get(MADT_ENTRIES_OFFSET..).unwrap_or(&[]);
+    core::iter::from_fn(move || {
+        let [kind, length, ..] = *remaining else {
+            return None;
+        };
+        let length = usize::from(length);
+        if length < 2 || length > remaining.len() {
+            return None;
+        }
+
+        let (entry, rest) = remaining.split_at(length);
+        remaining = rest;
+        Some((kind, entry))
+    })
+}
+
+/// Reads the little-endian [`u32`] at `offset` in `bytes`.
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Reads the little-endian [`u64`] at `offset` in `bytes`.
+fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    let bytes = bytes.get(offset..offset.checked_add(8)?)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+
+    /// Builds a MADT containing `entries`.
+    fn madt(entries: &[Vec<u8>]) -> Vec<u8> {
+        let mut madt = Vec::from(*b"APIC");
+        madt.resize(MADT_ENTRIES_OFFSET, 0);
+        for entry in entries {
+            madt.extend_from_slice(entry);
+        }
+        let length = u32::try_from(madt.len()).unwrap();
+        madt[4..8].copy_from_slice(&length.to_le_bytes());
+        madt
+    }
+
+    /// Builds a GICD structure.
+    fn gicd(base: u64, version: u8) -> Vec<u8> {
+        let mut entry = std::vec![0; 24];
+        entry[0] = MADT_GICD;
+        entry[1] = 24;
+        entry[8..16].copy_from_slice(&base.to_le_bytes());
+        entry[20] = version;
+        entry
+    }
+
+    /// Builds a GICR structure.
+    fn gicr(base: u64, length: u32) -> Vec<u8> {
+        let mut entry = std::vec![0; 16];
+        entry[0] = MADT_GICR;
+        entry[1] = 16;
+        entry[4..12].copy_from_slice(&base.to_le_bytes());
+        entry[12..16].copy_from_slice(&length.to_le_bytes());
+        entry
+    }
+
+    /// Builds a GICC structure.
+    fn gicc(gicc: u64, gicv: u64, gich: u64, gicr: u64) -> Vec<u8> {
+        let mut entry = std::vec![0; 80];
+        entry[0] = MADT_GICC;
+        entry[1] = 80;
+        entry[32..40].copy_from_slice(&gicc.to_le_bytes());
+        entry[40..48].copy_from_slice(&gicv.to_le_bytes());
+        entry[48..56].copy_from_slice(&gich.to_le_bytes());
+        entry[60..68].copy_from_slice(&gicr.to_le_bytes());
+        entry
+    }
+
+    const fn region(base: u64, size: u64) -> Region {
+        Region { base, size }
+    }
+
+    #[test]
+    fn madt_descriptions() {
+        struct Case {
+            entries: Vec<Vec<u8>>,
+            version: GicVersion,
+            distributor: Region,
+            cpu_interface: Option<Region>,
+            hypervisor_interface: Option<Region>,
+            redistributors: Vec<Region>,
+        }
+
+        let cases = [
+            Case {
+                entries: std::vec![
+                    gicc(0x0801_0000, 0x0804_0000, 0x0803_0000, 0),
+                    gicc(0x0801_0000, 0x0804_0000, 0x0803_0000, 0),
+                    gicd(0x0800_0000, 2),
+                ],
+                version: GicVersion::V2,
+                distributor: region(0x0800_0000, 0x1000),
+                cpu_interface: Some(region(0x0801_0000, 0x2000)),
+                hypervisor_interface: Some(region(0x0803_0000, 0x2000)),
+                redistributors: Vec::new(),
+            },
+            Case {
+                entries: std::vec![
+                    gicd(0x0800_0000, 3),
+                    gicr(0x080A_0000, 0x0080_0000),
+                    gicc(0, 0, 0, 0),
+                ],
+                version: GicVersion::V3,
+                distributor: region(0x0800_0000, 0x10000),
+                cpu_interface: None,
+                hypervisor_interface: None,
+                redistributors: std::vec![region(0x080A_0000, 0x0080_0000)],
+            },
+            Case {
+                entries: std::vec![
+                    gicd(0x0800_0000, 0),
+                    gicc(0, 0, 0, 0x080A_0000),
+                    gicc(0, 0, 0, 0x080C_0000),
+                    gicc(0, 0, 0, 0x0900_0000),
+                ],
+                version: GicVersion::V3,
+                distributor: region(0x0800_0000, 0x10000),
+                cpu_interface: None,
+                hypervisor_interface: None,
+                redistributors: std::vec![
+                    region(0x080A_0000, 0x40000),
+                    region(0x0900_0000, 0x20000)
+                ],
+            },
+            Case {
+                entries: std::vec![gicd(0x2F00_0000, 4), gicr(0x2F10_0000, 0x0010_0000)],
+                version: GicVersion::V4,
+                distributor: region(0x2F00_0000, 0x10000),
+                cpu_interface: None,
+                hypervisor_interface: None,
+                redistributors: std::vec![region(0x2F10_0000, 0x0010_0000)],
+            },
+        ];
+
+        for case in cases {
+            let description = from_madt(&madt(&case.entries)).unwrap();
+            assert_eq!(description.version, case.version);
+            assert_eq!(description.distributor, case.distributor);
+            assert_eq!(description.cpu_interface, case.cpu_interface);
+            assert_eq!(description.hypervisor_interface, case.hypervisor_interface);
+            assert_eq!(description.redistributors(), case.redistributors);
+        }
+    }
+
+    #[test]
+    fn malformed_madt() {
+        assert_eq!(from_madt(b"FACP"), None);
+        assert_eq!(from_madt(&madt(&[gicc(0x1000, 0, 0, 0)])), None);
+        assert_eq!(from_madt(&madt(&[gicd(0x1000, 9)])), None);
+
+        let mut truncated = madt(&[gicd(0x1000, 2)]);
+        truncated.pop();
+        assert_eq!(from_madt(&truncated), None);
+    }
+}
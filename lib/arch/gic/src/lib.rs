@@ -0,0 +1,733 @@
+//! Definitions and drivers for the Arm Generic Interrupt Controller (GIC).
+//!
+//! [`v2`] drives GICv2, whose distributor and CPU interface are both memory-mapped, while [`v3`]
+//! drives GICv3 and GICv4, whose CPU interface is accessed through the `ICC_*_EL1` system
+//! registers. [`Gic`] wraps both behind a single interface, and [`discovery`] locates the GIC
+//! through the device tree or the ACPI MADT.
+
+#![no_std]
+
+use core::fmt;
+
+pub mod discovery;
+pub mod v2;
+pub mod v3;
+
+/// The identifier of an interrupt handled by the GIC.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct InterruptId(u32);
+
+impl InterruptId {
+    /// The number of software generated interrupts.
+    pub const SGI_COUNT: u32 = 16;
+    /// The first private peripheral interrupt.
+    pub const PPI_START: u32 = 16;
+    /// The first shared peripheral interrupt.
+    pub const SPI_START: u32 = 32;
+    /// The first special interrupt identifier.
+    pub const SPECIAL_START: u32 = 1020;
+
+    /// The interrupt identifier returned when no interrupt is pending.
+    pub const SPURIOUS: Self = Self(1023);
+
+    /// The private peripheral interrupt of the EL2 physical timer.
+    pub const EL2_PHYSICAL_TIMER: Self = Self(26);
+    /// The private peripheral interrupt of the EL1 virtual timer.
+    pub const EL1_VIRTUAL_TIMER: Self = Self(27);
+    /// The private peripheral interrupt of the EL1 physical timer.
+    pub const EL1_PHYSICAL_TIMER: Self = Self(30);
+
+    /// Creates a new [`InterruptId`] from its raw value.
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    /// Returns the [`InterruptId`] of the software generated interrupt `sgi`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sgi` is not a valid software generated interrupt.
+    pub const fn sgi(sgi: u8) -> Self {
+        assert!((sgi as u32) < Self::SGI_COUNT, "invalid SGI");
+        Self(sgi as u32)
+    }
+
+    /// Returns the [`InterruptId`] of the private peripheral interrupt `ppi`, numbered from zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ppi` is not a valid private peripheral interrupt.
+    pub const fn ppi(ppi: u32) -> Self {
+        assert!(ppi < Self::SPI_START - Self::PPI_START, "invalid PPI");
+        Self(Self::PPI_START + ppi)
+    }
+
+    /// Returns the [`InterruptId`] of the shared peripheral interrupt `spi`, numbered from zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `spi` is not a valid shared peripheral interrupt.
+    pub const fn spi(spi: u32) -> Self {
+        assert!(spi < Self::SPECIAL_START - Self::SPI_START, "invalid SPI");
+        Self(Self::SPI_START + spi)
+    }
+
+    /// Returns the raw value of the [`InterruptId`].
+    pub const fn value(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the [`InterruptKind`] of the [`InterruptId`].
+    pub const fn kind(self) -> InterruptKind {
+        match self.0 {
+            0..Self::PPI_START => InterruptKind::Sgi,
+            Self::PPI_START..Self::SPI_START => InterruptKind::Ppi,
+            Self::SPI_START..Self::SPECIAL_START => InterruptKind::Spi,
+            Self::SPECIAL_START..=1023 => InterruptKind::Special,
+            8192.. => InterruptKind::Lpi,
+            _ => InterruptKind::Reserved,
+        }
+    }
+
+    /// Returns `true` if the [`InterruptId`] is private to each processor.
+    pub const fn is_private(self) -> bool {
+        self.0 < Self::SPI_START
+    }
+}
+
+impl fmt::Display for InterruptId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind() {
+            InterruptKind::Sgi => write!(f, "SGI {}", self.0),
+            InterruptKind::Ppi => write!(f, "PPI {}", self.0 - Self::PPI_START),
+            InterruptKind::Spi => write!(f, "SPI {}", self.0 - Self::SPI_START),
+            InterruptKind::Special => write!(f, "special INTID {}", self.0),
+            InterruptKind::Lpi => write!(f, "LPI {}", self.0),
+            InterruptKind::Reserved => write!(f, "reserved INTID {}", self.0),
+        }
+    }
+}
+
+/// The kinds of interrupts handled by the GIC.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum InterruptKind {
+    /// A software generated interrupt, used for inter-processor interrupts.
+    Sgi,
+    /// A private peripheral interrupt, such as the generic timer.
+    Ppi,
+    /// A shared peripheral interrupt, which can be routed to any processor.
+    Spi,
+    /// An interrupt identifier with a special meaning.
+    Special,
+    /// A locality-specific peripheral interrupt.
+    Lpi,
+    /// A reserved interrupt identifier.
+    Reserved,
+}
+
+/// The conditions that trigger a peripheral interrupt.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Trigger {
+    /// The interrupt is asserted while the signal is high.
+    Level,
+    /// The interrupt is asserted on a rising edge of the signal.
+    Edge,
+}
+
+/// The affinity of a processor, as reported by `MPIDR_EL1`.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Affinity(u64);
+
+impl Affinity {
+    /// The mask of the affinity fields of `MPIDR_EL1`.
+    const MPIDR_MASK: u64 = 0xFF_00FF_FFFF;
+
+    /// Creates an [`Affinity`] from its four affinity levels.
+    pub const fn new(aff3: u8, aff2: u8, aff1: u8, aff0: u8) -> Self {
+        Self(((aff3 as u64) << 32) | ((aff2 as u64) << 16) | ((aff1 as u64) << 8) | aff0 as u64)
+    }
+
+    /// Creates an [`Affinity`] from the value of `MPIDR_EL1`.
+    pub const fn from_mpidr(mpidr: u64) -> Self {
+        Self(mpidr & Self::MPIDR_MASK)
+    }
+
+    /// Returns the [`Affinity`] of the current processor.
+    #[cfg(target_arch = "aarch64")]
+    pub fn current() -> Self {
+        // SAFETY:
+        //
+        // Reading `MPIDR_EL1` has no side effects.
+        Self::from_mpidr(unsafe { aarch64::msr::raw::read_mpidr_el1() })
+    }
+
+    /// Returns the value of affinity level 0.
+    pub const fn aff0(self) -> u8 {
+        (self.0 & 0xFF) as u8
+    }
+
+    /// Returns the value of affinity level 1.
+    pub const fn aff1(self) -> u8 {
+        ((self.0 >> 8) & 0xFF) as u8
+    }
+
+    /// Returns the value of affinity level 2.
+    pub const fn aff2(self) -> u8 {
+        ((self.0 >> 16) & 0xFF) as u8
+    }
+
+    /// Returns the value of affinity level 3.
+    pub const fn aff3(self) -> u8 {
+        ((self.0 >> 32) & 0xFF) as u8
+    }
+
+    /// Returns the affinity packed as `Aff3.Aff2.Aff1.Aff0`, as used by `GICR_TYPER`.
+    pub const fn packed(self) -> u32 {
+        ((self.aff3() as u32) << 24)
+            | ((self.aff2() as u32) << 16)
+            | ((self.aff1() as u32) << 8)
+            | self.aff0() as u32
+    }
+}
+
+/// A processor that can be targeted by a shared peripheral interrupt or a software generated
+/// interrupt.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Target {
+    /// The [`Affinity`] of the processor, used by GICv3.
+    pub affinity: Affinity,
+    /// The CPU interface number of the processor, used by GICv2.
+    pub cpu_interface: u8,
+}
+
+/// The destinations of a software generated interrupt.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum SgiTarget {
+    /// The interrupt is sent to the provided processor.
+    Processor(Target),
+    /// The interrupt is sent to every processor except the current processor.
+    AllOthers,
+}
+
+/// The major versions of the GIC architecture.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GicVersion {
+    /// GICv2, with a memory-mapped CPU interface.
+    V2,
+    /// GICv3, with redistributors and a system register CPU interface.
+    V3,
+    /// GICv4, which extends GICv3 with direct injection of virtual interrupts.
+    V4,
+}
+
+/// The default priority assigned to interrupts when they are configured.
+pub const DEFAULT_PRIORITY: u8 = 0xA0;
+
+/// An initialized handle to the GIC.
+#[derive(Clone, Copy, Debug)]
+pub enum Gic {
+    /// A GICv2 interrupt controller.
+    V2 {
+        /// The distributor.
+        distributor: v2::Distributor,
+        /// The CPU interface of every processor, which is banked per processor.
+        cpu_interface: v2::CpuInterface,
+    },
+    /// A GICv3 or GICv4 interrupt controller.
+    V3 {
+        /// The distributor.
+        distributor: v3::Distributor,
+        /// The redistributors of every processor.
+        redistributors: v3::RedistributorRegion,
+    },
+}
+
+#[cfg(target_arch = "aarch64")]
+impl Gic {
+    /// Initializes the distributor: every shared peripheral interrupt is disabled, assigned
+    /// [`DEFAULT_PRIORITY`], made level-triggered, and routed to the current processor.
+    ///
+    /// # Safety
+    ///
+    /// This must be called once, before any call to [`Gic::init_cpu()`], and the interrupt
+    /// controller must not be in use by other software.
+    pub unsafe fn init_distributor(&self) {
+        match self {
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            Self::V2 { distributor, .. } => unsafe { distributor.init() },
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            Self::V3 { distributor, .. } => unsafe { distributor.init(Affinity::current()) },
+        }
+    }
+
+    /// Initializes the interface between the GIC and the current processor, after which
+    /// interrupts are signaled to the processor once unmasked in `PSTATE`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the GIC has no redistributor for the current processor.
+    ///
+    /// # Safety
+    ///
+    /// [`Gic::init_distributor()`] must have been called, and every enabled interrupt must have a
+    /// handler.
+    pub unsafe fn init_cpu(&self) {
+        match self {
+            Self::V2 {
+                distributor,
+                cpu_interface,
+            } => {
+                // SAFETY:
+                //
+                // The invariants of this function ensure that this operation is safe.
+                unsafe { distributor.init_private() }
+                // SAFETY:
+                //
+                // The invariants of this function ensure that this operation is safe.
+                unsafe { cpu_interface.init() }
+            }
+            Self::V3 { redistributors, .. } => {
+                let redistributor = redistributors
+                    .find(Affinity::current())
+                    .expect("no redistributor for the current processor");
+
+                // SAFETY:
+                //
+                // The invariants of this function ensure that this operation is safe.
+                unsafe { redistributor.init() }
+                // SAFETY:
+                //
+                // The invariants of this function ensure that this operation is safe.
+                unsafe { v3::CpuInterface::init() }
+            }
+        }
+    }
+
+    /// Enables or disables forwarding of the interrupt `id` to the current processor, or to its
+    /// target processors if `id` is a shared peripheral interrupt.
+    ///
+    /// # Safety
+    ///
+    /// If `enabled` is `true`, the interrupt must have a handler.
+    pub unsafe fn set_enabled(&self, id: InterruptId, enabled: bool) {
+        match self {
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            Self::V2 { distributor, .. } => unsafe { distributor.set_enabled(id, enabled) },
+            Self::V3 {
+                distributor,
+                redistributors,
+            } => {
+                if id.is_private() {
+                    // SAFETY:
+                    //
+                    // The invariants of this function ensure that this operation is safe.
+                    unsafe { current_redistributor(redistributors).set_enabled(id, enabled) }
+                } else {
+                    // SAFETY:
+                    //
+                    // The invariants of this function ensure that this operation is safe.
+                    unsafe { distributor.set_enabled(id, enabled) }
+                }
+            }
+        }
+    }
+
+    /// Sets the priority of the interrupt `id`, where lower values are higher priorities.
+    pub fn set_priority(&self, id: InterruptId, priority: u8) {
+        match self {
+            Self::V2 { distributor, .. } => distributor.set_priority(id, priority),
+            Self::V3 {
+                distributor,
+                redistributors,
+            } => {
+                if id.is_private() {
+                    current_redistributor(redistributors).set_priority(id, priority);
+                } else {
+                    distributor.set_priority(id, priority);
+                }
+            }
+        }
+    }
+
+    /// Sets the [`Trigger`] of the peripheral interrupt `id`.
+    pub fn set_trigger(&self, id: InterruptId, trigger: Trigger) {
+        match self {
+            Self::V2 { distributor, .. } => distributor.set_trigger(id, trigger),
+            Self::V3 {
+                distributor,
+                redistributors,
+            } => {
+                if id.is_private() {
+                    current_redistributor(redistributors).set_trigger(id, trigger);
+                } else {
+                    distributor.set_trigger(id, trigger);
+                }
+            }
+        }
+    }
+
+    /// Routes the shared peripheral interrupt `id` to `target`.
+    pub fn route_spi(&self, id: InterruptId, target: Target) {
+        match self {
+            Self::V2 { distributor, .. } => {
+                distributor.set_targets(id, 1 << (target.cpu_interface & 0b111));
+            }
+            Self::V3 { distributor, .. } => distributor.set_route(id, target.affinity),
+        }
+    }
+
+    /// Sends the software generated interrupt `sgi` to `target`.
+    ///
+    /// # Safety
+    ///
+    /// The target processors must be able to handle the interrupt.
+    pub unsafe fn send_sgi(&self, sgi: InterruptId, target: SgiTarget) {
+        match self {
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            Self::V2 { distributor, .. } => unsafe { distributor.send_sgi(sgi, target) },
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            Self::V3 { .. } => unsafe { v3::CpuInterface::send_sgi(sgi, target) },
+        }
+    }
+
+    /// Acknowledges the highest priority pending interrupt, returning [`InterruptId::SPURIOUS`] if
+    /// no interrupt is pending.
+    ///
+    /// # Safety
+    ///
+    /// Every acknowledged interrupt other than [`InterruptId::SPURIOUS`] must be completed with
+    /// [`Gic::end_of_interrupt()`].
+    pub unsafe fn acknowledge(&self) -> InterruptId {
+        match self {
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            Self::V2 { cpu_interface, .. } => unsafe { cpu_interface.acknowledge() },
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            Self::V3 { .. } => unsafe { v3::CpuInterface::acknowledge() },
+        }
+    }
+
+    /// Signals the completion of the handling of the interrupt `id`.
+    ///
+    /// # Safety
+    ///
+    /// `id` must have been returned by [`Gic::acknowledge()`] on the current processor and not
+    /// yet completed.
+    pub unsafe fn end_of_interrupt(&self, id: InterruptId) {
+        match self {
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            Self::V2 { cpu_interface, .. } => unsafe { cpu_interface.end_of_interrupt(id) },
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            Self::V3 { .. } => unsafe { v3::CpuInterface::end_of_interrupt(id) },
+        }
+    }
+}
+
+/// Returns the [`v3::Redistributor`] of the current processor.
+#[cfg(target_arch = "aarch64")]
+fn current_redistributor(redistributors: &v3::RedistributorRegion) -> v3::Redistributor {
+    redistributors
+        .find(Affinity::current())
+        .expect("no redistributor for the current processor")
+}
+
+/// A block of memory-mapped registers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Mmio(*mut u8);
+
+// SAFETY:
+//
+// [`Mmio`] only performs volatile accesses to device registers, whose synchronization is the
+// responsibility of the unsafe functions that access them.
+unsafe impl Send for Mmio {}
+// SAFETY:
+//
+// [`Mmio`] only performs volatile accesses to device registers, whose synchronization is the
+// responsibility of the unsafe functions that access them.
+unsafe impl Sync for Mmio {}
+
+impl Mmio {
+    /// Reads the 8-bit register at `offset`.
+    ///
+    /// # Safety
+    ///
+    /// The register must be mapped and reading it must not violate system invariants.
+    unsafe fn read8(self, offset: usize) -> u8 {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { self.0.wrapping_add(offset).read_volatile() }
+    }
+
+    /// Writes `value` to the 8-bit register at `offset`.
+    ///
+    /// # Safety
+    ///
+    /// The register must be mapped and writing it must not violate system invariants.
+    unsafe fn write8(self, offset: usize, value: u8) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { self.0.wrapping_add(offset).write_volatile(value) }
+    }
+
+    /// Reads the 32-bit register at `offset`.
+    ///
+    /// # Safety
+    ///
+    /// The register must be mapped and reading it must not violate system invariants.
+    unsafe fn read32(self, offset: usize) -> u32 {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { self.0.wrapping_add(offset).cast::<u32>().read_volatile() }
+    }
+
+    /// Writes `value` to the 32-bit register at `offset`.
+    ///
+    /// # Safety
+    ///
+    /// The register must be mapped and writing it must not violate system invariants.
+    unsafe fn write32(self, offset: usize, value: u32) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe {
+            self.0
+                .wrapping_add(offset)
+                .cast::<u32>()
+                .write_volatile(value)
+        }
+    }
+
+    /// Reads the 64-bit register at `offset`.
+    ///
+    /// # Safety
+    ///
+    /// The register must be mapped and reading it must not violate system invariants.
+    unsafe fn read64(self, offset: usize) -> u64 {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { self.0.wrapping_add(offset).cast::<u64>().read_volatile() }
+    }
+
+    /// Writes `value` to the 64-bit register at `offset`.
+    ///
+    /// # Safety
+    ///
+    /// The register must be mapped and writing it must not violate system invariants.
+    unsafe fn write64(self, offset: usize, value: u64) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe {
+            self.0
+                .wrapping_add(offset)
+                .cast::<u64>()
+                .write_volatile(value)
+        }
+    }
+}
+
+/// Shared accessors for the interrupt configuration registers that are laid out identically in
+/// the GICv2 distributor, the GICv3 distributor, and the GICv3 redistributor `SGI_base` frame.
+mod registers {
+    use conversion::u32_to_usize_strict;
+
+    use crate::{InterruptId, Mmio, Trigger};
+
+    /// The offset of the `IGROUPR<n>` registers.
+    pub const IGROUPR: usize = 0x080;
+    /// The offset of the `ISENABLER<n>` registers.
+    pub const ISENABLER: usize = 0x100;
+    /// The offset of the `ICENABLER<n>` registers.
+    pub const ICENABLER: usize = 0x180;
+    /// The offset of the `ICPENDR<n>` registers.
+    pub const ICPENDR: usize = 0x280;
+    /// The offset of the `ICACTIVER<n>` registers.
+    pub const ICACTIVER: usize = 0x380;
+    /// The offset of the `IPRIORITYR<n>` registers.
+    pub const IPRIORITYR: usize = 0x400;
+    /// The offset of the `ICFGR<n>` registers.
+    pub const ICFGR: usize = 0xC00;
+
+    /// Returns the offset of the 32-bit register containing the bit of `id` in a bitmap starting
+    /// at `base`, and the mask of that bit.
+    pub fn bit(base: usize, id: InterruptId) -> (usize, u32) {
+        let id = id.value();
+        (base + 4 * u32_to_usize_strict(id / 32), 1 << (id % 32))
+    }
+
+    /// Enables or disables forwarding of `id`.
+    ///
+    /// # Safety
+    ///
+    /// The registers must be mapped, and if `enabled` is `true`, `id` must have a handler.
+    pub unsafe fn set_enabled(registers: Mmio, id: InterruptId, enabled: bool) {
+        let (offset, mask) = bit(if enabled { ISENABLER } else { ICENABLER }, id);
+
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { registers.write32(offset, mask) }
+    }
+
+    /// Sets the priority of `id`.
+    ///
+    /// # Safety
+    ///
+    /// The registers must be mapped.
+    pub unsafe fn set_priority(registers: Mmio, id: InterruptId, priority: u8) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { registers.write8(IPRIORITYR + u32_to_usize_strict(id.value()), priority) }
+    }
+
+    /// Sets the [`Trigger`] of `id`.
+    ///
+    /// # Safety
+    ///
+    /// The registers must be mapped.
+    pub unsafe fn set_trigger(registers: Mmio, id: InterruptId, trigger: Trigger) {
+        let offset = ICFGR + 4 * u32_to_usize_strict(id.value() / 16);
+        let shift = 2 * (id.value() % 16) + 1;
+
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        let value = unsafe { registers.read32(offset) };
+        let value = match trigger {
+            Trigger::Level => value & !(1 << shift),
+            Trigger::Edge => value | (1 << shift),
+        };
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { registers.write32(offset, value) }
+    }
+
+    /// Disables, deactivates, and clears the pending state of every interrupt in
+    /// `first..first + count`, assigns them [`DEFAULT_PRIORITY`][dp] and group 1, and makes
+    /// peripheral interrupts level-triggered.
+    ///
+    /// `first` and `count` must be multiples of 32.
+    ///
+    /// # Safety
+    ///
+    /// The registers must be mapped.
+    ///
+    /// [dp]: crate::DEFAULT_PRIORITY
+    pub unsafe fn reset_range(registers: Mmio, first: u32, count: u32) {
+        for id in (first..first + count).step_by(32) {
+            let (offset, _) = bit(0, InterruptId::new(id));
+
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            unsafe { registers.write32(ICENABLER + offset, u32::MAX) }
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            unsafe { registers.write32(ICPENDR + offset, u32::MAX) }
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            unsafe { registers.write32(ICACTIVER + offset, u32::MAX) }
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            unsafe { registers.write32(IGROUPR + offset, u32::MAX) }
+        }
+
+        for id in first..first + count {
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            unsafe { set_priority(registers, InterruptId::new(id), crate::DEFAULT_PRIORITY) }
+        }
+
+        // Software generated interrupts are always edge-triggered.
+        for id in (first.max(InterruptId::PPI_START)..first + count).step_by(16) {
+            let offset = ICFGR + 4 * u32_to_usize_strict(id / 16);
+
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            unsafe { registers.write32(offset, 0) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn interrupt_kinds() {
+        let cases = [
+            (0, InterruptKind::Sgi),
+            (15, InterruptKind::Sgi),
+            (16, InterruptKind::Ppi),
+            (30, InterruptKind::Ppi),
+            (32, InterruptKind::Spi),
+            (1019, InterruptKind::Spi),
+            (1020, InterruptKind::Special),
+            (1023, InterruptKind::Special),
+            (1024, InterruptKind::Reserved),
+            (8192, InterruptKind::Lpi),
+        ];
+
+        for (id, kind) in cases {
+            assert_eq!(InterruptId::new(id).kind(), kind, "{id}");
+        }
+
+        assert_eq!(InterruptId::ppi(14), InterruptId::EL1_PHYSICAL_TIMER);
+        assert_eq!(InterruptId::spi(1).value(), 33);
+        assert!(InterruptId::sgi(3).is_private());
+        assert!(!InterruptId::spi(0).is_private());
+    }
+
+    #[test]
+    fn affinity() {
+        let affinity = Affinity::from_mpidr(0x8000_0012_8100_0304);
+        assert_eq!(affinity, Affinity::new(0x12, 0x00, 0x03, 0x04));
+        assert_eq!(affinity.packed(), 0x1200_0304);
+    }
+
+    #[test]
+    fn register_bits() {
+        assert_eq!(
+            registers::bit(registers::ISENABLER, InterruptId::new(0)),
+            (0x100, 1)
+        );
+        assert_eq!(
+            registers::bit(registers::ISENABLER, InterruptId::new(33)),
+            (0x104, 1 << 1)
+        );
+        assert_eq!(
+            registers::bit(registers::ICENABLER, InterruptId::new(95)),
+            (0x188, 1 << 31)
+        );
+    }
+}
@@ -0,0 +1,269 @@
+//! Driver for GICv2, whose distributor and CPU interface are both memory-mapped.
+
+use crate::{InterruptId, Mmio, SgiTarget, Trigger, registers};
+
+/// The offset of the `GICD_CTLR` register.
+const GICD_CTLR: usize = 0x000;
+/// The offset of the `GICD_TYPER` register.
+const GICD_TYPER: usize = 0x004;
+/// The offset of the `GICD_ITARGETSR<n>` registers.
+const GICD_ITARGETSR: usize = 0x800;
+/// The offset of the `GICD_SGIR` register.
+const GICD_SGIR: usize = 0xF00;
+
+/// The bit of `GICD_CTLR` that enables forwarding of interrupts to the CPU interfaces.
+const GICD_CTLR_ENABLE: u32 = 1 << 0;
+
+/// The offset of the `GICC_CTLR` register.
+const GICC_CTLR: usize = 0x0000;
+/// The offset of the `GICC_PMR` register.
+const GICC_PMR: usize = 0x0004;
+/// The offset of the `GICC_BPR` register.
+const GICC_BPR: usize = 0x0008;
+/// The offset of the `GICC_IAR` register.
+const GICC_IAR: usize = 0x000C;
+/// The offset of the `GICC_EOIR` register.
+const GICC_EOIR: usize = 0x0010;
+
+/// The bit of `GICC_CTLR` that enables signaling of interrupts to the processor.
+const GICC_CTLR_ENABLE: u32 = 1 << 0;
+
+/// The mask of the interrupt identifier in `GICC_IAR` and `GICC_EOIR`.
+const GICC_IAR_ID_MASK: u32 = 0x3FF;
+
+/// Returns the value of `GICD_SGIR` that sends `sgi` to `target`.
+pub const fn sgir(sgi: InterruptId, target: SgiTarget) -> u32 {
+    let (filter, targets) = match target {
+        SgiTarget::Processor(target) => (0b00, 1u32 << (target.cpu_interface & 0b111)),
+        SgiTarget::AllOthers => (0b01, 0),
+    };
+
+    (filter << 24) | (targets << 16) | (sgi.value() & 0xF)
+}
+
+/// The GICv2 distributor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Distributor(Mmio);
+
+impl Distributor {
+    /// Creates a [`Distributor`] that accesses the distributor through the registers mapped at
+    /// `registers`.
+    ///
+    /// # Safety
+    ///
+    /// `registers` must be a device memory mapping of the 4 KiB distributor register frame, which
+    /// remains valid for as long as the returned [`Distributor`] is used.
+    pub const unsafe fn new(registers: *mut u8) -> Self {
+        Self(Mmio(registers))
+    }
+
+    /// Returns the number of interrupt identifiers supported by the distributor.
+    pub fn interrupt_lines(&self) -> u32 {
+        // SAFETY:
+        //
+        // Reading `GICD_TYPER` has no side effects.
+        let typer = unsafe { self.0.read32(GICD_TYPER) };
+        32 * ((typer & 0x1F) + 1)
+    }
+
+    /// Resets every shared peripheral interrupt, routes them to CPU interface 0, and enables the
+    /// distributor.
+    ///
+    /// # Safety
+    ///
+    /// The distributor must not be in use by other software.
+    pub unsafe fn init(&self) {
+        let lines = self.interrupt_lines().min(InterruptId::SPECIAL_START & !31);
+
+        // SAFETY:
+        //
+        // The invariants of this function ensure that the distributor is not in use.
+        unsafe { self.0.write32(GICD_CTLR, 0) }
+        // SAFETY:
+        //
+        // The invariants of this function ensure that the distributor is not in use.
+        unsafe {
+            registers::reset_range(
+                self.0,
+                InterruptId::SPI_START,
+                lines - InterruptId::SPI_START,
+            )
+        }
+        for id in InterruptId::SPI_START..lines {
+            self.set_targets(InterruptId::new(id), 0b1);
+        }
+        // SAFETY:
+        //
+        // Every shared peripheral interrupt is disabled.
+        unsafe { self.0.write32(GICD_CTLR, GICD_CTLR_ENABLE) }
+    }
+
+    /// Resets the banked software generated and private peripheral interrupts of the current
+    /// processor.
+    ///
+    /// # Safety
+    ///
+    /// The private interrupts of the current processor must not be in use by other software.
+    pub unsafe fn init_private(&self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that the private interrupts are not in use.
+        unsafe { registers::reset_range(self.0, 0, InterruptId::SPI_START) }
+    }
+
+    /// Enables or disables forwarding of the interrupt `id`.
+    ///
+    /// # Safety
+    ///
+    /// If `enabled` is `true`, the interrupt must have a handler.
+    pub unsafe fn set_enabled(&self, id: InterruptId, enabled: bool) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { registers::set_enabled(self.0, id, enabled) }
+    }
+
+    /// Sets the priority of the interrupt `id`.
+    pub fn set_priority(&self, id: InterruptId, priority: u8) {
+        // SAFETY:
+        //
+        // Changing the priority of an interrupt does not affect memory safety.
+        unsafe { registers::set_priority(self.0, id, priority) }
+    }
+
+    /// Sets the [`Trigger`] of the peripheral interrupt `id`.
+    pub fn set_trigger(&self, id: InterruptId, trigger: Trigger) {
+        // SAFETY:
+        //
+        // Changing the trigger of an interrupt does not affect memory safety.
+        unsafe { registers::set_trigger(self.0, id, trigger) }
+    }
+
+    /// Sets the mask of CPU interfaces to which the shared peripheral interrupt `id` is
+    /// forwarded.
+    pub fn set_targets(&self, id: InterruptId, targets: u8) {
+        let offset = GICD_ITARGETSR + conversion::u32_to_usize_strict(id.value());
+
+        // SAFETY:
+        //
+        // Changing the targets of an interrupt does not affect memory safety.
+        unsafe { self.0.write8(offset, targets) }
+    }
+
+    /// Returns the mask of CPU interfaces to which the interrupt `id` is forwarded.
+    ///
+    /// For private interrupts, this returns the CPU interface of the current processor.
+    pub fn targets(&self, id: InterruptId) -> u8 {
+        let offset = GICD_ITARGETSR + conversion::u32_to_usize_strict(id.value());
+
+        // SAFETY:
+        //
+        // Reading `GICD_ITARGETSR<n>` has no side effects.
+        unsafe { self.0.read8(offset) }
+    }
+
+    /// Sends the software generated interrupt `sgi` to `target`.
+    ///
+    /// # Safety
+    ///
+    /// The target processors must be able to handle the interrupt.
+    pub unsafe fn send_sgi(&self, sgi: InterruptId, target: SgiTarget) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { self.0.write32(GICD_SGIR, sgir(sgi, target)) }
+    }
+}
+
+/// The GICv2 CPU interface, which is banked per processor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CpuInterface(Mmio);
+
+impl CpuInterface {
+    /// Creates a [`CpuInterface`] that accesses the CPU interface through the registers mapped at
+    /// `registers`.
+    ///
+    /// # Safety
+    ///
+    /// `registers` must be a device memory mapping of the 8 KiB CPU interface register frame,
+    /// which remains valid for as long as the returned [`CpuInterface`] is used.
+    pub const unsafe fn new(registers: *mut u8) -> Self {
+        Self(Mmio(registers))
+    }
+
+    /// Unmasks every priority and enables signaling of interrupts to the current processor.
+    ///
+    /// # Safety
+    ///
+    /// Every enabled interrupt must have a handler.
+    pub unsafe fn init(&self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { self.0.write32(GICC_PMR, 0xFF) }
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { self.0.write32(GICC_BPR, 0) }
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { self.0.write32(GICC_CTLR, GICC_CTLR_ENABLE) }
+    }
+
+    /// Acknowledges the highest priority pending interrupt.
+    ///
+    /// # Safety
+    ///
+    /// Every acknowledged interrupt other than [`InterruptId::SPURIOUS`] must be completed with
+    /// [`CpuInterface::end_of_interrupt()`].
+    pub unsafe fn acknowledge(&self) -> InterruptId {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        InterruptId::new(unsafe { self.0.read32(GICC_IAR) } & GICC_IAR_ID_MASK)
+    }
+
+    /// Signals the completion of the handling of the interrupt `id`.
+    ///
+    /// # Safety
+    ///
+    /// `id` must have been acknowledged on the current processor and not yet completed.
+    pub unsafe fn end_of_interrupt(&self, id: InterruptId) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { self.0.write32(GICC_EOIR, id.value()) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Affinity, Target};
+
+    #[test]
+    fn sgi_register() {
+        let target = Target {
+            affinity: Affinity::new(0, 0, 0, 2),
+            cpu_interface: 2,
+        };
+        let cases = [
+            (
+                InterruptId::sgi(0),
+                SgiTarget::Processor(target),
+                0x0004_0000,
+            ),
+            (
+                InterruptId::sgi(7),
+                SgiTarget::Processor(target),
+                0x0004_0007,
+            ),
+            (InterruptId::sgi(15), SgiTarget::AllOthers, 0x0100_000F),
+        ];
+
+        for (sgi, target, bits) in cases {
+            assert_eq!(sgir(sgi, target), bits, "{sgi} {target:?}");
+        }
+    }
+}
@@ -0,0 +1,478 @@
+//! Driver for GICv3 and GICv4, whose CPU interface is accessed through the `ICC_*_EL1` system
+//! registers.
+//!
+//! The system register interface and affinity routing are always enabled, and every interrupt is
+//! placed in non-secure group 1.
+
+#[cfg(target_arch = "aarch64")]
+use aarch64::msr::raw;
+
+use crate::{Affinity, InterruptId, Mmio, SgiTarget, Trigger, registers};
+
+/// The offset of the `GICD_CTLR` register.
+const GICD_CTLR: usize = 0x0000;
+/// The offset of the `GICD_TYPER` register.
+const GICD_TYPER: usize = 0x0004;
+/// The offset of the `GICD_IROUTER<n>` registers.
+const GICD_IROUTER: usize = 0x6000;
+/// The offset of the `GICD_PIDR2` register.
+const GICD_PIDR2: usize = 0xFFE8;
+
+/// The bit of `GICD_CTLR` that enables forwarding of group 1 interrupts.
+const GICD_CTLR_ENABLE_G1: u32 = 1 << 0;
+/// The bit of `GICD_CTLR` that enables forwarding of non-secure group 1 interrupts.
+const GICD_CTLR_ENABLE_G1A: u32 = 1 << 1;
+/// The bit of `GICD_CTLR` that enables affinity routing.
+const GICD_CTLR_ARE: u32 = 1 << 4;
+/// The bit of `GICD_CTLR` that indicates that a register write is pending.
+const GICD_CTLR_RWP: u32 = 1 << 31;
+
+/// The size, in bytes, of a single redistributor register frame.
+const GICR_FRAME_SIZE: usize = 0x10000;
+/// The offset of the `GICR_CTLR` register.
+const GICR_CTLR: usize = 0x0000;
+/// The offset of the `GICR_TYPER` register.
+const GICR_TYPER: usize = 0x0008;
+/// The offset of the `GICR_WAKER` register.
+const GICR_WAKER: usize = 0x0014;
+
+/// The bit of `GICR_CTLR` that indicates that a register write is pending.
+const GICR_CTLR_RWP: u32 = 1 << 3;
+/// The bit of `GICR_TYPER` that indicates that the redistributor supports virtual LPIs.
+const GICR_TYPER_VLPIS: u64 = 1 << 1;
+/// The bit of `GICR_TYPER` that indicates that the redistributor is the last in its region.
+const GICR_TYPER_LAST: u64 = 1 << 4;
+/// The bit of `GICR_WAKER` that indicates that the processor is asleep.
+const GICR_WAKER_PROCESSOR_SLEEP: u32 = 1 << 1;
+/// The bit of `GICR_WAKER` that indicates that the interface to the processor is quiescent.
+const GICR_WAKER_CHILDREN_ASLEEP: u32 = 1 << 2;
+
+/// The mask of the interrupt identifier in `ICC_IAR1_EL1` and `ICC_EOIR1_EL1`.
+#[cfg(target_arch = "aarch64")]
+const ICC_IAR_ID_MASK: u64 = 0xFF_FFFF;
+
+/// Returns the value of `ICC_SGI1R_EL1` that sends `sgi` to `target`.
+pub const fn sgi1r(sgi: InterruptId, target: SgiTarget) -> u64 {
+    let id = ((sgi.value() & 0xF) as u64) << 24;
+    match target {
+        SgiTarget::Processor(target) => {
+            let affinity = target.affinity;
+            let range = (affinity.aff0() / 16) as u64;
+            let list = 1u64 << (affinity.aff0() % 16);
+
+            ((affinity.aff3() as u64) << 48)
+                | (range << 44)
+                | ((affinity.aff2() as u64) << 32)
+                | id
+                | ((affinity.aff1() as u64) << 16)
+                | list
+        }
+        SgiTarget::AllOthers => (1 << 40) | id,
+    }
+}
+
+/// The GICv3 distributor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Distributor(Mmio);
+
+impl Distributor {
+    /// Creates a [`Distributor`] that accesses the distributor through the registers mapped at
+    /// `registers`.
+    ///
+    /// # Safety
+    ///
+    /// `registers` must be a device memory mapping of the 64 KiB distributor register frame,
+    /// which remains valid for as long as the returned [`Distributor`] is used.
+    pub const unsafe fn new(registers: *mut u8) -> Self {
+        Self(Mmio(registers))
+    }
+
+    /// Returns the architecture revision reported by `GICD_PIDR2`.
+    pub fn architecture_revision(&self) -> u8 {
+        // SAFETY:
+        //
+        // Reading `GICD_PIDR2` has no side effects.
+        let pidr2 = unsafe { self.0.read32(GICD_PIDR2) };
+        ((pidr2 >> 4) & 0xF) as u8
+    }
+
+    /// Returns the number of interrupt identifiers supported by the distributor, excluding LPIs.
+    pub fn interrupt_lines(&self) -> u32 {
+        // SAFETY:
+        //
+        // Reading `GICD_TYPER` has no side effects.
+        let typer = unsafe { self.0.read32(GICD_TYPER) };
+        32 * ((typer & 0x1F) + 1)
+    }
+
+    /// Resets every shared peripheral interrupt, routes them to the processor with `affinity`,
+    /// and enables the distributor with affinity routing.
+    ///
+    /// # Safety
+    ///
+    /// The distributor must not be in use by other software.
+    pub unsafe fn init(&self, affinity: Affinity) {
+        let lines = self.interrupt_lines().min(InterruptId::SPECIAL_START & !31);
+
+        // SAFETY:
+        //
+        // The invariants of this function ensure that the distributor is not in use.
+        unsafe { self.0.write32(GICD_CTLR, 0) }
+        self.wait_for_writes();
+
+        // SAFETY:
+        //
+        // The invariants of this function ensure that the distributor is not in use.
+        unsafe {
+            registers::reset_range(
+                self.0,
+                InterruptId::SPI_START,
+                lines - InterruptId::SPI_START,
+            )
+        }
+        for id in InterruptId::SPI_START..lines {
+            self.set_route(InterruptId::new(id), affinity);
+        }
+        self.wait_for_writes();
+
+        // SAFETY:
+        //
+        // Every shared peripheral interrupt is disabled.
+        unsafe {
+            self.0.write32(
+                GICD_CTLR,
+                GICD_CTLR_ARE | GICD_CTLR_ENABLE_G1A | GICD_CTLR_ENABLE_G1,
+            )
+        }
+        self.wait_for_writes();
+    }
+
+    /// Enables or disables forwarding of the shared peripheral interrupt `id`.
+    ///
+    /// # Safety
+    ///
+    /// If `enabled` is `true`, the interrupt must have a handler.
+    pub unsafe fn set_enabled(&self, id: InterruptId, enabled: bool) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { registers::set_enabled(self.0, id, enabled) }
+        if !enabled {
+            self.wait_for_writes();
+        }
+    }
+
+    /// Sets the priority of the shared peripheral interrupt `id`.
+    pub fn set_priority(&self, id: InterruptId, priority: u8) {
+        // SAFETY:
+        //
+        // Changing the priority of an interrupt does not affect memory safety.
+        unsafe { registers::set_priority(self.0, id, priority) }
+    }
+
+    /// Sets the [`Trigger`] of the shared peripheral interrupt `id`.
+    pub fn set_trigger(&self, id: InterruptId, trigger: Trigger) {
+        // SAFETY:
+        //
+        // Changing the trigger of an interrupt does not affect memory safety.
+        unsafe { registers::set_trigger(self.0, id, trigger) }
+    }
+
+    /// Routes the shared peripheral interrupt `id` to the processor with `affinity`.
+    pub fn set_route(&self, id: InterruptId, affinity: Affinity) {
+        let offset = GICD_IROUTER + 8 * conversion::u32_to_usize_strict(id.value());
+        let route = (u64::from(affinity.aff3()) << 32)
+            | (u64::from(affinity.aff2()) << 16)
+            | (u64::from(affinity.aff1()) << 8)
+            | u64::from(affinity.aff0());
+
+        // SAFETY:
+        //
+        // Changing the route of an interrupt does not affect memory safety.
+        unsafe { self.0.write64(offset, route) }
+    }
+
+    /// Waits until the effects of previous writes to `GICD_CTLR` and `GICD_ICENABLER<n>` are
+    /// visible.
+    fn wait_for_writes(&self) {
+        // SAFETY:
+        //
+        // Reading `GICD_CTLR` has no side effects.
+        while unsafe { self.0.read32(GICD_CTLR) } & GICD_CTLR_RWP != 0 {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// A contiguous region of redistributors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RedistributorRegion {
+    /// The registers of the region.
+    registers: Mmio,
+    /// The size, in bytes, of the region.
+    size: usize,
+}
+
+impl RedistributorRegion {
+    /// Creates a [`RedistributorRegion`] that accesses the redistributors mapped at `registers`.
+    ///
+    /// # Safety
+    ///
+    /// `registers` must be a device memory mapping of the `size` bytes of the redistributor
+    /// region, which remains valid for as long as the returned [`RedistributorRegion`] is used.
+    pub const unsafe fn new(registers: *mut u8, size: usize) -> Self {
+        Self {
+            registers: Mmio(registers),
+            size,
+        }
+    }
+
+    /// Returns the [`Redistributor`] of the processor with `affinity`, if it is part of the
+    /// region.
+    pub fn find(&self, affinity: Affinity) -> Option<Redistributor> {
+        let mut offset = 0;
+        while offset + 2 * GICR_FRAME_SIZE <= self.size {
+            let frame = Mmio(self.registers.0.wrapping_add(offset));
+
+            // SAFETY:
+            //
+            // The frame lies within the region and reading `GICR_TYPER` has no side effects.
+            let typer = unsafe { frame.read64(GICR_TYPER) };
+            if (typer >> 32) as u32 == affinity.packed() {
+                return Some(Redistributor(frame));
+            }
+            if typer & GICR_TYPER_LAST != 0 {
+                return None;
+            }
+
+            offset += if typer & GICR_TYPER_VLPIS != 0 {
+                4 * GICR_FRAME_SIZE
+            } else {
+                2 * GICR_FRAME_SIZE
+            };
+        }
+
+        None
+    }
+}
+
+/// The redistributor of a single processor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Redistributor(Mmio);
+
+impl Redistributor {
+    /// Returns the `SGI_base` frame of the redistributor.
+    fn sgi_frame(&self) -> Mmio {
+        Mmio(self.0.0.wrapping_add(GICR_FRAME_SIZE))
+    }
+
+    /// Wakes the redistributor and resets the software generated and private peripheral
+    /// interrupts of its processor.
+    ///
+    /// # Safety
+    ///
+    /// The private interrupts of the processor must not be in use by other software.
+    pub unsafe fn init(&self) {
+        // SAFETY:
+        //
+        // Reading `GICR_WAKER` has no side effects.
+        let waker = unsafe { self.0.read32(GICR_WAKER) };
+        // SAFETY:
+        //
+        // Marking the processor as awake does not affect memory safety.
+        unsafe {
+            self.0
+                .write32(GICR_WAKER, waker & !GICR_WAKER_PROCESSOR_SLEEP)
+        }
+        // SAFETY:
+        //
+        // Reading `GICR_WAKER` has no side effects.
+        while unsafe { self.0.read32(GICR_WAKER) } & GICR_WAKER_CHILDREN_ASLEEP != 0 {
+            core::hint::spin_loop();
+        }
+
+        // SAFETY:
+        //
+        // The invariants of this function ensure that the private interrupts are not in use.
+        unsafe { registers::reset_range(self.sgi_frame(), 0, InterruptId::SPI_START) }
+        self.wait_for_writes();
+    }
+
+    /// Enables or disables forwarding of the private interrupt `id`.
+    ///
+    /// # Safety
+    ///
+    /// If `enabled` is `true`, the interrupt must have a handler.
+    pub unsafe fn set_enabled(&self, id: InterruptId, enabled: bool) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { registers::set_enabled(self.sgi_frame(), id, enabled) }
+        if !enabled {
+            self.wait_for_writes();
+        }
+    }
+
+    /// Sets the priority of the private interrupt `id`.
+    pub fn set_priority(&self, id: InterruptId, priority: u8) {
+        // SAFETY:
+        //
+        // Changing the priority of an interrupt does not affect memory safety.
+        unsafe { registers::set_priority(self.sgi_frame(), id, priority) }
+    }
+
+    /// Sets the [`Trigger`] of the private peripheral interrupt `id`.
+    pub fn set_trigger(&self, id: InterruptId, trigger: Trigger) {
+        // SAFETY:
+        //
+        // Changing the trigger of an interrupt does not affect memory safety.
+        unsafe { registers::set_trigger(self.sgi_frame(), id, trigger) }
+    }
+
+    /// Waits until the effects of previous writes to `GICR_ICENABLER0` are visible.
+    fn wait_for_writes(&self) {
+        // SAFETY:
+        //
+        // Reading `GICR_CTLR` has no side effects.
+        while unsafe { self.0.read32(GICR_CTLR) } & GICR_CTLR_RWP != 0 {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// The system register CPU interface of the current processor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CpuInterface;
+
+#[cfg(target_arch = "aarch64")]
+impl CpuInterface {
+    /// Enables the system register interface, unmasks every priority, and enables signaling of
+    /// group 1 interrupts to the current processor.
+    ///
+    /// # Safety
+    ///
+    /// Every enabled interrupt must have a handler.
+    pub unsafe fn init() {
+        // SAFETY:
+        //
+        // Reading `ICC_SRE_EL1` has no side effects.
+        let sre = unsafe { raw::read_icc_sre_el1() };
+        // SAFETY:
+        //
+        // Enabling the system register interface does not affect memory safety.
+        unsafe { raw::write_icc_sre_el1(sre | 0b1) }
+        isb();
+
+        // SAFETY:
+        //
+        // Unmasking priorities does not signal interrupts until group 1 is enabled.
+        unsafe { raw::write_icc_pmr_el1(0xFF) }
+        // SAFETY:
+        //
+        // Changing the binary point does not affect memory safety.
+        unsafe { raw::write_icc_bpr1_el1(0) }
+        // SAFETY:
+        //
+        // Selecting combined priority drop and deactivation does not affect memory safety.
+        unsafe { raw::write_icc_ctlr_el1(0) }
+        // SAFETY:
+        //
+        // The invariants of this function ensure that every enabled interrupt has a handler.
+        unsafe { raw::write_icc_igrpen1_el1(1) }
+        isb();
+    }
+
+    /// Sends the software generated interrupt `sgi` to `target`.
+    ///
+    /// # Safety
+    ///
+    /// The target processors must be able to handle the interrupt.
+    pub unsafe fn send_sgi(sgi: InterruptId, target: SgiTarget) {
+        // SAFETY:
+        //
+        // A store barrier does not affect memory safety.
+        unsafe { core::arch::asm!("dsb ishst", options(nostack, preserves_flags)) }
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { raw::write_icc_sgi1r_el1(sgi1r(sgi, target)) }
+        isb();
+    }
+
+    /// Acknowledges the highest priority pending group 1 interrupt.
+    ///
+    /// # Safety
+    ///
+    /// Every acknowledged interrupt other than [`InterruptId::SPURIOUS`] must be completed with
+    /// [`CpuInterface::end_of_interrupt()`].
+    pub unsafe fn acknowledge() -> InterruptId {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        let iar = unsafe { raw::read_icc_iar1_el1() };
+        InterruptId::new((iar & ICC_IAR_ID_MASK) as u32)
+    }
+
+    /// Signals the completion of the handling of the interrupt `id`.
+    ///
+    /// # Safety
+    ///
+    /// `id` must have been acknowledged on the current processor and not yet completed.
+    pub unsafe fn end_of_interrupt(id: InterruptId) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { raw::write_icc_eoir1_el1(u64::from(id.value())) }
+    }
+}
+
+/// Synchronizes the context of the current processor with previous system register writes.
+#[cfg(target_arch = "aarch64")]
+fn isb() {
+    // SAFETY:
+    //
+    // An instruction synchronization barrier does not affect memory safety.
+    unsafe { core::arch::asm!("isb", options(nomem, nostack, preserves_flags)) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Target;
+
+    #[test]
+    fn sgi_register() {
+        let target = |affinity| {
+            SgiTarget::Processor(Target {
+                affinity,
+                cpu_interface: 0,
+            })
+        };
+        let cases = [
+            (
+                InterruptId::sgi(1),
+                target(Affinity::new(0, 0, 0, 0)),
+                0x0000_0000_0100_0001,
+            ),
+            (
+                InterruptId::sgi(15),
+                target(Affinity::new(0, 0, 1, 3)),
+                0x0000_0000_0F01_0008,
+            ),
+            (
+                InterruptId::sgi(2),
+                target(Affinity::new(4, 3, 2, 17)),
+                0x0004_1003_0202_0002,
+            ),
+            (
+                InterruptId::sgi(5),
+                SgiTarget::AllOthers,
+                0x0000_0100_0500_0000,
+            ),
+        ];
+
+        for (sgi, target, bits) in cases {
+            assert_eq!(sgi1r(sgi, target), bits, "{sgi} {target:?}");
+        }
+    }
+}
@@ -0,0 +1,1141 @@
+//! Local Advanced Programmable Interrupt Controller (APIC) structures and functions.
+//!
+//! The local APIC is accessed either through a memory-mapped register page (xAPIC mode) or
+//! through a range of MSRs (x2APIC mode). [`LocalApic`] abstracts over both access methods.
+
+#![cfg_attr(
+    not(test),
+    expect(
+        clippy::missing_docs_in_private_items,
+        reason = "bit shift/masking is documented in the register structures"
+    )
+)]
+
+use core::fmt;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::{
+    cpuid::{cpuid_unchecked, supports_cpuid},
+    msr::{read_msr, write_msr},
+};
+
+/// The `IA32_APIC_BASE` MSR.
+pub const IA32_APIC_BASE: u32 = 0x1B;
+/// The `IA32_TSC_DEADLINE` MSR.
+pub const IA32_TSC_DEADLINE: u32 = 0x6E0;
+/// The first MSR of the x2APIC register range.
+const X2APIC_MSR_BASE: u32 = 0x800;
+
+const BASE_BSP_SHIFT: u32 = 8;
+const BASE_BSP_BIT: u64 = 1 << BASE_BSP_SHIFT;
+
+const BASE_X2APIC_SHIFT: u32 = 10;
+const BASE_X2APIC_BIT: u64 = 1 << BASE_X2APIC_SHIFT;
+
+const BASE_ENABLE_SHIFT: u32 = 11;
+const BASE_ENABLE_BIT: u64 = 1 << BASE_ENABLE_SHIFT;
+
+const BASE_ADDRESS_MASK: u64 = 0x000F_FFFF_FFFF_F000;
+
+const VECTOR_MASK: u32 = 0xFF;
+
+const VERSION_MASK: u32 = 0xFF;
+const VERSION_MAX_LVT_SHIFT: u32 = 16;
+const VERSION_MAX_LVT_MASK: u32 = 0xFF << VERSION_MAX_LVT_SHIFT;
+const VERSION_EOI_SUPPRESSION_SHIFT: u32 = 24;
+const VERSION_EOI_SUPPRESSION_BIT: u32 = 1 << VERSION_EOI_SUPPRESSION_SHIFT;
+
+const SVR_ENABLE_SHIFT: u32 = 8;
+const SVR_ENABLE_BIT: u32 = 1 << SVR_ENABLE_SHIFT;
+const SVR_EOI_SUPPRESSION_SHIFT: u32 = 12;
+const SVR_EOI_SUPPRESSION_BIT: u32 = 1 << SVR_EOI_SUPPRESSION_SHIFT;
+
+const LVT_DELIVERY_STATUS_SHIFT: u32 = 12;
+const LVT_DELIVERY_STATUS_BIT: u32 = 1 << LVT_DELIVERY_STATUS_SHIFT;
+const LVT_MASK_SHIFT: u32 = 16;
+const LVT_MASK_BIT: u32 = 1 << LVT_MASK_SHIFT;
+const LVT_TIMER_MODE_SHIFT: u32 = 17;
+const LVT_TIMER_MODE_MASK: u32 = 0b11 << LVT_TIMER_MODE_SHIFT;
+
+const ICR_VECTOR_MASK: u64 = 0xFF;
+const ICR_DELIVERY_MODE_SHIFT: u32 = 8;
+const ICR_DELIVERY_MODE_MASK: u64 = 0b111 << ICR_DELIVERY_MODE_SHIFT;
+const ICR_LOGICAL_SHIFT: u32 = 11;
+const ICR_LOGICAL_BIT: u64 = 1 << ICR_LOGICAL_SHIFT;
+const ICR_DELIVERY_STATUS_SHIFT: u32 = 12;
+const ICR_DELIVERY_STATUS_BIT: u64 = 1 << ICR_DELIVERY_STATUS_SHIFT;
+const ICR_ASSERT_SHIFT: u32 = 14;
+const ICR_ASSERT_BIT: u64 = 1 << ICR_ASSERT_SHIFT;
+const ICR_LEVEL_TRIGGERED_SHIFT: u32 = 15;
+const ICR_LEVEL_TRIGGERED_BIT: u64 = 1 << ICR_LEVEL_TRIGGERED_SHIFT;
+const ICR_SHORTHAND_SHIFT: u32 = 18;
+const ICR_SHORTHAND_MASK: u64 = 0b11 << ICR_SHORTHAND_SHIFT;
+const ICR_DESTINATION_SHIFT: u32 = 32;
+const ICR_DESTINATION_MASK: u64 = 0xFFFF_FFFF << ICR_DESTINATION_SHIFT;
+
+/// The shift of the destination field in the high half of the xAPIC interrupt command register.
+const XAPIC_DESTINATION_SHIFT: u32 = 24;
+
+/// Returns `true` if the processor has a local APIC.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn supports_apic() -> bool {
+    // SAFETY:
+    //
+    // The CPUID instruction is supported.
+    supports_cpuid() && (unsafe { cpuid_unchecked(1, 0) }.edx >> 9) & 1 == 1
+}
+
+/// Returns `true` if the processor's local APIC supports x2APIC mode.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn supports_x2apic() -> bool {
+    // SAFETY:
+    //
+    // The CPUID instruction is supported.
+    supports_cpuid() && (unsafe { cpuid_unchecked(1, 0) }.ecx >> 21) & 1 == 1
+}
+
+/// Returns `true` if the processor's local APIC timer supports [`TimerMode::TscDeadline`].
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn supports_tsc_deadline() -> bool {
+    // SAFETY:
+    //
+    // The CPUID instruction is supported.
+    supports_cpuid() && (unsafe { cpuid_unchecked(1, 0) }.ecx >> 24) & 1 == 1
+}
+
+/// The state of the `IA32_APIC_BASE` MSR.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct ApicBase(u64);
+
+impl ApicBase {
+    /// Returns the value of the `IA32_APIC_BASE` MSR.
+    ///
+    /// # Safety
+    ///
+    /// The processor must have a local APIC and support MSRs.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub unsafe fn get() -> Self {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that the MSR exists.
+        Self(unsafe { read_msr(IA32_APIC_BASE) })
+    }
+
+    /// Writes `self` to the `IA32_APIC_BASE` MSR.
+    ///
+    /// # Safety
+    ///
+    /// The processor must have a local APIC and support MSRs, and the new configuration must be a
+    /// valid transition from the current state of the local APIC.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub unsafe fn set(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { write_msr(IA32_APIC_BASE, self.0) }
+    }
+
+    /// Constructs a new [`ApicBase`] from the bit representation.
+    pub const fn from_bits(raw: u64) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the bit representation of the [`ApicBase`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns `true` if the current processor is the bootstrap processor.
+    pub const fn bsp(self) -> bool {
+        self.0 & BASE_BSP_BIT == BASE_BSP_BIT
+    }
+
+    /// Returns `true` if x2APIC mode is enabled.
+    pub const fn x2apic(self) -> bool {
+        self.0 & BASE_X2APIC_BIT == BASE_X2APIC_BIT
+    }
+
+    /// Sets whether x2APIC mode is enabled.
+    pub const fn set_x2apic(self, x2apic: bool) -> Self {
+        Self((self.0 & !BASE_X2APIC_BIT) | (bool_as_u64(x2apic) << BASE_X2APIC_SHIFT))
+    }
+
+    /// Returns `true` if the local APIC is globally enabled.
+    pub const fn enabled(self) -> bool {
+        self.0 & BASE_ENABLE_BIT == BASE_ENABLE_BIT
+    }
+
+    /// Sets whether the local APIC is globally enabled.
+    pub const fn set_enabled(self, enabled: bool) -> Self {
+        Self((self.0 & !BASE_ENABLE_BIT) | (bool_as_u64(enabled) << BASE_ENABLE_SHIFT))
+    }
+
+    /// Returns the physical address of the xAPIC register page.
+    pub const fn address(self) -> u64 {
+        self.0 & BASE_ADDRESS_MASK
+    }
+
+    /// Sets the physical address of the xAPIC register page.
+    ///
+    /// `address` must be aligned to a 4 KiB boundary.
+    pub const fn set_address(self, address: u64) -> Self {
+        Self((self.0 & !BASE_ADDRESS_MASK) | (address & BASE_ADDRESS_MASK))
+    }
+}
+
+/// The registers of the local APIC, identified by their offset in the xAPIC register page.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Register {
+    /// The local APIC ID register.
+    Id = 0x20,
+    /// The local APIC version register.
+    Version = 0x30,
+    /// The task priority register.
+    TaskPriority = 0x80,
+    /// The end of interrupt register.
+    Eoi = 0xB0,
+    /// The logical destination register.
+    LogicalDestination = 0xD0,
+    /// The spurious interrupt vector register.
+    SpuriousInterruptVector = 0xF0,
+    /// The error status register.
+    ErrorStatus = 0x280,
+    /// The lower half of the interrupt command register.
+    InterruptCommandLow = 0x300,
+    /// The upper half of the interrupt command register, which is not present in x2APIC mode.
+    InterruptCommandHigh = 0x310,
+    /// The LVT timer register.
+    LvtTimer = 0x320,
+    /// The LVT thermal sensor register.
+    LvtThermal = 0x330,
+    /// The LVT performance monitoring counters register.
+    LvtPerformance = 0x340,
+    /// The LVT LINT0 register.
+    LvtLint0 = 0x350,
+    /// The LVT LINT1 register.
+    LvtLint1 = 0x360,
+    /// The LVT error register.
+    LvtError = 0x370,
+    /// The initial count register of the timer.
+    TimerInitialCount = 0x380,
+    /// The current count register of the timer.
+    TimerCurrentCount = 0x390,
+    /// The divide configuration register of the timer.
+    TimerDivide = 0x3E0,
+}
+
+impl Register {
+    /// Returns the offset of the [`Register`] in the xAPIC register page.
+    pub const fn offset(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the MSR through which the [`Register`] is accessed in x2APIC mode.
+    pub const fn msr(self) -> u32 {
+        X2APIC_MSR_BASE + ((self as u32) >> 4)
+    }
+}
+
+/// The state of the local APIC version register.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Version(u32);
+
+impl Version {
+    /// Constructs a new [`Version`] from the bit representation.
+    pub const fn from_bits(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the bit representation of the [`Version`].
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the version of the local APIC.
+    pub const fn version(self) -> u8 {
+        (self.0 & VERSION_MASK) as u8
+    }
+
+    /// Returns the index of the last LVT entry supported by the local APIC.
+    pub const fn max_lvt_entry(self) -> u8 {
+        ((self.0 & VERSION_MAX_LVT_MASK) >> VERSION_MAX_LVT_SHIFT) as u8
+    }
+
+    /// Returns `true` if the broadcast of EOI messages to I/O APICs can be suppressed.
+    pub const fn eoi_broadcast_suppression(self) -> bool {
+        self.0 & VERSION_EOI_SUPPRESSION_BIT == VERSION_EOI_SUPPRESSION_BIT
+    }
+}
+
+/// The state of the spurious interrupt vector register.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, Default, PartialEq, Eq)]
+pub struct SpuriousInterruptVector(u32);
+
+impl SpuriousInterruptVector {
+    /// Constructs a new [`SpuriousInterruptVector`] from the bit representation.
+    pub const fn from_bits(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the bit representation of the [`SpuriousInterruptVector`].
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the vector delivered for spurious interrupts.
+    pub const fn vector(self) -> u8 {
+        (self.0 & VECTOR_MASK) as u8
+    }
+
+    /// Sets the vector delivered for spurious interrupts.
+    pub const fn set_vector(self, vector: u8) -> Self {
+        Self((self.0 & !VECTOR_MASK) | vector as u32)
+    }
+
+    /// Returns `true` if the local APIC is software enabled.
+    pub const fn enabled(self) -> bool {
+        self.0 & SVR_ENABLE_BIT == SVR_ENABLE_BIT
+    }
+
+    /// Sets whether the local APIC is software enabled.
+    pub const fn set_enabled(self, enabled: bool) -> Self {
+        Self((self.0 & !SVR_ENABLE_BIT) | ((enabled as u32) << SVR_ENABLE_SHIFT))
+    }
+
+    /// Returns `true` if the broadcast of EOI messages to I/O APICs is suppressed.
+    pub const fn eoi_broadcast_suppression(self) -> bool {
+        self.0 & SVR_EOI_SUPPRESSION_BIT == SVR_EOI_SUPPRESSION_BIT
+    }
+
+    /// Sets whether the broadcast of EOI messages to I/O APICs is suppressed.
+    pub const fn set_eoi_broadcast_suppression(self, suppress: bool) -> Self {
+        Self((self.0 & !SVR_EOI_SUPPRESSION_BIT) | ((suppress as u32) << SVR_EOI_SUPPRESSION_SHIFT))
+    }
+}
+
+/// The operating modes of the local APIC timer.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum TimerMode {
+    /// The timer counts down once from the initial count.
+    OneShot = 0,
+    /// The timer counts down repeatedly, reloading the initial count each time it reaches zero.
+    Periodic = 1,
+    /// The timer fires when the time-stamp counter reaches the value of the `IA32_TSC_DEADLINE`
+    /// MSR.
+    TscDeadline = 2,
+}
+
+/// The state of the LVT timer register.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct LvtTimer(u32);
+
+impl LvtTimer {
+    /// Creates an unmasked [`LvtTimer`] that delivers `vector` in the provided `mode`.
+    pub const fn new(vector: u8, mode: TimerMode) -> Self {
+        Self(0).set_vector(vector).set_mode(mode)
+    }
+
+    /// Constructs a new [`LvtTimer`] from the bit representation.
+    pub const fn from_bits(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the bit representation of the [`LvtTimer`].
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the vector delivered when the timer fires.
+    pub const fn vector(self) -> u8 {
+        (self.0 & VECTOR_MASK) as u8
+    }
+
+    /// Sets the vector delivered when the timer fires.
+    pub const fn set_vector(self, vector: u8) -> Self {
+        Self((self.0 & !VECTOR_MASK) | vector as u32)
+    }
+
+    /// Returns `true` if an interrupt is pending delivery.
+    pub const fn delivery_pending(self) -> bool {
+        self.0 & LVT_DELIVERY_STATUS_BIT == LVT_DELIVERY_STATUS_BIT
+    }
+
+    /// Returns `true` if the timer interrupt is masked.
+    pub const fn masked(self) -> bool {
+        self.0 & LVT_MASK_BIT == LVT_MASK_BIT
+    }
+
+    /// Sets whether the timer interrupt is masked.
+    pub const fn set_masked(self, masked: bool) -> Self {
+        Self((self.0 & !LVT_MASK_BIT) | ((masked as u32) << LVT_MASK_SHIFT))
+    }
+
+    /// Returns the [`TimerMode`] of the timer, or [`None`] if the mode is reserved.
+    pub const fn mode(self) -> Option<TimerMode> {
+        match (self.0 & LVT_TIMER_MODE_MASK) >> LVT_TIMER_MODE_SHIFT {
+            0 => Some(TimerMode::OneShot),
+            1 => Some(TimerMode::Periodic),
+            2 => Some(TimerMode::TscDeadline),
+            _ => None,
+        }
+    }
+
+    /// Sets the [`TimerMode`] of the timer.
+    pub const fn set_mode(self, mode: TimerMode) -> Self {
+        Self((self.0 & !LVT_TIMER_MODE_MASK) | ((mode as u32) << LVT_TIMER_MODE_SHIFT))
+    }
+}
+
+/// The divisors that can be applied to the bus clock to drive the local APIC timer.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum TimerDivide {
+    /// Divide by 1.
+    By1 = 0b1011,
+    /// Divide by 2.
+    By2 = 0b0000,
+    /// Divide by 4.
+    By4 = 0b0001,
+    /// Divide by 8.
+    By8 = 0b0010,
+    /// Divide by 16.
+    By16 = 0b0011,
+    /// Divide by 32.
+    By32 = 0b1000,
+    /// Divide by 64.
+    By64 = 0b1001,
+    /// Divide by 128.
+    By128 = 0b1010,
+}
+
+impl TimerDivide {
+    /// Returns the value of the divide configuration register that selects the [`TimerDivide`].
+    pub const fn to_bits(self) -> u32 {
+        self as u32
+    }
+
+    /// Returns the [`TimerDivide`] selected by the divide configuration register value `raw`.
+    pub const fn from_bits(raw: u32) -> Self {
+        match raw & 0b1011 {
+            0b0000 => Self::By2,
+            0b0001 => Self::By4,
+            0b0010 => Self::By8,
+            0b0011 => Self::By16,
+            0b1000 => Self::By32,
+            0b1001 => Self::By64,
+            0b1010 => Self::By128,
+            _ => Self::By1,
+        }
+    }
+
+    /// Returns the divisor applied by the [`TimerDivide`].
+    pub const fn divisor(self) -> u32 {
+        match self {
+            Self::By1 => 1,
+            Self::By2 => 2,
+            Self::By4 => 4,
+            Self::By8 => 8,
+            Self::By16 => 16,
+            Self::By32 => 32,
+            Self::By64 => 64,
+            Self::By128 => 128,
+        }
+    }
+}
+
+/// The delivery modes of an inter-processor interrupt.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum DeliveryMode {
+    /// Delivers the vector to the destination processors.
+    Fixed = 0b000,
+    /// Delivers the vector to the lowest priority destination processor.
+    LowestPriority = 0b001,
+    /// Delivers a system management interrupt.
+    Smi = 0b010,
+    /// Delivers a non-maskable interrupt.
+    Nmi = 0b100,
+    /// Delivers an INIT request.
+    Init = 0b101,
+    /// Delivers a start-up IPI, which starts execution at the page identified by the vector.
+    StartUp = 0b110,
+}
+
+/// The destination shorthands of an inter-processor interrupt.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum DestinationShorthand {
+    /// The destination is specified by the destination field.
+    None = 0b00,
+    /// The interrupt is sent to the issuing processor.
+    SelfOnly = 0b01,
+    /// The interrupt is sent to every processor, including the issuing processor.
+    AllIncludingSelf = 0b10,
+    /// The interrupt is sent to every processor, excluding the issuing processor.
+    AllExcludingSelf = 0b11,
+}
+
+/// The state of the interrupt command register, laid out as in x2APIC mode.
+///
+/// In xAPIC mode, only the lower 8 bits of the destination are used.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, Default, PartialEq, Eq)]
+pub struct InterruptCommand(u64);
+
+impl InterruptCommand {
+    /// Creates an edge-triggered [`InterruptCommand`] that delivers `vector` to the processor with
+    /// the physical APIC ID `destination`.
+    pub const fn fixed(vector: u8, destination: u32) -> Self {
+        Self(0)
+            .set_vector(vector)
+            .set_delivery_mode(DeliveryMode::Fixed)
+            .set_assert(true)
+            .set_destination(destination)
+    }
+
+    /// Creates an [`InterruptCommand`] that delivers a non-maskable interrupt to the processor
+    /// with the physical APIC ID `destination`.
+    pub const fn nmi(destination: u32) -> Self {
+        Self(0)
+            .set_delivery_mode(DeliveryMode::Nmi)
+            .set_assert(true)
+            .set_destination(destination)
+    }
+
+    /// Creates an [`InterruptCommand`] that delivers an INIT request to the processor with the
+    /// physical APIC ID `destination`.
+    pub const fn init(destination: u32) -> Self {
+        Self(0)
+            .set_delivery_mode(DeliveryMode::Init)
+            .set_assert(true)
+            .set_destination(destination)
+    }
+
+    /// Creates an [`InterruptCommand`] that delivers a start-up IPI to the processor with the
+    /// physical APIC ID `destination`, which starts execution at physical address
+    /// `page << 12`.
+    pub const fn startup(page: u8, destination: u32) -> Self {
+        Self(0)
+            .set_vector(page)
+            .set_delivery_mode(DeliveryMode::StartUp)
+            .set_assert(true)
+            .set_destination(destination)
+    }
+
+    /// Constructs a new [`InterruptCommand`] from the bit representation.
+    pub const fn from_bits(raw: u64) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the bit representation of the [`InterruptCommand`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the vector of the interrupt.
+    pub const fn vector(self) -> u8 {
+        (self.0 & ICR_VECTOR_MASK) as u8
+    }
+
+    /// Sets the vector of the interrupt.
+    pub const fn set_vector(self, vector: u8) -> Self {
+        Self((self.0 & !ICR_VECTOR_MASK) | vector as u64)
+    }
+
+    /// Returns the [`DeliveryMode`] of the interrupt, or [`None`] if the mode is reserved.
+    pub const fn delivery_mode(self) -> Option<DeliveryMode> {
+        match (self.0 & ICR_DELIVERY_MODE_MASK) >> ICR_DELIVERY_MODE_SHIFT {
+            0b000 => Some(DeliveryMode::Fixed),
+            0b001 => Some(DeliveryMode::LowestPriority),
+            0b010 => Some(DeliveryMode::Smi),
+            0b100 => Some(DeliveryMode::Nmi),
+            0b101 => Some(DeliveryMode::Init),
+            0b110 => Some(DeliveryMode::StartUp),
+            _ => None,
+        }
+    }
+
+    /// Sets the [`DeliveryMode`] of the interrupt.
+    pub const fn set_delivery_mode(self, mode: DeliveryMode) -> Self {
+        Self((self.0 & !ICR_DELIVERY_MODE_MASK) | ((mode as u64) << ICR_DELIVERY_MODE_SHIFT))
+    }
+
+    /// Returns `true` if the destination is a logical destination.
+    pub const fn logical(self) -> bool {
+        self.0 & ICR_LOGICAL_BIT == ICR_LOGICAL_BIT
+    }
+
+    /// Sets whether the destination is a logical destination.
+    pub const fn set_logical(self, logical: bool) -> Self {
+        Self((self.0 & !ICR_LOGICAL_BIT) | (bool_as_u64(logical) << ICR_LOGICAL_SHIFT))
+    }
+
+    /// Returns `true` if the interrupt has not yet been accepted by its destination.
+    ///
+    /// This is only reported in xAPIC mode.
+    pub const fn delivery_pending(self) -> bool {
+        self.0 & ICR_DELIVERY_STATUS_BIT == ICR_DELIVERY_STATUS_BIT
+    }
+
+    /// Returns `true` if the level of the interrupt is asserted.
+    pub const fn assert(self) -> bool {
+        self.0 & ICR_ASSERT_BIT == ICR_ASSERT_BIT
+    }
+
+    /// Sets whether the level of the interrupt is asserted.
+    pub const fn set_assert(self, assert: bool) -> Self {
+        Self((self.0 & !ICR_ASSERT_BIT) | (bool_as_u64(assert) << ICR_ASSERT_SHIFT))
+    }
+
+    /// Returns `true` if the interrupt is level triggered.
+    pub const fn level_triggered(self) -> bool {
+        self.0 & ICR_LEVEL_TRIGGERED_BIT == ICR_LEVEL_TRIGGERED_BIT
+    }
+
+    /// Sets whether the interrupt is level triggered.
+    pub const fn set_level_triggered(self, level: bool) -> Self {
+        Self(
+            (self.0 & !ICR_LEVEL_TRIGGERED_BIT) | (bool_as_u64(level) << ICR_LEVEL_TRIGGERED_SHIFT),
+        )
+    }
+
+    /// Returns the [`DestinationShorthand`] of the interrupt.
+    pub const fn shorthand(self) -> DestinationShorthand {
+        match (self.0 & ICR_SHORTHAND_MASK) >> ICR_SHORTHAND_SHIFT {
+            0b00 => DestinationShorthand::None,
+            0b01 => DestinationShorthand::SelfOnly,
+            0b10 => DestinationShorthand::AllIncludingSelf,
+            _ => DestinationShorthand::AllExcludingSelf,
+        }
+    }
+
+    /// Sets the [`DestinationShorthand`] of the interrupt.
+    pub const fn set_shorthand(self, shorthand: DestinationShorthand) -> Self {
+        Self((self.0 & !ICR_SHORTHAND_MASK) | ((shorthand as u64) << ICR_SHORTHAND_SHIFT))
+    }
+
+    /// Returns the destination of the interrupt.
+    pub const fn destination(self) -> u32 {
+        ((self.0 & ICR_DESTINATION_MASK) >> ICR_DESTINATION_SHIFT) as u32
+    }
+
+    /// Sets the destination of the interrupt.
+    pub const fn set_destination(self, destination: u32) -> Self {
+        Self((self.0 & !ICR_DESTINATION_MASK) | ((destination as u64) << ICR_DESTINATION_SHIFT))
+    }
+
+    /// Returns the values written to the lower and upper halves of the interrupt command register
+    /// in xAPIC mode.
+    pub const fn to_xapic(self) -> (u32, u32) {
+        let low = (self.0 & 0xFFFF_FFFF) as u32;
+        let high = (self.destination() & 0xFF) << XAPIC_DESTINATION_SHIFT;
+        (low, high)
+    }
+}
+
+/// The state of the error status register.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, Default, PartialEq, Eq)]
+pub struct ErrorStatus(u32);
+
+#[allow(missing_docs)]
+impl ErrorStatus {
+    /// Constructs a new [`ErrorStatus`] from the bit representation.
+    pub const fn from_bits(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the bit representation of the [`ErrorStatus`].
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns `true` if no errors were recorded.
+    pub const fn is_empty(self) -> bool {
+        self.0 & 0xFF == 0
+    }
+
+    pub const fn send_checksum(self) -> bool {
+        self.0 & (1 << 0) != 0
+    }
+
+    pub const fn receive_checksum(self) -> bool {
+        self.0 & (1 << 1) != 0
+    }
+
+    pub const fn send_accept(self) -> bool {
+        self.0 & (1 << 2) != 0
+    }
+
+    pub const fn receive_accept(self) -> bool {
+        self.0 & (1 << 3) != 0
+    }
+
+    pub const fn redirectable_ipi(self) -> bool {
+        self.0 & (1 << 4) != 0
+    }
+
+    pub const fn send_illegal_vector(self) -> bool {
+        self.0 & (1 << 5) != 0
+    }
+
+    pub const fn receive_illegal_vector(self) -> bool {
+        self.0 & (1 << 6) != 0
+    }
+
+    pub const fn illegal_register_address(self) -> bool {
+        self.0 & (1 << 7) != 0
+    }
+}
+
+impl fmt::Display for ErrorStatus {
+    #[expect(unused_assignments)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ESR {:#010x} [", self.0)?;
+
+        let mut first = true;
+        macro_rules! flag {
+            ($cond:expr, $name:literal) => {
+                if $cond {
+                    if !first {
+                        write!(f, " ")?;
+                    }
+                    first = false;
+                    write!(f, $name)?;
+                }
+            };
+        }
+
+        flag!(self.send_checksum(), "SEND_CHECKSUM");
+        flag!(self.receive_checksum(), "RECEIVE_CHECKSUM");
+        flag!(self.send_accept(), "SEND_ACCEPT");
+        flag!(self.receive_accept(), "RECEIVE_ACCEPT");
+        flag!(self.redirectable_ipi(), "REDIRECTABLE_IPI");
+        flag!(self.send_illegal_vector(), "SEND_ILLEGAL_VECTOR");
+        flag!(self.receive_illegal_vector(), "RECEIVE_ILLEGAL_VECTOR");
+        flag!(self.illegal_register_address(), "ILLEGAL_REGISTER_ADDRESS");
+
+        write!(f, "]")
+    }
+}
+
+/// A handle to the local APIC of the current processor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocalApic {
+    /// The local APIC is accessed through the memory-mapped register page at the contained
+    /// virtual address.
+    XApic(*mut u32),
+    /// The local APIC is accessed through MSRs.
+    X2Apic,
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl LocalApic {
+    /// Returns a [`LocalApic`] that accesses the local APIC through the register page mapped at
+    /// `registers`.
+    ///
+    /// # Safety
+    ///
+    /// `registers` must be a mapping of the physical page described by [`ApicBase::address()`]
+    /// with an uncacheable memory type, which remains valid for as long as the returned
+    /// [`LocalApic`] is used.
+    pub const unsafe fn xapic(registers: *mut u32) -> Self {
+        Self::XApic(registers)
+    }
+
+    /// Returns a [`LocalApic`] that accesses the local APIC through MSRs.
+    ///
+    /// # Safety
+    ///
+    /// The local APIC of the current processor must be in x2APIC mode.
+    pub const unsafe fn x2apic() -> Self {
+        Self::X2Apic
+    }
+
+    /// Reads the provided [`Register`].
+    ///
+    /// # Safety
+    ///
+    /// Reading `register` must not violate system invariants.
+    pub unsafe fn read(self, register: Register) -> u32 {
+        match self {
+            Self::XApic(registers) => {
+                let register = registers.wrapping_byte_add(register.offset());
+                // SAFETY:
+                //
+                // The invariants of [`LocalApic::xapic()`] ensure that the register page is
+                // mapped.
+                unsafe { register.read_volatile() }
+            }
+            Self::X2Apic => {
+                // SAFETY:
+                //
+                // The invariants of [`LocalApic::x2apic()`] ensure that the register MSRs exist.
+                let value = unsafe { read_msr(register.msr()) };
+                (value & 0xFFFF_FFFF) as u32
+            }
+        }
+    }
+
+    /// Writes `value` to the provided [`Register`].
+    ///
+    /// # Safety
+    ///
+    /// Writing `value` to `register` must not violate system invariants.
+    pub unsafe fn write(self, register: Register, value: u32) {
+        match self {
+            Self::XApic(registers) => {
+                let register = registers.wrapping_byte_add(register.offset());
+                // SAFETY:
+                //
+                // The invariants of [`LocalApic::xapic()`] ensure that the register page is
+                // mapped.
+                unsafe { register.write_volatile(value) }
+            }
+            // SAFETY:
+            //
+            // The invariants of [`LocalApic::x2apic()`] ensure that the register MSRs exist.
+            Self::X2Apic => unsafe { write_msr(register.msr(), u64::from(value)) },
+        }
+    }
+
+    /// Returns the ID of the local APIC.
+    pub fn id(self) -> u32 {
+        // SAFETY:
+        //
+        // Reading the ID register has no side effects.
+        let id = unsafe { self.read(Register::Id) };
+        match self {
+            Self::XApic(_) => id >> 24,
+            Self::X2Apic => id,
+        }
+    }
+
+    /// Returns the [`Version`] of the local APIC.
+    pub fn version(self) -> Version {
+        // SAFETY:
+        //
+        // Reading the version register has no side effects.
+        Version::from_bits(unsafe { self.read(Register::Version) })
+    }
+
+    /// Signals the end of the handling of the current interrupt.
+    ///
+    /// # Safety
+    ///
+    /// An interrupt delivered by the local APIC must currently be in service.
+    pub unsafe fn eoi(self) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { self.write(Register::Eoi, 0) }
+    }
+
+    /// Returns the state of the spurious interrupt vector register.
+    pub fn spurious_interrupt_vector(self) -> SpuriousInterruptVector {
+        // SAFETY:
+        //
+        // Reading the spurious interrupt vector register has no side effects.
+        SpuriousInterruptVector::from_bits(unsafe { self.read(Register::SpuriousInterruptVector) })
+    }
+
+    /// Writes the spurious interrupt vector register.
+    ///
+    /// # Safety
+    ///
+    /// The vector must have a handler, and enabling or disabling the local APIC must not violate
+    /// system invariants.
+    pub unsafe fn set_spurious_interrupt_vector(self, value: SpuriousInterruptVector) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { self.write(Register::SpuriousInterruptVector, value.to_bits()) }
+    }
+
+    /// Software enables the local APIC, delivering spurious interrupts to `spurious_vector`.
+    ///
+    /// # Safety
+    ///
+    /// `spurious_vector` must have a handler, and enabling the local APIC must not violate system
+    /// invariants.
+    pub unsafe fn enable(self, spurious_vector: u8) {
+        let value = self
+            .spurious_interrupt_vector()
+            .set_vector(spurious_vector)
+            .set_enabled(true);
+
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { self.set_spurious_interrupt_vector(value) }
+    }
+
+    /// Returns the state of the LVT timer register.
+    pub fn lvt_timer(self) -> LvtTimer {
+        // SAFETY:
+        //
+        // Reading the LVT timer register has no side effects.
+        LvtTimer::from_bits(unsafe { self.read(Register::LvtTimer) })
+    }
+
+    /// Writes the LVT timer register.
+    ///
+    /// # Safety
+    ///
+    /// The vector must have a handler if the timer is unmasked, and [`TimerMode::TscDeadline`]
+    /// must only be used if [`supports_tsc_deadline()`] returns `true`.
+    pub unsafe fn set_lvt_timer(self, value: LvtTimer) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that this operation is safe.
+        unsafe { self.write(Register::LvtTimer, value.to_bits()) }
+
+        if value.mode() == Some(TimerMode::TscDeadline) {
+            // Writes to `IA32_TSC_DEADLINE` must be ordered after the switch into TSC-deadline
+            // mode.
+            //
+            // SAFETY:
+            //
+            // `mfence` has no side effects beyond ordering memory accesses.
+            unsafe { core::arch::asm!("mfence", options(nostack, preserves_flags)) }
+        }
+    }
+
+    /// Sets the [`TimerDivide`] used by the timer in one-shot and periodic modes.
+    pub fn set_timer_divide(self, divide: TimerDivide) {
+        // SAFETY:
+        //
+        // Changing the divisor of the timer does not affect memory safety.
+        unsafe { self.write(Register::TimerDivide, divide.to_bits()) }
+    }
+
+    /// Starts the timer in one-shot or periodic mode with the provided `count`.
+    ///
+    /// A `count` of zero stops the timer.
+    pub fn set_timer_initial_count(self, count: u32) {
+        // SAFETY:
+        //
+        // The interrupt delivered by the timer is governed by the LVT timer register, which must
+        // be valid according to the invariants of [`LocalApic::set_lvt_timer()`].
+        unsafe { self.write(Register::TimerInitialCount, count) }
+    }
+
+    /// Returns the current count of the timer in one-shot or periodic mode.
+    pub fn timer_current_count(self) -> u32 {
+        // SAFETY:
+        //
+        // Reading the current count register has no side effects.
+        unsafe { self.read(Register::TimerCurrentCount) }
+    }
+
+    /// Arms the timer in TSC-deadline mode to fire once the time-stamp counter reaches `deadline`.
+    ///
+    /// A `deadline` of zero disarms the timer.
+    ///
+    /// # Safety
+    ///
+    /// The timer must be in [`TimerMode::TscDeadline`].
+    pub unsafe fn set_tsc_deadline(self, deadline: u64) {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that the `IA32_TSC_DEADLINE` MSR exists.
+        unsafe { write_msr(IA32_TSC_DEADLINE, deadline) }
+    }
+
+    /// Sends the inter-processor interrupt described by `command`.
+    ///
+    /// In xAPIC mode, this waits for the local APIC to accept the interrupt.
+    ///
+    /// # Safety
+    ///
+    /// Delivering the interrupt must not violate system invariants.
+    pub unsafe fn send_ipi(self, command: InterruptCommand) {
+        match self {
+            Self::XApic(_) => {
+                let (low, high) = command.to_xapic();
+                // SAFETY:
+                //
+                // The write to the lower half of the interrupt command register sends the
+                // interrupt, so writing the upper half is safe.
+                unsafe { self.write(Register::InterruptCommandHigh, high) }
+                // SAFETY:
+                //
+                // The invariants of this function ensure that this operation is safe.
+                unsafe { self.write(Register::InterruptCommandLow, low) }
+
+                while self.interrupt_command().delivery_pending() {
+                    core::hint::spin_loop();
+                }
+            }
+            // SAFETY:
+            //
+            // The invariants of [`LocalApic::x2apic()`] ensure that the interrupt command MSR
+            // exists, while the invariants of this function ensure that sending the interrupt is
+            // safe.
+            Self::X2Apic => unsafe {
+                write_msr(Register::InterruptCommandLow.msr(), command.to_bits())
+            },
+        }
+    }
+
+    /// Returns the state of the interrupt command register.
+    pub fn interrupt_command(self) -> InterruptCommand {
+        match self {
+            Self::XApic(_) => {
+                // SAFETY:
+                //
+                // Reading the interrupt command register has no side effects.
+                let low = unsafe { self.read(Register::InterruptCommandLow) };
+                // SAFETY:
+                //
+                // Reading the interrupt command register has no side effects.
+                let high = unsafe { self.read(Register::InterruptCommandHigh) };
+
+                InterruptCommand::from_bits(u64::from(low))
+                    .set_destination(high >> XAPIC_DESTINATION_SHIFT)
+            }
+            // SAFETY:
+            //
+            // The invariants of [`LocalApic::x2apic()`] ensure that the interrupt command MSR
+            // exists, and reading it has no side effects.
+            Self::X2Apic => InterruptCommand::from_bits(unsafe {
+                read_msr(Register::InterruptCommandLow.msr())
+            }),
+        }
+    }
+
+    /// Returns the errors recorded by the local APIC since the last call to this function.
+    pub fn error_status(self) -> ErrorStatus {
+        // SAFETY:
+        //
+        // Writing the error status register latches the errors recorded since the last write.
+        unsafe { self.write(Register::ErrorStatus, 0) }
+        // SAFETY:
+        //
+        // Reading the error status register has no side effects.
+        ErrorStatus::from_bits(unsafe { self.read(Register::ErrorStatus) })
+    }
+}
+
+#[expect(clippy::as_conversions)]
+const fn bool_as_u64(value: bool) -> u64 {
+    value as u64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn register_addresses() {
+        let cases = [
+            (Register::Id, 0x20, 0x802),
+            (Register::Version, 0x30, 0x803),
+            (Register::Eoi, 0xB0, 0x80B),
+            (Register::SpuriousInterruptVector, 0xF0, 0x80F),
+            (Register::ErrorStatus, 0x280, 0x828),
+            (Register::InterruptCommandLow, 0x300, 0x830),
+            (Register::LvtTimer, 0x320, 0x832),
+            (Register::TimerInitialCount, 0x380, 0x838),
+            (Register::TimerCurrentCount, 0x390, 0x839),
+            (Register::TimerDivide, 0x3E0, 0x83E),
+        ];
+
+        for (register, offset, msr) in cases {
+            assert_eq!(register.offset(), offset, "{register:?}");
+            assert_eq!(register.msr(), msr, "{register:?}");
+        }
+    }
+
+    #[test]
+    fn interrupt_commands() {
+        let cases = [
+            (InterruptCommand::fixed(0x40, 3), 0x0000_0003_0000_4040),
+            (InterruptCommand::nmi(1), 0x0000_0001_0000_4400),
+            (InterruptCommand::init(2), 0x0000_0002_0000_4500),
+            (InterruptCommand::startup(0x08, 2), 0x0000_0002_0000_4608),
+            (
+                InterruptCommand::fixed(0xF0, 0)
+                    .set_shorthand(DestinationShorthand::AllExcludingSelf),
+                0x0000_0000_000C_40F0,
+            ),
+        ];
+
+        for (command, bits) in cases {
+            assert_eq!(command.to_bits(), bits, "{command:?}");
+        }
+
+        assert_eq!(
+            InterruptCommand::startup(0x08, 0x12).to_xapic(),
+            (0x0000_4608, 0x1200_0000)
+        );
+        assert_eq!(
+            InterruptCommand::init(2).delivery_mode(),
+            Some(DeliveryMode::Init)
+        );
+    }
+
+    #[test]
+    fn lvt_timer() {
+        let cases = [
+            (TimerMode::OneShot, 0x0000_0020),
+            (TimerMode::Periodic, 0x0002_0020),
+            (TimerMode::TscDeadline, 0x0004_0020),
+        ];
+
+        for (mode, bits) in cases {
+            let timer = LvtTimer::new(0x20, mode);
+            assert_eq!(timer.to_bits(), bits);
+            assert_eq!(timer.mode(), Some(mode));
+            assert_eq!(timer.vector(), 0x20);
+            assert!(!timer.masked());
+        }
+
+        assert_eq!(
+            LvtTimer::from_bits(0x0001_00FF).set_masked(false).to_bits(),
+            0xFF
+        );
+        assert_eq!(LvtTimer::from_bits(0x0006_0000).mode(), None);
+    }
+
+    #[test]
+    fn timer_divide() {
+        let cases = [
+            (TimerDivide::By1, 0b1011, 1),
+            (TimerDivide::By2, 0b0000, 2),
+            (TimerDivide::By16, 0b0011, 16),
+            (TimerDivide::By128, 0b1010, 128),
+        ];
+
+        for (divide, bits, divisor) in cases {
+            assert_eq!(divide.to_bits(), bits);
+            assert_eq!(TimerDivide::from_bits(bits), divide);
+            assert_eq!(divide.divisor(), divisor);
+        }
+    }
+
+    #[test]
+    fn base_and_spurious_vector() {
+        let base = ApicBase::from_bits(0xFEE0_0900);
+        assert!(base.bsp());
+        assert!(base.enabled());
+        assert!(!base.x2apic());
+        assert_eq!(base.address(), 0xFEE0_0000);
+        assert_eq!(base.set_x2apic(true).to_bits(), 0xFEE0_0D00);
+
+        let svr = SpuriousInterruptVector::default()
+            .set_vector(0xFF)
+            .set_enabled(true);
+        assert_eq!(svr.to_bits(), 0x1FF);
+
+        let version = Version::from_bits(0x0105_0014);
+        assert_eq!(version.version(), 0x14);
+        assert_eq!(version.max_lvt_entry(), 5);
+        assert!(version.eoi_broadcast_suppression());
+
+        let errors = ErrorStatus::from_bits(0x80);
+        assert!(errors.illegal_register_address());
+        assert!(!errors.is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 //! Control register-related structures and functions.
 
-#[expect(clippy::missing_docs_in_private_items)]
+#[cfg_attr(not(test), expect(clippy::missing_docs_in_private_items))]
 mod constants {
     pub const CR0_PE_SHIFT: u32 = 0;
     pub const CR0_PE_BIT: u64 = 1 << CR0_PE_SHIFT;
@@ -826,7 +826,8 @@ impl fmt::Display for Cr4 {
     }
 }
 
-#[expect(clippy::as_conversions, clippy::missing_docs_in_private_items)]
+#[expect(clippy::as_conversions)]
+#[cfg_attr(not(test), expect(clippy::missing_docs_in_private_items))]
 const fn bool_as_u64(value: bool) -> u64 {
     value as u64
 }
@@ -0,0 +1,426 @@
+//! Gate descriptors and the Interrupt Descriptor Table.
+
+#![cfg_attr(
+    not(test),
+    expect(
+        clippy::missing_docs_in_private_items,
+        reason = "bit shift/masking is documented in `GateDescriptor32` and `GateDescriptor64`"
+    )
+)]
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use core::arch::asm;
+
+use crate::{PrivilegeLevel, segmentation::DescriptorTablePointer, segmentation::SegmentSelector};
+
+const OFFSET_LOW_MASK: u64 = 0xFFFF;
+const OFFSET_HIGH_SHIFT: u32 = 48;
+const OFFSET_HIGH_MASK: u64 = 0xFFFF << OFFSET_HIGH_SHIFT;
+
+const SELECTOR_SHIFT: u32 = 16;
+const SELECTOR_MASK: u64 = 0xFFFF << SELECTOR_SHIFT;
+
+const IST_SHIFT: u32 = 32;
+const IST_MASK: u64 = 0b111 << IST_SHIFT;
+
+const TYPE_SHIFT: u32 = 40;
+const TYPE_MASK: u64 = 0xF << TYPE_SHIFT;
+
+const DPL_SHIFT: u32 = 45;
+const DPL_MASK: u64 = 0b11 << DPL_SHIFT;
+
+const PRESENT_SHIFT: u32 = 47;
+const PRESENT_BIT: u64 = 1 << PRESENT_SHIFT;
+
+/// The number of entries in a full Interrupt Descriptor Table.
+pub const VECTOR_COUNT: usize = 256;
+
+/// The types of gates that can be described by a [`GateDescriptor32`] or a [`GateDescriptor64`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GateType {
+    /// A task gate, which is only supported in 32-bit modes.
+    Task = 0x5,
+    /// An interrupt gate, which clears `IF` upon entry.
+    Interrupt = 0xE,
+    /// A trap gate, which leaves `IF` unchanged upon entry.
+    Trap = 0xF,
+}
+
+/// An 8-byte gate descriptor used in the Interrupt Descriptor Table in 32-bit modes.
+///
+/// This representation does not do any validity checking.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GateDescriptor32(u64);
+
+impl GateDescriptor32 {
+    /// Creates a [`GateDescriptor32`] that is not present.
+    pub const fn non_present() -> Self {
+        Self(0)
+    }
+
+    /// Creates a present, ring 0 interrupt [`GateDescriptor32`] that enters `offset` in the code
+    /// segment referred to by `selector`.
+    pub const fn interrupt(offset: u32, selector: SegmentSelector) -> Self {
+        Self::gate(GateType::Interrupt, offset, selector)
+    }
+
+    /// Creates a present, ring 0 trap [`GateDescriptor32`] that enters `offset` in the code
+    /// segment referred to by `selector`.
+    pub const fn trap(offset: u32, selector: SegmentSelector) -> Self {
+        Self::gate(GateType::Trap, offset, selector)
+    }
+
+    /// Creates a present, ring 0 task [`GateDescriptor32`] that switches to the task state segment
+    /// referred to by `selector`.
+    pub const fn task(selector: SegmentSelector) -> Self {
+        Self::gate(GateType::Task, 0, selector)
+    }
+
+    /// Creates a present, ring 0 [`GateDescriptor32`] of the provided `ty`.
+    const fn gate(ty: GateType, offset: u32, selector: SegmentSelector) -> Self {
+        Self::non_present()
+            .set_gate_type(ty)
+            .set_offset(offset)
+            .set_selector(selector)
+            .set_dpl(PrivilegeLevel::Ring0)
+            .set_present(true)
+    }
+
+    /// Constructs a new [`GateDescriptor32`] from the bit representation.
+    pub const fn from_bits(raw: u64) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the bit representation of the [`GateDescriptor32`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the offset of the entry point of the handler.
+    pub const fn offset(self) -> u32 {
+        (((self.0 & OFFSET_LOW_MASK) | ((self.0 & OFFSET_HIGH_MASK) >> 32)) & 0xFFFF_FFFF) as u32
+    }
+
+    /// Sets the offset of the entry point of the handler.
+    pub const fn set_offset(self, offset: u32) -> Self {
+        let offset = offset as u64;
+        Self(
+            (self.0 & !(OFFSET_LOW_MASK | OFFSET_HIGH_MASK))
+                | (offset & OFFSET_LOW_MASK)
+                | ((offset << 32) & OFFSET_HIGH_MASK),
+        )
+    }
+
+    /// Returns the [`SegmentSelector`] of the code segment of the handler, or of the task state
+    /// segment of a task gate.
+    pub const fn selector(self) -> SegmentSelector {
+        SegmentSelector::from_bits(((self.0 & SELECTOR_MASK) >> SELECTOR_SHIFT) as u16)
+    }
+
+    /// Sets the [`SegmentSelector`] of the code segment of the handler, or of the task state
+    /// segment of a task gate.
+    pub const fn set_selector(self, selector: SegmentSelector) -> Self {
+        Self((self.0 & !SELECTOR_MASK) | ((selector.to_bits() as u64) << SELECTOR_SHIFT))
+    }
+
+    /// Returns the [`GateType`] of the [`GateDescriptor32`], or [`None`] if the type is not
+    /// known.
+    ///
+    /// 16-bit gates are reported as [`None`].
+    pub const fn gate_type(self) -> Option<GateType> {
+        gate_type((self.0 & TYPE_MASK) >> TYPE_SHIFT)
+    }
+
+    /// Sets the [`GateType`] of the [`GateDescriptor32`].
+    pub const fn set_gate_type(self, ty: GateType) -> Self {
+        Self((self.0 & !TYPE_MASK) | ((ty as u64) << TYPE_SHIFT))
+    }
+
+    /// Returns the most privileged level from which the gate can be entered by a software
+    /// interrupt.
+    pub const fn dpl(self) -> PrivilegeLevel {
+        privilege_level((self.0 & DPL_MASK) >> DPL_SHIFT)
+    }
+
+    /// Sets the most privileged level from which the gate can be entered by a software interrupt.
+    pub const fn set_dpl(self, dpl: PrivilegeLevel) -> Self {
+        Self((self.0 & !DPL_MASK) | ((dpl as u64) << DPL_SHIFT))
+    }
+
+    /// Returns `true` if the gate is present.
+    pub const fn present(self) -> bool {
+        self.0 & PRESENT_BIT == PRESENT_BIT
+    }
+
+    /// Sets whether the gate is present.
+    pub const fn set_present(self, present: bool) -> Self {
+        Self((self.0 & !PRESENT_BIT) | (bool_as_u64(present) << PRESENT_SHIFT))
+    }
+}
+
+/// A 16-byte gate descriptor used in the Interrupt Descriptor Table in 64-bit mode.
+///
+/// This representation does not do any validity checking.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GateDescriptor64 {
+    /// The lower half of the gate, which is laid out like a [`GateDescriptor32`] with the
+    /// addition of the interrupt stack table index.
+    low: u64,
+    /// The upper half of the gate, which contains the upper 32 bits of the offset.
+    high: u64,
+}
+
+impl GateDescriptor64 {
+    /// Creates a [`GateDescriptor64`] that is not present.
+    pub const fn non_present() -> Self {
+        Self { low: 0, high: 0 }
+    }
+
+    /// Creates a present, ring 0 interrupt [`GateDescriptor64`] that enters `offset` in the code
+    /// segment referred to by `selector`.
+    pub const fn interrupt(offset: u64, selector: SegmentSelector) -> Self {
+        Self::gate(GateType::Interrupt, offset, selector)
+    }
+
+    /// Creates a present, ring 0 trap [`GateDescriptor64`] that enters `offset` in the code
+    /// segment referred to by `selector`.
+    pub const fn trap(offset: u64, selector: SegmentSelector) -> Self {
+        Self::gate(GateType::Trap, offset, selector)
+    }
+
+    /// Creates a present, ring 0 [`GateDescriptor64`] of the provided `ty`.
+    const fn gate(ty: GateType, offset: u64, selector: SegmentSelector) -> Self {
+        Self::non_present()
+            .set_gate_type(ty)
+            .set_offset(offset)
+            .set_selector(selector)
+            .set_dpl(PrivilegeLevel::Ring0)
+            .set_present(true)
+    }
+
+    /// Constructs a new [`GateDescriptor64`] from the bit representation.
+    pub const fn from_bits(raw: [u64; 2]) -> Self {
+        Self {
+            low: raw[0],
+            high: raw[1],
+        }
+    }
+
+    /// Returns the bit representation of the [`GateDescriptor64`], in memory order.
+    pub const fn to_bits(self) -> [u64; 2] {
+        [self.low, self.high]
+    }
+
+    /// Returns the lower half of the [`GateDescriptor64`] viewed as a [`GateDescriptor32`].
+    const fn low(self) -> GateDescriptor32 {
+        GateDescriptor32::from_bits(self.low)
+    }
+
+    /// Returns a copy of `self` with the lower half replaced by `low`.
+    const fn with_low(self, low: GateDescriptor32) -> Self {
+        Self {
+            low: low.to_bits(),
+            high: self.high,
+        }
+    }
+
+    /// Returns the offset of the entry point of the handler.
+    pub const fn offset(self) -> u64 {
+        (self.low().offset() as u64) | ((self.high & 0xFFFF_FFFF) << 32)
+    }
+
+    /// Sets the offset of the entry point of the handler.
+    pub const fn set_offset(self, offset: u64) -> Self {
+        let low = self.low().set_offset((offset & 0xFFFF_FFFF) as u32);
+        Self {
+            low: low.to_bits(),
+            high: (self.high & !0xFFFF_FFFF) | (offset >> 32),
+        }
+    }
+
+    /// Returns the [`SegmentSelector`] of the code segment of the handler.
+    pub const fn selector(self) -> SegmentSelector {
+        self.low().selector()
+    }
+
+    /// Sets the [`SegmentSelector`] of the code segment of the handler.
+    pub const fn set_selector(self, selector: SegmentSelector) -> Self {
+        self.with_low(self.low().set_selector(selector))
+    }
+
+    /// Returns the interrupt stack table index used when entering the gate, or `0` if the stack is
+    /// not switched through the interrupt stack table.
+    pub const fn ist(self) -> u8 {
+        ((self.low & IST_MASK) >> IST_SHIFT) as u8
+    }
+
+    /// Sets the interrupt stack table index used when entering the gate.
+    ///
+    /// An `ist` of `0` disables the interrupt stack table mechanism for the gate. Only the lower 3
+    /// bits of `ist` are used.
+    pub const fn set_ist(self, ist: u8) -> Self {
+        Self {
+            low: (self.low & !IST_MASK) | (((ist as u64) << IST_SHIFT) & IST_MASK),
+            high: self.high,
+        }
+    }
+
+    /// Returns the [`GateType`] of the [`GateDescriptor64`], or [`None`] if the type is not
+    /// known.
+    pub const fn gate_type(self) -> Option<GateType> {
+        self.low().gate_type()
+    }
+
+    /// Sets the [`GateType`] of the [`GateDescriptor64`].
+    ///
+    /// [`GateType::Task`] is not supported in 64-bit mode.
+    pub const fn set_gate_type(self, ty: GateType) -> Self {
+        self.with_low(self.low().set_gate_type(ty))
+    }
+
+    /// Returns the most privileged level from which the gate can be entered by a software
+    /// interrupt.
+    pub const fn dpl(self) -> PrivilegeLevel {
+        self.low().dpl()
+    }
+
+    /// Sets the most privileged level from which the gate can be entered by a software interrupt.
+    pub const fn set_dpl(self, dpl: PrivilegeLevel) -> Self {
+        self.with_low(self.low().set_dpl(dpl))
+    }
+
+    /// Returns `true` if the gate is present.
+    pub const fn present(self) -> bool {
+        self.low().present()
+    }
+
+    /// Sets whether the gate is present.
+    pub const fn set_present(self, present: bool) -> Self {
+        self.with_low(self.low().set_present(present))
+    }
+}
+
+/// Loads the Interrupt Descriptor Table described by `pointer`.
+///
+/// # Safety
+///
+/// The table described by `pointer` must remain valid and accessible for as long as it is in
+/// use, and every gate that may be entered must refer to a valid handler.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn load_idt(pointer: &DescriptorTablePointer) {
+    // SAFETY:
+    //
+    // The invariants of this function ensure that this operation is safe.
+    unsafe { asm!("lidt [{}]", in(reg) pointer, options(readonly, nostack, preserves_flags)) }
+}
+
+/// Returns the [`DescriptorTablePointer`] of the currently loaded Interrupt Descriptor Table.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn store_idt() -> DescriptorTablePointer {
+    let mut pointer = DescriptorTablePointer::default();
+
+    // SAFETY:
+    //
+    // `pointer` is valid for writes of a `DescriptorTablePointer`.
+    unsafe { asm!("sidt [{}]", in(reg) &raw mut pointer, options(nostack, preserves_flags)) }
+    pointer
+}
+
+/// Returns the [`PrivilegeLevel`] corresponding to the lower two bits of `value`.
+const fn privilege_level(value: u64) -> PrivilegeLevel {
+    match value & 0b11 {
+        0 => PrivilegeLevel::Ring0,
+        1 => PrivilegeLevel::Ring1,
+        2 => PrivilegeLevel::Ring2,
+        _ => PrivilegeLevel::Ring3,
+    }
+}
+
+/// Returns the [`GateType`] corresponding to `value`.
+const fn gate_type(value: u64) -> Option<GateType> {
+    match value {
+        0x5 => Some(GateType::Task),
+        0xE => Some(GateType::Interrupt),
+        0xF => Some(GateType::Trap),
+        _ => None,
+    }
+}
+
+#[expect(clippy::as_conversions)]
+const fn bool_as_u64(value: bool) -> u64 {
+    value as u64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gate_descriptor_32() {
+        let selector = SegmentSelector::new(1, PrivilegeLevel::Ring0);
+        let cases = [
+            (GateDescriptor32::non_present(), 0x0000_0000_0000_0000),
+            (
+                GateDescriptor32::interrupt(0x1234_5678, selector),
+                0x1234_8E00_0008_5678,
+            ),
+            (
+                GateDescriptor32::trap(0x1234_5678, selector),
+                0x1234_8F00_0008_5678,
+            ),
+            (
+                GateDescriptor32::task(SegmentSelector::new(5, PrivilegeLevel::Ring0)),
+                0x0000_8500_0028_0000,
+            ),
+            (
+                GateDescriptor32::interrupt(0x1234_5678, selector).set_dpl(PrivilegeLevel::Ring3),
+                0x1234_EE00_0008_5678,
+            ),
+        ];
+
+        for (gate, bits) in cases {
+            assert_eq!(gate.to_bits(), bits, "{gate:?}");
+        }
+
+        let gate = GateDescriptor32::from_bits(0x1234_8E00_0008_5678);
+        assert_eq!(gate.offset(), 0x1234_5678);
+        assert_eq!(gate.selector(), selector);
+        assert_eq!(gate.gate_type(), Some(GateType::Interrupt));
+        assert!(gate.present());
+    }
+
+    #[test]
+    fn gate_descriptor_64() {
+        let selector = SegmentSelector::new(3, PrivilegeLevel::Ring0);
+        let cases = [
+            (GateDescriptor64::non_present(), [0, 0]),
+            (
+                GateDescriptor64::interrupt(0xFFFF_8000_1234_5678, selector),
+                [0x1234_8E00_0018_5678, 0x0000_0000_FFFF_8000],
+            ),
+            (
+                GateDescriptor64::trap(0xFFFF_8000_1234_5678, selector),
+                [0x1234_8F00_0018_5678, 0x0000_0000_FFFF_8000],
+            ),
+            (
+                GateDescriptor64::interrupt(0xFFFF_8000_1234_5678, selector).set_ist(3),
+                [0x1234_8E03_0018_5678, 0x0000_0000_FFFF_8000],
+            ),
+        ];
+
+        for (gate, bits) in cases {
+            assert_eq!(gate.to_bits(), bits, "{gate:?}");
+        }
+
+        let gate = GateDescriptor64::from_bits([0x1234_8E03_0018_5678, 0x0000_0000_FFFF_8000]);
+        assert_eq!(gate.offset(), 0xFFFF_8000_1234_5678);
+        assert_eq!(gate.selector(), selector);
+        assert_eq!(gate.ist(), 3);
+        assert_eq!(gate.gate_type(), Some(GateType::Interrupt));
+        assert_eq!(gate.dpl(), PrivilegeLevel::Ring0);
+        assert!(gate.present());
+        assert_eq!(core::mem::size_of::<GateDescriptor64>(), 16);
+    }
+}
@@ -122,7 +122,7 @@ pub unsafe fn read_u32(port: u16) -> u32 {
     // According to the invariants of this function, this is safe to run.
     unsafe {
         asm!(
-            "in ax, dx",
+            "in eax, dx",
             in("dx") port,
             lateout("eax") val,
             options(nomem, nostack, preserves_flags)
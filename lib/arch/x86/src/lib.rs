@@ -3,11 +3,18 @@
 
 #![no_std]
 
+pub mod apic;
 pub mod control;
 pub mod cpuid;
+pub mod interrupt;
 pub mod io_port;
 pub mod msr;
 pub mod paging;
+pub mod pat;
+pub mod segmentation;
+pub mod tsc;
+pub mod tss;
+pub mod xsave;
 
 /// The privilege level associated with an item.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
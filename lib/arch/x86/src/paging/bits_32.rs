@@ -331,7 +331,7 @@ impl TranslationDescriptor {
     }
 }
 
-#[expect(clippy::missing_docs_in_private_items)]
+#[cfg_attr(not(test), expect(clippy::missing_docs_in_private_items))]
 const fn bool_as_u32(value: bool) -> u32 {
     value as u32
 }
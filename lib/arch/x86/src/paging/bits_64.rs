@@ -1,8 +1,11 @@
 //! Paging-related utilities.
 
-#![expect(
-    clippy::missing_docs_in_private_items,
-    reason = "bit shift/masking is documented in `TranslationDescriptor`"
+#![cfg_attr(
+    not(test),
+    expect(
+        clippy::missing_docs_in_private_items,
+        reason = "bit shift/masking is documented in `TranslationDescriptor`"
+    )
 )]
 
 /// The number of [`TranslationDescriptor`]'s in a single [`TranslationDescriptor`] table.
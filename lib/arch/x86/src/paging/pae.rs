@@ -419,7 +419,7 @@ impl TranslationDescriptor {
     }
 }
 
-#[expect(clippy::missing_docs_in_private_items)]
+#[cfg_attr(not(test), expect(clippy::missing_docs_in_private_items))]
 const fn bool_as_u64(value: bool) -> u64 {
     value as u64
 }
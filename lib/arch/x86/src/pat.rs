@@ -0,0 +1,368 @@
+//! Page Attribute Table and memory type definitions.
+//!
+//! The memory type used for an access is determined by combining the type selected by the page
+//! tables, through the Page Attribute Table ([`Pat`]), with the type assigned to the physical
+//! address by the MTRRs. [`PatSelector`] describes the page table bits that select an entry of
+//! the [`Pat`], and [`effective_memory_type()`] computes the resulting memory type.
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::{
+    cpuid::{cpuid_unchecked, supports_cpuid},
+    msr::{read_msr, write_msr},
+};
+
+/// The architectural index of the `IA32_PAT` MSR.
+pub const IA32_PAT: u32 = 0x277;
+
+/// The memory types that can be programmed into the [`Pat`] or the MTRRs.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum MemoryType {
+    /// Accesses are not cached, are not speculative, and are performed in program order.
+    Uncacheable = 0,
+    /// Accesses are not cached, but writes may be combined and performed out of order.
+    WriteCombining = 1,
+    /// Reads are cached, while writes are written through to memory.
+    WriteThrough = 4,
+    /// Reads are cached, while writes are propagated to memory and invalidate cached copies.
+    WriteProtected = 5,
+    /// Reads and writes are cached, with writes propagated to memory on eviction.
+    WriteBack = 6,
+    /// Identical to [`MemoryType::Uncacheable`], except that it can be overridden by an MTRR
+    /// [`MemoryType::WriteCombining`] type.
+    ///
+    /// This type is only valid in the [`Pat`].
+    UncacheableMinus = 7,
+}
+
+impl MemoryType {
+    /// Returns the [`MemoryType`] associated with `value`, or [`None`] if `value` is reserved.
+    pub const fn from_bits(value: u8) -> Option<Self> {
+        let memory_type = match value {
+            0 => Self::Uncacheable,
+            1 => Self::WriteCombining,
+            4 => Self::WriteThrough,
+            5 => Self::WriteProtected,
+            6 => Self::WriteBack,
+            7 => Self::UncacheableMinus,
+            _ => return None,
+        };
+
+        Some(memory_type)
+    }
+
+    /// Returns the encoding of this [`MemoryType`].
+    pub const fn to_bits(self) -> u8 {
+        self as u8
+    }
+}
+
+/// The page table bits that select an entry of the [`Pat`].
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct PatSelector {
+    /// The value of the page-level write-through bit.
+    pub pwt: bool,
+    /// The value of the page-level cache disable bit.
+    pub pcd: bool,
+    /// The value of the page attribute table bit.
+    pub pat: bool,
+}
+
+impl PatSelector {
+    /// Returns the [`PatSelector`] that selects entry `index` of the [`Pat`].
+    ///
+    /// Only the lowest three bits of `index` are used.
+    pub const fn from_index(index: u8) -> Self {
+        Self {
+            pwt: index & 0b001 != 0,
+            pcd: index & 0b010 != 0,
+            pat: index & 0b100 != 0,
+        }
+    }
+
+    /// Returns the index of the [`Pat`] entry selected by this [`PatSelector`].
+    #[expect(clippy::as_conversions)]
+    pub const fn index(self) -> u8 {
+        ((self.pat as u8) << 2) | ((self.pcd as u8) << 1) | (self.pwt as u8)
+    }
+}
+
+/// The contents of the `IA32_PAT` MSR.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Pat(pub u64);
+
+impl Pat {
+    /// The number of entries in the [`Pat`].
+    pub const ENTRIES: u8 = 8;
+
+    /// The value of the [`Pat`] after power-up or reset.
+    ///
+    /// This layout is compatible with processors that do not support the [`Pat`], in which the
+    /// PCD and PWT bits directly select the memory type.
+    pub const POWER_ON: Self = Self(0x0007_0406_0007_0406);
+
+    /// Returns the [`MemoryType`] programmed into entry `index`, or [`None`] if `index` is out of
+    /// range or the entry contains a reserved encoding.
+    #[expect(clippy::as_conversions)]
+    pub const fn entry(self, index: u8) -> Option<MemoryType> {
+        if index >= Self::ENTRIES {
+            return None;
+        }
+
+        MemoryType::from_bits(((self.0 >> (index * 8)) & 0b111) as u8)
+    }
+
+    /// Returns a copy of this [`Pat`] with entry `index` set to `memory_type`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    #[expect(clippy::as_conversions)]
+    pub const fn set_entry(self, index: u8, memory_type: MemoryType) -> Self {
+        assert!(index < Self::ENTRIES, "PAT entry index out of range");
+
+        let shift = index * 8;
+        Self((self.0 & !(0xFF << shift)) | ((memory_type.to_bits() as u64) << shift))
+    }
+
+    /// Returns the [`PatSelector`] of the first entry programmed with `memory_type`.
+    pub const fn find(self, memory_type: MemoryType) -> Option<PatSelector> {
+        let mut index = 0;
+        while index < Self::ENTRIES {
+            if let Some(entry) = self.entry(index)
+                && entry.to_bits() == memory_type.to_bits()
+            {
+                return Some(PatSelector::from_index(index));
+            }
+
+            index += 1;
+        }
+
+        None
+    }
+
+    /// Returns `true` if every entry of this [`Pat`] contains a valid [`MemoryType`].
+    ///
+    /// Writing an invalid value to the `IA32_PAT` MSR causes a general-protection fault.
+    pub const fn is_valid(self) -> bool {
+        let mut index = 0;
+        while index < Self::ENTRIES {
+            if self.entry(index).is_none() || (self.0 >> (index * 8)) & 0xF8 != 0 {
+                return false;
+            }
+
+            index += 1;
+        }
+
+        true
+    }
+}
+
+/// Returns the [`MemoryType`] used for accesses to memory whose MTRR type is `mtrr` and that is
+/// mapped through a [`Pat`] entry of type `pat`.
+///
+/// An MTRR type of [`MemoryType::UncacheableMinus`] is not architecturally valid and is treated
+/// as [`MemoryType::Uncacheable`].
+pub const fn effective_memory_type(mtrr: MemoryType, pat: MemoryType) -> MemoryType {
+    match (mtrr, pat) {
+        (_, MemoryType::Uncacheable) => MemoryType::Uncacheable,
+        (_, MemoryType::WriteCombining) => MemoryType::WriteCombining,
+        (MemoryType::WriteCombining, MemoryType::UncacheableMinus | MemoryType::WriteBack) => {
+            MemoryType::WriteCombining
+        }
+        (_, MemoryType::UncacheableMinus)
+        | (
+            MemoryType::Uncacheable | MemoryType::UncacheableMinus | MemoryType::WriteCombining,
+            _,
+        ) => MemoryType::Uncacheable,
+        (mtrr, MemoryType::WriteBack) => mtrr,
+        (MemoryType::WriteProtected, _) | (_, MemoryType::WriteProtected) => {
+            MemoryType::WriteProtected
+        }
+        (MemoryType::WriteThrough | MemoryType::WriteBack, MemoryType::WriteThrough) => {
+            MemoryType::WriteThrough
+        }
+    }
+}
+
+/// Returns `true` if the processor supports the [`Pat`].
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn supports_pat() -> bool {
+    if !supports_cpuid() {
+        return false;
+    }
+
+    // SAFETY:
+    //
+    // The CPUID instruction is supported.
+    let result = unsafe { cpuid_unchecked(1, 0) };
+
+    (result.edx >> 16) & 1 == 1
+}
+
+/// Returns the current value of the `IA32_PAT` MSR.
+///
+/// # Safety
+///
+/// The processor must support the [`Pat`].
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn read_pat() -> Pat {
+    debug_assert!(supports_pat());
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that the `IA32_PAT` MSR exists.
+    Pat(unsafe { read_msr(IA32_PAT) })
+}
+
+/// Writes `pat` to the `IA32_PAT` MSR.
+///
+/// # Safety
+///
+/// The processor must support the [`Pat`], `pat` must be [valid][Pat::is_valid], and existing
+/// mappings must remain consistent with the new memory types. The caller is responsible for
+/// invalidating any affected TLB entries and cache lines.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn write_pat(pat: Pat) {
+    debug_assert!(supports_pat());
+    debug_assert!(pat.is_valid());
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that the `IA32_PAT` MSR exists and that `pat` is a
+    // valid value.
+    unsafe { write_msr(IA32_PAT, pat.0) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn power_on_layout() {
+        let expected = [
+            MemoryType::WriteBack,
+            MemoryType::WriteThrough,
+            MemoryType::UncacheableMinus,
+            MemoryType::Uncacheable,
+            MemoryType::WriteBack,
+            MemoryType::WriteThrough,
+            MemoryType::UncacheableMinus,
+            MemoryType::Uncacheable,
+        ];
+
+        for (index, memory_type) in (0..Pat::ENTRIES).zip(expected) {
+            assert_eq!(Pat::POWER_ON.entry(index), Some(memory_type));
+        }
+        assert_eq!(Pat::POWER_ON.entry(Pat::ENTRIES), None);
+        assert!(Pat::POWER_ON.is_valid());
+    }
+
+    #[test]
+    fn entry_modification() {
+        let pat = Pat::POWER_ON.set_entry(1, MemoryType::WriteCombining);
+
+        assert_eq!(pat.0, 0x0007_0406_0007_0106);
+        assert_eq!(
+            pat.find(MemoryType::WriteCombining),
+            Some(PatSelector {
+                pwt: true,
+                pcd: false,
+                pat: false,
+            })
+        );
+        assert_eq!(Pat::POWER_ON.find(MemoryType::WriteCombining), None);
+        assert!(!Pat(0x0007_0406_0007_0402).is_valid());
+        assert!(!Pat(0x0007_0406_0007_0416).is_valid());
+    }
+
+    #[test]
+    fn selector_round_trip() {
+        for index in 0..Pat::ENTRIES {
+            assert_eq!(PatSelector::from_index(index).index(), index);
+        }
+    }
+
+    #[test]
+    fn effective_types() {
+        use MemoryType::*;
+
+        // Rows are MTRR types, columns are PAT types in the order UC, UC-, WC, WT, WP, WB.
+        let table = [
+            (
+                Uncacheable,
+                [
+                    Uncacheable,
+                    Uncacheable,
+                    WriteCombining,
+                    Uncacheable,
+                    Uncacheable,
+                    Uncacheable,
+                ],
+            ),
+            (
+                WriteCombining,
+                [
+                    Uncacheable,
+                    WriteCombining,
+                    WriteCombining,
+                    Uncacheable,
+                    Uncacheable,
+                    WriteCombining,
+                ],
+            ),
+            (
+                WriteThrough,
+                [
+                    Uncacheable,
+                    Uncacheable,
+                    WriteCombining,
+                    WriteThrough,
+                    WriteProtected,
+                    WriteThrough,
+                ],
+            ),
+            (
+                WriteProtected,
+                [
+                    Uncacheable,
+                    Uncacheable,
+                    WriteCombining,
+                    WriteProtected,
+                    WriteProtected,
+                    WriteProtected,
+                ],
+            ),
+            (
+                WriteBack,
+                [
+                    Uncacheable,
+                    Uncacheable,
+                    WriteCombining,
+                    WriteThrough,
+                    WriteProtected,
+                    WriteBack,
+                ],
+            ),
+        ];
+        let columns = [
+            Uncacheable,
+            UncacheableMinus,
+            WriteCombining,
+            WriteThrough,
+            WriteProtected,
+            WriteBack,
+        ];
+
+        for (mtrr, row) in table {
+            for (pat, expected) in columns.into_iter().zip(row) {
+                assert_eq!(
+                    effective_memory_type(mtrr, pat),
+                    expected,
+                    "MTRR {mtrr:?} with PAT {pat:?}"
+                );
+            }
+        }
+    }
+}
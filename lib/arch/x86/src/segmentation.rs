@@ -0,0 +1,853 @@
+//! Segment selectors, segment descriptors, and the Global Descriptor Table.
+
+#![cfg_attr(
+    not(test),
+    expect(
+        clippy::missing_docs_in_private_items,
+        reason = "bit shift/masking is documented in `SegmentDescriptor`"
+    )
+)]
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use core::arch::asm;
+
+use crate::PrivilegeLevel;
+
+const SELECTOR_RPL_SHIFT: u32 = 0;
+const SELECTOR_RPL_MASK: u16 = 0b11 << SELECTOR_RPL_SHIFT;
+
+const SELECTOR_LOCAL_SHIFT: u32 = 2;
+const SELECTOR_LOCAL_BIT: u16 = 1 << SELECTOR_LOCAL_SHIFT;
+
+const SELECTOR_INDEX_SHIFT: u32 = 3;
+const SELECTOR_INDEX_MASK: u16 = 0x1FFF << SELECTOR_INDEX_SHIFT;
+
+const LIMIT_LOW_MASK: u64 = 0xFFFF;
+const LIMIT_HIGH_SHIFT: u32 = 48;
+const LIMIT_HIGH_MASK: u64 = 0xF << LIMIT_HIGH_SHIFT;
+
+const BASE_LOW_SHIFT: u32 = 16;
+const BASE_LOW_MASK: u64 = 0xFF_FFFF << BASE_LOW_SHIFT;
+const BASE_HIGH_SHIFT: u32 = 56;
+const BASE_HIGH_MASK: u64 = 0xFF << BASE_HIGH_SHIFT;
+
+const ACCESSED_SHIFT: u32 = 40;
+const ACCESSED_BIT: u64 = 1 << ACCESSED_SHIFT;
+
+const READ_WRITE_SHIFT: u32 = 41;
+const READ_WRITE_BIT: u64 = 1 << READ_WRITE_SHIFT;
+
+const CONFORMING_EXPAND_DOWN_SHIFT: u32 = 42;
+const CONFORMING_EXPAND_DOWN_BIT: u64 = 1 << CONFORMING_EXPAND_DOWN_SHIFT;
+
+const EXECUTABLE_SHIFT: u32 = 43;
+const EXECUTABLE_BIT: u64 = 1 << EXECUTABLE_SHIFT;
+
+const TYPE_SHIFT: u32 = 40;
+const TYPE_MASK: u64 = 0xF << TYPE_SHIFT;
+
+const CODE_OR_DATA_SHIFT: u32 = 44;
+const CODE_OR_DATA_BIT: u64 = 1 << CODE_OR_DATA_SHIFT;
+
+const DPL_SHIFT: u32 = 45;
+const DPL_MASK: u64 = 0b11 << DPL_SHIFT;
+
+const PRESENT_SHIFT: u32 = 47;
+const PRESENT_BIT: u64 = 1 << PRESENT_SHIFT;
+
+const AVAILABLE_SHIFT: u32 = 52;
+const AVAILABLE_BIT: u64 = 1 << AVAILABLE_SHIFT;
+
+const LONG_MODE_SHIFT: u32 = 53;
+const LONG_MODE_BIT: u64 = 1 << LONG_MODE_SHIFT;
+
+const DEFAULT_SIZE_SHIFT: u32 = 54;
+const DEFAULT_SIZE_BIT: u64 = 1 << DEFAULT_SIZE_SHIFT;
+
+const GRANULARITY_SHIFT: u32 = 55;
+const GRANULARITY_BIT: u64 = 1 << GRANULARITY_SHIFT;
+
+/// The maximum value of the limit of a [`SegmentDescriptor`].
+pub const MAX_SEGMENT_LIMIT: u32 = 0xF_FFFF;
+
+/// A reference to a descriptor in either the Global Descriptor Table or the active Local
+/// Descriptor Table.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SegmentSelector(u16);
+
+impl SegmentSelector {
+    /// The null [`SegmentSelector`].
+    pub const NULL: Self = Self(0);
+
+    /// Creates a new [`SegmentSelector`] that refers to the descriptor at `index` in the Global
+    /// Descriptor Table with the requested privilege level `rpl`.
+    pub const fn new(index: u16, rpl: PrivilegeLevel) -> Self {
+        Self(0).set_index(index).set_rpl(rpl)
+    }
+
+    /// Constructs a new [`SegmentSelector`] from the bit representation.
+    pub const fn from_bits(raw: u16) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the bit representation of the [`SegmentSelector`].
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the requested privilege level of the [`SegmentSelector`].
+    pub const fn rpl(self) -> PrivilegeLevel {
+        privilege_level((self.0 & SELECTOR_RPL_MASK) >> SELECTOR_RPL_SHIFT)
+    }
+
+    /// Sets the requested privilege level of the [`SegmentSelector`].
+    pub const fn set_rpl(self, rpl: PrivilegeLevel) -> Self {
+        Self((self.0 & !SELECTOR_RPL_MASK) | ((rpl as u16) << SELECTOR_RPL_SHIFT))
+    }
+
+    /// Returns `true` if the [`SegmentSelector`] refers to the Local Descriptor Table.
+    pub const fn local(self) -> bool {
+        self.0 & SELECTOR_LOCAL_BIT == SELECTOR_LOCAL_BIT
+    }
+
+    /// Sets whether the [`SegmentSelector`] refers to the Local Descriptor Table.
+    pub const fn set_local(self, local: bool) -> Self {
+        Self((self.0 & !SELECTOR_LOCAL_BIT) | ((local as u16) << SELECTOR_LOCAL_SHIFT))
+    }
+
+    /// Returns the index of the descriptor to which the [`SegmentSelector`] refers.
+    pub const fn index(self) -> u16 {
+        (self.0 & SELECTOR_INDEX_MASK) >> SELECTOR_INDEX_SHIFT
+    }
+
+    /// Sets the index of the descriptor to which the [`SegmentSelector`] refers.
+    ///
+    /// Only the lower 13 bits of `index` are used.
+    pub const fn set_index(self, index: u16) -> Self {
+        Self(
+            (self.0 & !SELECTOR_INDEX_MASK)
+                | ((index << SELECTOR_INDEX_SHIFT) & SELECTOR_INDEX_MASK),
+        )
+    }
+}
+
+/// The types of system segments that can be described by a [`SegmentDescriptor`] or a
+/// [`SystemSegmentDescriptor64`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SystemSegmentType {
+    /// A Local Descriptor Table.
+    Ldt = 0x2,
+    /// A task state segment that is not busy.
+    AvailableTss = 0x9,
+    /// A task state segment that is busy.
+    BusyTss = 0xB,
+}
+
+/// A code, data, or 32-bit system segment descriptor.
+///
+/// This representation does not do any validity checking.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SegmentDescriptor(u64);
+
+impl SegmentDescriptor {
+    /// Creates the null [`SegmentDescriptor`].
+    pub const fn null() -> Self {
+        Self(0)
+    }
+
+    /// Creates a present, flat, ring 0 [`SegmentDescriptor`] with the provided `executable` flag,
+    /// which covers the entire 4 GiB address space.
+    ///
+    /// The descriptor is marked as accessed so that loading it does not write to the table.
+    const fn flat(executable: bool) -> Self {
+        Self::null()
+            .set_base(0)
+            .set_limit(MAX_SEGMENT_LIMIT)
+            .set_granularity(true)
+            .set_accessed(true)
+            .set_read_write(true)
+            .set_executable(executable)
+            .set_code_or_data(true)
+            .set_dpl(PrivilegeLevel::Ring0)
+            .set_present(true)
+    }
+
+    /// Creates a present, flat, ring 0, 32-bit code [`SegmentDescriptor`].
+    pub const fn code_32() -> Self {
+        Self::flat(true).set_default_size(true)
+    }
+
+    /// Creates a present, flat, ring 0, 32-bit data [`SegmentDescriptor`].
+    pub const fn data_32() -> Self {
+        Self::flat(false).set_default_size(true)
+    }
+
+    /// Creates a present, ring 0, 64-bit code [`SegmentDescriptor`].
+    pub const fn code_64() -> Self {
+        Self::flat(true).set_long_mode(true)
+    }
+
+    /// Creates a present, ring 0 data [`SegmentDescriptor`] for use in 64-bit mode.
+    ///
+    /// This is identical to [`SegmentDescriptor::data_32()`], since 64-bit mode ignores most
+    /// fields of data segment descriptors.
+    pub const fn data_64() -> Self {
+        Self::data_32()
+    }
+
+    /// Creates a present, ring 0 system [`SegmentDescriptor`] that describes a 32-bit task state
+    /// segment at `base` with the provided `limit`.
+    pub const fn tss_32(base: u32, limit: u32) -> Self {
+        Self::null()
+            .set_base(base)
+            .set_limit(limit)
+            .set_system_type(SystemSegmentType::AvailableTss)
+            .set_dpl(PrivilegeLevel::Ring0)
+            .set_present(true)
+    }
+
+    /// Constructs a new [`SegmentDescriptor`] from the bit representation.
+    pub const fn from_bits(raw: u64) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the bit representation of the [`SegmentDescriptor`].
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the base address of the segment.
+    pub const fn base(self) -> u32 {
+        ((((self.0 & BASE_LOW_MASK) >> BASE_LOW_SHIFT) | ((self.0 & BASE_HIGH_MASK) >> 32))
+            & 0xFFFF_FFFF) as u32
+    }
+
+    /// Sets the base address of the segment.
+    pub const fn set_base(self, base: u32) -> Self {
+        let base = base as u64;
+        Self(
+            (self.0 & !(BASE_LOW_MASK | BASE_HIGH_MASK))
+                | ((base << BASE_LOW_SHIFT) & BASE_LOW_MASK)
+                | ((base << 32) & BASE_HIGH_MASK),
+        )
+    }
+
+    /// Returns the limit of the segment, in units determined by
+    /// [`SegmentDescriptor::granularity()`].
+    pub const fn limit(self) -> u32 {
+        (((self.0 & LIMIT_LOW_MASK) | ((self.0 & LIMIT_HIGH_MASK) >> 32)) & 0xFFFF_FFFF) as u32
+    }
+
+    /// Sets the limit of the segment, in units determined by
+    /// [`SegmentDescriptor::granularity()`].
+    ///
+    /// Only the lower 20 bits of `limit` are used.
+    pub const fn set_limit(self, limit: u32) -> Self {
+        let limit = limit as u64;
+        Self(
+            (self.0 & !(LIMIT_LOW_MASK | LIMIT_HIGH_MASK))
+                | (limit & LIMIT_LOW_MASK)
+                | ((limit << 32) & LIMIT_HIGH_MASK),
+        )
+    }
+
+    /// Returns `true` if the segment has been accessed.
+    ///
+    /// This should only be used on code or data segment descriptors.
+    pub const fn accessed(self) -> bool {
+        self.0 & ACCESSED_BIT == ACCESSED_BIT
+    }
+
+    /// Sets whether the segment has been accessed.
+    ///
+    /// This should only be used on code or data segment descriptors.
+    pub const fn set_accessed(self, accessed: bool) -> Self {
+        Self((self.0 & !ACCESSED_BIT) | (bool_as_u64(accessed) << ACCESSED_SHIFT))
+    }
+
+    /// Returns `true` if the code segment is readable or the data segment is writable.
+    ///
+    /// This should only be used on code or data segment descriptors.
+    pub const fn read_write(self) -> bool {
+        self.0 & READ_WRITE_BIT == READ_WRITE_BIT
+    }
+
+    /// Sets whether the code segment is readable or the data segment is writable.
+    ///
+    /// This should only be used on code or data segment descriptors.
+    pub const fn set_read_write(self, read_write: bool) -> Self {
+        Self((self.0 & !READ_WRITE_BIT) | (bool_as_u64(read_write) << READ_WRITE_SHIFT))
+    }
+
+    /// Returns `true` if the code segment is conforming or the data segment expands downwards.
+    ///
+    /// This should only be used on code or data segment descriptors.
+    pub const fn conforming_expand_down(self) -> bool {
+        self.0 & CONFORMING_EXPAND_DOWN_BIT == CONFORMING_EXPAND_DOWN_BIT
+    }
+
+    /// Sets whether the code segment is conforming or the data segment expands downwards.
+    ///
+    /// This should only be used on code or data segment descriptors.
+    pub const fn set_conforming_expand_down(self, value: bool) -> Self {
+        Self(
+            (self.0 & !CONFORMING_EXPAND_DOWN_BIT)
+                | (bool_as_u64(value) << CONFORMING_EXPAND_DOWN_SHIFT),
+        )
+    }
+
+    /// Returns `true` if the segment is a code segment.
+    ///
+    /// This should only be used on code or data segment descriptors.
+    pub const fn executable(self) -> bool {
+        self.0 & EXECUTABLE_BIT == EXECUTABLE_BIT
+    }
+
+    /// Sets whether the segment is a code segment.
+    ///
+    /// This should only be used on code or data segment descriptors.
+    pub const fn set_executable(self, executable: bool) -> Self {
+        Self((self.0 & !EXECUTABLE_BIT) | (bool_as_u64(executable) << EXECUTABLE_SHIFT))
+    }
+
+    /// Returns `true` if the [`SegmentDescriptor`] describes a code or data segment, rather than a
+    /// system segment.
+    pub const fn code_or_data(self) -> bool {
+        self.0 & CODE_OR_DATA_BIT == CODE_OR_DATA_BIT
+    }
+
+    /// Sets whether the [`SegmentDescriptor`] describes a code or data segment, rather than a
+    /// system segment.
+    pub const fn set_code_or_data(self, code_or_data: bool) -> Self {
+        Self((self.0 & !CODE_OR_DATA_BIT) | (bool_as_u64(code_or_data) << CODE_OR_DATA_SHIFT))
+    }
+
+    /// Returns the [`SystemSegmentType`] of the [`SegmentDescriptor`], or [`None`] if the
+    /// [`SegmentDescriptor`] is not a system segment descriptor of a known type.
+    pub const fn system_type(self) -> Option<SystemSegmentType> {
+        if self.code_or_data() {
+            return None;
+        }
+
+        system_segment_type((self.0 & TYPE_MASK) >> TYPE_SHIFT)
+    }
+
+    /// Marks the [`SegmentDescriptor`] as a system segment descriptor of the provided `ty`.
+    pub const fn set_system_type(self, ty: SystemSegmentType) -> Self {
+        Self((self.0 & !(TYPE_MASK | CODE_OR_DATA_BIT)) | ((ty as u64) << TYPE_SHIFT))
+    }
+
+    /// Returns the descriptor privilege level of the segment.
+    pub const fn dpl(self) -> PrivilegeLevel {
+        privilege_level(((self.0 & DPL_MASK) >> DPL_SHIFT) as u16)
+    }
+
+    /// Sets the descriptor privilege level of the segment.
+    pub const fn set_dpl(self, dpl: PrivilegeLevel) -> Self {
+        Self((self.0 & !DPL_MASK) | ((dpl as u64) << DPL_SHIFT))
+    }
+
+    /// Returns `true` if the segment is present.
+    pub const fn present(self) -> bool {
+        self.0 & PRESENT_BIT == PRESENT_BIT
+    }
+
+    /// Sets whether the segment is present.
+    pub const fn set_present(self, present: bool) -> Self {
+        Self((self.0 & !PRESENT_BIT) | (bool_as_u64(present) << PRESENT_SHIFT))
+    }
+
+    /// Returns the value of the bit that is available for use by system software.
+    pub const fn available(self) -> bool {
+        self.0 & AVAILABLE_BIT == AVAILABLE_BIT
+    }
+
+    /// Sets the value of the bit that is available for use by system software.
+    pub const fn set_available(self, available: bool) -> Self {
+        Self((self.0 & !AVAILABLE_BIT) | (bool_as_u64(available) << AVAILABLE_SHIFT))
+    }
+
+    /// Returns `true` if the code segment contains 64-bit code.
+    ///
+    /// This should only be used on code segment descriptors.
+    pub const fn long_mode(self) -> bool {
+        self.0 & LONG_MODE_BIT == LONG_MODE_BIT
+    }
+
+    /// Sets whether the code segment contains 64-bit code.
+    ///
+    /// This should only be used on code segment descriptors.
+    pub const fn set_long_mode(self, long_mode: bool) -> Self {
+        Self((self.0 & !LONG_MODE_BIT) | (bool_as_u64(long_mode) << LONG_MODE_SHIFT))
+    }
+
+    /// Returns `true` if the segment uses 32-bit default operands and addresses.
+    pub const fn default_size(self) -> bool {
+        self.0 & DEFAULT_SIZE_BIT == DEFAULT_SIZE_BIT
+    }
+
+    /// Sets whether the segment uses 32-bit default operands and addresses.
+    pub const fn set_default_size(self, default_size: bool) -> Self {
+        Self((self.0 & !DEFAULT_SIZE_BIT) | (bool_as_u64(default_size) << DEFAULT_SIZE_SHIFT))
+    }
+
+    /// Returns `true` if the limit of the segment is measured in 4 KiB units rather than bytes.
+    pub const fn granularity(self) -> bool {
+        self.0 & GRANULARITY_BIT == GRANULARITY_BIT
+    }
+
+    /// Sets whether the limit of the segment is measured in 4 KiB units rather than bytes.
+    pub const fn set_granularity(self, granularity: bool) -> Self {
+        Self((self.0 & !GRANULARITY_BIT) | (bool_as_u64(granularity) << GRANULARITY_SHIFT))
+    }
+}
+
+/// A 64-bit system segment descriptor, which occupies two entries of the Global Descriptor Table.
+///
+/// This representation does not do any validity checking.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SystemSegmentDescriptor64 {
+    /// The lower half of the descriptor, which is laid out like a [`SegmentDescriptor`].
+    low: SegmentDescriptor,
+    /// The upper half of the descriptor, which contains the upper 32 bits of the base address.
+    high: u64,
+}
+
+impl SystemSegmentDescriptor64 {
+    /// Creates a [`SystemSegmentDescriptor64`] that is not present.
+    pub const fn non_present() -> Self {
+        Self {
+            low: SegmentDescriptor::null(),
+            high: 0,
+        }
+    }
+
+    /// Creates a present, ring 0 [`SystemSegmentDescriptor64`] that describes a 64-bit task state
+    /// segment at `base` with the provided `limit`.
+    pub const fn tss(base: u64, limit: u32) -> Self {
+        Self::non_present()
+            .set_base(base)
+            .set_limit(limit)
+            .set_system_type(SystemSegmentType::AvailableTss)
+            .set_dpl(PrivilegeLevel::Ring0)
+            .set_present(true)
+    }
+
+    /// Constructs a new [`SystemSegmentDescriptor64`] from the bit representation.
+    pub const fn from_bits(raw: [u64; 2]) -> Self {
+        Self {
+            low: SegmentDescriptor::from_bits(raw[0]),
+            high: raw[1],
+        }
+    }
+
+    /// Returns the bit representation of the [`SystemSegmentDescriptor64`], in the order in which
+    /// the two entries are placed in the Global Descriptor Table.
+    pub const fn to_bits(self) -> [u64; 2] {
+        [self.low.to_bits(), self.high]
+    }
+
+    /// Returns the base address of the segment.
+    pub const fn base(self) -> u64 {
+        (self.low.base() as u64) | ((self.high & 0xFFFF_FFFF) << 32)
+    }
+
+    /// Sets the base address of the segment.
+    pub const fn set_base(self, base: u64) -> Self {
+        Self {
+            low: self.low.set_base((base & 0xFFFF_FFFF) as u32),
+            high: (self.high & !0xFFFF_FFFF) | (base >> 32),
+        }
+    }
+
+    /// Returns the limit of the segment, in units determined by
+    /// [`SystemSegmentDescriptor64::granularity()`].
+    pub const fn limit(self) -> u32 {
+        self.low.limit()
+    }
+
+    /// Sets the limit of the segment, in units determined by
+    /// [`SystemSegmentDescriptor64::granularity()`].
+    ///
+    /// Only the lower 20 bits of `limit` are used.
+    pub const fn set_limit(self, limit: u32) -> Self {
+        Self {
+            low: self.low.set_limit(limit),
+            high: self.high,
+        }
+    }
+
+    /// Returns the [`SystemSegmentType`] of the [`SystemSegmentDescriptor64`], or [`None`] if the
+    /// type is not known.
+    pub const fn system_type(self) -> Option<SystemSegmentType> {
+        self.low.system_type()
+    }
+
+    /// Sets the [`SystemSegmentType`] of the [`SystemSegmentDescriptor64`].
+    pub const fn set_system_type(self, ty: SystemSegmentType) -> Self {
+        Self {
+            low: self.low.set_system_type(ty),
+            high: self.high,
+        }
+    }
+
+    /// Returns the descriptor privilege level of the segment.
+    pub const fn dpl(self) -> PrivilegeLevel {
+        self.low.dpl()
+    }
+
+    /// Sets the descriptor privilege level of the segment.
+    pub const fn set_dpl(self, dpl: PrivilegeLevel) -> Self {
+        Self {
+            low: self.low.set_dpl(dpl),
+            high: self.high,
+        }
+    }
+
+    /// Returns `true` if the segment is present.
+    pub const fn present(self) -> bool {
+        self.low.present()
+    }
+
+    /// Sets whether the segment is present.
+    pub const fn set_present(self, present: bool) -> Self {
+        Self {
+            low: self.low.set_present(present),
+            high: self.high,
+        }
+    }
+
+    /// Returns `true` if the limit of the segment is measured in 4 KiB units rather than bytes.
+    pub const fn granularity(self) -> bool {
+        self.low.granularity()
+    }
+
+    /// Sets whether the limit of the segment is measured in 4 KiB units rather than bytes.
+    pub const fn set_granularity(self, granularity: bool) -> Self {
+        Self {
+            low: self.low.set_granularity(granularity),
+            high: self.high,
+        }
+    }
+}
+
+/// The in-memory operand of the `LGDT`, `LIDT`, `SGDT`, and `SIDT` instructions.
+///
+/// In 32-bit modes, only the lower 32 bits of [`DescriptorTablePointer::base`] are used.
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DescriptorTablePointer {
+    /// The size, in bytes, of the table minus one.
+    pub limit: u16,
+    /// The linear address of the table.
+    pub base: u64,
+}
+
+impl DescriptorTablePointer {
+    /// Creates a [`DescriptorTablePointer`] that refers to the provided `table`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `table` is empty or larger than 64 KiB.
+    pub fn new<T>(table: &[T]) -> Self {
+        let limit = core::mem::size_of_val(table)
+            .checked_sub(1)
+            .and_then(|limit| u16::try_from(limit).ok())
+            .expect("descriptor table size is invalid");
+
+        #[expect(clippy::as_conversions)]
+        Self {
+            limit,
+            base: table.as_ptr().addr() as u64,
+        }
+    }
+}
+
+/// Loads the Global Descriptor Table described by `pointer`.
+///
+/// This does not reload any segment registers.
+///
+/// # Safety
+///
+/// The table described by `pointer` must remain valid and accessible for as long as it is in
+/// use, and every segment register that is subsequently reloaded must refer to a valid descriptor.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn load_gdt(pointer: &DescriptorTablePointer) {
+    // SAFETY:
+    //
+    // The invariants of this function ensure that this operation is safe.
+    unsafe { asm!("lgdt [{}]", in(reg) pointer, options(readonly, nostack, preserves_flags)) }
+}
+
+/// Returns the [`DescriptorTablePointer`] of the currently loaded Global Descriptor Table.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn store_gdt() -> DescriptorTablePointer {
+    let mut pointer = DescriptorTablePointer::default();
+
+    // SAFETY:
+    //
+    // `pointer` is valid for writes of a `DescriptorTablePointer`.
+    unsafe { asm!("sgdt [{}]", in(reg) &raw mut pointer, options(nostack, preserves_flags)) }
+    pointer
+}
+
+/// Wrapper around generating segment register read and load functions.
+macro_rules! segment_register {
+    ($read_name:ident, $load_name:ident, $reg_name:literal) => {
+        #[doc = concat!("Returns the [`SegmentSelector`] loaded in the `", $reg_name, "` register.")]
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        pub fn $read_name() -> SegmentSelector {
+            let selector: u16;
+
+            // SAFETY:
+            //
+            // Reading a segment register has no side effects.
+            unsafe {
+                asm!(
+                    concat!("mov {:x}, ", $reg_name),
+                    out(reg) selector,
+                    options(nomem, nostack, preserves_flags)
+                )
+            }
+            SegmentSelector::from_bits(selector)
+        }
+
+        #[doc = concat!("Loads `selector` into the `", $reg_name, "` register.")]
+        ///
+        /// # Safety
+        ///
+        /// `selector` must refer to a descriptor that is valid for the register and the change
+        /// must be compatible with the current state of the system.
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        pub unsafe fn $load_name(selector: SegmentSelector) {
+            // SAFETY:
+            //
+            // The invariants of this function ensure that this operation is safe.
+            unsafe {
+                asm!(
+                    concat!("mov ", $reg_name, ", {:x}"),
+                    in(reg) selector.to_bits(),
+                    options(nostack, preserves_flags)
+                )
+            }
+        }
+    };
+}
+
+segment_register! {read_ds, load_ds, "ds"}
+segment_register! {read_es, load_es, "es"}
+segment_register! {read_fs, load_fs, "fs"}
+segment_register! {read_gs, load_gs, "gs"}
+segment_register! {read_ss, load_ss, "ss"}
+
+/// Returns the [`SegmentSelector`] loaded in the `cs` register.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn read_cs() -> SegmentSelector {
+    let selector: u16;
+
+    // SAFETY:
+    //
+    // Reading a segment register has no side effects.
+    unsafe {
+        asm!(
+            "mov {:x}, cs",
+            out(reg) selector,
+            options(nomem, nostack, preserves_flags)
+        )
+    }
+    SegmentSelector::from_bits(selector)
+}
+
+/// Loads `selector` into the `cs` register by performing a far return to the next instruction.
+///
+/// # Safety
+///
+/// `selector` must refer to a code segment descriptor that is compatible with the current
+/// operating mode and the change must be compatible with the current state of the system.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn load_cs(selector: SegmentSelector) {
+    let selector = usize::from(selector.to_bits());
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that this operation is safe.
+    unsafe {
+        asm!(
+            "push {selector}",
+            "call 2f",
+            "2:",
+            "pop {tmp}",
+            ".equ load_cs_offset, 3f - 2b",
+            "add {tmp}, offset load_cs_offset",
+            "push {tmp}",
+            #[cfg(target_arch = "x86")]
+            "retf",
+            #[cfg(target_arch = "x86_64")]
+            "retfq",
+            "3:",
+            selector = in(reg) selector,
+            tmp = lateout(reg) _,
+        )
+    }
+}
+
+/// Loads `selector` into the `ds`, `es`, `fs`, `gs`, and `ss` registers.
+///
+/// # Safety
+///
+/// `selector` must refer to a writable data segment descriptor and the change must be compatible
+/// with the current state of the system.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn load_data_segments(selector: SegmentSelector) {
+    // SAFETY:
+    //
+    // The invariants of this function ensure that this operation is safe.
+    unsafe {
+        asm!(
+            "mov ds, {0:x}",
+            "mov es, {0:x}",
+            "mov fs, {0:x}",
+            "mov gs, {0:x}",
+            "mov ss, {0:x}",
+            in(reg) selector.to_bits(),
+            options(nostack, preserves_flags)
+        )
+    }
+}
+
+/// Returns the [`PrivilegeLevel`] corresponding to the lower two bits of `value`.
+const fn privilege_level(value: u16) -> PrivilegeLevel {
+    match value & 0b11 {
+        0 => PrivilegeLevel::Ring0,
+        1 => PrivilegeLevel::Ring1,
+        2 => PrivilegeLevel::Ring2,
+        _ => PrivilegeLevel::Ring3,
+    }
+}
+
+/// Returns the [`SystemSegmentType`] corresponding to `value`.
+const fn system_segment_type(value: u64) -> Option<SystemSegmentType> {
+    match value {
+        0x2 => Some(SystemSegmentType::Ldt),
+        0x9 => Some(SystemSegmentType::AvailableTss),
+        0xB => Some(SystemSegmentType::BusyTss),
+        _ => None,
+    }
+}
+
+#[expect(clippy::as_conversions)]
+const fn bool_as_u64(value: bool) -> u64 {
+    value as u64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn flat_segment_descriptors() {
+        let cases = [
+            (SegmentDescriptor::null(), 0x0000_0000_0000_0000),
+            (SegmentDescriptor::code_32(), 0x00CF_9B00_0000_FFFF),
+            (SegmentDescriptor::data_32(), 0x00CF_9300_0000_FFFF),
+            (SegmentDescriptor::code_64(), 0x00AF_9B00_0000_FFFF),
+            (SegmentDescriptor::data_64(), 0x00CF_9300_0000_FFFF),
+        ];
+
+        for (descriptor, bits) in cases {
+            assert_eq!(descriptor.to_bits(), bits, "{descriptor:?}");
+        }
+    }
+
+    #[test]
+    fn segment_descriptor_fields() {
+        let descriptor = SegmentDescriptor::null()
+            .set_base(0x1234_5678)
+            .set_limit(0xA_BCDE)
+            .set_dpl(PrivilegeLevel::Ring3)
+            .set_available(true);
+
+        assert_eq!(descriptor.to_bits(), 0x121A_6034_5678_BCDE);
+        assert_eq!(descriptor.base(), 0x1234_5678);
+        assert_eq!(descriptor.limit(), 0xA_BCDE);
+        assert_eq!(descriptor.dpl(), PrivilegeLevel::Ring3);
+        assert!(descriptor.available());
+        assert!(!descriptor.present());
+
+        let descriptor = descriptor.set_base(0).set_limit(0);
+        assert_eq!(descriptor.to_bits(), 0x0010_6000_0000_0000);
+    }
+
+    #[test]
+    fn task_state_segment_descriptors() {
+        let descriptor = SegmentDescriptor::tss_32(0x0012_3400, 0x67);
+        assert_eq!(descriptor.to_bits(), 0x0000_8912_3400_0067);
+        assert_eq!(
+            descriptor.system_type(),
+            Some(SystemSegmentType::AvailableTss)
+        );
+
+        let descriptor = SystemSegmentDescriptor64::tss(0xFFFF_8000_1234_5678, 0x67);
+        assert_eq!(
+            descriptor.to_bits(),
+            [0x1200_8934_5678_0067, 0x0000_0000_FFFF_8000]
+        );
+        assert_eq!(descriptor.base(), 0xFFFF_8000_1234_5678);
+        assert_eq!(descriptor.limit(), 0x67);
+        assert_eq!(
+            descriptor
+                .set_system_type(SystemSegmentType::BusyTss)
+                .system_type(),
+            Some(SystemSegmentType::BusyTss)
+        );
+        assert_eq!(SegmentDescriptor::code_64().system_type(), None);
+    }
+
+    #[test]
+    fn segment_selectors() {
+        let cases = [
+            (SegmentSelector::NULL, 0, false, PrivilegeLevel::Ring0, 0x00),
+            (
+                SegmentSelector::new(3, PrivilegeLevel::Ring0),
+                3,
+                false,
+                PrivilegeLevel::Ring0,
+                0x18,
+            ),
+            (
+                SegmentSelector::new(4, PrivilegeLevel::Ring3),
+                4,
+                false,
+                PrivilegeLevel::Ring3,
+                0x23,
+            ),
+            (
+                SegmentSelector::new(1, PrivilegeLevel::Ring1).set_local(true),
+                1,
+                true,
+                PrivilegeLevel::Ring1,
+                0x0D,
+            ),
+        ];
+
+        for (selector, index, local, rpl, bits) in cases {
+            assert_eq!(selector.index(), index);
+            assert_eq!(selector.local(), local);
+            assert_eq!(selector.rpl(), rpl);
+            assert_eq!(selector.to_bits(), bits);
+        }
+    }
+
+    #[test]
+    fn descriptor_table_pointer() {
+        let table = [0u64; 5];
+        let pointer = DescriptorTablePointer::new(&table);
+
+        assert_eq!({ pointer.limit }, 39);
+        assert_eq!({ pointer.base }, table.as_ptr().addr() as u64);
+        assert_eq!(core::mem::size_of::<DescriptorTablePointer>(), 10);
+    }
+}
@@ -0,0 +1,518 @@
+//! Time Stamp Counter (TSC) access, frequency discovery, and calibration.
+//!
+//! The TSC frequency is reported directly by CPUID leaf 0x15 on recent processors. Otherwise, it
+//! must be measured against a [`ReferenceClock`] of known frequency, such as the [`Hpet`] or the
+//! ACPI [`PmTimer`]. [`select_frequency()`] describes the order in which these sources are used.
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use core::arch::asm;
+
+use crate::cpuid::Cpuid;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::cpuid::{cpuid_unchecked, supports_cpuid};
+
+/// The number of femtoseconds in a second.
+const FEMTOSECONDS_PER_SECOND: u64 = 1_000_000_000_000_000;
+
+/// Returns `true` if the processor supports the RDTSC instruction.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn supports_tsc() -> bool {
+    // SAFETY:
+    //
+    // The CPUID instruction is supported.
+    supports_cpuid() && (unsafe { cpuid_unchecked(1, 0) }.edx >> 4) & 1 == 1
+}
+
+/// Returns `true` if the TSC runs at a constant rate in all ACPI P-, C-, and T-states.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn supports_invariant_tsc() -> bool {
+    if !supports_cpuid() {
+        return false;
+    }
+
+    // SAFETY:
+    //
+    // The CPUID instruction is supported.
+    let max_extended = unsafe { cpuid_unchecked(0x8000_0000, 0) }.eax;
+    // SAFETY:
+    //
+    // The CPUID instruction is supported and leaf 0x8000_0007 is within the supported range.
+    max_extended >= 0x8000_0007 && (unsafe { cpuid_unchecked(0x8000_0007, 0) }.edx >> 8) & 1 == 1
+}
+
+/// Returns the current value of the TSC.
+///
+/// The read is not ordered with respect to surrounding instructions; use [`read_tsc_ordered()`]
+/// when measuring an interval.
+///
+/// # Safety
+///
+/// The RDTSC instruction must be supported and permitted at the current privilege level.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn read_tsc() -> u64 {
+    let low: u32;
+    let high: u32;
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that RDTSC is supported and permitted.
+    unsafe {
+        asm!(
+            "rdtsc",
+            lateout("eax") low,
+            lateout("edx") high,
+            options(nomem, nostack, preserves_flags)
+        )
+    }
+
+    (u64::from(high) << 32) | u64::from(low)
+}
+
+/// Returns the current value of the TSC, after every preceding instruction has completed and
+/// before any following instruction begins.
+///
+/// # Safety
+///
+/// The RDTSC instruction must be supported and permitted at the current privilege level, and the
+/// processor must support SSE2.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn read_tsc_ordered() -> u64 {
+    let low: u32;
+    let high: u32;
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that RDTSC and LFENCE are supported and permitted.
+    unsafe {
+        asm!(
+            "lfence",
+            "rdtsc",
+            "lfence",
+            lateout("eax") low,
+            lateout("edx") high,
+            options(nomem, nostack, preserves_flags)
+        )
+    }
+
+    (u64::from(high) << 32) | u64::from(low)
+}
+
+/// Returns the TSC frequency, in hertz, reported by CPUID leaf 0x15.
+///
+/// Returns [`None`] if the processor does not enumerate the TSC frequency.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn cpuid_frequency() -> Option<u64> {
+    cpuid_leaf(0x15).and_then(frequency_from_tsc_leaf)
+}
+
+/// Returns the processor base frequency, in hertz, reported by CPUID leaf 0x16.
+///
+/// The base frequency is not architecturally defined to be the TSC frequency and is only an
+/// approximation of it, so it should only be used when the TSC frequency can neither be read from
+/// CPUID leaf 0x15 nor calibrated.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn cpuid_base_frequency() -> Option<u64> {
+    cpuid_leaf(0x16).and_then(frequency_from_frequency_leaf)
+}
+
+/// Returns the contents of the basic CPUID leaf `leaf`, or [`None`] if it is not supported.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn cpuid_leaf(leaf: u32) -> Option<Cpuid> {
+    if !supports_cpuid() {
+        return None;
+    }
+
+    // SAFETY:
+    //
+    // The CPUID instruction is supported.
+    let max_basic = unsafe { cpuid_unchecked(0, 0) }.eax;
+    // SAFETY:
+    //
+    // The CPUID instruction is supported and `leaf` is within the supported range.
+    (max_basic >= leaf).then(|| unsafe { cpuid_unchecked(leaf, 0) })
+}
+
+/// Computes the TSC frequency, in hertz, from the contents of CPUID leaf 0x15 (TSC and core
+/// crystal clock information).
+///
+/// Returns [`None`] if the leaf does not enumerate the TSC ratio or the crystal clock frequency.
+pub const fn frequency_from_tsc_leaf(tsc_leaf: Cpuid) -> Option<u64> {
+    let Cpuid {
+        eax: denominator,
+        ebx: numerator,
+        ecx: crystal_frequency,
+        ..
+    } = tsc_leaf;
+    if denominator == 0 || numerator == 0 || crystal_frequency == 0 {
+        return None;
+    }
+
+    Some(crystal_frequency as u64 * numerator as u64 / denominator as u64)
+}
+
+/// Computes the processor base frequency, in hertz, from the contents of CPUID leaf 0x16
+/// (processor frequency information).
+///
+/// Returns [`None`] if the leaf does not enumerate the base frequency.
+pub const fn frequency_from_frequency_leaf(frequency_leaf: Cpuid) -> Option<u64> {
+    match frequency_leaf.eax & 0xFFFF {
+        0 => None,
+        megahertz => Some(megahertz as u64 * 1_000_000),
+    }
+}
+
+/// Selects the TSC frequency, in hertz, from the available sources in order of accuracy.
+///
+/// The frequency enumerated by CPUID leaf 0x15 is exact and preferred. Otherwise, the TSC is
+/// calibrated against a [`ReferenceClock`] by `calibrate`, which is only invoked when needed. The
+/// base frequency from CPUID leaf 0x16 is only an approximation of the TSC frequency and is used as
+/// a last resort when no reference clock is available.
+pub fn select_frequency(
+    cpuid_frequency: Option<u64>,
+    calibrate: impl FnOnce() -> Option<u64>,
+    base_frequency: Option<u64>,
+) -> Option<u64> {
+    cpuid_frequency
+        .or_else(calibrate)
+        .filter(|&frequency| frequency != 0)
+        .or(base_frequency)
+}
+
+/// A free-running counter of known frequency against which the TSC can be calibrated.
+pub trait ReferenceClock {
+    /// Returns the frequency of the counter, in hertz.
+    fn frequency(&self) -> u64;
+
+    /// Returns the number of implemented bits of the counter, after which it wraps to zero.
+    fn width(&self) -> u32;
+
+    /// Returns the current value of the counter.
+    fn read(&self) -> u64;
+}
+
+/// Returns the number of ticks elapsed between `start` and `end` on a counter that is `width` bits
+/// wide, accounting for a single wrap of the counter.
+pub const fn counter_delta(start: u64, end: u64, width: u32) -> u64 {
+    let mask = if width >= 64 {
+        u64::MAX
+    } else {
+        (1 << width) - 1
+    };
+
+    end.wrapping_sub(start) & mask
+}
+
+/// Returns the frequency of a counter that advanced by `ticks` while a reference counter of
+/// `reference_frequency` hertz advanced by `reference_ticks`.
+///
+/// # Panics
+///
+/// Panics if `reference_ticks` is zero.
+pub fn scale_frequency(ticks: u64, reference_ticks: u64, reference_frequency: u64) -> u64 {
+    assert!(reference_ticks != 0, "reference counter did not advance");
+
+    let frequency =
+        u128::from(ticks) * u128::from(reference_frequency) / u128::from(reference_ticks);
+    u64::try_from(frequency).unwrap_or(u64::MAX)
+}
+
+/// Measures the TSC frequency, in hertz, by counting TSC ticks while `reference` advances by at
+/// least `reference_ticks`.
+///
+/// Interrupts should be disabled while calibrating, since any delay between reading the two
+/// counters skews the result.
+///
+/// # Safety
+///
+/// The RDTSC instruction must be supported and permitted at the current privilege level, and the
+/// processor must support SSE2.
+///
+/// # Panics
+///
+/// Panics if `reference_ticks` is zero or not smaller than the range of `reference`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn calibrate(reference: &impl ReferenceClock, reference_ticks: u64) -> u64 {
+    let width = reference.width();
+    assert!(
+        reference_ticks != 0 && (width >= 64 || reference_ticks < 1 << width),
+        "invalid calibration interval"
+    );
+
+    let reference_start = reference.read();
+    // SAFETY:
+    //
+    // The invariants of this function ensure that this operation is safe.
+    let tsc_start = unsafe { read_tsc_ordered() };
+
+    let mut reference_end = reference.read();
+    while counter_delta(reference_start, reference_end, width) < reference_ticks {
+        core::hint::spin_loop();
+        reference_end = reference.read();
+    }
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that this operation is safe.
+    let tsc_end = unsafe { read_tsc_ordered() };
+
+    scale_frequency(
+        tsc_end.wrapping_sub(tsc_start),
+        counter_delta(reference_start, reference_end, width),
+        reference.frequency(),
+    )
+}
+
+/// The ACPI power management timer, a 24-bit or 32-bit counter running at 3.579545 MHz.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PmTimer {
+    /// The I/O port of the `PM_TMR` register.
+    port: u16,
+    /// `true` if the counter is 32 bits wide.
+    extended: bool,
+}
+
+impl PmTimer {
+    /// The frequency, in hertz, of the ACPI power management timer.
+    pub const FREQUENCY: u64 = 3_579_545;
+
+    /// Creates a [`PmTimer`] that reads the counter at `port`.
+    ///
+    /// `extended` is the `TMR_VAL_EXT` flag of the FADT, which indicates a 32-bit counter.
+    ///
+    /// # Safety
+    ///
+    /// `port` must be the `PM_TMR_BLK` port described by the FADT.
+    pub const unsafe fn new(port: u16, extended: bool) -> Self {
+        Self { port, extended }
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl ReferenceClock for PmTimer {
+    fn frequency(&self) -> u64 {
+        Self::FREQUENCY
+    }
+
+    fn width(&self) -> u32 {
+        if self.extended { 32 } else { 24 }
+    }
+
+    fn read(&self) -> u64 {
+        // SAFETY:
+        //
+        // The invariants of [`PmTimer::new()`] ensure that `port` is the power management timer,
+        // which has no side effects when read.
+        let value = unsafe { crate::io_port::read_u32(self.port) };
+        counter_delta(0, u64::from(value), self.width())
+    }
+}
+
+/// The main counter of the High Precision Event Timer (HPET).
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Hpet {
+    /// The HPET register block.
+    registers: *mut u8,
+}
+
+impl Hpet {
+    /// The offset of the general capabilities and ID register.
+    const CAPABILITIES: usize = 0x000;
+    /// The offset of the general configuration register.
+    const CONFIGURATION: usize = 0x010;
+    /// The offset of the main counter value register.
+    const MAIN_COUNTER: usize = 0x0F0;
+
+    /// The bit of the general capabilities register that indicates a 64-bit main counter.
+    const COUNTER_SIZE_BIT: u64 = 1 << 13;
+    /// The bit of the general configuration register that enables the main counter.
+    const ENABLE_BIT: u64 = 1 << 0;
+
+    /// Creates an [`Hpet`] that accesses the HPET register block mapped at `registers`.
+    ///
+    /// # Safety
+    ///
+    /// `registers` must be an uncached mapping of the 1 KiB HPET register block described by the
+    /// ACPI HPET table, which remains valid for as long as the returned [`Hpet`] is used.
+    pub const unsafe fn new(registers: *mut u8) -> Self {
+        Self { registers }
+    }
+
+    /// Returns the contents of the general capabilities and ID register.
+    fn capabilities(&self) -> u64 {
+        // SAFETY:
+        //
+        // The invariants of [`Hpet::new()`] ensure that the register block is mapped, and reading
+        // the capabilities register has no side effects.
+        unsafe {
+            self.registers
+                .wrapping_add(Self::CAPABILITIES)
+                .cast::<u64>()
+                .read_volatile()
+        }
+    }
+
+    /// Returns the period of the main counter, in femtoseconds.
+    pub fn period(&self) -> u64 {
+        self.capabilities() >> 32
+    }
+
+    /// Returns `true` if the main counter is enabled.
+    pub fn enabled(&self) -> bool {
+        // SAFETY:
+        //
+        // The invariants of [`Hpet::new()`] ensure that the register block is mapped, and reading
+        // the configuration register has no side effects.
+        let configuration = unsafe {
+            self.registers
+                .wrapping_add(Self::CONFIGURATION)
+                .cast::<u64>()
+                .read_volatile()
+        };
+        configuration & Self::ENABLE_BIT == Self::ENABLE_BIT
+    }
+
+    /// Starts the main counter.
+    ///
+    /// # Safety
+    ///
+    /// Starting the main counter must not violate the expectations of other software using the
+    /// HPET.
+    pub unsafe fn enable(&self) {
+        let configuration = self
+            .registers
+            .wrapping_add(Self::CONFIGURATION)
+            .cast::<u64>();
+
+        // SAFETY:
+        //
+        // The invariants of [`Hpet::new()`] ensure that the register block is mapped.
+        let value = unsafe { configuration.read_volatile() };
+        // SAFETY:
+        //
+        // The invariants of this function ensure that starting the main counter is safe.
+        unsafe { configuration.write_volatile(value | Self::ENABLE_BIT) }
+    }
+}
+
+impl ReferenceClock for Hpet {
+    fn frequency(&self) -> u64 {
+        FEMTOSECONDS_PER_SECOND
+            .checked_div(self.period())
+            .unwrap_or(0)
+    }
+
+    fn width(&self) -> u32 {
+        if self.capabilities() & Self::COUNTER_SIZE_BIT == Self::COUNTER_SIZE_BIT {
+            64
+        } else {
+            32
+        }
+    }
+
+    fn read(&self) -> u64 {
+        // SAFETY:
+        //
+        // The invariants of [`Hpet::new()`] ensure that the register block is mapped, and reading
+        // the main counter has no side effects.
+        let value = unsafe {
+            self.registers
+                .wrapping_add(Self::MAIN_COUNTER)
+                .cast::<u64>()
+                .read_volatile()
+        };
+        counter_delta(0, value, self.width())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cpuid_frequencies() {
+        let leaf = |eax, ebx, ecx| Cpuid {
+            eax,
+            ebx,
+            ecx,
+            edx: 0,
+        };
+
+        let tsc_cases = [
+            (leaf(2, 176, 24_000_000), Some(2_112_000_000)),
+            (leaf(2, 200, 38_400_000), Some(3_840_000_000)),
+            (leaf(2, 176, 0), None),
+            (leaf(0, 0, 0), None),
+        ];
+        for (tsc_leaf, frequency) in tsc_cases {
+            assert_eq!(frequency_from_tsc_leaf(tsc_leaf), frequency, "{tsc_leaf:?}");
+        }
+
+        let frequency_cases = [
+            (leaf(2100, 3700, 100), Some(2_100_000_000)),
+            (leaf(0x1_0708, 0, 0), Some(1_800_000_000)),
+            (leaf(0, 0, 0), None),
+        ];
+        for (frequency_leaf, frequency) in frequency_cases {
+            assert_eq!(
+                frequency_from_frequency_leaf(frequency_leaf),
+                frequency,
+                "{frequency_leaf:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn frequency_selection() {
+        let cases = [
+            (Some(3_000), Some(2_000), Some(1_000), Some(3_000), false),
+            (None, Some(2_000), Some(1_000), Some(2_000), true),
+            (None, Some(0), Some(1_000), Some(1_000), true),
+            (None, None, Some(1_000), Some(1_000), true),
+            (None, None, None, None, true),
+        ];
+
+        for (cpuid, calibrated, base, frequency, calibrates) in cases {
+            let mut calibrated_called = false;
+            let selected = select_frequency(
+                cpuid,
+                || {
+                    calibrated_called = true;
+                    calibrated
+                },
+                base,
+            );
+            assert_eq!(selected, frequency, "{cpuid:?} {calibrated:?} {base:?}");
+            assert_eq!(calibrated_called, calibrates);
+        }
+    }
+
+    #[test]
+    fn counter_deltas() {
+        let cases = [
+            (100, 250, 24, 150),
+            (0xFF_FFF0, 0x10, 24, 0x20),
+            (0xFFFF_FFF0, 0x10, 32, 0x20),
+            (u64::MAX - 1, 2, 64, 4),
+        ];
+
+        for (start, end, width, delta) in cases {
+            assert_eq!(counter_delta(start, end, width), delta);
+        }
+    }
+
+    #[test]
+    fn scaled_frequencies() {
+        let cases = [
+            (2_000_000, 1_000, 1_000, 2_000_000),
+            (358_000_000, 357_954, PmTimer::FREQUENCY, 3_580_005_000),
+            (u64::MAX, 1, 2, u64::MAX),
+        ];
+
+        for (ticks, reference_ticks, reference_frequency, frequency) in cases {
+            assert_eq!(
+                scale_frequency(ticks, reference_ticks, reference_frequency),
+                frequency
+            );
+        }
+    }
+}
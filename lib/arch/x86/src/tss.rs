@@ -0,0 +1,203 @@
+//! Task state segments and the task register.
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use core::arch::asm;
+
+use crate::segmentation::SegmentSelector;
+
+/// The number of stacks in the interrupt stack table of a [`TaskStateSegment64`].
+pub const IST_COUNT: usize = 7;
+
+/// The 64-bit task state segment, which holds the stacks used when entering more privileged
+/// levels and the interrupt stack table.
+#[repr(C, packed(4))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TaskStateSegment64 {
+    /// Reserved.
+    reserved_0: u32,
+    /// The stack pointers loaded when entering rings 0, 1, and 2 from a less privileged ring.
+    pub privilege_stacks: [u64; 3],
+    /// Reserved.
+    reserved_1: u64,
+    /// The stack pointers selected by the interrupt stack table index of a gate, where entry `0`
+    /// corresponds to index `1`.
+    pub interrupt_stacks: [u64; IST_COUNT],
+    /// Reserved.
+    reserved_2: u64,
+    /// Reserved.
+    reserved_3: u16,
+    /// The offset, from the start of the [`TaskStateSegment64`], of the I/O permission bitmap.
+    pub io_map_base: u16,
+}
+
+impl TaskStateSegment64 {
+    /// Creates a [`TaskStateSegment64`] with no stacks and no I/O permission bitmap.
+    pub const fn new() -> Self {
+        Self {
+            reserved_0: 0,
+            privilege_stacks: [0; 3],
+            reserved_1: 0,
+            interrupt_stacks: [0; IST_COUNT],
+            reserved_2: 0,
+            reserved_3: 0,
+            io_map_base: Self::LIMIT + 1,
+        }
+    }
+
+    /// The limit of a segment descriptor that describes a [`TaskStateSegment64`] without an I/O
+    /// permission bitmap.
+    pub const LIMIT: u16 = 0x67;
+
+    /// Returns the stack pointer selected by the interrupt stack table index `ist`, or [`None`] if
+    /// `ist` is not in the range `1..=7`.
+    pub const fn interrupt_stack(&self, ist: u8) -> Option<u64> {
+        match ist {
+            1..=7 => Some(self.interrupt_stacks[ist as usize - 1]),
+            _ => None,
+        }
+    }
+
+    /// Sets the stack pointer selected by the interrupt stack table index `ist`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ist` is not in the range `1..=7`.
+    pub const fn set_interrupt_stack(&mut self, ist: u8, stack_pointer: u64) {
+        assert!(ist >= 1 && ist <= 7, "invalid interrupt stack table index");
+
+        self.interrupt_stacks[ist as usize - 1] = stack_pointer;
+    }
+}
+
+/// The 32-bit task state segment, which holds the state saved and restored by hardware task
+/// switches and the stacks used when entering more privileged levels.
+///
+/// Selector fields occupy the lower 16 bits of their `u32`; the upper 16 bits are reserved.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TaskStateSegment32 {
+    /// The selector of the task state segment of the previous task.
+    pub previous_task_link: u32,
+    /// The stack pointer loaded when entering ring 0.
+    pub esp0: u32,
+    /// The stack segment selector loaded when entering ring 0.
+    pub ss0: u32,
+    /// The stack pointer loaded when entering ring 1.
+    pub esp1: u32,
+    /// The stack segment selector loaded when entering ring 1.
+    pub ss1: u32,
+    /// The stack pointer loaded when entering ring 2.
+    pub esp2: u32,
+    /// The stack segment selector loaded when entering ring 2.
+    pub ss2: u32,
+    /// The value of the `CR3` register of the task.
+    pub cr3: u32,
+    /// The value of the `EIP` register of the task.
+    pub eip: u32,
+    /// The value of the `EFLAGS` register of the task.
+    pub eflags: u32,
+    /// The value of the `EAX` register of the task.
+    pub eax: u32,
+    /// The value of the `ECX` register of the task.
+    pub ecx: u32,
+    /// The value of the `EDX` register of the task.
+    pub edx: u32,
+    /// The value of the `EBX` register of the task.
+    pub ebx: u32,
+    /// The value of the `ESP` register of the task.
+    pub esp: u32,
+    /// The value of the `EBP` register of the task.
+    pub ebp: u32,
+    /// The value of the `ESI` register of the task.
+    pub esi: u32,
+    /// The value of the `EDI` register of the task.
+    pub edi: u32,
+    /// The value of the `ES` register of the task.
+    pub es: u32,
+    /// The value of the `CS` register of the task.
+    pub cs: u32,
+    /// The value of the `SS` register of the task.
+    pub ss: u32,
+    /// The value of the `DS` register of the task.
+    pub ds: u32,
+    /// The value of the `FS` register of the task.
+    pub fs: u32,
+    /// The value of the `GS` register of the task.
+    pub gs: u32,
+    /// The selector of the Local Descriptor Table of the task.
+    pub ldt: u32,
+    /// Bit 0 is the debug trap flag, and the upper 16 bits are the offset, from the start of the
+    /// [`TaskStateSegment32`], of the I/O permission bitmap.
+    pub trap_io_map_base: u32,
+}
+
+impl TaskStateSegment32 {
+    /// The limit of a segment descriptor that describes a [`TaskStateSegment32`] without an I/O
+    /// permission bitmap.
+    pub const LIMIT: u16 = 0x67;
+}
+
+/// Loads `selector` into the task register, marking the referenced task state segment as busy.
+///
+/// # Safety
+///
+/// `selector` must refer to an available task state segment descriptor in the Global Descriptor
+/// Table, and the task state segment must remain valid and accessible for as long as it is in
+/// use.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn load_task_register(selector: SegmentSelector) {
+    // SAFETY:
+    //
+    // The invariants of this function ensure that this operation is safe.
+    unsafe { asm!("ltr {:x}", in(reg) selector.to_bits(), options(nostack, preserves_flags)) }
+}
+
+/// Returns the [`SegmentSelector`] loaded in the task register.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn store_task_register() -> SegmentSelector {
+    let selector: u16;
+
+    // SAFETY:
+    //
+    // Reading the task register has no side effects.
+    unsafe { asm!("str {:x}", out(reg) selector, options(nomem, nostack, preserves_flags)) }
+    SegmentSelector::from_bits(selector)
+}
+
+#[cfg(test)]
+mod test {
+    use core::mem::{offset_of, size_of};
+
+    use super::*;
+
+    #[test]
+    fn task_state_segment_64_layout() {
+        assert_eq!(size_of::<TaskStateSegment64>(), 104);
+        assert_eq!(offset_of!(TaskStateSegment64, privilege_stacks), 0x04);
+        assert_eq!(offset_of!(TaskStateSegment64, interrupt_stacks), 0x24);
+        assert_eq!(offset_of!(TaskStateSegment64, io_map_base), 0x66);
+        assert_eq!(TaskStateSegment64::LIMIT, 0x67);
+    }
+
+    #[test]
+    fn task_state_segment_32_layout() {
+        assert_eq!(size_of::<TaskStateSegment32>(), 104);
+        assert_eq!(offset_of!(TaskStateSegment32, esp0), 0x04);
+        assert_eq!(offset_of!(TaskStateSegment32, cr3), 0x1C);
+        assert_eq!(offset_of!(TaskStateSegment32, ldt), 0x60);
+        assert_eq!(TaskStateSegment32::LIMIT, 0x67);
+    }
+
+    #[test]
+    fn interrupt_stacks() {
+        let mut tss = TaskStateSegment64::new();
+        tss.set_interrupt_stack(1, 0x1000);
+        tss.set_interrupt_stack(7, 0x7000);
+
+        assert_eq!(tss.interrupt_stack(0), None);
+        assert_eq!(tss.interrupt_stack(1), Some(0x1000));
+        assert_eq!(tss.interrupt_stack(7), Some(0x7000));
+        assert_eq!(tss.interrupt_stack(8), None);
+        assert_eq!({ tss.io_map_base }, 104);
+    }
+}
@@ -0,0 +1,959 @@
+//! XSAVE feature detection, `XCR0` management, and saving and restoring of extended processor
+//! state.
+//!
+//! CPUID leaf 0xD enumerates the state components that the processor can manage and the layout of
+//! each component within an XSAVE area. [`XsaveSupport`] and [`ComponentLayout`] decode that
+//! enumeration, from which [`standard_size()`] and [`compacted_size()`] compute the size of the
+//! area required for a set of [`XFeatures`]. [`XsaveArea`] provides a correctly aligned area of
+//! fixed capacity, along with the instructions that save to and restore from it.
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use core::arch::asm;
+use core::ops::{BitOr, BitOrAssign};
+
+use crate::cpuid::Cpuid;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::{
+    control::Cr4,
+    cpuid::{cpuid_unchecked, supports_cpuid},
+    msr::{read_msr, write_msr},
+};
+
+/// The size, in bytes, of the legacy region of an XSAVE area, which has the layout used by
+/// `FXSAVE`.
+pub const LEGACY_REGION_SIZE: u32 = 512;
+
+/// The size, in bytes, of the XSAVE header, which follows the legacy region.
+pub const HEADER_SIZE: u32 = 64;
+
+/// The offset, in bytes, of the extended region of an XSAVE area.
+pub const EXTENDED_REGION_OFFSET: u32 = LEGACY_REGION_SIZE + HEADER_SIZE;
+
+/// The architectural index of the `IA32_XSS` MSR, which enables supervisor state components.
+const IA32_XSS: u32 = 0xDA0;
+
+/// The offset of `MXCSR` within the legacy region.
+const MXCSR_OFFSET: usize = 24;
+
+/// The offset of `XSTATE_BV` within an XSAVE area.
+const XSTATE_BV_OFFSET: usize = LEGACY_REGION_SIZE as usize;
+
+/// The offset of `XCOMP_BV` within an XSAVE area.
+const XCOMP_BV_OFFSET: usize = XSTATE_BV_OFFSET + 8;
+
+/// The bit of `XCOMP_BV` that is set when an XSAVE area uses the compacted format.
+const XCOMP_BV_COMPACTED: u64 = 1 << 63;
+
+/// The initial value of `MXCSR`, with all SIMD floating-point exceptions masked.
+const MXCSR_DEFAULT: u32 = 0x1F80;
+
+/// The initial value of the x87 FPU control word.
+const FCW_DEFAULT: u16 = 0x037F;
+
+/// A set of XSAVE-managed state components.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct XFeatures(pub u64);
+
+impl XFeatures {
+    /// No state components.
+    pub const EMPTY: Self = Self(0);
+    /// The x87 FPU state.
+    pub const X87: Self = Self(1 << 0);
+    /// The `XMM` registers and `MXCSR`.
+    pub const SSE: Self = Self(1 << 1);
+    /// The upper halves of the `YMM` registers.
+    pub const AVX: Self = Self(1 << 2);
+    /// The MPX bounds registers.
+    pub const BNDREGS: Self = Self(1 << 3);
+    /// The MPX configuration and status registers.
+    pub const BNDCSR: Self = Self(1 << 4);
+    /// The AVX-512 opmask registers.
+    pub const OPMASK: Self = Self(1 << 5);
+    /// The upper halves of the lower 16 `ZMM` registers.
+    pub const ZMM_HI256: Self = Self(1 << 6);
+    /// The upper 16 `ZMM` registers.
+    pub const HI16_ZMM: Self = Self(1 << 7);
+    /// The processor trace state.
+    pub const PT: Self = Self(1 << 8);
+    /// The protection key rights register for user pages.
+    pub const PKRU: Self = Self(1 << 9);
+    /// The `IA32_PASID` state.
+    pub const PASID: Self = Self(1 << 10);
+    /// The user-mode control-flow enforcement state.
+    pub const CET_U: Self = Self(1 << 11);
+    /// The supervisor-mode control-flow enforcement state.
+    pub const CET_S: Self = Self(1 << 12);
+    /// The hardware duty cycling state.
+    pub const HDC: Self = Self(1 << 13);
+    /// The user interrupt state.
+    pub const UINTR: Self = Self(1 << 14);
+    /// The last branch record state.
+    pub const LBR: Self = Self(1 << 15);
+    /// The hardware P-state state.
+    pub const HWP: Self = Self(1 << 16);
+    /// The AMX tile configuration.
+    pub const TILECFG: Self = Self(1 << 17);
+    /// The AMX tile data registers.
+    pub const TILEDATA: Self = Self(1 << 18);
+
+    /// The state components used by AVX-512.
+    pub const AVX512: Self = Self(Self::OPMASK.0 | Self::ZMM_HI256.0 | Self::HI16_ZMM.0);
+    /// The state components used by MPX.
+    pub const MPX: Self = Self(Self::BNDREGS.0 | Self::BNDCSR.0);
+    /// The state components used by AMX.
+    pub const AMX: Self = Self(Self::TILECFG.0 | Self::TILEDATA.0);
+    /// The state components that are enabled through `XCR0`.
+    pub const USER: Self = Self(
+        Self::X87.0
+            | Self::SSE.0
+            | Self::AVX.0
+            | Self::MPX.0
+            | Self::AVX512.0
+            | Self::PKRU.0
+            | Self::AMX.0,
+    );
+    /// The state components that are enabled through the `IA32_XSS` MSR.
+    pub const SUPERVISOR: Self = Self(
+        Self::PT.0
+            | Self::PASID.0
+            | Self::CET_U.0
+            | Self::CET_S.0
+            | Self::HDC.0
+            | Self::UINTR.0
+            | Self::LBR.0
+            | Self::HWP.0,
+    );
+
+    /// Returns `true` if the state components in `other` are set in `self`.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `true` if any state component in `other` is set in `self`.
+    pub const fn intersects(&self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Returns `true` if no state components are set.
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the state components set in both `self` and `other`.
+    pub const fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// Returns the state components set in `self` but not in `other`.
+    pub const fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// Returns `true` if the state components form a value that `XSETBV` accepts for `XCR0`.
+    ///
+    /// x87 state must always be enabled, AVX requires SSE, AVX-512 requires AVX and must be
+    /// enabled as a whole, and the MPX and AMX components must each be enabled together.
+    pub const fn is_valid_xcr0(self) -> bool {
+        self.contains(Self::X87)
+            && self.difference(Self::USER).is_empty()
+            && (!self.contains(Self::AVX) || self.contains(Self::SSE))
+            && (!self.intersects(Self::AVX512) || self.contains(Self::AVX))
+            && self.all_or_none(Self::AVX512)
+            && self.all_or_none(Self::MPX)
+            && self.all_or_none(Self::AMX)
+    }
+
+    /// Returns `true` if either all or none of the state components in `features` are set.
+    const fn all_or_none(self, features: Self) -> bool {
+        let present = self.intersection(features);
+        present.is_empty() || present.0 == features.0
+    }
+
+    /// Returns an iterator over the indices of the state components in the set, in ascending
+    /// order.
+    pub fn components(self) -> impl Iterator<Item = u32> {
+        (0..64).filter(move |&index| self.0 & (1 << index) != 0)
+    }
+}
+
+impl BitOr for XFeatures {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for XFeatures {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+/// The XSAVE capabilities of a processor, as enumerated by CPUID leaf 0xD.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct XsaveSupport {
+    /// The state components that can be enabled in `XCR0`.
+    pub user: XFeatures,
+    /// The state components that can be enabled in the `IA32_XSS` MSR.
+    pub supervisor: XFeatures,
+    /// The size, in bytes, of the standard format XSAVE area required by the state components
+    /// currently enabled in `XCR0`.
+    pub enabled_size: u32,
+    /// The size, in bytes, of the standard format XSAVE area required by every state component
+    /// that can be enabled in `XCR0`.
+    pub max_size: u32,
+    /// If `true`, `XSAVEOPT` is supported.
+    pub xsaveopt: bool,
+    /// If `true`, `XSAVEC` and the compacted format are supported.
+    pub xsavec: bool,
+    /// If `true`, `XGETBV` with `ECX = 1` is supported.
+    pub xgetbv1: bool,
+    /// If `true`, `XSAVES`, `XRSTORS`, and the `IA32_XSS` MSR are supported.
+    pub xsaves: bool,
+}
+
+impl XsaveSupport {
+    /// Decodes the [`XsaveSupport`] from subleaf 0 and subleaf 1 of CPUID leaf 0xD.
+    pub const fn from_cpuid(main: Cpuid, extended: Cpuid) -> Self {
+        Self {
+            user: XFeatures(((main.edx as u64) << 32) | main.eax as u64),
+            supervisor: XFeatures(((extended.edx as u64) << 32) | extended.ecx as u64),
+            enabled_size: main.ebx,
+            max_size: main.ecx,
+            xsaveopt: extended.eax & (1 << 0) != 0,
+            xsavec: extended.eax & (1 << 1) != 0,
+            xgetbv1: extended.eax & (1 << 2) != 0,
+            xsaves: extended.eax & (1 << 3) != 0,
+        }
+    }
+}
+
+/// The location of a state component within an XSAVE area, as enumerated by CPUID leaf 0xD.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct ComponentLayout {
+    /// The size, in bytes, of the state component.
+    pub size: u32,
+    /// The offset, in bytes, of the state component in the standard format.
+    ///
+    /// This is zero for supervisor state components, which only appear in the compacted format.
+    pub offset: u32,
+    /// If `true`, the state component is enabled through the `IA32_XSS` MSR.
+    pub supervisor: bool,
+    /// If `true`, the state component is 64-byte aligned in the compacted format.
+    pub aligned: bool,
+}
+
+impl ComponentLayout {
+    /// Decodes the [`ComponentLayout`] from subleaf `n` of CPUID leaf 0xD, where `n` is the index
+    /// of the state component and at least 2.
+    pub const fn from_cpuid(leaf: Cpuid) -> Self {
+        Self {
+            size: leaf.eax,
+            offset: leaf.ebx,
+            supervisor: leaf.ecx & (1 << 0) != 0,
+            aligned: leaf.ecx & (1 << 1) != 0,
+        }
+    }
+}
+
+/// Returns the size, in bytes, of a standard format XSAVE area that holds `features`.
+///
+/// `layout` returns the [`ComponentLayout`] of the state component with the given index, which is
+/// always at least 2. Supervisor state components cannot be saved in the standard format and are
+/// ignored.
+pub fn standard_size(features: XFeatures, mut layout: impl FnMut(u32) -> ComponentLayout) -> u32 {
+    features
+        .components()
+        .filter(|&index| index >= 2)
+        .map(&mut layout)
+        .filter(|layout| !layout.supervisor)
+        .map(|layout| layout.offset + layout.size)
+        .fold(EXTENDED_REGION_OFFSET, u32::max)
+}
+
+/// Returns the size, in bytes, of a compacted format XSAVE area that holds `features`.
+///
+/// `layout` returns the [`ComponentLayout`] of the state component with the given index, which is
+/// always at least 2.
+pub fn compacted_size(features: XFeatures, mut layout: impl FnMut(u32) -> ComponentLayout) -> u32 {
+    features
+        .components()
+        .filter(|&index| index >= 2)
+        .map(&mut layout)
+        .fold(EXTENDED_REGION_OFFSET, |offset, layout| {
+            let offset = if layout.aligned {
+                offset.next_multiple_of(64)
+            } else {
+                offset
+            };
+            offset + layout.size
+        })
+}
+
+/// Returns `true` if the processor supports `XSAVE`, `XRSTOR`, `XGETBV`, and `XSETBV`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn supports_xsave() -> bool {
+    if !supports_cpuid() {
+        return false;
+    }
+
+    // SAFETY:
+    //
+    // The CPUID instruction is supported.
+    let max_basic = unsafe { cpuid_unchecked(0, 0) }.eax;
+    // SAFETY:
+    //
+    // The CPUID instruction is supported.
+    max_basic >= 0xD && (unsafe { cpuid_unchecked(1, 0) }.ecx >> 26) & 1 == 1
+}
+
+/// Returns the [`XsaveSupport`] of this processor, or [`None`] if XSAVE is not supported.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn xsave_support() -> Option<XsaveSupport> {
+    if !supports_xsave() {
+        return None;
+    }
+
+    // SAFETY:
+    //
+    // The CPUID instruction is supported and leaf 0xD is within the supported range.
+    let main = unsafe { cpuid_unchecked(0xD, 0) };
+    // SAFETY:
+    //
+    // The CPUID instruction is supported and leaf 0xD is within the supported range.
+    let extended = unsafe { cpuid_unchecked(0xD, 1) };
+
+    Some(XsaveSupport::from_cpuid(main, extended))
+}
+
+/// Returns the [`ComponentLayout`] of the state component `index` on this processor.
+///
+/// Returns [`None`] if XSAVE is not supported or if `index` does not name a supported state
+/// component in the extended region.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn component_layout(index: u32) -> Option<ComponentLayout> {
+    let support = xsave_support()?;
+    let supported = support.user | support.supervisor;
+    if !(2..63).contains(&index) || !supported.contains(XFeatures(1 << index)) {
+        return None;
+    }
+
+    // SAFETY:
+    //
+    // The CPUID instruction is supported and leaf 0xD is within the supported range.
+    Some(ComponentLayout::from_cpuid(unsafe {
+        cpuid_unchecked(0xD, index)
+    }))
+}
+
+/// Returns the value of the extended control register `index`.
+///
+/// # Safety
+///
+/// `CR4.OSXSAVE` must be set and `index` must name a supported extended control register.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn xgetbv(index: u32) -> u64 {
+    let low: u32;
+    let high: u32;
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that XGETBV does not fault.
+    unsafe {
+        asm!(
+            "xgetbv",
+            in("ecx") index,
+            lateout("eax") low,
+            lateout("edx") high,
+            options(nomem, nostack, preserves_flags)
+        )
+    }
+
+    (u64::from(high) << 32) | u64::from(low)
+}
+
+/// Writes `value` to the extended control register `index`.
+///
+/// # Safety
+///
+/// `CR4.OSXSAVE` must be set, this must be executed at privilege level 0, and `value` must be a
+/// valid value for the extended control register `index`. Disabling a state component discards
+/// its current contents.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn xsetbv(index: u32, value: u64) {
+    // SAFETY:
+    //
+    // The invariants of this function ensure that XSETBV does not fault.
+    unsafe {
+        asm!(
+            "xsetbv",
+            in("ecx") index,
+            in("eax") (value & 0xFFFF_FFFF) as u32,
+            in("edx") (value >> 32) as u32,
+            options(nomem, nostack, preserves_flags)
+        )
+    }
+}
+
+/// Returns the state components enabled in `XCR0`.
+///
+/// # Safety
+///
+/// `CR4.OSXSAVE` must be set.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn read_xcr0() -> XFeatures {
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `XCR0` can be read.
+    XFeatures(unsafe { xgetbv(0) })
+}
+
+/// Enables the state components `features` in `XCR0`.
+///
+/// # Safety
+///
+/// `CR4.OSXSAVE` must be set, this must be executed at privilege level 0, `features` must be
+/// supported by the processor, and [`XFeatures::is_valid_xcr0()`] must hold for `features`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn write_xcr0(features: XFeatures) {
+    debug_assert!(features.is_valid_xcr0());
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `features` is a valid value for `XCR0`.
+    unsafe { xsetbv(0, features.0) }
+}
+
+/// Returns the supervisor state components enabled in the `IA32_XSS` MSR.
+///
+/// # Safety
+///
+/// `XSAVES` must be supported and this must be executed at privilege level 0.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn read_xss() -> XFeatures {
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `IA32_XSS` exists and can be read.
+    XFeatures(unsafe { read_msr(IA32_XSS) })
+}
+
+/// Enables the supervisor state components `features` in the `IA32_XSS` MSR.
+///
+/// # Safety
+///
+/// `XSAVES` must be supported, this must be executed at privilege level 0, and `features` must
+/// be a subset of [`XsaveSupport::supervisor`].
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn write_xss(features: XFeatures) {
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `features` is a valid value for `IA32_XSS`.
+    unsafe { write_msr(IA32_XSS, features.0) }
+}
+
+/// Sets `CR4.OSXSAVE` and enables the state components `features` in `XCR0`.
+///
+/// # Safety
+///
+/// The invariants of [`Cr4::set()`] and [`write_xcr0()`] must hold, and XSAVE must be supported.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub unsafe fn enable(features: XFeatures) {
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `CR4` can be accessed.
+    let cr4 = unsafe { Cr4::get() };
+    if !cr4.osxsave() {
+        // SAFETY:
+        //
+        // The invariants of this function ensure that XSAVE is supported, so `CR4.OSXSAVE` can
+        // be set.
+        unsafe { cr4.set_osxsave(true).set() }
+    }
+
+    // SAFETY:
+    //
+    // `CR4.OSXSAVE` is set and the invariants of this function ensure the rest of the
+    // invariants of `write_xcr0()`.
+    unsafe { write_xcr0(features) }
+}
+
+/// The instruction used to save state to an [`XsaveArea`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum SaveInstruction {
+    /// `XSAVE`, which saves in the standard format.
+    Xsave,
+    /// `XSAVEOPT`, which saves in the standard format and skips components that are in their
+    /// initial configuration or unmodified since the last `XRSTOR` from the same area.
+    Xsaveopt,
+    /// `XSAVEC`, which saves in the compacted format and skips components that are in their
+    /// initial configuration.
+    Xsavec,
+    /// `XSAVES`, which saves user and supervisor state components in the compacted format.
+    Xsaves,
+}
+
+/// The instruction used to restore state from an [`XsaveArea`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum RestoreInstruction {
+    /// `XRSTOR`, which restores user state components from either format.
+    Xrstor,
+    /// `XRSTORS`, which restores user and supervisor state components from the compacted format.
+    Xrstors,
+}
+
+/// A 64-byte aligned XSAVE area that holds up to `SIZE` bytes of state.
+///
+/// The size required for a set of state components is given by [`XsaveSupport::max_size`],
+/// [`standard_size()`], or [`compacted_size()`], depending on the format.
+#[repr(C, align(64))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct XsaveArea<const SIZE: usize> {
+    /// The contents of the area.
+    bytes: [u8; SIZE],
+}
+
+impl<const SIZE: usize> XsaveArea<SIZE> {
+    /// Creates a standard format [`XsaveArea`] in which every state component is in its initial
+    /// configuration.
+    ///
+    /// # Panics
+    ///
+    /// Fails to compile if `SIZE` is smaller than the legacy region and the XSAVE header.
+    pub const fn new() -> Self {
+        const { assert!(SIZE >= EXTENDED_REGION_OFFSET as usize) }
+
+        let mut area = Self { bytes: [0; SIZE] };
+        area.write_bytes(0, &FCW_DEFAULT.to_le_bytes());
+        area.write_bytes(MXCSR_OFFSET, &MXCSR_DEFAULT.to_le_bytes());
+        area
+    }
+
+    /// Creates a compacted format [`XsaveArea`] for `features`, in which every state component
+    /// is in its initial configuration.
+    ///
+    /// # Panics
+    ///
+    /// Fails to compile if `SIZE` is smaller than the legacy region and the XSAVE header.
+    pub const fn new_compacted(features: XFeatures) -> Self {
+        let mut area = Self::new();
+        area.write_bytes(
+            XCOMP_BV_OFFSET,
+            &(features.0 | XCOMP_BV_COMPACTED).to_le_bytes(),
+        );
+        area
+    }
+
+    /// Returns `true` if the area can hold `size` bytes of state.
+    pub const fn fits(&self, size: u32) -> bool {
+        size as usize <= SIZE
+    }
+
+    /// Returns the contents of the area.
+    pub const fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the contents of the area, mutably.
+    pub const fn as_bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes
+    }
+
+    /// Returns the saved value of `MXCSR`.
+    pub const fn mxcsr(&self) -> u32 {
+        self.read_u32(MXCSR_OFFSET)
+    }
+
+    /// Returns the state components that were not in their initial configuration when the area
+    /// was saved (`XSTATE_BV`).
+    pub const fn xstate_bv(&self) -> XFeatures {
+        XFeatures(self.read_u64(XSTATE_BV_OFFSET))
+    }
+
+    /// Returns `true` if the area uses the compacted format.
+    pub const fn is_compacted(&self) -> bool {
+        self.read_u64(XCOMP_BV_OFFSET) & XCOMP_BV_COMPACTED != 0
+    }
+
+    /// Returns the state components laid out in the area, if it uses the compacted format
+    /// (`XCOMP_BV`).
+    pub const fn xcomp_bv(&self) -> XFeatures {
+        XFeatures(self.read_u64(XCOMP_BV_OFFSET) & !XCOMP_BV_COMPACTED)
+    }
+
+    /// Saves the state components in `features` that are enabled in `XCR0` (and, for
+    /// [`SaveInstruction::Xsaves`], in `IA32_XSS`) to the area.
+    ///
+    /// # Safety
+    ///
+    /// `CR4.OSXSAVE` must be set, `instruction` must be supported, and the area must be large
+    /// enough to hold the enabled state components of `features` in the format written by
+    /// `instruction`. [`SaveInstruction::Xsaves`] must be executed at privilege level 0.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub unsafe fn save(&mut self, features: XFeatures, instruction: SaveInstruction) {
+        let area = self.bytes.as_mut_ptr();
+        let low = (features.0 & 0xFFFF_FFFF) as u32;
+        let high = (features.0 >> 32) as u32;
+
+        macro_rules! save {
+            ($instruction:literal) => {
+                // SAFETY:
+                //
+                // The invariants of this function ensure that the instruction is supported and
+                // that the area is large enough, and the area is 64-byte aligned.
+                unsafe {
+                    asm!(
+                        concat!($instruction, " [{}]"),
+                        in(reg) area,
+                        in("eax") low,
+                        in("edx") high,
+                        options(nostack, preserves_flags)
+                    )
+                }
+            };
+        }
+
+        #[cfg(target_arch = "x86_64")]
+        match instruction {
+            SaveInstruction::Xsave => save!("xsave64"),
+            SaveInstruction::Xsaveopt => save!("xsaveopt64"),
+            SaveInstruction::Xsavec => save!("xsavec64"),
+            SaveInstruction::Xsaves => save!("xsaves64"),
+        }
+        #[cfg(target_arch = "x86")]
+        match instruction {
+            SaveInstruction::Xsave => save!("xsave"),
+            SaveInstruction::Xsaveopt => save!("xsaveopt"),
+            SaveInstruction::Xsavec => save!("xsavec"),
+            SaveInstruction::Xsaves => save!("xsaves"),
+        }
+    }
+
+    /// Restores the state components in `features` that are enabled in `XCR0` (and, for
+    /// [`RestoreInstruction::Xrstors`], in `IA32_XSS`) from the area.
+    ///
+    /// State components that are not set in [`XsaveArea::xstate_bv()`] are set to their initial
+    /// configuration.
+    ///
+    /// # Safety
+    ///
+    /// `CR4.OSXSAVE` must be set, `instruction` must be supported, and the area must hold a
+    /// valid XSAVE header and state in the format expected by `instruction`.
+    /// [`RestoreInstruction::Xrstors`] must be executed at privilege level 0.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub unsafe fn restore(&self, features: XFeatures, instruction: RestoreInstruction) {
+        let area = self.bytes.as_ptr();
+        let low = (features.0 & 0xFFFF_FFFF) as u32;
+        let high = (features.0 >> 32) as u32;
+
+        macro_rules! restore {
+            ($instruction:literal) => {
+                // SAFETY:
+                //
+                // The invariants of this function ensure that the instruction is supported and
+                // that the area holds valid state, and the area is 64-byte aligned.
+                unsafe {
+                    asm!(
+                        concat!($instruction, " [{}]"),
+                        in(reg) area,
+                        in("eax") low,
+                        in("edx") high,
+                        options(nostack, preserves_flags, readonly)
+                    )
+                }
+            };
+        }
+
+        #[cfg(target_arch = "x86_64")]
+        match instruction {
+            RestoreInstruction::Xrstor => restore!("xrstor64"),
+            RestoreInstruction::Xrstors => restore!("xrstors64"),
+        }
+        #[cfg(target_arch = "x86")]
+        match instruction {
+            RestoreInstruction::Xrstor => restore!("xrstor"),
+            RestoreInstruction::Xrstors => restore!("xrstors"),
+        }
+    }
+
+    /// Returns the little-endian `u32` at `offset`.
+    const fn read_u32(&self, offset: usize) -> u32 {
+        let mut bytes = [0; 4];
+        let mut index = 0;
+        while index < bytes.len() {
+            bytes[index] = self.bytes[offset + index];
+            index += 1;
+        }
+        u32::from_le_bytes(bytes)
+    }
+
+    /// Returns the little-endian `u64` at `offset`.
+    const fn read_u64(&self, offset: usize) -> u64 {
+        let mut bytes = [0; 8];
+        let mut index = 0;
+        while index < bytes.len() {
+            bytes[index] = self.bytes[offset + index];
+            index += 1;
+        }
+        u64::from_le_bytes(bytes)
+    }
+
+    /// Writes `bytes` at `offset`.
+    const fn write_bytes(&mut self, offset: usize, bytes: &[u8]) {
+        let mut index = 0;
+        while index < bytes.len() {
+            self.bytes[offset + index] = bytes[index];
+            index += 1;
+        }
+    }
+}
+
+impl<const SIZE: usize> Default for XsaveArea<SIZE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A 16-byte aligned area that holds the x87 FPU, `MXCSR`, and `XMM` state saved by `FXSAVE`.
+///
+/// This is used on processors that do not support XSAVE.
+#[repr(C, align(16))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct FxsaveArea {
+    /// The contents of the area.
+    bytes: [u8; LEGACY_REGION_SIZE as usize],
+}
+
+impl FxsaveArea {
+    /// Creates an [`FxsaveArea`] that holds the initial x87 FPU and SSE state.
+    pub const fn new() -> Self {
+        let mut bytes = [0; LEGACY_REGION_SIZE as usize];
+        let fcw = FCW_DEFAULT.to_le_bytes();
+        bytes[0] = fcw[0];
+        bytes[1] = fcw[1];
+
+        let mxcsr = MXCSR_DEFAULT.to_le_bytes();
+        let mut index = 0;
+        while index < mxcsr.len() {
+            bytes[MXCSR_OFFSET + index] = mxcsr[index];
+            index += 1;
+        }
+
+        Self { bytes }
+    }
+
+    /// Returns the contents of the area.
+    pub const fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the contents of the area, mutably.
+    pub const fn as_bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes
+    }
+
+    /// Saves the x87 FPU, `MXCSR`, and `XMM` state to the area.
+    ///
+    /// # Safety
+    ///
+    /// `FXSAVE` must be supported and `CR0.TS` and `CR0.EM` must be clear.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub unsafe fn save(&mut self) {
+        let area = self.bytes.as_mut_ptr();
+
+        #[cfg(target_arch = "x86_64")]
+        // SAFETY:
+        //
+        // The invariants of this function ensure that FXSAVE does not fault, and the area is
+        // 16-byte aligned.
+        unsafe {
+            asm!("fxsave64 [{}]", in(reg) area, options(nostack, preserves_flags))
+        }
+        #[cfg(target_arch = "x86")]
+        // SAFETY:
+        //
+        // The invariants of this function ensure that FXSAVE does not fault, and the area is
+        // 16-byte aligned.
+        unsafe {
+            asm!("fxsave [{}]", in(reg) area, options(nostack, preserves_flags))
+        }
+    }
+
+    /// Restores the x87 FPU, `MXCSR`, and `XMM` state from the area.
+    ///
+    /// # Safety
+    ///
+    /// `FXRSTOR` must be supported, `CR0.TS` and `CR0.EM` must be clear, and the saved `MXCSR`
+    /// must not set reserved bits.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub unsafe fn restore(&self) {
+        let area = self.bytes.as_ptr();
+
+        #[cfg(target_arch = "x86_64")]
+        // SAFETY:
+        //
+        // The invariants of this function ensure that FXRSTOR does not fault, and the area is
+        // 16-byte aligned.
+        unsafe {
+            asm!("fxrstor64 [{}]", in(reg) area, options(nostack, preserves_flags, readonly))
+        }
+        #[cfg(target_arch = "x86")]
+        // SAFETY:
+        //
+        // The invariants of this function ensure that FXRSTOR does not fault, and the area is
+        // 16-byte aligned.
+        unsafe {
+            asm!("fxrstor [{}]", in(reg) area, options(nostack, preserves_flags, readonly))
+        }
+    }
+}
+
+impl Default for FxsaveArea {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Returns the [`ComponentLayout`]s reported by a processor with AVX-512, PKRU, and CET.
+    fn layout(index: u32) -> ComponentLayout {
+        let (size, offset, supervisor, aligned) = match index {
+            2 => (256, 576, false, false),
+            5 => (64, 1088, false, false),
+            6 => (512, 1152, false, false),
+            7 => (1024, 1664, false, false),
+            9 => (8, 2688, false, false),
+            11 => (16, 0, true, false),
+            12 => (24, 0, true, false),
+            17 => (64, 2752, false, false),
+            18 => (8192, 2816, false, true),
+            _ => panic!("unexpected state component {index}"),
+        };
+
+        ComponentLayout {
+            size,
+            offset,
+            supervisor,
+            aligned,
+        }
+    }
+
+    #[test]
+    fn support_from_cpuid() {
+        let main = Cpuid {
+            eax: 0x0006_02E7,
+            ebx: 0x0000_0340,
+            ecx: 0x0000_2B00,
+            edx: 0,
+        };
+        let extended = Cpuid {
+            eax: 0xF,
+            ebx: 0x0000_0988,
+            ecx: 0x0000_1800,
+            edx: 0,
+        };
+
+        let support = XsaveSupport::from_cpuid(main, extended);
+        assert_eq!(
+            support.user,
+            XFeatures::X87
+                | XFeatures::SSE
+                | XFeatures::AVX
+                | XFeatures::AVX512
+                | XFeatures::PKRU
+                | XFeatures::AMX
+        );
+        assert_eq!(support.supervisor, XFeatures::CET_U | XFeatures::CET_S);
+        assert_eq!((support.enabled_size, support.max_size), (0x340, 0x2B00));
+        assert!(support.xsaveopt && support.xsavec && support.xgetbv1 && support.xsaves);
+
+        let layout = ComponentLayout::from_cpuid(Cpuid {
+            eax: 8192,
+            ebx: 2816,
+            ecx: 0b10,
+            edx: 0,
+        });
+        assert_eq!(layout, super::test::layout(18));
+    }
+
+    #[test]
+    fn area_sizes() {
+        let legacy = XFeatures::X87 | XFeatures::SSE;
+        let avx = legacy | XFeatures::AVX;
+        let avx512 = avx | XFeatures::AVX512;
+        let cet = XFeatures::CET_U | XFeatures::CET_S;
+
+        let cases = [
+            (legacy, 576, 576),
+            (avx, 832, 832),
+            (avx512, 2688, 2432),
+            (avx512 | XFeatures::PKRU, 2696, 2440),
+            (avx | cet, 832, 872),
+            (avx | XFeatures::AMX, 11008, 9088),
+        ];
+
+        for (features, standard, compacted) in cases {
+            assert_eq!(
+                standard_size(features, layout),
+                standard,
+                "{features:?} standard"
+            );
+            assert_eq!(
+                compacted_size(features, layout),
+                compacted,
+                "{features:?} compacted"
+            );
+        }
+    }
+
+    #[test]
+    fn xcr0_validity() {
+        let cases = [
+            (XFeatures::X87, true),
+            (XFeatures::SSE, false),
+            (XFeatures::X87 | XFeatures::AVX, false),
+            (XFeatures::X87 | XFeatures::SSE | XFeatures::AVX, true),
+            (
+                XFeatures::X87 | XFeatures::SSE | XFeatures::AVX | XFeatures::OPMASK,
+                false,
+            ),
+            (XFeatures::X87 | XFeatures::SSE | XFeatures::AVX512, false),
+            (
+                XFeatures::X87 | XFeatures::SSE | XFeatures::AVX | XFeatures::AVX512,
+                true,
+            ),
+            (XFeatures::X87 | XFeatures::BNDREGS, false),
+            (XFeatures::X87 | XFeatures::MPX, true),
+            (XFeatures::X87 | XFeatures::SSE | XFeatures::TILEDATA, false),
+            (XFeatures::X87 | XFeatures::CET_U, false),
+        ];
+
+        for (features, valid) in cases {
+            assert_eq!(features.is_valid_xcr0(), valid, "{features:?}");
+        }
+    }
+
+    #[test]
+    fn initial_areas() {
+        let area = XsaveArea::<1024>::new();
+        assert_eq!(area.mxcsr(), MXCSR_DEFAULT);
+        assert!(area.xstate_bv().is_empty());
+        assert!(!area.is_compacted());
+        assert!(area.fits(832) && !area.fits(2688));
+        assert_eq!(core::mem::align_of::<XsaveArea<1024>>(), 64);
+
+        let features = XFeatures::X87 | XFeatures::SSE | XFeatures::AVX;
+        let area = XsaveArea::<1024>::new_compacted(features);
+        assert!(area.is_compacted());
+        assert_eq!(area.xcomp_bv(), features);
+
+        let area = FxsaveArea::new();
+        assert_eq!(area.as_bytes()[..2], FCW_DEFAULT.to_le_bytes());
+        assert_eq!(area.as_bytes()[24..28], MXCSR_DEFAULT.to_le_bytes());
+        assert_eq!(core::mem::align_of::<FxsaveArea>(), 16);
+    }
+}
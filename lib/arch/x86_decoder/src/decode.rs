@@ -0,0 +1,821 @@
+//! Decoding of instruction bytes into [`Instruction`]s.
+
+use crate::{
+    DecodeError, Instruction, MAX_INSTRUCTION_LENGTH, MAX_OPERANDS, Mnemonic, Mode,
+    operand::{AddressSize, MemoryOperand, Operand, OperandSize, Register, Segment},
+    prefix::{Prefixes, Repeat, Rex, Vex},
+};
+
+/// The index of the accumulator register.
+const ACCUMULATOR: u8 = 0;
+/// The index of the stack pointer register.
+const STACK_POINTER: u8 = 4;
+/// The index of the frame pointer register.
+const FRAME_POINTER: u8 = 5;
+/// The index of the source index register.
+const SOURCE_INDEX: u8 = 6;
+/// The index of the destination index register.
+const DESTINATION_INDEX: u8 = 7;
+/// The index of the base register.
+const BASE: u8 = 3;
+
+/// Decodes the instruction at the start of `bytes` when executing in `mode`.
+pub(crate) fn decode(bytes: &[u8], mode: Mode) -> Result<Instruction, DecodeError> {
+    let mut reader = Reader { bytes, position: 0 };
+    let prefixes = decode_prefixes(&mut reader, mode)?;
+
+    let operand_size = match mode {
+        Mode::Bits64 if prefixes.w() => OperandSize::Qword,
+        Mode::Bits16 if prefixes.operand_size_override => OperandSize::Dword,
+        Mode::Bits16 => OperandSize::Word,
+        Mode::Bits32 | Mode::Bits64 if prefixes.operand_size_override => OperandSize::Word,
+        Mode::Bits32 | Mode::Bits64 => OperandSize::Dword,
+    };
+    let address_size = match (mode, prefixes.address_size_override) {
+        (Mode::Bits16, false) | (Mode::Bits32, true) => AddressSize::Bits16,
+        (Mode::Bits16, true) | (Mode::Bits32, false) | (Mode::Bits64, true) => AddressSize::Bits32,
+        (Mode::Bits64, false) => AddressSize::Bits64,
+    };
+    let context = Context {
+        mode,
+        prefixes,
+        operand_size,
+        address_size,
+    };
+
+    let decoded = match prefixes.vex {
+        Some(vex) => decode_vex(&mut reader, &context, vex)?,
+        None => match reader.read_u8()? {
+            0x0F => decode_two_byte(&mut reader, &context)?,
+            opcode => decode_one_byte(&mut reader, &context, opcode)?,
+        },
+    };
+
+    if prefixes.lock
+        && !(decoded.mnemonic.lockable() && matches!(decoded.operands[0], Operand::Memory(_)))
+    {
+        return Err(DecodeError::InvalidEncoding);
+    }
+
+    Ok(Instruction {
+        mnemonic: decoded.mnemonic,
+        operands: decoded.operands,
+        operand_count: decoded.operand_count,
+        operand_size: decoded.operand_size,
+        address_size,
+        prefixes,
+        length: reader.position,
+    })
+}
+
+/// Decodes the legacy, `REX`, and `VEX` prefixes of an instruction.
+fn decode_prefixes(reader: &mut Reader, mode: Mode) -> Result<Prefixes, DecodeError> {
+    let mut prefixes = Prefixes::default();
+    loop {
+        let byte = reader.peek_u8(0)?;
+        match byte {
+            0xF0 => prefixes.lock = true,
+            0xF2 => prefixes.repeat = Some(Repeat::RepeatNotEqual),
+            0xF3 => prefixes.repeat = Some(Repeat::Repeat),
+            0x26 => prefixes.segment = Some(Segment::Es),
+            0x2E => prefixes.segment = Some(Segment::Cs),
+            0x36 => prefixes.segment = Some(Segment::Ss),
+            0x3E => prefixes.segment = Some(Segment::Ds),
+            0x64 => prefixes.segment = Some(Segment::Fs),
+            0x65 => prefixes.segment = Some(Segment::Gs),
+            0x66 => prefixes.operand_size_override = true,
+            0x67 => prefixes.address_size_override = true,
+            0x40..=0x4F if mode == Mode::Bits64 => {
+                reader.read_u8()?;
+                prefixes.rex = Rex::from_byte(byte);
+                continue;
+            }
+            0xC4 | 0xC5 => {
+                // Outside of 64-bit mode, `0xC4` and `0xC5` encode `LES` and `LDS` unless the
+                // following byte would be a register form `ModRM` byte.
+                if mode != Mode::Bits64 && reader.peek_u8(1)? & 0xC0 != 0xC0 {
+                    return Ok(prefixes);
+                }
+                if prefixes.lock
+                    || prefixes.repeat.is_some()
+                    || prefixes.operand_size_override
+                    || prefixes.rex.is_some()
+                {
+                    return Err(DecodeError::InvalidEncoding);
+                }
+
+                reader.read_u8()?;
+                let vex = if byte == 0xC5 {
+                    Vex::two_byte(reader.read_u8()?)
+                } else {
+                    let first = reader.read_u8()?;
+                    Vex::three_byte(first, reader.read_u8()?)
+                };
+                prefixes.vex = Some(vex);
+                return Ok(prefixes);
+            }
+            _ => return Ok(prefixes),
+        }
+
+        // A `REX` prefix only takes effect if it immediately precedes the opcode.
+        reader.read_u8()?;
+        prefixes.rex = None;
+    }
+}
+
+/// Decodes an instruction from the one-byte opcode map.
+fn decode_one_byte(
+    reader: &mut Reader,
+    context: &Context,
+    opcode: u8,
+) -> Result<Decoded, DecodeError> {
+    let size_v = context.operand_size;
+    match opcode {
+        0x00..=0x3F if opcode & 0b111 < 6 => {
+            let mnemonic = ARITHMETIC[usize::from(opcode >> 3)];
+            match opcode & 0b111 {
+                0 => decode_rm_reg(reader, context, mnemonic, OperandSize::Byte),
+                1 => decode_rm_reg(reader, context, mnemonic, size_v),
+                2 => decode_reg_rm(reader, context, mnemonic, OperandSize::Byte),
+                3 => decode_reg_rm(reader, context, mnemonic, size_v),
+                4 => {
+                    let immediate = read_immediate(reader, OperandSize::Byte, OperandSize::Byte)?;
+                    let accumulator = context.register(ACCUMULATOR, OperandSize::Byte);
+                    Ok(Decoded::new(
+                        mnemonic,
+                        OperandSize::Byte,
+                        &[accumulator, immediate],
+                    ))
+                }
+                _ => {
+                    let immediate = read_immediate_z(reader, size_v)?;
+                    let accumulator = context.register(ACCUMULATOR, size_v);
+                    Ok(Decoded::new(mnemonic, size_v, &[accumulator, immediate]))
+                }
+            }
+        }
+        0x63 if context.mode == Mode::Bits64 => {
+            let modrm = ModRm::read(reader)?;
+            let source = decode_rm(reader, context, modrm, OperandSize::Dword)?;
+            let destination = context.register(context.reg(modrm), size_v);
+            Ok(Decoded::new(
+                Mnemonic::Movsxd,
+                size_v,
+                &[destination, source],
+            ))
+        }
+        0x80..=0x83 => {
+            if opcode == 0x82 && context.mode == Mode::Bits64 {
+                return Err(DecodeError::InvalidEncoding);
+            }
+
+            let size = if opcode & 1 == 0 {
+                OperandSize::Byte
+            } else {
+                size_v
+            };
+            let modrm = ModRm::read(reader)?;
+            let destination = decode_rm(reader, context, modrm, size)?;
+            let immediate = if opcode == 0x81 {
+                read_immediate_z(reader, size)?
+            } else {
+                read_immediate(reader, OperandSize::Byte, size)?
+            };
+            Ok(Decoded::new(
+                ARITHMETIC[usize::from(modrm.reg)],
+                size,
+                &[destination, immediate],
+            ))
+        }
+        0x84 => decode_rm_reg(reader, context, Mnemonic::Test, OperandSize::Byte),
+        0x85 => decode_rm_reg(reader, context, Mnemonic::Test, size_v),
+        0x88 => decode_rm_reg(reader, context, Mnemonic::Mov, OperandSize::Byte),
+        0x89 => decode_rm_reg(reader, context, Mnemonic::Mov, size_v),
+        0x8A => decode_reg_rm(reader, context, Mnemonic::Mov, OperandSize::Byte),
+        0x8B => decode_reg_rm(reader, context, Mnemonic::Mov, size_v),
+        0x8C => {
+            let modrm = ModRm::read(reader)?;
+            let segment = Segment::from_index(modrm.reg).ok_or(DecodeError::InvalidEncoding)?;
+            let size = if modrm.mode == 0b11 {
+                size_v
+            } else {
+                OperandSize::Word
+            };
+            let destination = decode_rm(reader, context, modrm, size)?;
+            Ok(Decoded::new(
+                Mnemonic::Mov,
+                size,
+                &[destination, Operand::Register(Register::Segment(segment))],
+            ))
+        }
+        0x8E => {
+            let modrm = ModRm::read(reader)?;
+            let segment = match Segment::from_index(modrm.reg) {
+                Some(Segment::Cs) | None => return Err(DecodeError::InvalidEncoding),
+                Some(segment) => segment,
+            };
+            let source = decode_rm(reader, context, modrm, OperandSize::Word)?;
+            Ok(Decoded::new(
+                Mnemonic::Mov,
+                OperandSize::Word,
+                &[Operand::Register(Register::Segment(segment)), source],
+            ))
+        }
+        0xA0..=0xA3 => {
+            let size = if opcode & 1 == 0 {
+                OperandSize::Byte
+            } else {
+                size_v
+            };
+            let offset = match context.address_size {
+                AddressSize::Bits16 => u64::from(reader.read_u16()?),
+                AddressSize::Bits32 => u64::from(reader.read_u32()?),
+                AddressSize::Bits64 => reader.read_u64()?,
+            };
+            let (segment, explicit_segment) = context.segment(Segment::Ds);
+            let memory = Operand::Memory(MemoryOperand {
+                segment,
+                explicit_segment,
+                base: None,
+                index: None,
+                scale: 1,
+                displacement: offset.cast_signed(),
+                size,
+                address_size: context.address_size,
+            });
+            let accumulator = context.register(ACCUMULATOR, size);
+
+            let operands = if opcode < 0xA2 {
+                [accumulator, memory]
+            } else {
+                [memory, accumulator]
+            };
+            Ok(Decoded::new(Mnemonic::Mov, size, &operands))
+        }
+        0xA4 | 0xA5 => {
+            let size = if opcode == 0xA4 {
+                OperandSize::Byte
+            } else {
+                size_v
+            };
+            let (segment, _) = context.segment(Segment::Ds);
+            let destination = context.string_operand(Segment::Es, DESTINATION_INDEX, size);
+            let source = context.string_operand(segment, SOURCE_INDEX, size);
+            Ok(Decoded::new(Mnemonic::Movs, size, &[destination, source]))
+        }
+        0xA8 => {
+            let immediate = read_immediate(reader, OperandSize::Byte, OperandSize::Byte)?;
+            let accumulator = context.register(ACCUMULATOR, OperandSize::Byte);
+            Ok(Decoded::new(
+                Mnemonic::Test,
+                OperandSize::Byte,
+                &[accumulator, immediate],
+            ))
+        }
+        0xA9 => {
+            let immediate = read_immediate_z(reader, size_v)?;
+            let accumulator = context.register(ACCUMULATOR, size_v);
+            Ok(Decoded::new(
+                Mnemonic::Test,
+                size_v,
+                &[accumulator, immediate],
+            ))
+        }
+        0xAA | 0xAB => {
+            let size = if opcode == 0xAA {
+                OperandSize::Byte
+            } else {
+                size_v
+            };
+            let destination = context.string_operand(Segment::Es, DESTINATION_INDEX, size);
+            let accumulator = context.register(ACCUMULATOR, size);
+            Ok(Decoded::new(
+                Mnemonic::Stos,
+                size,
+                &[destination, accumulator],
+            ))
+        }
+        0xB0..=0xB7 => {
+            let index = (opcode & 0b111) | (u8::from(context.prefixes.b()) << 3);
+            let immediate = read_immediate(reader, OperandSize::Byte, OperandSize::Byte)?;
+            let destination = context.register(index, OperandSize::Byte);
+            Ok(Decoded::new(
+                Mnemonic::Mov,
+                OperandSize::Byte,
+                &[destination, immediate],
+            ))
+        }
+        0xB8..=0xBF => {
+            let index = (opcode & 0b111) | (u8::from(context.prefixes.b()) << 3);
+            let immediate = read_immediate(reader, size_v, size_v)?;
+            let destination = context.register(index, size_v);
+            Ok(Decoded::new(
+                Mnemonic::Mov,
+                size_v,
+                &[destination, immediate],
+            ))
+        }
+        0xC6 | 0xC7 | 0xF6 | 0xF7 => {
+            let size = if opcode & 1 == 0 {
+                OperandSize::Byte
+            } else {
+                size_v
+            };
+            let modrm = ModRm::read(reader)?;
+            let mnemonic = match (opcode, modrm.reg) {
+                (0xC6 | 0xC7, 0) => Mnemonic::Mov,
+                (0xF6 | 0xF7, 0 | 1) => Mnemonic::Test,
+                _ => return Err(DecodeError::UnsupportedOpcode),
+            };
+            let destination = decode_rm(reader, context, modrm, size)?;
+            let immediate = read_immediate_z(reader, size)?;
+            Ok(Decoded::new(mnemonic, size, &[destination, immediate]))
+        }
+        _ => Err(DecodeError::UnsupportedOpcode),
+    }
+}
+
+/// Decodes an instruction from the two-byte (`0x0F`) opcode map.
+fn decode_two_byte(reader: &mut Reader, context: &Context) -> Result<Decoded, DecodeError> {
+    let opcode = reader.read_u8()?;
+    match opcode {
+        0xB6 | 0xB7 | 0xBE | 0xBF => {
+            let mnemonic = if opcode < 0xB8 {
+                Mnemonic::Movzx
+            } else {
+                Mnemonic::Movsx
+            };
+            let source_size = if opcode & 1 == 0 {
+                OperandSize::Byte
+            } else {
+                OperandSize::Word
+            };
+            let modrm = ModRm::read(reader)?;
+            let source = decode_rm(reader, context, modrm, source_size)?;
+            let destination = context.register(context.reg(modrm), context.operand_size);
+            Ok(Decoded::new(
+                mnemonic,
+                context.operand_size,
+                &[destination, source],
+            ))
+        }
+        _ => {
+            let mandatory_prefix = match context.prefixes.repeat {
+                Some(Repeat::Repeat) => MandatoryPrefix::F3,
+                Some(Repeat::RepeatNotEqual) => MandatoryPrefix::F2,
+                None if context.prefixes.operand_size_override => MandatoryPrefix::P66,
+                None => MandatoryPrefix::None,
+            };
+            let mnemonic = vector_move(opcode, mandatory_prefix, false)?;
+            decode_vector_move(reader, context, mnemonic, opcode, OperandSize::Xmmword)
+        }
+    }
+}
+
+/// Decodes a `VEX` encoded instruction.
+fn decode_vex(reader: &mut Reader, context: &Context, vex: Vex) -> Result<Decoded, DecodeError> {
+    let opcode = reader.read_u8()?;
+    if vex.map() != 1 {
+        return Err(DecodeError::UnsupportedOpcode);
+    }
+
+    let mandatory_prefix = match vex.pp() {
+        0 => MandatoryPrefix::None,
+        1 => MandatoryPrefix::P66,
+        2 => MandatoryPrefix::F3,
+        _ => MandatoryPrefix::F2,
+    };
+    let mnemonic = vector_move(opcode, mandatory_prefix, true)?;
+    if vex.vvvv() != 0 {
+        return Err(DecodeError::InvalidEncoding);
+    }
+
+    let size = if vex.l() {
+        OperandSize::Ymmword
+    } else {
+        OperandSize::Xmmword
+    };
+    decode_vector_move(reader, context, mnemonic, opcode, size)
+}
+
+/// Returns the [`Mnemonic`] of the vector move with the given `opcode` in the `0x0F` map.
+fn vector_move(
+    opcode: u8,
+    mandatory_prefix: MandatoryPrefix,
+    vex: bool,
+) -> Result<Mnemonic, DecodeError> {
+    let (legacy, vex_encoded) = match (opcode, mandatory_prefix) {
+        (0x10 | 0x11, MandatoryPrefix::None) => (Mnemonic::Movups, Mnemonic::Vmovups),
+        (0x10 | 0x11, MandatoryPrefix::P66) => (Mnemonic::Movupd, Mnemonic::Vmovupd),
+        (0x28 | 0x29, MandatoryPrefix::None) => (Mnemonic::Movaps, Mnemonic::Vmovaps),
+        (0x28 | 0x29, MandatoryPrefix::P66) => (Mnemonic::Movapd, Mnemonic::Vmovapd),
+        (0x6F | 0x7F, MandatoryPrefix::P66) => (Mnemonic::Movdqa, Mnemonic::Vmovdqa),
+        (0x6F | 0x7F, MandatoryPrefix::F3) => (Mnemonic::Movdqu, Mnemonic::Vmovdqu),
+        _ => return Err(DecodeError::UnsupportedOpcode),
+    };
+
+    Ok(if vex { vex_encoded } else { legacy })
+}
+
+/// Decodes the operands of a vector move with the given `opcode` in the `0x0F` map.
+fn decode_vector_move(
+    reader: &mut Reader,
+    context: &Context,
+    mnemonic: Mnemonic,
+    opcode: u8,
+    size: OperandSize,
+) -> Result<Decoded, DecodeError> {
+    let modrm = ModRm::read(reader)?;
+    let memory = decode_rm(reader, context, modrm, size)?;
+    let register = Operand::Register(vector_register(context.reg(modrm), size));
+
+    let operands = match opcode {
+        0x10 | 0x28 | 0x6F => [register, memory],
+        _ => [memory, register],
+    };
+    Ok(Decoded::new(mnemonic, size, &operands))
+}
+
+/// Decodes an instruction whose first operand is `ModRM.rm` and second operand is `ModRM.reg`.
+fn decode_rm_reg(
+    reader: &mut Reader,
+    context: &Context,
+    mnemonic: Mnemonic,
+    size: OperandSize,
+) -> Result<Decoded, DecodeError> {
+    let modrm = ModRm::read(reader)?;
+    let destination = decode_rm(reader, context, modrm, size)?;
+    let source = context.register(context.reg(modrm), size);
+    Ok(Decoded::new(mnemonic, size, &[destination, source]))
+}
+
+/// Decodes an instruction whose first operand is `ModRM.reg` and second operand is `ModRM.rm`.
+fn decode_reg_rm(
+    reader: &mut Reader,
+    context: &Context,
+    mnemonic: Mnemonic,
+    size: OperandSize,
+) -> Result<Decoded, DecodeError> {
+    let modrm = ModRm::read(reader)?;
+    let source = decode_rm(reader, context, modrm, size)?;
+    let destination = context.register(context.reg(modrm), size);
+    Ok(Decoded::new(mnemonic, size, &[destination, source]))
+}
+
+/// Decodes the operand described by `ModRM.mod` and `ModRM.rm`, along with any `SIB` byte and
+/// displacement.
+fn decode_rm(
+    reader: &mut Reader,
+    context: &Context,
+    modrm: ModRm,
+    size: OperandSize,
+) -> Result<Operand, DecodeError> {
+    if modrm.mode == 0b11 {
+        let index = modrm.rm | (u8::from(context.prefixes.b()) << 3);
+        return Ok(match size {
+            OperandSize::Xmmword | OperandSize::Ymmword => {
+                Operand::Register(vector_register(index, size))
+            }
+            _ => context.register(index, size),
+        });
+    }
+
+    let memory = match context.address_size {
+        AddressSize::Bits16 => decode_memory_16(reader, context, modrm, size)?,
+        AddressSize::Bits32 | AddressSize::Bits64 => {
+            decode_memory_32(reader, context, modrm, size)?
+        }
+    };
+    Ok(Operand::Memory(memory))
+}
+
+/// Decodes a memory operand that uses 16-bit addressing.
+fn decode_memory_16(
+    reader: &mut Reader,
+    context: &Context,
+    modrm: ModRm,
+    size: OperandSize,
+) -> Result<MemoryOperand, DecodeError> {
+    let (base, index) = match modrm.rm {
+        0 => (Some(BASE), Some(SOURCE_INDEX)),
+        1 => (Some(BASE), Some(DESTINATION_INDEX)),
+        2 => (Some(FRAME_POINTER), Some(SOURCE_INDEX)),
+        3 => (Some(FRAME_POINTER), Some(DESTINATION_INDEX)),
+        4 => (Some(SOURCE_INDEX), None),
+        5 => (Some(DESTINATION_INDEX), None),
+        6 if modrm.mode == 0b00 => (None, None),
+        6 => (Some(FRAME_POINTER), None),
+        _ => (Some(BASE), None),
+    };
+
+    let displacement = match modrm.mode {
+        0b00 if base.is_none() => i64::from(reader.read_u16()?.cast_signed()),
+        0b00 => 0,
+        0b01 => i64::from(reader.read_u8()?.cast_signed()),
+        _ => i64::from(reader.read_u16()?.cast_signed()),
+    };
+
+    let default_segment = if base == Some(FRAME_POINTER) {
+        Segment::Ss
+    } else {
+        Segment::Ds
+    };
+    let (segment, explicit_segment) = context.segment(default_segment);
+    Ok(MemoryOperand {
+        segment,
+        explicit_segment,
+        base: base.map(Register::Gpr16),
+        index: index.map(Register::Gpr16),
+        scale: 1,
+        displacement,
+        size,
+        address_size: AddressSize::Bits16,
+    })
+}
+
+/// Decodes a memory operand that uses 32-bit or 64-bit addressing.
+fn decode_memory_32(
+    reader: &mut Reader,
+    context: &Context,
+    modrm: ModRm,
+    size: OperandSize,
+) -> Result<MemoryOperand, DecodeError> {
+    let address_register =
+        |index| Register::general_purpose(index, context.address_size.operand_size(), true);
+
+    let mut base = None;
+    let mut base_index = None;
+    let mut index = None;
+    let mut scale = 1;
+    let mut displacement = None;
+    if modrm.rm == 0b100 {
+        let sib = reader.read_u8()?;
+        scale = 1 << (sib >> 6);
+
+        let index_bits = ((sib >> 3) & 0b111) | (u8::from(context.prefixes.x()) << 3);
+        if index_bits != STACK_POINTER {
+            index = Some(address_register(index_bits));
+        }
+
+        if sib & 0b111 == FRAME_POINTER && modrm.mode == 0b00 {
+            displacement = Some(i64::from(reader.read_u32()?.cast_signed()));
+        } else {
+            let bits = (sib & 0b111) | (u8::from(context.prefixes.b()) << 3);
+            base_index = Some(bits);
+            base = Some(address_register(bits));
+        }
+    } else if modrm.rm == 0b101 && modrm.mode == 0b00 {
+        displacement = Some(i64::from(reader.read_u32()?.cast_signed()));
+        if context.mode == Mode::Bits64 {
+            base = Some(match context.address_size {
+                AddressSize::Bits64 => Register::Rip,
+                _ => Register::Eip,
+            });
+        }
+    } else {
+        let bits = modrm.rm | (u8::from(context.prefixes.b()) << 3);
+        base_index = Some(bits);
+        base = Some(address_register(bits));
+    }
+
+    let displacement = match (displacement, modrm.mode) {
+        (Some(displacement), _) => displacement,
+        (None, 0b00) => 0,
+        (None, 0b01) => i64::from(reader.read_u8()?.cast_signed()),
+        (None, _) => i64::from(reader.read_u32()?.cast_signed()),
+    };
+
+    let default_segment = match base_index {
+        Some(STACK_POINTER | FRAME_POINTER) => Segment::Ss,
+        _ => Segment::Ds,
+    };
+    let (segment, explicit_segment) = context.segment(default_segment);
+    Ok(MemoryOperand {
+        segment,
+        explicit_segment,
+        base,
+        index,
+        scale,
+        displacement,
+        size,
+        address_size: context.address_size,
+    })
+}
+
+/// Reads an immediate of `encoded_size`, sign extended for an operation of `size`.
+fn read_immediate(
+    reader: &mut Reader,
+    encoded_size: OperandSize,
+    size: OperandSize,
+) -> Result<Operand, DecodeError> {
+    let value = match encoded_size {
+        OperandSize::Byte => i64::from(reader.read_u8()?.cast_signed()),
+        OperandSize::Word => i64::from(reader.read_u16()?.cast_signed()),
+        OperandSize::Dword => i64::from(reader.read_u32()?.cast_signed()),
+        _ => reader.read_u64()?.cast_signed(),
+    };
+
+    Ok(Operand::Immediate { value, size })
+}
+
+/// Reads an immediate for an operation of `size` whose encoding is at most 32 bits wide.
+fn read_immediate_z(reader: &mut Reader, size: OperandSize) -> Result<Operand, DecodeError> {
+    let encoded_size = match size {
+        OperandSize::Byte | OperandSize::Word => size,
+        _ => OperandSize::Dword,
+    };
+    read_immediate(reader, encoded_size, size)
+}
+
+/// Returns the vector register with the given `index` and `size`.
+fn vector_register(index: u8, size: OperandSize) -> Register {
+    match size {
+        OperandSize::Ymmword => Register::Ymm(index),
+        _ => Register::Xmm(index),
+    }
+}
+
+/// The arithmetic instructions, indexed by bits 3 through 5 of their opcode or by `ModRM.reg`
+/// in the `0x80` through `0x83` group.
+const ARITHMETIC: [Mnemonic; 8] = [
+    Mnemonic::Add,
+    Mnemonic::Or,
+    Mnemonic::Adc,
+    Mnemonic::Sbb,
+    Mnemonic::And,
+    Mnemonic::Sub,
+    Mnemonic::Xor,
+    Mnemonic::Cmp,
+];
+
+/// The prefix that selects between instructions sharing an opcode in the `0x0F` map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MandatoryPrefix {
+    /// No mandatory prefix.
+    None,
+    /// The `0x66` prefix.
+    P66,
+    /// The `0xF3` prefix.
+    F3,
+    /// The `0xF2` prefix.
+    F2,
+}
+
+/// State that affects how the remainder of an instruction is decoded.
+struct Context {
+    /// The mode in which the instruction executes.
+    mode: Mode,
+    /// The prefixes of the instruction.
+    prefixes: Prefixes,
+    /// The operand size selected by the mode and the prefixes.
+    operand_size: OperandSize,
+    /// The address size selected by the mode and the prefixes.
+    address_size: AddressSize,
+}
+
+impl Context {
+    /// Returns the `ModRM.reg` field, extended by `REX.R` or `VEX.R`.
+    fn reg(&self, modrm: ModRm) -> u8 {
+        modrm.reg | (u8::from(self.prefixes.r()) << 3)
+    }
+
+    /// Returns the general purpose register operand with the given `index` and `size`.
+    fn register(&self, index: u8, size: OperandSize) -> Operand {
+        Operand::Register(Register::general_purpose(
+            index,
+            size,
+            self.prefixes.rex.is_some(),
+        ))
+    }
+
+    /// Returns the segment used by a memory operand whose default segment is `default`, and
+    /// whether it was selected by a segment override prefix.
+    fn segment(&self, default: Segment) -> (Segment, bool) {
+        match self.prefixes.segment {
+            Some(segment @ (Segment::Fs | Segment::Gs)) => (segment, true),
+            Some(segment) if self.mode != Mode::Bits64 => (segment, true),
+            _ => (default, false),
+        }
+    }
+
+    /// Returns the memory operand of a string instruction addressed by `segment:[index]`.
+    fn string_operand(&self, segment: Segment, index: u8, size: OperandSize) -> Operand {
+        Operand::Memory(MemoryOperand {
+            segment,
+            explicit_segment: true,
+            base: Some(Register::general_purpose(
+                index,
+                self.address_size.operand_size(),
+                true,
+            )),
+            index: None,
+            scale: 1,
+            displacement: 0,
+            size,
+            address_size: self.address_size,
+        })
+    }
+}
+
+/// The fields of a `ModRM` byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ModRm {
+    /// The addressing mode (`ModRM.mod`).
+    mode: u8,
+    /// The register or opcode extension (`ModRM.reg`).
+    reg: u8,
+    /// The register or memory operand (`ModRM.rm`).
+    rm: u8,
+}
+
+impl ModRm {
+    /// Reads a [`ModRm`] byte.
+    fn read(reader: &mut Reader) -> Result<Self, DecodeError> {
+        let byte = reader.read_u8()?;
+        Ok(Self {
+            mode: byte >> 6,
+            reg: (byte >> 3) & 0b111,
+            rm: byte & 0b111,
+        })
+    }
+}
+
+/// The mnemonic, operand size, and operands of a decoded instruction.
+struct Decoded {
+    /// The mnemonic of the instruction.
+    mnemonic: Mnemonic,
+    /// The size of the operation performed by the instruction.
+    operand_size: OperandSize,
+    /// The operands of the instruction, of which the first `operand_count` are valid.
+    operands: [Operand; MAX_OPERANDS],
+    /// The number of valid operands.
+    operand_count: usize,
+}
+
+impl Decoded {
+    /// Creates a new [`Decoded`] instruction.
+    fn new(mnemonic: Mnemonic, operand_size: OperandSize, operands: &[Operand]) -> Self {
+        let mut stored = [Instruction::UNUSED_OPERAND; MAX_OPERANDS];
+        stored[..operands.len()].copy_from_slice(operands);
+        Self {
+            mnemonic,
+            operand_size,
+            operands: stored,
+            operand_count: operands.len(),
+        }
+    }
+}
+
+/// A cursor over the bytes of an instruction.
+struct Reader<'a> {
+    /// The bytes from which the instruction is decoded.
+    bytes: &'a [u8],
+    /// The offset of the next byte to read.
+    position: usize,
+}
+
+impl Reader<'_> {
+    /// Returns the byte `offset` bytes past the current position without consuming it.
+    fn peek_u8(&self, offset: usize) -> Result<u8, DecodeError> {
+        let position = self.position + offset;
+        if position >= MAX_INSTRUCTION_LENGTH {
+            return Err(DecodeError::TooLong);
+        }
+
+        self.bytes
+            .get(position)
+            .copied()
+            .ok_or(DecodeError::Truncated)
+    }
+
+    /// Reads a byte.
+    fn read_u8(&mut self) -> Result<u8, DecodeError> {
+        let byte = self.peek_u8(0)?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    /// Reads `N` bytes.
+    fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let mut bytes = [0; N];
+        for byte in &mut bytes {
+            *byte = self.read_u8()?;
+        }
+        Ok(bytes)
+    }
+
+    /// Reads a little-endian [`u16`].
+    fn read_u16(&mut self) -> Result<u16, DecodeError> {
+        self.read_bytes().map(u16::from_le_bytes)
+    }
+
+    /// Reads a little-endian [`u32`].
+    fn read_u32(&mut self) -> Result<u32, DecodeError> {
+        self.read_bytes().map(u32::from_le_bytes)
+    }
+
+    /// Reads a little-endian [`u64`].
+    fn read_u64(&mut self) -> Result<u64, DecodeError> {
+        self.read_bytes().map(u64::from_le_bytes)
+    }
+}
@@ -0,0 +1,675 @@
+//! Decoder for `i686` and `x86_64` instructions.
+//!
+//! [`decode()`] decodes a single instruction in 16-bit, 32-bit, or 64-bit [`Mode`], including its
+//! legacy, `REX`, and `VEX` [`prefix`]es, `ModRM` and `SIB` bytes, displacement, and immediate.
+//! The decoder covers the data movement and arithmetic instructions that commonly access
+//! memory-mapped I/O, which makes it suitable for emulating faulting accesses and for printing
+//! Intel syntax disassembly in traces.
+
+#![no_std]
+
+use core::{error, fmt};
+
+use crate::{
+    operand::{AddressSize, MemoryOperand, Operand, OperandSize},
+    prefix::{Prefixes, Repeat},
+};
+
+mod decode;
+pub mod operand;
+pub mod prefix;
+
+/// The maximum length, in bytes, of an instruction.
+pub const MAX_INSTRUCTION_LENGTH: usize = 15;
+
+/// The maximum number of operands of an [`Instruction`].
+pub const MAX_OPERANDS: usize = 3;
+
+/// Decodes the instruction at the start of `bytes` when executing in `mode`.
+///
+/// # Errors
+///
+/// - [`DecodeError::Truncated`]: `bytes` ends before the instruction is complete.
+/// - [`DecodeError::TooLong`]: the instruction is longer than [`MAX_INSTRUCTION_LENGTH`].
+/// - [`DecodeError::InvalidEncoding`]: the instruction would raise an invalid opcode exception.
+/// - [`DecodeError::UnsupportedOpcode`]: the instruction is not supported by this decoder.
+pub fn decode(bytes: &[u8], mode: Mode) -> Result<Instruction, DecodeError> {
+    decode::decode(bytes, mode)
+}
+
+/// The mode in which an instruction executes, which determines its default operand and address
+/// sizes.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Mode {
+    /// Real mode, virtual-8086 mode, or 16-bit protected mode.
+    Bits16,
+    /// 32-bit protected mode or 32-bit compatibility mode.
+    Bits32,
+    /// 64-bit mode.
+    Bits64,
+}
+
+/// A decoded instruction.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Instruction {
+    /// The mnemonic of the instruction.
+    mnemonic: Mnemonic,
+    /// The operands of the instruction, of which the first `operand_count` are valid.
+    operands: [Operand; MAX_OPERANDS],
+    /// The number of valid operands.
+    operand_count: usize,
+    /// The size of the operation performed by the instruction.
+    operand_size: OperandSize,
+    /// The size of the addresses computed by the instruction.
+    address_size: AddressSize,
+    /// The prefixes of the instruction.
+    prefixes: Prefixes,
+    /// The length, in bytes, of the instruction.
+    length: usize,
+}
+
+impl Instruction {
+    /// The value of operand slots that are not used by an [`Instruction`].
+    const UNUSED_OPERAND: Operand = Operand::Immediate {
+        value: 0,
+        size: OperandSize::Byte,
+    };
+
+    /// Returns the [`Mnemonic`] of the instruction.
+    pub const fn mnemonic(&self) -> Mnemonic {
+        self.mnemonic
+    }
+
+    /// Returns the operands of the instruction, destination first.
+    pub fn operands(&self) -> &[Operand] {
+        &self.operands[..self.operand_count]
+    }
+
+    /// Returns the first memory operand of the instruction, if any.
+    ///
+    /// For [`Mnemonic::Movs`], this is the destination; the source is the second operand.
+    pub fn memory_operand(&self) -> Option<&MemoryOperand> {
+        self.operands().iter().find_map(|operand| match operand {
+            Operand::Memory(memory) => Some(memory),
+            _ => None,
+        })
+    }
+
+    /// Returns the size of the operation performed by the instruction.
+    ///
+    /// For [`Mnemonic::Movzx`], [`Mnemonic::Movsx`], and [`Mnemonic::Movsxd`], this is the size
+    /// of the destination.
+    pub const fn operand_size(&self) -> OperandSize {
+        self.operand_size
+    }
+
+    /// Returns the size of the addresses computed by the instruction.
+    pub const fn address_size(&self) -> AddressSize {
+        self.address_size
+    }
+
+    /// Returns the [`Prefixes`] of the instruction.
+    pub const fn prefixes(&self) -> &Prefixes {
+        &self.prefixes
+    }
+
+    /// Returns the length, in bytes, of the instruction.
+    pub const fn length(&self) -> usize {
+        self.length
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.prefixes.lock {
+            f.write_str("lock ")?;
+        }
+        if matches!(self.mnemonic, Mnemonic::Movs | Mnemonic::Stos) {
+            match self.prefixes.repeat {
+                Some(Repeat::Repeat) => f.write_str("rep ")?,
+                Some(Repeat::RepeatNotEqual) => f.write_str("repne ")?,
+                None => {}
+            }
+        }
+
+        f.write_str(self.mnemonic.name())?;
+        for (index, operand) in self.operands().iter().enumerate() {
+            f.write_str(if index == 0 { " " } else { ", " })?;
+            fmt::Display::fmt(operand, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The operation performed by an [`Instruction`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Mnemonic {
+    /// Add with carry.
+    Adc,
+    /// Add.
+    Add,
+    /// Logical AND.
+    And,
+    /// Compare.
+    Cmp,
+    /// Move.
+    Mov,
+    /// Move aligned packed double-precision floating-point values.
+    Movapd,
+    /// Move aligned packed single-precision floating-point values.
+    Movaps,
+    /// Move aligned packed integer values.
+    Movdqa,
+    /// Move unaligned packed integer values.
+    Movdqu,
+    /// Move data from string to string.
+    Movs,
+    /// Move with sign extension.
+    Movsx,
+    /// Move doubleword to quadword with sign extension.
+    Movsxd,
+    /// Move unaligned packed double-precision floating-point values.
+    Movupd,
+    /// Move unaligned packed single-precision floating-point values.
+    Movups,
+    /// Move with zero extension.
+    Movzx,
+    /// Logical inclusive OR.
+    Or,
+    /// Integer subtraction with borrow.
+    Sbb,
+    /// Store string.
+    Stos,
+    /// Subtract.
+    Sub,
+    /// Logical compare.
+    Test,
+    /// `VEX` encoded [`Mnemonic::Movapd`].
+    Vmovapd,
+    /// `VEX` encoded [`Mnemonic::Movaps`].
+    Vmovaps,
+    /// `VEX` encoded [`Mnemonic::Movdqa`].
+    Vmovdqa,
+    /// `VEX` encoded [`Mnemonic::Movdqu`].
+    Vmovdqu,
+    /// `VEX` encoded [`Mnemonic::Movupd`].
+    Vmovupd,
+    /// `VEX` encoded [`Mnemonic::Movups`].
+    Vmovups,
+    /// Logical exclusive OR.
+    Xor,
+}
+
+impl Mnemonic {
+    /// Returns the Intel syntax name of the [`Mnemonic`].
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Adc => "adc",
+            Self::Add => "add",
+            Self::And => "and",
+            Self::Cmp => "cmp",
+            Self::Mov => "mov",
+            Self::Movapd => "movapd",
+            Self::Movaps => "movaps",
+            Self::Movdqa => "movdqa",
+            Self::Movdqu => "movdqu",
+            Self::Movs => "movs",
+            Self::Movsx => "movsx",
+            Self::Movsxd => "movsxd",
+            Self::Movupd => "movupd",
+            Self::Movups => "movups",
+            Self::Movzx => "movzx",
+            Self::Or => "or",
+            Self::Sbb => "sbb",
+            Self::Stos => "stos",
+            Self::Sub => "sub",
+            Self::Test => "test",
+            Self::Vmovapd => "vmovapd",
+            Self::Vmovaps => "vmovaps",
+            Self::Vmovdqa => "vmovdqa",
+            Self::Vmovdqu => "vmovdqu",
+            Self::Vmovupd => "vmovupd",
+            Self::Vmovups => "vmovups",
+            Self::Xor => "xor",
+        }
+    }
+
+    /// Returns `true` if the instruction writes its first operand.
+    ///
+    /// [`Mnemonic::Cmp`] and [`Mnemonic::Test`] only update the flags.
+    pub const fn writes_destination(self) -> bool {
+        !matches!(self, Self::Cmp | Self::Test)
+    }
+
+    /// Returns `true` if the instruction accepts the `LOCK` prefix when its destination is in
+    /// memory.
+    pub const fn lockable(self) -> bool {
+        matches!(
+            self,
+            Self::Adc | Self::Add | Self::And | Self::Or | Self::Sbb | Self::Sub | Self::Xor
+        )
+    }
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Various errors that can occur when decoding an [`Instruction`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The bytes end before the instruction is complete.
+    Truncated,
+    /// The instruction is longer than [`MAX_INSTRUCTION_LENGTH`].
+    TooLong,
+    /// The instruction would raise an invalid opcode exception.
+    InvalidEncoding,
+    /// The instruction is not supported by the decoder.
+    UnsupportedOpcode,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "instruction bytes end before the instruction"),
+            Self::TooLong => write!(
+                f,
+                "instruction is longer than {MAX_INSTRUCTION_LENGTH} bytes"
+            ),
+            Self::InvalidEncoding => write!(f, "invalid instruction encoding"),
+            Self::UnsupportedOpcode => write!(f, "unsupported opcode"),
+        }
+    }
+}
+
+impl error::Error for DecodeError {}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use std::string::ToString;
+
+    use super::*;
+    use crate::operand::{Register, Segment};
+
+    #[test]
+    fn disassembly() {
+        let cases: &[(Mode, &[u8], &str)] = &[
+            (
+                Mode::Bits64,
+                &[0x89, 0x4C, 0x98, 0x10],
+                "mov dword ptr [rax+rbx*4+0x10], ecx",
+            ),
+            (
+                Mode::Bits64,
+                &[0x48, 0x8B, 0x05, 0x00, 0x10, 0x00, 0x00],
+                "mov rax, qword ptr [rip+0x1000]",
+            ),
+            (
+                Mode::Bits64,
+                &[0xC6, 0x07, 0x7F],
+                "mov byte ptr [rdi], 0x7f",
+            ),
+            (
+                Mode::Bits64,
+                &[0x4B, 0xC7, 0x44, 0xEC, 0xF8, 0x78, 0x56, 0x34, 0x12],
+                "mov qword ptr [r12+r13*8-0x8], 0x12345678",
+            ),
+            (
+                Mode::Bits64,
+                &[0x66, 0x44, 0x8B, 0x4D, 0x00],
+                "mov r9w, word ptr [rbp]",
+            ),
+            (
+                Mode::Bits64,
+                &[0x40, 0x8A, 0x76, 0x01],
+                "mov sil, byte ptr [rsi+0x1]",
+            ),
+            (Mode::Bits64, &[0x8A, 0x21], "mov ah, byte ptr [rcx]"),
+            (
+                Mode::Bits64,
+                &[0x64, 0x8B, 0x04, 0x25, 0x28, 0x00, 0x00, 0x00],
+                "mov eax, dword ptr fs:[0x28]",
+            ),
+            (
+                Mode::Bits64,
+                &[0x48, 0xA1, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11],
+                "mov rax, qword ptr [0x1122334455667788]",
+            ),
+            (
+                Mode::Bits64,
+                &[0xA2, 0x00, 0x00, 0xE0, 0xFE, 0x00, 0x00, 0x00, 0x00],
+                "mov byte ptr [0xfee00000], al",
+            ),
+            (
+                Mode::Bits64,
+                &[0x48, 0xB8, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11],
+                "mov rax, 0x1122334455667788",
+            ),
+            (
+                Mode::Bits64,
+                &[0xB9, 0xEF, 0xBE, 0xAD, 0xDE],
+                "mov ecx, 0xdeadbeef",
+            ),
+            (Mode::Bits64, &[0x41, 0xB3, 0x12], "mov r11b, 0x12"),
+            (
+                Mode::Bits64,
+                &[0x0F, 0xB6, 0x02],
+                "movzx eax, byte ptr [rdx]",
+            ),
+            (
+                Mode::Bits64,
+                &[0x4C, 0x0F, 0xB7, 0x04, 0x08],
+                "movzx r8, word ptr [rax+rcx]",
+            ),
+            (
+                Mode::Bits64,
+                &[0x0F, 0xBE, 0x4B, 0x80],
+                "movsx ecx, byte ptr [rbx-0x80]",
+            ),
+            (
+                Mode::Bits64,
+                &[0x48, 0x0F, 0xBF, 0x44, 0x24, 0x08],
+                "movsx rax, word ptr [rsp+0x8]",
+            ),
+            (
+                Mode::Bits64,
+                &[0x48, 0x63, 0x10],
+                "movsxd rdx, dword ptr [rax]",
+            ),
+            (
+                Mode::Bits64,
+                &[0xF3, 0xAA],
+                "rep stos byte ptr es:[rdi], al",
+            ),
+            (
+                Mode::Bits64,
+                &[0xF3, 0x48, 0xAB],
+                "rep stos qword ptr es:[rdi], rax",
+            ),
+            (Mode::Bits64, &[0xAB], "stos dword ptr es:[rdi], eax"),
+            (
+                Mode::Bits64,
+                &[0xF3, 0xA4],
+                "rep movs byte ptr es:[rdi], byte ptr ds:[rsi]",
+            ),
+            (
+                Mode::Bits64,
+                &[0x66, 0xA5],
+                "movs word ptr es:[rdi], word ptr ds:[rsi]",
+            ),
+            (
+                Mode::Bits64,
+                &[0x65, 0xA4],
+                "movs byte ptr es:[rdi], byte ptr gs:[rsi]",
+            ),
+            (
+                Mode::Bits64,
+                &[0x83, 0x20, 0xF0],
+                "and dword ptr [rax], 0xfffffff0",
+            ),
+            (
+                Mode::Bits64,
+                &[0x80, 0x4B, 0x04, 0x01],
+                "or byte ptr [rbx+0x4], 0x1",
+            ),
+            (
+                Mode::Bits64,
+                &[0x48, 0x31, 0x37],
+                "xor qword ptr [rdi], rsi",
+            ),
+            (Mode::Bits64, &[0x03, 0x06], "add eax, dword ptr [rsi]"),
+            (
+                Mode::Bits64,
+                &[0x66, 0x83, 0x29, 0x10],
+                "sub word ptr [rcx], 0x10",
+            ),
+            (Mode::Bits64, &[0x80, 0x38, 0x00], "cmp byte ptr [rax], 0x0"),
+            (
+                Mode::Bits64,
+                &[0x48, 0x83, 0xBA, 0x00, 0x01, 0x00, 0x00, 0x80],
+                "cmp qword ptr [rdx+0x100], 0xffffffffffffff80",
+            ),
+            (
+                Mode::Bits64,
+                &[0xF7, 0x00, 0x00, 0x01, 0x00, 0x00],
+                "test dword ptr [rax], 0x100",
+            ),
+            (Mode::Bits64, &[0x84, 0x03], "test byte ptr [rbx], al"),
+            (Mode::Bits64, &[0xA8, 0x01], "test al, 0x1"),
+            (
+                Mode::Bits64,
+                &[0xF0, 0x83, 0x00, 0x01],
+                "lock add dword ptr [rax], 0x1",
+            ),
+            (
+                Mode::Bits64,
+                &[0xF0, 0x4D, 0x31, 0x08],
+                "lock xor qword ptr [r8], r9",
+            ),
+            (
+                Mode::Bits64,
+                &[0x15, 0x34, 0x12, 0x00, 0x00],
+                "adc eax, 0x1234",
+            ),
+            (Mode::Bits64, &[0x80, 0xD9, 0x03], "sbb cl, 0x3"),
+            (Mode::Bits64, &[0x8C, 0x18], "mov word ptr [rax], ds"),
+            (Mode::Bits64, &[0x8E, 0x00], "mov es, word ptr [rax]"),
+            (
+                Mode::Bits64,
+                &[0x0F, 0x11, 0x08],
+                "movups xmmword ptr [rax], xmm1",
+            ),
+            (
+                Mode::Bits64,
+                &[0xF3, 0x0F, 0x6F, 0x16],
+                "movdqu xmm2, xmmword ptr [rsi]",
+            ),
+            (
+                Mode::Bits64,
+                &[0x66, 0x44, 0x0F, 0x7F, 0x7F, 0x20],
+                "movdqa xmmword ptr [rdi+0x20], xmm15",
+            ),
+            (
+                Mode::Bits64,
+                &[0x66, 0x0F, 0x28, 0x00],
+                "movapd xmm0, xmmword ptr [rax]",
+            ),
+            (
+                Mode::Bits64,
+                &[0xC5, 0xFE, 0x6F, 0x08],
+                "vmovdqu ymm1, ymmword ptr [rax]",
+            ),
+            (
+                Mode::Bits64,
+                &[0xC4, 0x41, 0x78, 0x11, 0x08],
+                "vmovups xmmword ptr [r8], xmm9",
+            ),
+            (
+                Mode::Bits64,
+                &[0xC5, 0x7D, 0x7F, 0x64, 0x24, 0x40],
+                "vmovdqa ymmword ptr [rsp+0x40], ymm12",
+            ),
+            (
+                Mode::Bits64,
+                &[0x67, 0x8B, 0x00],
+                "mov eax, dword ptr [eax]",
+            ),
+            (
+                Mode::Bits64,
+                &[0x41, 0x89, 0x45, 0x00],
+                "mov dword ptr [r13], eax",
+            ),
+            (
+                Mode::Bits64,
+                &[0x89, 0x04, 0x24],
+                "mov dword ptr [rsp], eax",
+            ),
+            (
+                Mode::Bits64,
+                &[0x89, 0x04, 0x25, 0x00, 0x10, 0x00, 0x00],
+                "mov dword ptr [0x1000], eax",
+            ),
+            (
+                Mode::Bits64,
+                &[0x40, 0x48, 0x8B, 0x00],
+                "mov rax, qword ptr [rax]",
+            ),
+            (
+                Mode::Bits64,
+                &[0x48, 0x66, 0x8B, 0x00],
+                "mov ax, word ptr [rax]",
+            ),
+            (
+                Mode::Bits32,
+                &[0x8B, 0x44, 0x73, 0x04],
+                "mov eax, dword ptr [ebx+esi*2+0x4]",
+            ),
+            (
+                Mode::Bits32,
+                &[0x89, 0x05, 0x00, 0x10, 0x00, 0x00],
+                "mov dword ptr [0x1000], eax",
+            ),
+            (
+                Mode::Bits32,
+                &[0x66, 0x67, 0x8B, 0x00],
+                "mov ax, word ptr [bx+si]",
+            ),
+            (
+                Mode::Bits32,
+                &[0x2E, 0x8B, 0x00],
+                "mov eax, dword ptr cs:[eax]",
+            ),
+            (
+                Mode::Bits32,
+                &[0xC5, 0xF9, 0x6F, 0xC1],
+                "vmovdqa xmm0, xmm1",
+            ),
+            (
+                Mode::Bits16,
+                &[0x8B, 0x43, 0x10],
+                "mov ax, word ptr [bp+di+0x10]",
+            ),
+            (Mode::Bits16, &[0x66, 0x8B, 0x04], "mov eax, dword ptr [si]"),
+            (Mode::Bits16, &[0xF3, 0xAB], "rep stos word ptr es:[di], ax"),
+            (
+                Mode::Bits16,
+                &[0xA2, 0x34, 0x12],
+                "mov byte ptr [0x1234], al",
+            ),
+            (
+                Mode::Bits16,
+                &[0x8B, 0x1E, 0x00, 0x80],
+                "mov bx, word ptr [0x8000]",
+            ),
+        ];
+
+        for (mode, bytes, expected) in cases {
+            let instruction = decode(bytes, *mode).unwrap();
+            assert_eq!(instruction.to_string(), *expected, "{bytes:02x?}");
+            assert_eq!(instruction.length(), bytes.len(), "{bytes:02x?}");
+        }
+    }
+
+    #[test]
+    fn decode_errors() {
+        let cases: &[(Mode, &[u8], DecodeError)] = &[
+            (Mode::Bits64, &[], DecodeError::Truncated),
+            (Mode::Bits64, &[0x8B], DecodeError::Truncated),
+            (Mode::Bits64, &[0x8B, 0x04], DecodeError::Truncated),
+            (Mode::Bits64, &[0xC7, 0x00, 0x01], DecodeError::Truncated),
+            (Mode::Bits64, &[0x66; 16], DecodeError::TooLong),
+            (
+                Mode::Bits64,
+                &[0xF0, 0x89, 0x00],
+                DecodeError::InvalidEncoding,
+            ),
+            (
+                Mode::Bits64,
+                &[0xF0, 0x01, 0xD8],
+                DecodeError::InvalidEncoding,
+            ),
+            (
+                Mode::Bits64,
+                &[0x82, 0x00, 0x01],
+                DecodeError::InvalidEncoding,
+            ),
+            (Mode::Bits64, &[0x8E, 0x08], DecodeError::InvalidEncoding),
+            (
+                Mode::Bits64,
+                &[0xC5, 0xF6, 0x6F, 0x08],
+                DecodeError::InvalidEncoding,
+            ),
+            (
+                Mode::Bits64,
+                &[0x66, 0xC5, 0xFE, 0x6F, 0x08],
+                DecodeError::InvalidEncoding,
+            ),
+            (Mode::Bits64, &[0x90], DecodeError::UnsupportedOpcode),
+            (
+                Mode::Bits64,
+                &[0xC7, 0x08, 0x00],
+                DecodeError::UnsupportedOpcode,
+            ),
+            (Mode::Bits64, &[0xF7, 0x10], DecodeError::UnsupportedOpcode),
+            (Mode::Bits32, &[0x40], DecodeError::UnsupportedOpcode),
+            (Mode::Bits32, &[0xC4, 0x00], DecodeError::UnsupportedOpcode),
+            (Mode::Bits32, &[0x63, 0x00], DecodeError::UnsupportedOpcode),
+        ];
+
+        for (mode, bytes, expected) in cases {
+            assert_eq!(decode(bytes, *mode), Err(*expected), "{bytes:02x?}");
+        }
+    }
+
+    #[test]
+    fn memory_access() {
+        // mov dword ptr [rax+rbx*4+0x10], ecx
+        let instruction = decode(&[0x89, 0x4C, 0x98, 0x10], Mode::Bits64).unwrap();
+        let memory = instruction.memory_operand().unwrap();
+        assert!(instruction.mnemonic().writes_destination());
+        assert_eq!(memory.size(), OperandSize::Dword);
+        assert_eq!(memory.segment(), Segment::Ds);
+        assert_eq!(
+            instruction.operands()[1],
+            Operand::Register(Register::Gpr32(1))
+        );
+        let address = memory.effective_address(|register| match register {
+            Register::Gpr64(0) => 0x1000,
+            Register::Gpr64(3) => 0x3,
+            _ => unreachable!(),
+        });
+        assert_eq!(address, 0x101C);
+
+        // mov rax, qword ptr [rip-0x10]
+        let instruction =
+            decode(&[0x48, 0x8B, 0x05, 0xF0, 0xFF, 0xFF, 0xFF], Mode::Bits64).unwrap();
+        let address = instruction
+            .memory_operand()
+            .unwrap()
+            .effective_address(|register| {
+                assert_eq!(register, Register::Rip);
+                0x2000
+            });
+        assert_eq!(address, 0x1FF0);
+
+        // mov ax, word ptr [bp+di-0x2]
+        let instruction = decode(&[0x8B, 0x43, 0xFE], Mode::Bits16).unwrap();
+        let memory = instruction.memory_operand().unwrap();
+        assert_eq!(memory.segment(), Segment::Ss);
+        let address = memory.effective_address(|register| match register {
+            Register::Gpr16(5) => 0xFFFF_0000,
+            Register::Gpr16(7) => 0x1,
+            _ => unreachable!(),
+        });
+        assert_eq!(address, 0xFFFF);
+
+        // cmp byte ptr [rax], 0x0
+        let instruction = decode(&[0x80, 0x38, 0x00], Mode::Bits64).unwrap();
+        assert!(!instruction.mnemonic().writes_destination());
+    }
+}
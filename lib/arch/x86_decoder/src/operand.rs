@@ -0,0 +1,388 @@
+//! Registers, memory references, and immediates that make up the operands of an instruction.
+
+use core::fmt;
+
+/// The size of an operand.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OperandSize {
+    /// An 8-bit operand.
+    Byte,
+    /// A 16-bit operand.
+    Word,
+    /// A 32-bit operand.
+    Dword,
+    /// A 64-bit operand.
+    Qword,
+    /// A 128-bit operand.
+    Xmmword,
+    /// A 256-bit operand.
+    Ymmword,
+}
+
+impl OperandSize {
+    /// Returns the size, in bytes, of the operand.
+    pub const fn bytes(self) -> u8 {
+        match self {
+            Self::Byte => 1,
+            Self::Word => 2,
+            Self::Dword => 4,
+            Self::Qword => 8,
+            Self::Xmmword => 16,
+            Self::Ymmword => 32,
+        }
+    }
+
+    /// Returns a mask covering the bits of an operand of this size, saturating at 64 bits.
+    pub const fn mask(self) -> u64 {
+        match self {
+            Self::Byte => 0xFF,
+            Self::Word => 0xFFFF,
+            Self::Dword => 0xFFFF_FFFF,
+            Self::Qword | Self::Xmmword | Self::Ymmword => u64::MAX,
+        }
+    }
+
+    /// Returns the name used for this size in Intel syntax memory operands.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Byte => "byte",
+            Self::Word => "word",
+            Self::Dword => "dword",
+            Self::Qword => "qword",
+            Self::Xmmword => "xmmword",
+            Self::Ymmword => "ymmword",
+        }
+    }
+}
+
+/// The size of the addresses computed by an instruction.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AddressSize {
+    /// Addresses are 16 bits wide.
+    Bits16,
+    /// Addresses are 32 bits wide.
+    Bits32,
+    /// Addresses are 64 bits wide.
+    Bits64,
+}
+
+impl AddressSize {
+    /// Returns a mask covering the bits of an address of this size.
+    pub const fn mask(self) -> u64 {
+        match self {
+            Self::Bits16 => 0xFFFF,
+            Self::Bits32 => 0xFFFF_FFFF,
+            Self::Bits64 => u64::MAX,
+        }
+    }
+
+    /// Returns the [`OperandSize`] of a general purpose register holding an address of this
+    /// size.
+    pub const fn operand_size(self) -> OperandSize {
+        match self {
+            Self::Bits16 => OperandSize::Word,
+            Self::Bits32 => OperandSize::Dword,
+            Self::Bits64 => OperandSize::Qword,
+        }
+    }
+}
+
+/// A segment register.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Segment {
+    /// The `ES` segment register.
+    Es,
+    /// The `CS` segment register.
+    Cs,
+    /// The `SS` segment register.
+    Ss,
+    /// The `DS` segment register.
+    Ds,
+    /// The `FS` segment register.
+    Fs,
+    /// The `GS` segment register.
+    Gs,
+}
+
+impl Segment {
+    /// Returns the [`Segment`] with the given encoding, or [`None`] if the encoding is invalid.
+    pub const fn from_index(index: u8) -> Option<Self> {
+        match index {
+            0 => Some(Self::Es),
+            1 => Some(Self::Cs),
+            2 => Some(Self::Ss),
+            3 => Some(Self::Ds),
+            4 => Some(Self::Fs),
+            5 => Some(Self::Gs),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the [`Segment`].
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Es => "es",
+            Self::Cs => "cs",
+            Self::Ss => "ss",
+            Self::Ds => "ds",
+            Self::Fs => "fs",
+            Self::Gs => "gs",
+        }
+    }
+}
+
+/// A register operand.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Register {
+    /// The low 8 bits of the general purpose register with the given index.
+    Gpr8(u8),
+    /// The second lowest 8 bits (`AH`, `CH`, `DH`, or `BH`) of the general purpose register with
+    /// the given index.
+    Gpr8High(u8),
+    /// The low 16 bits of the general purpose register with the given index.
+    Gpr16(u8),
+    /// The low 32 bits of the general purpose register with the given index.
+    Gpr32(u8),
+    /// The general purpose register with the given index.
+    Gpr64(u8),
+    /// A segment register.
+    Segment(Segment),
+    /// The 128-bit vector register with the given index.
+    Xmm(u8),
+    /// The 256-bit vector register with the given index.
+    Ymm(u8),
+    /// The instruction pointer, used as the base of 64-bit RIP-relative addresses.
+    Rip,
+    /// The low 32 bits of the instruction pointer, used as the base of 32-bit RIP-relative
+    /// addresses.
+    Eip,
+}
+
+impl Register {
+    /// Returns the general purpose register with the given `index` and `size`.
+    ///
+    /// `rex` selects between `AH`-`BH` and `SPL`-`DIL` for 8-bit registers with indices 4 through
+    /// 7.
+    pub const fn general_purpose(index: u8, size: OperandSize, rex: bool) -> Self {
+        match size {
+            OperandSize::Byte if index >= 4 && index < 8 && !rex => Self::Gpr8High(index - 4),
+            OperandSize::Byte => Self::Gpr8(index),
+            OperandSize::Word => Self::Gpr16(index),
+            OperandSize::Dword => Self::Gpr32(index),
+            OperandSize::Qword | OperandSize::Xmmword | OperandSize::Ymmword => Self::Gpr64(index),
+        }
+    }
+
+    /// Returns the [`OperandSize`] of the register.
+    pub const fn size(self) -> OperandSize {
+        match self {
+            Self::Gpr8(_) | Self::Gpr8High(_) => OperandSize::Byte,
+            Self::Gpr16(_) | Self::Segment(_) => OperandSize::Word,
+            Self::Gpr32(_) | Self::Eip => OperandSize::Dword,
+            Self::Gpr64(_) | Self::Rip => OperandSize::Qword,
+            Self::Xmm(_) => OperandSize::Xmmword,
+            Self::Ymm(_) => OperandSize::Ymmword,
+        }
+    }
+
+    /// Returns the name of the register.
+    pub const fn name(self) -> &'static str {
+        /// The names of the 64-bit general purpose registers.
+        const GPR64: [&str; 16] = [
+            "rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi", "r8", "r9", "r10", "r11",
+            "r12", "r13", "r14", "r15",
+        ];
+        /// The names of the 32-bit general purpose registers.
+        const GPR32: [&str; 16] = [
+            "eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi", "r8d", "r9d", "r10d", "r11d",
+            "r12d", "r13d", "r14d", "r15d",
+        ];
+        /// The names of the 16-bit general purpose registers.
+        const GPR16: [&str; 16] = [
+            "ax", "cx", "dx", "bx", "sp", "bp", "si", "di", "r8w", "r9w", "r10w", "r11w", "r12w",
+            "r13w", "r14w", "r15w",
+        ];
+        /// The names of the 8-bit general purpose registers.
+        const GPR8: [&str; 16] = [
+            "al", "cl", "dl", "bl", "spl", "bpl", "sil", "dil", "r8b", "r9b", "r10b", "r11b",
+            "r12b", "r13b", "r14b", "r15b",
+        ];
+        /// The names of the high 8-bit general purpose registers.
+        const GPR8_HIGH: [&str; 4] = ["ah", "ch", "dh", "bh"];
+        /// The names of the 128-bit vector registers.
+        const XMM: [&str; 16] = [
+            "xmm0", "xmm1", "xmm2", "xmm3", "xmm4", "xmm5", "xmm6", "xmm7", "xmm8", "xmm9",
+            "xmm10", "xmm11", "xmm12", "xmm13", "xmm14", "xmm15",
+        ];
+        /// The names of the 256-bit vector registers.
+        const YMM: [&str; 16] = [
+            "ymm0", "ymm1", "ymm2", "ymm3", "ymm4", "ymm5", "ymm6", "ymm7", "ymm8", "ymm9",
+            "ymm10", "ymm11", "ymm12", "ymm13", "ymm14", "ymm15",
+        ];
+
+        match self {
+            Self::Gpr8(index) => GPR8[index as usize & 0xF],
+            Self::Gpr8High(index) => GPR8_HIGH[index as usize & 0b11],
+            Self::Gpr16(index) => GPR16[index as usize & 0xF],
+            Self::Gpr32(index) => GPR32[index as usize & 0xF],
+            Self::Gpr64(index) => GPR64[index as usize & 0xF],
+            Self::Segment(segment) => segment.name(),
+            Self::Xmm(index) => XMM[index as usize & 0xF],
+            Self::Ymm(index) => YMM[index as usize & 0xF],
+            Self::Rip => "rip",
+            Self::Eip => "eip",
+        }
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A reference to memory.
+///
+/// The effective address is `base + index * scale + displacement`, truncated to the
+/// [`AddressSize`], and is relative to [`MemoryOperand::segment()`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct MemoryOperand {
+    /// The segment that the effective address is relative to.
+    pub(crate) segment: Segment,
+    /// If `true`, the segment was selected by a segment override prefix or is fixed by the
+    /// instruction.
+    pub(crate) explicit_segment: bool,
+    /// The base register.
+    pub(crate) base: Option<Register>,
+    /// The index register.
+    pub(crate) index: Option<Register>,
+    /// The factor by which the index register is multiplied.
+    pub(crate) scale: u8,
+    /// The signed displacement.
+    pub(crate) displacement: i64,
+    /// The size of the accessed memory.
+    pub(crate) size: OperandSize,
+    /// The size of the computed address.
+    pub(crate) address_size: AddressSize,
+}
+
+impl MemoryOperand {
+    /// Returns the segment that the effective address is relative to.
+    pub const fn segment(&self) -> Segment {
+        self.segment
+    }
+
+    /// Returns the base register, if any.
+    pub const fn base(&self) -> Option<Register> {
+        self.base
+    }
+
+    /// Returns the index register, if any.
+    pub const fn index(&self) -> Option<Register> {
+        self.index
+    }
+
+    /// Returns the factor by which the index register is multiplied.
+    pub const fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// Returns the signed displacement.
+    pub const fn displacement(&self) -> i64 {
+        self.displacement
+    }
+
+    /// Returns the size of the accessed memory.
+    pub const fn size(&self) -> OperandSize {
+        self.size
+    }
+
+    /// Returns the size of the computed address.
+    pub const fn address_size(&self) -> AddressSize {
+        self.address_size
+    }
+
+    /// Computes the effective address of the [`MemoryOperand`], not including the segment base.
+    ///
+    /// `read_register` must return the value of the requested register. For [`Register::Rip`] and
+    /// [`Register::Eip`], it must return the address of the next instruction.
+    pub fn effective_address(&self, mut read_register: impl FnMut(Register) -> u64) -> u64 {
+        let mut address = self.displacement.cast_unsigned();
+        if let Some(base) = self.base {
+            address = address.wrapping_add(read_register(base) & base.size().mask());
+        }
+        if let Some(index) = self.index {
+            let index_value = read_register(index) & index.size().mask();
+            address = address.wrapping_add(index_value.wrapping_mul(u64::from(self.scale)));
+        }
+
+        address & self.address_size.mask()
+    }
+}
+
+impl fmt::Display for MemoryOperand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ptr ", self.size.name())?;
+        if self.explicit_segment {
+            write!(f, "{}:", self.segment.name())?;
+        }
+
+        f.write_str("[")?;
+        let mut empty = true;
+        if let Some(base) = self.base {
+            f.write_str(base.name())?;
+            empty = false;
+        }
+        if let Some(index) = self.index {
+            if !empty {
+                f.write_str("+")?;
+            }
+            f.write_str(index.name())?;
+            if self.scale != 1 {
+                write!(f, "*{}", self.scale)?;
+            }
+            empty = false;
+        }
+
+        if empty {
+            write!(
+                f,
+                "{:#x}",
+                self.displacement.cast_unsigned() & self.address_size.mask()
+            )?;
+        } else if self.displacement < 0 {
+            write!(f, "-{:#x}", self.displacement.unsigned_abs())?;
+        } else if self.displacement > 0 {
+            write!(f, "+{:#x}", self.displacement)?;
+        }
+        f.write_str("]")
+    }
+}
+
+/// An operand of an instruction.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Operand {
+    /// A register operand.
+    Register(Register),
+    /// A memory operand.
+    Memory(MemoryOperand),
+    /// An immediate operand.
+    Immediate {
+        /// The value of the immediate, sign extended from its encoded width.
+        value: i64,
+        /// The size of the operation that consumes the immediate.
+        size: OperandSize,
+    },
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Register(register) => fmt::Display::fmt(register, f),
+            Self::Memory(memory) => fmt::Display::fmt(memory, f),
+            Self::Immediate { value, size } => {
+                write!(f, "{:#x}", value.cast_unsigned() & size.mask())
+            }
+        }
+    }
+}
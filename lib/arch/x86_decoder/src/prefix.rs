@@ -0,0 +1,223 @@
+//! Legacy, `REX`, and `VEX` instruction prefixes.
+
+use crate::operand::Segment;
+
+/// The prefixes attached to an instruction.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Prefixes {
+    /// If `true`, the `LOCK` prefix (`0xF0`) is present.
+    pub(crate) lock: bool,
+    /// The last repeat prefix (`0xF2` or `0xF3`) that is present.
+    pub(crate) repeat: Option<Repeat>,
+    /// The last segment override prefix that is present.
+    pub(crate) segment: Option<Segment>,
+    /// If `true`, the operand-size override prefix (`0x66`) is present.
+    pub(crate) operand_size_override: bool,
+    /// If `true`, the address-size override prefix (`0x67`) is present.
+    pub(crate) address_size_override: bool,
+    /// The `REX` prefix that immediately precedes the opcode.
+    pub(crate) rex: Option<Rex>,
+    /// The `VEX` prefix that immediately precedes the opcode.
+    pub(crate) vex: Option<Vex>,
+}
+
+impl Prefixes {
+    /// Returns `true` if the `LOCK` prefix is present.
+    pub const fn lock(&self) -> bool {
+        self.lock
+    }
+
+    /// Returns the [`Repeat`] prefix that is present, if any.
+    ///
+    /// When both `0xF2` and `0xF3` are present, the last one takes effect.
+    pub const fn repeat(&self) -> Option<Repeat> {
+        self.repeat
+    }
+
+    /// Returns the segment named by the segment override prefix that is present, if any.
+    ///
+    /// In 64-bit mode, only the `FS` and `GS` overrides take effect, but all overrides are
+    /// reported here.
+    pub const fn segment(&self) -> Option<Segment> {
+        self.segment
+    }
+
+    /// Returns `true` if the operand-size override prefix (`0x66`) is present.
+    pub const fn operand_size_override(&self) -> bool {
+        self.operand_size_override
+    }
+
+    /// Returns `true` if the address-size override prefix (`0x67`) is present.
+    pub const fn address_size_override(&self) -> bool {
+        self.address_size_override
+    }
+
+    /// Returns the [`Rex`] prefix that is present, if any.
+    pub const fn rex(&self) -> Option<Rex> {
+        self.rex
+    }
+
+    /// Returns the [`Vex`] prefix that is present, if any.
+    pub const fn vex(&self) -> Option<Vex> {
+        self.vex
+    }
+
+    /// Returns the `W` bit from either the [`Rex`] or the [`Vex`] prefix.
+    pub(crate) const fn w(&self) -> bool {
+        match (self.rex, self.vex) {
+            (Some(rex), _) => rex.w(),
+            (None, Some(vex)) => vex.w(),
+            (None, None) => false,
+        }
+    }
+
+    /// Returns the `R` bit from either the [`Rex`] or the [`Vex`] prefix.
+    pub(crate) const fn r(&self) -> bool {
+        match (self.rex, self.vex) {
+            (Some(rex), _) => rex.r(),
+            (None, Some(vex)) => vex.r(),
+            (None, None) => false,
+        }
+    }
+
+    /// Returns the `X` bit from either the [`Rex`] or the [`Vex`] prefix.
+    pub(crate) const fn x(&self) -> bool {
+        match (self.rex, self.vex) {
+            (Some(rex), _) => rex.x(),
+            (None, Some(vex)) => vex.x(),
+            (None, None) => false,
+        }
+    }
+
+    /// Returns the `B` bit from either the [`Rex`] or the [`Vex`] prefix.
+    pub(crate) const fn b(&self) -> bool {
+        match (self.rex, self.vex) {
+            (Some(rex), _) => rex.b(),
+            (None, Some(vex)) => vex.b(),
+            (None, None) => false,
+        }
+    }
+}
+
+/// A repeat prefix.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Repeat {
+    /// `REP`, `REPE`, or `REPZ` (`0xF3`).
+    Repeat,
+    /// `REPNE` or `REPNZ` (`0xF2`).
+    RepeatNotEqual,
+}
+
+/// A `REX` prefix, which extends register encodings and selects 64-bit operands in 64-bit mode.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Rex(u8);
+
+impl Rex {
+    /// Creates a [`Rex`] from the prefix byte, returning [`None`] if `byte` is not a `REX`
+    /// prefix.
+    pub const fn from_byte(byte: u8) -> Option<Self> {
+        if byte & 0xF0 == 0x40 {
+            Some(Self(byte))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the prefix byte.
+    pub const fn to_byte(self) -> u8 {
+        self.0
+    }
+
+    /// Returns `true` if the operand size is promoted to 64 bits (`REX.W`).
+    pub const fn w(self) -> bool {
+        self.0 & 0b1000 != 0
+    }
+
+    /// Returns `true` if the `ModRM.reg` field is extended (`REX.R`).
+    pub const fn r(self) -> bool {
+        self.0 & 0b0100 != 0
+    }
+
+    /// Returns `true` if the `SIB.index` field is extended (`REX.X`).
+    pub const fn x(self) -> bool {
+        self.0 & 0b0010 != 0
+    }
+
+    /// Returns `true` if the `ModRM.rm`, `SIB.base`, or opcode register field is extended
+    /// (`REX.B`).
+    pub const fn b(self) -> bool {
+        self.0 & 0b0001 != 0
+    }
+}
+
+/// A two-byte (`0xC5`) or three-byte (`0xC4`) `VEX` prefix.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Vex {
+    /// The inverted `R`, `X`, and `B` bits, followed by the opcode map, as encoded in the first
+    /// payload byte of a three-byte `VEX` prefix.
+    pub(crate) rxb_map: u8,
+    /// The `W`, inverted `vvvv`, `L`, and `pp` fields, as encoded in the last payload byte.
+    pub(crate) wvvvv_l_pp: u8,
+}
+
+impl Vex {
+    /// Decodes a two-byte `VEX` prefix from its payload byte.
+    pub(crate) const fn two_byte(payload: u8) -> Self {
+        Self {
+            rxb_map: (payload & 0x80) | 0x60 | 0b00001,
+            wvvvv_l_pp: payload & 0x7F,
+        }
+    }
+
+    /// Decodes a three-byte `VEX` prefix from its payload bytes.
+    pub(crate) const fn three_byte(first: u8, second: u8) -> Self {
+        Self {
+            rxb_map: first,
+            wvvvv_l_pp: second,
+        }
+    }
+
+    /// Returns `true` if the `ModRM.reg` field is extended (`VEX.R`).
+    pub const fn r(self) -> bool {
+        self.rxb_map & 0x80 == 0
+    }
+
+    /// Returns `true` if the `SIB.index` field is extended (`VEX.X`).
+    pub const fn x(self) -> bool {
+        self.rxb_map & 0x40 == 0
+    }
+
+    /// Returns `true` if the `ModRM.rm` or `SIB.base` field is extended (`VEX.B`).
+    pub const fn b(self) -> bool {
+        self.rxb_map & 0x20 == 0
+    }
+
+    /// Returns the opcode map selected by the prefix (`VEX.mmmmm`).
+    ///
+    /// Map 1 is the `0x0F` map, map 2 is the `0x0F 0x38` map, and map 3 is the `0x0F 0x3A` map.
+    pub const fn map(self) -> u8 {
+        self.rxb_map & 0x1F
+    }
+
+    /// Returns the `VEX.W` bit.
+    pub const fn w(self) -> bool {
+        self.wvvvv_l_pp & 0x80 != 0
+    }
+
+    /// Returns the additional register operand (`VEX.vvvv`), already un-inverted.
+    pub const fn vvvv(self) -> u8 {
+        !(self.wvvvv_l_pp >> 3) & 0xF
+    }
+
+    /// Returns `true` if the vector length is 256 bits (`VEX.L`).
+    pub const fn l(self) -> bool {
+        self.wvvvv_l_pp & 0b100 != 0
+    }
+
+    /// Returns the implied mandatory prefix (`VEX.pp`).
+    ///
+    /// `0` means none, `1` means `0x66`, `2` means `0xF3`, and `3` means `0xF2`.
+    pub const fn pp(self) -> u8 {
+        self.wvvvv_l_pp & 0b11
+    }
+}
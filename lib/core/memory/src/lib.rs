@@ -2,6 +2,7 @@
 #![no_std]
 
 pub mod range;
+pub mod range_set;
 
 /// A description of the parameters of an address space.
 ///
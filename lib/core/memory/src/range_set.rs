@@ -0,0 +1,637 @@
+//! A set of typed, non-overlapping address ranges backed by caller-provided storage.
+//!
+//! [`RangeSet`] is intended for tracking the designation of regions of an address space, such as
+//! a physical memory map or the free regions of a virtual address space, before any heap is
+//! available.
+
+use core::{error, fmt, mem::MaybeUninit, ops::Range, slice};
+
+/// A set of non-overlapping ranges, each associated with a value of type `T`.
+///
+/// The ranges are kept sorted by address, and adjacent ranges associated with equal values are
+/// always coalesced into a single [`RangeSetEntry`]. The entries are stored in the buffer provided
+/// to [`RangeSet::new()`], so operations that would require more entries than the buffer can hold
+/// fail with [`CapacityExceeded`] and leave the [`RangeSet`] unmodified.
+pub struct RangeSet<'storage, T: Copy + Eq> {
+    /// The buffer in which the entries are stored.
+    ///
+    /// The first [`RangeSet::len`] elements are initialized.
+    storage: &'storage mut [MaybeUninit<RangeSetEntry<T>>],
+    /// The number of entries in the [`RangeSet`].
+    len: usize,
+}
+
+impl<'storage, T: Copy + Eq> RangeSet<'storage, T> {
+    /// Creates a new, empty [`RangeSet`] that stores its entries in `storage`.
+    pub const fn new(storage: &'storage mut [MaybeUninit<RangeSetEntry<T>>]) -> Self {
+        Self { storage, len: 0 }
+    }
+
+    /// Returns the number of entries in the [`RangeSet`].
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the [`RangeSet`] contains no entries.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum number of entries the [`RangeSet`] can hold.
+    pub const fn capacity(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// Returns the entries of the [`RangeSet`], sorted by address.
+    pub fn entries(&self) -> &[RangeSetEntry<T>] {
+        let initialized = &self.storage[..self.len];
+
+        // SAFETY:
+        //
+        // The first `self.len` elements of `self.storage` are initialized, and `MaybeUninit<U>` has
+        // the same layout as `U`.
+        unsafe { slice::from_raw_parts(initialized.as_ptr().cast(), initialized.len()) }
+    }
+
+    /// Returns an iterator over the entries of the [`RangeSet`], sorted by address.
+    pub fn iter(&self) -> slice::Iter<'_, RangeSetEntry<T>> {
+        self.entries().iter()
+    }
+
+    /// Returns the entry containing `address`, or [`None`] if `address` is not part of any range.
+    pub fn get(&self, address: u64) -> Option<&RangeSetEntry<T>> {
+        let entries = self.entries();
+        let index = entries.partition_point(|entry| entry.end < address);
+
+        entries.get(index).filter(|entry| entry.start <= address)
+    }
+
+    /// Removes all entries from the [`RangeSet`].
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Associates the `count` addresses starting at `start` with `value`, replacing any values
+    /// previously associated with those addresses.
+    ///
+    /// Inserting an empty range has no effect.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CapacityExceeded`] if the result does not fit in the storage of the
+    /// [`RangeSet`], in which case the [`RangeSet`] is left unmodified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range extends past [`u64::MAX`].
+    pub fn insert(&mut self, start: u64, count: u64, value: T) -> Result<(), CapacityExceeded> {
+        let Some(end) = inclusive_end(start, count) else {
+            return Ok(());
+        };
+
+        let entries = self.entries();
+        let mut replaced = self.overlapping(start, end);
+        let mut inserted = RangeSetEntry { start, end, value };
+        let mut lower = None;
+        let mut upper = None;
+
+        if let Some(first) = entries[replaced.clone()].first()
+            && first.start < start
+        {
+            if first.value == value {
+                inserted.start = first.start;
+            } else {
+                lower = Some(RangeSetEntry {
+                    start: first.start,
+                    end: start - 1,
+                    value: first.value,
+                });
+            }
+        }
+
+        if let Some(last) = entries[replaced.clone()].last()
+            && last.end > end
+        {
+            if last.value == value {
+                inserted.end = last.end;
+            } else {
+                upper = Some(RangeSetEntry {
+                    start: end + 1,
+                    end: last.end,
+                    value: last.value,
+                });
+            }
+        }
+
+        if lower.is_none()
+            && let Some(previous) = replaced.start.checked_sub(1).map(|index| entries[index])
+            && previous.value == value
+            && previous.end.checked_add(1) == Some(inserted.start)
+        {
+            inserted.start = previous.start;
+            replaced.start -= 1;
+        }
+
+        if upper.is_none()
+            && let Some(next) = entries.get(replaced.end)
+            && next.value == value
+            && inserted.end.checked_add(1) == Some(next.start)
+        {
+            inserted.end = next.end;
+            replaced.end += 1;
+        }
+
+        let mut replacement = Replacement::new(inserted);
+        replacement.push_option(lower);
+        replacement.push(inserted);
+        replacement.push_option(upper);
+        self.splice(replaced, replacement.as_slice())
+    }
+
+    /// Removes the `count` addresses starting at `start` from the [`RangeSet`].
+    ///
+    /// Removing addresses that are not part of any range has no effect.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CapacityExceeded`] if removing the range splits an entry and the result does not
+    /// fit in the storage of the [`RangeSet`], in which case the [`RangeSet`] is left unmodified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range extends past [`u64::MAX`].
+    pub fn subtract(&mut self, start: u64, count: u64) -> Result<(), CapacityExceeded> {
+        let Some(end) = inclusive_end(start, count) else {
+            return Ok(());
+        };
+
+        let entries = self.entries();
+        let replaced = self.overlapping(start, end);
+        let (Some(&first), Some(&last)) = (
+            entries[replaced.clone()].first(),
+            entries[replaced.clone()].last(),
+        ) else {
+            return Ok(());
+        };
+
+        let mut replacement = Replacement::new(first);
+        if first.start < start {
+            replacement.push(RangeSetEntry {
+                start: first.start,
+                end: start - 1,
+                value: first.value,
+            });
+        }
+        if last.end > end {
+            replacement.push(RangeSetEntry {
+                start: end + 1,
+                end: last.end,
+                value: last.value,
+            });
+        }
+
+        self.splice(replaced, replacement.as_slice())
+    }
+
+    /// Returns the starting address of a region of `count` addresses, aligned to `alignment`, that
+    /// lies within a single entry whose value satisfies `filter` and that complies with `policy`.
+    ///
+    /// Returns [`None`] if no such region exists or if `count` is zero. The [`RangeSet`] is not
+    /// modified; callers typically follow a successful search with [`RangeSet::insert()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alignment` is not a power of two.
+    pub fn find_fit(
+        &self,
+        count: u64,
+        alignment: u64,
+        policy: FitPolicy,
+        mut filter: impl FnMut(&T) -> bool,
+    ) -> Option<u64> {
+        assert!(alignment.is_power_of_two());
+        let span = count.checked_sub(1)?;
+
+        let lowest = |entry: &RangeSetEntry<T>, max: u64| {
+            let start = align_up(entry.start, alignment)?;
+            let end = start.checked_add(span)?;
+            (end <= entry.end.min(max)).then_some(start)
+        };
+
+        match policy {
+            FitPolicy::Lowest => self
+                .iter()
+                .filter(|entry| filter(&entry.value))
+                .find_map(|entry| lowest(entry, u64::MAX)),
+            FitPolicy::Highest => self
+                .iter()
+                .rev()
+                .filter(|entry| filter(&entry.value))
+                .find_map(|entry| {
+                    let start = entry.end.checked_sub(span)? & !(alignment - 1);
+                    (start >= entry.start).then_some(start)
+                }),
+            FitPolicy::At(address) => {
+                let entry = self.get(address)?;
+                let end = address.checked_add(span)?;
+                (address & (alignment - 1) == 0 && end <= entry.end && filter(&entry.value))
+                    .then_some(address)
+            }
+            FitPolicy::InclusiveMax(max) => self
+                .iter()
+                .take_while(|entry| entry.start <= max)
+                .filter(|entry| filter(&entry.value))
+                .find_map(|entry| lowest(entry, max)),
+        }
+    }
+
+    /// Returns the range of indices of the entries that overlap the inclusive range
+    /// `[start, end]`.
+    fn overlapping(&self, start: u64, end: u64) -> Range<usize> {
+        let entries = self.entries();
+
+        entries.partition_point(|entry| entry.end < start)
+            ..entries.partition_point(|entry| entry.start <= end)
+    }
+
+    /// Replaces the entries at the indices in `replaced` with `replacement`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CapacityExceeded`] if the resulting entries do not fit in the storage of the
+    /// [`RangeSet`], in which case the [`RangeSet`] is left unmodified.
+    fn splice(
+        &mut self,
+        replaced: Range<usize>,
+        replacement: &[RangeSetEntry<T>],
+    ) -> Result<(), CapacityExceeded> {
+        let new_len = self.len - replaced.len() + replacement.len();
+        if new_len > self.storage.len() {
+            return Err(CapacityExceeded);
+        }
+
+        self.storage
+            .copy_within(replaced.end..self.len, replaced.start + replacement.len());
+        for (slot, entry) in self.storage[replaced.start..].iter_mut().zip(replacement) {
+            slot.write(*entry);
+        }
+
+        self.len = new_len;
+        Ok(())
+    }
+}
+
+impl<'set, T: Copy + Eq> IntoIterator for &'set RangeSet<'_, T> {
+    type Item = &'set RangeSetEntry<T>;
+    type IntoIter = slice::Iter<'set, RangeSetEntry<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Copy + Eq + fmt::Debug> fmt::Debug for RangeSet<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// A range of addresses and its associated value in a [`RangeSet`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct RangeSetEntry<T> {
+    /// The first address of the range.
+    start: u64,
+    /// The last address of the range.
+    end: u64,
+    /// The value associated with the range.
+    value: T,
+}
+
+impl<T: Copy> RangeSetEntry<T> {
+    /// Returns the first address of the range.
+    pub const fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the last address of the range.
+    pub const fn end(&self) -> u64 {
+        self.end
+    }
+
+    /// Returns the number of addresses in the range.
+    pub const fn count(&self) -> u64 {
+        // Ranges are constructed from a `u64` count, so this cannot overflow.
+        self.end - self.start + 1
+    }
+
+    /// Returns the value associated with the range.
+    pub const fn value(&self) -> T {
+        self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RangeSetEntry<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RangeSetEntry")
+            .field("start", &format_args!("{:#x}", self.start))
+            .field("end", &format_args!("{:#x}", self.end))
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+/// Structure controlling the region selected by [`RangeSet::find_fit()`].
+#[derive(Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FitPolicy {
+    /// The suitable region with the lowest starting address is selected.
+    #[default]
+    Lowest,
+    /// The suitable region with the highest starting address is selected.
+    Highest,
+    /// Only the region starting at the associated address is suitable.
+    At(u64),
+    /// The suitable region with the lowest starting address that lies entirely at or below the
+    /// associated inclusive maximum address is selected.
+    InclusiveMax(u64),
+}
+
+impl fmt::Debug for FitPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lowest => f.pad("Lowest"),
+            Self::Highest => f.pad("Highest"),
+            Self::At(value) => write!(f, "At({value:#x})"),
+            Self::InclusiveMax(value) => write!(f, "InclusiveMax({value:#x})"),
+        }
+    }
+}
+
+/// Indicates that an operation on a [`RangeSet`] would require more entries than its storage can
+/// hold.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CapacityExceeded;
+
+impl fmt::Display for CapacityExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("range set capacity exceeded")
+    }
+}
+
+impl error::Error for CapacityExceeded {}
+
+/// The at most three entries that replace the entries affected by a [`RangeSet`] operation.
+struct Replacement<T> {
+    /// The buffer holding the entries.
+    entries: [RangeSetEntry<T>; 3],
+    /// The number of valid entries in [`Replacement::entries`].
+    len: usize,
+}
+
+impl<T: Copy> Replacement<T> {
+    /// Creates an empty [`Replacement`], using `filler` to initialize the unused entries.
+    fn new(filler: RangeSetEntry<T>) -> Self {
+        Self {
+            entries: [filler; 3],
+            len: 0,
+        }
+    }
+
+    /// Appends `entry` to the [`Replacement`].
+    fn push(&mut self, entry: RangeSetEntry<T>) {
+        self.entries[self.len] = entry;
+        self.len += 1;
+    }
+
+    /// Appends `entry` to the [`Replacement`] if it is [`Some`].
+    fn push_option(&mut self, entry: Option<RangeSetEntry<T>>) {
+        if let Some(entry) = entry {
+            self.push(entry);
+        }
+    }
+
+    /// Returns the valid entries of the [`Replacement`].
+    fn as_slice(&self) -> &[RangeSetEntry<T>] {
+        &self.entries[..self.len]
+    }
+}
+
+/// Returns the last address of the range of `count` addresses starting at `start`, or [`None`] if
+/// `count` is zero.
+///
+/// # Panics
+///
+/// Panics if the range extends past [`u64::MAX`].
+fn inclusive_end(start: u64, count: u64) -> Option<u64> {
+    let span = count.checked_sub(1)?;
+    Some(
+        start
+            .checked_add(span)
+            .expect("range extends past the end of the address space"),
+    )
+}
+
+/// Returns the smallest multiple of `alignment` that is greater than or equal to `value`, or
+/// [`None`] if it is not representable.
+fn align_up(value: u64, alignment: u64) -> Option<u64> {
+    value
+        .checked_add(alignment - 1)
+        .map(|value| value & !(alignment - 1))
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// The number of addresses tracked by the model used in the property tests.
+    const SPACE: u64 = 96;
+
+    /// An operation applied to both a [`RangeSet`] and its model.
+    #[derive(Clone, Copy, Debug)]
+    enum Operation {
+        /// Calls [`RangeSet::insert()`].
+        Insert(u64, u64, u8),
+        /// Calls [`RangeSet::subtract()`].
+        Subtract(u64, u64),
+    }
+
+    /// Returns a strategy generating [`Operation`]s within the modelled address space.
+    fn operation() -> impl Strategy<Value = Operation> {
+        let range = (0..SPACE).prop_flat_map(|start| (Just(start), 0..=(SPACE - start).min(24)));
+        prop_oneof![
+            3 => (range.clone(), 0..3u8)
+                .prop_map(|((start, count), value)| Operation::Insert(start, count, value)),
+            1 => range.prop_map(|(start, count)| Operation::Subtract(start, count)),
+        ]
+    }
+
+    /// Converts a modelled `address` into an index into the model.
+    fn index(address: u64) -> usize {
+        usize::try_from(address).unwrap()
+    }
+
+    /// Returns the value associated with each address of the modelled address space.
+    fn expand(set: &RangeSet<'_, u8>) -> Vec<Option<u8>> {
+        let mut model = std::vec![None; index(SPACE)];
+        for entry in set {
+            for address in entry.start()..=entry.end() {
+                model[index(address)] = Some(entry.value());
+            }
+        }
+        model
+    }
+
+    /// Asserts that the entries of `set` are sorted, disjoint and coalesced.
+    fn assert_canonical(set: &RangeSet<'_, u8>) {
+        for pair in set.entries().windows(2) {
+            assert!(pair[0].end() < pair[1].start(), "{set:?}");
+            assert!(
+                pair[0].end() + 1 != pair[1].start() || pair[0].value() != pair[1].value(),
+                "{set:?}"
+            );
+        }
+    }
+
+    /// Applies `operations` to a [`RangeSet`] with `storage`, checking it against a model.
+    fn check_against_model(
+        storage: &mut [MaybeUninit<RangeSetEntry<u8>>],
+        operations: &[Operation],
+    ) -> Vec<Option<u8>> {
+        let mut set = RangeSet::new(storage);
+        let mut model = std::vec![None; index(SPACE)];
+
+        for &operation in operations {
+            let before = set.entries().to_vec();
+            let (result, value) = match operation {
+                Operation::Insert(start, count, value) => {
+                    (set.insert(start, count, value), Some(value))
+                }
+                Operation::Subtract(start, count) => (set.subtract(start, count), None),
+            };
+            let (Operation::Insert(start, count, _) | Operation::Subtract(start, count)) =
+                operation;
+
+            match result {
+                Ok(()) => model[index(start)..index(start + count)].fill(value),
+                Err(CapacityExceeded) => assert_eq!(set.entries(), before),
+            }
+
+            assert_canonical(&set);
+            assert_eq!(expand(&set), model);
+        }
+
+        model
+    }
+
+    proptest! {
+        #[test]
+        fn operations_match_model(operations in prop::collection::vec(operation(), 0..48)) {
+            let mut storage = [MaybeUninit::uninit(); 64];
+            check_against_model(&mut storage, &operations);
+        }
+
+        #[test]
+        fn exhausted_storage_leaves_set_unmodified(
+            operations in prop::collection::vec(operation(), 0..48),
+        ) {
+            let mut storage = [MaybeUninit::uninit(); 4];
+            check_against_model(&mut storage, &operations);
+        }
+
+        #[test]
+        fn find_fit_matches_model(
+            operations in prop::collection::vec(operation(), 0..32),
+            count in 0..12u64,
+            alignment_shift in 0..4u32,
+            value in 0..3u8,
+            policy in prop_oneof![
+                Just(FitPolicy::Lowest),
+                Just(FitPolicy::Highest),
+                (0..SPACE).prop_map(FitPolicy::At),
+                (0..SPACE).prop_map(FitPolicy::InclusiveMax),
+            ],
+        ) {
+            let mut storage = [MaybeUninit::uninit(); 64];
+            let model = check_against_model(&mut storage, &operations);
+            let mut set = RangeSet::new(&mut storage);
+            for operation in operations {
+                match operation {
+                    Operation::Insert(start, count, value) => {
+                        set.insert(start, count, value).unwrap()
+                    }
+                    Operation::Subtract(start, count) => set.subtract(start, count).unwrap(),
+                }
+            }
+
+            let alignment = 1u64 << alignment_shift;
+            let fits = |start: u64| {
+                count != 0
+                    && start.is_multiple_of(alignment)
+                    && start + count <= SPACE
+                    && model[index(start)..index(start + count)]
+                        .iter()
+                        .all(|entry| *entry == Some(value))
+            };
+            let expected = match policy {
+                FitPolicy::Lowest => (0..SPACE).find(|&start| fits(start)),
+                FitPolicy::Highest => (0..SPACE).rev().find(|&start| fits(start)),
+                FitPolicy::At(address) => Some(address).filter(|&start| fits(start)),
+                FitPolicy::InclusiveMax(max) => {
+                    (0..SPACE).find(|&start| fits(start) && start + count - 1 <= max)
+                }
+            };
+
+            let found = set.find_fit(count, alignment, policy, |entry| *entry == value);
+            prop_assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn insert_splits_and_coalesces() {
+        let mut storage = [MaybeUninit::uninit(); 8];
+        let mut set = RangeSet::new(&mut storage);
+
+        set.insert(0x1000, 0x3000, 'f').unwrap();
+        set.insert(0x2000, 0x1000, 'u').unwrap();
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.get(0x2800).map(RangeSetEntry::value), Some('u'));
+
+        set.insert(0x2000, 0x1000, 'f').unwrap();
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.entries()[0].start(), 0x1000);
+        assert_eq!(set.entries()[0].count(), 0x3000);
+
+        set.subtract(0x1000, 0x1000).unwrap();
+        assert_eq!(set.get(0x1000), None);
+        assert_eq!(set.entries()[0].start(), 0x2000);
+    }
+
+    #[test]
+    fn ranges_reaching_end_of_address_space() {
+        let mut storage = [MaybeUninit::uninit(); 4];
+        let mut set = RangeSet::new(&mut storage);
+
+        set.insert(u64::MAX - 0xFFF, 0x1000, 0u8).unwrap();
+        set.insert(u64::MAX, 1, 1).unwrap();
+        assert_eq!(set.entries()[0].end(), u64::MAX - 1);
+        assert_eq!(set.entries()[1].end(), u64::MAX);
+
+        assert_eq!(
+            set.find_fit(0x800, 0x800, FitPolicy::Highest, |value| *value == 0),
+            Some(u64::MAX - 0xFFF)
+        );
+        assert_eq!(
+            set.find_fit(1, 1, FitPolicy::Highest, |value| *value == 1),
+            Some(u64::MAX)
+        );
+        assert_eq!(
+            set.find_fit(2, 1, FitPolicy::Lowest, |value| *value == 1),
+            None
+        );
+    }
+}
@@ -0,0 +1,1081 @@
+//! Implementation of a DAG-based initialization routine.
+//!
+//! This is based on Managarm's `InitGraph`. Tasks and stages are declared using [`define_task!`]
+//! and [`define_stage!`], which place them in the `.initgraph` section. The linker script of the
+//! final binary must keep that section and delimit it with the `__initgraph_start` and
+//! `__initgraph_end` symbols.
+//!
+//! Each declared [`InitGraphNode`] is exported under a symbol derived from its name, and each
+//! reference to another node by name refers to that symbol. A reference to a node that does not
+//! exist is therefore an undefined symbol, and two nodes with the same name are a duplicate
+//! symbol, both of which are rejected when the binary is linked. Cycles cannot be expressed to the
+//! linker, so the graph as a whole is checked for them by [`verify()`] and the first time it is
+//! [`run()`], before any node has executed.
+
+#![no_std]
+
+use core::{
+    fmt, mem, ptr, slice,
+    sync::atomic::{AtomicBool, AtomicU8, AtomicU64, AtomicUsize, Ordering},
+};
+
+use sync::{ControlledModificationCell, Spinlock};
+
+#[doc(hidden)]
+pub use sync;
+
+unsafe extern "Rust" {
+    #[link_name = "__initgraph_start"]
+    static INITGRAPH_START: InitGraphNode<'static>;
+    #[link_name = "__initgraph_end"]
+    static INITGRAPH_END: InitGraphNode<'static>;
+}
+
+/// Lock over the initialization subsystem.
+static INITGRAPH_LOCK: Spinlock<()> = Spinlock::new(());
+/// Indicator that the subsystem has been initialized.
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+/// The clock used to measure the execution time of each [`InitGraphNode`].
+static CLOCK: Spinlock<fn() -> u64> = Spinlock::new(no_clock);
+/// The number of [`InitGraphNode`]s that have been executed.
+static EXECUTED: AtomicUsize = AtomicUsize::new(0);
+
+/// Runs `node` and all of its dependencies.
+///
+/// # Panics
+///
+/// Panics if this function is called in a reentrant manner or if the initialization graph
+/// contains a cycle.
+pub fn run(node: &'static InitGraphNode) {
+    let Ok(lock) = INITGRAPH_LOCK.try_lock() else {
+        panic!("initgraph subsystem cannot be called in a reentrant manner");
+    };
+
+    let nodes = init_nodes();
+    initialize_once(nodes);
+    run_internal(node, nodes);
+
+    drop(lock);
+}
+
+/// Checks the embedded initialization graph without running any [`InitGraphNode`].
+///
+/// [`run()`] performs the same checks the first time it is called, so this only needs to be
+/// called to validate a graph that might never be run, such as from a host test.
+///
+/// # Panics
+///
+/// Panics if this function is called in a reentrant manner or if the initialization graph
+/// contains a cycle.
+pub fn verify() {
+    let Ok(lock) = INITGRAPH_LOCK.try_lock() else {
+        panic!("initgraph subsystem cannot be called in a reentrant manner");
+    };
+
+    initialize_once(init_nodes());
+
+    drop(lock);
+}
+
+/// Initializes `nodes` if they have not already been initialized.
+///
+/// # Panics
+///
+/// Panics if the initialization graph contains a cycle.
+fn initialize_once<'a>(nodes: &'a [InitGraphNode<'a>]) {
+    if !INITIALIZED.load(Ordering::Relaxed) {
+        initialize(nodes);
+        INITIALIZED.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Sets the clock used to measure how long each [`InitGraphNode`] takes to execute.
+///
+/// `clock` must return a monotonically increasing tick count, whose unit is left to the caller.
+pub fn set_clock(clock: fn() -> u64) {
+    *CLOCK.lock() = clock;
+}
+
+/// The clock used if no other clock has been set, under which every node takes zero ticks.
+fn no_clock() -> u64 {
+    0
+}
+
+/// Returns an [`Iterator`] over the embedded [`InitGraphNode`]s that have been executed, in the
+/// order in which they were executed.
+pub fn executed() -> Executed<'static> {
+    Executed {
+        nodes: init_nodes(),
+        sequence: 1,
+    }
+}
+
+/// Returns the list of embedded [`InitGraphNode`]s.
+fn init_nodes() -> &'static [InitGraphNode<'static>] {
+    let start = &raw const INITGRAPH_START;
+    let end = &raw const INITGRAPH_END;
+
+    let size = (end.addr() - start.addr()) / mem::size_of::<InitGraphNode>();
+    // SAFETY:
+    //
+    // The contained [`InitGraphNode`]s were implemented using a macro and are initialized.
+    unsafe { slice::from_raw_parts(start, size) }
+}
+
+/// Executes an initialization graph until the target `node` is executed.
+fn run_internal<'a>(node: &'a InitGraphNode<'a>, nodes: &'a [InitGraphNode<'a>]) {
+    let mut queue = LinkList::new();
+
+    assert_ne!(
+        node.state(),
+        State::Inactive,
+        "inactive nodes must not be executed"
+    );
+    if node.state() == State::Done {
+        return;
+    }
+
+    // Reset markings from previous executions.
+    clear_wanted(nodes);
+
+    // Mark all nodes that should be run as `State::Wanted`.
+    node.set_state(State::Wanted);
+    queue.push_back(&node.link);
+    while let Some(node) = queue.pop_front() {
+        let node = node.get().node();
+        for requirement in node.requires() {
+            if requirement.state() != State::Active {
+                continue;
+            }
+
+            requirement.set_state(State::Wanted);
+            queue.push_back(&requirement.link);
+        }
+    }
+
+    // Gather all nodes that should be run in a single list.
+    for node in nodes {
+        if node.state() == State::Wanted {
+            queue.push_back(&node.link);
+        }
+    }
+
+    // The graph was verified to be acyclic by `check_acyclic()`, so every pass over the queue
+    // executes at least one node.
+    'outer: while let Some(node) = queue.pop_front() {
+        let node = node.get().node();
+        assert_eq!(node.state(), State::Wanted);
+
+        for requirement in node.requires() {
+            if requirement.state() == State::Wanted {
+                queue.push_back(&node.link);
+                continue 'outer;
+            }
+        }
+
+        for after_node in node.after() {
+            if after_node.state() == State::Wanted {
+                queue.push_back(&node.link);
+                continue 'outer;
+            }
+        }
+
+        node.execute();
+        node.set_state(State::Done);
+    }
+}
+
+/// Clears all nodes of their [`State::Wanted`] status.
+fn clear_wanted<'a>(nodes: &'a [InitGraphNode<'a>]) {
+    for node in nodes {
+        if node.state() == State::Wanted {
+            node.set_state(State::Active);
+        }
+    }
+}
+
+/// Initializes the linked lists of required and after nodes.
+///
+/// # Panics
+///
+/// Panics if two nodes share a name, if a node refers to itself, or if the resulting graph
+/// contains a cycle.
+fn initialize<'a>(nodes: &'a [InitGraphNode<'a>]) {
+    for (i, node) in nodes.iter().enumerate() {
+        for test_node in nodes.iter().skip(i + 1) {
+            assert_ne!(
+                node.name, test_node.name,
+                "execution node names must be unique"
+            );
+        }
+
+        // SAFETY:
+        //
+        // This function and all related functions are controlled by a spinlock.
+        unsafe { node.link.get_mut().node = Some(node) }
+    }
+
+    for node in nodes {
+        let CompileTimeDesc::Task {
+            func: _,
+            requires,
+            dependents,
+            after,
+            before,
+        } = node.compile_time_desc
+        else {
+            continue;
+        };
+
+        // SAFETY:
+        //
+        // This function and all related functions are controlled by a spinlock.
+        let runtime_desc = unsafe { node.runtime_desc.get_mut() };
+
+        for (&required, link) in requires.iter().zip(runtime_desc.requires_links.iter()) {
+            assert!(!ptr::eq(required, node), "a node must not require itself");
+
+            // SAFETY:
+            //
+            // This link is protected by the spinlock and is not referenced elsewhere.
+            unsafe { link.get_mut().node = Some(required) }
+            runtime_desc.requires_list.push_back(link);
+        }
+
+        for (&after, link) in after.iter().zip(runtime_desc.after_links.iter()) {
+            assert!(!ptr::eq(after, node), "a node must not be after itself");
+
+            // SAFETY:
+            //
+            // This link is protected by the spinlock and is not referenced elsewhere.
+            unsafe { link.get_mut().node = Some(after) }
+            runtime_desc.after_list.push_back(link);
+        }
+
+        for (&dependent, link) in dependents.iter().zip(runtime_desc.dependents_links.iter()) {
+            assert!(
+                !ptr::eq(dependent, node),
+                "a node must not be dependent on itself"
+            );
+
+            // SAFETY:
+            //
+            // This link is protected by the spinlock and is not referenced elsewhere.
+            unsafe { link.get_mut().node = Some(node) }
+
+            // SAFETY:
+            //
+            // This function and all related functions are controlled by a spinlock, and
+            // `dependent` is a different node than `node`.
+            let dependent_desc = unsafe { dependent.runtime_desc.get_mut() };
+            dependent_desc.requires_list.push_back(link);
+        }
+
+        for (&before, link) in before.iter().zip(runtime_desc.before_links.iter()) {
+            assert!(!ptr::eq(before, node), "a node must not be before itself");
+
+            // SAFETY:
+            //
+            // This link is protected by the spinlock and is not referenced elsewhere.
+            unsafe { link.get_mut().node = Some(node) }
+
+            // SAFETY:
+            //
+            // This function and all related functions are controlled by a spinlock, and `before`
+            // is a different node than `node`.
+            let before_desc = unsafe { before.runtime_desc.get_mut() };
+            before_desc.after_list.push_back(link);
+        }
+    }
+
+    check_acyclic(nodes);
+}
+
+/// Verifies that the requirement and ordering constraints between `nodes` form a DAG.
+///
+/// Every node is considered, regardless of its [`State`], so that the result does not depend on
+/// which nodes happen to be active.
+///
+/// # Panics
+///
+/// Panics, naming a node that takes part in the cycle, if the constraints contain a cycle.
+fn check_acyclic<'a>(nodes: &'a [InitGraphNode<'a>]) {
+    let mut queue = LinkList::new();
+    let mut remaining = 0;
+    for node in nodes {
+        node.ordered.store(false, Ordering::Relaxed);
+        queue.push_back(&node.link);
+        remaining += 1;
+    }
+
+    let mut loops_since_last_placement = 0;
+    while let Some(link) = queue.pop_front() {
+        let node = link.get().node();
+        assert!(
+            loops_since_last_placement < remaining,
+            "cycle in initialization graph involving {:?}",
+            node.name
+        );
+
+        if node
+            .requires()
+            .chain(node.after())
+            .any(|previous| !previous.ordered.load(Ordering::Relaxed))
+        {
+            loops_since_last_placement += 1;
+            queue.push_back(link);
+            continue;
+        }
+
+        node.ordered.store(true, Ordering::Relaxed);
+        loops_since_last_placement = 0;
+        remaining -= 1;
+    }
+}
+
+/// Representation of a task or stage in the initialization graph.
+pub struct InitGraphNode<'a> {
+    /// The name of the node.
+    name: &'a str,
+
+    /// The state of the [`InitGraphNode`].
+    state: AtomicU8,
+
+    /// The position of the [`InitGraphNode`] in the execution order, starting at one, or zero if it
+    /// has not been executed.
+    sequence: AtomicUsize,
+    /// The number of clock ticks the [`InitGraphNode`] took to execute.
+    elapsed: AtomicU64,
+    /// Indicator that [`check_acyclic()`] has placed the [`InitGraphNode`] in a topological order.
+    ordered: AtomicBool,
+
+    /// An intrusive link.
+    link: ControlledModificationCell<Link<'a>>,
+
+    /// The compile representation of the [`InitGraphNode`] from which the runtime representation
+    /// will be derived.
+    compile_time_desc: CompileTimeDesc<'a>,
+
+    /// The runtime representation of the [`InitGraphNode`].
+    runtime_desc: ControlledModificationCell<RuntimeDesc<'a>>,
+}
+
+impl<'a> InitGraphNode<'a> {
+    #[doc(hidden)]
+    #[expect(clippy::too_many_arguments)]
+    pub const fn new_task<const R: usize, const D: usize, const A: usize, const B: usize>(
+        name: &'a str,
+        func: fn(),
+        active: bool,
+        requires: &'a [&'a InitGraphNode<'a>; R],
+        requires_links: &'a [ControlledModificationCell<Link<'a>>; R],
+        dependents: &'a [&'a InitGraphNode<'a>; D],
+        dependents_links: &'a [ControlledModificationCell<Link<'a>>; D],
+        after: &'a [&'a InitGraphNode<'a>; A],
+        after_links: &'a [ControlledModificationCell<Link<'a>>; A],
+        before: &'a [&'a InitGraphNode<'a>; B],
+        before_links: &'a [ControlledModificationCell<Link<'a>>; B],
+    ) -> Self {
+        let state = if active {
+            State::Active
+        } else {
+            State::Inactive
+        };
+        let state_val = Self::state_to_u8(state);
+
+        Self {
+            name,
+            state: AtomicU8::new(state_val),
+            sequence: AtomicUsize::new(0),
+            elapsed: AtomicU64::new(0),
+            ordered: AtomicBool::new(false),
+            link: ControlledModificationCell::new(Link::empty()),
+            compile_time_desc: CompileTimeDesc::Task {
+                func,
+                requires,
+                dependents,
+                after,
+                before,
+            },
+            runtime_desc: ControlledModificationCell::new(RuntimeDesc {
+                requires_list: LinkList::new(),
+                after_list: LinkList::new(),
+
+                requires_links,
+                dependents_links,
+                after_links,
+                before_links,
+            }),
+        }
+    }
+
+    #[doc(hidden)]
+    pub const fn new_stage(name: &'a str, active: bool) -> Self {
+        let state = if active {
+            State::Active
+        } else {
+            State::Inactive
+        };
+        let state_val = Self::state_to_u8(state);
+
+        Self {
+            name,
+            state: AtomicU8::new(state_val),
+            sequence: AtomicUsize::new(0),
+            elapsed: AtomicU64::new(0),
+            ordered: AtomicBool::new(false),
+            link: ControlledModificationCell::new(Link::empty()),
+            compile_time_desc: CompileTimeDesc::Stage,
+            runtime_desc: ControlledModificationCell::new(RuntimeDesc {
+                requires_list: LinkList::new(),
+                after_list: LinkList::new(),
+
+                requires_links: &[],
+                dependents_links: &[],
+                after_links: &[],
+                before_links: &[],
+            }),
+        }
+    }
+
+    /// Returns the name of this [`InitGraphNode`].
+    pub const fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Returns `true` if this [`InitGraphNode`] is a stage rather than a task.
+    pub const fn is_stage(&self) -> bool {
+        matches!(self.compile_time_desc, CompileTimeDesc::Stage)
+    }
+
+    /// Returns the number of clock ticks this [`InitGraphNode`] took to execute, or [`None`] if it
+    /// has not been executed.
+    ///
+    /// The unit of the ticks is determined by the clock passed to [`set_clock()`].
+    pub fn elapsed_ticks(&self) -> Option<u64> {
+        (self.sequence.load(Ordering::Relaxed) != 0).then(|| self.elapsed.load(Ordering::Relaxed))
+    }
+
+    /// Activates this [`InitGraphNode`], thereby signalling that it is valid for processing.
+    pub fn activate(&self) {
+        self.set_state(State::Active);
+    }
+
+    /// Deactivates this [`InitGraphNode`], thereby signalling that it is not valid for processing.
+    pub fn deactivate(&self) {
+        self.set_state(State::Inactive);
+    }
+
+    /// Returns the current [`State`] of this [`InitGraphNode`].
+    fn state(&self) -> State {
+        let val = self.state.load(Ordering::Relaxed);
+        match val {
+            0 => State::Inactive,
+            1 => State::Active,
+            2 => State::Wanted,
+            3 => State::Done,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Sets the [`State`] of this [`InitGraphNode`].
+    fn set_state(&self, state: State) {
+        self.state
+            .store(Self::state_to_u8(state), Ordering::Relaxed);
+    }
+
+    /// Converts the provided [`State`] into its [`u8`] value.
+    const fn state_to_u8(state: State) -> u8 {
+        match state {
+            State::Inactive => 0,
+            State::Active => 1,
+            State::Wanted => 2,
+            State::Done => 3,
+        }
+    }
+
+    /// Executes the function associated with this [`InitGraphNode`] and records its position in
+    /// the execution order and its execution time.
+    fn execute(&self) {
+        let clock = *CLOCK.lock();
+        let start = clock();
+
+        match self.compile_time_desc {
+            CompileTimeDesc::Stage => {}
+            CompileTimeDesc::Task {
+                func,
+                requires: _,
+                dependents: _,
+                after: _,
+                before: _,
+            } => func(),
+        }
+
+        self.elapsed
+            .store(clock().wrapping_sub(start), Ordering::Relaxed);
+        self.sequence.store(
+            EXECUTED.fetch_add(1, Ordering::Relaxed) + 1,
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Returns an [`Iterator`] over the nodes that this [`InitGraphNode`] requires before
+    /// executing itself.
+    fn requires(&self) -> LinkIter<'a> {
+        self.runtime_desc.get().requires_list.iter()
+    }
+
+    /// Returns an [`Iterator`] over the nodes that this [`InitGraphNode`] wants to run after if in
+    /// the same [`run()`] execution.
+    fn after(&self) -> LinkIter<'a> {
+        self.runtime_desc.get().after_list.iter()
+    }
+}
+
+impl fmt::Debug for InitGraphNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InitGraphNode")
+            .field("name", &self.name)
+            .field("state", &self.state())
+            .field("stage", &self.is_stage())
+            .field("elapsed_ticks", &self.elapsed_ticks())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The information that represents the node at compile time.
+///
+/// This is what the runtime representation is derived from.
+#[derive(Debug)]
+enum CompileTimeDesc<'a> {
+    /// A sychronization point for other [`InitGraphNode`]s.
+    Stage,
+    /// A task or operation that should be performed.
+    Task {
+        /// The function that should be performed.
+        func: fn(),
+
+        /// [`InitGraphNode`]s that this [`InitGraphNode`] requires the successful execution of before
+        /// executing itself.
+        requires: &'a [&'a InitGraphNode<'a>],
+
+        /// [`InitGraphNode`]s that require the successful execution of this [`InitGraphNode`].
+        dependents: &'a [&'a InitGraphNode<'a>],
+
+        /// [`InitGraphNode`]s that this [`InitGraphNode`] will run after, if they exist in the
+        /// [`InitGraphNode`] tree.
+        after: &'a [&'a InitGraphNode<'a>],
+
+        /// [`InitGraphNode`]s that this [`InitGraphNode`] will run before, if they exist in the
+        /// [`InitGraphNode`] tree.
+        before: &'a [&'a InitGraphNode<'a>],
+    },
+}
+
+/// State for the [`InitGraphNode`] that is constructed at runtime.
+#[derive(Debug)]
+struct RuntimeDesc<'a> {
+    /// List of all nodes that this [`InitGraphNode`] requires before executing itself.
+    requires_list: LinkList<'a>,
+    /// List of all nodes that this [`InitGraphNode`] will run after, if they exist in the same
+    /// [`run()`] execution.
+    after_list: LinkList<'a>,
+
+    /// Storage for the [`Link`]s of the required nodes.
+    requires_links: &'a [ControlledModificationCell<Link<'a>>],
+    /// Storage for the [`Link`]s of the dependent nodes.
+    dependents_links: &'a [ControlledModificationCell<Link<'a>>],
+    /// Storage for the [`Link`]s of the nodes that this node runs after.
+    after_links: &'a [ControlledModificationCell<Link<'a>>],
+    /// Storage for the [`Link`]s of the nodes that this node runs before.
+    before_links: &'a [ControlledModificationCell<Link<'a>>],
+}
+
+/// The execution state of an [`InitGraphNode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    /// The [`InitGraphNode`] is not active and is not considered in initialization graph
+    /// calculations.
+    Inactive,
+    /// The [`InitGraphNode`] is active and considered in initialization graph calculations.
+    Active,
+    /// The [`InitGraphNode`] should be executed.
+    Wanted,
+    /// The [`InitGraphNode`] has been executed.
+    Done,
+}
+
+/// An [`Iterator`] over the [`InitGraphNode`]s that have been executed, in the order in which they
+/// were executed.
+#[derive(Debug)]
+pub struct Executed<'a> {
+    /// The [`InitGraphNode`]s to search.
+    nodes: &'a [InitGraphNode<'a>],
+    /// The position in the execution order of the next [`InitGraphNode`] to return.
+    sequence: usize,
+}
+
+impl<'a> Iterator for Executed<'a> {
+    type Item = &'a InitGraphNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self
+            .nodes
+            .iter()
+            .find(|node| node.sequence.load(Ordering::Relaxed) == self.sequence)?;
+        self.sequence += 1;
+        Some(node)
+    }
+}
+
+/// A single chain in the [`InitGraphNode`] [`LinkList`].
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Link<'a> {
+    /// The associated [`InitGraphNode`].
+    node: Option<&'a InitGraphNode<'a>>,
+    /// The next [`Link`] in the linked list.
+    next: Option<&'a ControlledModificationCell<Link<'a>>>,
+}
+
+impl<'a> Link<'a> {
+    /// Constructs an empty [`Link`].
+    #[doc(hidden)]
+    pub const fn empty() -> Self {
+        Self {
+            node: None,
+            next: None,
+        }
+    }
+
+    /// The [`InitGraphNode`] associated with this [`Link`].
+    const fn node(&self) -> &'a InitGraphNode<'a> {
+        self.node.unwrap()
+    }
+}
+
+/// A singly linked list of [`InitGraphNode`]s.
+#[derive(Debug)]
+struct LinkList<'a> {
+    /// The start of the [`LinkList`].
+    head: Option<&'a ControlledModificationCell<Link<'a>>>,
+    /// The last node of the [`LinkList`].
+    tail: Option<&'a ControlledModificationCell<Link<'a>>>,
+}
+
+impl<'a> LinkList<'a> {
+    /// Constructs an empty [`LinkList`].
+    const fn new() -> Self {
+        Self {
+            head: None,
+            tail: None,
+        }
+    }
+
+    /// Retrieves the first node from the start of this [`LinkList`].
+    fn pop_front(&mut self) -> Option<&'a ControlledModificationCell<Link<'a>>> {
+        let head = self.head?;
+        self.head = head.get().next;
+        if self.head.is_none() {
+            self.tail = None;
+        }
+
+        Some(head)
+    }
+
+    /// Places the provided `link` at the end of this [`LinkList`].
+    fn push_back(&mut self, link: &'a ControlledModificationCell<Link<'a>>) {
+        // SAFETY:
+        //
+        // The user does not have any other links active.
+        let link_mut = unsafe { link.get_mut() };
+        link_mut.next = None;
+
+        if self.head.is_none() {
+            self.head = Some(link);
+        }
+
+        if let Some(tail) = self.tail {
+            // SAFETY:
+            //
+            // The user does not have any other links active.
+            let tail_mut = unsafe { tail.get_mut() };
+            tail_mut.next = Some(link);
+        }
+
+        self.tail = Some(link);
+    }
+
+    /// Returns an [`Iterator`] over the [`InitGraphNode`]s in this [`LinkList`].
+    fn iter(&self) -> LinkIter<'a> {
+        LinkIter {
+            current_link: self.head,
+        }
+    }
+}
+
+/// An [`Iterator`] over a singly linked list of [`InitGraphNode`]s.
+struct LinkIter<'a> {
+    /// The current [`Link`] in the list.
+    current_link: Option<&'a ControlledModificationCell<Link<'a>>>,
+}
+
+impl<'a> Iterator for LinkIter<'a> {
+    type Item = &'a InitGraphNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current_link = self.current_link?.get();
+        self.current_link = current_link.next;
+        current_link.node
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! make_task {
+    (
+        $name:expr,
+        func = $func:expr,
+        active = $active:expr,
+        requires = [$($requires:expr),* $(,)?],
+        dependents = [$($dependents:expr),* $(,)?],
+        after = [$($after:expr),* $(,)?],
+        before = [$($before:expr),* $(,)?]
+    ) => {{
+        const REQUIRES_COUNT: usize = <[&str]>::len(&[$(stringify!($requires)),*]);
+        const DEPENDENTS_COUNT: usize = <[&str]>::len(&[$(stringify!($dependents)),*]);
+        const AFTER_COUNT: usize = <[&str]>::len(&[$(stringify!($after)),*]);
+        const BEFORE_COUNT: usize = <[&str]>::len(&[$(stringify!($before)),*]);
+
+        static REQUIRES: [&$crate::InitGraphNode; REQUIRES_COUNT] = [$($requires),*];
+        static DEPENDENTS: [&$crate::InitGraphNode; DEPENDENTS_COUNT] = [$($dependents),*];
+        static AFTER: [&$crate::InitGraphNode; AFTER_COUNT] = [$($after),*];
+        static BEFORE: [&$crate::InitGraphNode; BEFORE_COUNT] = [$($before),*];
+
+        static REQUIRES_LINKS: [$crate::sync::ControlledModificationCell<$crate::Link>;
+            REQUIRES_COUNT] =
+            [const { $crate::sync::ControlledModificationCell::new($crate::Link::empty()) };
+                REQUIRES_COUNT];
+        static DEPENDENTS_LINKS: [$crate::sync::ControlledModificationCell<$crate::Link>;
+            DEPENDENTS_COUNT] =
+            [const { $crate::sync::ControlledModificationCell::new($crate::Link::empty()) };
+                DEPENDENTS_COUNT];
+        static AFTER_LINKS: [$crate::sync::ControlledModificationCell<$crate::Link>;
+            AFTER_COUNT] =
+            [const { $crate::sync::ControlledModificationCell::new($crate::Link::empty()) };
+                AFTER_COUNT];
+        static BEFORE_LINKS: [$crate::sync::ControlledModificationCell<$crate::Link>;
+            BEFORE_COUNT] =
+            [const { $crate::sync::ControlledModificationCell::new($crate::Link::empty()) };
+                BEFORE_COUNT];
+
+        $crate::InitGraphNode::new_task(
+            $name,
+            $func,
+            $active,
+            &REQUIRES,
+            &REQUIRES_LINKS,
+            &DEPENDENTS,
+            &DEPENDENTS_LINKS,
+            &AFTER,
+            &AFTER_LINKS,
+            &BEFORE,
+            &BEFORE_LINKS,
+        )
+    }};
+}
+
+/// Resolves the name of an [`InitGraphNode`] declared using [`define_task!`] or [`define_stage!`]
+/// to a reference to that node.
+///
+/// The reference is resolved by the linker, so a name that does not belong to any node fails to
+/// link.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! node {
+    ($name:literal) => {{
+        unsafe extern "Rust" {
+            #[link_name = concat!("initgraph.", $name)]
+            static NODE: $crate::InitGraphNode<'static>;
+        }
+
+        // SAFETY:
+        //
+        // Symbols in the `initgraph.` namespace are only defined by `define_task!` and
+        // `define_stage!`, both of which define them as an `InitGraphNode`.
+        unsafe { &NODE }
+    }};
+}
+
+/// Defines a new [`InitGraphNode`] task.
+///
+/// Other nodes are referred to by name. A name that does not belong to any node, or a name that
+/// belongs to more than one node, fails to link.
+#[macro_export]
+macro_rules! define_task {
+    (
+        $static_name:ident,
+        $name:literal,
+        func = $func:expr,
+        inactive,
+        requires = [$($requires:literal),* $(,)?],
+        dependents = [$($dependents:literal),* $(,)?],
+        after = [$($after:literal),* $(,)?],
+        before = [$($before:literal),* $(,)?] $(,)?
+    ) => {
+        #[used]
+        #[unsafe(export_name = concat!("initgraph.", $name))]
+        #[unsafe(link_section = ".initgraph")]
+        static $static_name: $crate::InitGraphNode = $crate::make_task!(
+            $name,
+            func = $func,
+            active = false,
+            requires = [$($crate::node!($requires)),*],
+            dependents = [$($crate::node!($dependents)),*],
+            after = [$($crate::node!($after)),*],
+            before = [$($crate::node!($before)),*]
+        );
+    };
+    (
+        $static_name:ident,
+        $name:literal,
+        func = $func:expr,
+        active,
+        requires = [$($requires:literal),* $(,)?],
+        dependents = [$($dependents:literal),* $(,)?],
+        after = [$($after:literal),* $(,)?],
+        before = [$($before:literal),* $(,)?] $(,)?
+    ) => {
+        #[used]
+        #[unsafe(export_name = concat!("initgraph.", $name))]
+        #[unsafe(link_section = ".initgraph")]
+        static $static_name: $crate::InitGraphNode = $crate::make_task!(
+            $name,
+            func = $func,
+            active = true,
+            requires = [$($crate::node!($requires)),*],
+            dependents = [$($crate::node!($dependents)),*],
+            after = [$($crate::node!($after)),*],
+            before = [$($crate::node!($before)),*]
+        );
+    };
+}
+
+/// Defines a new [`InitGraphNode`] stage.
+///
+/// A name that belongs to more than one node fails to link.
+#[macro_export]
+macro_rules! define_stage {
+    ($static_name:ident, $name:literal, active) => {
+        #[used]
+        #[unsafe(export_name = concat!("initgraph.", $name))]
+        #[unsafe(link_section = ".initgraph")]
+        static $static_name: $crate::InitGraphNode = $crate::InitGraphNode::new_stage($name, true);
+    };
+    ($static_name:ident, $name:literal, inactive) => {
+        #[used]
+        #[unsafe(export_name = concat!("initgraph.", $name))]
+        #[unsafe(link_section = ".initgraph")]
+        static $static_name: $crate::InitGraphNode = $crate::InitGraphNode::new_stage($name, false);
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use core::sync::atomic::Ordering;
+
+    use super::{Executed, InitGraphNode, initialize, run_internal};
+
+    fn test() {}
+
+    #[test]
+    fn forward_links_resolve() {
+        static AB: [InitGraphNode; 2] = [
+            make_task!(
+                "A",
+                func = test,
+                active = true,
+                requires = [&AB[1]],
+                dependents = [],
+                after = [&AB[1]],
+                before = []
+            ),
+            make_task!(
+                "B",
+                func = test,
+                active = true,
+                requires = [],
+                dependents = [],
+                after = [],
+                before = []
+            ),
+        ];
+
+        initialize(&AB);
+
+        assert_eq!(AB[0].requires().count(), 1);
+        assert_eq!(AB[1].requires().count(), 0);
+
+        assert_eq!(AB[0].after().count(), 1);
+        assert_eq!(AB[1].after().count(), 0);
+    }
+
+    #[test]
+    fn reverse_links_resolve() {
+        static AB: [InitGraphNode; 2] = [
+            make_task!(
+                "A",
+                func = test,
+                active = true,
+                requires = [],
+                dependents = [&AB[1]],
+                after = [],
+                before = [&AB[1]]
+            ),
+            make_task!(
+                "B",
+                func = test,
+                active = true,
+                requires = [],
+                dependents = [],
+                after = [],
+                before = []
+            ),
+        ];
+
+        initialize(&AB);
+
+        assert_eq!(AB[0].requires().count(), 0);
+        assert_eq!(AB[1].requires().count(), 1);
+        assert!(AB[1].requires().all(|node| node.name() == "A"));
+
+        assert_eq!(AB[0].after().count(), 0);
+        assert_eq!(AB[1].after().count(), 1);
+        assert!(AB[1].after().all(|node| node.name() == "A"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn duplicate_nodes_panic() {
+        static AA: [InitGraphNode; 2] = [
+            make_task!(
+                "A",
+                func = test,
+                active = true,
+                requires = [],
+                dependents = [],
+                after = [],
+                before = []
+            ),
+            make_task!(
+                "A",
+                func = test,
+                active = true,
+                requires = [],
+                dependents = [],
+                after = [],
+                before = []
+            ),
+        ];
+
+        initialize(&AA);
+    }
+
+    #[test]
+    #[should_panic = "cycle in initialization graph"]
+    fn cycles_panic() {
+        static ABC: [InitGraphNode; 3] = [
+            make_task!(
+                "A",
+                func = test,
+                active = true,
+                requires = [&ABC[1]],
+                dependents = [],
+                after = [],
+                before = []
+            ),
+            make_task!(
+                "B",
+                func = test,
+                active = true,
+                requires = [],
+                dependents = [],
+                after = [&ABC[2]],
+                before = []
+            ),
+            make_task!(
+                "C",
+                func = test,
+                active = false,
+                requires = [],
+                dependents = [],
+                after = [&ABC[0]],
+                before = []
+            ),
+        ];
+
+        initialize(&ABC);
+    }
+
+    #[test]
+    fn execution_follows_constraints() {
+        static NODES: [InitGraphNode; 5] = [
+            make_task!(
+                "A",
+                func = test,
+                active = true,
+                requires = [&NODES[2]],
+                dependents = [&NODES[4]],
+                after = [&NODES[1]],
+                before = []
+            ),
+            make_task!(
+                "B",
+                func = test,
+                active = true,
+                requires = [],
+                dependents = [&NODES[4]],
+                after = [],
+                before = []
+            ),
+            make_task!(
+                "C",
+                func = test,
+                active = true,
+                requires = [],
+                dependents = [],
+                after = [],
+                before = []
+            ),
+            make_task!(
+                "D",
+                func = test,
+                active = false,
+                requires = [],
+                dependents = [&NODES[4]],
+                after = [],
+                before = []
+            ),
+            InitGraphNode::new_stage("E", true),
+        ];
+
+        initialize(&NODES);
+        run_internal(&NODES[4], &NODES);
+
+        let first = NODES
+            .iter()
+            .map(|node| node.sequence.load(Ordering::Relaxed))
+            .filter(|&sequence| sequence != 0)
+            .min()
+            .unwrap();
+        let order = Executed {
+            nodes: &NODES,
+            sequence: first,
+        }
+        .map(InitGraphNode::name);
+
+        assert!(order.eq(["B", "C", "A", "E"]));
+        assert_eq!(NODES[3].elapsed_ticks(), None);
+        assert_eq!(NODES[4].elapsed_ticks(), Some(0));
+    }
+}
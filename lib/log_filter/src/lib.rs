@@ -0,0 +1,523 @@
+//! Runtime configuration of log levels shared between the stub and `revm`.
+//!
+//! A [`Filter`] is described by a comma-separated list of directives, usually given as the value
+//! of a `log=` argument on the boot command line (e.g. `log=trace` or
+//! `log=memory:debug,switch:trace`):
+//!
+//! - `level` sets the level of modules that no other directive matches.
+//! - `module:level` sets the level of modules whose path contains `module` as a sequence of
+//!   complete path segments. For example, `memory` matches both `revm::memory` and
+//!   `revm_stub::platform::generic::memory::phys`, while `generic::memory` only matches the latter.
+//! - `path::to::module` enables every level for the matching modules. A bare module must contain
+//!   a `::` separator, so that a misspelled level is reported rather than taken as a module.
+//!
+//! If several directives match a module, the one naming the longest module wins. The recognized
+//! levels are `trace`, `debug`, `info`, `warn`, `error`, and `off`.
+//!
+//! The active [`Filter`] is installed with [`set_filter()`] and consulted with [`enabled()`],
+//! which rejects messages below the most verbose level that any directive enables with a single
+//! atomic load, before the message is formatted.
+
+#![no_std]
+
+use core::{
+    error, fmt,
+    str::FromStr,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use sync::ControlledModificationCell;
+
+/// The maximum number of module directives a [`Filter`] can hold.
+pub const MAX_DIRECTIVES: usize = 16;
+/// The maximum length, in bytes, of the module named by a directive.
+pub const MAX_MODULE_LENGTH: usize = 48;
+
+/// The active [`Filter`], which lets every message through until [`set_filter()`] is called.
+static FILTER: ControlledModificationCell<Filter> =
+    ControlledModificationCell::new(Filter::new(LevelFilter::Trace));
+/// The most verbose [`LevelFilter`] enabled by the active [`Filter`].
+static MOST_VERBOSE: AtomicU8 = AtomicU8::new(LevelFilter::Trace as u8);
+
+/// Installs `filter` as the active [`Filter`].
+///
+/// # Safety
+///
+/// There must be zero overlapping calls to [`set_filter()`], [`filter()`], or [`enabled()`].
+pub unsafe fn set_filter(filter: Filter) {
+    MOST_VERBOSE.store(filter.most_verbose() as u8, Ordering::Relaxed);
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that [`FILTER`] is not accessed for the duration of
+    // this operation.
+    unsafe { *FILTER.get_mut() = filter }
+}
+
+/// Returns the active [`Filter`].
+pub fn filter() -> &'static Filter {
+    FILTER.get()
+}
+
+/// Returns `true` if a message at `level` from the module at `module_path` should be logged
+/// according to the active [`Filter`].
+#[inline]
+pub fn enabled(level: LogLevel, module_path: &str) -> bool {
+    if (level as u8) < MOST_VERBOSE.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    FILTER.get().enabled(level, module_path)
+}
+
+/// Various levels to determine the priority of information.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    /// Designates very low priority information.
+    Trace,
+    /// Designates lower priority information.
+    Debug,
+    /// Designates informatory logs.
+    Info,
+    /// Designates hazardous logs.
+    Warn,
+    /// Designates very serious logs.
+    Error,
+}
+
+/// The least severe [`LogLevel`] that is logged, or [`LevelFilter::Off`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LevelFilter {
+    /// Every message is logged.
+    Trace,
+    /// Messages at [`LogLevel::Debug`] and above are logged.
+    Debug,
+    /// Messages at [`LogLevel::Info`] and above are logged.
+    Info,
+    /// Messages at [`LogLevel::Warn`] and above are logged.
+    Warn,
+    /// Only messages at [`LogLevel::Error`] are logged.
+    Error,
+    /// No messages are logged.
+    Off,
+}
+
+impl LevelFilter {
+    /// Returns `true` if messages at `level` pass this [`LevelFilter`].
+    pub const fn enables(self, level: LogLevel) -> bool {
+        level as u8 >= self as u8
+    }
+
+    /// Returns the name of this [`LevelFilter`] as it appears in a directive.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Trace => "trace",
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+            Self::Off => "off",
+        }
+    }
+}
+
+impl FromStr for LevelFilter {
+    type Err = ParseFilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Self::Trace,
+            Self::Debug,
+            Self::Info,
+            Self::Warn,
+            Self::Error,
+            Self::Off,
+        ]
+        .into_iter()
+        .find(|level| level.as_str().eq_ignore_ascii_case(s))
+        .ok_or(ParseFilterError::InvalidLevel)
+    }
+}
+
+impl fmt::Display for LevelFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// A set of directives that select the [`LevelFilter`] of each module.
+#[derive(Clone, Copy)]
+pub struct Filter {
+    /// The [`LevelFilter`] of modules that no directive matches.
+    default: LevelFilter,
+    /// Storage for the module directives.
+    directives: [Directive; MAX_DIRECTIVES],
+    /// The number of module directives in use.
+    count: usize,
+}
+
+impl Filter {
+    /// Creates a new [`Filter`] that applies `default` to every module.
+    pub const fn new(default: LevelFilter) -> Self {
+        Self {
+            default,
+            directives: [Directive::EMPTY; MAX_DIRECTIVES],
+            count: 0,
+        }
+    }
+
+    /// Parses the comma-separated directives in `spec` into a [`Filter`] whose level defaults to
+    /// `default` for modules that no directive matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseFilterError`] describing the first invalid directive in `spec`.
+    pub fn parse(spec: &str, default: LevelFilter) -> Result<Self, ParseFilterError> {
+        let mut filter = Self::new(default);
+        filter.add_directives(spec)?;
+        Ok(filter)
+    }
+
+    /// Parses the directives of every `log=` argument among the whitespace-separated arguments of
+    /// `command_line` into a [`Filter`] whose level defaults to `default` for modules that no
+    /// directive matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseFilterError`] describing the first invalid directive.
+    pub fn from_command_line(
+        command_line: &str,
+        default: LevelFilter,
+    ) -> Result<Self, ParseFilterError> {
+        let mut filter = Self::new(default);
+        for spec in command_line
+            .split_whitespace()
+            .filter_map(|argument| argument.strip_prefix("log="))
+        {
+            filter.add_directives(spec)?;
+        }
+
+        Ok(filter)
+    }
+
+    /// Adds the comma-separated directives in `spec` to this [`Filter`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseFilterError`] describing the first invalid directive in `spec`. The
+    /// directives that precede it remain applied.
+    pub fn add_directives(&mut self, spec: &str) -> Result<(), ParseFilterError> {
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            // A `:` that is part of a `::` path separator does not separate a level.
+            match directive
+                .rsplit_once(':')
+                .filter(|(module, _)| !module.ends_with(':'))
+            {
+                Some((module, level)) => self.add_directive(Some(module), level.parse()?)?,
+                // Only a token containing a path separator names a module, so that a misspelled
+                // level is reported instead of enabling every level for a module of that name.
+                None if directive.contains("::") => {
+                    self.add_directive(Some(directive), LevelFilter::Trace)?;
+                }
+                None => self.add_directive(None, directive.parse()?)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the [`LevelFilter`] of the modules matching `module`, or of the modules that no
+    /// directive matches if `module` is [`None`].
+    ///
+    /// # Errors
+    ///
+    /// - [`ParseFilterError::InvalidModule`]: Returned if `module` is empty or contains
+    ///   characters that cannot appear in a module path.
+    /// - [`ParseFilterError::ModuleTooLong`]: Returned if `module` is longer than
+    ///   [`MAX_MODULE_LENGTH`] bytes.
+    /// - [`ParseFilterError::TooManyDirectives`]: Returned if the [`Filter`] already holds
+    ///   [`MAX_DIRECTIVES`] module directives.
+    pub fn add_directive(
+        &mut self,
+        module: Option<&str>,
+        level: LevelFilter,
+    ) -> Result<(), ParseFilterError> {
+        let Some(module) = module else {
+            self.default = level;
+            return Ok(());
+        };
+
+        let valid = !module.is_empty()
+            && !module.starts_with(':')
+            && !module.ends_with(':')
+            && module
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_' || byte == b':');
+        if !valid {
+            return Err(ParseFilterError::InvalidModule);
+        }
+        if module.len() > MAX_MODULE_LENGTH {
+            return Err(ParseFilterError::ModuleTooLong);
+        }
+
+        // A later directive for the same module replaces the earlier one.
+        if let Some(directive) = self.directives[..self.count]
+            .iter_mut()
+            .find(|directive| directive.module() == module)
+        {
+            directive.level = level;
+            return Ok(());
+        }
+
+        let Some(directive) = self.directives.get_mut(self.count) else {
+            return Err(ParseFilterError::TooManyDirectives);
+        };
+        directive.module[..module.len()].copy_from_slice(module.as_bytes());
+        directive.module_length = module.len();
+        directive.level = level;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Returns the [`LevelFilter`] of modules that no directive matches.
+    pub const fn default_level(&self) -> LevelFilter {
+        self.default
+    }
+
+    /// Returns the [`LevelFilter`] that applies to the module at `module_path`.
+    pub fn level_for(&self, module_path: &str) -> LevelFilter {
+        self.directives[..self.count]
+            .iter()
+            .filter(|directive| directive.matches(module_path))
+            .max_by_key(|directive| directive.module_length)
+            .map_or(self.default, |directive| directive.level)
+    }
+
+    /// Returns `true` if a message at `level` from the module at `module_path` passes this
+    /// [`Filter`].
+    pub fn enabled(&self, level: LogLevel, module_path: &str) -> bool {
+        if self.count == 0 {
+            return self.default.enables(level);
+        }
+
+        self.level_for(module_path).enables(level)
+    }
+
+    /// Returns the most verbose [`LevelFilter`] that this [`Filter`] applies to any module.
+    pub fn most_verbose(&self) -> LevelFilter {
+        self.directives[..self.count]
+            .iter()
+            .map(|directive| directive.level)
+            .fold(self.default, LevelFilter::min)
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.default)?;
+        for directive in &self.directives[..self.count] {
+            write!(f, ",{}:{}", directive.module(), directive.level)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Filter");
+        debug.field("default", &self.default);
+        debug.field("directives", &DirectiveList(&self.directives[..self.count]));
+        debug.finish()
+    }
+}
+
+/// Helper to format a list of [`Directive`]s.
+struct DirectiveList<'a>(&'a [Directive]);
+
+impl fmt::Debug for DirectiveList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.0
+                    .iter()
+                    .map(|directive| (directive.module(), directive.level)),
+            )
+            .finish()
+    }
+}
+
+/// A directive that sets the [`LevelFilter`] of the modules matching a module name.
+#[derive(Clone, Copy)]
+struct Directive {
+    /// Storage for the name of the module.
+    module: [u8; MAX_MODULE_LENGTH],
+    /// The length, in bytes, of the name of the module.
+    module_length: usize,
+    /// The [`LevelFilter`] of the matching modules.
+    level: LevelFilter,
+}
+
+impl Directive {
+    /// An unused [`Directive`].
+    const EMPTY: Self = Self {
+        module: [0; MAX_MODULE_LENGTH],
+        module_length: 0,
+        level: LevelFilter::Off,
+    };
+
+    /// Returns the name of the module that this [`Directive`] matches.
+    fn module(&self) -> &str {
+        // Module names are validated to be ASCII when the directive is added.
+        core::str::from_utf8(&self.module[..self.module_length]).unwrap_or_default()
+    }
+
+    /// Returns `true` if `module_path` contains the module of this [`Directive`] as a sequence of
+    /// complete path segments.
+    fn matches(&self, module_path: &str) -> bool {
+        let module = self.module();
+
+        let mut remaining = module_path;
+        loop {
+            if let Some(after) = remaining.strip_prefix(module)
+                && (after.is_empty() || after.starts_with("::"))
+            {
+                return true;
+            }
+
+            match remaining.split_once("::") {
+                Some((_, next)) => remaining = next,
+                None => return false,
+            }
+        }
+    }
+}
+
+/// Various errors that can occur while parsing a [`Filter`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ParseFilterError {
+    /// A directive named an unknown level.
+    InvalidLevel,
+    /// A directive named an empty or malformed module.
+    InvalidModule,
+    /// A directive named a module longer than [`MAX_MODULE_LENGTH`] bytes.
+    ModuleTooLong,
+    /// More than [`MAX_DIRECTIVES`] module directives were given.
+    TooManyDirectives,
+}
+
+impl fmt::Display for ParseFilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLevel => f.pad("invalid log level"),
+            Self::InvalidModule => f.pad("invalid module name"),
+            Self::ModuleTooLong => f.pad("module name is too long"),
+            Self::TooManyDirectives => f.pad("too many log directives"),
+        }
+    }
+}
+
+impl error::Error for ParseFilterError {}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use std::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn bare_level_sets_default() {
+        let filter = Filter::parse("trace", LevelFilter::Info).unwrap();
+        assert_eq!(filter.default_level(), LevelFilter::Trace);
+        assert!(filter.enabled(LogLevel::Trace, "revm::memory"));
+
+        let filter = Filter::parse("WARN", LevelFilter::Info).unwrap();
+        assert!(!filter.enabled(LogLevel::Info, "revm::memory"));
+        assert!(filter.enabled(LogLevel::Error, "revm::memory"));
+    }
+
+    #[test]
+    fn module_directives_match_path_segments() {
+        let filter = Filter::parse("memory:debug,switch:trace", LevelFilter::Info).unwrap();
+
+        assert!(filter.enabled(LogLevel::Debug, "revm::memory"));
+        assert!(filter.enabled(
+            LogLevel::Debug,
+            "revm_stub::platform::generic::memory::phys"
+        ));
+        assert!(!filter.enabled(LogLevel::Trace, "revm::memory"));
+        assert!(filter.enabled(LogLevel::Trace, "revm_stub::arch::generic::switch::setup"));
+        assert!(!filter.enabled(LogLevel::Debug, "revm_stub::platform::uefi"));
+        assert!(!filter.enabled(LogLevel::Debug, "revm::memory_map"));
+        assert_eq!(filter.most_verbose(), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn bare_module_path_enables_trace() {
+        let filter = Filter::parse("generic::memory", LevelFilter::Info).unwrap();
+
+        assert_eq!(
+            filter.level_for("revm_stub::platform::generic::memory::phys"),
+            LevelFilter::Trace
+        );
+        assert_eq!(filter.level_for("revm::memory"), LevelFilter::Info);
+    }
+
+    #[test]
+    fn longest_module_wins() {
+        let filter = Filter::parse(
+            "off,memory:info,generic::memory:error,memory:trace",
+            LevelFilter::Info,
+        )
+        .unwrap();
+
+        assert_eq!(filter.level_for("revm::memory"), LevelFilter::Trace);
+        assert_eq!(
+            filter.level_for("revm_stub::platform::generic::memory::phys"),
+            LevelFilter::Error
+        );
+        assert_eq!(filter.level_for("revm::time"), LevelFilter::Off);
+        assert_eq!(filter.to_string(), "off,memory:trace,generic::memory:error");
+    }
+
+    #[test]
+    fn command_line_arguments() {
+        let filter = Filter::from_command_line(
+            "console=ttyS0 log=info log=switch:trace quiet",
+            LevelFilter::Debug,
+        )
+        .unwrap();
+        assert_eq!(filter.default_level(), LevelFilter::Info);
+        assert_eq!(
+            filter.level_for("revm_stub::arch::switch"),
+            LevelFilter::Trace
+        );
+
+        assert_eq!(
+            Filter::from_command_line("log=memory:loud", LevelFilter::Debug).err(),
+            Some(ParseFilterError::InvalidLevel)
+        );
+        assert_eq!(
+            Filter::parse("a-b:info", LevelFilter::Debug).err(),
+            Some(ParseFilterError::InvalidModule)
+        );
+    }
+
+    #[test]
+    fn bare_tokens_without_path_separator_are_levels() {
+        assert_eq!(
+            Filter::parse("dbug", LevelFilter::Info).err(),
+            Some(ParseFilterError::InvalidLevel)
+        );
+        assert_eq!(
+            Filter::parse("memory", LevelFilter::Info).err(),
+            Some(ParseFilterError::InvalidLevel)
+        );
+        assert_eq!(
+            Filter::parse("a-b::c", LevelFilter::Info).err(),
+            Some(ParseFilterError::InvalidModule)
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Definitions related to UEFI Graphics Output protocol.
+
+use crate::{
+    data_type::{Guid, Status},
+    guid,
+};
+
+/// Provides a basic abstraction to set video modes and copy pixels to and from the graphics
+/// controller's frame buffer.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash)]
+pub struct GraphicsOutputProtocol {
+    /// Returns information for an available graphics mode that the graphics device and the set of
+    /// active video output devices supports.
+    pub query_mode: QueryMode,
+    /// Sets the video device into the specified mode and clears the visible portions of the
+    /// output display to black.
+    pub set_mode: SetMode,
+    /// Software abstraction to draw on the video device's frame buffer.
+    pub blt: Blt,
+    /// The current mode of the protocol.
+    pub mode: *mut GraphicsOutputProtocolMode,
+}
+
+impl GraphicsOutputProtocol {
+    /// The [`Guid`] associated with the [`GraphicsOutputProtocol`].
+    pub const GUID: Guid = guid!("9042a9de-23dc-4a38-96fb-7aded080516a");
+}
+
+/// Returns information for an available graphics mode that the graphics device and the set of
+/// active video output devices supports.
+///
+/// The buffer written to `info` is allocated by the callee and must be freed by the caller.
+pub type QueryMode = unsafe extern "efiapi" fn(
+    this: *mut GraphicsOutputProtocol,
+    mode_number: u32,
+    size_of_info: *mut usize,
+    info: *mut *mut GraphicsOutputModeInformation,
+) -> Status;
+/// Sets the video device into the specified mode and clears the visible portions of the output
+/// display to black.
+pub type SetMode =
+    unsafe extern "efiapi" fn(this: *mut GraphicsOutputProtocol, mode_number: u32) -> Status;
+/// Draws, copies, or reads rectangles of pixels to and from the video device's frame buffer.
+pub type Blt = unsafe extern "efiapi" fn(
+    this: *mut GraphicsOutputProtocol,
+    blt_buffer: *mut BltPixel,
+    blt_operation: BltOperation,
+    source_x: usize,
+    source_y: usize,
+    destination_x: usize,
+    destination_y: usize,
+    width: usize,
+    height: usize,
+    delta: usize,
+) -> Status;
+
+/// Information about the current mode of the [`GraphicsOutputProtocol`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct GraphicsOutputProtocolMode {
+    /// The number of modes supported by [`GraphicsOutputProtocol::query_mode`] and
+    /// [`GraphicsOutputProtocol::set_mode`].
+    pub max_mode: u32,
+    /// The current mode of the graphics device.
+    pub mode: u32,
+    /// Pointer to a read-only [`GraphicsOutputModeInformation`] describing the current mode.
+    pub info: *mut GraphicsOutputModeInformation,
+    /// The size, in bytes, of the structure pointed to by [`GraphicsOutputProtocolMode::info`].
+    pub size_of_info: usize,
+    /// The physical address at the start of the linear frame buffer.
+    pub frame_buffer_base: u64,
+    /// The size, in bytes, of the linear frame buffer.
+    pub frame_buffer_size: usize,
+}
+
+/// Description of a graphics mode.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct GraphicsOutputModeInformation {
+    /// The version of this data structure.
+    pub version: u32,
+    /// The size of the video screen in pixels in the X dimension.
+    pub horizontal_resolution: u32,
+    /// The size of the video screen in pixels in the Y dimension.
+    pub vertical_resolution: u32,
+    /// The physical format of a pixel.
+    pub pixel_format: GraphicsPixelFormat,
+    /// The layout of a pixel when [`GraphicsOutputModeInformation::pixel_format`] is
+    /// [`GraphicsPixelFormat::BIT_MASK`].
+    pub pixel_information: PixelBitmask,
+    /// The number of pixels per video memory line.
+    pub pixels_per_scan_line: u32,
+}
+
+/// The physical format of a pixel.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct GraphicsPixelFormat(pub u32);
+
+impl GraphicsPixelFormat {
+    /// A pixel is 32 bits. Byte 0 represents red, byte 1 green, byte 2 blue, and byte 3 is
+    /// reserved.
+    pub const RED_GREEN_BLUE_RESERVED_8_BIT_PER_COLOR: Self = Self(0);
+    /// A pixel is 32 bits. Byte 0 represents blue, byte 1 green, byte 2 red, and byte 3 is
+    /// reserved.
+    pub const BLUE_GREEN_RED_RESERVED_8_BIT_PER_COLOR: Self = Self(1);
+    /// The pixel layout is defined by [`GraphicsOutputModeInformation::pixel_information`].
+    pub const BIT_MASK: Self = Self(2);
+    /// The graphics device does not support a physical frame buffer.
+    pub const BLT_ONLY: Self = Self(3);
+}
+
+/// The bits of a pixel used by each color component.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct PixelBitmask {
+    /// The bits used by the red component.
+    pub red_mask: u32,
+    /// The bits used by the green component.
+    pub green_mask: u32,
+    /// The bits used by the blue component.
+    pub blue_mask: u32,
+    /// The bits that are reserved.
+    pub reserved_mask: u32,
+}
+
+/// A pixel as used by [`GraphicsOutputProtocol::blt`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct BltPixel {
+    /// The blue component of the pixel.
+    pub blue: u8,
+    /// The green component of the pixel.
+    pub green: u8,
+    /// The red component of the pixel.
+    pub red: u8,
+    /// Reserved.
+    pub reserved: u8,
+}
+
+/// The operation performed by [`GraphicsOutputProtocol::blt`].
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct BltOperation(pub u32);
+
+impl BltOperation {
+    /// Writes the first pixel of the buffer to every pixel of the destination rectangle.
+    pub const VIDEO_FILL: Self = Self(0);
+    /// Reads the source rectangle of the screen into the buffer.
+    pub const VIDEO_TO_BLT_BUFFER: Self = Self(1);
+    /// Writes the source rectangle of the buffer to the screen.
+    pub const BUFFER_TO_VIDEO: Self = Self(2);
+    /// Copies the source rectangle of the screen to the destination rectangle of the screen.
+    pub const VIDEO_TO_VIDEO: Self = Self(3);
+}
@@ -1,3 +1,4 @@
 //! Definitions of UEFI Console Support Protocols.
 
+pub mod graphics_output;
 pub mod simple_text;
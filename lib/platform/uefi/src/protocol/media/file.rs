@@ -0,0 +1,164 @@
+//! Definitions related to UEFI File protocol.
+
+use core::ffi;
+
+use crate::{
+    data_type::{Char16, Guid, Status},
+    guid,
+    table::runtime::Time,
+};
+
+/// Provides file based access to supported file systems.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash)]
+pub struct FileProtocol {
+    /// The revision of the [`FileProtocol`].
+    ///
+    /// All future revisions must be backwards compatible.
+    pub revision: u64,
+    /// Opens a new file relative to the source file's location.
+    pub open: Open,
+    /// Closes a specified file handle.
+    pub close: Close,
+    /// Closes and deletes a file.
+    pub delete: Delete,
+    /// Reads data from a file.
+    pub read: Read,
+    /// Writes data to a file.
+    pub write: Write,
+    /// Returns a file's current position.
+    pub get_position: GetPosition,
+    /// Sets a file's current position.
+    pub set_position: SetPosition,
+    /// Returns information about a file.
+    pub get_info: GetInfo,
+    /// Sets information about a file.
+    pub set_info: SetInfo,
+    /// Flushes all modified data associated with a file to a device.
+    pub flush: Flush,
+}
+
+impl FileProtocol {
+    /// The first revision of the [`FileProtocol`].
+    pub const REVISION_1: u64 = 0x0001_0000;
+    /// The second revision of the [`FileProtocol`].
+    pub const REVISION_2: u64 = 0x0002_0000;
+}
+
+/// Opens the file named by `file_name` relative to `this` and writes a handle to the opened file
+/// to `new_handle`.
+pub type Open = unsafe extern "efiapi" fn(
+    this: *mut FileProtocol,
+    new_handle: *mut *mut FileProtocol,
+    file_name: *const Char16,
+    open_mode: OpenMode,
+    attributes: FileAttributes,
+) -> Status;
+/// Closes the file handle `this`.
+pub type Close = unsafe extern "efiapi" fn(this: *mut FileProtocol) -> Status;
+/// Closes and deletes the file `this`.
+pub type Delete = unsafe extern "efiapi" fn(this: *mut FileProtocol) -> Status;
+/// Reads up to `buffer_size` bytes from the current position of the file into `buffer`.
+///
+/// On return, `buffer_size` contains the number of bytes read.
+pub type Read = unsafe extern "efiapi" fn(
+    this: *mut FileProtocol,
+    buffer_size: *mut usize,
+    buffer: *mut ffi::c_void,
+) -> Status;
+/// Writes `buffer_size` bytes from `buffer` to the current position of the file.
+///
+/// On return, `buffer_size` contains the number of bytes written.
+pub type Write = unsafe extern "efiapi" fn(
+    this: *mut FileProtocol,
+    buffer_size: *mut usize,
+    buffer: *mut ffi::c_void,
+) -> Status;
+/// Writes the current position of the file to `position`.
+pub type GetPosition =
+    unsafe extern "efiapi" fn(this: *mut FileProtocol, position: *mut u64) -> Status;
+/// Sets the current position of the file to `position`.
+pub type SetPosition = unsafe extern "efiapi" fn(this: *mut FileProtocol, position: u64) -> Status;
+/// Writes the information of type `information_type` about the file to `buffer`.
+///
+/// If `buffer_size` is too small, [`Status::BUFFER_TOO_SMALL`] is returned and `buffer_size`
+/// contains the size of the buffer required.
+pub type GetInfo = unsafe extern "efiapi" fn(
+    this: *mut FileProtocol,
+    information_type: *const Guid,
+    buffer_size: *mut usize,
+    buffer: *mut ffi::c_void,
+) -> Status;
+/// Sets the information of type `information_type` about the file to the contents of `buffer`.
+pub type SetInfo = unsafe extern "efiapi" fn(
+    this: *mut FileProtocol,
+    information_type: *const Guid,
+    buffer_size: usize,
+    buffer: *mut ffi::c_void,
+) -> Status;
+/// Flushes all modified data associated with the file to the device.
+pub type Flush = unsafe extern "efiapi" fn(this: *mut FileProtocol) -> Status;
+
+/// The mode in which a file is opened.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct OpenMode(pub u64);
+
+impl OpenMode {
+    /// The file is opened for reading.
+    pub const READ: Self = Self(0x0000_0000_0000_0001);
+    /// The file is opened for writing.
+    pub const WRITE: Self = Self(0x0000_0000_0000_0002);
+    /// The file is created if it does not exist.
+    pub const CREATE: Self = Self(0x8000_0000_0000_0000);
+}
+
+/// The attributes of a file.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct FileAttributes(pub u64);
+
+impl FileAttributes {
+    /// The file is read-only.
+    pub const READ_ONLY: Self = Self(0x0000_0000_0000_0001);
+    /// The file is hidden.
+    pub const HIDDEN: Self = Self(0x0000_0000_0000_0002);
+    /// The file is a system file.
+    pub const SYSTEM: Self = Self(0x0000_0000_0000_0004);
+    /// Reserved.
+    pub const RESERVED: Self = Self(0x0000_0000_0000_0008);
+    /// The file is a directory.
+    pub const DIRECTORY: Self = Self(0x0000_0000_0000_0010);
+    /// The file should be archived.
+    pub const ARCHIVE: Self = Self(0x0000_0000_0000_0020);
+    /// Mask over all valid attributes.
+    pub const VALID: Self = Self(0x0000_0000_0000_0037);
+}
+
+/// Generic information about a file.
+///
+/// The null-terminated name of the file immediately follows this structure.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct FileInfo {
+    /// The size, in bytes, of the [`FileInfo`] structure, including the file name.
+    pub size: u64,
+    /// The size, in bytes, of the file.
+    pub file_size: u64,
+    /// The amount of physical space, in bytes, the file consumes on the file system volume.
+    pub physical_size: u64,
+    /// The time at which the file was created.
+    pub create_time: Time,
+    /// The time at which the file was last accessed.
+    pub last_access_time: Time,
+    /// The time at which the file's contents were last modified.
+    pub modification_time: Time,
+    /// The attributes of the file.
+    pub attribute: FileAttributes,
+}
+
+impl FileInfo {
+    /// The [`Guid`] identifying [`FileInfo`] requests to [`FileProtocol::get_info`] and
+    /// [`FileProtocol::set_info`].
+    pub const GUID: Guid = guid!("09576e92-6d3f-11d2-8e39-00a0c969723b");
+}
@@ -0,0 +1,4 @@
+//! Definitions of UEFI Media Access Protocols.
+
+pub mod file;
+pub mod simple_file_system;
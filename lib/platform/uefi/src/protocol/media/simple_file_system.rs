@@ -0,0 +1,33 @@
+//! Definitions related to UEFI Simple File System protocol.
+
+use crate::{
+    data_type::{Guid, Status},
+    guid,
+    protocol::media::file::FileProtocol,
+};
+
+/// Provides a minimal interface for file-type access to a device.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash)]
+pub struct SimpleFileSystemProtocol {
+    /// The revision of the [`SimpleFileSystemProtocol`].
+    ///
+    /// All future revisions must be backwards compatible.
+    pub revision: u64,
+    /// Opens the root directory on a volume.
+    pub open_volume: OpenVolume,
+}
+
+impl SimpleFileSystemProtocol {
+    /// The [`Guid`] associated with the [`SimpleFileSystemProtocol`].
+    pub const GUID: Guid = guid!("964e5b22-6459-11d2-8e39-00a0c969723b");
+
+    /// The revision of the [`SimpleFileSystemProtocol`] described by this structure.
+    pub const REVISION: u64 = 0x0001_0000;
+}
+
+/// Opens the root directory on a volume and writes a [`FileProtocol`] handle to it to `root`.
+pub type OpenVolume = unsafe extern "efiapi" fn(
+    this: *mut SimpleFileSystemProtocol,
+    root: *mut *mut FileProtocol,
+) -> Status;
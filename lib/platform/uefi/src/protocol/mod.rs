@@ -6,4 +6,5 @@ pub mod device_path;
 pub mod device_path_utilities;
 pub mod loaded_image;
 pub mod loaded_image_device_path;
+pub mod media;
 pub mod mp;
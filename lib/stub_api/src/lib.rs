@@ -26,11 +26,16 @@ use core::{
 
 pub mod aarch64;
 pub mod i686;
+pub mod log_ring;
 pub mod raw;
 pub mod x86_64;
 
-pub use GenericTableV0 as GenericTable;
+pub use CpuDescriptorV0 as CpuDescriptor;
+pub use FramebufferV0 as Framebuffer;
+pub use GenericTableV5 as GenericTable;
 pub use HeaderV0 as Header;
+pub use MemoryDescriptorV1 as MemoryDescriptor;
+pub use ModuleV0 as Module;
 
 /// The header for the REVM protocol table.
 ///
@@ -89,7 +94,7 @@ pub struct GenericTableV0 {
     /// Various flags concerning the relationship between the bootloader and the executable.
     pub flags: Flags,
 
-    /// A UTF-8 command line.
+    /// A NUL-terminated UTF-8 command line.
     pub command_line: *const u8,
 
     /// Writes the UTF-8 string described by `string` and `length` to some logging mechanism
@@ -131,6 +136,10 @@ pub struct GenericTableV0 {
     /// Maps the physically contiguous region starting at `physical_address` and extending for
     /// `count` frames into the executable's address space starting at `virtual_address` and
     /// extending for `count` pages.
+    ///
+    /// The memory attributes of the mapping are selected by [`MapFlags::mapping_type`]. Requests
+    /// for a [`MappingType`] that is not reported in [`GenericTableV4::supported_mapping_types`]
+    /// fail with [`Status::NOT_SUPPORTED`].
     pub map: unsafe extern "C" fn(
         physical_address: u64,
         virtual_address: *mut usize,
@@ -158,7 +167,7 @@ pub struct GenericTableV0 {
     pub run_on_all_processors: unsafe extern "C" fn(procedure: Procedure, arg: *mut ()) -> Status,
 }
 
-/// The function prototype required for [`GenericTable::run_on_all_processors`].
+/// The function prototype required for [`GenericTableV0::run_on_all_processors`].
 pub type Procedure = extern "C" fn(cpu_id: u64, arg: *mut ());
 
 impl GenericTableV0 {
@@ -166,6 +175,274 @@ impl GenericTableV0 {
     pub const VERSION: u64 = 0;
 }
 
+/// Table providing information and functionality that is cross-architectural in nature.
+///
+/// All functions must be called on the main CPU.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct GenericTableV1 {
+    /// The fields of the [`GenericTableV0`] structure.
+    pub v0: GenericTableV0,
+
+    /// The size, in bytes, of the [`Framebuffer`] pointed to by [`GenericTableV1::framebuffer`].
+    pub framebuffer_descriptor_size: u64,
+    /// The version associated with the layout of [`Framebuffer`].
+    pub framebuffer_descriptor_version: u64,
+    /// A pointer to the [`Framebuffer`] describing the primary graphical output device.
+    ///
+    /// This is NULL if the bootloader did not find a linear framebuffer.
+    pub framebuffer: *const Framebuffer,
+}
+
+impl GenericTableV1 {
+    /// The version of the [`GenericTable`] with which this [`GenericTable`] is associated.
+    pub const VERSION: u64 = 1;
+}
+
+/// Table providing information and functionality that is cross-architectural in nature.
+///
+/// All functions must be called on the main CPU.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct GenericTableV2 {
+    /// The fields of the [`GenericTableV1`] structure.
+    pub v1: GenericTableV1,
+
+    /// The size, in bytes, of each [`CpuDescriptor`] in the array pointed to by
+    /// [`GenericTableV2::cpus`].
+    pub cpu_descriptor_size: u64,
+    /// The version associated with the layout of [`CpuDescriptor`].
+    pub cpu_descriptor_version: u64,
+    /// A pointer to an array of [`GenericTableV0::cpu_count`] [`CpuDescriptor`]s.
+    ///
+    /// The descriptor at index `n` describes the CPU that receives `n` as its `cpu_id` in
+    /// [`GenericTableV0::run_on_all_processors`].
+    pub cpus: *const CpuDescriptor,
+}
+
+impl GenericTableV2 {
+    /// The version of the [`GenericTable`] with which this [`GenericTable`] is associated.
+    pub const VERSION: u64 = 2;
+}
+
+/// Table providing information and functionality that is cross-architectural in nature.
+///
+/// All functions must be called on the main CPU.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct GenericTableV3 {
+    /// The fields of the [`GenericTableV2`] structure.
+    pub v2: GenericTableV2,
+
+    /// The number of [`Module`]s in the array pointed to by [`GenericTableV3::modules`].
+    pub module_count: u64,
+    /// The size, in bytes, of each [`Module`] in the array pointed to by
+    /// [`GenericTableV3::modules`].
+    pub module_descriptor_size: u64,
+    /// The version associated with the layout of [`Module`].
+    pub module_descriptor_version: u64,
+    /// A pointer to an array of [`GenericTableV3::module_count`] [`Module`]s.
+    ///
+    /// This is NULL if no modules were provided to the bootloader.
+    pub modules: *const Module,
+}
+
+impl GenericTableV3 {
+    /// The version of the [`GenericTable`] with which this [`GenericTable`] is associated.
+    pub const VERSION: u64 = 3;
+}
+
+/// Table providing information and functionality that is cross-architectural in nature.
+///
+/// All functions must be called on the main CPU.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct GenericTableV4 {
+    /// The fields of the [`GenericTableV3`] structure.
+    pub v3: GenericTableV3,
+
+    /// A bitmask of the [`MappingType`]s accepted by [`GenericTableV0::map`], where bit `n` is set
+    /// if `MappingType(n)` is supported.
+    ///
+    /// Bootloaders that provide an older [`GenericTable`] only support [`MappingType::NORMAL`].
+    pub supported_mapping_types: u64,
+}
+
+impl GenericTableV4 {
+    /// The version of the [`GenericTable`] with which this [`GenericTable`] is associated.
+    pub const VERSION: u64 = 4;
+
+    /// Returns `true` if [`GenericTableV0::map`] accepts `mapping_type`.
+    pub const fn supports_mapping_type(&self, mapping_type: MappingType) -> bool {
+        mapping_type.0 < 64 && (self.supported_mapping_types >> mapping_type.0) & 1 == 1
+    }
+}
+
+/// Table providing information and functionality that is cross-architectural in nature.
+///
+/// All functions must be called on the main CPU.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct GenericTableV5 {
+    /// The fields of the [`GenericTableV4`] structure.
+    pub v4: GenericTableV4,
+
+    /// A pointer to the [`LogRingHeader`][log_ring::LogRingHeader] at the start of the persistent
+    /// log ring.
+    ///
+    /// This is NULL if the bootloader does not provide a log ring. The log ring is identity mapped
+    /// and remains valid after [`GenericTableV0::takeover`] succeeds.
+    pub log_ring: *mut log_ring::LogRingHeader,
+}
+
+impl GenericTableV5 {
+    /// The version of the [`GenericTable`] with which this [`GenericTable`] is associated.
+    pub const VERSION: u64 = 5;
+
+    /// Returns the fields of the [`GenericTableV0`] structure.
+    pub const fn v0(&self) -> &GenericTableV0 {
+        &self.v4.v3.v2.v1.v0
+    }
+
+    /// Returns the fields of the [`GenericTableV1`] structure.
+    pub const fn v1(&self) -> &GenericTableV1 {
+        &self.v4.v3.v2.v1
+    }
+
+    /// Returns the fields of the [`GenericTableV2`] structure.
+    pub const fn v2(&self) -> &GenericTableV2 {
+        &self.v4.v3.v2
+    }
+
+    /// Returns the fields of the [`GenericTableV3`] structure.
+    pub const fn v3(&self) -> &GenericTableV3 {
+        &self.v4.v3
+    }
+}
+
+/// Description of a linear framebuffer.
+///
+/// The framebuffer remains valid after [`GenericTableV0::takeover`] succeeds.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct FramebufferV0 {
+    /// The physical address of the start of the framebuffer.
+    pub physical_address: u64,
+    /// The width, in pixels, of the framebuffer.
+    pub width: u64,
+    /// The height, in pixels, of the framebuffer.
+    pub height: u64,
+    /// The number of bytes between the start of one row of pixels and the start of the next.
+    pub pitch: u64,
+
+    /// The number of bits that make up a single pixel.
+    pub bits_per_pixel: u16,
+    /// The number of bits that make up the red component of a pixel.
+    pub red_mask_size: u8,
+    /// The offset, in bits, of the red component of a pixel.
+    pub red_mask_shift: u8,
+    /// The number of bits that make up the green component of a pixel.
+    pub green_mask_size: u8,
+    /// The offset, in bits, of the green component of a pixel.
+    pub green_mask_shift: u8,
+    /// The number of bits that make up the blue component of a pixel.
+    pub blue_mask_size: u8,
+    /// The offset, in bits, of the blue component of a pixel.
+    pub blue_mask_shift: u8,
+}
+
+impl FramebufferV0 {
+    /// The version of the [`Framebuffer`] with which this [`Framebuffer`] is associated (this is
+    /// the value of [`GenericTableV1::framebuffer_descriptor_version`]).
+    pub const VERSION: u64 = 0;
+}
+
+/// Description of a single CPU.
+///
+/// This will be backwards compatible within a major version.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct CpuDescriptorV0 {
+    /// The architectural identifier of the CPU.
+    ///
+    /// - **aarch64**: The affinity fields of `MPIDR_EL1`.
+    /// - **i686** and **x86_64**: The x2APIC ID of the CPU, or the initial APIC ID if the x2APIC
+    ///   ID is unavailable.
+    pub hardware_id: u64,
+    /// Various flags describing the state of the CPU.
+    pub flags: CpuFlags,
+    /// The NUMA proximity domain to which the CPU belongs.
+    ///
+    /// This is only valid if [`CpuFlags::PROXIMITY_DOMAIN_VALID`] is set.
+    pub proximity_domain: u32,
+}
+
+impl CpuDescriptorV0 {
+    /// The version of the [`CpuDescriptor`] with which this [`CpuDescriptor`] is associated (this
+    /// is the value of [`GenericTableV2::cpu_descriptor_version`]).
+    pub const VERSION: u64 = 0;
+}
+
+/// Various flags describing the state of a CPU.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct CpuFlags(pub u32);
+
+impl CpuFlags {
+    /// The CPU is enabled and usable.
+    pub const ENABLED: Self = Self(1 << 0);
+    /// [`CpuDescriptorV0::proximity_domain`] contains the NUMA proximity domain of the CPU.
+    pub const PROXIMITY_DOMAIN_VALID: Self = Self(1 << 1);
+
+    /// Returns `true` if the flags in `other` are set in `self`.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for CpuFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for CpuFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+/// Description of a file loaded into memory by the bootloader on behalf of the executable.
+///
+/// The name and command line are located within the memory that holds the REVM protocol table,
+/// while the contents of the module reside in [`MemoryType::BOOTLOADER_RECLAIMABLE`] memory.
+///
+/// This will be backwards compatible within a major version.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct ModuleV0 {
+    /// The physical address of the start of the module.
+    pub physical_address: u64,
+    /// The size, in bytes, of the module.
+    pub size: u64,
+    /// A pointer to the UTF-8 name of the module.
+    pub name: *const u8,
+    /// The length, in bytes, of the name of the module.
+    pub name_length: u64,
+    /// A pointer to the UTF-8 command line associated with the module.
+    pub command_line: *const u8,
+    /// The length, in bytes, of the command line associated with the module.
+    pub command_line_length: u64,
+}
+
+impl ModuleV0 {
+    /// The version of the [`Module`] with which this [`Module`] is associated (this is the value
+    /// of [`GenericTableV3::module_descriptor_version`]).
+    pub const VERSION: u64 = 0;
+}
+
 /// Various flags concerning the relationship between the bootloader and the executable.
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -182,7 +459,7 @@ impl Flags {
     }
 }
 
-/// Various flags affecting the behavior of [`GenericTable::allocate_frames`].
+/// Various flags affecting the behavior of [`GenericTableV0::allocate_frames`].
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct AllocationFlags(pub u64);
@@ -202,7 +479,7 @@ impl AllocationFlags {
     pub const VALID: Self = Self(Self::TYPE.0);
 }
 
-/// Various flags affecting the behavior of [`GenericTable::takeover`].
+/// Various flags affecting the behavior of [`GenericTableV0::takeover`].
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TakeoverFlags(pub u64);
@@ -258,7 +535,7 @@ impl BitAndAssign for TakeoverFlags {
 /// This will be backwards compatible within a major version.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct MemoryDescriptor {
+pub struct MemoryDescriptorV0 {
     /// The physical memory address at the start of the physical memory region described by this
     /// [`MemoryDescriptor`].
     pub start: u64,
@@ -268,12 +545,31 @@ pub struct MemoryDescriptor {
     pub region_type: MemoryType,
 }
 
-impl MemoryDescriptor {
+impl MemoryDescriptorV0 {
     /// The version of the [`MemoryDescriptor`] with wich this [`MemoryDescriptor`] is associated
     /// (this is the value returned in `descriptor_version`).
     pub const VERSION: u64 = 0;
 }
 
+/// Description of a single memory region.
+///
+/// This will be backwards compatible within a major version.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryDescriptorV1 {
+    /// The fields of the [`MemoryDescriptorV0`] structure.
+    pub v0: MemoryDescriptorV0,
+
+    /// The caching and runtime attributes of the memory region.
+    pub attributes: MemoryAttributes,
+}
+
+impl MemoryDescriptorV1 {
+    /// The version of the [`MemoryDescriptor`] with wich this [`MemoryDescriptor`] is associated
+    /// (this is the value returned in `descriptor_version`).
+    pub const VERSION: u64 = 1;
+}
+
 /// Various types of memory regions.
 #[repr(transparent)]
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -294,6 +590,33 @@ impl MemoryType {
     pub const ACPI_RECLAIMABLE: Self = Self(4);
     /// Memory that holds non-volatile ACPI data.
     pub const ACPI_NON_VOLATILE: Self = Self(5);
+    /// Memory that holds the code of the firmware's runtime services.
+    ///
+    /// This memory must be preserved and mapped with the [`MemoryAttributes`] of the region for as
+    /// long as the runtime services are used.
+    pub const RUNTIME_SERVICES_CODE: Self = Self(6);
+    /// Memory that holds the data of the firmware's runtime services.
+    ///
+    /// This memory must be preserved and mapped with the [`MemoryAttributes`] of the region for as
+    /// long as the runtime services are used.
+    pub const RUNTIME_SERVICES_DATA: Self = Self(7);
+    /// Memory-mapped I/O regions reported by the firmware.
+    pub const MMIO: Self = Self(8);
+    /// Byte-addressable memory that retains its contents across resets.
+    pub const PERSISTENT: Self = Self(9);
+    /// Memory that backs a framebuffer.
+    pub const FRAMEBUFFER: Self = Self(10);
+    /// Memory that holds the loaded image of the executable.
+    pub const EXECUTABLE: Self = Self(11);
+    /// Memory that holds the REVM protocol table and the structures that it references.
+    ///
+    /// This memory can be reclaimed once the executable no longer uses the REVM protocol table.
+    pub const PROTOCOL_TABLE: Self = Self(12);
+    /// Memory that holds the persistent log ring described by [`GenericTableV5::log_ring`].
+    ///
+    /// The contents of this memory should be preserved across resets so that the log can be
+    /// recovered by the next boot.
+    pub const LOG_RING: Self = Self(13);
 }
 
 impl fmt::Debug for MemoryType {
@@ -305,13 +628,63 @@ impl fmt::Debug for MemoryType {
             Self::BAD => f.pad("BAD"),
             Self::ACPI_RECLAIMABLE => f.pad("ACPI_RECLAIMABLE"),
             Self::ACPI_NON_VOLATILE => f.pad("ACPI_NON_VOLATILE"),
+            Self::RUNTIME_SERVICES_CODE => f.pad("RUNTIME_SERVICES_CODE"),
+            Self::RUNTIME_SERVICES_DATA => f.pad("RUNTIME_SERVICES_DATA"),
+            Self::MMIO => f.pad("MMIO"),
+            Self::PERSISTENT => f.pad("PERSISTENT"),
+            Self::FRAMEBUFFER => f.pad("FRAMEBUFFER"),
+            Self::EXECUTABLE => f.pad("EXECUTABLE"),
+            Self::PROTOCOL_TABLE => f.pad("PROTOCOL_TABLE"),
+            Self::LOG_RING => f.pad("LOG_RING"),
 
             unknown => f.debug_tuple("MemoryType").field(&unknown.0).finish(),
         }
     }
 }
 
-/// Various flags affecting the behavior of [`GenericTable::map`].
+/// Attributes of a memory region.
+///
+/// The caching attributes describe the memory types that the region supports, not the memory
+/// type with which it is currently mapped. A region without any caching attributes has unknown
+/// caching capabilities.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct MemoryAttributes(pub u64);
+
+impl MemoryAttributes {
+    /// The region supports being mapped as uncacheable.
+    pub const UNCACHEABLE: Self = Self(1 << 0);
+    /// The region supports being mapped as write-combining.
+    pub const WRITE_COMBINING: Self = Self(1 << 1);
+    /// The region supports being mapped as write-through.
+    pub const WRITE_THROUGH: Self = Self(1 << 2);
+    /// The region supports being mapped as write-back.
+    pub const WRITE_BACK: Self = Self(1 << 3);
+    /// The region is used by the firmware's runtime services and must be mapped for them to be
+    /// usable.
+    pub const RUNTIME: Self = Self(1 << 63);
+
+    /// Returns `true` if the flags in `other` are set in `self`.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for MemoryAttributes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for MemoryAttributes {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+/// Various flags affecting the behavior of [`GenericTableV0::map`].
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct MapFlags(pub u64);
@@ -328,21 +701,48 @@ impl MapFlags {
     pub const MAY_OVERWRITE: Self = Self(1 << 3);
     /// The mapping function should choose the virtual location of the mapping (it will ignore the
     /// initial value of `virtual_address` and write the chosen address to `virtual_address`).
+    ///
+    /// This flag cannot be combined with [`MapFlags::MAY_OVERWRITE`], and such requests fail with
+    /// [`Status::NOT_SUPPORTED`].
     pub const CHOOSE_LOCATION: Self = Self(1 << 4);
 
+    /// The offset, in bits, of the [`MappingType`] field.
+    pub const MAPPING_TYPE_SHIFT: u32 = 8;
+    /// Bitmask of the [`MappingType`] field.
+    ///
+    /// A value of zero requests [`MappingType::NORMAL`], so callers unaware of this field keep the
+    /// behavior of earlier versions of the protocol.
+    pub const MAPPING_TYPE_MASK: Self = Self(0xFF << Self::MAPPING_TYPE_SHIFT);
+
     /// Bitmask of the valid flags.
     pub const VALID: Self = Self(
         Self::READ.0
             | Self::WRITE.0
             | Self::EXEC.0
             | Self::MAY_OVERWRITE.0
-            | Self::CHOOSE_LOCATION.0,
+            | Self::CHOOSE_LOCATION.0
+            | Self::MAPPING_TYPE_MASK.0,
     );
 
     /// Returns `true` if the flags in `other` are set in `self`.
     pub const fn contains(&self, other: Self) -> bool {
         self.0 & other.0 == other.0
     }
+
+    /// Returns the [`MappingType`] requested by these [`MapFlags`].
+    pub const fn mapping_type(&self) -> MappingType {
+        MappingType((self.0 & Self::MAPPING_TYPE_MASK.0) >> Self::MAPPING_TYPE_SHIFT)
+    }
+
+    /// Returns a copy of these [`MapFlags`] that requests `mapping_type`.
+    ///
+    /// Only the bits of `mapping_type` that fit in [`MapFlags::MAPPING_TYPE_MASK`] are used.
+    pub const fn with_mapping_type(self, mapping_type: MappingType) -> Self {
+        Self(
+            (self.0 & !Self::MAPPING_TYPE_MASK.0)
+                | ((mapping_type.0 << Self::MAPPING_TYPE_SHIFT) & Self::MAPPING_TYPE_MASK.0),
+        )
+    }
 }
 
 impl BitOr for MapFlags {
@@ -373,6 +773,41 @@ impl BitAndAssign for MapFlags {
     }
 }
 
+/// The memory attributes with which [`GenericTableV0::map`] maps a region.
+///
+/// Support for each type is reported by [`GenericTableV4::supported_mapping_types`].
+#[repr(transparent)]
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct MappingType(pub u64);
+
+impl MappingType {
+    /// Normal, cacheable memory.
+    pub const NORMAL: Self = Self(0);
+    /// Normal memory that is not cached (typically memory shared with non-coherent DMA devices).
+    pub const NORMAL_NONCACHEABLE: Self = Self(1);
+    /// Device memory, for which accesses are neither cached, combined, nor reordered (typically
+    /// memory-mapped registers).
+    pub const DEVICE: Self = Self(2);
+    /// Device memory on which writes may be combined (typically framebuffers).
+    ///
+    /// Bootloaders may map such regions as [`MappingType::NORMAL_NONCACHEABLE`] when the
+    /// architecture provides no means to combine writes.
+    pub const WRITE_COMBINING: Self = Self(3);
+}
+
+impl fmt::Debug for MappingType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::NORMAL => f.pad("NORMAL"),
+            Self::NORMAL_NONCACHEABLE => f.pad("NORMAL_NONCACHEABLE"),
+            Self::DEVICE => f.pad("DEVICE"),
+            Self::WRITE_COMBINING => f.pad("WRITE_COMBINING"),
+
+            unknown => f.debug_tuple("MappingType").field(&unknown.0).finish(),
+        }
+    }
+}
+
 /// Various status codes.
 #[repr(transparent)]
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -0,0 +1,737 @@
+//! # The Persistent Log Ring
+//!
+//! The log ring is a region of physical memory into which both the bootloader and the executable
+//! append log records. Its location is reported by [`GenericTableV5::log_ring`] and it is
+//! described in the memory map as [`MemoryType::LOG_RING`]. The ring remains valid after
+//! [`GenericTableV0::takeover`] succeeds, and a bootloader that finds a valid ring from a previous
+//! boot reports its records before reusing it, so the log survives crashes and resets.
+//!
+//! ## Layout
+//!
+//! The region starts with a [`LogRingHeader`], which is followed by the data area at
+//! [`LogRingHeaderV0::data_offset`]. Records are stored at monotonically increasing byte
+//! positions, where position `p` refers to the byte at offset `p % data_size` of the data area.
+//! The oldest record starts at [`LogRingHeaderV0::tail`] and the next record will be written at
+//! [`LogRingHeaderV0::head`].
+//!
+//! Each record consists of a [`LogRecord`] followed by its UTF-8 message and is padded to a
+//! multiple of [`RECORD_ALIGNMENT`] bytes. Records never wrap around the end of the data area: if
+//! fewer than `size_of::<LogRecord>()` bytes remain before the end, or the record found there has
+//! [`LogRecordFlags::PADDING`] set, the next record starts at the beginning of the data area.
+//!
+//! All fields are stored in the native byte order of the architecture.
+//!
+//! [`GenericTableV5::log_ring`]: crate::GenericTableV5::log_ring
+//! [`MemoryType::LOG_RING`]: crate::MemoryType::LOG_RING
+//! [`GenericTableV0::takeover`]: crate::GenericTableV0::takeover
+
+use core::{
+    fmt, mem,
+    ops::{BitOr, BitOrAssign},
+    ptr::NonNull,
+    slice,
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+};
+
+pub use LogRecordV0 as LogRecord;
+pub use LogRingHeaderV0 as LogRingHeader;
+
+/// The value of [`LogRingHeaderV0::signature`] in a valid log ring.
+pub const SIGNATURE: [u8; 8] = *b"REVMLOG\0";
+
+/// The alignment, in bytes, of each [`LogRecord`] within the data area.
+pub const RECORD_ALIGNMENT: usize = 8;
+
+/// The smallest data area, in bytes, that a log ring may have.
+pub const MINIMUM_DATA_SIZE: usize = 256;
+
+/// The number of attempts made to acquire [`LogRingHeaderV0::lock`] before an operation is
+/// abandoned.
+const LOCK_ATTEMPTS: usize = 1 << 20;
+
+/// The largest record, in bytes, that is written to a log ring.
+const MAXIMUM_RECORD_LENGTH: usize = 64 * 1024;
+
+/// The size, in bytes, of a [`LogRecord`].
+const RECORD_HEADER_SIZE: usize = mem::size_of::<LogRecord>();
+
+/// The header at the start of the log ring.
+#[repr(C)]
+#[derive(Debug)]
+pub struct LogRingHeaderV0 {
+    /// Identifies the region as a log ring. This is always [`SIGNATURE`].
+    pub signature: [u8; 8],
+    /// The version of the [`LogRingHeader`] with which this log ring identifies.
+    pub version: u64,
+    /// The total size, in bytes, of the log ring, including this header.
+    pub size: u64,
+    /// The offset, in bytes, from the start of the [`LogRingHeader`] to the data area.
+    pub data_offset: u64,
+    /// The size, in bytes, of the data area. This is a multiple of [`RECORD_ALIGNMENT`].
+    pub data_size: u64,
+    /// The frequency, in hertz, of the counter used for [`LogRecordV0::timestamp`], or zero if it
+    /// is unknown.
+    pub counter_frequency: AtomicU64,
+    /// The position at which the next record will be written.
+    pub head: AtomicU64,
+    /// The position of the oldest record.
+    pub tail: AtomicU64,
+    /// The sequence number that the next record will receive.
+    pub next_sequence: AtomicU64,
+    /// Non-zero while a writer or reader is accessing the data area.
+    pub lock: AtomicU32,
+    /// Reserved for future use. This must be zero.
+    pub reserved: u32,
+}
+
+impl LogRingHeaderV0 {
+    /// The version of the [`LogRingHeader`] with which this [`LogRingHeader`] is associated.
+    pub const VERSION: u64 = 0;
+}
+
+/// The header of a single record in the log ring.
+///
+/// The message of the record immediately follows this header.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct LogRecordV0 {
+    /// The total size, in bytes, of the record, including this header, the message, and any
+    /// padding.
+    pub record_length: u32,
+    /// The size, in bytes, of the message.
+    pub message_length: u32,
+    /// The sequence number of the record, which increases by one for each record written.
+    pub sequence: u64,
+    /// The value of the architectural counter when the record was written: the TSC on `i686` and
+    /// `x86_64` and `CNTVCT_EL0` on `aarch64`.
+    pub timestamp: u64,
+    /// The ID of the CPU that wrote the record, as passed to
+    /// [`GenericTableV0::run_on_all_processors`][crate::GenericTableV0::run_on_all_processors], or
+    /// `u32::MAX` if the writer did not identify the CPU.
+    pub cpu: u32,
+    /// The severity of the record.
+    pub level: LogRecordLevel,
+    /// Reserved for future use. This must be zero.
+    pub reserved: u8,
+    /// Various flags describing the record.
+    pub flags: LogRecordFlags,
+}
+
+/// The severity of a [`LogRecord`].
+#[repr(transparent)]
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LogRecordLevel(pub u8);
+
+impl LogRecordLevel {
+    /// Very low priority information.
+    pub const TRACE: Self = Self(0);
+    /// Lower priority information.
+    pub const DEBUG: Self = Self(1);
+    /// Informatory messages.
+    pub const INFO: Self = Self(2);
+    /// Hazardous conditions.
+    pub const WARN: Self = Self(3);
+    /// Very serious errors.
+    pub const ERROR: Self = Self(4);
+}
+
+impl fmt::Debug for LogRecordLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::TRACE => f.pad("TRACE"),
+            Self::DEBUG => f.pad("DEBUG"),
+            Self::INFO => f.pad("INFO"),
+            Self::WARN => f.pad("WARN"),
+            Self::ERROR => f.pad("ERROR"),
+
+            unknown => f.debug_tuple("LogRecordLevel").field(&unknown.0).finish(),
+        }
+    }
+}
+
+/// Various flags describing a [`LogRecord`].
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct LogRecordFlags(pub u16);
+
+impl LogRecordFlags {
+    /// The record only fills the end of the data area and carries no message.
+    pub const PADDING: Self = Self(1 << 0);
+    /// The record was written by the executable rather than by the bootloader.
+    pub const EXECUTABLE: Self = Self(1 << 1);
+
+    /// Returns `true` if the flags in `other` are set in `self`.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for LogRecordFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for LogRecordFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+/// A handle through which records are appended to and read from a log ring.
+///
+/// Accesses to the data area are serialized through [`LogRingHeaderV0::lock`]. An operation that
+/// cannot acquire the lock within a bounded number of attempts is abandoned, so a processor that
+/// stopped while holding the lock, or a context that interrupted the holder, cannot deadlock the
+/// logging paths that use the ring.
+#[derive(Clone, Copy, Debug)]
+pub struct LogRing {
+    /// The header at the start of the log ring.
+    header: NonNull<LogRingHeader>,
+    /// The start of the data area.
+    data: NonNull<u8>,
+    /// The size, in bytes, of the data area.
+    data_size: usize,
+}
+
+// SAFETY:
+// All accesses to the log ring are either atomic or serialized through its lock.
+unsafe impl Send for LogRing {}
+
+// SAFETY:
+// All accesses to the log ring are either atomic or serialized through its lock.
+unsafe impl Sync for LogRing {}
+
+impl LogRing {
+    /// Formats the `size` bytes starting at `base` as an empty log ring.
+    ///
+    /// Returns [`None`] if `base` is not aligned to [`RECORD_ALIGNMENT`] or `size` is too small to
+    /// hold a data area of at least [`MINIMUM_DATA_SIZE`] bytes.
+    ///
+    /// # Safety
+    ///
+    /// The `size` bytes starting at `base` must be valid for reads and writes for as long as any
+    /// [`LogRing`] referring to them exists, and must not be accessed other than through
+    /// [`LogRing`]s.
+    pub unsafe fn initialize(base: NonNull<u8>, size: usize) -> Option<Self> {
+        let data_offset = mem::size_of::<LogRingHeader>().next_multiple_of(RECORD_ALIGNMENT);
+        let data_size = size.checked_sub(data_offset)? / RECORD_ALIGNMENT * RECORD_ALIGNMENT;
+        if base.as_ptr().align_offset(RECORD_ALIGNMENT) != 0 || data_size < MINIMUM_DATA_SIZE {
+            return None;
+        }
+
+        let header = LogRingHeader {
+            signature: SIGNATURE,
+            version: LogRingHeader::VERSION,
+            size: size as u64,
+            data_offset: data_offset as u64,
+            data_size: data_size as u64,
+            counter_frequency: AtomicU64::new(0),
+            head: AtomicU64::new(0),
+            tail: AtomicU64::new(0),
+            next_sequence: AtomicU64::new(0),
+            lock: AtomicU32::new(0),
+            reserved: 0,
+        };
+
+        // SAFETY:
+        //
+        // The invariants of this function ensure that `base` is valid for writes of `size` bytes,
+        // which includes the [`LogRingHeader`], and `base` is suitably aligned.
+        unsafe { base.cast::<LogRingHeader>().write(header) }
+
+        // SAFETY:
+        //
+        // The data area lies within the `size` bytes starting at `base`.
+        let data = unsafe { base.add(data_offset) };
+
+        Some(Self {
+            header: base.cast(),
+            data,
+            data_size,
+        })
+    }
+
+    /// Returns a handle to the log ring starting at `base`, which must have been formatted by
+    /// [`LogRing::initialize()`].
+    ///
+    /// Returns [`None`] if the [`LogRingHeader`] is invalid.
+    ///
+    /// # Safety
+    ///
+    /// `base` must be the start of a log ring that was formatted by [`LogRing::initialize()`] and
+    /// that remains valid for as long as the returned [`LogRing`] exists.
+    pub unsafe fn from_raw(base: NonNull<u8>) -> Option<Self> {
+        if base.as_ptr().align_offset(RECORD_ALIGNMENT) != 0 {
+            return None;
+        }
+
+        // SAFETY:
+        //
+        // The invariants of this function ensure that `base` points to a [`LogRingHeader`].
+        let header = unsafe { base.cast::<LogRingHeader>().as_ref() };
+        Self::validate(base, header, header.size)
+    }
+
+    /// Returns a handle to a log ring left in the `size` bytes starting at `base` by a previous
+    /// boot, or [`None`] if the region does not contain a valid log ring.
+    ///
+    /// The lock of the recovered log ring is released, as its holder no longer exists.
+    ///
+    /// # Safety
+    ///
+    /// The `size` bytes starting at `base` must be valid for reads and writes for as long as the
+    /// returned [`LogRing`] exists, and must not be accessed by any other context during that
+    /// time.
+    pub unsafe fn recover(base: NonNull<u8>, size: usize) -> Option<Self> {
+        if base.as_ptr().align_offset(RECORD_ALIGNMENT) != 0
+            || size < mem::size_of::<LogRingHeader>()
+        {
+            return None;
+        }
+
+        // SAFETY:
+        //
+        // The invariants of this function ensure that `base` is valid for reads of `size` bytes,
+        // which includes the [`LogRingHeader`], and every bit pattern is a valid [`LogRingHeader`].
+        let header = unsafe { base.cast::<LogRingHeader>().as_ref() };
+        let ring = Self::validate(base, header, size as u64)?;
+        header.lock.store(0, Ordering::Release);
+        Some(ring)
+    }
+
+    /// Checks that `header` describes a consistent log ring of at most `size` bytes starting at
+    /// `base`.
+    fn validate(base: NonNull<u8>, header: &LogRingHeader, size: u64) -> Option<Self> {
+        let alignment = RECORD_ALIGNMENT as u64;
+        let head = header.head.load(Ordering::Relaxed);
+        let tail = header.tail.load(Ordering::Relaxed);
+
+        let valid = header.signature == SIGNATURE
+            && header.version == LogRingHeader::VERSION
+            && header.size <= size
+            && header.data_offset >= mem::size_of::<LogRingHeader>() as u64
+            && header.data_offset.is_multiple_of(alignment)
+            && header.data_size >= MINIMUM_DATA_SIZE as u64
+            && header.data_size.is_multiple_of(alignment)
+            && header
+                .data_offset
+                .checked_add(header.data_size)
+                .is_some_and(|end| end <= header.size)
+            && head.is_multiple_of(alignment)
+            && tail.is_multiple_of(alignment)
+            && tail <= head
+            && head - tail <= header.data_size;
+        if !valid {
+            return None;
+        }
+
+        let data_offset = usize::try_from(header.data_offset).ok()?;
+        // SAFETY:
+        //
+        // `header` was validated, so the data area lies within the log ring starting at `base`.
+        let data = unsafe { base.add(data_offset) };
+
+        Some(Self {
+            header: base.cast(),
+            data,
+            data_size: usize::try_from(header.data_size).ok()?,
+        })
+    }
+
+    /// Returns the total size, in bytes, of the log ring.
+    pub fn size(&self) -> u64 {
+        self.header().size
+    }
+
+    /// Returns the frequency, in hertz, of the counter used for [`LogRecordV0::timestamp`], or
+    /// zero if it is unknown.
+    pub fn counter_frequency(&self) -> u64 {
+        self.header().counter_frequency.load(Ordering::Relaxed)
+    }
+
+    /// Records `frequency` as the frequency, in hertz, of the counter used for
+    /// [`LogRecordV0::timestamp`].
+    pub fn set_counter_frequency(&self, frequency: u64) {
+        self.header()
+            .counter_frequency
+            .store(frequency, Ordering::Relaxed);
+    }
+
+    /// Appends a record containing `message` to the log ring, discarding the oldest records as
+    /// required to make space for it.
+    ///
+    /// Messages longer than a quarter of the data area, or than 64 KiB, are truncated. Returns `false` if the
+    /// record could not be written because the lock could not be acquired.
+    pub fn append(
+        &self,
+        level: LogRecordLevel,
+        flags: LogRecordFlags,
+        cpu: u32,
+        timestamp: u64,
+        message: &[u8],
+    ) -> bool {
+        let maximum_record_length =
+            (self.data_size / 4).min(MAXIMUM_RECORD_LENGTH) / RECORD_ALIGNMENT * RECORD_ALIGNMENT;
+        let message = &message[..message
+            .len()
+            .min(maximum_record_length - RECORD_HEADER_SIZE)];
+        let record_length = (RECORD_HEADER_SIZE + message.len()).next_multiple_of(RECORD_ALIGNMENT);
+
+        let Some(mut guard) = self.lock() else {
+            return false;
+        };
+        let header = self.header();
+        let data = self.data(&mut guard);
+
+        let head = header.head.load(Ordering::Relaxed);
+        let remaining = data.len() - self.offset(head);
+        let padding = if remaining < record_length {
+            remaining
+        } else {
+            0
+        };
+        let new_head = head + (padding + record_length) as u64;
+        self.evict(data, new_head);
+
+        if padding >= RECORD_HEADER_SIZE {
+            let record = LogRecord {
+                record_length: record_field(padding),
+                message_length: 0,
+                sequence: 0,
+                timestamp,
+                cpu,
+                level,
+                reserved: 0,
+                flags: LogRecordFlags::PADDING,
+            };
+            write_record(&mut data[self.offset(head)..], &record);
+        }
+
+        let offset = self.offset(head + padding as u64);
+        let record = LogRecord {
+            record_length: record_field(record_length),
+            message_length: record_field(message.len()),
+            sequence: header.next_sequence.fetch_add(1, Ordering::Relaxed),
+            timestamp,
+            cpu,
+            level,
+            reserved: 0,
+            flags: LogRecordFlags(flags.0 & !LogRecordFlags::PADDING.0),
+        };
+        write_record(&mut data[offset..], &record);
+        data[offset + RECORD_HEADER_SIZE..][..message.len()].copy_from_slice(message);
+
+        header.head.store(new_head, Ordering::Release);
+        true
+    }
+
+    /// Calls `func` with each record in the log ring and its message, from oldest to newest.
+    ///
+    /// Reading stops at the first record that is malformed. `func` must not append to the log
+    /// ring. Returns `false` if the records could not be read because the lock could not be
+    /// acquired.
+    pub fn for_each(&self, mut func: impl FnMut(&LogRecord, &[u8])) -> bool {
+        let Some(mut guard) = self.lock() else {
+            return false;
+        };
+        let header = self.header();
+        let data = self.data(&mut guard);
+
+        let head = header.head.load(Ordering::Relaxed);
+        let mut position = header.tail.load(Ordering::Relaxed);
+        while position < head {
+            let Some(record) = self.record_at(data, position, head) else {
+                break;
+            };
+
+            if let Some(record) = record
+                && !record.flags.contains(LogRecordFlags::PADDING)
+            {
+                let message = &data[self.offset(position) + RECORD_HEADER_SIZE..]
+                    [..record.message_length as usize];
+                func(&record, message);
+            }
+
+            position += self.record_span(position, record);
+        }
+
+        true
+    }
+
+    /// Advances the tail of the log ring until a head of `new_head` leaves no more than the size
+    /// of the data area in use.
+    fn evict(&self, data: &[u8], new_head: u64) {
+        let header = self.header();
+        let head = header.head.load(Ordering::Relaxed);
+        let mut tail = header.tail.load(Ordering::Relaxed);
+
+        while new_head - tail > self.data_size as u64 {
+            match self.record_at(data, tail, head) {
+                Some(record) => tail += self.record_span(tail, record),
+                // The ring is corrupted, so every record is discarded.
+                None => tail = head,
+            }
+        }
+
+        header.tail.store(tail, Ordering::Relaxed);
+    }
+
+    /// Returns the record at `position` if a record header fits before the end of the data area,
+    /// `Some(None)` if the remainder of the data area is too small to hold one, or [`None`] if the
+    /// record is malformed.
+    fn record_at(&self, data: &[u8], position: u64, head: u64) -> Option<Option<LogRecord>> {
+        let offset = self.offset(position);
+        let remaining = data.len() - offset;
+        if remaining < RECORD_HEADER_SIZE {
+            return Some(None);
+        }
+
+        let record = read_record(&data[offset..]);
+        let record_length = record.record_length as usize;
+        let valid = record_length >= RECORD_HEADER_SIZE
+            && record_length.is_multiple_of(RECORD_ALIGNMENT)
+            && record_length <= remaining
+            && (record_length as u64) <= head - position
+            && record.message_length as usize <= record_length - RECORD_HEADER_SIZE;
+
+        valid.then_some(Some(record))
+    }
+
+    /// Returns the number of bytes occupied by `record` at `position`, where [`None`] denotes the
+    /// unused remainder of the data area.
+    fn record_span(&self, position: u64, record: Option<LogRecord>) -> u64 {
+        match record {
+            Some(record) => u64::from(record.record_length),
+            None => (self.data_size - self.offset(position)) as u64,
+        }
+    }
+
+    /// Returns the offset into the data area that corresponds to `position`.
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "the offset is less than the size of the data area, which is a `usize`"
+    )]
+    fn offset(&self, position: u64) -> usize {
+        (position % self.data_size as u64) as usize
+    }
+
+    /// Returns the [`LogRingHeader`] of the log ring.
+    fn header(&self) -> &LogRingHeader {
+        // SAFETY:
+        //
+        // The log ring remains valid for as long as `self` exists and the fields of the header
+        // that may change are atomics.
+        unsafe { self.header.as_ref() }
+    }
+
+    /// Returns the data area of the log ring.
+    fn data<'guard>(&'guard self, _guard: &'guard mut LockGuard<'_>) -> &'guard mut [u8] {
+        // SAFETY:
+        //
+        // The log ring remains valid for as long as `self` exists, and the data area is only
+        // accessed while the lock, which `_guard` proves is held, is held.
+        unsafe { slice::from_raw_parts_mut(self.data.as_ptr(), self.data_size) }
+    }
+
+    /// Acquires the lock of the log ring, or returns [`None`] if it could not be acquired within
+    /// [`LOCK_ATTEMPTS`] attempts.
+    fn lock(&self) -> Option<LockGuard<'_>> {
+        let lock = &self.header().lock;
+
+        for _ in 0..LOCK_ATTEMPTS {
+            if lock
+                .compare_exchange_weak(0, 1, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                return Some(LockGuard(lock));
+            }
+
+            core::hint::spin_loop();
+        }
+
+        None
+    }
+}
+
+/// Releases [`LogRingHeaderV0::lock`] when dropped.
+struct LockGuard<'ring>(&'ring AtomicU32);
+
+impl Drop for LockGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(0, Ordering::Release);
+    }
+}
+
+/// Converts a length, which is at most [`MAXIMUM_RECORD_LENGTH`], into a [`LogRecord`] field.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "lengths are at most `MAXIMUM_RECORD_LENGTH`, which fits in a `u32`"
+)]
+const fn record_field(length: usize) -> u32 {
+    debug_assert!(length <= MAXIMUM_RECORD_LENGTH);
+    length as u32
+}
+
+/// Reads the [`LogRecord`] at the start of `bytes`.
+fn read_record(bytes: &[u8]) -> LogRecord {
+    /// Returns the `N` bytes of the field at `offset`.
+    fn field<const N: usize>(bytes: &[u8], offset: usize) -> [u8; N] {
+        let mut field = [0; N];
+        field.copy_from_slice(&bytes[offset..][..N]);
+        field
+    }
+
+    LogRecord {
+        record_length: u32::from_ne_bytes(field(bytes, mem::offset_of!(LogRecord, record_length))),
+        message_length: u32::from_ne_bytes(field(
+            bytes,
+            mem::offset_of!(LogRecord, message_length),
+        )),
+        sequence: u64::from_ne_bytes(field(bytes, mem::offset_of!(LogRecord, sequence))),
+        timestamp: u64::from_ne_bytes(field(bytes, mem::offset_of!(LogRecord, timestamp))),
+        cpu: u32::from_ne_bytes(field(bytes, mem::offset_of!(LogRecord, cpu))),
+        level: LogRecordLevel(bytes[mem::offset_of!(LogRecord, level)]),
+        reserved: bytes[mem::offset_of!(LogRecord, reserved)],
+        flags: LogRecordFlags(u16::from_ne_bytes(field(
+            bytes,
+            mem::offset_of!(LogRecord, flags),
+        ))),
+    }
+}
+
+/// Writes `record` to the start of `bytes`.
+fn write_record(bytes: &mut [u8], record: &LogRecord) {
+    /// Copies `field` to `offset`.
+    fn field<const N: usize>(bytes: &mut [u8], offset: usize, field: [u8; N]) {
+        bytes[offset..][..N].copy_from_slice(&field);
+    }
+
+    field(
+        bytes,
+        mem::offset_of!(LogRecord, record_length),
+        record.record_length.to_ne_bytes(),
+    );
+    field(
+        bytes,
+        mem::offset_of!(LogRecord, message_length),
+        record.message_length.to_ne_bytes(),
+    );
+    field(
+        bytes,
+        mem::offset_of!(LogRecord, sequence),
+        record.sequence.to_ne_bytes(),
+    );
+    field(
+        bytes,
+        mem::offset_of!(LogRecord, timestamp),
+        record.timestamp.to_ne_bytes(),
+    );
+    field(
+        bytes,
+        mem::offset_of!(LogRecord, cpu),
+        record.cpu.to_ne_bytes(),
+    );
+    field(bytes, mem::offset_of!(LogRecord, level), [record.level.0]);
+    field(
+        bytes,
+        mem::offset_of!(LogRecord, reserved),
+        [record.reserved],
+    );
+    field(
+        bytes,
+        mem::offset_of!(LogRecord, flags),
+        record.flags.0.to_ne_bytes(),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use std::{string::String, vec::Vec};
+
+    use super::*;
+
+    /// Returns the messages in `ring`, from oldest to newest.
+    fn messages(ring: &LogRing) -> Vec<String> {
+        let mut messages = Vec::new();
+        assert!(ring.for_each(|_, message| {
+            messages.push(String::from_utf8(message.to_vec()).unwrap());
+        }));
+        messages
+    }
+
+    #[test]
+    fn oldest_records_are_discarded() {
+        let mut buffer = [0u64; 64];
+        let base = NonNull::from(&mut buffer).cast::<u8>();
+        // SAFETY:
+        // `buffer` outlives `ring` and is only accessed through it.
+        let ring = unsafe { LogRing::initialize(base, mem::size_of_val(&buffer)) }.unwrap();
+
+        for index in 0..40 {
+            let message = std::format!("message {index}");
+            assert!(ring.append(
+                LogRecordLevel::INFO,
+                LogRecordFlags(0),
+                0,
+                index,
+                message.as_bytes(),
+            ));
+        }
+
+        let messages = messages(&ring);
+        assert_eq!(messages.last().map(String::as_str), Some("message 39"));
+        for pair in messages.windows(2) {
+            let [first, second] = [&pair[0], &pair[1]].map(|message| {
+                message
+                    .strip_prefix("message ")
+                    .unwrap()
+                    .parse::<u32>()
+                    .unwrap()
+            });
+            assert_eq!(first + 1, second);
+        }
+    }
+
+    #[test]
+    fn recovered_ring_keeps_records() {
+        let mut buffer = [0u64; 64];
+        let base = NonNull::from(&mut buffer).cast::<u8>();
+        let size = mem::size_of_val(&buffer);
+
+        // SAFETY:
+        // `buffer` outlives both handles and is only accessed through them.
+        let ring = unsafe { LogRing::initialize(base, size) }.unwrap();
+        assert!(ring.append(
+            LogRecordLevel::ERROR,
+            LogRecordFlags::EXECUTABLE,
+            3,
+            7,
+            b"crash",
+        ));
+        // Simulate a processor that stopped while holding the lock.
+        ring.header().lock.store(1, Ordering::Relaxed);
+
+        // SAFETY:
+        // `buffer` outlives both handles and is only accessed through them.
+        let recovered = unsafe { LogRing::recover(base, size) }.unwrap();
+        let mut records = Vec::new();
+        recovered.for_each(|record, message| records.push((*record, message.to_vec())));
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].0.cpu, 3);
+        assert_eq!(records[0].0.level, LogRecordLevel::ERROR);
+        assert!(records[0].0.flags.contains(LogRecordFlags::EXECUTABLE));
+        assert_eq!(records[0].1, b"crash");
+
+        // SAFETY:
+        // `base` points to the signature of the log ring, which is valid for writes.
+        unsafe { base.write(0) }
+        // SAFETY:
+        // `buffer` outlives the handle and is only accessed through it.
+        assert!(unsafe { LogRing::recover(base, size) }.is_none());
+    }
+}
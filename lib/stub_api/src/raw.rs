@@ -2,7 +2,9 @@
 
 use crate::Flags;
 
-#[repr(C)]
+// `u64` is only 4-byte aligned on `i686`, so the 32-bit table must be packed to match the layout
+// of [`GenericTable`][crate::GenericTable] regardless of the target on which it is built.
+#[repr(C, packed(4))]
 #[derive(Clone, Copy)]
 #[expect(missing_docs)]
 pub struct GenericTable32 {
@@ -29,6 +31,23 @@ pub struct GenericTable32 {
     pub takeover: u32,
 
     pub run_on_all_processors: u32,
+
+    pub framebuffer_descriptor_size: u64,
+    pub framebuffer_descriptor_version: u64,
+    pub framebuffer: u32,
+
+    pub cpu_descriptor_size: u64,
+    pub cpu_descriptor_version: u64,
+    pub cpus: u32,
+
+    pub module_count: u64,
+    pub module_descriptor_size: u64,
+    pub module_descriptor_version: u64,
+    pub modules: u32,
+
+    pub supported_mapping_types: u64,
+
+    pub log_ring: u32,
 }
 
 #[repr(C)]
@@ -58,4 +77,47 @@ pub struct GenericTable64 {
     pub takeover: u64,
 
     pub run_on_all_processors: u64,
+
+    pub framebuffer_descriptor_size: u64,
+    pub framebuffer_descriptor_version: u64,
+    pub framebuffer: u64,
+
+    pub cpu_descriptor_size: u64,
+    pub cpu_descriptor_version: u64,
+    pub cpus: u64,
+
+    pub module_count: u64,
+    pub module_descriptor_size: u64,
+    pub module_descriptor_version: u64,
+    pub modules: u64,
+
+    pub supported_mapping_types: u64,
+
+    pub log_ring: u64,
+}
+
+// `u64` is only 4-byte aligned on `i686`, so the 32-bit descriptor must be packed to match the
+// layout of [`Module`][crate::Module] regardless of the target on which it is built.
+#[repr(C, packed(4))]
+#[derive(Clone, Copy)]
+#[expect(missing_docs)]
+pub struct Module32 {
+    pub physical_address: u64,
+    pub size: u64,
+    pub name: u32,
+    pub name_length: u64,
+    pub command_line: u32,
+    pub command_line_length: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[expect(missing_docs)]
+pub struct Module64 {
+    pub physical_address: u64,
+    pub size: u64,
+    pub name: u64,
+    pub name_length: u64,
+    pub command_line: u64,
+    pub command_line_length: u64,
 }
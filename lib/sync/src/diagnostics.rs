@@ -0,0 +1,476 @@
+//! Opt-in diagnostics for [`Spinlock`][crate::Spinlock].
+//!
+//! With the `diagnostics` feature enabled, every [`RawSpinlock`][crate::RawSpinlock] records the
+//! processor that holds it and the location at which it was acquired. This information is used to
+//! detect a processor attempting to acquire a lock that it already holds, which can never succeed,
+//! and to report acquisitions that spin for longer than a configurable threshold.
+//!
+//! With the `lockdep` feature enabled, the order in which each processor acquires locks is
+//! recorded as well, and acquiring two locks in the opposite order of a previous acquisition is
+//! reported. Locks are identified by their address, so only locks that live for the duration of
+//! the program, such as `static` locks, are checked meaningfully, and only direct inversions
+//! between two locks are detected.
+//!
+//! Diagnostics are delivered to the handler registered with [`set_lock_diagnostics_handler()`].
+//! The handler is not invoked recursively: diagnostics raised while a handler is running are
+//! dropped.
+
+use core::{
+    fmt,
+    panic::Location,
+    ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+};
+
+use crate::{ControlledModificationCell, current_processor_id};
+
+/// The registered diagnostics handler.
+static HANDLER: ControlledModificationCell<Option<Handler>> = ControlledModificationCell::new(None);
+
+/// Set while a diagnostic is being reported, which prevents recursive reports from locks taken by
+/// the handler.
+static REPORTING: AtomicBool = AtomicBool::new(false);
+
+/// A registered diagnostics handler.
+#[derive(Clone, Copy)]
+struct Handler {
+    /// The function to which diagnostics are reported.
+    report: fn(&LockDiagnostic),
+    /// The number of spins after which [`LockDiagnostic::LongSpin`] is reported.
+    spin_threshold: usize,
+}
+
+/// Registers `report` as the handler of [`LockDiagnostic`]s.
+///
+/// [`LockDiagnostic::LongSpin`] is reported each time an acquisition has spun another
+/// `spin_threshold` times. A `spin_threshold` of zero disables spin reports.
+///
+/// Until a handler is registered, [`LockDiagnostic::Reacquired`] and
+/// `LockDiagnostic::OrderInversion` panic, while spin reports are discarded.
+///
+/// # Safety
+///
+/// This function must not be called while any other processor may be acquiring a
+/// [`Spinlock`][crate::Spinlock].
+pub unsafe fn set_lock_diagnostics_handler(report: fn(&LockDiagnostic), spin_threshold: usize) {
+    // SAFETY:
+    //
+    // The invariants of `set_lock_diagnostics_handler()` ensure that no other accesses to
+    // `HANDLER` overlap.
+    unsafe {
+        *HANDLER.get_mut() = Some(Handler {
+            report,
+            spin_threshold,
+        })
+    }
+}
+
+/// A problem detected while acquiring a [`Spinlock`][crate::Spinlock].
+///
+/// Locks are identified by an address that is unique to each lock.
+#[derive(Clone, Copy, Debug)]
+pub enum LockDiagnostic {
+    /// A processor attempted to acquire a lock that it already holds.
+    Reacquired {
+        /// The address identifying the lock.
+        lock: usize,
+        /// The processor holding the lock.
+        processor: usize,
+        /// The location at which the lock was acquired.
+        holder: &'static Location<'static>,
+        /// The location of the second acquisition.
+        caller: &'static Location<'static>,
+    },
+    /// A processor has been spinning on a lock for a long time.
+    LongSpin {
+        /// The address identifying the lock.
+        lock: usize,
+        /// The processor that is spinning.
+        processor: usize,
+        /// The processor holding the lock, if known.
+        owner: Option<usize>,
+        /// The location at which the lock was acquired, if known.
+        holder: Option<&'static Location<'static>>,
+        /// The location of the spinning acquisition.
+        caller: &'static Location<'static>,
+        /// The number of times the acquisition has spun.
+        spins: usize,
+    },
+    /// A processor acquired two locks in the opposite order of a previous acquisition.
+    #[cfg(feature = "lockdep")]
+    OrderInversion {
+        /// The address identifying the lock that is already held.
+        held: usize,
+        /// The address identifying the lock being acquired.
+        acquiring: usize,
+        /// The processor acquiring the locks.
+        processor: usize,
+        /// The location of the acquisition of `acquiring`.
+        caller: &'static Location<'static>,
+    },
+}
+
+impl fmt::Display for LockDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Reacquired {
+                lock,
+                processor,
+                holder,
+                caller,
+            } => write!(
+                f,
+                "processor {processor} re-acquired lock {lock:#x} at {caller}, which it acquired \
+                 at {holder}"
+            ),
+            Self::LongSpin {
+                lock,
+                processor,
+                owner,
+                holder,
+                caller,
+                spins,
+            } => {
+                write!(
+                    f,
+                    "processor {processor} spun {spins} times on lock {lock:#x} at {caller}"
+                )?;
+                match (owner, holder) {
+                    (Some(owner), Some(holder)) => {
+                        write!(f, ", held by processor {owner} since {holder}")
+                    }
+                    _ => Ok(()),
+                }
+            }
+            #[cfg(feature = "lockdep")]
+            Self::OrderInversion {
+                held,
+                acquiring,
+                processor,
+                caller,
+            } => write!(
+                f,
+                "processor {processor} acquired lock {acquiring:#x} at {caller} while holding lock \
+                 {held:#x}, which was previously acquired after it"
+            ),
+        }
+    }
+}
+
+/// Delivers `diagnostic` to the registered handler.
+///
+/// # Panics
+///
+/// Panics if no handler is registered and `diagnostic` indicates a certain or potential deadlock.
+fn report(diagnostic: LockDiagnostic) {
+    let Some(handler) = HANDLER.copy() else {
+        if matches!(diagnostic, LockDiagnostic::LongSpin { .. }) {
+            return;
+        }
+
+        panic!("{diagnostic}");
+    };
+
+    if REPORTING.swap(true, Ordering::Acquire) {
+        return;
+    }
+
+    (handler.report)(&diagnostic);
+    REPORTING.store(false, Ordering::Release);
+}
+
+/// The diagnostic state of a [`RawSpinlock`][crate::RawSpinlock].
+#[derive(Debug)]
+pub(crate) struct LockState {
+    /// One more than the identifier of the processor holding the lock, or zero if the lock is not
+    /// held.
+    owner: AtomicUsize,
+    /// The location at which the lock was acquired, or null if the lock is not held.
+    location: AtomicPtr<Location<'static>>,
+}
+
+impl LockState {
+    /// Creates the [`LockState`] of an unlocked lock.
+    pub(crate) const fn new() -> Self {
+        Self {
+            owner: AtomicUsize::new(0),
+            location: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Returns the address identifying the lock.
+    fn address(&self) -> usize {
+        ptr::from_ref(self).addr()
+    }
+
+    /// Returns the location at which the lock was acquired, if it is held.
+    fn holder(&self) -> Option<&'static Location<'static>> {
+        let location = self.location.load(Ordering::Relaxed);
+
+        // SAFETY:
+        //
+        // `location` is either null or was produced from a `&'static Location<'static>`.
+        unsafe { location.as_ref() }
+    }
+
+    /// Starts an attempt by `caller` to acquire the lock, reporting an attempt to acquire a lock
+    /// that the current processor already holds.
+    pub(crate) fn begin(&self, caller: &'static Location<'static>) -> Acquisition<'_> {
+        let processor = current_processor_id();
+
+        if self.owner.load(Ordering::Relaxed) == processor.wrapping_add(1)
+            && let Some(holder) = self.holder()
+        {
+            report(LockDiagnostic::Reacquired {
+                lock: self.address(),
+                processor,
+                holder,
+                caller,
+            });
+        }
+
+        #[cfg(feature = "lockdep")]
+        lockdep::check_order(self.address(), processor, caller);
+
+        Acquisition {
+            state: self,
+            caller,
+            processor,
+            spins: 0,
+        }
+    }
+
+    /// Records that the lock was acquired by `caller` without spinning.
+    pub(crate) fn acquired(&self, caller: &'static Location<'static>) {
+        Acquisition {
+            state: self,
+            caller,
+            processor: current_processor_id(),
+            spins: 0,
+        }
+        .finish()
+    }
+
+    /// Records that the lock was released.
+    pub(crate) fn release(&self) {
+        self.location.store(ptr::null_mut(), Ordering::Relaxed);
+        self.owner.store(0, Ordering::Relaxed);
+
+        #[cfg(feature = "lockdep")]
+        lockdep::pop(self.address(), current_processor_id());
+    }
+}
+
+/// An in-progress acquisition of a lock.
+pub(crate) struct Acquisition<'a> {
+    /// The [`LockState`] of the lock being acquired.
+    state: &'a LockState,
+    /// The location of the acquisition.
+    caller: &'static Location<'static>,
+    /// The processor acquiring the lock.
+    processor: usize,
+    /// The number of times the acquisition has spun.
+    spins: usize,
+}
+
+impl Acquisition<'_> {
+    /// Records a spin of the acquisition, reporting the acquisition each time it exceeds another
+    /// multiple of the spin threshold.
+    pub(crate) fn spin(&mut self) {
+        self.spins = self.spins.wrapping_add(1);
+
+        let Some(handler) = HANDLER.copy() else {
+            return;
+        };
+        if handler.spin_threshold == 0 || !self.spins.is_multiple_of(handler.spin_threshold) {
+            return;
+        }
+
+        let owner = self.state.owner.load(Ordering::Relaxed).checked_sub(1);
+        report(LockDiagnostic::LongSpin {
+            lock: self.state.address(),
+            processor: self.processor,
+            owner,
+            holder: self.state.holder(),
+            caller: self.caller,
+            spins: self.spins,
+        });
+    }
+
+    /// Records that the lock was acquired.
+    pub(crate) fn finish(self) {
+        self.state
+            .location
+            .store(ptr::from_ref(self.caller).cast_mut(), Ordering::Relaxed);
+        self.state
+            .owner
+            .store(self.processor.wrapping_add(1), Ordering::Relaxed);
+
+        #[cfg(feature = "lockdep")]
+        lockdep::push(self.state.address(), self.processor);
+    }
+}
+
+/// Lock ordering checks.
+#[cfg(feature = "lockdep")]
+mod lockdep {
+    use core::{
+        panic::Location,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::{LockDiagnostic, report};
+    use crate::PerCpu;
+
+    /// The maximum number of processors whose held locks are tracked.
+    const MAX_PROCESSORS: usize = 64;
+    /// The maximum number of locks tracked as held by a single processor.
+    const MAX_HELD: usize = 16;
+    /// The maximum number of lock orderings that are recorded.
+    const MAX_ORDERS: usize = 512;
+
+    /// The locks held by each processor.
+    static HELD: PerCpu<HeldLocks, MAX_PROCESSORS> =
+        PerCpu::new([const { HeldLocks::new() }; MAX_PROCESSORS]);
+
+    /// The first lock of each recorded ordering, or zero if the ordering has not been written yet.
+    static ORDER_FIRST: [AtomicUsize; MAX_ORDERS] = [const { AtomicUsize::new(0) }; MAX_ORDERS];
+    /// The second lock of each recorded ordering.
+    static ORDER_SECOND: [AtomicUsize; MAX_ORDERS] = [const { AtomicUsize::new(0) }; MAX_ORDERS];
+    /// The number of orderings that have been reserved.
+    static ORDER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    /// The locks held by a processor.
+    struct HeldLocks {
+        /// The number of valid entries in [`HeldLocks::locks`].
+        count: AtomicUsize,
+        /// The addresses identifying the held locks, in acquisition order.
+        locks: [AtomicUsize; MAX_HELD],
+    }
+
+    impl HeldLocks {
+        /// Creates an empty [`HeldLocks`].
+        const fn new() -> Self {
+            Self {
+                count: AtomicUsize::new(0),
+                locks: [const { AtomicUsize::new(0) }; MAX_HELD],
+            }
+        }
+
+        /// Returns the addresses identifying the held locks.
+        fn iter(&self) -> impl Iterator<Item = usize> {
+            let count = self.count.load(Ordering::Relaxed).min(MAX_HELD);
+            self.locks[..count]
+                .iter()
+                .map(|lock| lock.load(Ordering::Relaxed))
+        }
+    }
+
+    /// Returns `true` if `first` has been recorded as acquired before `second`.
+    fn ordered(first: usize, second: usize) -> bool {
+        let count = ORDER_COUNT.load(Ordering::Acquire).min(MAX_ORDERS);
+        (0..count).any(|index| {
+            ORDER_FIRST[index].load(Ordering::Acquire) == first
+                && ORDER_SECOND[index].load(Ordering::Relaxed) == second
+        })
+    }
+
+    /// Records that `first` was acquired before `second`.
+    fn record(first: usize, second: usize) {
+        let index = ORDER_COUNT.fetch_add(1, Ordering::Relaxed);
+        if index >= MAX_ORDERS {
+            ORDER_COUNT.store(MAX_ORDERS, Ordering::Relaxed);
+            return;
+        }
+
+        ORDER_SECOND[index].store(second, Ordering::Relaxed);
+        ORDER_FIRST[index].store(first, Ordering::Release);
+    }
+
+    /// Checks the acquisition of `lock` by `processor` against the recorded lock orderings.
+    pub(super) fn check_order(lock: usize, processor: usize, caller: &'static Location<'static>) {
+        let Some(held) = HELD.get_for(processor) else {
+            return;
+        };
+
+        for held in held.iter().filter(|&held| held != lock) {
+            if ordered(lock, held) {
+                report(LockDiagnostic::OrderInversion {
+                    held,
+                    acquiring: lock,
+                    processor,
+                    caller,
+                });
+            } else if !ordered(held, lock) {
+                record(held, lock);
+            }
+        }
+    }
+
+    /// Records that `processor` holds `lock`.
+    pub(super) fn push(lock: usize, processor: usize) {
+        let Some(held) = HELD.get_for(processor) else {
+            return;
+        };
+
+        let count = held.count.load(Ordering::Relaxed);
+        if let Some(slot) = held.locks.get(count) {
+            slot.store(lock, Ordering::Relaxed);
+        }
+        held.count.store(count.saturating_add(1), Ordering::Relaxed);
+    }
+
+    /// Records that `processor` no longer holds `lock`.
+    pub(super) fn pop(lock: usize, processor: usize) {
+        let Some(held) = HELD.get_for(processor) else {
+            return;
+        };
+
+        let count = held.count.load(Ordering::Relaxed);
+        if count > MAX_HELD {
+            held.count.store(count - 1, Ordering::Relaxed);
+            return;
+        }
+
+        // Locks may be released out of order, so remove `lock` wherever it is and shift the
+        // locks acquired after it down.
+        let Some(position) = held.iter().position(|held| held == lock) else {
+            return;
+        };
+        for index in position..count - 1 {
+            let next = held.locks[index + 1].load(Ordering::Relaxed);
+            held.locks[index].store(next, Ordering::Relaxed);
+        }
+        held.count.store(count - 1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Spinlock;
+
+    #[test]
+    #[should_panic(expected = "re-acquired lock")]
+    fn reacquisition_is_detected() {
+        let lock = Spinlock::new(());
+
+        let _guard = lock.lock();
+        let _second = lock.lock();
+    }
+
+    #[test]
+    #[cfg(feature = "lockdep")]
+    #[should_panic(expected = "previously acquired after it")]
+    fn order_inversion_is_detected() {
+        static FIRST: Spinlock<()> = Spinlock::new(());
+        static SECOND: Spinlock<()> = Spinlock::new(());
+
+        {
+            let _first = FIRST.lock();
+            let _second = SECOND.lock();
+        }
+
+        let _second = SECOND.lock();
+        let _first = FIRST.lock();
+    }
+}
@@ -0,0 +1,169 @@
+//! Guards that keep local interrupts disabled while a lock is held.
+//!
+//! A lock that is acquired both by regular code and by an interrupt handler deadlocks if the
+//! interrupt arrives on a processor that already holds the lock. Acquiring such locks through
+//! [`Spinlock::lock_irq()`][crate::Spinlock::lock_irq] or
+//! [`TicketLock::lock_irq()`][crate::TicketLock::lock_irq] prevents this.
+
+use core::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+/// A RAII guard that disables interrupts on the current processor and restores their previous
+/// state when dropped.
+///
+/// [`InterruptGuard`]s may be nested: only the outermost guard re-enables interrupts.
+#[must_use = "interrupts are restored when the guard is dropped"]
+pub struct InterruptGuard {
+    /// The architecture specific interrupt state prior to the creation of this guard.
+    saved: usize,
+    /// Prevents the guard from restoring the interrupt state of a different processor.
+    _not_send: PhantomData<*mut ()>,
+}
+
+impl InterruptGuard {
+    /// Disables interrupts on the current processor until the returned guard is dropped.
+    pub fn new() -> Self {
+        Self {
+            saved: arch::save_and_disable(),
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns `true` if interrupts were enabled when this guard was created.
+    pub fn were_enabled(&self) -> bool {
+        arch::enabled_in(self.saved)
+    }
+}
+
+impl Default for InterruptGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        // SAFETY:
+        //
+        // `saved` was produced by `save_and_disable()` on this processor, as the guard cannot be
+        // sent to another processor.
+        unsafe { arch::restore(self.saved) }
+    }
+}
+
+/// A lock guard that keeps interrupts disabled on the current processor for as long as it is
+/// held.
+///
+/// The lock is released before interrupts are restored.
+#[must_use = "lock guard must be held to access protected data"]
+pub struct InterruptsDisabled<G> {
+    /// The guard of the held lock.
+    guard: G,
+    /// The guard restoring the interrupt state once [`InterruptsDisabled::guard`] is dropped.
+    _interrupts: InterruptGuard,
+}
+
+impl<G> InterruptsDisabled<G> {
+    /// Combines `guard`, which must have been acquired while `interrupts` was held, with
+    /// `interrupts`.
+    pub(crate) fn new(interrupts: InterruptGuard, guard: G) -> Self {
+        Self {
+            guard,
+            _interrupts: interrupts,
+        }
+    }
+}
+
+impl<G: Deref> Deref for InterruptsDisabled<G> {
+    type Target = G::Target;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<G: DerefMut> DerefMut for InterruptsDisabled<G> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+/// Implementation of interrupt control for `x86` and `x86_64`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod arch {
+    use core::arch::asm;
+
+    /// The interrupt enable flag of the `FLAGS` register.
+    const INTERRUPT_FLAG: usize = 1 << 9;
+
+    /// Disables interrupts and returns the previous value of the `FLAGS` register.
+    pub fn save_and_disable() -> usize {
+        let flags: usize;
+
+        // SAFETY:
+        //
+        // Reading the `FLAGS` register and disabling interrupts does not violate memory safety.
+        unsafe { asm!("pushf", "pop {}", "cli", out(reg) flags) }
+
+        flags
+    }
+
+    /// Returns `true` if `saved` indicates that interrupts were enabled.
+    pub fn enabled_in(saved: usize) -> bool {
+        saved & INTERRUPT_FLAG != 0
+    }
+
+    /// Re-enables interrupts if they were enabled in `saved`.
+    ///
+    /// # Safety
+    ///
+    /// `saved` must have been returned by [`save_and_disable()`] on the current processor.
+    pub unsafe fn restore(saved: usize) {
+        if enabled_in(saved) {
+            // SAFETY:
+            //
+            // Interrupts were enabled before the matching call to `save_and_disable()`.
+            unsafe { asm!("sti", options(nostack)) }
+        }
+    }
+}
+
+/// Implementation of interrupt control for `aarch64`.
+#[cfg(target_arch = "aarch64")]
+mod arch {
+    use core::arch::asm;
+
+    /// The IRQ and FIQ mask bits of the `DAIF` register.
+    const IRQ_FIQ_MASK: usize = 0b11 << 6;
+
+    /// Masks IRQs and FIQs and returns the previous value of the `DAIF` register.
+    pub fn save_and_disable() -> usize {
+        let daif: usize;
+
+        // SAFETY:
+        //
+        // Reading the `DAIF` register and masking interrupts does not violate memory safety.
+        unsafe { asm!("mrs {}, daif", "msr daifset, #3", out(reg) daif, options(nostack)) }
+
+        daif
+    }
+
+    /// Returns `true` if `saved` indicates that interrupts were enabled.
+    pub fn enabled_in(saved: usize) -> bool {
+        saved & IRQ_FIQ_MASK == 0
+    }
+
+    /// Restores the `DAIF` register to `saved`.
+    ///
+    /// # Safety
+    ///
+    /// `saved` must have been returned by [`save_and_disable()`] on the current processor.
+    pub unsafe fn restore(saved: usize) {
+        // SAFETY:
+        //
+        // `saved` is the value of `DAIF` before the matching call to `save_and_disable()`.
+        unsafe { asm!("msr daif, {}", in(reg) saved, options(nostack)) }
+    }
+}
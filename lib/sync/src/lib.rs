@@ -3,7 +3,23 @@
 #![no_std]
 
 mod controlled_modification;
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+mod interrupts;
+mod once;
+mod per_cpu;
+mod rwlock;
 mod spinlock;
+mod ticket;
 
 pub use controlled_modification::ControlledModificationCell;
+#[cfg(feature = "diagnostics")]
+pub use diagnostics::{LockDiagnostic, set_lock_diagnostics_handler};
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+pub use interrupts::{InterruptGuard, InterruptsDisabled};
+pub use once::{Lazy, Once};
+pub use per_cpu::{PerCpu, current_processor_id, processor_count, set_processor_id_source};
+pub use rwlock::{RawRwLock, RwLock, RwLockAcquisitionError, RwLockReadGuard, RwLockWriteGuard};
 pub use spinlock::{RawSpinlock, Spinlock, SpinlockAcquisitionError, SpinlockGuard};
+pub use ticket::{RawTicketLock, TicketLock, TicketLockAcquisitionError, TicketLockGuard};
@@ -0,0 +1,246 @@
+//! One-time initialization primitives that are usable before any other subsystem is available.
+//!
+//! Neither [`Once`] nor [`Lazy`] allocate or depend on processor identification, so they can be
+//! used from the earliest stages of boot. A context that observes initialization in progress on
+//! another processor spins until it completes.
+
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    mem::MaybeUninit,
+    ops::Deref,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+/// The value of [`Once::state`] before initialization has started.
+const INCOMPLETE: u8 = 0;
+/// The value of [`Once::state`] while a context is initializing the value.
+const RUNNING: u8 = 1;
+/// The value of [`Once::state`] after the value has been initialized.
+const COMPLETE: u8 = 2;
+
+/// A cell that is written exactly once and can be read from any context afterwards.
+///
+/// A context must not attempt to initialize a [`Once`] from within its own initializer: doing so
+/// spins forever. Likewise, if an initializer panics, every context waiting on the [`Once`] spins
+/// forever.
+pub struct Once<T> {
+    /// The initialization state of [`Once::value`].
+    state: AtomicU8,
+    /// The value, initialized when [`Once::state`] is [`COMPLETE`].
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// SAFETY:
+// The value is only mutated once, before any shared references to it are created, so `Once<T>`
+// may be shared between threads as long as `T` can be sent to and shared between threads.
+unsafe impl<T: Send + Sync> Sync for Once<T> {}
+
+// SAFETY:
+// Nothing about `Once<T>` changes whether it is safe to send `T` across threads.
+unsafe impl<T: Send> Send for Once<T> {}
+
+impl<T> Once<T> {
+    /// Creates a new, uninitialized [`Once`].
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(INCOMPLETE),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Creates a new [`Once`] that has already been initialized with `value`.
+    pub const fn with_value(value: T) -> Self {
+        Self {
+            state: AtomicU8::new(COMPLETE),
+            value: UnsafeCell::new(MaybeUninit::new(value)),
+        }
+    }
+
+    /// Returns the stored value, initializing it with `init` if this [`Once`] has not been
+    /// initialized.
+    ///
+    /// If another context is currently initializing this [`Once`], this function spins until that
+    /// initialization completes.
+    pub fn call_once<F: FnOnce() -> T>(&self, init: F) -> &T {
+        if self
+            .state
+            .compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+            .is_ok()
+        {
+            let value = init();
+
+            // SAFETY:
+            // This context won the transition to `RUNNING`, so no other context accesses `value`
+            // until `state` is set to `COMPLETE`.
+            unsafe { (*self.value.get()).write(value) };
+            self.state.store(COMPLETE, Ordering::Release);
+        }
+
+        self.wait()
+    }
+
+    /// Initializes this [`Once`] with `value`.
+    ///
+    /// # Errors
+    /// If this [`Once`] has already been initialized or is being initialized, `value` is returned.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        if self
+            .state
+            .compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return Err(value);
+        }
+
+        // SAFETY:
+        // This context won the transition to `RUNNING`, so no other context accesses `value` until
+        // `state` is set to `COMPLETE`.
+        unsafe { (*self.value.get()).write(value) };
+        self.state.store(COMPLETE, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Returns the stored value, or [`None`] if this [`Once`] has not finished initialization.
+    pub fn get(&self) -> Option<&T> {
+        if self.state.load(Ordering::Acquire) != COMPLETE {
+            return None;
+        }
+
+        // SAFETY:
+        // `state` is `COMPLETE`, so `value` is never mutated again.
+        let value = unsafe { &*self.value.get() };
+        // SAFETY:
+        // `state` is `COMPLETE`, so `value` is initialized.
+        Some(unsafe { value.assume_init_ref() })
+    }
+
+    /// Returns the stored value, spinning until another context finishes initializing it.
+    pub fn wait(&self) -> &T {
+        loop {
+            if let Some(value) = self.get() {
+                return value;
+            }
+
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Returns `true` if this [`Once`] has been initialized.
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+}
+
+impl<T> Default for Once<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for Once<T> {
+    fn drop(&mut self) {
+        if *self.state.get_mut() == COMPLETE {
+            // SAFETY:
+            // `state` is `COMPLETE`, so `value` is initialized.
+            unsafe { self.value.get_mut().assume_init_drop() }
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Once<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(value) => f.debug_tuple("Once").field(value).finish(),
+            None => f.write_str("Once(<uninitialized>)"),
+        }
+    }
+}
+
+/// A value that is initialized by `F` the first time it is accessed.
+pub struct Lazy<T, F = fn() -> T> {
+    /// The storage of the initialized value.
+    once: Once<T>,
+    /// The initializer, taken by the context that initializes [`Lazy::once`].
+    init: UnsafeCell<Option<F>>,
+}
+
+// SAFETY:
+// `init` is only accessed by the single context that wins the initialization of `once`, so it is
+// sufficient for `F` to be `Send`.
+unsafe impl<T: Send + Sync, F: Send> Sync for Lazy<T, F> {}
+
+impl<T, F> Lazy<T, F> {
+    /// Creates a new [`Lazy`] that is initialized by `init`.
+    pub const fn new(init: F) -> Self {
+        Self {
+            once: Once::new(),
+            init: UnsafeCell::new(Some(init)),
+        }
+    }
+}
+
+impl<T, F: FnOnce() -> T> Lazy<T, F> {
+    /// Returns the value of `this`, initializing it if necessary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the initializer of `this` panics.
+    pub fn force(this: &Self) -> &T {
+        this.once.call_once(|| {
+            // SAFETY:
+            // Only the context that won the initialization of `once` runs this closure, so it has
+            // exclusive access to `init`.
+            let init = unsafe { (*this.init.get()).take() };
+            init.expect("initializer of Lazy instance was already taken")()
+        })
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for Lazy<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        Self::force(self)
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for Lazy<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.once.get() {
+            Some(value) => f.debug_tuple("Lazy").field(value).finish(),
+            None => f.write_str("Lazy(<uninitialized>)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::sync::atomic::AtomicUsize;
+
+    use super::*;
+
+    #[test]
+    fn initializes_once() {
+        let once = Once::new();
+        assert_eq!(once.get(), None);
+
+        assert_eq!(*once.call_once(|| 1), 1);
+        assert_eq!(*once.call_once(|| 2), 1);
+        assert_eq!(once.set(3), Err(3));
+        assert_eq!(once.get(), Some(&1));
+        assert!(once.is_completed());
+    }
+
+    #[test]
+    fn lazy_runs_initializer_on_first_access() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        static VALUE: Lazy<usize> = Lazy::new(|| CALLS.fetch_add(1, Ordering::Relaxed) + 10);
+
+        assert_eq!(CALLS.load(Ordering::Relaxed), 0);
+        assert_eq!(*VALUE, 10);
+        assert_eq!(*VALUE, 10);
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,152 @@
+//! Storage with a separate instance of a value for each processor.
+//!
+//! The identifier of the current processor is obtained through the function registered with
+//! [`set_processor_id_source()`], along with the number of processors that it identifies. Until a
+//! source is registered, every context is treated as running on processor 0 of 1, which matches
+//! the single processor environment of early boot.
+
+use core::fmt;
+
+use crate::ControlledModificationCell;
+
+/// The function that returns the identifier of the current processor and the number of
+/// processors that it identifies.
+static PROCESSOR_ID_SOURCE: ControlledModificationCell<(fn() -> usize, usize)> =
+    ControlledModificationCell::new((boot_processor_id, 1));
+
+/// Registers `source` as the function returning the identifier of the current processor, where
+/// identifiers are less than `processor_count`.
+///
+/// # Safety
+///
+/// - This function must be called before any processor other than the calling processor is
+///   running, and must not overlap with any access to a [`PerCpu`].
+/// - `source` must return a distinct identifier on each processor, and must return the same
+///   identifier for the lifetime of every context running on a processor.
+/// - `source` must not panic.
+pub unsafe fn set_processor_id_source(source: fn() -> usize, processor_count: usize) {
+    // SAFETY:
+    //
+    // The invariants of `set_processor_id_source()` ensure that no other accesses to
+    // `PROCESSOR_ID_SOURCE` overlap.
+    unsafe { *PROCESSOR_ID_SOURCE.get_mut() = (source, processor_count) }
+}
+
+/// Returns the identifier of the current processor.
+pub fn current_processor_id() -> usize {
+    (PROCESSOR_ID_SOURCE.copy().0)()
+}
+
+/// Returns the number of processors identified by the registered processor identifier source.
+pub fn processor_count() -> usize {
+    PROCESSOR_ID_SOURCE.copy().1
+}
+
+/// The processor identifier source used before [`set_processor_id_source()`] is called.
+fn boot_processor_id() -> usize {
+    0
+}
+
+/// A separate instance of `T` for each of up to `N` processors.
+///
+/// Since each processor only accesses its own instance through [`PerCpu::get()`], `T` need not be
+/// [`Sync`]. Accessing the instances of other processors requires `T` to be [`Sync`].
+pub struct PerCpu<T, const N: usize> {
+    /// The instance of each processor, indexed by processor identifier.
+    values: [T; N],
+}
+
+// SAFETY:
+// Without `T: Sync`, each instance is only accessed from the processor that owns it, so sharing
+// `PerCpu<T, N>` only requires that the instances may be created on one processor and used on
+// another.
+unsafe impl<T: Send, const N: usize> Sync for PerCpu<T, N> {}
+
+impl<T, const N: usize> PerCpu<T, N> {
+    /// Creates a new [`PerCpu`] with the provided instance for each processor.
+    pub const fn new(values: [T; N]) -> Self {
+        Self { values }
+    }
+
+    /// Returns the instance belonging to the current processor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the identifier of the current processor is not less than [`processor_count()`]
+    /// or `N`.
+    pub fn get(&self) -> &T {
+        let id = current_processor_id();
+        let count = processor_count();
+        assert!(
+            id < count,
+            "processor {id} is out of range for {count} processors"
+        );
+
+        self.values
+            .get(id)
+            .unwrap_or_else(|| panic!("processor {id} exceeds per-CPU capacity of {N}"))
+    }
+
+    /// Returns the instance belonging to the processor identified by `id`, or [`None`] if `id` is
+    /// not less than [`processor_count()`] or `N`.
+    pub fn get_for(&self, id: usize) -> Option<&T>
+    where
+        T: Sync,
+    {
+        if id >= processor_count() {
+            return None;
+        }
+
+        self.values.get(id)
+    }
+
+    /// Returns an iterator over the instances of all processors.
+    pub fn iter(&self) -> core::slice::Iter<'_, T>
+    where
+        T: Sync,
+    {
+        self.values.iter()
+    }
+
+    /// Returns mutable access to the instances of all processors.
+    ///
+    /// Since this call borrows the [`PerCpu`] mutably, no processor can be accessing its
+    /// instance.
+    pub fn get_mut(&mut self) -> &mut [T; N] {
+        &mut self.values
+    }
+}
+
+impl<T: fmt::Debug + Sync, const N: usize> fmt::Debug for PerCpu<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn boot_processor_uses_first_instance() {
+        let per_cpu = PerCpu::new([const { Cell::new(0u32) }; 4]);
+
+        per_cpu.get().set(5);
+        assert_eq!(per_cpu.get().get(), 5);
+
+        let mut per_cpu = per_cpu;
+        let values = per_cpu.get_mut().each_mut().map(|value| value.get());
+        assert_eq!(values, [5, 0, 0, 0]);
+    }
+
+    #[test]
+    fn identifiers_beyond_processor_count_are_rejected() {
+        let per_cpu = PerCpu::new([const { 0u32 }; 4]);
+
+        assert_eq!(per_cpu.get_for(0), Some(&0));
+        assert_eq!(per_cpu.get_for(1), None);
+        assert_eq!(per_cpu.get_for(4), None);
+    }
+}
@@ -0,0 +1,341 @@
+//! Spinning reader-writer lock implementation.
+
+use core::{
+    cell::UnsafeCell,
+    error, fmt,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Set while a writer holds the [`RawRwLock`].
+const WRITER: usize = 0b01;
+/// Set while a writer is waiting for the [`RawRwLock`], which prevents new readers from acquiring
+/// it.
+const WRITER_WAITING: usize = 0b10;
+/// The amount added to the state for each reader holding the [`RawRwLock`].
+const READER: usize = 0b100;
+
+/// The locking component of a [`RwLock`].
+///
+/// Waiting writers take priority over new readers, so a context that already holds a read lock
+/// must not attempt to acquire another one while a writer may be waiting.
+#[derive(Debug)]
+pub struct RawRwLock {
+    /// The reader count, shifted past the [`WRITER`] and [`WRITER_WAITING`] bits.
+    state: AtomicUsize,
+}
+
+impl RawRwLock {
+    /// Creates a new [`RawRwLock`] in the unlocked state.
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicUsize::new(0),
+        }
+    }
+
+    /// Acquires shared access to the [`RawRwLock`], spinning until it is available.
+    pub fn read(&self) {
+        while self.try_read().is_err() {
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Attempts to acquire shared access to the [`RawRwLock`].
+    ///
+    /// This function does not spin or block.
+    ///
+    /// # Errors
+    /// If a writer holds or is waiting for the [`RawRwLock`], then this call will return an
+    /// [`Err`].
+    ///
+    /// # Panics
+    /// Panics if the number of readers would overflow.
+    pub fn try_read(&self) -> Result<(), RwLockAcquisitionError> {
+        let state = self.state.load(Ordering::Relaxed);
+        if state & (WRITER | WRITER_WAITING) != 0 {
+            return Err(RwLockAcquisitionError);
+        }
+
+        let new_state = state.checked_add(READER).expect("too many readers");
+        self.state
+            .compare_exchange(state, new_state, Ordering::Acquire, Ordering::Relaxed)
+            .map(|_| ())
+            .map_err(|_| RwLockAcquisitionError)
+    }
+
+    /// Releases shared access to the [`RawRwLock`].
+    pub fn read_unlock(&self) {
+        self.state.fetch_sub(READER, Ordering::Release);
+    }
+
+    /// Acquires exclusive access to the [`RawRwLock`], spinning until it is available.
+    pub fn write(&self) {
+        let mut state = self.state.load(Ordering::Relaxed);
+
+        loop {
+            if state & !WRITER_WAITING == 0 {
+                match self.state.compare_exchange_weak(
+                    state,
+                    WRITER,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => break,
+                    Err(current) => state = current,
+                }
+            } else {
+                if state & WRITER_WAITING == 0 {
+                    self.state.fetch_or(WRITER_WAITING, Ordering::Relaxed);
+                }
+
+                core::hint::spin_loop();
+                state = self.state.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Attempts to acquire exclusive access to the [`RawRwLock`].
+    ///
+    /// This function does not spin or block.
+    ///
+    /// # Errors
+    /// If the [`RawRwLock`] is held by a reader or a writer, then this call will return an
+    /// [`Err`].
+    pub fn try_write(&self) -> Result<(), RwLockAcquisitionError> {
+        let state = self.state.load(Ordering::Relaxed);
+        if state & !WRITER_WAITING != 0 {
+            return Err(RwLockAcquisitionError);
+        }
+
+        self.state
+            .compare_exchange(state, WRITER, Ordering::Acquire, Ordering::Relaxed)
+            .map(|_| ())
+            .map_err(|_| RwLockAcquisitionError)
+    }
+
+    /// Releases exclusive access to the [`RawRwLock`].
+    pub fn write_unlock(&self) {
+        self.state.fetch_and(!WRITER, Ordering::Release);
+    }
+}
+
+impl Default for RawRwLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A reader-writer lock useful for protecting shared data that is read far more often than it is
+/// written.
+pub struct RwLock<T: ?Sized> {
+    /// The lock.
+    lock: RawRwLock,
+    /// The value protected by the [`RwLock`].
+    value: UnsafeCell<T>,
+}
+
+// SAFETY:
+// Nothing about `RwLock<T>` changes whether it
+// is safe to send `T` across threads.
+unsafe impl<T: ?Sized + Send> Send for RwLock<T> {}
+
+// SAFETY:
+// Multiple readers may access `T` simultaneously from different threads, so `T` must be `Sync`,
+// while a writer may mutate `T` from any thread, so `T` must be `Send`.
+unsafe impl<T: ?Sized + Send + Sync> Sync for RwLock<T> {}
+
+impl<T> RwLock<T> {
+    /// Creates a new [`RwLock`] in an unlocked state ready for use.
+    pub const fn new(value: T) -> Self {
+        Self {
+            lock: RawRwLock::new(),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes this [`RwLock`], returning the underlying data.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> RwLock<T> {
+    /// Acquires shared access to the [`RwLock`], spinning until it is available.
+    ///
+    /// A RAII guard is returned which releases the shared access when dropped.
+    pub fn read<'a>(&'a self) -> RwLockReadGuard<'a, T> {
+        self.lock.read();
+
+        RwLockReadGuard {
+            lock: &self.lock,
+            value: &self.value,
+        }
+    }
+
+    /// Attempts to acquire shared access to the [`RwLock`].
+    ///
+    /// This function does not block.
+    ///
+    /// # Errors
+    /// If a writer holds or is waiting for the [`RwLock`], then this call will return an [`Err`].
+    pub fn try_read<'a>(&'a self) -> Result<RwLockReadGuard<'a, T>, RwLockAcquisitionError> {
+        self.lock.try_read().map(|()| RwLockReadGuard {
+            lock: &self.lock,
+            value: &self.value,
+        })
+    }
+
+    /// Acquires exclusive access to the [`RwLock`], spinning until it is available.
+    ///
+    /// A RAII guard is returned which releases the exclusive access when dropped.
+    pub fn write<'a>(&'a self) -> RwLockWriteGuard<'a, T> {
+        self.lock.write();
+
+        RwLockWriteGuard {
+            lock: &self.lock,
+            value: &self.value,
+        }
+    }
+
+    /// Attempts to acquire exclusive access to the [`RwLock`].
+    ///
+    /// This function does not block.
+    ///
+    /// # Errors
+    /// If the [`RwLock`] is held by a reader or a writer, then this call will return an [`Err`].
+    pub fn try_write<'a>(&'a self) -> Result<RwLockWriteGuard<'a, T>, RwLockAcquisitionError> {
+        self.lock.try_write().map(|()| RwLockWriteGuard {
+            lock: &self.lock,
+            value: &self.value,
+        })
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// Since this call borrows the [`RwLock`] mutably, no actual locking needs to take place: the
+    /// mutable borrow statically guarantees no locks exist.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+/// A RAII implementation of shared access to a [`RwLock`]. When this structure is dropped, the
+/// shared access is released.
+///
+/// This structure is created by the [`RwLock::read()`] and [`RwLock::try_read()`] methods.
+#[must_use = "lock guard must be held to access protected data"]
+pub struct RwLockReadGuard<'a, T: ?Sized> {
+    /// The [`RawRwLock`] guarding [`RwLockReadGuard::value`].
+    lock: &'a RawRwLock,
+    /// The value to which this [`RwLockReadGuard`] provides access.
+    value: &'a UnsafeCell<T>,
+}
+
+impl<T: ?Sized> Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        let value_ptr = self.value.get();
+
+        // SAFETY:
+        // No writer can access the value pointed to by `value_ptr` while this guard exists.
+        unsafe { &*value_ptr }
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.read_unlock();
+    }
+}
+
+/// A RAII implementation of exclusive access to a [`RwLock`]. When this structure is dropped, the
+/// exclusive access is released.
+///
+/// This structure is created by the [`RwLock::write()`] and [`RwLock::try_write()`] methods.
+#[must_use = "lock guard must be held to access protected data"]
+pub struct RwLockWriteGuard<'a, T: ?Sized> {
+    /// The [`RawRwLock`] guarding [`RwLockWriteGuard::value`].
+    lock: &'a RawRwLock,
+    /// The value to which this [`RwLockWriteGuard`] provides access.
+    value: &'a UnsafeCell<T>,
+}
+
+impl<T: ?Sized> Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        let value_ptr = self.value.get();
+
+        // SAFETY:
+        // We have exclusive access to the value pointed to by `value_ptr`.
+        unsafe { &*value_ptr }
+    }
+}
+
+impl<T: ?Sized> DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let value_ptr = self.value.get();
+
+        // SAFETY:
+        // We have exclusive access to the value pointed to by `value_ptr`.
+        unsafe { &mut *value_ptr }
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.write_unlock();
+    }
+}
+
+/// Represents the failure to acquire a [`RwLock`].
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RwLockAcquisitionError;
+
+impl fmt::Display for RwLockAcquisitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("failed to acquire reader-writer lock because it is held or contended")
+    }
+}
+
+impl error::Error for RwLockAcquisitionError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn readers_share_and_writers_exclude() {
+        let lock = RwLock::new(5u32);
+
+        let first = lock.read();
+        let second = lock.try_read().unwrap();
+        assert_eq!(*first + *second, 10);
+        assert!(lock.try_write().is_err());
+        drop((first, second));
+
+        let mut writer = lock.try_write().unwrap();
+        *writer = 7;
+        assert!(lock.try_read().is_err());
+        assert!(lock.try_write().is_err());
+        drop(writer);
+
+        assert_eq!(*lock.read(), 7);
+    }
+
+    #[test]
+    fn waiting_writer_blocks_new_readers() {
+        let lock = RawRwLock::new();
+
+        lock.read();
+        lock.state.fetch_or(WRITER_WAITING, Ordering::Relaxed);
+        assert!(lock.try_read().is_err());
+
+        lock.read_unlock();
+        assert!(lock.try_write().is_ok());
+        assert_eq!(lock.state.load(Ordering::Relaxed), WRITER);
+        lock.write_unlock();
+        assert!(lock.try_read().is_ok());
+    }
+}
@@ -7,11 +7,19 @@ use core::{
     sync::atomic::{AtomicBool, Ordering},
 };
 
+#[cfg(feature = "diagnostics")]
+use crate::diagnostics::LockState;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+use crate::{InterruptGuard, InterruptsDisabled};
+
 /// The locking component of a [`Spinlock`].
 #[derive(Debug)]
 pub struct RawSpinlock {
     /// The lock.
     lock: AtomicBool,
+    /// The owner and acquisition location of the lock.
+    #[cfg(feature = "diagnostics")]
+    diagnostics: LockState,
 }
 
 impl RawSpinlock {
@@ -19,13 +27,18 @@ impl RawSpinlock {
     pub const fn new() -> Self {
         Self {
             lock: AtomicBool::new(false),
+            #[cfg(feature = "diagnostics")]
+            diagnostics: LockState::new(),
         }
     }
 
     /// Locks the [`RawSpinlock`], spinning until the lock is acquired.
     ///
     /// This function does not return until the lock has been acquired.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
     pub fn lock(&self) {
+        #[cfg(feature = "diagnostics")]
+        let mut acquisition = self.diagnostics.begin(core::panic::Location::caller());
         let mut was_locked = self.lock.load(Ordering::Relaxed);
 
         loop {
@@ -41,8 +54,13 @@ impl RawSpinlock {
                 was_locked = self.lock.load(Ordering::Relaxed);
             }
 
+            #[cfg(feature = "diagnostics")]
+            acquisition.spin();
             core::hint::spin_loop();
         }
+
+        #[cfg(feature = "diagnostics")]
+        acquisition.finish();
     }
 
     /// Attempts to lock the [`RawSpinlock`].
@@ -51,12 +69,16 @@ impl RawSpinlock {
     ///
     /// # Errors
     /// If the [`RawSpinlock`] was already locked, then this call will return an [`Err`].
+    #[cfg_attr(feature = "diagnostics", track_caller)]
     pub fn try_lock(&self) -> Result<(), SpinlockAcquisitionError> {
         if self
             .lock
             .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
             .is_ok()
         {
+            #[cfg(feature = "diagnostics")]
+            self.diagnostics.acquired(core::panic::Location::caller());
+
             Ok(())
         } else {
             Err(SpinlockAcquisitionError)
@@ -65,6 +87,9 @@ impl RawSpinlock {
 
     /// Unlocks the [`RawSpinlock`].
     pub fn unlock(&self) {
+        #[cfg(feature = "diagnostics")]
+        self.diagnostics.release();
+
         self.lock.store(false, Ordering::Release);
     }
 }
@@ -114,6 +139,7 @@ impl<T: ?Sized> Spinlock<T> {
     /// This function will spin until the lock is available. Upon returning, this context is the
     /// only context with the lock held. A RAII guard is returned to allow for scoped unlock of the
     /// [`Spinlock`].
+    #[cfg_attr(feature = "diagnostics", track_caller)]
     pub fn lock<'a>(&'a self) -> SpinlockGuard<'a, T> {
         self.lock.lock();
 
@@ -123,6 +149,17 @@ impl<T: ?Sized> Spinlock<T> {
         }
     }
 
+    /// Disables interrupts on the current processor, then acquires the [`Spinlock`].
+    ///
+    /// Interrupts remain disabled until the returned guard is dropped, which prevents an interrupt
+    /// handler on this processor from deadlocking on the [`Spinlock`].
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn lock_irq<'a>(&'a self) -> InterruptsDisabled<SpinlockGuard<'a, T>> {
+        let interrupts = InterruptGuard::new();
+        InterruptsDisabled::new(interrupts, self.lock())
+    }
+
     /// Attempts to acquire this [`Spinlock`].
     ///
     /// If the lock could not be acquired, then [`Err`] is returned. Otherwise, a RAII guard is
@@ -133,6 +170,7 @@ impl<T: ?Sized> Spinlock<T> {
     /// # Errors
     /// If the [`Spinlock`] could not be acquire because it is already locked, then this call will
     /// return an [`Err`].
+    #[cfg_attr(feature = "diagnostics", track_caller)]
     pub fn try_lock<'a>(&'a self) -> Result<SpinlockGuard<'a, T>, SpinlockAcquisitionError> {
         self.lock.try_lock().map(|()| SpinlockGuard {
             lock: &self.lock,
@@ -0,0 +1,260 @@
+//! Fair ticket lock implementation.
+
+use core::{
+    cell::UnsafeCell,
+    error, fmt,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+use crate::{InterruptGuard, InterruptsDisabled};
+
+/// The locking component of a [`TicketLock`].
+///
+/// Contexts acquire a [`RawTicketLock`] in the order in which they started waiting for it, which
+/// prevents a context from being starved by others repeatedly acquiring the lock.
+#[derive(Debug)]
+pub struct RawTicketLock {
+    /// The ticket that will be handed out to the next context that attempts to acquire the lock.
+    next: AtomicUsize,
+    /// The ticket of the context that currently holds the lock.
+    serving: AtomicUsize,
+}
+
+impl RawTicketLock {
+    /// Creates a new [`RawTicketLock`] in the unlocked state.
+    pub const fn new() -> Self {
+        Self {
+            next: AtomicUsize::new(0),
+            serving: AtomicUsize::new(0),
+        }
+    }
+
+    /// Locks the [`RawTicketLock`], spinning until the lock is acquired.
+    ///
+    /// This function does not return until the lock has been acquired.
+    pub fn lock(&self) {
+        let ticket = self.next.fetch_add(1, Ordering::Relaxed);
+
+        while self.serving.load(Ordering::Acquire) != ticket {
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Attempts to lock the [`RawTicketLock`].
+    ///
+    /// This function does not spin or block.
+    ///
+    /// # Errors
+    /// If the [`RawTicketLock`] was already locked or contended, then this call will return an
+    /// [`Err`].
+    pub fn try_lock(&self) -> Result<(), TicketLockAcquisitionError> {
+        let serving = self.serving.load(Ordering::Relaxed);
+
+        self.next
+            .compare_exchange(
+                serving,
+                serving.wrapping_add(1),
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .map(|_| ())
+            .map_err(|_| TicketLockAcquisitionError)
+    }
+
+    /// Unlocks the [`RawTicketLock`], handing it to the next waiting context.
+    pub fn unlock(&self) {
+        // Only the holder of the lock modifies `serving`, so a load and store suffices.
+        let serving = self.serving.load(Ordering::Relaxed);
+        self.serving
+            .store(serving.wrapping_add(1), Ordering::Release);
+    }
+
+    /// Returns `true` if the [`RawTicketLock`] is currently held.
+    pub fn is_locked(&self) -> bool {
+        self.next.load(Ordering::Relaxed) != self.serving.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for RawTicketLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A fair mutual exclusion primitive useful for protecting shared data.
+pub struct TicketLock<T: ?Sized> {
+    /// The lock.
+    lock: RawTicketLock,
+    /// The value protected by the [`TicketLock`].
+    value: UnsafeCell<T>,
+}
+
+// SAFETY:
+// Nothing about `TicketLock<T>` changes whether it
+// is safe to send `T` across threads.
+unsafe impl<T: ?Sized + Send> Send for TicketLock<T> {}
+
+// SAFETY:
+// If `T` is safe to send across threads, then `TicketLock<T>`
+// makes it safe to access from multiple threads simultaneously.
+unsafe impl<T: ?Sized + Send> Sync for TicketLock<T> {}
+
+impl<T> TicketLock<T> {
+    /// Creates a new [`TicketLock`] in an unlocked state ready for use.
+    pub const fn new(value: T) -> Self {
+        Self {
+            lock: RawTicketLock::new(),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes this [`TicketLock`], returning the underlying data.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> TicketLock<T> {
+    /// Acquires the [`TicketLock`], spinning until the lock is available.
+    ///
+    /// Waiting contexts acquire the lock in first-come, first-served order. Upon returning, this
+    /// context is the only context with the lock held. A RAII guard is returned to allow for
+    /// scoped unlock of the [`TicketLock`].
+    pub fn lock<'a>(&'a self) -> TicketLockGuard<'a, T> {
+        self.lock.lock();
+
+        TicketLockGuard {
+            lock: &self.lock,
+            value: &self.value,
+        }
+    }
+
+    /// Disables interrupts on the current processor, then acquires the [`TicketLock`].
+    ///
+    /// Interrupts remain disabled until the returned guard is dropped, which prevents an interrupt
+    /// handler on this processor from deadlocking on the [`TicketLock`].
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+    pub fn lock_irq<'a>(&'a self) -> InterruptsDisabled<TicketLockGuard<'a, T>> {
+        let interrupts = InterruptGuard::new();
+        InterruptsDisabled::new(interrupts, self.lock())
+    }
+
+    /// Attempts to acquire this [`TicketLock`].
+    ///
+    /// If the lock could not be acquired, then [`Err`] is returned. Otherwise, a RAII guard is
+    /// returned. The lock will be unlocked when the guard is dropped.
+    ///
+    /// This function does not block.
+    ///
+    /// # Errors
+    /// If the [`TicketLock`] could not be acquired because it is already locked, then this call
+    /// will return an [`Err`].
+    pub fn try_lock<'a>(&'a self) -> Result<TicketLockGuard<'a, T>, TicketLockAcquisitionError> {
+        self.lock.try_lock().map(|()| TicketLockGuard {
+            lock: &self.lock,
+            value: &self.value,
+        })
+    }
+
+    /// Method that makes unlocking a [`TicketLock`] more explicit.
+    pub fn unlock(guard: TicketLockGuard<T>) {
+        drop(guard)
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// Since this call borrows the [`TicketLock`] mutably, no actual locking needs to take place:
+    /// the mutable borrow statically guarantees no locks exist.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+/// A RAII implementation of a "scoped lock" implemented using a [`TicketLock`]. When this
+/// structure is dropped, the [`TicketLock`] will be handed to the next waiting context.
+///
+/// This structure is created by the [`TicketLock::lock()`] and [`TicketLock::try_lock()`]
+/// methods.
+#[must_use = "lock guard must be held to access protected data"]
+pub struct TicketLockGuard<'a, T: ?Sized> {
+    /// The [`RawTicketLock`] guarding [`TicketLockGuard::value`].
+    lock: &'a RawTicketLock,
+    /// The value to which this [`TicketLockGuard`] provides access.
+    value: &'a UnsafeCell<T>,
+}
+
+impl<T: ?Sized> Deref for TicketLockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        let value_ptr = self.value.get();
+
+        // SAFETY:
+        // We have exclusive access to the value pointed to by `value_ptr`.
+        unsafe { &*value_ptr }
+    }
+}
+
+impl<T: ?Sized> DerefMut for TicketLockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let value_ptr = self.value.get();
+
+        // SAFETY:
+        // We have exclusive access to the value pointed to by `value_ptr`.
+        unsafe { &mut *value_ptr }
+    }
+}
+
+impl<T: ?Sized> Drop for TicketLockGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.unlock();
+    }
+}
+
+/// Represents the failure to acquire a [`TicketLock`].
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TicketLockAcquisitionError;
+
+impl fmt::Display for TicketLockAcquisitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("try_lock failed because ticket lock is already held")
+    }
+}
+
+impl error::Error for TicketLockAcquisitionError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn try_lock_respects_holder() {
+        let lock = TicketLock::new(0u32);
+
+        let mut guard = lock.lock();
+        *guard += 1;
+        assert!(lock.lock.is_locked());
+        assert_eq!(lock.try_lock().err(), Some(TicketLockAcquisitionError));
+        drop(guard);
+
+        assert!(!lock.lock.is_locked());
+        assert_eq!(*lock.try_lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn tickets_wrap_around() {
+        let lock = RawTicketLock {
+            next: AtomicUsize::new(usize::MAX),
+            serving: AtomicUsize::new(usize::MAX),
+        };
+
+        lock.lock();
+        assert!(lock.try_lock().is_err());
+        lock.unlock();
+        assert!(lock.try_lock().is_ok());
+        lock.unlock();
+        assert!(!lock.is_locked());
+    }
+}
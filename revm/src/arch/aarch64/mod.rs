@@ -3,3 +3,4 @@
 
 pub mod capabilities;
 pub mod memory;
+pub mod time;
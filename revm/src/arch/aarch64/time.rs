@@ -0,0 +1,28 @@
+//! Monotonic counter support for `aarch64`, backed by the generic timer's virtual counter.
+
+use aarch64::msr::raw::{read_cntfrq_el0, read_cntvct_el0};
+
+/// Returns the current value of the virtual counter.
+pub fn read_counter() -> u64 {
+    // SAFETY:
+    //
+    // An instruction synchronization barrier does not affect memory safety, and prevents the
+    // counter from being read early.
+    unsafe { core::arch::asm!("isb", options(nomem, nostack, preserves_flags)) }
+
+    // SAFETY:
+    //
+    // `CNTVCT_EL0` is always accessible at EL1 and EL2.
+    unsafe { read_cntvct_el0() }
+}
+
+/// Returns the frequency of the virtual counter, in hertz.
+///
+/// Returns [`None`] if firmware did not program `CNTFRQ_EL0`.
+pub fn counter_frequency() -> Option<u64> {
+    // SAFETY:
+    //
+    // `CNTFRQ_EL0` is always accessible at EL1 and EL2.
+    let frequency = unsafe { read_cntfrq_el0() } & 0xFFFF_FFFF;
+    (frequency != 0).then_some(frequency)
+}
@@ -3,4 +3,4 @@
 
 pub mod memory;
 
-pub use crate::arch::x86::capabilities;
+pub use crate::arch::x86::{capabilities, time};
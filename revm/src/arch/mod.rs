@@ -22,3 +22,4 @@ use x86_64 as arch_impl;
 
 pub mod capabilities;
 pub mod memory;
+pub mod time;
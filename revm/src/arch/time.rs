@@ -0,0 +1,3 @@
+//! Architectural monotonic counter functionality.
+
+pub use crate::arch::arch_impl::time::{counter_frequency, read_counter};
@@ -0,0 +1,151 @@
+//! Discovery of the ACPI tables that describe the reference clocks used to calibrate the TSC.
+
+use conversion::u32_to_usize;
+use stub_api::MapFlags;
+use x86::tsc::PmTimer;
+
+use crate::stub_protocol::{arch_table, mapping::PhysicalMapping};
+
+/// The signature of the RSDP structure.
+const RSDP_SIGNATURE: [u8; 8] = *b"RSD PTR ";
+/// The size, in bytes, of the RSDP structure since ACPI 2.0.
+const RSDP_SIZE: usize = 36;
+/// The offset of the `Revision` field of the RSDP structure.
+const RSDP_REVISION: usize = 15;
+/// The offset of the `RsdtAddress` field of the RSDP structure.
+const RSDP_RSDT_ADDRESS: usize = 16;
+/// The offset of the `XsdtAddress` field of the RSDP structure, present since revision 2.
+const RSDP_XSDT_ADDRESS: usize = 24;
+
+/// The size, in bytes, of the header shared by all system description tables.
+const HEADER_SIZE: usize = 36;
+/// The offset of the `Length` field of the system description table header.
+const HEADER_LENGTH: usize = 4;
+
+/// The offset of the `PM_TMR_BLK` field of the FADT.
+const FADT_PM_TMR_BLK: usize = 76;
+/// The offset of the `Flags` field of the FADT.
+const FADT_FLAGS: usize = 112;
+/// The offset of the `X_PM_TMR_BLK` field of the FADT.
+const FADT_X_PM_TMR_BLK: usize = 208;
+/// The bit of the FADT `Flags` field that indicates a 32-bit power management timer.
+const FADT_TMR_VAL_EXT: u32 = 1 << 8;
+
+/// The offset of the `BASE_ADDRESS` field of the HPET table.
+const HPET_BASE_ADDRESS: usize = 40;
+
+/// The offset of the `Address` field of a Generic Address Structure.
+const GAS_ADDRESS: usize = 4;
+/// The Generic Address Structure address space of system memory.
+const GAS_SYSTEM_MEMORY: u8 = 0;
+/// The Generic Address Structure address space of system I/O.
+const GAS_SYSTEM_IO: u8 = 1;
+
+/// Returns the ACPI power management timer described by the FADT.
+///
+/// Returns [`None`] if the FADT cannot be found or does not describe an I/O port timer.
+pub fn pm_timer() -> Option<PmTimer> {
+    let fadt = find_table(*b"FACP")?;
+
+    let flags = read_u32(&fadt, FADT_FLAGS).unwrap_or(0);
+    let port = gas_address(&fadt, FADT_X_PM_TMR_BLK, GAS_SYSTEM_IO)
+        .or_else(|| read_u32(&fadt, FADT_PM_TMR_BLK).map(u64::from))?;
+    let port = u16::try_from(port).ok().filter(|&port| port != 0)?;
+
+    // SAFETY:
+    //
+    // `port` is the power management timer port described by the FADT.
+    Some(unsafe { PmTimer::new(port, flags & FADT_TMR_VAL_EXT == FADT_TMR_VAL_EXT) })
+}
+
+/// Returns the physical address of the HPET register block described by the HPET table.
+///
+/// Returns [`None`] if the HPET table cannot be found or does not describe a memory-mapped HPET.
+pub fn hpet_address() -> Option<u64> {
+    let hpet = find_table(*b"HPET")?;
+    gas_address(&hpet, HPET_BASE_ADDRESS, GAS_SYSTEM_MEMORY)
+}
+
+/// Maps the system description table whose signature is `signature`.
+fn find_table(signature: [u8; 4]) -> Option<PhysicalMapping> {
+    let (root_address, root_signature, entry_size) = root_table()?;
+    let root = map_table(root_address, root_signature)?;
+
+    (HEADER_SIZE..root.size())
+        .step_by(entry_size)
+        .filter_map(|offset| match entry_size {
+            8 => read_u64(&root, offset),
+            _ => read_u32(&root, offset).map(u64::from),
+        })
+        .find_map(|address| map_table(address, signature))
+}
+
+/// Returns the physical address, signature, and entry size, in bytes, of the XSDT, or of the RSDT
+/// if the firmware does not provide an XSDT.
+fn root_table() -> Option<(u64, [u8; 4], usize)> {
+    let arch_table = arch_table()?;
+
+    for rsdp_address in [arch_table.xsdp, arch_table.rsdp] {
+        if rsdp_address == 0 {
+            continue;
+        }
+
+        let Some(rsdp) = PhysicalMapping::new(rsdp_address, RSDP_SIZE, MapFlags::READ) else {
+            continue;
+        };
+        if rsdp.read_bytes(0) != Some(RSDP_SIGNATURE) {
+            continue;
+        }
+
+        let [revision] = rsdp.read_bytes(RSDP_REVISION)?;
+        if revision >= 2
+            && let Some(xsdt) = read_u64(&rsdp, RSDP_XSDT_ADDRESS).filter(|&xsdt| xsdt != 0)
+        {
+            return Some((xsdt, *b"XSDT", 8));
+        }
+        if let Some(rsdt) = read_u32(&rsdp, RSDP_RSDT_ADDRESS).filter(|&rsdt| rsdt != 0) {
+            return Some((u64::from(rsdt), *b"RSDT", 4));
+        }
+    }
+
+    None
+}
+
+/// Maps the entirety of the system description table at `physical_address`.
+///
+/// Returns [`None`] if the table does not start with `signature` or cannot be mapped.
+fn map_table(physical_address: u64, signature: [u8; 4]) -> Option<PhysicalMapping> {
+    let header = PhysicalMapping::new(physical_address, HEADER_SIZE, MapFlags::READ)?;
+    if header.read_bytes(0)? != signature {
+        return None;
+    }
+
+    let length = u32_to_usize(read_u32(&header, HEADER_LENGTH)?);
+    drop(header);
+    if length < HEADER_SIZE {
+        return None;
+    }
+
+    PhysicalMapping::new(physical_address, length, MapFlags::READ)
+}
+
+/// Returns the address of the Generic Address Structure located `offset` bytes into `table`.
+///
+/// Returns [`None`] if the structure does not lie within `table`, does not describe
+/// `address_space`, or is zero.
+fn gas_address(table: &PhysicalMapping, offset: usize, address_space: u8) -> Option<u64> {
+    let [space] = table.read_bytes(offset)?;
+    let address = read_u64(table, offset.checked_add(GAS_ADDRESS)?)?;
+
+    (space == address_space && address != 0).then_some(address)
+}
+
+/// Reads the little-endian [`u32`] located `offset` bytes into `table`.
+fn read_u32(table: &PhysicalMapping, offset: usize) -> Option<u32> {
+    table.read_bytes(offset).map(u32::from_le_bytes)
+}
+
+/// Reads the little-endian [`u64`] located `offset` bytes into `table`.
+fn read_u64(table: &PhysicalMapping, offset: usize) -> Option<u64> {
+    table.read_bytes(offset).map(u64::from_le_bytes)
+}
@@ -10,18 +10,23 @@ pub struct ArchCapabilities {
     vendor: Vendor,
     max_basic_cpuid: u32,
 
+    tsc: bool,
     pse: bool,
     pse36: bool,
     msr: bool,
+    sse2: bool,
     pae: bool,
     apic: bool,
     mtrr: bool,
     pat: bool,
+    x2apic: bool,
+    tsc_deadline: bool,
 
     la57: bool,
 
     max_extended_cpuid: u32,
 
+    invariant_tsc: bool,
     nxe: bool,
     gib_pages: bool,
     long_mode: bool,
@@ -38,6 +43,7 @@ impl ArchCapabilities {
 
             max_basic_cpuid: 0,
 
+            tsc: false,
             pse: false,
             msr: false,
             pae: false,
@@ -45,11 +51,15 @@ impl ArchCapabilities {
             mtrr: false,
             pat: false,
             pse36: false,
+            sse2: false,
+            x2apic: false,
+            tsc_deadline: false,
 
             la57: false,
 
             max_extended_cpuid: 0,
 
+            invariant_tsc: false,
             nxe: false,
             gib_pages: false,
             long_mode: false,
@@ -99,16 +109,20 @@ impl ArchCapabilities {
                 let Cpuid {
                     eax: _,
                     ebx: _,
-                    ecx: _,
+                    ecx,
                     edx,
                 } = unsafe { cpuid_unchecked(0x1, 0) };
                 support.pse = ((edx >> 3) & 0b1) == 0b1;
+                support.tsc = ((edx >> 4) & 0b1) == 0b1;
                 support.msr = ((edx >> 5) & 0b1) == 0b1;
                 support.pae = ((edx >> 6) & 0b1) == 0b1;
                 support.apic = ((edx >> 9) & 0b1) == 0b1;
                 support.mtrr = ((edx >> 9) & 0b1) == 0b1;
                 support.pat = ((edx >> 9) & 0b1) == 0b1;
                 support.pse36 = ((edx >> 17) & 0b1) == 0b1;
+                support.sse2 = ((edx >> 26) & 0b1) == 0b1;
+                support.x2apic = ((ecx >> 21) & 0b1) == 0b1;
+                support.tsc_deadline = ((ecx >> 24) & 0b1) == 0b1;
 
                 if support.max_basic_cpuid < 0x7 {
                     break 'basic;
@@ -157,6 +171,22 @@ impl ArchCapabilities {
                 support.gib_pages = ((edx >> 26) & 0b1) == 0b1;
                 support.long_mode = ((edx >> 29) & 0b1) == 0b1;
 
+                if support.max_extended_cpuid < 0x80000007 {
+                    break 'extended;
+                }
+
+                // SAFETY:
+                //
+                // The `CPUID` instruction is supported.
+                let Cpuid {
+                    eax: _,
+                    ebx: _,
+                    ecx: _,
+                    edx,
+                } = unsafe { cpuid_unchecked(0x80000007, 0) };
+
+                support.invariant_tsc = ((edx >> 8) & 0b1) == 0b1;
+
                 if support.max_extended_cpuid < 0x80000008 {
                     break 'extended;
                 }
@@ -198,6 +228,11 @@ impl ArchCapabilities {
         self.max_basic_cpuid
     }
 
+    /// Returns `true` if the Time Stamp Counter (TSC) is supported.
+    pub const fn tsc_supported(&self) -> bool {
+        self.tsc
+    }
+
     /// Returns `true` if Page Size Extensions (PSE) are supported.
     pub const fn pse_supported(&self) -> bool {
         self.pse
@@ -213,6 +248,11 @@ impl ArchCapabilities {
         self.msr
     }
 
+    /// Returns `true` if Streaming SIMD Extensions 2 (SSE2), and with it LFENCE, are supported.
+    pub const fn sse2_supported(&self) -> bool {
+        self.sse2
+    }
+
     /// Returns `true` if Physical Address Extension (PAE) is supported.
     pub const fn pae_supported(&self) -> bool {
         self.pae
@@ -223,6 +263,16 @@ impl ArchCapabilities {
         self.apic
     }
 
+    /// Returns `true` if the local APIC supports x2APIC mode.
+    pub const fn x2apic_supported(&self) -> bool {
+        self.x2apic
+    }
+
+    /// Returns `true` if the local APIC timer supports TSC-deadline mode.
+    pub const fn tsc_deadline_supported(&self) -> bool {
+        self.tsc_deadline
+    }
+
     /// Returns `true` if Memory Type Range Registers (MTRRs) are supported.
     pub const fn mtrr_supported(&self) -> bool {
         self.mtrr
@@ -243,6 +293,11 @@ impl ArchCapabilities {
         self.max_extended_cpuid
     }
 
+    /// Returns `true` if the TSC runs at a constant rate in all ACPI P-, C-, and T-states.
+    pub const fn invariant_tsc_supported(&self) -> bool {
+        self.invariant_tsc
+    }
+
     /// Returns `true` if the No-Execute (NXE) page protection bit is supported.
     pub const fn nxe_supported(&self) -> bool {
         self.nxe
@@ -1,5 +1,7 @@
 //! Functionality shared between `i686` and `x86_64`.
 #![allow(clippy::missing_panics_doc)]
 
+pub mod acpi;
 pub mod capabilities;
 pub mod memory;
+pub mod time;
@@ -0,0 +1,104 @@
+//! Monotonic counter support for `i686` and `x86_64`, backed by the Time Stamp Counter (TSC).
+
+use stub_api::{MapFlags, MappingType};
+use x86::tsc::{
+    Hpet, ReferenceClock, calibrate, cpuid_base_frequency, cpuid_frequency, read_tsc,
+    select_frequency,
+};
+
+use crate::{
+    arch::{capabilities::arch_capability_support, x86::acpi},
+    stub_protocol::{generic_table, mapping::PhysicalMapping},
+};
+
+/// The size, in bytes, of the HPET register block.
+const HPET_REGISTERS_SIZE: usize = 1024;
+
+/// The fraction of a second over which the TSC is calibrated against a reference clock.
+const CALIBRATIONS_PER_SECOND: u64 = 100;
+
+/// Returns the current value of the TSC, or zero if the TSC is not supported.
+pub fn read_counter() -> u64 {
+    if !arch_capability_support().tsc_supported() {
+        return 0;
+    }
+
+    // SAFETY:
+    //
+    // The TSC is supported and `revm` runs at CPL 0, where RDTSC is always permitted.
+    unsafe { read_tsc() }
+}
+
+/// Returns the frequency of the TSC, in hertz.
+///
+/// The frequency is read from CPUID leaf 0x15 when enumerated, and otherwise measured against the
+/// ACPI power management timer or the HPET. The processor base frequency from CPUID leaf 0x16 is
+/// only used if neither reference clock is available, since it merely approximates the TSC
+/// frequency.
+///
+/// Returns [`None`] if the TSC is not invariant, since its rate then changes with the processor
+/// frequency, or if none of these sources are available.
+pub fn counter_frequency() -> Option<u64> {
+    let capabilities = arch_capability_support();
+    if !capabilities.tsc_supported() || !capabilities.invariant_tsc_supported() {
+        return None;
+    }
+
+    select_frequency(
+        cpuid_frequency(),
+        calibrated_frequency,
+        cpuid_base_frequency(),
+    )
+}
+
+/// Measures the TSC frequency against the ACPI power management timer or, if there is none, an
+/// enabled HPET.
+///
+/// Returns [`None`] if neither reference clock is available.
+fn calibrated_frequency() -> Option<u64> {
+    if !arch_capability_support().sse2_supported() {
+        return None;
+    }
+
+    if let Some(pm_timer) = acpi::pm_timer() {
+        return Some(calibrate_against(&pm_timer));
+    }
+
+    let hpet_address = acpi::hpet_address()?;
+    if !generic_table()?
+        .v4
+        .supports_mapping_type(MappingType::DEVICE)
+    {
+        return None;
+    }
+    let registers = PhysicalMapping::new(
+        hpet_address,
+        HPET_REGISTERS_SIZE,
+        MapFlags::READ.with_mapping_type(MappingType::DEVICE),
+    )?;
+    // SAFETY:
+    //
+    // `registers` is an uncached mapping of the HPET register block described by the ACPI HPET
+    // table, and `hpet` is dropped before it.
+    let hpet = unsafe { Hpet::new(registers.as_ptr()) };
+
+    // The HPET is not started here, since the firmware may rely on its configuration.
+    (hpet.enabled() && hpet.frequency() != 0).then(|| calibrate_against(&hpet))
+}
+
+/// Measures the TSC frequency over [`CALIBRATIONS_PER_SECOND`]ths of a second of `reference`.
+fn calibrate_against(reference: &impl ReferenceClock) -> u64 {
+    let width = reference.width();
+    let max_ticks = if width >= 64 {
+        u64::MAX
+    } else {
+        (1 << width) - 1
+    };
+    let ticks = (reference.frequency() / CALIBRATIONS_PER_SECOND).clamp(1, max_ticks);
+
+    // SAFETY:
+    //
+    // The TSC and SSE2 are supported, `revm` runs at CPL 0, where RDTSC is always permitted, and
+    // `ticks` is nonzero and smaller than the range of `reference`.
+    unsafe { calibrate(reference, ticks) }
+}
@@ -8,6 +8,7 @@ pub mod arch;
 pub mod log;
 pub mod memory;
 pub mod stub_protocol;
+pub mod time;
 pub mod util;
 
 /// Generic handler for panics.
@@ -1,14 +1,33 @@
 //! Early logging solution.
 
-use core::fmt::{self, Write};
+use core::{
+    fmt::{self, Write},
+    ptr::NonNull,
+};
 
-use crate::{log::LogLevel, stub_protocol::generic_table};
+use log_filter::{Filter, LevelFilter};
+use stub_api::log_ring::{LogRecordFlags, LogRecordLevel, LogRing, LogRingHeader};
+use sync::ControlledModificationCell;
+
+use crate::{
+    log::LogLevel,
+    stub_protocol::generic_table,
+    time::{Instant, uptime},
+};
+
+/// The [`LevelFilter`] of modules that no `log=` directive matches.
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Debug;
+
+/// The persistent log ring provided by the stub.
+static LOG_RING: ControlledModificationCell<Option<LogRing>> =
+    ControlledModificationCell::new(None);
 
 /// Logs a message with [`LogLevel::Trace`].
 #[allow(unused_macros)]
 macro_rules! early_trace {
     ($($arg:tt)*) => ($crate::stub_protocol::log::_log(
         $crate::log::LogLevel::Trace,
+        module_path!(),
         format_args!($($arg)*))
     );
 }
@@ -18,6 +37,7 @@ macro_rules! early_trace {
 macro_rules! early_debug {
     ($($arg:tt)*) => ($crate::stub_protocol::log::_log(
         $crate::log::LogLevel::Debug,
+        module_path!(),
         format_args!($($arg)*))
     );
 }
@@ -27,6 +47,7 @@ macro_rules! early_debug {
 macro_rules! early_info {
     ($($arg:tt)*) => ($crate::stub_protocol::log::_log(
         $crate::log::LogLevel::Info,
+        module_path!(),
         format_args!($($arg)*))
     );
 }
@@ -36,6 +57,7 @@ macro_rules! early_info {
 macro_rules! early_warn {
     ($($arg:tt)*) => ($crate::stub_protocol::log::_log(
         $crate::log::LogLevel::Warn,
+        module_path!(),
         format_args!($($arg)*))
     );
 }
@@ -45,13 +67,14 @@ macro_rules! early_warn {
 macro_rules! early_error {
     ($($arg:tt)*) => ($crate::stub_protocol::log::_log(
         $crate::log::LogLevel::Error,
+        module_path!(),
         format_args!($($arg)*))
     );
 }
 
 #[doc(hidden)]
-pub fn _log(level: LogLevel, args: fmt::Arguments) {
-    if level < LogLevel::Debug {
+pub fn _log(level: LogLevel, module_path: &str, args: fmt::Arguments) {
+    if !log_filter::enabled(level, module_path) {
         return;
     }
 
@@ -62,13 +85,34 @@ pub fn _log(level: LogLevel, args: fmt::Arguments) {
     };
 
     // Ignore any logging errors because there is no method to report or deal with them.
-    let _ = match level {
-        LogLevel::Trace => buffer.write_fmt(format_args!("TRACE: {args}")),
-        LogLevel::Debug => buffer.write_fmt(format_args!("DEBUG: {args}")),
-        LogLevel::Info => buffer.write_fmt(format_args!("INFO : {args}")),
-        LogLevel::Warn => buffer.write_fmt(format_args!("WARN : {args}")),
-        LogLevel::Error => buffer.write_fmt(format_args!("ERROR: {args}")),
+    if let Some(uptime) = uptime() {
+        let _ = buffer.write_fmt(format_args!(
+            "[{:>5}.{:06}] ",
+            uptime.as_secs(),
+            uptime.subsec_micros()
+        ));
+    }
+    let (prefix, record_level) = match level {
+        LogLevel::Trace => ("TRACE: ", LogRecordLevel::TRACE),
+        LogLevel::Debug => ("DEBUG: ", LogRecordLevel::DEBUG),
+        LogLevel::Info => ("INFO : ", LogRecordLevel::INFO),
+        LogLevel::Warn => ("WARN : ", LogRecordLevel::WARN),
+        LogLevel::Error => ("ERROR: ", LogRecordLevel::ERROR),
     };
+    let _ = buffer.write_str(prefix);
+    let message_start = buffer.length;
+    let _ = buffer.write_fmt(args);
+
+    if let Some(ring) = log_ring() {
+        let cpu = u32::try_from(sync::current_processor_id()).unwrap_or(u32::MAX);
+        ring.append(
+            record_level,
+            LogRecordFlags::EXECUTABLE,
+            cpu,
+            Instant::now().ticks(),
+            &buffer.buffer[message_start..buffer.length],
+        );
+    }
 
     if buffer.truncated {
         let marker = b"<truncated>";
@@ -84,10 +128,59 @@ pub fn _log(level: LogLevel, args: fmt::Arguments) {
         //
         // The REVM protocol ensures that the function pointer is valid and the provided
         // arguments point to a buffer of valid UTF-8 that is at least as long as `s.len()`.
-        let _ = unsafe { (generic_table.write)(buffer.buffer.as_ptr(), buffer.length) };
+        let _ = unsafe { (generic_table.v0().write)(buffer.buffer.as_ptr(), buffer.length) };
     }
 }
 
+/// Attaches to the persistent log ring whose [`LogRingHeader`] is pointed to by `header`.
+///
+/// Returns `false` if `header` does not refer to a valid log ring.
+///
+/// # Safety
+///
+/// - `header` must be the pointer to the log ring reported by the REVM protocol table.
+/// - There must be zero overlapping calls to [`attach_log_ring()`], [`log_ring()`], or [`_log()`].
+pub(super) unsafe fn attach_log_ring(header: *mut LogRingHeader) -> bool {
+    let Some(base) = NonNull::new(header.cast::<u8>()) else {
+        return false;
+    };
+
+    // SAFETY:
+    //
+    // The REVM protocol ensures that the log ring is identity mapped and remains valid after
+    // takeover.
+    let ring = unsafe { LogRing::from_raw(base) };
+    // SAFETY:
+    //
+    // The invariants of this function ensure that [`LOG_RING`] is not accessed for the duration
+    // of this operation.
+    unsafe { *LOG_RING.get_mut() = ring }
+
+    ring.is_some()
+}
+
+/// Applies the log filter configured by the `log=` arguments of `command_line`.
+///
+/// # Safety
+///
+/// There must be zero overlapping calls to [`configure_log_filter()`] or [`_log()`].
+pub(super) unsafe fn configure_log_filter(command_line: &str) {
+    match Filter::from_command_line(command_line, DEFAULT_LEVEL) {
+        Ok(filter) => {
+            // SAFETY:
+            //
+            // The invariants of this function ensure that no logging occurs concurrently.
+            unsafe { log_filter::set_filter(filter) }
+        }
+        Err(error) => early_warn!("ignoring log filter: {error}"),
+    }
+}
+
+/// Returns the persistent log ring provided by the stub, if any.
+pub fn log_ring() -> Option<LogRing> {
+    *LOG_RING.get()
+}
+
 /// Buffered logging system for early `revm` logging.
 struct LogBuffer<const SIZE: usize> {
     /// The buffer in which the log message is stored.
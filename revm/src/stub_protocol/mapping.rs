@@ -0,0 +1,104 @@
+//! Temporary mappings of physical memory created through the REVM protocol.
+
+use core::ptr;
+
+use conversion::{u64_to_usize_strict, usize_to_u64};
+use stub_api::{MapFlags, Status};
+
+use crate::{arch::memory::compute_page_frame_size, stub_protocol::generic_table};
+
+/// A mapping of physical memory created by [`GenericTableV0::map`][stub_api::GenericTableV0::map]
+/// that is unmapped when dropped.
+pub struct PhysicalMapping {
+    /// The virtual address of the first mapped page.
+    virtual_address: usize,
+    /// The offset of the requested physical address within the first mapped page.
+    offset: usize,
+    /// The number of bytes that were requested.
+    size: usize,
+    /// The number of mapped pages.
+    count: usize,
+}
+
+impl PhysicalMapping {
+    /// Maps the `size` bytes of physical memory starting at `physical_address` with `flags`, at a
+    /// virtual location chosen by the bootloader.
+    ///
+    /// Returns [`None`] if the REVM protocol table is unavailable or the bootloader fails to create
+    /// the mapping.
+    pub fn new(physical_address: u64, size: usize, flags: MapFlags) -> Option<Self> {
+        let generic_table = generic_table()?;
+        let page_size = compute_page_frame_size();
+
+        let offset = u64_to_usize_strict(physical_address % usize_to_u64(page_size));
+        let count = offset.checked_add(size)?.div_ceil(page_size).max(1);
+
+        let mut virtual_address = 0;
+        // SAFETY:
+        //
+        // The REVM protocol ensures that the function pointer is valid, `virtual_address` is a
+        // valid location for the chosen address, and [`MapFlags::CHOOSE_LOCATION`] ensures that no
+        // existing mapping is modified.
+        let status = unsafe {
+            (generic_table.v0().map)(
+                physical_address - usize_to_u64(offset),
+                &raw mut virtual_address,
+                count,
+                flags | MapFlags::CHOOSE_LOCATION,
+            )
+        };
+        if status != Status::SUCCESS {
+            return None;
+        }
+
+        Some(Self {
+            virtual_address,
+            offset,
+            size,
+            count,
+        })
+    }
+
+    /// Returns a pointer to the mapped copy of the requested physical address.
+    pub fn as_ptr(&self) -> *mut u8 {
+        ptr::with_exposed_provenance_mut::<u8>(self.virtual_address).wrapping_add(self.offset)
+    }
+
+    /// Returns the number of bytes that were requested.
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Reads the `N` bytes located `offset` bytes into the mapping.
+    ///
+    /// Returns [`None`] if the bytes do not lie entirely within the requested bytes.
+    pub fn read_bytes<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+        if offset.checked_add(N)? > self.size {
+            return None;
+        }
+
+        // SAFETY:
+        //
+        // The bounds check ensures that the bytes lie within the mapping.
+        Some(unsafe {
+            self.as_ptr()
+                .wrapping_add(offset)
+                .cast::<[u8; N]>()
+                .read_unaligned()
+        })
+    }
+}
+
+impl Drop for PhysicalMapping {
+    fn drop(&mut self) {
+        let Some(generic_table) = generic_table() else {
+            return;
+        };
+
+        // SAFETY:
+        //
+        // The REVM protocol ensures that the function pointer is valid, and the region was mapped
+        // by [`PhysicalMapping::new()`] and is no longer referenced.
+        let _ = unsafe { (generic_table.v0().unmap)(self.virtual_address, self.count) };
+    }
+}
@@ -1,6 +1,7 @@
 //! Code interacting with the REVM protocol.
 
 use core::{
+    ffi::CStr,
     mem, ptr,
     sync::atomic::{AtomicBool, AtomicPtr, Ordering},
 };
@@ -21,10 +22,12 @@ use crate::{
         validate_arch_capabilities_match,
     },
     memory::initialize_memory_management,
+    time::{counter_frequency, initialize_clock},
 };
 
 #[macro_use]
 pub mod log;
+pub mod mapping;
 
 /// Pointer to the REVM protocol table.
 static PROTOCOL_TABLE: AtomicPtr<HeaderV0> = AtomicPtr::new(ptr::null_mut());
@@ -39,13 +42,37 @@ extern "C" fn revm_entry(header_ptr: *mut HeaderV0) -> Status {
 
     // Initialize protocol table and print basic information.
     PROTOCOL_TABLE.store(header_ptr, Ordering::Release);
+    if !generic_table.log_ring.is_null() {
+        // SAFETY:
+        //
+        // The REVM protocol identity maps the log ring, and no logging has occurred yet.
+        let attached = unsafe { log::attach_log_ring(generic_table.log_ring) };
+        if !attached {
+            early_warn!("Invalid log ring at {:p}", generic_table.log_ring);
+        }
+    }
+    // The log filter is configured even without a command line, since the active filter lets every
+    // message through until then.
+    let command_line = if generic_table.v0().command_line.is_null() {
+        ""
+    } else {
+        // SAFETY:
+        //
+        // The REVM protocol ensures that a non-null command line is a valid NUL-terminated string.
+        let command_line = unsafe { CStr::from_ptr(generic_table.v0().command_line.cast()) };
+        command_line.to_str().unwrap_or_default()
+    };
+    // SAFETY:
+    //
+    // Logging only occurs on this processor.
+    unsafe { log::configure_log_filter(command_line) }
     early_debug!(
         "REVM Image Physical Address: {:#x}",
-        generic_table.image_physical_address
+        generic_table.v0().image_physical_address
     );
     early_debug!(
         "REVM Image Virtual Address: {:#x}",
-        generic_table.image_virtual_address
+        generic_table.v0().image_virtual_address
     );
     early_debug!("Image Start: {:#x}", crate::util::image_start());
     early_debug!("{arch_table:#x?}");
@@ -56,6 +83,20 @@ extern "C" fn revm_entry(header_ptr: *mut HeaderV0) -> Status {
     }
     early_debug!("{:#x?}", arch_capability_support());
 
+    // SAFETY:
+    //
+    // The architectural capabilities have been initialized and no clock API has been utilized.
+    unsafe { initialize_clock() }
+    match counter_frequency() {
+        Some(frequency) => {
+            early_debug!("Counter Frequency: {frequency} Hz");
+            if let Some(ring) = log::log_ring() {
+                ring.set_counter_frequency(frequency);
+            }
+        }
+        None => early_warn!("Counter frequency unknown: timestamps and timeouts are unavailable"),
+    }
+
     // SAFETY:
     //
     // This function is called before any memory management functionality has been utilized.
@@ -148,12 +189,19 @@ fn validate_protocol_table(
     //
     // The REVM boot protocol states that the generic table is always in existence and that it is
     // located `header.generic_table_offset` bytes away from the start of [`Header`].
-    let generic_table = unsafe { &mut *generic_table };
-    if generic_table.version != GenericTableV0::VERSION {
-        // We currently only support the first version of the REVM boot protocol.
+    let generic_table_v0 = unsafe { &*generic_table };
+    if generic_table_v0.version < GenericTable::VERSION {
+        // Newer versions of the generic table only append fields, so only older versions are
+        // missing fields that `revm` uses.
         return Err(Status::NOT_SUPPORTED);
     }
 
+    // SAFETY:
+    //
+    // The version check ensures that the generic table starts with a [`GenericTable`] and the size
+    // check ensures that it fits within the protocol table.
+    let generic_table = unsafe { &*generic_table.cast::<GenericTable>() };
+
     let arch_table = header_ptr
         .wrapping_byte_add(u64_to_usize_strict(header_v0.arch_table_offset))
         .cast::<ArchTableV0>();
@@ -161,12 +209,23 @@ fn validate_protocol_table(
     //
     // The REVM boot protocol states that the generic table is always in existence and that it is
     // located `header.arch_table_offset` bytes away from the start of [`Header`].
-    let arch_table = unsafe { &mut *arch_table };
-    if arch_table.version != ArchTable::VERSION {
-        // We currently only support the first version of the REVM boot protocol.
+    let arch_table_v0 = unsafe { &*arch_table };
+    #[allow(
+        clippy::absurd_extreme_comparisons,
+        reason = "the architecture tables are still at their first version"
+    )]
+    if arch_table_v0.version < ArchTable::VERSION {
+        // Newer versions of the architecture table only append fields, so only older versions are
+        // missing fields that `revm` uses.
         return Err(Status::NOT_SUPPORTED);
     }
 
+    // SAFETY:
+    //
+    // The version check ensures that the architecture table starts with an [`ArchTable`] and the
+    // size check ensures that it fits within the protocol table.
+    let arch_table = unsafe { &*arch_table.cast::<ArchTable>() };
+
     Ok((generic_table, arch_table))
 }
 
@@ -196,7 +255,7 @@ fn initalize_and_validate_arch_capability_support(generic_table: &GenericTable)
     // A valid function was passed and the argument is a valid pointer in regards to the passed
     // function.
     unsafe {
-        (generic_table.run_on_all_processors)(
+        (generic_table.v0().run_on_all_processors)(
             validate_same_arch_capability_support,
             ptr::from_ref(&all_same_config).cast_mut().cast::<()>(),
         )
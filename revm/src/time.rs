@@ -0,0 +1,131 @@
+//! Monotonic clock for `revm`, used for log timestamps, timeouts, and sampling.
+//!
+//! The clock is backed by an architectural counter: the TSC on `i686` and `x86_64` and the
+//! generic timer's virtual counter on `aarch64`. [`Instant`]s can always be taken and compared,
+//! but they can only be converted to [`Duration`]s once the counter frequency is known.
+
+use core::time::Duration;
+
+use sync::ControlledModificationCell;
+
+use crate::arch;
+
+/// The number of nanoseconds in a second.
+const NANOSECONDS_PER_SECOND: u128 = 1_000_000_000;
+
+/// The frequency, in hertz, of the counter backing [`Instant`], or zero if it is unknown.
+static COUNTER_FREQUENCY: ControlledModificationCell<u64> = ControlledModificationCell::new(0);
+
+/// The [`Instant`] at which the clock was initialized.
+static CLOCK_START: ControlledModificationCell<Instant> =
+    ControlledModificationCell::new(Instant(0));
+
+/// Initializes the monotonic clock.
+///
+/// # Safety
+///
+/// This must be called a single time, after the architectural capabilities have been initialized
+/// and before any other clock API is utilized.
+pub unsafe fn initialize_clock() {
+    let frequency = arch::time::counter_frequency().unwrap_or(0);
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that [`COUNTER_FREQUENCY`] and [`CLOCK_START`] will
+    // not be accessed for the duration of this function and thus it is safe to modify them.
+    unsafe {
+        *COUNTER_FREQUENCY.get_mut() = frequency;
+    }
+    // SAFETY:
+    //
+    // The invariants of this function ensure that [`COUNTER_FREQUENCY`] and [`CLOCK_START`] will
+    // not be accessed for the duration of this function and thus it is safe to modify them.
+    unsafe {
+        *CLOCK_START.get_mut() = Instant::now();
+    }
+}
+
+/// Returns the frequency, in hertz, of the counter backing [`Instant`].
+///
+/// Returns [`None`] if the frequency could not be determined.
+pub fn counter_frequency() -> Option<u64> {
+    let frequency = *COUNTER_FREQUENCY.get();
+    (frequency != 0).then_some(frequency)
+}
+
+/// Returns the time elapsed since the clock was initialized.
+///
+/// Returns [`None`] if the counter frequency could not be determined.
+pub fn uptime() -> Option<Duration> {
+    Instant::now().checked_duration_since(*CLOCK_START.get())
+}
+
+/// A measurement of the monotonic clock.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Instant(u64);
+
+impl Instant {
+    /// Returns an [`Instant`] corresponding to the current time.
+    pub fn now() -> Self {
+        Self(arch::time::read_counter())
+    }
+
+    /// Returns the raw counter value of this [`Instant`].
+    pub const fn ticks(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the [`Duration`] elapsed from `earlier` to this [`Instant`].
+    ///
+    /// Returns [`None`] if the counter frequency is unknown or `earlier` is later than this
+    /// [`Instant`].
+    pub fn checked_duration_since(self, earlier: Instant) -> Option<Duration> {
+        let frequency = u128::from(counter_frequency()?);
+        let ticks = u128::from(self.0.checked_sub(earlier.0)?);
+
+        let seconds = u64::try_from(ticks / frequency).ok()?;
+        let nanoseconds = (ticks % frequency) * NANOSECONDS_PER_SECOND / frequency;
+        let nanoseconds = u32::try_from(nanoseconds).ok()?;
+        Some(Duration::new(seconds, nanoseconds))
+    }
+
+    /// Returns the [`Duration`] elapsed from `earlier` to this [`Instant`], or zero if it cannot
+    /// be determined.
+    pub fn saturating_duration_since(self, earlier: Instant) -> Duration {
+        self.checked_duration_since(earlier).unwrap_or_default()
+    }
+
+    /// Returns the [`Duration`] elapsed since this [`Instant`], or zero if it cannot be
+    /// determined.
+    pub fn elapsed(self) -> Duration {
+        Instant::now().saturating_duration_since(self)
+    }
+
+    /// Returns the [`Instant`] that is `duration` after this [`Instant`].
+    ///
+    /// Returns [`None`] if the counter frequency is unknown or the result is not representable.
+    pub fn checked_add(self, duration: Duration) -> Option<Instant> {
+        let frequency = u128::from(counter_frequency()?);
+        let ticks = duration.as_nanos().checked_mul(frequency)? / NANOSECONDS_PER_SECOND;
+
+        self.0.checked_add(u64::try_from(ticks).ok()?).map(Instant)
+    }
+}
+
+/// A point in time after which an operation should be abandoned.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Deadline(Option<Instant>);
+
+impl Deadline {
+    /// Returns a [`Deadline`] that expires `timeout` after the current time.
+    ///
+    /// If the counter frequency is unknown, the [`Deadline`] never expires.
+    pub fn after(timeout: Duration) -> Self {
+        Self(Instant::now().checked_add(timeout))
+    }
+
+    /// Returns `true` if the [`Deadline`] has passed.
+    pub fn expired(self) -> bool {
+        self.0.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
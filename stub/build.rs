@@ -10,16 +10,18 @@ use std::{
 const DEFAULT_FONT: &str = "../assets/Tamsyn8x16r.psf";
 
 fn main() -> Result<(), ()> {
+    let manifest_dir =
+        std::env::var("CARGO_MANIFEST_DIR").expect("cargo didn't pass CARGO_MANIFEST_DIR");
+
     if let Ok(val) = env::var("CARGO_CFG_PANIC")
         && val == "unwind"
     {
-        // `revm-stub` is never built with unwinding panics except for when testing.
+        // `revm-stub` is never built with unwinding panics except for when testing, in which case
+        // the host linker script only needs to delimit the initialization graph.
+        println!("cargo::rustc-link-arg=-T{manifest_dir}/test-linker-script.ld");
         return Ok(());
     }
 
-    let manifest_dir =
-        std::env::var("CARGO_MANIFEST_DIR").expect("cargo didn't pass CARGO_MANIFEST_DIR");
-
     println!("cargo::rustc-link-arg=-T{manifest_dir}/linker-script.ld");
 
     let font_path = if let Some(font_path) = env::var_os("STUB_FONT") {
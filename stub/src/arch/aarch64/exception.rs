@@ -0,0 +1,233 @@
+//! Exception handling for `aarch64`.
+//!
+//! Every entry of the exception vector table saves the general purpose registers and then enters
+//! [`exception_handler()`], which reports the state of the processor and panics.
+
+use core::{
+    arch::global_asm,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use aarch64::{
+    EL,
+    esr::ExceptionSyndrome,
+    msr::{CurrentEl, raw},
+};
+use conversion::{u64_to_usize_strict, usize_to_u64};
+
+use crate::arch::generic::exception::{CodeAddress, begin_exception, log_backtrace};
+
+/// The size, in bytes, of an [`ExceptionFrame`].
+const FRAME_SIZE: usize = core::mem::size_of::<ExceptionFrame>();
+
+/// Whether the stub's exception vector table should be loaded.
+static VECTORS_READY: AtomicBool = AtomicBool::new(false);
+
+/// Loads the stub's exception vector table on the current processor.
+///
+/// If `firmware_interrupts` is `true`, the firmware's exception vector table is left in place,
+/// since its interrupt handlers cannot be reached from another vector table without clobbering
+/// registers.
+///
+/// # Safety
+///
+/// Replacing the exception vector table of the current exception level must not violate system
+/// invariants.
+pub unsafe fn install_exception_handlers(firmware_interrupts: bool) {
+    if firmware_interrupts {
+        return;
+    }
+
+    VECTORS_READY.store(true, Ordering::Release);
+    load_exception_handlers();
+}
+
+/// Loads the stub's exception vector table on the current processor if
+/// [`install_exception_handlers()`] has installed it.
+pub fn load_exception_handlers() {
+    if !VECTORS_READY.load(Ordering::Acquire) {
+        return;
+    }
+
+    let vectors = usize_to_u64((&raw const AARCH64_EXCEPTION_VECTORS).addr());
+    match CurrentEl::get().el() {
+        // SAFETY:
+        //
+        // The vector table is part of the stub's image and handles every exception.
+        EL::EL1 => unsafe { raw::write_vbar_el1(vectors) },
+        // SAFETY:
+        //
+        // The vector table is part of the stub's image and handles every exception.
+        EL::EL2 => unsafe { raw::write_vbar_el2(vectors) },
+        EL::EL0 | EL::EL3 => return,
+    }
+
+    // SAFETY:
+    //
+    // An instruction synchronization barrier has no side effects beyond ensuring that the new
+    // vector table is used.
+    unsafe { core::arch::asm!("isb", options(nomem, nostack, preserves_flags)) }
+}
+
+/// The processor state saved by the exception vector table.
+#[repr(C)]
+struct ExceptionFrame {
+    /// The values of the `x0` to `x30` registers.
+    x: [u64; 31],
+    /// The value of the stack pointer when the exception was taken.
+    sp: u64,
+}
+
+/// Reports the processor state described by `frame` and then panics.
+///
+/// `vector` is the index of the entry of the exception vector table that was taken.
+extern "C" fn exception_handler(frame: &ExceptionFrame, vector: u64) -> ! {
+    begin_exception();
+
+    let el = CurrentEl::get().el();
+    let (esr, far, elr, spsr, suffix) = if el == EL::EL2 {
+        // SAFETY:
+        //
+        // The processor is executing at EL2.
+        let esr = unsafe { raw::read_esr_el2() };
+        // SAFETY:
+        //
+        // The processor is executing at EL2.
+        let far = unsafe { raw::read_far_el2() };
+        // SAFETY:
+        //
+        // The processor is executing at EL2.
+        let elr = unsafe { raw::read_elr_el2() };
+        // SAFETY:
+        //
+        // The processor is executing at EL2.
+        let spsr = unsafe { raw::read_spsr_el2() };
+        (esr, far, elr, spsr, "EL2")
+    } else {
+        // SAFETY:
+        //
+        // The processor is executing at EL1.
+        let esr = unsafe { raw::read_esr_el1() };
+        // SAFETY:
+        //
+        // The processor is executing at EL1.
+        let far = unsafe { raw::read_far_el1() };
+        // SAFETY:
+        //
+        // The processor is executing at EL1.
+        let elr = unsafe { raw::read_elr_el1() };
+        // SAFETY:
+        //
+        // The processor is executing at EL1.
+        let spsr = unsafe { raw::read_spsr_el1() };
+        (esr, far, elr, spsr, "EL1")
+    };
+
+    let kind = match vector % 4 {
+        0 => "synchronous exception",
+        1 => "IRQ",
+        2 => "FIQ",
+        _ => "SError",
+    };
+    let source = match vector / 4 {
+        0 => "current EL with SP_EL0",
+        1 => "current EL with SP_ELx",
+        2 => "lower EL using AArch64",
+        _ => "lower EL using AArch32",
+    };
+    crate::error!("unhandled {kind} taken from {source} to {suffix}");
+
+    let syndrome = ExceptionSyndrome::from_bits(esr);
+    let class = syndrome.exception_class();
+    crate::error!(
+        "ESR_{suffix} {esr:#018x} [EC {:#04x} ({}), IL {}, ISS {:#09x}]",
+        class.to_bits(),
+        class.description(),
+        u8::from(syndrome.instruction_length_32()),
+        syndrome.instruction_specific_syndrome()
+    );
+    crate::error!("{:?}", syndrome.decode());
+    crate::error!(
+        "ELR_{suffix} {}  FAR_{suffix} {far:#018x}  SPSR_{suffix} {spsr:#018x}",
+        CodeAddress(u64_to_usize_strict(elr))
+    );
+
+    for base in (0..28).step_by(4) {
+        crate::error!(
+            "x{:<2} {:#018x}  x{:<2} {:#018x}  x{:<2} {:#018x}  x{:<2} {:#018x}",
+            base,
+            frame.x[base],
+            base + 1,
+            frame.x[base + 1],
+            base + 2,
+            frame.x[base + 2],
+            base + 3,
+            frame.x[base + 3]
+        );
+    }
+    crate::error!(
+        "x28 {:#018x}  x29 {:#018x}  x30 {:#018x}  sp  {:#018x}",
+        frame.x[28],
+        frame.x[29],
+        frame.x[30],
+        frame.sp
+    );
+
+    log_backtrace(u64_to_usize_strict(frame.x[29]));
+
+    panic!(
+        "unhandled {kind} at {}",
+        CodeAddress(u64_to_usize_strict(elr))
+    )
+}
+
+unsafe extern "C" {
+    static AARCH64_EXCEPTION_VECTORS: u8;
+}
+
+global_asm! {
+    // The exception vector table consists of 16 entries, each 0x80 bytes long.
+    ".balign 0x800",
+    ".global AARCH64_EXCEPTION_VECTORS",
+    "AARCH64_EXCEPTION_VECTORS:",
+
+    ".set aarch64_exception_vector, 0",
+    ".rept 16",
+    ".balign 0x80",
+
+    "sub sp, sp, #{FRAME_SIZE}",
+    "stp x0, x1, [sp]",
+    "mov x0, #aarch64_exception_vector",
+    "b aarch64_exception_common",
+
+    ".set aarch64_exception_vector, aarch64_exception_vector + 1",
+    ".endr",
+
+    "aarch64_exception_common:",
+    "stp x2, x3, [sp, #16]",
+    "stp x4, x5, [sp, #32]",
+    "stp x6, x7, [sp, #48]",
+    "stp x8, x9, [sp, #64]",
+    "stp x10, x11, [sp, #80]",
+    "stp x12, x13, [sp, #96]",
+    "stp x14, x15, [sp, #112]",
+    "stp x16, x17, [sp, #128]",
+    "stp x18, x19, [sp, #144]",
+    "stp x20, x21, [sp, #160]",
+    "stp x22, x23, [sp, #176]",
+    "stp x24, x25, [sp, #192]",
+    "stp x26, x27, [sp, #208]",
+    "stp x28, x29, [sp, #224]",
+
+    // Store the link register and the stack pointer at the time of the exception.
+    "add x1, sp, #{FRAME_SIZE}",
+    "stp x30, x1, [sp, #240]",
+
+    "mov x1, x0",
+    "mov x0, sp",
+    "bl {exception_handler}",
+    "brk #0",
+
+    FRAME_SIZE = const FRAME_SIZE,
+    exception_handler = sym exception_handler,
+}
@@ -2,7 +2,7 @@
 
 mod vmsa_v8;
 
-use aarch64::{Granule, PhysicalAddressSpaceSize};
+use aarch64::{Granule, PhysicalAddressSpaceSize, msr::MairEL1};
 use elf::header::Machine;
 use memory::AddressSpaceDescriptor;
 
@@ -14,7 +14,7 @@ use crate::{
             TranslationScheme,
         },
     },
-    platform::{MapError, MappingType, Permissions},
+    platform::{MapError, MappingType, OutOfMemory, Permissions},
 };
 
 /// Implementation of [`TranslationScheme`] for `aarch64` paging.
@@ -102,6 +102,13 @@ impl Aarch64TranslationScheme {
             Inner::VmsaV8(scheme) => scheme.ipa(),
         }
     }
+
+    /// Returns the `MAIR_ELx` value that the page tables were built against.
+    pub fn mair(&self) -> MairEL1 {
+        match &self.0 {
+            Inner::VmsaV8(scheme) => scheme.mair(),
+        }
+    }
 }
 
 impl TranslationScheme for Aarch64TranslationScheme {
@@ -123,6 +130,12 @@ impl TranslationScheme for Aarch64TranslationScheme {
         }
     }
 
+    fn supports_mapping_type(&self, mapping_type: MappingType) -> bool {
+        match &self.0 {
+            Inner::VmsaV8(scheme) => scheme.supports_mapping_type(mapping_type),
+        }
+    }
+
     fn map_at(
         &mut self,
         input: ExternalPageRange,
@@ -135,7 +148,7 @@ impl TranslationScheme for Aarch64TranslationScheme {
         }
     }
 
-    unsafe fn unmap(&mut self, input: ExternalPageRange) {
+    unsafe fn unmap(&mut self, input: ExternalPageRange) -> Result<(), OutOfMemory> {
         match &mut self.0 {
             // SAFETY:
             //
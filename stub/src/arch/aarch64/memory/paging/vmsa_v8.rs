@@ -5,7 +5,7 @@ use core::mem;
 use aarch64::{
     EL, Granule, PhysicalAddressSpaceSize,
     msr::{
-        Aarch64MemoryModelFeatureRegister0EL1, CurrentEl, TcrEL1,
+        Aarch64MemoryModelFeatureRegister0EL1, CurrentEl, MairEL1, MemoryAttribute, TcrEL1,
         raw::{read_ttbr0_el1, read_ttbr1_el1},
     },
     paging::{AddressSize, vmsa_v8::TranslationDescriptor},
@@ -16,7 +16,7 @@ use memory::AddressSpaceDescriptor;
 use crate::{
     arch::generic::memory::paging::{
         ExternalFrameRange, ExternalPageRange, ExternalPhysicalAddress, ExternalVirtualAddress,
-        TranslationScheme,
+        TranslationScheme, block_fits,
     },
     platform::{
         AllocationPolicy, FrameRange, MapError, MappingType, OutOfMemory, Permissions,
@@ -24,6 +24,16 @@ use crate::{
     },
 };
 
+/// The `MAIR_ELx` value used by [`VmsaV8TranslationScheme`]s that own their page tables.
+///
+/// Each [`MappingType`] has a dedicated attribute index; [`MappingType::WriteCombining`] shares
+/// the Normal non-cacheable attribute, which permits gathering.
+pub const EXECUTABLE_MAIR: MairEL1 = MairEL1::from_bits(0)
+    .set_attribute(0, MemoryAttribute::NORMAL_WRITE_BACK)
+    .set_attribute(1, MemoryAttribute::NORMAL_NONCACHEABLE)
+    .set_attribute(2, MemoryAttribute::DEVICE_NGNRE)
+    .set_attribute(3, MemoryAttribute::DEVICE_NGNRNE);
+
 /// Implementation of [`TranslationScheme`] for `aarch64` address translation.
 pub struct VmsaV8TranslationScheme {
     /// The [`Granule`] used for this paging scheme.
@@ -46,6 +56,9 @@ pub struct VmsaV8TranslationScheme {
 
     /// The output address space size.
     output: PhysicalAddressSpaceSize,
+
+    /// The memory attributes selected by the `AttrIndx` field of page and block descriptors.
+    mair: MairEL1,
 }
 
 impl VmsaV8TranslationScheme {
@@ -90,6 +103,8 @@ impl VmsaV8TranslationScheme {
                 t1sz,
 
                 output,
+
+                mair: EXECUTABLE_MAIR,
             }
         } else if el == EL::EL2 {
             todo!("implement EL2")
@@ -138,6 +153,12 @@ impl VmsaV8TranslationScheme {
 
             let output = tcr_el1.ipas();
 
+            // SAFETY:
+            //
+            // Since the program is in [`EL::EL1`], it is safe to read
+            // [`MairEL1`].
+            let mair = unsafe { MairEL1::get() };
+
             Self {
                 granule,
 
@@ -151,6 +172,8 @@ impl VmsaV8TranslationScheme {
                 t1sz,
 
                 output,
+
+                mair,
             }
         } else if el == EL::EL2 {
             todo!("implement EL2")
@@ -215,6 +238,37 @@ impl VmsaV8TranslationScheme {
         self.output
     }
 
+    /// Returns the `MAIR_ELx` value that the page tables were built against.
+    pub const fn mair(&self) -> MairEL1 {
+        self.mair
+    }
+
+    /// Returns the index of the first attribute in `MAIR_ELx` that implements `mapping_type`.
+    fn attribute_index(&self, mapping_type: MappingType) -> Option<u8> {
+        (0..MairEL1::ATTRIBUTES).find(|&index| {
+            let attribute = self.mair.attribute(index);
+            match mapping_type {
+                MappingType::Normal => attribute.is_normal_write_back(),
+                MappingType::NormalNoncacheable | MappingType::WriteCombining => {
+                    attribute.is_normal_noncacheable()
+                }
+                MappingType::Device => attribute.is_device(),
+            }
+        })
+    }
+
+    /// Returns the [`MappingType`] that best describes the attribute at `index` in `MAIR_ELx`.
+    fn mapping_type(&self, index: u8) -> MappingType {
+        let attribute = self.mair.attribute(index);
+        if attribute.is_device() {
+            MappingType::Device
+        } else if attribute.is_normal_noncacheable() {
+            MappingType::NormalNoncacheable
+        } else {
+            MappingType::Normal
+        }
+    }
+
     /// Returns the number of entries in each table.
     const fn entries_per_table(&self) -> u32 {
         self.granule.size() / 8
@@ -267,6 +321,68 @@ impl VmsaV8TranslationScheme {
         mem::forget(range);
         Some(frame_range)
     }
+
+    /// Returns `true` if block descriptors are permitted in tables `depth` levels above the last
+    /// level.
+    const fn block_allowed(&self, depth: u32) -> bool {
+        match self.granule {
+            Granule::Page4KiB => depth == 1 || depth == 2,
+            Granule::Page16KiB | Granule::Page64KiB => depth == 1,
+        }
+    }
+
+    /// Replaces the block descriptor at `entry_address`, which maps `block_size` bytes, with a
+    /// table of smaller translations that map the same region with the same attributes, returning
+    /// the new table descriptor.
+    ///
+    /// If `last_level` is `true`, the new table holds page descriptors rather than block
+    /// descriptors.
+    fn split_block(
+        &mut self,
+        entry_address: PhysicalAddress,
+        entry: TranslationDescriptor,
+        block_size: u64,
+        last_level: bool,
+    ) -> Result<TranslationDescriptor, OutOfMemory> {
+        let frame = self.allocate_zeroed_table().ok_or(OutOfMemory)?;
+        let table_address = frame.start_address();
+
+        let entries = u64::from(self.entries_per_table());
+        let child_size = block_size / entries;
+        let block_address = entry.block_address(self.granule, self.output_size());
+        let attributes = entry.set_block_address(self.granule, self.output_size(), 0);
+        for index in 0..entries {
+            let address = block_address + index * child_size;
+            let child = if last_level {
+                attributes.set_page(true).set_page_address(
+                    self.granule,
+                    self.output_size(),
+                    address,
+                )
+            } else {
+                attributes.set_block_address(self.granule, self.output_size(), address)
+            };
+
+            if !write_u64_at(table_address.strict_add(index * 8), child.to_bits()) {
+                panic!("failed to write to physical memory")
+            }
+        }
+
+        let table = TranslationDescriptor::non_present()
+            .set_present(true)
+            .set_table(true)
+            .set_table_address(self.granule, self.output_size(), table_address.value());
+
+        if !write_u64_at(entry_address, 0) {
+            panic!("failed to write to physical memory")
+        }
+
+        if !write_u64_at(entry_address, table.to_bits()) {
+            panic!("failed to write to physical memory")
+        }
+
+        Ok(table)
+    }
 }
 
 impl TranslationScheme for VmsaV8TranslationScheme {
@@ -286,6 +402,10 @@ impl TranslationScheme for VmsaV8TranslationScheme {
         u64::from(self.granule.size())
     }
 
+    fn supports_mapping_type(&self, mapping_type: MappingType) -> bool {
+        self.attribute_index(mapping_type).is_some()
+    }
+
     fn map_at(
         &mut self,
         input: ExternalPageRange,
@@ -294,9 +414,9 @@ impl TranslationScheme for VmsaV8TranslationScheme {
         mapping_type: MappingType,
     ) -> Result<(), MapError> {
         // TODO: Implement permissions.
-        if mapping_type != MappingType::Normal {
-            todo!("implement cacheability modifiers")
-        }
+        let attribute_index = self
+            .attribute_index(mapping_type)
+            .ok_or(MapError::FindFreeRegionError)?;
 
         let input_range = input.address_range(self.chunk_size());
         if !self.input_descriptor().is_valid_range(
@@ -343,9 +463,19 @@ impl TranslationScheme for VmsaV8TranslationScheme {
         let address_space_bits = u32::from(64 - txsz);
         let target_bits = address_space_bits - offset_bits;
         let levels = self.levels(txsz);
-        for (input_chunk, output_chunk) in input.iter().zip(output.iter()) {
-            let input_address = input_chunk.start_address(self.chunk_size()).value();
-            let output_address = output_chunk.start_address(self.chunk_size()).value();
+        let mut page_index = 0;
+        'pages: while page_index < input.count() {
+            let input_address = input
+                .start()
+                .strict_add(page_index)
+                .start_address(self.chunk_size())
+                .value();
+            let output_address = output
+                .start()
+                .strict_add(page_index)
+                .start_address(self.chunk_size())
+                .value();
+            let remaining = (input.count() - page_index) * self.chunk_size();
 
             let mut level = 0;
             let mut base_bit = (levels - 1) * bits_per_level;
@@ -368,6 +498,7 @@ impl TranslationScheme for VmsaV8TranslationScheme {
                         .set_present(true)
                         .set_page(true)
                         .set_page_block_accessed(true)
+                        .set_page_block_attribute_index(attribute_index)
                         .set_page_address(self.granule, self.output_size(), output_address);
 
                     if !write_u64_at(entry_address, 0) {
@@ -380,10 +511,28 @@ impl TranslationScheme for VmsaV8TranslationScheme {
                     break;
                 }
 
+                let block_size = 1u64 << (base_bit + offset_bits);
                 let entry_value =
                     read_u64_at(entry_address).expect("failed to read from physical memory");
                 let entry = TranslationDescriptor::from_bits(entry_value);
                 if !entry.present() {
+                    if self.block_allowed(levels - 1 - level)
+                        && block_fits(input_address, output_address, remaining, block_size)
+                    {
+                        let descriptor = TranslationDescriptor::non_present()
+                            .set_present(true)
+                            .set_page_block_accessed(true)
+                            .set_page_block_attribute_index(attribute_index)
+                            .set_block_address(self.granule, self.output_size(), output_address);
+
+                        if !write_u64_at(entry_address, descriptor.to_bits()) {
+                            panic!("failed to write to physical memory")
+                        }
+
+                        page_index += block_size / self.chunk_size();
+                        continue 'pages;
+                    }
+
                     let new_table = self
                         .allocate_zeroed_table()
                         .ok_or(MapError::FrameAllocation(OutOfMemory))?;
@@ -409,18 +558,24 @@ impl TranslationScheme for VmsaV8TranslationScheme {
                     table_address =
                         PhysicalAddress::new(entry.table_address(self.granule, self.output_size()));
                 } else {
-                    unimplemented!("implement block splitting")
+                    let table = self
+                        .split_block(entry_address, entry, block_size, level + 1 == levels - 1)
+                        .map_err(MapError::FrameAllocation)?;
+                    table_address =
+                        PhysicalAddress::new(table.table_address(self.granule, self.output_size()));
                 }
 
                 base_bit = base_bit.saturating_sub(bits_per_level);
                 level += 1;
             }
+
+            page_index += 1;
         }
 
         Ok(())
     }
 
-    unsafe fn unmap(&mut self, input: ExternalPageRange) {
+    unsafe fn unmap(&mut self, input: ExternalPageRange) -> Result<(), OutOfMemory> {
         let input_range = input.address_range(self.chunk_size());
 
         assert!(self.input_descriptor().is_valid_range(
@@ -444,8 +599,14 @@ impl TranslationScheme for VmsaV8TranslationScheme {
         let address_space_bits = u32::from(64 - txsz);
         let target_bits = address_space_bits - offset_bits;
         let levels = self.levels(txsz);
-        for page in input.iter() {
-            let input_address = page.start_address(self.chunk_size()).value();
+        let mut page_index = 0;
+        'pages: while page_index < input.count() {
+            let input_address = input
+                .start()
+                .strict_add(page_index)
+                .start_address(self.chunk_size())
+                .value();
+            let remaining = (input.count() - page_index) * self.chunk_size();
 
             let mut level = 0;
             let mut base_bit = (levels - 1) * bits_per_level;
@@ -473,22 +634,46 @@ impl TranslationScheme for VmsaV8TranslationScheme {
                     break;
                 }
 
+                let block_size = 1u64 << (base_bit + offset_bits);
                 let entry_value =
                     read_u64_at(entry_address).expect("failed to read from physical memory");
                 let entry = TranslationDescriptor::from_bits(entry_value);
                 if !entry.present() {
-                    break;
+                    let skip = (block_size - input_address % block_size).min(remaining);
+                    page_index += skip / self.chunk_size();
+                    continue 'pages;
                 } else if entry.table() {
                     table_address =
                         PhysicalAddress::new(entry.table_address(self.granule, self.output_size()));
+                } else if block_fits(input_address, 0, remaining, block_size) {
+                    if !write_u64_at(
+                        entry_address,
+                        TranslationDescriptor::non_present().to_bits(),
+                    ) {
+                        panic!("failed to write to physical memory")
+                    }
+
+                    page_index += block_size / self.chunk_size();
+                    continue 'pages;
                 } else {
-                    unimplemented!("implement block splitting")
+                    let table = self.split_block(
+                        entry_address,
+                        entry,
+                        block_size,
+                        level + 1 == levels - 1,
+                    )?;
+                    table_address =
+                        PhysicalAddress::new(table.table_address(self.granule, self.output_size()));
                 }
 
                 base_bit = base_bit.saturating_sub(bits_per_level);
                 level += 1;
             }
+
+            page_index += 1;
         }
+
+        Ok(())
     }
 
     fn translate(
@@ -549,9 +734,9 @@ impl TranslationScheme for VmsaV8TranslationScheme {
                 let page_offset_mask = u64::from(self.granule.size()) - 1;
                 let page_offset = address.value() & page_offset_mask;
 
-                // TODO: Fix permissions and mapping type computation.
+                // TODO: Fix permissions computation.
                 let permissions = Permissions::Read;
-                let mapping_type = MappingType::Normal;
+                let mapping_type = self.mapping_type(entry.page_block_attribute_index());
                 let physical_address = ExternalPhysicalAddress::new(output_base | page_offset);
                 return Some((permissions, mapping_type, physical_address));
             } else if entry.table() {
@@ -563,9 +748,9 @@ impl TranslationScheme for VmsaV8TranslationScheme {
                 let page_offset_mask = (1u64 << (base_bit + offset_bits)) - 1;
                 let page_offset = address.value() & page_offset_mask;
 
-                // TODO: Fix permissions and mapping type computation.
+                // TODO: Fix permissions computation.
                 let permissions = Permissions::Read;
-                let mapping_type = MappingType::Normal;
+                let mapping_type = self.mapping_type(entry.page_block_attribute_index());
                 let physical_address = ExternalPhysicalAddress::new(output_base | page_offset);
                 return Some((permissions, mapping_type, physical_address));
             }
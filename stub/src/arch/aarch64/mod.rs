@@ -1,5 +1,32 @@
 //! Structures and functionality that are specific to `aarch64`.
 
+pub mod exception;
 pub mod memory;
 pub mod relocation;
 pub mod switch;
+
+/// Mask over the affinity fields of `MPIDR_EL1`.
+pub const MPIDR_AFFINITY_MASK: u64 = 0xFF_00FF_FFFF;
+
+/// Returns the affinity fields of the current processor's `MPIDR_EL1` register.
+pub fn current_hardware_id() -> u64 {
+    // SAFETY:
+    //
+    // `MPIDR_EL1` is always readable at EL1 and above.
+    let mpidr = unsafe { aarch64::msr::raw::read_mpidr_el1() };
+    mpidr & MPIDR_AFFINITY_MASK
+}
+
+/// Returns the current value of the generic timer's virtual counter.
+pub fn read_counter() -> u64 {
+    // SAFETY:
+    //
+    // An instruction synchronization barrier does not affect memory safety, and prevents the
+    // counter from being read early.
+    unsafe { core::arch::asm!("isb", options(nomem, nostack, preserves_flags)) }
+
+    // SAFETY:
+    //
+    // `CNTVCT_EL0` is always readable at EL1 and above.
+    unsafe { aarch64::msr::raw::read_cntvct_el0() }
+}
@@ -23,7 +23,7 @@ use crate::{
         memory::ArchTranslationScheme,
     },
     platform::{
-        Permissions, allocate_frames_aligned, frame_size, map_identity, write_bytes_at,
+        MemoryType, Permissions, allocate_frames_aligned, frame_size, map_identity, write_bytes_at,
         write_u64_at,
     },
 };
@@ -44,6 +44,7 @@ pub fn allocate_code(
         code_size_u64.div_ceil(frame_size()),
         scheme.chunk_size(),
         arch_policy(),
+        MemoryType::BootloaderReclaimable,
     )?;
 
     // Map code region into the two address spaces.
@@ -9,6 +9,7 @@ use sync::Spinlock;
 
 use crate::{
     arch::{
+        aarch64::exception::load_exception_handlers,
         generic::switch::{
             function::{
                 ENTER_FUNC_ID, EXEC_ON_PROCESSOR_FUNC_ID, MAX_GENERIC_EXECUTABLE_ID,
@@ -132,6 +133,7 @@ pub fn base_cpu_storage(scheme: &mut ArchTranslationScheme) -> CpuStorage {
             ttbr0_elx,
             ttbr1_elx,
 
+            mair_elx: scheme.mair().to_bits(),
             sctlr_elx: 0b1,
 
             ..Default::default()
@@ -202,6 +204,10 @@ type ExecAllData<'a> = (&'a [Spinlock<CpuData>], u64, u64);
 
 /// Executes the provided function on the provided processor.
 extern "C" fn exec_all(cpu_id: u64, arg: *mut ()) {
+    // Processors other than the main processor have not loaded the stub's exception handlers, and
+    // the handlers loaded here are restored whenever the executable calls back into the stub.
+    load_exception_handlers();
+
     let arg = arg.cast::<ExecAllData>();
     // SAFETY:
     //
@@ -0,0 +1,99 @@
+//! Architecture-independent support for reporting unhandled exceptions.
+
+use core::{
+    fmt, mem, ptr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::util::image_start;
+
+/// The maximum number of frames printed as part of a backtrace.
+const MAX_BACKTRACE_DEPTH: usize = 64;
+
+/// The number of exceptions that are currently being reported.
+static EXCEPTION_DEPTH: AtomicUsize = AtomicUsize::new(0);
+
+unsafe extern "C" {
+    #[link_name = "_text_start"]
+    static TEXT_START: u8;
+    #[link_name = "_text_end"]
+    static TEXT_END: u8;
+}
+
+/// Marks the start of the report of an unhandled exception.
+///
+/// If an exception occurs while another exception is being reported, the report is abandoned and
+/// the processor halts, since the reporting machinery itself cannot be trusted.
+pub fn begin_exception() {
+    match EXCEPTION_DEPTH.fetch_add(1, Ordering::AcqRel) {
+        0 => {}
+        1 => {
+            crate::error!("nested exception while reporting an unhandled exception");
+            halt()
+        }
+        _ => halt(),
+    }
+}
+
+/// Logs the backtrace formed by the chain of frame records starting at `frame_pointer`.
+///
+/// Every frame record is expected to be a pair of pointer-sized values: the frame pointer of the
+/// caller followed by the return address.
+pub fn log_backtrace(mut frame_pointer: usize) {
+    crate::error!("backtrace:");
+
+    for depth in 0..MAX_BACKTRACE_DEPTH {
+        if frame_pointer == 0 || !frame_pointer.is_multiple_of(mem::align_of::<usize>()) {
+            return;
+        }
+
+        let record = ptr::with_exposed_provenance::<[usize; 2]>(frame_pointer);
+        // SAFETY:
+        //
+        // The frame pointer is non-null and properly aligned. If the frame record chain is
+        // corrupted, the resulting fault is caught by [`begin_exception()`].
+        let [next_frame_pointer, return_address] = unsafe { record.read_volatile() };
+        if return_address == 0 {
+            return;
+        }
+
+        crate::error!("  #{depth:<2} {}", CodeAddress(return_address));
+
+        // Stacks grow downwards, so frame records must be located at increasing addresses.
+        if next_frame_pointer <= frame_pointer {
+            return;
+        }
+        frame_pointer = next_frame_pointer;
+    }
+
+    crate::error!("  ...");
+}
+
+/// Halts the current processor.
+fn halt() -> ! {
+    loop {
+        core::hint::spin_loop()
+    }
+}
+
+/// An address that refers to code, which is displayed relative to the stub's image if it falls
+/// within the stub's code.
+///
+/// Since the stub is linked at address zero, the displayed offset can be symbolized directly
+/// against the stub's ELF file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CodeAddress(pub usize);
+
+impl fmt::Display for CodeAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text_start = (&raw const TEXT_START).addr();
+        let text_end = (&raw const TEXT_END).addr();
+
+        write!(f, "{:#018x}", self.0)?;
+        if (text_start..text_end).contains(&self.0) {
+            write!(f, " <revm-stub+{:#x}>", self.0 - image_start())?;
+        }
+
+        Ok(())
+    }
+}
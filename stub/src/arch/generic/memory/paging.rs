@@ -3,7 +3,7 @@
 
 use memory::AddressSpaceDescriptor;
 
-use crate::platform::{MapError, MappingType, Permissions};
+use crate::platform::{MapError, MappingType, OutOfMemory, Permissions};
 
 /// A trait representing low-level [`ExternalVirtualAddress`] translation.
 ///
@@ -28,6 +28,10 @@ pub trait TranslationScheme {
     /// Returns the size, in bytes, of the smallest translation granule.
     fn chunk_size(&self) -> u64;
 
+    /// Returns `true` if memory mapped with `mapping_type` receives exactly that [`MappingType`]
+    /// rather than a substitute.
+    fn supports_mapping_type(&self, mapping_type: MappingType) -> bool;
+
     /// Maps the provided [`ExternalFrameRange`] into the input address space with the
     /// requested [`Permissions`] and [`MappingType`].
     ///
@@ -100,10 +104,16 @@ pub trait TranslationScheme {
 
     /// Unmaps the [`ExternalPageRange`] from the [`TranslationScheme`].
     ///
+    /// # Errors
+    ///
+    /// Returns [`OutOfMemory`] if a block that is partially covered by `input` could not be split
+    /// because a table could not be allocated. The part of `input` that precedes the block has
+    /// been unmapped, while the block and the rest of `input` remain mapped.
+    ///
     /// # Safety
     ///
     /// The [`ExternalPageRange`] in the input address space must not be in use.
-    unsafe fn unmap(&mut self, input: ExternalPageRange);
+    unsafe fn unmap(&mut self, input: ExternalPageRange) -> Result<(), OutOfMemory>;
 
     /// Translates the provided [`ExternalVirtualAddress`] in the input address space into its
     /// corresponding [`ExternalPhysicalAddress`] in the output address space and returns its
@@ -254,6 +264,20 @@ pub trait TranslationScheme {
     }
 }
 
+/// Returns `true` if a block translation of `block_size` bytes can map `input_address` to
+/// `output_address` when `remaining` bytes of the mapping are left, that is, if both addresses are
+/// aligned to `block_size` and the block does not extend past the end of the mapping.
+pub const fn block_fits(
+    input_address: u64,
+    output_address: u64,
+    remaining: u64,
+    block_size: u64,
+) -> bool {
+    input_address.is_multiple_of(block_size)
+        && output_address.is_multiple_of(block_size)
+        && remaining >= block_size
+}
+
 /// Strategies for searching the input address space to locate an unmapped region capable of
 /// holding a requested address range.
 pub enum SearchStrategy {
@@ -334,3 +358,67 @@ memory::implement_address_chunk_range!(
     intersection_base_count_u64,
     partition_base_count_u64
 );
+
+/// Helpers for exercising [`TranslationScheme`] implementations on the mock platform.
+#[cfg(test)]
+pub mod test_util {
+    use super::{
+        ExternalFrame, ExternalFrameRange, ExternalPage, ExternalPageRange,
+        ExternalPhysicalAddress, ExternalVirtualAddress, TranslationScheme,
+    };
+    use crate::platform::{MappingType, Permissions};
+
+    /// Returns the [`ExternalPageRange`] covering the `size` bytes at `input`.
+    fn pages<T: TranslationScheme>(scheme: &T, input: u64, size: u64) -> ExternalPageRange {
+        let start = ExternalPage::containing_address(
+            ExternalVirtualAddress::new(input),
+            scheme.chunk_size(),
+        );
+        ExternalPageRange::new(start, start.strict_add(size / scheme.chunk_size() - 1))
+    }
+
+    /// Maps the `size` bytes at `input` to `output` as read-write [`MappingType::Normal`] memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region could not be mapped.
+    pub fn map<T: TranslationScheme>(scheme: &mut T, input: u64, output: u64, size: u64) {
+        let frames = ExternalFrameRange::new(
+            ExternalFrame::containing_address(
+                ExternalPhysicalAddress::new(output),
+                scheme.chunk_size(),
+            ),
+            size / scheme.chunk_size(),
+        );
+
+        scheme
+            .map_at(
+                pages(scheme, input, size),
+                frames,
+                Permissions::ReadWrite,
+                MappingType::Normal,
+            )
+            .expect("failed to map region");
+    }
+
+    /// Unmaps the `size` bytes at `input`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region could not be unmapped.
+    pub fn unmap<T: TranslationScheme>(scheme: &mut T, input: u64, size: u64) {
+        let pages = pages(scheme, input, size);
+
+        // SAFETY:
+        //
+        // The input address space of `scheme` is never accessed.
+        unsafe { scheme.unmap(pages) }.expect("failed to unmap region");
+    }
+
+    /// Returns the output address to which `input` translates.
+    pub fn translate<T: TranslationScheme>(scheme: &T, input: u64) -> Option<u64> {
+        scheme
+            .translate(ExternalVirtualAddress::new(input))
+            .map(|(_, _, output)| output.value())
+    }
+}
@@ -1,4 +1,5 @@
 //! Definitions, interfaces, and wrappers for architecture-specific code.
 
+pub mod exception;
 pub mod memory;
 pub mod switch;
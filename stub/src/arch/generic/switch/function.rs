@@ -18,11 +18,11 @@ use crate::{
         SearchStrategy, TranslationScheme,
     },
     platform::{
-        AllocationPolicy, BufferTooSmall, Frame, FrameRange, MapError, MappingType,
-        MemoryDescriptor, MemoryType, OutOfMemory, Permissions, PhysicalAddress,
+        AllocationPolicy, BufferTooSmall, Frame, FrameRange, LogLevel, MapError, MappingType,
+        MemoryAttributes, MemoryDescriptor, MemoryType, OutOfMemory, Permissions, PhysicalAddress,
         PhysicalAddressRange, allocate, allocate_frames_aligned, deallocate, deallocate_frames,
-        frame_size, memory_map, read_bytes_at, read_u32_at, read_u64_at, write_u32_at,
-        write_u64_at,
+        frame_size, log_executable_output, memory_map, read_bytes_at, read_u32_at, read_u64_at,
+        write_u32_at, write_u64_at,
     },
 };
 
@@ -96,6 +96,8 @@ fn write_func<T: TranslationScheme>(scheme: &mut T, arg_0: u64, arg_1: u64) -> R
     let mut string_len = arg_1;
     if string_ptr == 0 {
         return Err(Status::INVALID_USAGE);
+    } else if string_len == 0 {
+        return Ok(());
     }
 
     let start_ptr = ExternalVirtualAddress::new(string_ptr);
@@ -169,7 +171,7 @@ fn write_func<T: TranslationScheme>(scheme: &mut T, arg_0: u64, arg_1: u64) -> R
         };
 
         if !valid_str.is_empty() {
-            crate::info!("{valid_str}");
+            log_executable_output(LogLevel::Info, format_args!("{valid_str}"));
         }
 
         // Handle partial sequence carry over.
@@ -198,7 +200,11 @@ fn allocate_frames_func<T: TranslationScheme>(
     let alignment = arg_1;
     let flags = arg_2;
     let physical_address_ptr = arg_3;
-    if count == 0 || physical_address_ptr == 0 || flags & AllocationFlags::VALID.0 != flags {
+    if count == 0
+        || !alignment.is_power_of_two()
+        || physical_address_ptr == 0
+        || flags & AllocationFlags::VALID.0 != flags
+    {
         return Err(Status::INVALID_USAGE);
     }
 
@@ -215,6 +221,9 @@ fn allocate_frames_func<T: TranslationScheme>(
             let Some(value) = read_u64_at(physical_address_ptr_address) else {
                 return Err(Status::INVALID_USAGE);
             };
+            if !value.is_multiple_of(frame_size()) {
+                return Err(Status::INVALID_USAGE);
+            }
 
             AllocationPolicy::At(value)
         }
@@ -235,7 +244,12 @@ fn allocate_frames_func<T: TranslationScheme>(
         return Err(Status::OUT_OF_MEMORY);
     };
 
-    let Ok(frame_allocation) = allocate_frames_aligned(frame_count, alignment, policy) else {
+    let Ok(frame_allocation) = allocate_frames_aligned(
+        frame_count,
+        alignment,
+        policy,
+        MemoryType::BootloaderReclaimable,
+    ) else {
         return Err(Status::OUT_OF_MEMORY);
     };
 
@@ -335,15 +349,18 @@ fn get_memory_map_func<T: TranslationScheme>(
         return Err(Status::INVALID_USAGE);
     };
 
+    let total_buffer_size = required_count
+        .checked_mul(mem::size_of::<stub_api::MemoryDescriptor>())
+        .expect("buffer is too large");
     let write_success = if bits_32 {
         write_u32_at(
             PhysicalAddress::new(size_physical_address.value()),
-            usize_to_u32_strict(required_count),
+            usize_to_u32_strict(total_buffer_size),
         )
     } else {
         write_u64_at(
             PhysicalAddress::new(size_physical_address.value()),
-            usize_to_u64(required_count),
+            usize_to_u64(total_buffer_size),
         )
     };
     if !write_success {
@@ -351,9 +368,6 @@ fn get_memory_map_func<T: TranslationScheme>(
         return Err(Status::INVALID_USAGE);
     }
 
-    let total_buffer_size = required_count
-        .checked_mul(mem::size_of::<stub_api::MemoryDescriptor>())
-        .expect("buffer is too large");
     let total_buffer_size = usize_to_u64(total_buffer_size);
     if buffer_size < total_buffer_size {
         return Err(Status::BUFFER_TOO_SMALL);
@@ -409,15 +423,19 @@ fn get_memory_map_func<T: TranslationScheme>(
 
         let start_ptr = descriptor_ptr.strict_add(usize_to_u64(mem::offset_of!(
             stub_api::MemoryDescriptor,
-            start
+            v0.start
         )));
         let count_ptr = descriptor_ptr.strict_add(usize_to_u64(mem::offset_of!(
             stub_api::MemoryDescriptor,
-            count
+            v0.count
         )));
         let region_type_ptr = descriptor_ptr.strict_add(usize_to_u64(mem::offset_of!(
             stub_api::MemoryDescriptor,
-            region_type
+            v0.region_type
+        )));
+        let attributes_ptr = descriptor_ptr.strict_add(usize_to_u64(mem::offset_of!(
+            stub_api::MemoryDescriptor,
+            attributes
         )));
 
         let Some((_, _, start_physical_address)) =
@@ -435,6 +453,11 @@ fn get_memory_map_func<T: TranslationScheme>(
         else {
             return Err(Status::INVALID_USAGE);
         };
+        let Some((_, _, attributes_physical_address)) =
+            scheme.translate(ExternalVirtualAddress::new(attributes_ptr))
+        else {
+            return Err(Status::INVALID_USAGE);
+        };
 
         if !write_u64_at(
             PhysicalAddress::new(start_physical_address.value()),
@@ -459,6 +482,14 @@ fn get_memory_map_func<T: TranslationScheme>(
             MemoryType::Reserved => stub_api::MemoryType::RESERVED,
             MemoryType::AcpiReclaimable => stub_api::MemoryType::ACPI_RECLAIMABLE,
             MemoryType::AcpiNonVolatile => stub_api::MemoryType::ACPI_NON_VOLATILE,
+            MemoryType::RuntimeServicesCode => stub_api::MemoryType::RUNTIME_SERVICES_CODE,
+            MemoryType::RuntimeServicesData => stub_api::MemoryType::RUNTIME_SERVICES_DATA,
+            MemoryType::Mmio => stub_api::MemoryType::MMIO,
+            MemoryType::Persistent => stub_api::MemoryType::PERSISTENT,
+            MemoryType::Framebuffer => stub_api::MemoryType::FRAMEBUFFER,
+            MemoryType::Executable => stub_api::MemoryType::EXECUTABLE,
+            MemoryType::ProtocolTable => stub_api::MemoryType::PROTOCOL_TABLE,
+            MemoryType::LogRing => stub_api::MemoryType::LOG_RING,
         };
         if !write_u32_at(
             PhysicalAddress::new(region_type_physical_address.value()),
@@ -467,6 +498,15 @@ fn get_memory_map_func<T: TranslationScheme>(
             crate::warn!("error writing descriptor to get_memory_map buffer");
             return Err(Status::INVALID_USAGE);
         }
+
+        let attributes = memory_attributes(descriptor.attributes);
+        if !write_u64_at(
+            PhysicalAddress::new(attributes_physical_address.value()),
+            attributes.0,
+        ) {
+            crate::warn!("error writing descriptor to get_memory_map buffer");
+            return Err(Status::INVALID_USAGE);
+        }
     }
 
     let size = map
@@ -520,6 +560,33 @@ fn get_memory_map_func<T: TranslationScheme>(
     Ok(())
 }
 
+/// Returns the bitmask of the [`stub_api::MappingType`]s that [`map_func()`] accepts for `scheme`.
+pub fn supported_mapping_types<T: TranslationScheme>(scheme: &T) -> u64 {
+    [
+        stub_api::MappingType::NORMAL,
+        stub_api::MappingType::NORMAL_NONCACHEABLE,
+        stub_api::MappingType::DEVICE,
+        stub_api::MappingType::WRITE_COMBINING,
+    ]
+    .into_iter()
+    .filter(|&mapping_type| {
+        self::mapping_type(mapping_type)
+            .is_some_and(|mapping_type| scheme.supports_mapping_type(mapping_type))
+    })
+    .fold(0, |mask, mapping_type| mask | (1 << mapping_type.0))
+}
+
+/// Returns the [`MappingType`] that corresponds to the provided [`stub_api::MappingType`].
+fn mapping_type(mapping_type: stub_api::MappingType) -> Option<MappingType> {
+    match mapping_type {
+        stub_api::MappingType::NORMAL => Some(MappingType::Normal),
+        stub_api::MappingType::NORMAL_NONCACHEABLE => Some(MappingType::NormalNoncacheable),
+        stub_api::MappingType::DEVICE => Some(MappingType::Device),
+        stub_api::MappingType::WRITE_COMBINING => Some(MappingType::WriteCombining),
+        _ => None,
+    }
+}
+
 /// Implementation of [`stub_api::GenericTable::map`].
 fn map_func<T: TranslationScheme>(
     scheme: &mut T,
@@ -537,23 +604,36 @@ fn map_func<T: TranslationScheme>(
         return Err(Status::INVALID_USAGE);
     }
 
+    let may_overwrite =
+        flags & stub_api::MapFlags::MAY_OVERWRITE.0 == stub_api::MapFlags::MAY_OVERWRITE.0;
+    let may_choose =
+        flags & stub_api::MapFlags::CHOOSE_LOCATION.0 == stub_api::MapFlags::CHOOSE_LOCATION.0;
+
     let virtual_address_ptr = ExternalVirtualAddress::new(virtual_address_ptr);
     let Some((_, _, virtual_address_physical_address)) = scheme.translate(virtual_address_ptr)
     else {
         return Err(Status::INVALID_USAGE);
     };
-    let Some(virtual_address) = read_u64_at(PhysicalAddress::new(
-        virtual_address_physical_address.value(),
-    )) else {
+    let virtual_address_physical_address =
+        PhysicalAddress::new(virtual_address_physical_address.value());
+
+    let bits_32 = bits_32(scheme);
+    let virtual_address = if bits_32 {
+        read_u32_at(virtual_address_physical_address).map(u64::from)
+    } else {
+        read_u64_at(virtual_address_physical_address)
+    };
+    let Some(virtual_address) = virtual_address else {
         return Err(Status::INVALID_USAGE);
     };
     let virtual_address = ExternalVirtualAddress::new(virtual_address);
 
-    if count != 0 {
+    if count == 0 {
         return Err(Status::INVALID_USAGE);
     }
 
-    if !virtual_address.is_aligned(page_frame_size(scheme))
+    // The initial value of `virtual_address` is ignored when the location is chosen.
+    if (!may_choose && !virtual_address.is_aligned(page_frame_size(scheme)))
         || !physical_address.is_aligned(page_frame_size(scheme))
     {
         return Err(Status::INVALID_USAGE);
@@ -568,6 +648,12 @@ fn map_func<T: TranslationScheme>(
         (false, false) => Permissions::Read,
     };
 
+    let Some(mapping_type) = mapping_type(stub_api::MapFlags(flags).mapping_type())
+        .filter(|&mapping_type| scheme.supports_mapping_type(mapping_type))
+    else {
+        return Err(Status::NOT_SUPPORTED);
+    };
+
     let Some(page_count) = count
         .checked_mul(page_frame_size(scheme))
         .map(|total_bytes| total_bytes.div_ceil(scheme.chunk_size()))
@@ -576,7 +662,9 @@ fn map_func<T: TranslationScheme>(
     };
 
     let start = ExternalPage::containing_address(virtual_address, scheme.chunk_size());
-    let end = start.strict_add(page_count.saturating_sub(1));
+    let Some(end) = start.checked_add(page_count.saturating_sub(1)) else {
+        return Err(Status::INVALID_USAGE);
+    };
     let page_range = ExternalPageRange::new(start, end);
 
     let frame_range = ExternalFrameRange::new(
@@ -584,10 +672,6 @@ fn map_func<T: TranslationScheme>(
         page_count,
     );
 
-    let may_overwrite =
-        flags & stub_api::MapFlags::MAY_OVERWRITE.0 == stub_api::MapFlags::MAY_OVERWRITE.0;
-    let may_choose =
-        flags & stub_api::MapFlags::CHOOSE_LOCATION.0 == stub_api::MapFlags::CHOOSE_LOCATION.0;
     if may_overwrite && may_choose {
         return Err(Status::NOT_SUPPORTED);
     }
@@ -596,7 +680,7 @@ fn map_func<T: TranslationScheme>(
         // SAFETY:
         //
         // The executable requested this operation.
-        unsafe { scheme.unmap(page_range) }
+        unsafe { scheme.unmap(page_range) }.map_err(|OutOfMemory| Status::OUT_OF_MEMORY)?;
     }
 
     let result = if may_choose {
@@ -605,16 +689,21 @@ fn map_func<T: TranslationScheme>(
                 SearchStrategy::TopDown,
                 frame_range,
                 permissions,
-                MappingType::Normal,
+                mapping_type,
             )
             .map(|page_range| {
-                write_u64_at(
-                    PhysicalAddress::new(virtual_address_physical_address.value()),
-                    page_range.start_address(page_frame_size(scheme)).value(),
-                );
+                let virtual_address = page_range.start_address(scheme.chunk_size()).value();
+                if bits_32 {
+                    write_u32_at(
+                        virtual_address_physical_address,
+                        u32::try_from(virtual_address).expect("address is within 32-bit space"),
+                    );
+                } else {
+                    write_u64_at(virtual_address_physical_address, virtual_address);
+                }
             })
     } else {
-        scheme.map_at(page_range, frame_range, permissions, MappingType::Normal)
+        scheme.map_at(page_range, frame_range, permissions, mapping_type)
     };
     match result {
         Ok(()) => Ok(()),
@@ -642,14 +731,15 @@ fn unmap_func<T: TranslationScheme>(scheme: &mut T, arg_0: u64, arg_1: u64) -> R
         ExternalVirtualAddress::new(virtual_address),
         scheme.chunk_size(),
     );
-    let end = start.strict_add(page_count.saturating_sub(1));
+    let Some(end) = start.checked_add(page_count.saturating_sub(1)) else {
+        return Err(Status::INVALID_USAGE);
+    };
     let virtual_chunk = ExternalPageRange::new(start, end);
 
     // SAFETY:
     //
     // The application requested that said page range was unmapped.
-    unsafe { scheme.unmap(virtual_chunk) }
-    Ok(())
+    unsafe { scheme.unmap(virtual_chunk) }.map_err(|OutOfMemory| Status::OUT_OF_MEMORY)
 }
 
 /// Implementation of [`stub_api::GenericTable::takeover`].
@@ -660,6 +750,12 @@ fn takeover_func(arg_0: u64, arg_1: u64) -> Result<(), Status> {
         return Err(Status::INVALID_USAGE);
     }
 
+    let mut map = MEMORY_MAP.lock();
+    map.update();
+    if key != map.key {
+        return Err(Status::INVALID_KEY);
+    }
+
     todo!("implement takeover({key:#x}, {flags:?})")
 }
 
@@ -682,6 +778,38 @@ fn bits_32<T: TranslationScheme>(scheme: &T) -> bool {
         <= u64::from(u32::MAX)
 }
 
+/// Returns the [`stub_api::MemoryAttributes`] that correspond to the provided
+/// [`MemoryAttributes`].
+fn memory_attributes(attributes: MemoryAttributes) -> stub_api::MemoryAttributes {
+    [
+        (
+            MemoryAttributes::UNCACHEABLE,
+            stub_api::MemoryAttributes::UNCACHEABLE,
+        ),
+        (
+            MemoryAttributes::WRITE_COMBINING,
+            stub_api::MemoryAttributes::WRITE_COMBINING,
+        ),
+        (
+            MemoryAttributes::WRITE_THROUGH,
+            stub_api::MemoryAttributes::WRITE_THROUGH,
+        ),
+        (
+            MemoryAttributes::WRITE_BACK,
+            stub_api::MemoryAttributes::WRITE_BACK,
+        ),
+        (
+            MemoryAttributes::RUNTIME,
+            stub_api::MemoryAttributes::RUNTIME,
+        ),
+    ]
+    .into_iter()
+    .filter(|(attribute, _)| attributes.contains(*attribute))
+    .fold(stub_api::MemoryAttributes(0), |result, (_, attribute)| {
+        result | attribute
+    })
+}
+
 /// Wrapper around simple updates of the memory map.
 struct MemoryMapWrapper {
     /// Pointer to the start of the platform memory map as of the last update.
@@ -731,7 +859,7 @@ impl MemoryMapWrapper {
 
                     // Add additional entries to account for memory allocation.
                     let new_count = required_count.strict_add(4);
-                    let layout = Layout::array::<MemoryDescriptor>(required_count)
+                    let layout = Layout::array::<MemoryDescriptor>(new_count)
                         .expect("memory map buffer is too large");
 
                     self.capacity = new_count;
@@ -778,6 +906,7 @@ impl MemoryMapWrapper {
                 item.write(MemoryDescriptor {
                     range: PhysicalAddressRange::empty(),
                     region_type: MemoryType::Reserved,
+                    attributes: MemoryAttributes::NONE,
                 });
             }
 
@@ -799,3 +928,687 @@ unsafe impl Send for MemoryMapWrapper {}
 //
 // [`MemoryMapWrapper`] can be safely sent across threads.
 unsafe impl Sync for MemoryMapWrapper {}
+
+#[cfg(test)]
+mod test {
+    use core::mem;
+
+    use conversion::usize_to_u64;
+    use elf::header::Machine;
+    use stub_api::{AllocationFlags, MapFlags, Status, TakeoverFlags};
+
+    use super::{
+        ALLOCATE_FRAMES_FUNC_ID, DEALLOCATE_FRAMES_FUNC_ID, GET_MEMORY_MAP_FUNC_ID, MAP_FUNC_ID,
+        TAKEOVER_FUNC_ID, UNMAP_FUNC_ID, WRITE_FUNC_ID, handle_call,
+    };
+    use crate::{
+        arch::{
+            generic::memory::paging::{
+                ExternalFrame, ExternalFrameRange, ExternalPage, ExternalPageRange,
+                ExternalPhysicalAddress, ExternalVirtualAddress, TranslationScheme,
+            },
+            memory::ArchTranslationScheme,
+        },
+        platform::{
+            AllocationPolicy, MappingType, Permissions, PhysicalAddress, allocate_frames,
+            mock::{self, PHYSICAL_MEMORY_SIZE, RESERVED_SIZE},
+            read_u32_at, read_u64_at, write_bytes_at, write_u32_at, write_u64_at,
+        },
+    };
+
+    /// The size of a page in the executable's address space.
+    const PAGE: u64 = 4096;
+    /// The size, in bytes, of a [`stub_api::MemoryDescriptor`].
+    const DESCRIPTOR_SIZE: u64 = usize_to_u64(mem::size_of::<stub_api::MemoryDescriptor>());
+
+    /// The start of the executable's scratch region.
+    const DATA: u64 = 0x40_0000;
+    /// The number of pages that make up the executable's scratch region.
+    const DATA_PAGES: u64 = 4;
+    /// The `size` output of `get_memory_map`.
+    const SIZE: u64 = DATA;
+    /// The `key` output of `get_memory_map`.
+    const KEY: u64 = DATA + 8;
+    /// The `descriptor_size` output of `get_memory_map`.
+    const DESCRIPTOR_SIZE_OUT: u64 = DATA + 16;
+    /// The `descriptor_version` output of `get_memory_map`.
+    const DESCRIPTOR_VERSION_OUT: u64 = DATA + 24;
+    /// The in-out address slot of `allocate_frames` and `map`.
+    const VALUE: u64 = DATA + 32;
+    /// The start of the executable's general purpose buffer.
+    const BUFFER: u64 = DATA + PAGE;
+    /// The size, in bytes, of the executable's general purpose buffer.
+    const BUFFER_SIZE: u64 = (DATA_PAGES - 1) * PAGE;
+
+    /// A virtual address that is never mapped by the tests.
+    const UNMAPPED: u64 = 0x1000_0000;
+    /// A virtual address at which the tests create mappings.
+    const TARGET: u64 = 0x80_0000;
+
+    /// Mock executable that interacts with `revm-stub` through [`handle_call()`].
+    struct Executable {
+        /// The address space of the executable.
+        scheme: ArchTranslationScheme,
+    }
+
+    impl Executable {
+        /// Creates a new [`Executable`] that uses the native address space of the host.
+        fn native() -> Self {
+            #[cfg(target_arch = "aarch64")]
+            let machine = Machine::AARCH64;
+            #[cfg(target_arch = "x86")]
+            let machine = Machine::INTEL_386;
+            #[cfg(target_arch = "x86_64")]
+            let machine = Machine::X86_64;
+
+            Self::new(machine)
+        }
+
+        /// Creates a new [`Executable`] with an address space compatible with `machine` and a
+        /// mapped scratch region.
+        fn new(machine: Machine) -> Self {
+            let scheme = ArchTranslationScheme::max_supported(machine)
+                .expect("failed to create executable address space");
+
+            let mut executable = Self { scheme };
+            executable.map_new(DATA, DATA_PAGES);
+            executable
+        }
+
+        /// Maps `count` newly allocated frames at `address` and returns the physical address of
+        /// the first frame.
+        fn map_new(&mut self, address: u64, count: u64) -> u64 {
+            let frames = allocate_frames(count, AllocationPolicy::Any).expect("out of memory");
+            let physical_address = frames.range().start_address().value();
+            mem::forget(frames);
+
+            let start = ExternalPage::containing_address(
+                ExternalVirtualAddress::new(address),
+                self.scheme.chunk_size(),
+            );
+            let pages = ExternalPageRange::new(start, start.strict_add(count - 1));
+            let frames = ExternalFrameRange::new(
+                ExternalFrame::containing_address(
+                    ExternalPhysicalAddress::new(physical_address),
+                    self.scheme.chunk_size(),
+                ),
+                count,
+            );
+            self.scheme
+                .map_at(pages, frames, Permissions::ReadWrite, MappingType::Normal)
+                .expect("failed to map executable memory");
+
+            physical_address
+        }
+
+        /// Returns the physical address to which `address` is mapped.
+        fn translate(&self, address: u64) -> Option<u64> {
+            self.scheme
+                .translate(ExternalVirtualAddress::new(address))
+                .map(|(_, _, physical_address)| physical_address.value())
+        }
+
+        /// Returns the physical address to which `address` is mapped, panicking if it is unmapped.
+        fn physical(&self, address: u64) -> PhysicalAddress {
+            PhysicalAddress::new(self.translate(address).expect("address is unmapped"))
+        }
+
+        /// Reads the `u32` located at `address`.
+        fn read_u32(&self, address: u64) -> u32 {
+            read_u32_at(self.physical(address)).unwrap()
+        }
+
+        /// Reads the `u64` located at `address`.
+        fn read_u64(&self, address: u64) -> u64 {
+            read_u64_at(self.physical(address)).unwrap()
+        }
+
+        /// Writes `value` to `address`.
+        fn write_u32(&self, address: u64, value: u32) {
+            assert!(write_u32_at(self.physical(address), value));
+        }
+
+        /// Writes `value` to `address`.
+        fn write_u64(&self, address: u64, value: u64) {
+            assert!(write_u64_at(self.physical(address), value));
+        }
+
+        /// Writes `bytes` to `address`, which may cross page boundaries.
+        fn write_bytes(&self, address: u64, bytes: &[u8]) {
+            for (offset, byte) in (0..).zip(bytes) {
+                assert!(write_bytes_at(self.physical(address + offset), &[*byte]));
+            }
+        }
+
+        /// Reads the [`stub_api::MemoryDescriptor`] at `index` in the buffer at `address`.
+        fn read_descriptor(&self, address: u64, index: u64) -> stub_api::MemoryDescriptor {
+            let address = address + index * DESCRIPTOR_SIZE;
+            stub_api::MemoryDescriptor {
+                v0: stub_api::MemoryDescriptorV0 {
+                    start: self.read_u64(address),
+                    count: self.read_u64(address + 8),
+                    region_type: stub_api::MemoryType(self.read_u32(address + 16)),
+                },
+                attributes: stub_api::MemoryAttributes(self.read_u64(address + 24)),
+            }
+        }
+
+        /// Invokes the generic function associated with `func_id`.
+        fn call(&mut self, func_id: u16, args: [u64; 5]) -> Status {
+            let [arg_0, arg_1, arg_2, arg_3, arg_4] = args;
+            handle_call(&mut self.scheme, func_id, arg_0, arg_1, arg_2, arg_3, arg_4)
+        }
+
+        /// Invokes `write`.
+        fn write(&mut self, string: u64, length: u64) -> Status {
+            self.call(WRITE_FUNC_ID, [string, length, 0, 0, 0])
+        }
+
+        /// Invokes `allocate_frames` with [`VALUE`] as `physical_address`.
+        fn allocate_frames(
+            &mut self,
+            count: u64,
+            alignment: u64,
+            flags: AllocationFlags,
+        ) -> Status {
+            self.call(
+                ALLOCATE_FRAMES_FUNC_ID,
+                [count, alignment, flags.0, VALUE, 0],
+            )
+        }
+
+        /// Invokes `deallocate_frames`.
+        fn deallocate_frames(&mut self, physical_address: u64, count: u64) -> Status {
+            self.call(
+                DEALLOCATE_FRAMES_FUNC_ID,
+                [physical_address, count, 0, 0, 0],
+            )
+        }
+
+        /// Invokes `get_memory_map` with the standard output locations.
+        fn get_memory_map(&mut self, size: u64, map: u64) -> Status {
+            self.call(
+                GET_MEMORY_MAP_FUNC_ID,
+                [size, map, KEY, DESCRIPTOR_SIZE_OUT, DESCRIPTOR_VERSION_OUT],
+            )
+        }
+
+        /// Invokes `map` with [`VALUE`] as `virtual_address`.
+        fn map(&mut self, physical_address: u64, count: u64, flags: MapFlags) -> Status {
+            self.call(MAP_FUNC_ID, [physical_address, VALUE, count, flags.0, 0])
+        }
+
+        /// Invokes `unmap`.
+        fn unmap(&mut self, virtual_address: u64, count: u64) -> Status {
+            self.call(UNMAP_FUNC_ID, [virtual_address, count, 0, 0, 0])
+        }
+
+        /// Invokes `takeover`.
+        fn takeover(&mut self, key: u64, flags: TakeoverFlags) -> Status {
+            self.call(TAKEOVER_FUNC_ID, [key, flags.0, 0, 0, 0])
+        }
+
+        /// Returns the key of the current memory map.
+        fn memory_map_key(&mut self) -> u64 {
+            self.write_u64(SIZE, BUFFER_SIZE);
+            assert_eq!(self.get_memory_map(SIZE, BUFFER), Status::SUCCESS);
+            self.read_u64(KEY)
+        }
+    }
+
+    #[test]
+    fn write_validates_string() {
+        let _guard = mock::acquire();
+        let mut executable = Executable::native();
+
+        let message = "hello from the executable";
+        executable.write_bytes(BUFFER, message.as_bytes());
+        assert_eq!(
+            executable.write(BUFFER, usize_to_u64(message.len())),
+            Status::SUCCESS
+        );
+        assert_eq!(executable.write(BUFFER, 0), Status::SUCCESS);
+
+        assert_eq!(executable.write(0, 5), Status::INVALID_USAGE);
+        assert_eq!(executable.write(UNMAPPED, 5), Status::INVALID_USAGE);
+        assert_eq!(
+            executable.write(DATA + DATA_PAGES * PAGE - 2, 5),
+            Status::INVALID_USAGE
+        );
+
+        executable.write_bytes(BUFFER, &[b'a', 0xFF, b'b']);
+        assert_eq!(executable.write(BUFFER, 3), Status::INVALID_USAGE);
+
+        // A multi-byte sequence that is cut off by the end of the string.
+        executable.write_bytes(BUFFER, "aé".as_bytes());
+        assert_eq!(executable.write(BUFFER, 2), Status::INVALID_USAGE);
+    }
+
+    #[test]
+    fn write_handles_page_crossing_sequences() {
+        let _guard = mock::acquire();
+        let mut executable = Executable::native();
+
+        let message = "crossing é boundary";
+        let split = usize_to_u64(message.find('é').unwrap());
+        let start = BUFFER + PAGE - split - 1;
+        executable.write_bytes(start, message.as_bytes());
+        assert_eq!(
+            executable.write(start, usize_to_u64(message.len())),
+            Status::SUCCESS
+        );
+    }
+
+    #[test]
+    fn allocate_frames_validates_arguments() {
+        let _guard = mock::acquire();
+        let mut executable = Executable::native();
+
+        assert_eq!(
+            executable.allocate_frames(0, PAGE, AllocationFlags::ANY),
+            Status::INVALID_USAGE
+        );
+        assert_eq!(
+            executable.allocate_frames(1, 3 * PAGE, AllocationFlags::ANY),
+            Status::INVALID_USAGE
+        );
+        assert_eq!(
+            executable.allocate_frames(1, PAGE, AllocationFlags(AllocationFlags::TYPE.0)),
+            Status::INVALID_USAGE
+        );
+        assert_eq!(
+            executable.allocate_frames(1, PAGE, AllocationFlags(1 << 2)),
+            Status::INVALID_USAGE
+        );
+        assert_eq!(
+            executable.call(
+                ALLOCATE_FRAMES_FUNC_ID,
+                [1, PAGE, AllocationFlags::ANY.0, 0, 0]
+            ),
+            Status::INVALID_USAGE
+        );
+        assert_eq!(
+            executable.call(
+                ALLOCATE_FRAMES_FUNC_ID,
+                [1, PAGE, AllocationFlags::ANY.0, UNMAPPED, 0]
+            ),
+            Status::INVALID_USAGE
+        );
+
+        executable.write_u64(VALUE, RESERVED_SIZE + PAGE / 2);
+        assert_eq!(
+            executable.allocate_frames(1, PAGE, AllocationFlags::AT),
+            Status::INVALID_USAGE
+        );
+    }
+
+    #[test]
+    fn allocate_frames_honors_policies() {
+        let _guard = mock::acquire();
+        let mut executable = Executable::native();
+
+        let alignment = 0x10000;
+        assert_eq!(
+            executable.allocate_frames(2, alignment, AllocationFlags::ANY),
+            Status::SUCCESS
+        );
+        let any = executable.read_u64(VALUE);
+        assert!(any.is_multiple_of(alignment));
+        assert!(any >= RESERVED_SIZE && any + 2 * PAGE <= PHYSICAL_MEMORY_SIZE);
+
+        // The frames are in use, so they cannot be allocated again.
+        executable.write_u64(VALUE, any);
+        assert_eq!(
+            executable.allocate_frames(1, PAGE, AllocationFlags::AT),
+            Status::OUT_OF_MEMORY
+        );
+
+        // Once deallocated, the exact frames can be requested.
+        assert_eq!(executable.deallocate_frames(any, 2), Status::SUCCESS);
+        executable.write_u64(VALUE, any);
+        assert_eq!(
+            executable.allocate_frames(2, PAGE, AllocationFlags::AT),
+            Status::SUCCESS
+        );
+        assert_eq!(executable.read_u64(VALUE), any);
+        assert_eq!(executable.deallocate_frames(any, 2), Status::SUCCESS);
+
+        let maximum = PHYSICAL_MEMORY_SIZE / 2 - 1;
+        executable.write_u64(VALUE, maximum);
+        assert_eq!(
+            executable.allocate_frames(4, PAGE, AllocationFlags::INCLUSIVE_MAX),
+            Status::SUCCESS
+        );
+        let bounded = executable.read_u64(VALUE);
+        assert!(bounded + 4 * PAGE - 1 <= maximum);
+        assert_eq!(executable.deallocate_frames(bounded, 4), Status::SUCCESS);
+
+        executable.write_u64(VALUE, RESERVED_SIZE - 1);
+        assert_eq!(
+            executable.allocate_frames(1, PAGE, AllocationFlags::INCLUSIVE_MAX),
+            Status::OUT_OF_MEMORY
+        );
+        assert_eq!(
+            executable.allocate_frames(PHYSICAL_MEMORY_SIZE / PAGE, PAGE, AllocationFlags::ANY),
+            Status::OUT_OF_MEMORY
+        );
+        assert_eq!(
+            executable.allocate_frames(u64::MAX, PAGE, AllocationFlags::ANY),
+            Status::OUT_OF_MEMORY
+        );
+    }
+
+    #[test]
+    fn get_memory_map_reports_required_size() {
+        let _guard = mock::acquire();
+        let mut executable = Executable::native();
+
+        assert_eq!(executable.get_memory_map(0, BUFFER), Status::INVALID_USAGE);
+        assert_eq!(
+            executable.get_memory_map(SIZE + 4, BUFFER),
+            Status::INVALID_USAGE
+        );
+        assert_eq!(
+            executable.get_memory_map(UNMAPPED, BUFFER),
+            Status::INVALID_USAGE
+        );
+
+        executable.write_u64(SIZE, 0);
+        assert_eq!(
+            executable.get_memory_map(SIZE, BUFFER),
+            Status::BUFFER_TOO_SMALL
+        );
+        let required = executable.read_u64(SIZE);
+        assert_ne!(required, 0);
+        assert!(required.is_multiple_of(DESCRIPTOR_SIZE));
+
+        executable.write_u64(SIZE, required - 1);
+        assert_eq!(
+            executable.get_memory_map(SIZE, BUFFER),
+            Status::BUFFER_TOO_SMALL
+        );
+        assert_eq!(executable.read_u64(SIZE), required);
+
+        executable.write_u64(SIZE, required);
+        assert_eq!(executable.get_memory_map(SIZE, 0), Status::INVALID_USAGE);
+        executable.write_u64(SIZE, required);
+        assert_eq!(
+            executable.get_memory_map(SIZE, BUFFER + 4),
+            Status::INVALID_USAGE
+        );
+        executable.write_u64(SIZE, required);
+        assert_eq!(
+            executable.get_memory_map(SIZE, UNMAPPED),
+            Status::INVALID_USAGE
+        );
+    }
+
+    #[test]
+    fn get_memory_map_describes_physical_memory() {
+        let _guard = mock::acquire();
+        let mut executable = Executable::native();
+
+        executable.write_u64(SIZE, BUFFER_SIZE);
+        assert_eq!(executable.get_memory_map(SIZE, BUFFER), Status::SUCCESS);
+        assert_eq!(executable.read_u64(DESCRIPTOR_SIZE_OUT), DESCRIPTOR_SIZE);
+        assert_eq!(
+            executable.read_u64(DESCRIPTOR_VERSION_OUT),
+            stub_api::MemoryDescriptor::VERSION
+        );
+
+        let size = executable.read_u64(SIZE);
+        assert!(size <= BUFFER_SIZE && size.is_multiple_of(DESCRIPTOR_SIZE));
+
+        let first = executable.read_descriptor(BUFFER, 0);
+        assert_eq!(first.v0.start, 0);
+        assert_eq!(first.v0.region_type, stub_api::MemoryType::RESERVED);
+
+        // The descriptors are sorted, contiguous, and cover all of physical memory.
+        let mut end = 0;
+        for index in 0..size / DESCRIPTOR_SIZE {
+            let descriptor = executable.read_descriptor(BUFFER, index);
+            assert_eq!(descriptor.v0.start, end);
+            if descriptor.v0.region_type == stub_api::MemoryType::FREE {
+                assert!(
+                    descriptor
+                        .attributes
+                        .contains(stub_api::MemoryAttributes::WRITE_BACK)
+                );
+            }
+            end = descriptor.v0.start + descriptor.v0.count;
+        }
+        assert_eq!(end, PHYSICAL_MEMORY_SIZE);
+    }
+
+    #[test]
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn get_memory_map_uses_32_bit_sizes() {
+        let _guard = mock::acquire();
+        let mut executable = Executable::new(Machine::INTEL_386);
+
+        executable.write_u64(SIZE, u64::MAX);
+        executable.write_u32(SIZE, 0);
+        assert_eq!(
+            executable.get_memory_map(SIZE, BUFFER),
+            Status::BUFFER_TOO_SMALL
+        );
+        let required = executable.read_u32(SIZE);
+        assert!(u64::from(required).is_multiple_of(DESCRIPTOR_SIZE));
+        assert_eq!(executable.read_u32(SIZE + 4), u32::MAX);
+
+        executable.write_u32(SIZE, required);
+        executable.write_u32(DESCRIPTOR_SIZE_OUT + 4, u32::MAX);
+        assert_eq!(executable.get_memory_map(SIZE, BUFFER), Status::SUCCESS);
+        assert_eq!(u64::from(executable.read_u32(SIZE)), u64::from(required));
+        assert_eq!(
+            u64::from(executable.read_u32(DESCRIPTOR_SIZE_OUT)),
+            DESCRIPTOR_SIZE
+        );
+        assert_eq!(executable.read_u32(DESCRIPTOR_SIZE_OUT + 4), u32::MAX);
+    }
+
+    #[test]
+    fn map_validates_arguments() {
+        let _guard = mock::acquire();
+        let mut executable = Executable::native();
+        let physical_address = executable.translate(BUFFER).unwrap();
+        let flags = MapFlags(MapFlags::READ.0 | MapFlags::WRITE.0);
+
+        executable.write_u64(VALUE, TARGET);
+        assert_eq!(
+            executable.map(physical_address, 0, flags),
+            Status::INVALID_USAGE
+        );
+        assert_eq!(
+            executable.map(physical_address, 1, MapFlags(1 << 5)),
+            Status::INVALID_USAGE
+        );
+        assert_eq!(
+            executable.map(physical_address + 8, 1, flags),
+            Status::INVALID_USAGE
+        );
+        assert_eq!(
+            executable.call(MAP_FUNC_ID, [physical_address, UNMAPPED, 1, flags.0, 0]),
+            Status::INVALID_USAGE
+        );
+
+        executable.write_u64(VALUE, TARGET + 8);
+        assert_eq!(
+            executable.map(physical_address, 1, flags),
+            Status::INVALID_USAGE
+        );
+        assert_eq!(executable.translate(TARGET), None);
+    }
+
+    #[test]
+    fn map_reports_overlap() {
+        let _guard = mock::acquire();
+        let mut executable = Executable::native();
+        let first = executable.translate(BUFFER).unwrap();
+        let second = executable.translate(BUFFER + PAGE).unwrap();
+        let flags = MapFlags(MapFlags::READ.0 | MapFlags::WRITE.0);
+
+        executable.write_u64(VALUE, TARGET + PAGE);
+        assert_eq!(executable.map(first, 1, flags), Status::SUCCESS);
+        assert_eq!(executable.translate(TARGET + PAGE), Some(first));
+
+        // Mapping over an existing page fails without modifying any mapping.
+        executable.write_u64(VALUE, TARGET + PAGE);
+        assert_eq!(executable.map(second, 1, flags), Status::OVERLAP);
+        executable.write_u64(VALUE, TARGET);
+        assert_eq!(executable.map(second, 2, flags), Status::OVERLAP);
+        assert_eq!(executable.translate(TARGET), None);
+        assert_eq!(executable.translate(TARGET + PAGE), Some(first));
+
+        executable.write_u64(VALUE, TARGET);
+        assert_eq!(
+            executable.map(second, 2, flags | MapFlags::MAY_OVERWRITE),
+            Status::SUCCESS
+        );
+        assert_eq!(executable.translate(TARGET), Some(second));
+        assert_eq!(executable.translate(TARGET + PAGE), Some(second + PAGE));
+    }
+
+    #[test]
+    fn map_chooses_location() {
+        let _guard = mock::acquire();
+        let mut executable = Executable::native();
+        let physical_address = executable.translate(BUFFER).unwrap();
+        let flags = MapFlags(MapFlags::READ.0 | MapFlags::CHOOSE_LOCATION.0);
+
+        // The initial value of `virtual_address` is ignored.
+        executable.write_u64(VALUE, TARGET + 1);
+        assert_eq!(executable.map(physical_address, 2, flags), Status::SUCCESS);
+        let chosen = executable.read_u64(VALUE);
+        assert!(chosen.is_multiple_of(PAGE));
+        assert_eq!(executable.translate(chosen), Some(physical_address));
+        assert_eq!(
+            executable.translate(chosen + PAGE),
+            Some(physical_address + PAGE)
+        );
+
+        executable.write_u64(VALUE, TARGET);
+        assert_eq!(
+            executable.map(physical_address, 1, flags | MapFlags::MAY_OVERWRITE),
+            Status::NOT_SUPPORTED
+        );
+        assert_eq!(executable.translate(TARGET), None);
+    }
+
+    #[test]
+    fn map_applies_mapping_type() {
+        let _guard = mock::acquire();
+        let mut executable = Executable::native();
+        let physical_address = executable.translate(BUFFER).unwrap();
+        let flags = MapFlags(MapFlags::READ.0 | MapFlags::WRITE.0);
+        let mapping_type = |executable: &Executable, address: u64| {
+            executable
+                .scheme
+                .translate(ExternalVirtualAddress::new(address))
+                .map(|(_, mapping_type, _)| mapping_type)
+        };
+
+        let cases = [
+            (stub_api::MappingType::NORMAL, MappingType::Normal),
+            (
+                stub_api::MappingType::NORMAL_NONCACHEABLE,
+                MappingType::NormalNoncacheable,
+            ),
+            (stub_api::MappingType::DEVICE, MappingType::Device),
+        ];
+        for (page, (requested, expected)) in (0..).zip(cases) {
+            let address = TARGET + page * PAGE;
+            executable.write_u64(VALUE, address);
+            assert_eq!(
+                executable.map(physical_address, 1, flags.with_mapping_type(requested)),
+                Status::SUCCESS
+            );
+            assert_eq!(mapping_type(&executable, address), Some(expected));
+        }
+
+        // Write-combining is only accepted by schemes that can express it.
+        let address = TARGET + 3 * PAGE;
+        executable.write_u64(VALUE, address);
+        let status = executable.map(
+            physical_address,
+            1,
+            flags.with_mapping_type(stub_api::MappingType::WRITE_COMBINING),
+        );
+        if executable
+            .scheme
+            .supports_mapping_type(MappingType::WriteCombining)
+        {
+            assert_eq!(status, Status::SUCCESS);
+            assert_eq!(
+                mapping_type(&executable, address),
+                Some(MappingType::WriteCombining)
+            );
+        } else {
+            assert_eq!(status, Status::NOT_SUPPORTED);
+            assert_eq!(executable.translate(address), None);
+        }
+
+        let address = TARGET + 4 * PAGE;
+        executable.write_u64(VALUE, address);
+        assert_eq!(
+            executable.map(
+                physical_address,
+                1,
+                flags.with_mapping_type(stub_api::MappingType(0xFF))
+            ),
+            Status::NOT_SUPPORTED
+        );
+        assert_eq!(executable.translate(address), None);
+    }
+
+    #[test]
+    fn unmap_removes_mappings() {
+        let _guard = mock::acquire();
+        let mut executable = Executable::native();
+        let physical_address = executable.translate(BUFFER).unwrap();
+        let flags = MapFlags(MapFlags::READ.0);
+
+        executable.write_u64(VALUE, TARGET);
+        assert_eq!(executable.map(physical_address, 2, flags), Status::SUCCESS);
+
+        assert_eq!(executable.unmap(TARGET, 0), Status::INVALID_USAGE);
+        assert_eq!(executable.unmap(TARGET, u64::MAX), Status::INVALID_USAGE);
+        assert_eq!(executable.translate(TARGET), Some(physical_address));
+
+        assert_eq!(executable.unmap(TARGET, 1), Status::SUCCESS);
+        assert_eq!(executable.translate(TARGET), None);
+        assert_eq!(
+            executable.translate(TARGET + PAGE),
+            Some(physical_address + PAGE)
+        );
+
+        executable.write_u64(VALUE, TARGET);
+        assert_eq!(executable.map(physical_address, 1, flags), Status::SUCCESS);
+    }
+
+    #[test]
+    fn takeover_validates_key() {
+        let _guard = mock::acquire();
+        let mut executable = Executable::native();
+
+        let key = executable.memory_map_key();
+        assert_eq!(
+            executable.takeover(key, TakeoverFlags(1 << 7)),
+            Status::INVALID_USAGE
+        );
+        assert_eq!(
+            executable.takeover(key.wrapping_add(1), TakeoverFlags(0)),
+            Status::INVALID_KEY
+        );
+
+        // Any allocation invalidates the previously returned key.
+        assert_eq!(
+            executable.allocate_frames(1, PAGE, AllocationFlags::ANY),
+            Status::SUCCESS
+        );
+        assert_eq!(
+            executable.takeover(key, TakeoverFlags(0)),
+            Status::INVALID_KEY
+        );
+    }
+}
@@ -10,15 +10,18 @@ use core::{
 
 use conversion::{u64_to_usize_strict, usize_to_u64};
 use stub_api::{
-    Flags, GenericTable, Header,
-    raw::{GenericTable32, GenericTable64},
+    CpuDescriptor, CpuFlags, Flags, Framebuffer, GenericTable, Header, Module,
+    raw::{GenericTable32, GenericTable64, Module32, Module64},
 };
 use sync::{Spinlock, SpinlockGuard};
 
 use crate::{
     arch::{
-        generic::memory::paging::{
-            ExternalFrame, ExternalFrameRange, ExternalPhysicalAddress, TranslationScheme,
+        generic::{
+            memory::paging::{
+                ExternalFrame, ExternalFrameRange, ExternalPhysicalAddress, TranslationScheme,
+            },
+            switch::function::supported_mapping_types,
         },
         memory::ArchTranslationScheme,
         switch::{
@@ -28,9 +31,10 @@ use crate::{
         },
     },
     platform::{
-        AllocationPolicy, FrameAllocation, MapError, OutOfMemory, PageMapping, Permissions,
-        PhysicalAddress, allocate, allocate_frames_aligned, frame_size, main_processor_id,
-        map_identity, processor_count, write_bytes_at,
+        AllocationPolicy, FrameAllocation, MapError, MemoryType, OutOfMemory, PageMapping,
+        Permissions, PhysicalAddress, allocate, allocate_frames_aligned, frame_size, framebuffer,
+        log_ring_frames, main_processor_id, map_identity, modules, processor_count,
+        processor_descriptor, write_bytes_at,
     },
     util::DropWrapper,
 };
@@ -151,6 +155,7 @@ fn allocate_stack(
         STACK_SIZE.div_ceil(frame_size()),
         scheme.chunk_size(),
         policy,
+        MemoryType::BootloaderReclaimable,
     )?;
 
     let frame_range = ExternalFrameRange::new(
@@ -185,6 +190,7 @@ fn allocate_storage(
         storage_size_u64.div_ceil(frame_size()),
         scheme.chunk_size(),
         policy,
+        MemoryType::BootloaderReclaimable,
     )?;
 
     let frame_range = ExternalFrameRange::new(
@@ -215,14 +221,41 @@ pub fn allocate_protocol_table(
     command_line: &str,
 ) -> Result<FrameAllocation, ComponentError> {
     let arch_table_size = arch_table_size(scheme);
+    let table_64_bit = arch_table_64_bit(scheme);
     let arch_table_size_u64 = usize_to_u64(arch_table_size);
-    let total_size = arch_table_size.strict_add(command_line.len());
+    // The command line is followed by a NUL terminator.
+    let command_line_size = command_line.len().strict_add(1);
+    let framebuffer_offset = arch_table_size
+        .strict_add(command_line_size)
+        .next_multiple_of(mem::align_of::<Framebuffer>());
+    let framebuffer_end = if framebuffer().is_some() {
+        framebuffer_offset.strict_add(mem::size_of::<Framebuffer>())
+    } else {
+        arch_table_size.strict_add(command_line_size)
+    };
+    let cpus_offset = framebuffer_end.next_multiple_of(mem::align_of::<CpuDescriptor>());
+    let cpus_end = cpus_offset.strict_add(
+        mem::size_of::<CpuDescriptor>().strict_mul(u64_to_usize_strict(processor_count())),
+    );
+    let modules = modules();
+    let (module_size, module_align) = if table_64_bit {
+        (mem::size_of::<Module64>(), mem::align_of::<Module64>())
+    } else {
+        (mem::size_of::<Module32>(), mem::align_of::<Module32>())
+    };
+    let modules_offset = cpus_end.next_multiple_of(module_align);
+    let module_strings_offset = modules_offset.strict_add(module_size.strict_mul(modules.len()));
+    let total_size = modules.iter().fold(module_strings_offset, |size, module| {
+        size.strict_add(module.name.len())
+            .strict_add(module.command_line.len())
+    });
     let total_size_u64 = usize_to_u64(total_size);
 
     let frame_allocation = allocate_frames_aligned(
         total_size_u64.div_ceil(frame_size()),
         scheme.chunk_size(),
         AllocationPolicy::InclusiveMax(scheme.output_descriptor().valid_ranges()[0].1),
+        MemoryType::ProtocolTable,
     )?;
 
     let frame_range = ExternalFrameRange::new(
@@ -237,7 +270,17 @@ pub fn allocate_protocol_table(
 
     let address = frame_allocation.range().start().start_address();
     let command_line_address = address.strict_add(arch_table_size_u64);
-    if arch_table_64_bit(scheme) {
+    let framebuffer_address = framebuffer().map_or(PhysicalAddress::zero(), |_| {
+        address.strict_add(usize_to_u64(framebuffer_offset))
+    });
+    let cpus_address = address.strict_add(usize_to_u64(cpus_offset));
+    let modules_address = if modules.is_empty() {
+        PhysicalAddress::zero()
+    } else {
+        address.strict_add(usize_to_u64(modules_offset))
+    };
+    let log_ring_address = map_log_ring(scheme);
+    if table_64_bit {
         // 64-bit address space.
 
         let protocol_table = RevmProtocolTable64 {
@@ -272,6 +315,18 @@ pub fn allocate_protocol_table(
                 unmap: layout.unmap,
                 takeover: layout.takeover,
                 run_on_all_processors: layout.run_on_all_processors,
+                framebuffer_descriptor_size: usize_to_u64(mem::size_of::<Framebuffer>()),
+                framebuffer_descriptor_version: Framebuffer::VERSION,
+                framebuffer: framebuffer_address.value(),
+                cpu_descriptor_size: usize_to_u64(mem::size_of::<CpuDescriptor>()),
+                cpu_descriptor_version: CpuDescriptor::VERSION,
+                cpus: cpus_address.value(),
+                module_count: usize_to_u64(modules.len()),
+                module_descriptor_size: usize_to_u64(module_size),
+                module_descriptor_version: Module::VERSION,
+                modules: modules_address.value(),
+                supported_mapping_types: supported_mapping_types(scheme),
+                log_ring: log_ring_address.value(),
             },
             arch_table: (),
         };
@@ -318,6 +373,22 @@ pub fn allocate_protocol_table(
                     .expect("failed to convert function to u32"),
                 run_on_all_processors: u32::try_from(layout.run_on_all_processors)
                     .expect("failed to convert function to u32"),
+                framebuffer_descriptor_size: usize_to_u64(mem::size_of::<Framebuffer>()),
+                framebuffer_descriptor_version: Framebuffer::VERSION,
+                framebuffer: u32::try_from(framebuffer_address.value())
+                    .expect("failed to convert framebuffer address to u32"),
+                cpu_descriptor_size: usize_to_u64(mem::size_of::<CpuDescriptor>()),
+                cpu_descriptor_version: CpuDescriptor::VERSION,
+                cpus: u32::try_from(cpus_address.value())
+                    .expect("failed to convert CPU descriptor array address to u32"),
+                module_count: usize_to_u64(modules.len()),
+                module_descriptor_size: usize_to_u64(module_size),
+                module_descriptor_version: Module::VERSION,
+                modules: u32::try_from(modules_address.value())
+                    .expect("failed to convert module array address to u32"),
+                supported_mapping_types: supported_mapping_types(scheme),
+                log_ring: u32::try_from(log_ring_address.value())
+                    .expect("failed to convert log ring address to u32"),
             },
             arch_table: (),
         };
@@ -326,10 +397,146 @@ pub fn allocate_protocol_table(
     }
 
     write_bytes_at(command_line_address, command_line.as_bytes());
+    write_bytes_at(
+        command_line_address.strict_add(usize_to_u64(command_line.len())),
+        &[0],
+    );
+
+    if let Some(framebuffer) = framebuffer() {
+        let descriptor = Framebuffer {
+            physical_address: framebuffer.address.value(),
+            width: framebuffer.width,
+            height: framebuffer.height,
+            pitch: framebuffer.pitch,
+            bits_per_pixel: framebuffer.bpp,
+            red_mask_size: framebuffer.red_mask_size,
+            red_mask_shift: framebuffer.red_mask_shift,
+            green_mask_size: framebuffer.green_mask_size,
+            green_mask_shift: framebuffer.green_mask_shift,
+            blue_mask_size: framebuffer.blue_mask_size,
+            blue_mask_shift: framebuffer.blue_mask_shift,
+        };
+
+        // SAFETY:
+        //
+        // [`Framebuffer`] is a `repr(C)` structure without any padding bytes.
+        let bytes = unsafe {
+            slice::from_raw_parts(
+                ptr::from_ref(&descriptor).cast::<u8>(),
+                mem::size_of::<Framebuffer>(),
+            )
+        };
+        write_bytes_at(framebuffer_address, bytes);
+    }
+
+    for processor_id in 0..processor_count() {
+        let processor = processor_descriptor(processor_id);
+
+        let mut flags = CpuFlags(0);
+        if processor.enabled {
+            flags |= CpuFlags::ENABLED;
+        }
+        if processor.proximity_domain.is_some() {
+            flags |= CpuFlags::PROXIMITY_DOMAIN_VALID;
+        }
+
+        let descriptor = CpuDescriptor {
+            hardware_id: processor.hardware_id,
+            flags,
+            proximity_domain: processor.proximity_domain.unwrap_or(0),
+        };
+
+        // SAFETY:
+        //
+        // [`CpuDescriptor`] is a `repr(C)` structure without any padding bytes.
+        let bytes = unsafe {
+            slice::from_raw_parts(
+                ptr::from_ref(&descriptor).cast::<u8>(),
+                mem::size_of::<CpuDescriptor>(),
+            )
+        };
+        write_bytes_at(
+            cpus_address
+                .strict_add(processor_id.strict_mul(usize_to_u64(mem::size_of::<CpuDescriptor>()))),
+            bytes,
+        );
+    }
+
+    let mut string_address = address.strict_add(usize_to_u64(module_strings_offset));
+    for (index, module) in modules.iter().enumerate() {
+        let name_address = string_address;
+        write_bytes_at(name_address, module.name.as_bytes());
+        let command_line_address = name_address.strict_add(usize_to_u64(module.name.len()));
+        write_bytes_at(command_line_address, module.command_line.as_bytes());
+        string_address = command_line_address.strict_add(usize_to_u64(module.command_line.len()));
+
+        let descriptor_address =
+            modules_address.strict_add(usize_to_u64(index.strict_mul(module_size)));
+        if table_64_bit {
+            let descriptor = Module64 {
+                physical_address: module.range.start().value(),
+                size: module.range.count(),
+                name: name_address.value(),
+                name_length: usize_to_u64(module.name.len()),
+                command_line: command_line_address.value(),
+                command_line_length: usize_to_u64(module.command_line.len()),
+            };
+
+            // SAFETY:
+            //
+            // [`Module64`] is a `repr(C)` structure without any padding bytes.
+            let bytes = unsafe {
+                slice::from_raw_parts(ptr::from_ref(&descriptor).cast::<u8>(), module_size)
+            };
+            write_bytes_at(descriptor_address, bytes);
+        } else {
+            let descriptor = Module32 {
+                physical_address: module.range.start().value(),
+                size: module.range.count(),
+                name: u32::try_from(name_address.value())
+                    .expect("failed to convert module name address to u32"),
+                name_length: usize_to_u64(module.name.len()),
+                command_line: u32::try_from(command_line_address.value())
+                    .expect("failed to convert module command line address to u32"),
+                command_line_length: usize_to_u64(module.command_line.len()),
+            };
+
+            // SAFETY:
+            //
+            // [`Module32`] is a `repr(C, packed(4))` structure without any padding bytes.
+            let bytes = unsafe {
+                slice::from_raw_parts(ptr::from_ref(&descriptor).cast::<u8>(), module_size)
+            };
+            write_bytes_at(descriptor_address, bytes);
+        }
+    }
 
     Ok(frame_allocation)
 }
 
+/// Identity maps the log ring into the executable's address space and returns its physical
+/// address, or [`PhysicalAddress::zero()`] if there is no log ring or it could not be mapped.
+fn map_log_ring(scheme: &mut ArchTranslationScheme) -> PhysicalAddress {
+    let Some(frames) = log_ring_frames() else {
+        return PhysicalAddress::zero();
+    };
+
+    let frame_range = ExternalFrameRange::new(
+        ExternalFrame::containing_address(
+            ExternalPhysicalAddress::new(frames.start_address().value()),
+            scheme.chunk_size(),
+        ),
+        frames.byte_count().div_ceil(scheme.chunk_size()),
+    );
+    match scheme.map_identity(frame_range, Permissions::ReadWrite) {
+        Ok(_) => frames.start_address(),
+        Err(error) => {
+            crate::warn!("failed to map log ring into the executable: {error}");
+            PhysicalAddress::zero()
+        }
+    }
+}
+
 /// Important data needed to resolve cross address space calls.
 pub struct SwitchData {
     /// The [`ArchTranslationScheme`] used for mapping and translation.
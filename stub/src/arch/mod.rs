@@ -48,6 +48,14 @@ pub mod switch {
 
 /// Architecture-specific functionality.
 pub mod arch_specific {
+    #[cfg(target_arch = "aarch64")]
+    pub use super::aarch64::{
+        MPIDR_AFFINITY_MASK, current_hardware_id, exception::install_exception_handlers,
+        read_counter,
+    };
+
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-    pub use super::x86::load_gdt;
+    pub use super::x86::{
+        current_hardware_id, exception::install_exception_handlers, load_gdt, read_counter,
+    };
 }
@@ -0,0 +1,481 @@
+//! Exception and interrupt handling for `i686` and `x86_64`.
+//!
+//! Every vector is routed to a small entry stub that normalizes the stack (by pushing a dummy error
+//! code if the processor did not push one) and then enters [`exception_handler()`], which reports
+//! the state of the processor and panics.
+
+use core::{
+    arch::global_asm,
+    mem, ptr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use conversion::u64_to_usize_strict;
+use sync::Spinlock;
+use x86::{
+    control::{Cr0, Cr2, Cr3, Cr4},
+    interrupt::{VECTOR_COUNT, load_idt, store_idt},
+    segmentation::{DescriptorTablePointer, SegmentSelector, read_cs},
+};
+
+use crate::arch::generic::exception::{CodeAddress, begin_exception, log_backtrace};
+
+/// The number of vectors reserved for architecturally defined exceptions.
+const EXCEPTION_COUNT: usize = 32;
+/// The size, in bytes, of each entry stub in [`X86_EXCEPTION_STUBS`].
+const STUB_SIZE: usize = 16;
+
+/// The mnemonics of the architecturally defined exceptions.
+const EXCEPTION_NAMES: [&str; EXCEPTION_COUNT] = [
+    "#DE (divide error)",
+    "#DB (debug)",
+    "NMI (non-maskable interrupt)",
+    "#BP (breakpoint)",
+    "#OF (overflow)",
+    "#BR (bound range exceeded)",
+    "#UD (invalid opcode)",
+    "#NM (device not available)",
+    "#DF (double fault)",
+    "coprocessor segment overrun",
+    "#TS (invalid TSS)",
+    "#NP (segment not present)",
+    "#SS (stack-segment fault)",
+    "#GP (general protection)",
+    "#PF (page fault)",
+    "reserved",
+    "#MF (x87 floating-point error)",
+    "#AC (alignment check)",
+    "#MC (machine check)",
+    "#XM (SIMD floating-point exception)",
+    "#VE (virtualization exception)",
+    "#CP (control protection exception)",
+    "reserved",
+    "reserved",
+    "reserved",
+    "reserved",
+    "reserved",
+    "reserved",
+    "#HV (hypervisor injection exception)",
+    "#VC (VMM communication exception)",
+    "#SX (security exception)",
+    "reserved",
+];
+
+/// The IDT used by the stub.
+static IDT: Spinlock<Idt> = Spinlock::new(Idt {
+    gates: [Gate::non_present(); VECTOR_COUNT],
+});
+/// Whether [`IDT`] has been filled by [`install_exception_handlers()`].
+static IDT_READY: AtomicBool = AtomicBool::new(false);
+
+/// A gate descriptor in the IDT.
+#[cfg(target_arch = "x86")]
+type Gate = x86::interrupt::GateDescriptor32;
+/// A gate descriptor in the IDT.
+#[cfg(target_arch = "x86_64")]
+type Gate = x86::interrupt::GateDescriptor64;
+
+/// The layout of the IDT.
+#[repr(C, align(16))]
+struct Idt {
+    /// The gate descriptors of every vector.
+    gates: [Gate; VECTOR_COUNT],
+}
+
+/// Fills the stub's IDT and loads it on the current processor.
+///
+/// If `firmware_interrupts` is `true`, the gates of the current IDT for vectors outside of the
+/// architecturally defined exceptions are preserved, so that interrupts managed by the firmware
+/// continue to be serviced. Otherwise, every vector is routed to the stub's handler.
+///
+/// # Safety
+///
+/// - The current code segment must remain valid for as long as the IDT is in use.
+/// - If `firmware_interrupts` is `true`, the current IDT must be valid and accessible.
+pub unsafe fn install_exception_handlers(firmware_interrupts: bool) {
+    let code_segment = read_cs();
+    let previous = if firmware_interrupts {
+        store_idt()
+    } else {
+        DescriptorTablePointer::default()
+    };
+
+    let stubs = (&raw const X86_EXCEPTION_STUBS).addr();
+
+    let mut idt = IDT.lock();
+    for (vector, gate) in idt.gates.iter_mut().enumerate() {
+        *gate = if vector >= EXCEPTION_COUNT && firmware_interrupts {
+            let limit = usize::from(previous.limit);
+            if (vector + 1) * mem::size_of::<Gate>() - 1 <= limit {
+                let table =
+                    ptr::with_exposed_provenance::<Gate>(u64_to_usize_strict(previous.base));
+
+                // SAFETY:
+                //
+                // The invariants of [`install_exception_handlers()`] ensure that the current IDT
+                // is valid and accessible, while `vector` lies within its limit.
+                unsafe { table.wrapping_add(vector).read_unaligned() }
+            } else {
+                Gate::non_present()
+            }
+        } else {
+            interrupt_gate(stubs + vector * STUB_SIZE, code_segment)
+        };
+    }
+
+    IDT_READY.store(true, Ordering::Release);
+    load(&idt);
+}
+
+/// Loads the stub's IDT on the current processor if [`install_exception_handlers()`] has been
+/// called.
+pub fn load_exception_handlers() {
+    if !IDT_READY.load(Ordering::Acquire) {
+        return;
+    }
+
+    load(&IDT.lock());
+}
+
+/// Loads the provided [`Idt`] on the current processor.
+fn load(idt: &Idt) {
+    // SAFETY:
+    //
+    // The IDT is stored in a static and every present gate refers to a valid handler.
+    unsafe { load_idt(&DescriptorTablePointer::new(&idt.gates)) }
+}
+
+/// Constructs an interrupt gate that transfers control to `handler` using `code_segment`.
+#[cfg(target_arch = "x86")]
+fn interrupt_gate(handler: usize, code_segment: SegmentSelector) -> Gate {
+    Gate::interrupt(conversion::usize_to_u32_strict(handler), code_segment)
+}
+
+/// Constructs an interrupt gate that transfers control to `handler` using `code_segment`.
+#[cfg(target_arch = "x86_64")]
+fn interrupt_gate(handler: usize, code_segment: SegmentSelector) -> Gate {
+    Gate::interrupt(conversion::usize_to_u64(handler), code_segment)
+}
+
+/// The processor state saved by the entry stubs.
+#[cfg(target_arch = "x86")]
+#[repr(C)]
+struct ExceptionFrame {
+    /// The value of the `eax` register.
+    eax: usize,
+    /// The value of the `ebx` register.
+    ebx: usize,
+    /// The value of the `ecx` register.
+    ecx: usize,
+    /// The value of the `edx` register.
+    edx: usize,
+    /// The value of the `esi` register.
+    esi: usize,
+    /// The value of the `edi` register.
+    edi: usize,
+    /// The value of the `ebp` register.
+    ebp: usize,
+
+    /// The vector that was raised.
+    vector: usize,
+    /// The error code pushed by the processor, or zero.
+    error_code: usize,
+
+    /// The value of the `eip` register.
+    eip: usize,
+    /// The value of the `CS` register.
+    cs: usize,
+    /// The value of the `eflags` register.
+    eflags: usize,
+}
+
+/// The processor state saved by the entry stubs.
+#[cfg(target_arch = "x86_64")]
+#[repr(C)]
+struct ExceptionFrame {
+    /// The value of the `rax` register.
+    rax: usize,
+    /// The value of the `rbx` register.
+    rbx: usize,
+    /// The value of the `rcx` register.
+    rcx: usize,
+    /// The value of the `rdx` register.
+    rdx: usize,
+    /// The value of the `rsi` register.
+    rsi: usize,
+    /// The value of the `rdi` register.
+    rdi: usize,
+    /// The value of the `rbp` register.
+    rbp: usize,
+    /// The value of the `r8` register.
+    r8: usize,
+    /// The value of the `r9` register.
+    r9: usize,
+    /// The value of the `r10` register.
+    r10: usize,
+    /// The value of the `r11` register.
+    r11: usize,
+    /// The value of the `r12` register.
+    r12: usize,
+    /// The value of the `r13` register.
+    r13: usize,
+    /// The value of the `r14` register.
+    r14: usize,
+    /// The value of the `r15` register.
+    r15: usize,
+
+    /// The vector that was raised.
+    vector: usize,
+    /// The error code pushed by the processor, or zero.
+    error_code: usize,
+
+    /// The value of the `rip` register.
+    rip: usize,
+    /// The value of the `CS` register.
+    cs: usize,
+    /// The value of the `rflags` register.
+    rflags: usize,
+    /// The value of the `rsp` register.
+    rsp: usize,
+    /// The value of the `SS` register.
+    ss: usize,
+}
+
+/// Reports the processor state described by `frame` and then panics.
+extern "C" fn exception_handler(frame: &ExceptionFrame) -> ! {
+    begin_exception();
+
+    let name = EXCEPTION_NAMES
+        .get(frame.vector)
+        .copied()
+        .unwrap_or("unexpected interrupt");
+    crate::error!(
+        "unhandled exception: {name} [vector {}, error code {:#x}]",
+        frame.vector,
+        frame.error_code
+    );
+
+    log_registers(frame);
+
+    #[cfg(target_arch = "x86")]
+    let (instruction_pointer, frame_pointer) = (frame.eip, frame.ebp);
+    #[cfg(target_arch = "x86_64")]
+    let (instruction_pointer, frame_pointer) = (frame.rip, frame.rbp);
+    log_backtrace(frame_pointer);
+
+    panic!(
+        "unhandled exception: {name} at {}",
+        CodeAddress(instruction_pointer)
+    )
+}
+
+/// Logs the general purpose, segment, and control registers.
+fn log_registers(frame: &ExceptionFrame) {
+    #[cfg(target_arch = "x86")]
+    {
+        // Without a privilege level change, the processor does not push `esp` and `ss`.
+        let esp = (&raw const *frame).addr() + mem::size_of::<ExceptionFrame>();
+
+        crate::error!(
+            "EIP {}  CS {:#06x}  EFLAGS {:#010x}",
+            CodeAddress(frame.eip),
+            frame.cs,
+            frame.eflags
+        );
+        crate::error!(
+            "EAX {:#010x}  EBX {:#010x}  ECX {:#010x}  EDX {:#010x}",
+            frame.eax,
+            frame.ebx,
+            frame.ecx,
+            frame.edx
+        );
+        crate::error!(
+            "ESI {:#010x}  EDI {:#010x}  EBP {:#010x}  ESP {:#010x}",
+            frame.esi,
+            frame.edi,
+            frame.ebp,
+            esp
+        );
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        crate::error!(
+            "RIP {}  CS {:#06x}  RFLAGS {:#018x}",
+            CodeAddress(frame.rip),
+            frame.cs,
+            frame.rflags
+        );
+        crate::error!("RSP {:#018x}  SS {:#06x}", frame.rsp, frame.ss);
+        crate::error!(
+            "RAX {:#018x}  RBX {:#018x}  RCX {:#018x}",
+            frame.rax,
+            frame.rbx,
+            frame.rcx
+        );
+        crate::error!(
+            "RDX {:#018x}  RSI {:#018x}  RDI {:#018x}",
+            frame.rdx,
+            frame.rsi,
+            frame.rdi
+        );
+        crate::error!(
+            "RBP {:#018x}  R8  {:#018x}  R9  {:#018x}",
+            frame.rbp,
+            frame.r8,
+            frame.r9
+        );
+        crate::error!(
+            "R10 {:#018x}  R11 {:#018x}  R12 {:#018x}",
+            frame.r10,
+            frame.r11,
+            frame.r12
+        );
+        crate::error!(
+            "R13 {:#018x}  R14 {:#018x}  R15 {:#018x}",
+            frame.r13,
+            frame.r14,
+            frame.r15
+        );
+    }
+
+    let (ds, es, fs, gs): (u16, u16, u16, u16);
+    // SAFETY:
+    //
+    // Reading the segment registers has no side effects.
+    unsafe {
+        core::arch::asm!(
+            "mov {0:x}, ds",
+            "mov {1:x}, es",
+            "mov {2:x}, fs",
+            "mov {3:x}, gs",
+            out(reg) ds,
+            out(reg) es,
+            out(reg) fs,
+            out(reg) gs,
+            options(nomem, nostack, preserves_flags)
+        )
+    }
+    crate::error!("DS {ds:#06x}  ES {es:#06x}  FS {fs:#06x}  GS {gs:#06x}");
+
+    // SAFETY:
+    //
+    // The stub runs in ring 0, where the control registers are always readable.
+    let cr0 = unsafe { Cr0::get() };
+    // SAFETY:
+    //
+    // The stub runs in ring 0, where the control registers are always readable.
+    let cr2 = unsafe { Cr2::get() };
+    // SAFETY:
+    //
+    // The stub runs in ring 0, where the control registers are always readable.
+    let cr3 = unsafe { Cr3::get() };
+    // SAFETY:
+    //
+    // The stub runs in ring 0, where the control registers are always readable.
+    let cr4 = unsafe { Cr4::get() };
+    crate::error!(
+        "CR0 {:#018x}  CR2 {:#018x}  CR3 {:#018x}  CR4 {:#018x}",
+        cr0.to_bits(),
+        cr2.to_bits(),
+        cr3.to_bits(),
+        cr4.to_bits()
+    );
+}
+
+unsafe extern "C" {
+    static X86_EXCEPTION_STUBS: u8;
+}
+
+global_asm! {
+    // One entry stub per vector, each `STUB_SIZE` bytes long.
+    ".balign 16",
+    ".global X86_EXCEPTION_STUBS",
+    "X86_EXCEPTION_STUBS:",
+
+    ".set x86_exception_vector, 0",
+    ".rept {VECTOR_COUNT}",
+    ".balign {STUB_SIZE}, 0xCC",
+
+    // Push a dummy error code for the vectors where the processor does not push one.
+    ".if !((x86_exception_vector == 8) || ((x86_exception_vector >= 10) && (x86_exception_vector <= 14)) || (x86_exception_vector == 17) || (x86_exception_vector == 21) || (x86_exception_vector == 29) || (x86_exception_vector == 30))",
+    "push 0",
+    ".endif",
+
+    "push offset x86_exception_vector",
+    "jmp x86_exception_common",
+
+    ".set x86_exception_vector, x86_exception_vector + 1",
+    ".endr",
+
+    "x86_exception_common:",
+
+    #[cfg(target_arch = "x86")]
+    "push ebp",
+    #[cfg(target_arch = "x86")]
+    "push edi",
+    #[cfg(target_arch = "x86")]
+    "push esi",
+    #[cfg(target_arch = "x86")]
+    "push edx",
+    #[cfg(target_arch = "x86")]
+    "push ecx",
+    #[cfg(target_arch = "x86")]
+    "push ebx",
+    #[cfg(target_arch = "x86")]
+    "push eax",
+
+    // Pass a pointer to the `ExceptionFrame` on a 16-byte aligned stack.
+    #[cfg(target_arch = "x86")]
+    "mov eax, esp",
+    #[cfg(target_arch = "x86")]
+    "and esp, -16",
+    #[cfg(target_arch = "x86")]
+    "sub esp, 12",
+    #[cfg(target_arch = "x86")]
+    "push eax",
+
+    #[cfg(target_arch = "x86_64")]
+    "push r15",
+    #[cfg(target_arch = "x86_64")]
+    "push r14",
+    #[cfg(target_arch = "x86_64")]
+    "push r13",
+    #[cfg(target_arch = "x86_64")]
+    "push r12",
+    #[cfg(target_arch = "x86_64")]
+    "push r11",
+    #[cfg(target_arch = "x86_64")]
+    "push r10",
+    #[cfg(target_arch = "x86_64")]
+    "push r9",
+    #[cfg(target_arch = "x86_64")]
+    "push r8",
+    #[cfg(target_arch = "x86_64")]
+    "push rbp",
+    #[cfg(target_arch = "x86_64")]
+    "push rdi",
+    #[cfg(target_arch = "x86_64")]
+    "push rsi",
+    #[cfg(target_arch = "x86_64")]
+    "push rdx",
+    #[cfg(target_arch = "x86_64")]
+    "push rcx",
+    #[cfg(target_arch = "x86_64")]
+    "push rbx",
+    #[cfg(target_arch = "x86_64")]
+    "push rax",
+
+    // Pass a pointer to the `ExceptionFrame` on a 16-byte aligned stack.
+    #[cfg(target_arch = "x86_64")]
+    "mov rdi, rsp",
+    #[cfg(target_arch = "x86_64")]
+    "and rsp, -16",
+
+    "call {exception_handler}",
+    "ud2",
+
+    VECTOR_COUNT = const VECTOR_COUNT,
+    STUB_SIZE = const STUB_SIZE,
+    exception_handler = sym exception_handler,
+}
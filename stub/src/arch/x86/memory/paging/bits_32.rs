@@ -10,15 +10,19 @@ use x86::{
     paging::{
         PagingMode, bits_32::TranslationDescriptor, current_paging_mode, max_supported_paging_mode,
     },
+    pat::{PatSelector, supports_pat},
 };
 
 use crate::{
     arch::{
         generic::memory::paging::{
             ExternalFrameRange, ExternalPageRange, ExternalPhysicalAddress, ExternalVirtualAddress,
-            TranslationScheme,
+            TranslationScheme, block_fits,
+        },
+        x86::memory::{
+            paging::{pat_mapping_type, pat_selector},
+            physical_bits,
         },
-        x86::memory::physical_bits,
     },
     platform::{
         AllocationPolicy, FrameRange, MapError, MappingType, OutOfMemory, Permissions,
@@ -35,7 +39,10 @@ pub struct Bits32TranslationScheme {
     pse: bool,
 
     /// Whether 4 MiB pages can access beyond 32-bits.
-    _pse36: bool,
+    pse36: bool,
+    /// If `true`, the `IA32_PAT` MSR should be treated as containing
+    /// [`EXECUTABLE_PAT`][super::EXECUTABLE_PAT].
+    pat: bool,
 
     /// The maximum number of physical address bits supported.
     max_bits: u8,
@@ -67,8 +74,9 @@ impl Bits32TranslationScheme {
         let mut scheme = Self {
             physical_address: PhysicalAddress::zero(),
             pse,
-            _pse36: pse36,
+            pse36,
             max_bits,
+            pat: supports_pat(),
         };
 
         scheme.physical_address = scheme.allocate_zeroed_table()?.start_address();
@@ -111,8 +119,9 @@ impl Bits32TranslationScheme {
         Some(Self {
             physical_address,
             pse,
-            _pse36: pse36,
+            pse36,
             max_bits,
+            pat: false,
         })
     }
 
@@ -127,6 +136,12 @@ impl Bits32TranslationScheme {
         self.pse
     }
 
+    /// Returns `true` if the `IA32_PAT` MSR should be treated as containing
+    /// [`EXECUTABLE_PAT`][super::EXECUTABLE_PAT].
+    pub fn pat(&self) -> bool {
+        self.pat
+    }
+
     /// Allocates a zeroed page table.
     fn allocate_zeroed_table(&mut self) -> Option<FrameRange> {
         let range = allocate_physical(
@@ -147,8 +162,59 @@ impl Bits32TranslationScheme {
         mem::forget(range);
         Some(frame_range)
     }
+
+    /// Replaces the 4 MiB page at `entry_address` with a page table that maps the same region
+    /// with the same attributes, returning the new page directory entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the 4 MiB page maps memory above 4 GiB, which 4 KiB pages cannot reach.
+    fn split_block(
+        &mut self,
+        entry_address: PhysicalAddress,
+        entry: TranslationDescriptor,
+    ) -> Result<TranslationDescriptor, OutOfMemory> {
+        let block_address =
+            u32::try_from(entry.block_address()).expect("cannot split a 4 MiB page above 4 GiB");
+
+        let frame = self.allocate_zeroed_table().ok_or(OutOfMemory)?;
+        let table_address = frame.start_address();
+
+        for index in 0..ENTRIES {
+            let pte = entry
+                .set_block(false)
+                .set_page_pat(entry.block_pat())
+                .set_page_address(block_address + index * 4096);
+
+            if !write_u32_at(
+                table_address.strict_add(u64::from(index) * 4),
+                pte.to_bits(),
+            ) {
+                panic!("failed to write to physical memory");
+            }
+        }
+
+        let start_address =
+            u32::try_from(table_address.value()).expect("physical memory allocation failed");
+        let pde = TranslationDescriptor::non_present()
+            .set_present(true)
+            .set_writable(true)
+            .set_user(entry.user())
+            .set_table_address(start_address);
+        if !write_u32_at(entry_address, pde.to_bits()) {
+            panic!("failed to write to physical memory");
+        }
+
+        Ok(pde)
+    }
 }
 
+/// The number of entries in each page directory and page table.
+const ENTRIES: u32 = 1024;
+
+/// The size, in bytes, of the region mapped by a 4 MiB page.
+const BLOCK_SIZE: u64 = 1024 * 4096;
+
 impl TranslationScheme for Bits32TranslationScheme {
     fn input_descriptor(&self) -> AddressSpaceDescriptor {
         AddressSpaceDescriptor::new(32, false)
@@ -162,6 +228,10 @@ impl TranslationScheme for Bits32TranslationScheme {
         4096
     }
 
+    fn supports_mapping_type(&self, mapping_type: MappingType) -> bool {
+        mapping_type != MappingType::WriteCombining || self.pat
+    }
+
     fn map_at(
         &mut self,
         input: ExternalPageRange,
@@ -169,10 +239,6 @@ impl TranslationScheme for Bits32TranslationScheme {
         permissions: Permissions,
         mapping_type: MappingType,
     ) -> Result<(), MapError> {
-        if mapping_type != MappingType::Normal {
-            todo!("implement cacheability modifiers")
-        }
-
         let input_range = input.address_range(self.chunk_size());
         if !self.input_descriptor().is_valid_range(
             input_range.start().value(),
@@ -203,8 +269,22 @@ impl TranslationScheme for Bits32TranslationScheme {
             }
         }
 
-        for (input_chunk, output_chunk) in input.iter().zip(output.iter()) {
-            let address = input_chunk.start_address(self.chunk_size()).value();
+        let writable = permissions.writable();
+        let selector = pat_selector(mapping_type, self.pat);
+
+        let mut index = 0;
+        while index < input.count() {
+            let address = input
+                .start()
+                .strict_add(index)
+                .start_address(self.chunk_size())
+                .value();
+            let output_address = output
+                .start()
+                .strict_add(index)
+                .start_address(self.chunk_size())
+                .value();
+            let remaining = (input.count() - index) * self.chunk_size();
 
             let pd_index = (address >> 22) & 0x3FF;
             let pt_index = (address >> 12) & 0x3FF;
@@ -215,6 +295,27 @@ impl TranslationScheme for Bits32TranslationScheme {
                 let mut pde = TranslationDescriptor::from_bits(pde_value);
 
                 if !pde.present() {
+                    if self.pse
+                        && (self.pse36 || output_address <= u64::from(u32::MAX))
+                        && block_fits(address, output_address, remaining, BLOCK_SIZE)
+                    {
+                        pde = TranslationDescriptor::non_present()
+                            .set_present(true)
+                            .set_writable(writable)
+                            .set_pwt(selector.pwt)
+                            .set_pcd(selector.pcd)
+                            .set_block(true)
+                            .set_block_pat(selector.pat)
+                            .set_block_address(output_address);
+
+                        if !write_u32_at(pde_address, pde.to_bits()) {
+                            panic!("failed to write to physical memory");
+                        }
+
+                        index += BLOCK_SIZE / self.chunk_size();
+                        continue;
+                    }
+
                     let frame = self
                         .allocate_zeroed_table()
                         .ok_or(MapError::FrameAllocation(OutOfMemory))?;
@@ -229,50 +330,75 @@ impl TranslationScheme for Bits32TranslationScheme {
                     if !write_u32_at(pde_address, pde.to_bits()) {
                         panic!("failed to write to physical memory");
                     }
+                } else if self.pse && pde.block() {
+                    pde = self
+                        .split_block(pde_address, pde)
+                        .map_err(MapError::FrameAllocation)?;
                 }
 
                 PhysicalAddress::new(u64::from(pde.table_address()))
             };
 
-            let writable = permissions.writable();
-
             let pte_address = pt_table_address.strict_add(pt_index * 4);
 
             let start_address =
-                u32::try_from(output_chunk.start_address(self.chunk_size()).value())
-                    .expect("physical memory allocation failed");
+                u32::try_from(output_address).expect("physical memory allocation failed");
 
             let pte = TranslationDescriptor::non_present()
                 .set_present(true)
                 .set_writable(writable)
+                .set_pwt(selector.pwt)
+                .set_pcd(selector.pcd)
+                .set_page_pat(selector.pat)
                 .set_page_address(start_address);
 
             if !write_u32_at(pte_address, pte.to_bits()) {
                 panic!("failed to write to physical memory");
             }
+
+            index += 1;
         }
 
         Ok(())
     }
 
-    unsafe fn unmap(&mut self, input: ExternalPageRange) {
+    unsafe fn unmap(&mut self, input: ExternalPageRange) -> Result<(), OutOfMemory> {
         assert!(self.input_descriptor().is_valid_range(
             input.start_address(self.chunk_size()).value(),
             input.end_address_inclusive(self.chunk_size()).value()
         ));
 
-        for page in input.iter() {
-            let address = page.start_address(self.chunk_size()).value();
+        let mut index = 0;
+        while index < input.count() {
+            let address = input
+                .start()
+                .strict_add(index)
+                .start_address(self.chunk_size())
+                .value();
+            let remaining = (input.count() - index) * self.chunk_size();
 
             let pd_index = (address >> 22) & 0x3FF;
             let pt_index = (address >> 12) & 0x3FF;
 
             let pde_address = self.physical_address.strict_add(pd_index * 4);
             let pde_value = read_u32_at(pde_address).expect("failed to read from physical memory");
-            let pde = TranslationDescriptor::from_bits(pde_value);
+            let mut pde = TranslationDescriptor::from_bits(pde_value);
 
             if !pde.present() {
+                let skip = (BLOCK_SIZE - address % BLOCK_SIZE).min(remaining);
+                index += skip / self.chunk_size();
                 continue;
+            } else if self.pse && pde.block() {
+                if block_fits(address, 0, remaining, BLOCK_SIZE) {
+                    if !write_u32_at(pde_address, TranslationDescriptor::non_present().to_bits()) {
+                        panic!("failed to write to physical memory");
+                    }
+
+                    index += BLOCK_SIZE / self.chunk_size();
+                    continue;
+                }
+
+                pde = self.split_block(pde_address, pde)?;
             }
 
             let pt_table_address = PhysicalAddress::new(u64::from(pde.table_address()));
@@ -281,7 +407,11 @@ impl TranslationScheme for Bits32TranslationScheme {
             if !write_u32_at(pte_address, TranslationDescriptor::non_present().to_bits()) {
                 panic!("failed to write to physical memory");
             }
+
+            index += 1;
         }
+
+        Ok(())
     }
 
     fn translate(
@@ -316,8 +446,12 @@ impl TranslationScheme for Bits32TranslationScheme {
             } else {
                 Permissions::ReadExecute
             };
-            // TODO: Fix mapping type computation.
-            let mapping_type = MappingType::Normal;
+            let selector = PatSelector {
+                pwt: pde.pwt(),
+                pcd: pde.pcd(),
+                pat: pde.block_pat(),
+            };
+            let mapping_type = pat_mapping_type(selector, self.pat);
             let address = ExternalPhysicalAddress::new(pde.block_address()).strict_add(offset);
 
             return Some((permissions, mapping_type, address));
@@ -342,11 +476,119 @@ impl TranslationScheme for Bits32TranslationScheme {
         } else {
             Permissions::ReadExecute
         };
-        // TODO: Fix mapping type computation.
-        let mapping_type = MappingType::Normal;
+        let selector = PatSelector {
+            pwt: pte.pwt(),
+            pcd: pte.pcd(),
+            pat: pte.page_pat(),
+        };
+        let mapping_type = pat_mapping_type(selector, self.pat);
         let address =
             ExternalPhysicalAddress::new(u64::from(pte.page_address())).strict_add(offset);
 
         Some((permissions, mapping_type, address))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{BLOCK_SIZE, Bits32TranslationScheme, TranslationDescriptor};
+    use crate::{
+        arch::generic::memory::paging::test_util::{map, translate, unmap},
+        platform::{PhysicalAddress, mock, read_u32_at},
+    };
+
+    /// The size of a page.
+    const PAGE: u64 = 4096;
+    /// An input address that is aligned to every block size.
+    const INPUT: u64 = 0x4000_0000;
+    /// An output address that is aligned to every block size.
+    const OUTPUT: u64 = 0x8000_0000;
+
+    /// Creates a [`Bits32TranslationScheme`] that maps 4 MiB pages.
+    fn scheme() -> Bits32TranslationScheme {
+        let mut scheme =
+            Bits32TranslationScheme::max_supported().expect("32-bit paging is unsupported");
+        scheme.pse = true;
+        scheme
+    }
+
+    /// Returns the size of the region mapped by the entry that translates `address`.
+    fn mapping_size(scheme: &Bits32TranslationScheme, address: u64) -> Option<u64> {
+        let entry = |table: PhysicalAddress, shift: u64| {
+            let entry_address = table.strict_add(((address >> shift) & 0x3FF) * 4);
+            let entry = TranslationDescriptor::from_bits(read_u32_at(entry_address)?);
+            entry.present().then_some(entry)
+        };
+
+        let pde = entry(scheme.physical_address, 22)?;
+        if pde.block() {
+            return Some(BLOCK_SIZE);
+        }
+
+        entry(PhysicalAddress::new(u64::from(pde.table_address())), 12).map(|_| PAGE)
+    }
+
+    #[test]
+    fn map_uses_blocks_when_aligned() {
+        let _guard = mock::acquire();
+        let mut scheme = scheme();
+
+        let size = BLOCK_SIZE + PAGE;
+        map(&mut scheme, INPUT, OUTPUT, size);
+
+        assert_eq!(mapping_size(&scheme, INPUT), Some(BLOCK_SIZE));
+        assert_eq!(mapping_size(&scheme, INPUT + BLOCK_SIZE), Some(PAGE));
+        for offset in [0, BLOCK_SIZE - PAGE, BLOCK_SIZE] {
+            assert_eq!(translate(&scheme, INPUT + offset), Some(OUTPUT + offset));
+        }
+        assert_eq!(translate(&scheme, INPUT + size), None);
+    }
+
+    #[test]
+    fn map_uses_pages_when_misaligned() {
+        let _guard = mock::acquire();
+        let mut scheme = scheme();
+
+        map(&mut scheme, INPUT, OUTPUT + PAGE, BLOCK_SIZE);
+
+        assert_eq!(mapping_size(&scheme, INPUT), Some(PAGE));
+        assert_eq!(translate(&scheme, INPUT), Some(OUTPUT + PAGE));
+        assert_eq!(
+            translate(&scheme, INPUT + BLOCK_SIZE - PAGE),
+            Some(OUTPUT + BLOCK_SIZE)
+        );
+    }
+
+    #[test]
+    fn unmap_splits_4mib_page() {
+        let _guard = mock::acquire();
+        let mut scheme = scheme();
+
+        map(&mut scheme, INPUT, OUTPUT, BLOCK_SIZE);
+        let hole = INPUT + BLOCK_SIZE / 2;
+        unmap(&mut scheme, hole, PAGE);
+
+        assert_eq!(translate(&scheme, hole), None);
+        assert_eq!(mapping_size(&scheme, INPUT), Some(PAGE));
+        for offset in [
+            0,
+            BLOCK_SIZE / 2 - PAGE,
+            BLOCK_SIZE / 2 + PAGE,
+            BLOCK_SIZE - PAGE,
+        ] {
+            assert_eq!(translate(&scheme, INPUT + offset), Some(OUTPUT + offset));
+        }
+    }
+
+    #[test]
+    fn unmap_removes_whole_blocks() {
+        let _guard = mock::acquire();
+        let mut scheme = scheme();
+
+        map(&mut scheme, INPUT, OUTPUT, BLOCK_SIZE);
+        unmap(&mut scheme, INPUT, BLOCK_SIZE);
+
+        assert_eq!(mapping_size(&scheme, INPUT), None);
+        assert_eq!(translate(&scheme, INPUT + BLOCK_SIZE - PAGE), None);
+    }
+}
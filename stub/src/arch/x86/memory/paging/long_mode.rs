@@ -11,15 +11,19 @@ use x86::{
     paging::{
         PagingMode, bits_64::TranslationDescriptor, current_paging_mode, max_supported_paging_mode,
     },
+    pat::{PatSelector, supports_pat},
 };
 
 use crate::{
     arch::{
         generic::memory::paging::{
             ExternalFrameRange, ExternalPageRange, ExternalPhysicalAddress, ExternalVirtualAddress,
-            TranslationScheme,
+            TranslationScheme, block_fits,
+        },
+        x86::memory::{
+            paging::{pat_mapping_type, pat_selector},
+            physical_bits,
         },
-        x86::memory::physical_bits,
     },
     platform::{
         AllocationPolicy, FrameRange, MapError, MappingType, OutOfMemory, Permissions,
@@ -35,6 +39,11 @@ pub struct LongModeTranslationScheme {
     la57: bool,
     /// If `true`, the `NXE` bit should be treated as being set.
     nxe: bool,
+    /// If `true`, 1 GiB pages are supported.
+    page_1gib: bool,
+    /// If `true`, the `IA32_PAT` MSR should be treated as containing
+    /// [`EXECUTABLE_PAT`][super::EXECUTABLE_PAT].
+    pat: bool,
 }
 
 impl LongModeTranslationScheme {
@@ -58,6 +67,8 @@ impl LongModeTranslationScheme {
             physical_address: PhysicalAddress::zero(),
             la57: paging_mode == PagingMode::Level5,
             nxe,
+            page_1gib: supports_1gib_pages(),
+            pat: supports_pat(),
         };
 
         scheme.physical_address = scheme.allocate_zeroed_table()?.start_address();
@@ -97,6 +108,8 @@ impl LongModeTranslationScheme {
             physical_address,
             la57,
             nxe,
+            page_1gib: supports_1gib_pages(),
+            pat: false,
         })
     }
 
@@ -116,6 +129,12 @@ impl LongModeTranslationScheme {
         self.nxe
     }
 
+    /// Returns `true` if the `IA32_PAT` MSR should be treated as containing
+    /// [`EXECUTABLE_PAT`][super::EXECUTABLE_PAT].
+    pub fn pat(&self) -> bool {
+        self.pat
+    }
+
     /// Allocates a zeroed page table.
     fn allocate_zeroed_table(&mut self) -> Option<FrameRange> {
         let max_physical = 1u64
@@ -139,6 +158,94 @@ impl LongModeTranslationScheme {
         mem::forget(range);
         Some(frame_range)
     }
+
+    /// Returns the address of the table referenced by the PML`level`E at `entry_address`.
+    ///
+    /// If the entry is not present, an empty table is allocated. If the entry is a block, it is
+    /// split into a table that maps the same region.
+    fn next_table(
+        &mut self,
+        entry_address: PhysicalAddress,
+        level: u8,
+    ) -> Result<PhysicalAddress, OutOfMemory> {
+        let value =
+            read_u64_at(entry_address).unwrap_or_else(|| panic!("failed to read PML{level}E"));
+        let mut entry = TranslationDescriptor::from_bits(value);
+        if !entry.present() {
+            let frame = self.allocate_zeroed_table().ok_or(OutOfMemory)?;
+
+            entry =
+                TranslationDescriptor::new_table(frame.start_address().value()).set_writable(true);
+            if !write_u64_at(entry_address, entry.to_bits()) {
+                panic!("failed to write PML{level}E")
+            }
+        } else if (level == 2 || level == 3) && entry.block() {
+            entry = self.split_block(entry_address, entry, level)?;
+        }
+
+        Ok(PhysicalAddress::new(entry.table_address()))
+    }
+
+    /// Replaces the PML`level`E block at `entry_address` with a table of smaller translations that
+    /// map the same region with the same attributes, returning the new table descriptor.
+    fn split_block(
+        &mut self,
+        entry_address: PhysicalAddress,
+        entry: TranslationDescriptor,
+        level: u8,
+    ) -> Result<TranslationDescriptor, OutOfMemory> {
+        let frame = self.allocate_zeroed_table().ok_or(OutOfMemory)?;
+        let table_address = frame.start_address();
+
+        for index in 0..ENTRIES {
+            let child = if level == 3 {
+                entry.set_block_pml2_address(entry.block_pml3_address() + index * PML2_BLOCK_SIZE)
+            } else {
+                entry
+                    .set_block(false)
+                    .set_page_pat(entry.block_pat())
+                    .set_page_address(entry.block_pml2_address() + index * self.chunk_size())
+            };
+
+            if !write_u64_at(table_address.strict_add(index * 8), child.to_bits()) {
+                panic!("failed to write PML{}E", level - 1)
+            }
+        }
+
+        let table = TranslationDescriptor::new_table(table_address.value())
+            .set_writable(true)
+            .set_user(entry.user());
+        if !write_u64_at(entry_address, table.to_bits()) {
+            panic!("failed to write PML{level}E")
+        }
+
+        Ok(table)
+    }
+}
+
+/// The number of entries in each page table.
+const ENTRIES: u64 = 512;
+
+/// The size, in bytes, of the region mapped by a PML2 block.
+const PML2_BLOCK_SIZE: u64 = 512 * 4096;
+
+/// The size, in bytes, of the region mapped by a PML3 block.
+const PML3_BLOCK_SIZE: u64 = 512 * PML2_BLOCK_SIZE;
+
+/// Returns `true` if 1 GiB pages are supported.
+fn supports_1gib_pages() -> bool {
+    if !supports_cpuid() {
+        return false;
+    }
+
+    // SAFETY:
+    //
+    // The CPUID instruction is supported.
+    let max_extended = unsafe { cpuid_unchecked(0x8000_0000, 0).eax };
+    // SAFETY:
+    //
+    // The CPUID instruction is supported and leaf `0x8000_0001` is within the supported range.
+    max_extended >= 0x8000_0001 && unsafe { (cpuid_unchecked(0x8000_0001, 0).edx & (1 << 26)) != 0 }
 }
 
 impl TranslationScheme for LongModeTranslationScheme {
@@ -154,6 +261,10 @@ impl TranslationScheme for LongModeTranslationScheme {
         4096
     }
 
+    fn supports_mapping_type(&self, mapping_type: MappingType) -> bool {
+        mapping_type != MappingType::WriteCombining || self.pat
+    }
+
     fn map_at(
         &mut self,
         input: ExternalPageRange,
@@ -161,10 +272,6 @@ impl TranslationScheme for LongModeTranslationScheme {
         permissions: Permissions,
         mapping_type: MappingType,
     ) -> Result<(), MapError> {
-        if mapping_type != MappingType::Normal {
-            todo!("implement cacheability modifiers")
-        }
-
         let input_range = input.address_range(self.chunk_size());
         if !self.input_descriptor().is_valid_range(
             input_range.start().value(),
@@ -195,8 +302,23 @@ impl TranslationScheme for LongModeTranslationScheme {
             }
         }
 
-        for (input_chunk, output_chunk) in input.iter().zip(output.iter()) {
-            let address = input_chunk.start_address(self.chunk_size()).value();
+        let writable = permissions.writable();
+        let xd = self.nxe && !permissions.executable();
+        let selector = pat_selector(mapping_type, self.pat);
+
+        let mut index = 0;
+        while index < input.count() {
+            let address = input
+                .start()
+                .strict_add(index)
+                .start_address(self.chunk_size())
+                .value();
+            let output_address = output
+                .start()
+                .strict_add(index)
+                .start_address(self.chunk_size())
+                .value();
+            let remaining = (input.count() - index) * self.chunk_size();
 
             let pml5e_index = (address >> 48) & 0x1FF;
             let pml4e_index = (address >> 39) & 0x1FF;
@@ -206,124 +328,105 @@ impl TranslationScheme for LongModeTranslationScheme {
 
             let pml4_table_address = if self.la57 {
                 let pml5e_address = self.physical_address.strict_add(pml5e_index * 8);
-                let pml5e_value = read_u64_at(pml5e_address).expect("failed to read PML5E");
-                let mut pml5e = TranslationDescriptor::from_bits(pml5e_value);
-                if !pml5e.present() {
-                    let Some(frame) = self.allocate_zeroed_table() else {
-                        return Err(MapError::FrameAllocation(OutOfMemory));
-                    };
-
-                    pml5e = TranslationDescriptor::non_present()
-                        .set_present(true)
-                        .set_writable(true)
-                        .set_table_address(frame.start_address().value());
-
-                    if !write_u64_at(pml5e_address, pml5e.to_bits()) {
-                        panic!("failed to write PML5E")
-                    }
-                }
-
-                PhysicalAddress::new(pml5e.table_address())
+                self.next_table(pml5e_address, 5)
+                    .map_err(MapError::FrameAllocation)?
             } else {
                 self.physical_address
             };
 
-            let pml3_table_address = {
-                let pml4e_address = pml4_table_address.strict_add(pml4e_index * 8);
-                let pml4e_value = read_u64_at(pml4e_address).expect("failed to read PML4E");
-                let mut pml4e = TranslationDescriptor::from_bits(pml4e_value);
-                if !pml4e.present() {
-                    let Some(frame) = self.allocate_zeroed_table() else {
-                        return Err(MapError::FrameAllocation(OutOfMemory));
-                    };
-
-                    pml4e = TranslationDescriptor::non_present()
-                        .set_present(true)
-                        .set_writable(true)
-                        .set_table_address(frame.start_address().value());
-
-                    if !write_u64_at(pml4e_address, pml4e.to_bits()) {
-                        panic!("failed to write PML4E")
-                    }
-                }
-
-                PhysicalAddress::new(pml4e.table_address())
-            };
-
-            let pml2_table_address = {
-                let pml3e_address = pml3_table_address.strict_add(pml3e_index * 8);
-                let pml3e_value = read_u64_at(pml3e_address).expect("failed to read PML3E");
-                let mut pml3e = TranslationDescriptor::from_bits(pml3e_value);
-                if !pml3e.present() {
-                    let Some(frame) = self.allocate_zeroed_table() else {
-                        return Err(MapError::FrameAllocation(OutOfMemory));
-                    };
-
-                    pml3e = TranslationDescriptor::non_present()
-                        .set_present(true)
-                        .set_writable(true)
-                        .set_table_address(frame.start_address().value());
+            let pml4e_address = pml4_table_address.strict_add(pml4e_index * 8);
+            let pml3_table_address = self
+                .next_table(pml4e_address, 4)
+                .map_err(MapError::FrameAllocation)?;
 
-                    if !write_u64_at(pml3e_address, pml3e.to_bits()) {
-                        panic!("failed to write PML3E")
-                    }
-                } else if pml3e.block() {
-                    todo!("implement block page splitting")
+            let pml3e_address = pml3_table_address.strict_add(pml3e_index * 8);
+            if self.page_1gib
+                && block_fits(address, output_address, remaining, PML3_BLOCK_SIZE)
+                && !TranslationDescriptor::from_bits(
+                    read_u64_at(pml3e_address).expect("failed to read PML3E"),
+                )
+                .present()
+            {
+                let pml3e = TranslationDescriptor::new_block_pml3(output_address)
+                    .set_writable(writable)
+                    .set_pwt(selector.pwt)
+                    .set_pcd(selector.pcd)
+                    .set_block_pat(selector.pat)
+                    .set_xd(xd);
+                if !write_u64_at(pml3e_address, pml3e.to_bits()) {
+                    panic!("failed to write PML3E")
                 }
 
-                PhysicalAddress::new(pml3e.table_address())
-            };
-
-            let pml1_table_address = {
-                let pml2e_address = pml2_table_address.strict_add(pml2e_index * 8);
-                let pml2e_value = read_u64_at(pml2e_address).expect("failed to read PML2E");
-                let mut pml2e = TranslationDescriptor::from_bits(pml2e_value);
-                if !pml2e.present() {
-                    let Some(frame) = self.allocate_zeroed_table() else {
-                        return Err(MapError::FrameAllocation(OutOfMemory));
-                    };
+                index += PML3_BLOCK_SIZE / self.chunk_size();
+                continue;
+            }
 
-                    pml2e = TranslationDescriptor::non_present()
-                        .set_present(true)
-                        .set_writable(true)
-                        .set_table_address(frame.start_address().value());
+            let pml2_table_address = self
+                .next_table(pml3e_address, 3)
+                .map_err(MapError::FrameAllocation)?;
 
-                    if !write_u64_at(pml2e_address, pml2e.to_bits()) {
-                        panic!("failed to write PML2E")
-                    }
-                } else if pml2e.block() {
-                    todo!("implement block page splitting")
+            let pml2e_address = pml2_table_address.strict_add(pml2e_index * 8);
+            if block_fits(address, output_address, remaining, PML2_BLOCK_SIZE)
+                && !TranslationDescriptor::from_bits(
+                    read_u64_at(pml2e_address).expect("failed to read PML2E"),
+                )
+                .present()
+            {
+                let pml2e = TranslationDescriptor::new_block_pml2(output_address)
+                    .set_writable(writable)
+                    .set_pwt(selector.pwt)
+                    .set_pcd(selector.pcd)
+                    .set_block_pat(selector.pat)
+                    .set_xd(xd);
+                if !write_u64_at(pml2e_address, pml2e.to_bits()) {
+                    panic!("failed to write PML2E")
                 }
 
-                PhysicalAddress::new(pml2e.table_address())
-            };
+                index += PML2_BLOCK_SIZE / self.chunk_size();
+                continue;
+            }
 
-            let writable = permissions.writable();
-            let xd = self.nxe && !permissions.executable();
+            let pml1_table_address = self
+                .next_table(pml2e_address, 2)
+                .map_err(MapError::FrameAllocation)?;
 
             let pml1e_address = pml1_table_address.strict_add(pml1e_index * 8);
-            let pml1e = TranslationDescriptor::non_present()
-                .set_present(true)
+            let pml1e = TranslationDescriptor::new_page(output_address)
                 .set_writable(writable)
-                .set_page_address(output_chunk.start_address(self.chunk_size()).value())
+                .set_pwt(selector.pwt)
+                .set_pcd(selector.pcd)
+                .set_page_pat(selector.pat)
                 .set_xd(xd);
 
             if !write_u64_at(pml1e_address, pml1e.to_bits()) {
                 panic!("failed to write PML1E")
             }
+
+            index += 1;
         }
 
         Ok(())
     }
 
-    unsafe fn unmap(&mut self, input: ExternalPageRange) {
+    unsafe fn unmap(&mut self, input: ExternalPageRange) -> Result<(), OutOfMemory> {
         assert!(self.input_descriptor().is_valid_range(
             input.start_address(self.chunk_size()).value(),
             input.end_address_inclusive(self.chunk_size()).value()
         ));
 
-        for chunk in input.iter() {
-            let address = chunk.start_address(self.chunk_size()).value();
+        let mut index = 0;
+        while index < input.count() {
+            let address = input
+                .start()
+                .strict_add(index)
+                .start_address(self.chunk_size())
+                .value();
+            let remaining = (input.count() - index) * self.chunk_size();
+            // The number of chunks from `address` to the end of the region mapped by an entry of
+            // `size` bytes, limited to the end of `input`.
+            let chunk_size = self.chunk_size();
+            let skip =
+                |size: u64| ((size - address % size) / chunk_size).min(remaining / chunk_size);
 
             let pml5e_index = (address >> 48) & 0x1FF;
             let pml4e_index = (address >> 39) & 0x1FF;
@@ -336,6 +439,7 @@ impl TranslationScheme for LongModeTranslationScheme {
                 let pml5e_value = read_u64_at(pml5e_address).expect("failed to read PML5E");
                 let pml5e = TranslationDescriptor::from_bits(pml5e_value);
                 if !pml5e.present() {
+                    index += skip(ENTRIES * ENTRIES * PML3_BLOCK_SIZE);
                     continue;
                 }
 
@@ -349,6 +453,7 @@ impl TranslationScheme for LongModeTranslationScheme {
                 let pml4e_value = read_u64_at(pml4e_address).expect("failed to read PML4E");
                 let pml4e = TranslationDescriptor::from_bits(pml4e_value);
                 if !pml4e.present() {
+                    index += skip(ENTRIES * PML3_BLOCK_SIZE);
                     continue;
                 }
 
@@ -360,12 +465,26 @@ impl TranslationScheme for LongModeTranslationScheme {
                 let pml3e_value = read_u64_at(pml3e_address).expect("failed to read PML3E");
                 let pml3e = TranslationDescriptor::from_bits(pml3e_value);
                 if !pml3e.present() {
+                    index += skip(PML3_BLOCK_SIZE);
                     continue;
                 } else if pml3e.block() {
-                    todo!("implement block page splitting")
-                }
+                    if block_fits(address, 0, remaining, PML3_BLOCK_SIZE) {
+                        if !write_u64_at(
+                            pml3e_address,
+                            TranslationDescriptor::non_present().to_bits(),
+                        ) {
+                            panic!("failed to write PML3E")
+                        }
+
+                        index += PML3_BLOCK_SIZE / self.chunk_size();
+                        continue;
+                    }
 
-                PhysicalAddress::new(pml3e.table_address())
+                    let table = self.split_block(pml3e_address, pml3e, 3)?;
+                    PhysicalAddress::new(table.table_address())
+                } else {
+                    PhysicalAddress::new(pml3e.table_address())
+                }
             };
 
             let pml1_table_address = {
@@ -373,12 +492,26 @@ impl TranslationScheme for LongModeTranslationScheme {
                 let pml2e_value = read_u64_at(pml2e_address).expect("failed to read PML2E");
                 let pml2e = TranslationDescriptor::from_bits(pml2e_value);
                 if !pml2e.present() {
+                    index += skip(PML2_BLOCK_SIZE);
                     continue;
                 } else if pml2e.block() {
-                    todo!("implement block page splitting")
-                }
+                    if block_fits(address, 0, remaining, PML2_BLOCK_SIZE) {
+                        if !write_u64_at(
+                            pml2e_address,
+                            TranslationDescriptor::non_present().to_bits(),
+                        ) {
+                            panic!("failed to write PML2E")
+                        }
+
+                        index += PML2_BLOCK_SIZE / self.chunk_size();
+                        continue;
+                    }
 
-                PhysicalAddress::new(pml2e.table_address())
+                    let table = self.split_block(pml2e_address, pml2e, 2)?;
+                    PhysicalAddress::new(table.table_address())
+                } else {
+                    PhysicalAddress::new(pml2e.table_address())
+                }
             };
 
             let pml1e_address = pml1_table_address.strict_add(pml1e_index * 8);
@@ -387,7 +520,11 @@ impl TranslationScheme for LongModeTranslationScheme {
             if !write_u64_at(pml1e_address, pml1e.to_bits()) {
                 panic!("failed to write PML1E")
             }
+
+            index += 1;
         }
+
+        Ok(())
     }
 
     fn translate(
@@ -453,8 +590,12 @@ impl TranslationScheme for LongModeTranslationScheme {
                     (false, true) => Permissions::ReadExecute,
                     (false, false) => Permissions::Read,
                 };
-                // TODO: Fix mapping type computation.
-                let mapping_type = MappingType::Normal;
+                let selector = PatSelector {
+                    pwt: pml3e.pwt(),
+                    pcd: pml3e.pcd(),
+                    pat: pml3e.block_pat(),
+                };
+                let mapping_type = pat_mapping_type(selector, self.pat);
                 let address =
                     ExternalPhysicalAddress::new(pml3e.block_pml3_address()).strict_add(offset);
 
@@ -483,8 +624,12 @@ impl TranslationScheme for LongModeTranslationScheme {
                     (false, true) => Permissions::ReadExecute,
                     (false, false) => Permissions::Read,
                 };
-                // TODO: Fix mapping type computation.
-                let mapping_type = MappingType::Normal;
+                let selector = PatSelector {
+                    pwt: pml2e.pwt(),
+                    pcd: pml2e.pcd(),
+                    pat: pml2e.block_pat(),
+                };
+                let mapping_type = pat_mapping_type(selector, self.pat);
                 let address =
                     ExternalPhysicalAddress::new(pml2e.block_pml2_address()).strict_add(offset);
 
@@ -511,10 +656,159 @@ impl TranslationScheme for LongModeTranslationScheme {
             (false, true) => Permissions::ReadExecute,
             (false, false) => Permissions::Read,
         };
-        // TODO: Fix mapping type computation.
-        let mapping_type = MappingType::Normal;
+        let selector = PatSelector {
+            pwt: pml1e.pwt(),
+            pcd: pml1e.pcd(),
+            pat: pml1e.page_pat(),
+        };
+        let mapping_type = pat_mapping_type(selector, self.pat);
         let address = ExternalPhysicalAddress::new(pml1e.page_address()).strict_add(offset);
 
         Some((permissions, mapping_type, address))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{
+        LongModeTranslationScheme, PML2_BLOCK_SIZE, PML3_BLOCK_SIZE, TranslationDescriptor,
+    };
+    use crate::{
+        arch::generic::memory::paging::test_util::{map, translate, unmap},
+        platform::{PhysicalAddress, mock, read_u64_at},
+    };
+
+    /// The size of a page.
+    const PAGE: u64 = 4096;
+    /// An input address that is aligned to every block size.
+    const INPUT: u64 = 0x40_0000_0000;
+    /// An output address that is aligned to every block size.
+    const OUTPUT: u64 = 0x8000_0000;
+
+    /// Creates a 4-level [`LongModeTranslationScheme`] that maps 1 GiB blocks.
+    fn scheme() -> LongModeTranslationScheme {
+        let mut scheme =
+            LongModeTranslationScheme::max_supported().expect("long mode paging is unsupported");
+        scheme.la57 = false;
+        scheme.page_1gib = true;
+        scheme
+    }
+
+    /// Returns the size of the region mapped by the entry that translates `address`.
+    fn mapping_size(scheme: &LongModeTranslationScheme, address: u64) -> Option<u64> {
+        let entry = |table: PhysicalAddress, shift: u64| {
+            let entry_address = table.strict_add(((address >> shift) & 0x1FF) * 8);
+            let entry = TranslationDescriptor::from_bits(read_u64_at(entry_address)?);
+            entry.present().then_some(entry)
+        };
+
+        let pml4e = entry(scheme.physical_address, 39)?;
+        let pml3e = entry(PhysicalAddress::new(pml4e.table_address()), 30)?;
+        if pml3e.block() {
+            return Some(PML3_BLOCK_SIZE);
+        }
+
+        let pml2e = entry(PhysicalAddress::new(pml3e.table_address()), 21)?;
+        if pml2e.block() {
+            return Some(PML2_BLOCK_SIZE);
+        }
+
+        entry(PhysicalAddress::new(pml2e.table_address()), 12).map(|_| PAGE)
+    }
+
+    #[test]
+    fn map_uses_blocks_when_aligned() {
+        let _guard = mock::acquire();
+        let mut scheme = scheme();
+
+        let size = PML3_BLOCK_SIZE + PML2_BLOCK_SIZE + PAGE;
+        map(&mut scheme, INPUT, OUTPUT, size);
+
+        assert_eq!(mapping_size(&scheme, INPUT), Some(PML3_BLOCK_SIZE));
+        assert_eq!(
+            mapping_size(&scheme, INPUT + PML3_BLOCK_SIZE),
+            Some(PML2_BLOCK_SIZE)
+        );
+        assert_eq!(mapping_size(&scheme, INPUT + size - PAGE), Some(PAGE));
+        for offset in [
+            0,
+            PML3_BLOCK_SIZE - PAGE,
+            PML3_BLOCK_SIZE + PAGE,
+            size - PAGE,
+        ] {
+            assert_eq!(translate(&scheme, INPUT + offset), Some(OUTPUT + offset));
+        }
+        assert_eq!(translate(&scheme, INPUT + size), None);
+    }
+
+    #[test]
+    fn map_uses_pages_when_misaligned() {
+        let _guard = mock::acquire();
+        let mut scheme = scheme();
+
+        map(&mut scheme, INPUT, OUTPUT + PAGE, PML2_BLOCK_SIZE);
+
+        assert_eq!(mapping_size(&scheme, INPUT), Some(PAGE));
+        assert_eq!(translate(&scheme, INPUT), Some(OUTPUT + PAGE));
+        assert_eq!(
+            translate(&scheme, INPUT + PML2_BLOCK_SIZE - PAGE),
+            Some(OUTPUT + PML2_BLOCK_SIZE)
+        );
+    }
+
+    #[test]
+    fn unmap_splits_1gib_block() {
+        let _guard = mock::acquire();
+        let mut scheme = scheme();
+
+        map(&mut scheme, INPUT, OUTPUT, PML3_BLOCK_SIZE);
+        let hole = INPUT + PML2_BLOCK_SIZE + PAGE;
+        unmap(&mut scheme, hole, PAGE);
+
+        assert_eq!(translate(&scheme, hole), None);
+        assert_eq!(mapping_size(&scheme, INPUT), Some(PML2_BLOCK_SIZE));
+        assert_eq!(mapping_size(&scheme, hole - PAGE), Some(PAGE));
+        for offset in [
+            0,
+            PML2_BLOCK_SIZE,
+            2 * PAGE + PML2_BLOCK_SIZE,
+            2 * PML2_BLOCK_SIZE,
+            PML3_BLOCK_SIZE - PAGE,
+        ] {
+            assert_eq!(translate(&scheme, INPUT + offset), Some(OUTPUT + offset));
+        }
+    }
+
+    #[test]
+    fn unmap_splits_2mib_block() {
+        let _guard = mock::acquire();
+        let mut scheme = scheme();
+
+        map(&mut scheme, INPUT, OUTPUT, PML2_BLOCK_SIZE);
+        assert_eq!(mapping_size(&scheme, INPUT), Some(PML2_BLOCK_SIZE));
+
+        let half = PML2_BLOCK_SIZE / 2;
+        unmap(&mut scheme, INPUT + half, half);
+
+        assert_eq!(mapping_size(&scheme, INPUT), Some(PAGE));
+        assert_eq!(translate(&scheme, INPUT), Some(OUTPUT));
+        assert_eq!(
+            translate(&scheme, INPUT + half - PAGE),
+            Some(OUTPUT + half - PAGE)
+        );
+        assert_eq!(translate(&scheme, INPUT + half), None);
+        assert_eq!(translate(&scheme, INPUT + PML2_BLOCK_SIZE - PAGE), None);
+    }
+
+    #[test]
+    fn unmap_removes_whole_blocks() {
+        let _guard = mock::acquire();
+        let mut scheme = scheme();
+
+        map(&mut scheme, INPUT, OUTPUT, PML3_BLOCK_SIZE);
+        unmap(&mut scheme, INPUT, PML3_BLOCK_SIZE);
+
+        assert_eq!(mapping_size(&scheme, INPUT), None);
+        assert_eq!(translate(&scheme, INPUT + PML3_BLOCK_SIZE - PAGE), None);
+    }
+}
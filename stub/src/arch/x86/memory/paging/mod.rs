@@ -2,7 +2,10 @@
 
 use elf::header::Machine;
 use memory::AddressSpaceDescriptor;
-use x86::paging::{PagingMode, current_paging_mode};
+use x86::{
+    paging::{PagingMode, current_paging_mode},
+    pat::{MemoryType, Pat, PatSelector},
+};
 
 use crate::{
     arch::{
@@ -15,7 +18,7 @@ use crate::{
             pae::PaeTranslationScheme,
         },
     },
-    platform::{MapError, MappingType, Permissions},
+    platform::{MapError, MappingType, OutOfMemory, Permissions},
 };
 
 mod bits_32;
@@ -134,6 +137,16 @@ impl X86TranslationScheme {
             Inner::LongMode(long_mode) => long_mode.nxe(),
         }
     }
+
+    /// Returns `true` if this page table configuration requires the `IA32_PAT` MSR to contain
+    /// [`EXECUTABLE_PAT`].
+    pub fn pat(&self) -> bool {
+        match &self.0 {
+            Inner::Bits32(bits_32) => bits_32.pat(),
+            Inner::Pae(pae) => pae.pat(),
+            Inner::LongMode(long_mode) => long_mode.pat(),
+        }
+    }
 }
 
 impl TranslationScheme for X86TranslationScheme {
@@ -161,6 +174,14 @@ impl TranslationScheme for X86TranslationScheme {
         }
     }
 
+    fn supports_mapping_type(&self, mapping_type: MappingType) -> bool {
+        match &self.0 {
+            Inner::Bits32(scheme) => scheme.supports_mapping_type(mapping_type),
+            Inner::Pae(scheme) => scheme.supports_mapping_type(mapping_type),
+            Inner::LongMode(scheme) => scheme.supports_mapping_type(mapping_type),
+        }
+    }
+
     fn map_at(
         &mut self,
         input: ExternalPageRange,
@@ -175,7 +196,7 @@ impl TranslationScheme for X86TranslationScheme {
         }
     }
 
-    unsafe fn unmap(&mut self, input: ExternalPageRange) {
+    unsafe fn unmap(&mut self, input: ExternalPageRange) -> Result<(), OutOfMemory> {
         match &mut self.0 {
             // SAFETY:
             //
@@ -204,6 +225,48 @@ impl TranslationScheme for X86TranslationScheme {
     }
 }
 
+/// The [`Pat`] programmed while an address space that [requires it][X86TranslationScheme::pat]
+/// is active.
+///
+/// This is the power-on layout with entry 1 changed from write-through to write-combining, so
+/// every other combination of the PCD and PWT bits retains its traditional meaning.
+pub const EXECUTABLE_PAT: Pat = Pat::POWER_ON.set_entry(1, MemoryType::WriteCombining);
+
+/// Returns the [`Pat`] in effect for an address space, given whether it [requires
+/// `EXECUTABLE_PAT`][X86TranslationScheme::pat].
+const fn pat_layout(pat: bool) -> Pat {
+    if pat { EXECUTABLE_PAT } else { Pat::POWER_ON }
+}
+
+/// Returns the [`PatSelector`] used to map memory of `mapping_type`.
+///
+/// Without [`EXECUTABLE_PAT`], [`MappingType::WriteCombining`] falls back to UC-, which still
+/// combines writes to regions that the MTRRs mark as write-combining.
+fn pat_selector(mapping_type: MappingType, pat: bool) -> PatSelector {
+    let memory_type = match mapping_type {
+        MappingType::Normal => MemoryType::WriteBack,
+        MappingType::NormalNoncacheable => MemoryType::UncacheableMinus,
+        MappingType::Device => MemoryType::Uncacheable,
+        MappingType::WriteCombining if pat => MemoryType::WriteCombining,
+        MappingType::WriteCombining => MemoryType::UncacheableMinus,
+    };
+
+    pat_layout(pat)
+        .find(memory_type)
+        .expect("memory type missing from PAT layout")
+}
+
+/// Returns the [`MappingType`] of memory mapped through `selector`.
+fn pat_mapping_type(selector: PatSelector, pat: bool) -> MappingType {
+    match pat_layout(pat).entry(selector.index()) {
+        Some(MemoryType::WriteCombining) => MappingType::WriteCombining,
+        Some(MemoryType::UncacheableMinus) => MappingType::NormalNoncacheable,
+        Some(MemoryType::Uncacheable) => MappingType::Device,
+        Some(MemoryType::WriteThrough | MemoryType::WriteProtected | MemoryType::WriteBack)
+        | None => MappingType::Normal,
+    }
+}
+
 /// Internal type.
 enum Inner {
     /// 32-bit paging.
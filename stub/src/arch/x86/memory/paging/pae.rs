@@ -12,15 +12,19 @@ use x86::{
         PagingMode, current_paging_mode, max_supported_paging_mode,
         pae::{PdpteDescriptor, TranslationDescriptor},
     },
+    pat::{PatSelector, supports_pat},
 };
 
 use crate::{
     arch::{
         generic::memory::paging::{
             ExternalFrameRange, ExternalPageRange, ExternalPhysicalAddress, ExternalVirtualAddress,
-            TranslationScheme,
+            TranslationScheme, block_fits,
+        },
+        x86::memory::{
+            paging::{pat_mapping_type, pat_selector},
+            physical_bits,
         },
-        x86::memory::physical_bits,
     },
     platform::{
         AllocationPolicy, FrameRange, MapError, MappingType, OutOfMemory, Permissions,
@@ -36,6 +40,9 @@ pub struct PaeTranslationScheme {
     physical_address: PhysicalAddress,
     /// If `true`, the `NXE` bit should be treated as being set.
     nxe: bool,
+    /// If `true`, the `IA32_PAT` MSR should be treated as containing
+    /// [`EXECUTABLE_PAT`][super::EXECUTABLE_PAT].
+    pat: bool,
 }
 
 impl PaeTranslationScheme {
@@ -58,6 +65,7 @@ impl PaeTranslationScheme {
         let mut scheme = Self {
             physical_address: PhysicalAddress::zero(),
             nxe,
+            pat: supports_pat(),
         };
 
         scheme.physical_address = scheme.allocate_zeroed_table()?.start_address();
@@ -93,6 +101,7 @@ impl PaeTranslationScheme {
         Some(Self {
             physical_address,
             nxe,
+            pat: false,
         })
     }
 
@@ -107,6 +116,12 @@ impl PaeTranslationScheme {
         self.nxe
     }
 
+    /// Returns `true` if the `IA32_PAT` MSR should be treated as containing
+    /// [`EXECUTABLE_PAT`][super::EXECUTABLE_PAT].
+    pub fn pat(&self) -> bool {
+        self.pat
+    }
+
     /// Allocates a zeroed page table.
     fn allocate_zeroed_table(&mut self) -> Option<FrameRange> {
         let range = allocate_physical(
@@ -127,8 +142,50 @@ impl PaeTranslationScheme {
         mem::forget(range);
         Some(frame_range)
     }
+
+    /// Replaces the PML2 block at `entry_address` with a page table that maps the same region
+    /// with the same attributes, returning the new table descriptor.
+    fn split_block(
+        &mut self,
+        entry_address: PhysicalAddress,
+        entry: TranslationDescriptor,
+    ) -> Result<TranslationDescriptor, OutOfMemory> {
+        let frame = self.allocate_zeroed_table().ok_or(OutOfMemory)?;
+        let table_address = frame.start_address();
+
+        for index in 0..ENTRIES {
+            let pml1e = entry
+                .set_block(false)
+                .set_page_pat(entry.block_pat())
+                .set_page_address(entry.block_address() + index * self.chunk_size());
+
+            if !write_u64_at(table_address.strict_add(index * 8), pml1e.to_bits()) {
+                panic!("failed to write to physical memory")
+            }
+        }
+
+        let pml2e = TranslationDescriptor::non_present()
+            .set_present(true)
+            .set_writable(true)
+            .set_user(entry.user())
+            .set_table_address(table_address.value());
+        if !write_u64_at(entry_address, pml2e.to_bits()) {
+            panic!("failed to write to physical memory")
+        }
+
+        Ok(pml2e)
+    }
 }
 
+/// The number of entries in each page directory and page table.
+const ENTRIES: u64 = 512;
+
+/// The size, in bytes, of the region mapped by a PML2 block.
+const PML2_BLOCK_SIZE: u64 = 512 * 4096;
+
+/// The size, in bytes, of the region mapped by a PDPTE.
+const PDPTE_SIZE: u64 = 512 * PML2_BLOCK_SIZE;
+
 impl TranslationScheme for PaeTranslationScheme {
     fn input_descriptor(&self) -> AddressSpaceDescriptor {
         AddressSpaceDescriptor::new(32, false)
@@ -142,6 +199,10 @@ impl TranslationScheme for PaeTranslationScheme {
         4096
     }
 
+    fn supports_mapping_type(&self, mapping_type: MappingType) -> bool {
+        mapping_type != MappingType::WriteCombining || self.pat
+    }
+
     fn map_at(
         &mut self,
         input: ExternalPageRange,
@@ -149,10 +210,6 @@ impl TranslationScheme for PaeTranslationScheme {
         permissions: Permissions,
         mapping_type: MappingType,
     ) -> Result<(), MapError> {
-        if mapping_type != MappingType::Normal {
-            todo!("implement cacheability modifiers")
-        }
-
         let input_range = input.address_range(self.chunk_size());
         if !self.input_descriptor().is_valid_range(
             input_range.start().value(),
@@ -183,8 +240,23 @@ impl TranslationScheme for PaeTranslationScheme {
             }
         }
 
-        for (input_chunk, output_chunk) in input.iter().zip(output.iter()) {
-            let address = input_chunk.start_address(self.chunk_size()).value();
+        let writable = permissions.writable();
+        let xd = self.nxe && !permissions.executable();
+        let selector = pat_selector(mapping_type, self.pat);
+
+        let mut index = 0;
+        while index < input.count() {
+            let address = input
+                .start()
+                .strict_add(index)
+                .start_address(self.chunk_size())
+                .value();
+            let output_address = output
+                .start()
+                .strict_add(index)
+                .start_address(self.chunk_size())
+                .value();
+            let remaining = (input.count() - index) * self.chunk_size();
 
             let pdpte_index = (address >> 30) & 0b11;
             let pml2e_index = (address >> 21) & 0x1FF;
@@ -216,6 +288,25 @@ impl TranslationScheme for PaeTranslationScheme {
                 let pml2e_value = read_u64_at(pml2e_address).expect("failed to read PML2E");
                 let mut pml2e = TranslationDescriptor::from_bits(pml2e_value);
                 if !pml2e.present() {
+                    if block_fits(address, output_address, remaining, PML2_BLOCK_SIZE) {
+                        pml2e = TranslationDescriptor::non_present()
+                            .set_present(true)
+                            .set_writable(writable)
+                            .set_pwt(selector.pwt)
+                            .set_pcd(selector.pcd)
+                            .set_block(true)
+                            .set_block_pat(selector.pat)
+                            .set_block_address(output_address)
+                            .set_xd(xd);
+
+                        if !write_u64_at(pml2e_address, pml2e.to_bits()) {
+                            panic!("failed to write to physical memory")
+                        }
+
+                        index += PML2_BLOCK_SIZE / self.chunk_size();
+                        continue;
+                    }
+
                     let Some(frame) = self.allocate_zeroed_table() else {
                         return Err(MapError::FrameAllocation(OutOfMemory));
                     };
@@ -229,38 +320,53 @@ impl TranslationScheme for PaeTranslationScheme {
                         panic!("failed to write to physical memory")
                     }
                 } else if pml2e.block() {
-                    todo!("implement block page splitting")
+                    pml2e = self
+                        .split_block(pml2e_address, pml2e)
+                        .map_err(MapError::FrameAllocation)?;
                 }
 
                 PhysicalAddress::new(pml2e.table_address())
             };
 
-            let writable = permissions.writable();
-            let xd = self.nxe && !permissions.executable();
-
             let pml1e_address = pml1_table_address.strict_add(pml1e_index * 8);
             let pml1e = TranslationDescriptor::non_present()
                 .set_present(true)
                 .set_writable(writable)
-                .set_page_address(output_chunk.start_address(self.chunk_size()).value())
+                .set_pwt(selector.pwt)
+                .set_pcd(selector.pcd)
+                .set_page_pat(selector.pat)
+                .set_page_address(output_address)
                 .set_xd(xd);
 
             if !write_u64_at(pml1e_address, pml1e.to_bits()) {
                 panic!("failed to write to physical memory")
             }
+
+            index += 1;
         }
 
         Ok(())
     }
 
-    unsafe fn unmap(&mut self, input: ExternalPageRange) {
+    unsafe fn unmap(&mut self, input: ExternalPageRange) -> Result<(), OutOfMemory> {
         assert!(self.input_descriptor().is_valid_range(
             input.start_address(self.chunk_size()).value(),
             input.end_address_inclusive(self.chunk_size()).value()
         ));
 
-        for page in input.iter() {
-            let address = page.start_address(self.chunk_size()).value();
+        let mut index = 0;
+        while index < input.count() {
+            let address = input
+                .start()
+                .strict_add(index)
+                .start_address(self.chunk_size())
+                .value();
+            let remaining = (input.count() - index) * self.chunk_size();
+            let chunk_size = self.chunk_size();
+            // The number of chunks from `address` to the end of the region mapped by an entry of
+            // `size` bytes, limited to the end of `input`.
+            let skip =
+                |size: u64| ((size - address % size) / chunk_size).min(remaining / chunk_size);
 
             let pdpte_index = (address >> 30) & 0b11;
             let pml2e_index = (address >> 21) & 0x1FF;
@@ -271,6 +377,7 @@ impl TranslationScheme for PaeTranslationScheme {
                 let pdpte_value = read_u64_at(pdpte_address).expect("failed to read PDPTE");
                 let pdpte = PdpteDescriptor::from_bits(pdpte_value);
                 if !pdpte.present() {
+                    index += skip(PDPTE_SIZE);
                     continue;
                 }
 
@@ -279,12 +386,25 @@ impl TranslationScheme for PaeTranslationScheme {
 
             let pml1_table_address = {
                 let pml2e_address = pml2_table_address.strict_add(pml2e_index * 8);
-                let pml2e_value = read_u64_at(pml2e_address).expect("failed to read PDPTE");
-                let pml2e = TranslationDescriptor::from_bits(pml2e_value);
+                let pml2e_value = read_u64_at(pml2e_address).expect("failed to read PML2E");
+                let mut pml2e = TranslationDescriptor::from_bits(pml2e_value);
                 if !pml2e.present() {
+                    index += skip(PML2_BLOCK_SIZE);
                     continue;
                 } else if pml2e.block() {
-                    todo!("implement block page splitting")
+                    if block_fits(address, 0, remaining, PML2_BLOCK_SIZE) {
+                        if !write_u64_at(
+                            pml2e_address,
+                            TranslationDescriptor::non_present().to_bits(),
+                        ) {
+                            panic!("failed to write to physical memory")
+                        }
+
+                        index += PML2_BLOCK_SIZE / self.chunk_size();
+                        continue;
+                    }
+
+                    pml2e = self.split_block(pml2e_address, pml2e)?;
                 }
 
                 PhysicalAddress::new(pml2e.table_address())
@@ -296,7 +416,11 @@ impl TranslationScheme for PaeTranslationScheme {
             if !write_u64_at(pml1e_address, pml1e.to_bits()) {
                 panic!("failed to write to physical memory")
             }
+
+            index += 1;
         }
+
+        Ok(())
     }
 
     fn translate(
@@ -344,8 +468,12 @@ impl TranslationScheme for PaeTranslationScheme {
                     (false, true) => Permissions::ReadExecute,
                     (false, false) => Permissions::Read,
                 };
-                // TODO: Fix mapping type computation.
-                let mapping_type = MappingType::Normal;
+                let selector = PatSelector {
+                    pwt: pml2e.pwt(),
+                    pcd: pml2e.pcd(),
+                    pat: pml2e.block_pat(),
+                };
+                let mapping_type = pat_mapping_type(selector, self.pat);
                 let address =
                     ExternalPhysicalAddress::new(pml2e.block_address()).strict_add(offset);
 
@@ -375,10 +503,118 @@ impl TranslationScheme for PaeTranslationScheme {
             (false, true) => Permissions::ReadExecute,
             (false, false) => Permissions::Read,
         };
-        // TODO: Fix mapping type computation.
-        let mapping_type = MappingType::Normal;
+        let selector = PatSelector {
+            pwt: pml1e.pwt(),
+            pcd: pml1e.pcd(),
+            pat: pml1e.page_pat(),
+        };
+        let mapping_type = pat_mapping_type(selector, self.pat);
         let address = ExternalPhysicalAddress::new(pml1e.page_address()).strict_add(offset);
 
         Some((permissions, mapping_type, address))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{PML2_BLOCK_SIZE, PaeTranslationScheme, PdpteDescriptor, TranslationDescriptor};
+    use crate::{
+        arch::generic::memory::paging::test_util::{map, translate, unmap},
+        platform::{PhysicalAddress, mock, read_u64_at},
+    };
+
+    /// The size of a page.
+    const PAGE: u64 = 4096;
+    /// An input address that is aligned to every block size.
+    const INPUT: u64 = 0x4000_0000;
+    /// An output address that is aligned to every block size.
+    const OUTPUT: u64 = 0x8000_0000;
+
+    /// Creates a new [`PaeTranslationScheme`].
+    fn scheme() -> PaeTranslationScheme {
+        PaeTranslationScheme::max_supported().expect("PAE paging is unsupported")
+    }
+
+    /// Returns the size of the region mapped by the entry that translates `address`.
+    fn mapping_size(scheme: &PaeTranslationScheme, address: u64) -> Option<u64> {
+        let entry = |table: PhysicalAddress, shift: u64| {
+            let entry_address = table.strict_add(((address >> shift) & 0x1FF) * 8);
+            let entry = TranslationDescriptor::from_bits(read_u64_at(entry_address)?);
+            entry.present().then_some(entry)
+        };
+
+        let pdpte_address = scheme
+            .physical_address
+            .strict_add(((address >> 30) & 0b11) * 8);
+        let pdpte = PdpteDescriptor::from_bits(read_u64_at(pdpte_address)?);
+        if !pdpte.present() {
+            return None;
+        }
+
+        let pml2e = entry(PhysicalAddress::new(pdpte.address()), 21)?;
+        if pml2e.block() {
+            return Some(PML2_BLOCK_SIZE);
+        }
+
+        entry(PhysicalAddress::new(pml2e.table_address()), 12).map(|_| PAGE)
+    }
+
+    #[test]
+    fn map_uses_blocks_when_aligned() {
+        let _guard = mock::acquire();
+        let mut scheme = scheme();
+
+        let size = PML2_BLOCK_SIZE + PAGE;
+        map(&mut scheme, INPUT, OUTPUT, size);
+
+        assert_eq!(mapping_size(&scheme, INPUT), Some(PML2_BLOCK_SIZE));
+        assert_eq!(mapping_size(&scheme, INPUT + PML2_BLOCK_SIZE), Some(PAGE));
+        for offset in [0, PML2_BLOCK_SIZE - PAGE, PML2_BLOCK_SIZE] {
+            assert_eq!(translate(&scheme, INPUT + offset), Some(OUTPUT + offset));
+        }
+        assert_eq!(translate(&scheme, INPUT + size), None);
+    }
+
+    #[test]
+    fn map_uses_pages_when_misaligned() {
+        let _guard = mock::acquire();
+        let mut scheme = scheme();
+
+        map(&mut scheme, INPUT, OUTPUT + PAGE, PML2_BLOCK_SIZE);
+
+        assert_eq!(mapping_size(&scheme, INPUT), Some(PAGE));
+        assert_eq!(translate(&scheme, INPUT), Some(OUTPUT + PAGE));
+        assert_eq!(
+            translate(&scheme, INPUT + PML2_BLOCK_SIZE - PAGE),
+            Some(OUTPUT + PML2_BLOCK_SIZE)
+        );
+    }
+
+    #[test]
+    fn unmap_splits_2mib_block() {
+        let _guard = mock::acquire();
+        let mut scheme = scheme();
+
+        map(&mut scheme, INPUT, OUTPUT, PML2_BLOCK_SIZE);
+        let hole = INPUT + PAGE;
+        unmap(&mut scheme, hole, PAGE);
+
+        assert_eq!(translate(&scheme, hole), None);
+        assert_eq!(mapping_size(&scheme, INPUT), Some(PAGE));
+        for offset in [0, 2 * PAGE, PML2_BLOCK_SIZE - PAGE] {
+            assert_eq!(translate(&scheme, INPUT + offset), Some(OUTPUT + offset));
+        }
+    }
+
+    #[test]
+    fn unmap_removes_whole_blocks() {
+        let _guard = mock::acquire();
+        let mut scheme = scheme();
+
+        map(&mut scheme, INPUT, OUTPUT, PML2_BLOCK_SIZE);
+        unmap(&mut scheme, INPUT, PML2_BLOCK_SIZE);
+
+        assert_eq!(mapping_size(&scheme, INPUT), None);
+        assert_eq!(translate(&scheme, INPUT + PML2_BLOCK_SIZE - PAGE), None);
+    }
+}
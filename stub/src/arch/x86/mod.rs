@@ -1,65 +1,106 @@
 //! Structures and functionality that are shared between `i686` and `x86_64`.
 
-use x86::paging::{PagingMode, current_paging_mode};
-
-use crate::arch::x86::switch::TablePointer;
+use x86::{
+    PrivilegeLevel,
+    cpuid::{cpuid_unchecked, supports_cpuid},
+    paging::{PagingMode, current_paging_mode},
+    segmentation::{
+        DescriptorTablePointer, SegmentDescriptor, SegmentSelector, load_cs, load_data_segments,
+        load_gdt as load_gdtr,
+    },
+    tsc::{read_tsc, supports_tsc},
+};
 
+pub mod exception;
 pub mod memory;
 pub mod relocation;
 pub mod switch;
 
+/// The GDT used by the stub and by the executable.
+pub const GDT: [SegmentDescriptor; 5] = [
+    SegmentDescriptor::null(),
+    SegmentDescriptor::code_32(),
+    SegmentDescriptor::data_32(),
+    SegmentDescriptor::code_64(),
+    SegmentDescriptor::data_64(),
+];
+
+/// The selector of the 32-bit code segment in [`GDT`].
+pub const CODE_32_SELECTOR: SegmentSelector = SegmentSelector::new(1, PrivilegeLevel::Ring0);
+/// The selector of the 32-bit data segment in [`GDT`].
+pub const DATA_32_SELECTOR: SegmentSelector = SegmentSelector::new(2, PrivilegeLevel::Ring0);
+/// The selector of the 64-bit code segment in [`GDT`].
+pub const CODE_64_SELECTOR: SegmentSelector = SegmentSelector::new(3, PrivilegeLevel::Ring0);
+/// The selector of the 64-bit data segment in [`GDT`].
+pub const DATA_64_SELECTOR: SegmentSelector = SegmentSelector::new(4, PrivilegeLevel::Ring0);
+
 /// Loads in a default GDT.
 ///
 /// # Safety
 ///
 /// It must not violate system invariants to change the GDT or segmentation registers.
 pub unsafe fn load_gdt() {
-    static GDT: [u64; 5] = [
-        0x0000_0000_0000_0000, // Null Segment
-        0x00CF_9B00_0000_FFFF, // Kernel 32-bit code segment
-        0x00CF_9300_0000_FFFF, // Kernel 32-bit data segment
-        0x00AF_9B00_0000_FFFF, // Kernel 64-bit code segment
-        0x00CF_9300_0000_FFFF, // Kernel 64-bit data segment
-    ];
+    static STUB_GDT: [SegmentDescriptor; 5] = GDT;
 
     let (code_segment, data_segment) = match current_paging_mode() {
-        PagingMode::Disabled | PagingMode::Bits32 | PagingMode::Pae => (8usize, 16usize),
-        PagingMode::Level4 | PagingMode::Level5 => (24, 32),
-    };
-
-    let gdtr = TablePointer {
-        size: 8 * 5,
-        pointer: &raw const GDT as u64,
+        PagingMode::Disabled | PagingMode::Bits32 | PagingMode::Pae => {
+            (CODE_32_SELECTOR, DATA_32_SELECTOR)
+        }
+        PagingMode::Level4 | PagingMode::Level5 => (CODE_64_SELECTOR, DATA_64_SELECTOR),
     };
 
     // SAFETY:
     //
-    // The invariants of [`load_gdt()`] ensure that this operation is safe.
-    unsafe {
-        core::arch::asm!(
-            "lgdt [{gdtr}]",
-            "mov ds, {data:x}",
-            "mov es, {data:x}",
-            "mov fs, {data:x}",
-            "mov gs, {data:x}",
-            "mov ss, {data:x}",
-
-            "push {code}",
+    // `STUB_GDT` is stored in a static and the invariants of [`load_gdt()`] ensure that changing
+    // the GDT is safe.
+    unsafe { load_gdtr(&DescriptorTablePointer::new(&STUB_GDT)) }
+    // SAFETY:
+    //
+    // The data segment is valid in the current operating mode and the invariants of
+    // [`load_gdt()`] ensure that changing the segmentation registers is safe.
+    unsafe { load_data_segments(data_segment) }
+    // SAFETY:
+    //
+    // The code segment is valid in the current operating mode and the invariants of
+    // [`load_gdt()`] ensure that changing the segmentation registers is safe.
+    unsafe { load_cs(code_segment) }
+}
 
-            "call 99f",
-            "99:",
+/// Returns the x2APIC ID of the current processor, or its initial APIC ID if the x2APIC ID is
+/// unavailable.
+pub fn current_hardware_id() -> u64 {
+    if !supports_cpuid() {
+        return 0;
+    }
 
-            ".equ call_offset, 99f - 99b",
-            "pop {tmp}",
-            "add {tmp}, offset call_offset",
+    // SAFETY:
+    //
+    // The CPUID instruction is supported.
+    let max_leaf = unsafe { cpuid_unchecked(0, 0).eax };
+    if max_leaf >= 0xB {
+        // SAFETY:
+        //
+        // The CPUID instruction is supported and leaf 0xB is within the supported range.
+        let extended_topology = unsafe { cpuid_unchecked(0xB, 0) };
+        if extended_topology.ebx != 0 {
+            return u64::from(extended_topology.edx);
+        }
+    }
 
-            "push {tmp}",
+    // SAFETY:
+    //
+    // The CPUID instruction is supported and leaf 0x1 is always available.
+    u64::from(unsafe { cpuid_unchecked(0x1, 0).ebx } >> 24)
+}
 
-            #[cfg(target_arch = "x86")]
-            "retf",
-            #[cfg(target_arch = "x86_64")]
-            "retfq",
-            "99:",
-            gdtr = inout(reg) &raw const gdtr => _, code = inout(reg) code_segment => _, data = in(reg) data_segment, tmp = lateout(reg) _)
+/// Returns the current value of the time stamp counter, or zero if it is not supported.
+pub fn read_counter() -> u64 {
+    if !supports_tsc() {
+        return 0;
     }
+
+    // SAFETY:
+    //
+    // The RDTSC instruction is supported and the stub runs at the highest privilege level.
+    unsafe { read_tsc() }
 }
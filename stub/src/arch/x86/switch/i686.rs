@@ -23,7 +23,7 @@ use crate::{
         x86::switch::{ArchCodeLayout, CallStorage, CpuStorage, ModeStorage, arch_policy},
     },
     platform::{
-        Permissions, allocate_frames_aligned, frame_size, map_identity, write_bytes_at,
+        MemoryType, Permissions, allocate_frames_aligned, frame_size, map_identity, write_bytes_at,
         write_u64_at,
     },
 };
@@ -44,6 +44,7 @@ pub fn allocate_code(
         code_size_u64.div_ceil(frame_size()),
         scheme.chunk_size(),
         arch_policy(),
+        MemoryType::BootloaderReclaimable,
     )?;
 
     // Map code region into the two address spaces.
@@ -259,6 +260,20 @@ core::arch::global_asm! {
 
     "5:",
 
+    "call get_base",
+    "mov ecx, [ecx]",
+    "mov bl, [ecx + {STORAGE_CHANGE_PAT}]",
+    "cmp bl, 0",
+    "je 5f",
+
+    "mov ecx, 0x277",
+    "rdmsr",
+
+    "mov [ebp + {MODE_STORAGE_PAT}], eax",
+    "mov [ebp + {MODE_STORAGE_PAT} + 4], edx",
+
+    "5:",
+
     "call get_base",
     "mov esp, [ecx + other_mode_i686_offset]",
     "mov ebp, [ecx + own_mode_i686_offset]",
@@ -291,6 +306,17 @@ core::arch::global_asm! {
 
     "5:",
 
+    "mov al, [edi + {STORAGE_CHANGE_PAT}]",
+    "cmp al, 0",
+    "je 5f",
+
+    "mov eax, [esp + {MODE_STORAGE_PAT}]",
+    "mov edx, [esp + {MODE_STORAGE_PAT} + 4]",
+    "mov ecx, 0x277",
+    "wrmsr",
+
+    "5:",
+
     // Load control registers.
     "mov ecx, [esp + {MODE_STORAGE_CR3}]",
     "mov cr3, ecx",
@@ -759,6 +785,7 @@ core::arch::global_asm! {
     CALL_STORAGE_OFFSET = const { offset_of!(CpuStorage, call) },
 
     STORAGE_CHANGE_EFER = const { offset_of!(CpuStorage, change_efer) },
+    STORAGE_CHANGE_PAT = const { offset_of!(CpuStorage, change_pat) },
 
     CALL_STORAGE_FUNC_ID_OFFSET = const { offset_of!(CallStorage, func_id) },
     CALL_STORAGE_ARG_COUNT_OFFSET = const { offset_of!(CallStorage, arg_count) },
@@ -797,4 +824,5 @@ core::arch::global_asm! {
     MODE_STORAGE_IDTR = const { offset_of!(ModeStorage, idtr) },
 
     MODE_STORAGE_EFER = const { offset_of!(ModeStorage, efer) },
+    MODE_STORAGE_PAT = const { offset_of!(ModeStorage, pat) },
 }
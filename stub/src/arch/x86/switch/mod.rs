@@ -3,13 +3,14 @@
 
 use core::mem;
 
-use conversion::{u64_to_usize_strict, usize_to_u64};
+use conversion::{u64_to_usize_strict, usize_to_u16_strict, usize_to_u64};
 use stub_api::{i686::I686Table, x86_64::X86_64Table};
 use sync::Spinlock;
 use x86::{
     control::{Cr0, Cr4},
     cpuid::{cpuid_unchecked, supports_cpuid},
     paging::{PagingMode, current_paging_mode},
+    segmentation::{DescriptorTablePointer, SegmentDescriptor},
 };
 
 use crate::{
@@ -25,6 +26,7 @@ use crate::{
             },
         },
         memory::ArchTranslationScheme,
+        x86::{GDT, exception::load_exception_handlers, memory::paging::EXECUTABLE_PAT},
     },
     platform::{
         AllocationPolicy, PhysicalAddress, device_tree, main_processor_id, rsdp,
@@ -132,6 +134,7 @@ pub fn base_cpu_storage(scheme: &mut ArchTranslationScheme) -> CpuStorage {
             cr4,
 
             efer,
+            pat: EXECUTABLE_PAT.0,
             ..Default::default()
         },
         change_efer: if supports_cpuid() {
@@ -144,13 +147,8 @@ pub fn base_cpu_storage(scheme: &mut ArchTranslationScheme) -> CpuStorage {
         } else {
             0
         },
-        gdt: [
-            0x0000_0000_0000_0000, // Null Segment
-            0x00CF_9B00_0000_FFFF, // Kernel 32-bit code segment
-            0x00CF_9300_0000_FFFF, // Kernel 32-bit data segment
-            0x00AF_9B00_0000_FFFF, // Kernel 64-bit code segment
-            0x00CF_9300_0000_FFFF, // Kernel 64-bit data segment
-        ],
+        change_pat: u8::from(scheme.pat()),
+        gdt: GDT,
     }
 }
 
@@ -217,6 +215,10 @@ type ExecAllData<'a> = (&'a [Spinlock<CpuData>], u64, u64);
 
 /// Executes the provided function on the provided processor.
 extern "C" fn exec_all(cpu_id: u64, arg: *mut ()) {
+    // Processors other than the main processor have not loaded the stub's exception handlers, and
+    // the handlers loaded here are restored whenever the executable calls back into the stub.
+    load_exception_handlers();
+
     let arg = arg.cast::<ExecAllData>();
     // SAFETY:
     //
@@ -358,15 +360,16 @@ pub fn handle_stack_allocation(storage: &mut CpuStorage, stack_top: u64) {
 /// Adjusts the provided [`CpuStorage`] to utilize the newly allocated space for the [`CpuStorage`]
 /// at `storage_base`.
 pub fn handle_storage_allocation(storage: &mut CpuStorage, storage_base: u64) {
-    storage.executable.gdtr.size = 5 * 8 - 1;
-    storage.executable.gdtr.pointer =
-        storage_base.strict_add(usize_to_u64(mem::offset_of!(CpuStorage, gdt)));
+    storage.executable.gdtr = DescriptorTablePointer {
+        limit: usize_to_u16_strict(mem::size_of_val(&storage.gdt) - 1),
+        base: storage_base.strict_add(usize_to_u64(mem::offset_of!(CpuStorage, gdt))),
+    };
 }
 
 /// Writes the finished 32-bit protocol table at the provided `address`.
 pub fn write_protocol_table_32(table: RevmProtocolTable32<()>, address: PhysicalAddress) {
     let arch_table = I686Table {
-        version: X86_64Table::VERSION,
+        version: I686Table::VERSION,
 
         uefi_system_table: uefi_system_table().map(|addr| addr.value()).unwrap_or(0),
         rsdp: rsdp().map(|addr| addr.value()).unwrap_or(0),
@@ -434,9 +437,11 @@ pub struct CpuStorage {
 
     /// If non-zero, then the `EFER` MSR should be changed.
     change_efer: u8,
+    /// If non-zero, then the `IA32_PAT` MSR should be changed.
+    change_pat: u8,
 
     /// A hard-coded GDT used for the executable and switching code.
-    gdt: [u64; 5],
+    gdt: [SegmentDescriptor; 5],
 }
 
 /// Storage used for cross address space function calls.
@@ -529,23 +534,15 @@ struct ModeStorage {
     cr4: u64,
 
     /// The stored value of the `IDTR`.
-    gdtr: TablePointer,
+    gdtr: DescriptorTablePointer,
     /// The stored value of the `IDTR`.
-    idtr: TablePointer,
+    idtr: DescriptorTablePointer,
 
     /// The stored value of the `IA32_EFER` MSR.
     efer: u64,
+    /// The stored value of the `IA32_PAT` MSR.
+    pat: u64,
 
     /// Temporary storage used for switching.
     tmp_storage: [u64; 5],
 }
-
-/// A packed representation of a `GDTR` or `IDTR`.
-#[repr(C, packed)]
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-pub struct TablePointer {
-    /// The size, in bytes, of the table.
-    pub size: u16,
-    /// The linear address of the table.
-    pub pointer: u64,
-}
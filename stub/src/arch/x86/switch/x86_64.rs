@@ -23,7 +23,7 @@ use crate::{
         x86::switch::{ArchCodeLayout, CallStorage, CpuStorage, ModeStorage, arch_policy},
     },
     platform::{
-        Permissions, allocate_frames_aligned, frame_size, map_identity, write_bytes_at,
+        MemoryType, Permissions, allocate_frames_aligned, frame_size, map_identity, write_bytes_at,
         write_u64_at,
     },
 };
@@ -44,6 +44,7 @@ pub fn allocate_code(
         code_size_u64.div_ceil(frame_size()),
         scheme.chunk_size(),
         arch_policy(),
+        MemoryType::BootloaderReclaimable,
     )?;
 
     // Map code region into the two address spaces.
@@ -308,7 +309,7 @@ global_asm! {
     "sidt [rax + {MODE_STORAGE_IDTR}]",
 
     "mov rbx, [rip + storage_pointer]",
-    "mov bl, [rip + {STORAGE_CHANGE_EFER}]",
+    "mov bl, [rbx + {STORAGE_CHANGE_EFER}]",
     "cmp bl, 0",
     "je 5f",
 
@@ -321,6 +322,20 @@ global_asm! {
 
     "5:",
 
+    "mov rbx, [rip + storage_pointer]",
+    "mov bl, [rbx + {STORAGE_CHANGE_PAT}]",
+    "cmp bl, 0",
+    "je 5f",
+
+    "mov ecx, 0x277",
+    "rdmsr",
+
+    "mov rbx, [rip + own_mode_storage_pointer]",
+    "mov [rbx + {MODE_STORAGE_PAT}], eax",
+    "mov [rbx + {MODE_STORAGE_PAT} + 4], edx",
+
+    "5:",
+
     // All relevant registers have been stored; perform the switching now.
 
     // Load storage pointer.
@@ -415,6 +430,17 @@ global_asm! {
 
     "5:",
 
+    "mov al, [edi + {STORAGE_CHANGE_PAT}]",
+    "cmp al, 0",
+    "je 5f",
+
+    "mov eax, [esp + {MODE_STORAGE_PAT}]",
+    "mov edx, [esp + {MODE_STORAGE_PAT} + 4]",
+    "mov ecx, 0x277",
+    "wrmsr",
+
+    "5:",
+
     // Load control registers (this configures the CPU for long mode).
     "mov ecx, [esp + {MODE_STORAGE_CR3}]",
     "mov cr3, ecx",
@@ -695,6 +721,7 @@ global_asm! {
     CALL_STORAGE_OFFSET = const { offset_of!(CpuStorage, call) },
 
     STORAGE_CHANGE_EFER = const { offset_of!(CpuStorage, change_efer) },
+    STORAGE_CHANGE_PAT = const { offset_of!(CpuStorage, change_pat) },
     STORAGE_GDT = const { offset_of!(CpuStorage, gdt) },
 
     // [`CallStorage`] layout.
@@ -745,6 +772,7 @@ global_asm! {
     MODE_STORAGE_IDTR = const { offset_of!(ModeStorage, idtr) },
 
     MODE_STORAGE_EFER = const { offset_of!(ModeStorage, efer) },
+    MODE_STORAGE_PAT = const { offset_of!(ModeStorage, pat) },
 
     MODE_STORAGE_TMP_STORAGE = const { offset_of!(ModeStorage, tmp_storage) },
 }
@@ -18,8 +18,9 @@ use crate::{
     },
     executable::{elf::ParsedElf, layout::Layout},
     platform::{
-        AllocationPolicy, FrameAllocation, MapError, MappingType, OutOfMemory, Permissions,
-        PhysicalAddress, allocate_frames_aligned, frame_size, write_bytes_at, write_u8_at,
+        AllocationPolicy, FrameAllocation, MapError, MappingType, MemoryType, OutOfMemory,
+        Permissions, PhysicalAddress, allocate_frames_aligned, frame_size, write_bytes_at,
+        write_u8_at,
     },
 };
 
@@ -44,6 +45,7 @@ pub fn map_segments<T: TranslationScheme>(
         frame_count,
         scheme.chunk_size(),
         AllocationPolicy::InclusiveMax(scheme.output_descriptor().valid_ranges()[0].1),
+        MemoryType::Executable,
     )?;
 
     let mut frame_index = 0;
@@ -13,14 +13,17 @@ use crate::{
 
 pub mod arch;
 pub mod executable;
-pub mod initgraph;
 pub mod platform;
 pub mod util;
 
+// The items below are only reachable from the platform entry points, which host tests do not use.
+
 /// The platform-specific panic handler function.
+#[cfg_attr(test, allow(dead_code))]
 static PANIC_HANDLER: Spinlock<fn(&core::panic::PanicInfo) -> !> = Spinlock::new(fallback);
 
 /// Entry point used after all boot protocol and architecture specific code has been run.
+#[cfg_attr(test, allow(dead_code))]
 fn stub_main(executable_command_line: &str) -> Result<(), StubError> {
     let (scheme, entry_point, image_allocation, slide) = executable::load()?;
     crate::debug!("Executable Entry Point: {entry_point:#x}");
@@ -39,6 +42,7 @@ fn stub_main(executable_command_line: &str) -> Result<(), StubError> {
 
 /// Various errors that can occur in the architecture-independent phase.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(test, allow(dead_code))]
 enum StubError {
     /// An error occurred while loading the executable.
     LoadExecutableError(LoadExecutableError),
@@ -77,6 +81,7 @@ fn panic_handler(info: &core::panic::PanicInfo) -> ! {
 }
 
 /// The panic handler function utilized if no other panic handler is assigned.
+#[cfg_attr(test, allow(dead_code))]
 fn fallback(_: &core::panic::PanicInfo) -> ! {
     loop {
         core::hint::spin_loop()
@@ -10,9 +10,9 @@ use conversion::{u64_to_usize_strict, usize_to_u64};
 use sync::Spinlock;
 
 use crate::platform::{
-    AllocationPolicy, BufferTooSmall, Frame, FrameRange, MemoryDescriptor, MemoryMap, MemoryType,
-    OutOfMemory, PhysicalAddress, PhysicalAddressRange, frame_size, read_u8_at, read_u64_at,
-    write_u8_at, write_u64_at,
+    AllocationPolicy, BufferTooSmall, Frame, FrameRange, MemoryAttributes, MemoryDescriptor,
+    MemoryMap, MemoryType, OutOfMemory, PhysicalAddress, PhysicalAddressRange, frame_size,
+    read_u8_at, read_u64_at, write_u8_at, write_u64_at,
 };
 
 /// The link value indicating that the linked list has ended.
@@ -24,6 +24,9 @@ const DESCRIPTOR_SIZE: u64 = usize_to_u64(mem::size_of::<MemoryDescriptor>());
 static FRAME_ALLOCATOR: Spinlock<FrameAllocator> = Spinlock::new(FrameAllocator::new());
 
 /// Initializes the system [`FrameAllocator`].
+///
+/// Non-free [`MemoryDescriptor`]s without any [`MemoryAttributes`] inherit the attributes of the
+/// free memory in which they start.
 pub fn initialize<I: Iterator<Item = MemoryDescriptor> + Clone>(iter: I) {
     let mut frame_allocator = FRAME_ALLOCATOR.lock();
 
@@ -57,10 +60,14 @@ pub fn initialize<I: Iterator<Item = MemoryDescriptor> + Clone>(iter: I) {
         frame_allocator.validate_order();
     }
 
-    for descriptor in iter
+    for mut descriptor in iter
         .clone()
         .filter(|descriptor| descriptor.region_type != MemoryType::Free)
     {
+        if descriptor.attributes == MemoryAttributes::NONE {
+            descriptor.attributes = frame_allocator.attributes_at(descriptor.range.start());
+        }
+
         crate::trace!(
             "inserting {:?} {:?}",
             descriptor.range,
@@ -89,6 +96,7 @@ pub fn initialize<I: Iterator<Item = MemoryDescriptor> + Clone>(iter: I) {
             let descriptor = MemoryDescriptor {
                 range: PhysicalAddressRange::new(link, frame_size()),
                 region_type: MemoryType::BootloaderReclaimable,
+                attributes: frame_allocator.attributes_at(link),
             };
 
             crate::trace!(
@@ -124,7 +132,11 @@ pub fn initialize<I: Iterator<Item = MemoryDescriptor> + Clone>(iter: I) {
 }
 
 /// Implementation of [`crate::platform::allocate_frames()`] using the system [`FrameAllocator`].
-pub fn allocate_frames(count: u64, policy: AllocationPolicy) -> Result<FrameRange, OutOfMemory> {
+pub fn allocate_frames(
+    count: u64,
+    policy: AllocationPolicy,
+    region_type: MemoryType,
+) -> Result<FrameRange, OutOfMemory> {
     let mut allocator = FRAME_ALLOCATOR.lock();
 
     macro_rules! base_iter {
@@ -138,7 +150,7 @@ pub fn allocate_frames(count: u64, policy: AllocationPolicy) -> Result<FrameRang
                         let count = descriptor.range.count().saturating_sub(frame_size());
                         MemoryDescriptor {
                             range: PhysicalAddressRange::new(start, count),
-                            region_type: descriptor.region_type,
+                            ..descriptor
                         }
                     } else {
                         descriptor
@@ -153,7 +165,7 @@ pub fn allocate_frames(count: u64, policy: AllocationPolicy) -> Result<FrameRang
                     );
                     MemoryDescriptor {
                         range,
-                        region_type: descriptor.region_type,
+                        ..descriptor
                     }
                 })
                 .filter(|descriptor| descriptor.range.count() / frame_size() >= count)
@@ -168,7 +180,8 @@ pub fn allocate_frames(count: u64, policy: AllocationPolicy) -> Result<FrameRang
 
             let descriptor = MemoryDescriptor {
                 range: frame_range.address_range(),
-                region_type: MemoryType::BootloaderReclaimable,
+                region_type,
+                attributes: descriptor.attributes,
             };
             match allocator.try_insert_region(descriptor) {
                 Ok(()) => break Ok(frame_range),
@@ -182,19 +195,20 @@ pub fn allocate_frames(count: u64, policy: AllocationPolicy) -> Result<FrameRang
                 count,
             );
 
-            let free_range = base_iter!().any(|descriptor| {
+            let free_descriptor = base_iter!().find(|descriptor| {
                 range
                     .address_range()
                     .intersection(descriptor.range)
                     .is_some_and(|intersection| intersection == range.address_range())
             });
-            if !free_range {
+            let Some(free_descriptor) = free_descriptor else {
                 return Err(OutOfMemory);
-            }
+            };
 
             let descriptor = MemoryDescriptor {
                 range: range.address_range(),
-                region_type: MemoryType::BootloaderReclaimable,
+                region_type,
+                attributes: free_descriptor.attributes,
             };
             match allocator.try_insert_region(descriptor) {
                 Ok(()) => break Ok(range),
@@ -203,8 +217,14 @@ pub fn allocate_frames(count: u64, policy: AllocationPolicy) -> Result<FrameRang
         },
         AllocationPolicy::InclusiveMax(address) => loop {
             let descriptor = base_iter!()
-                .filter(|&descriptor| descriptor.range.end_exclusive().value() <= address)
-                .next()
+                .find(|descriptor| {
+                    // Descriptors whose last frame cannot be represented lie above `address`.
+                    count
+                        .checked_mul(frame_size())
+                        .and_then(|size| size.checked_sub(1))
+                        .and_then(|size| descriptor.range.start().value().checked_add(size))
+                        .is_some_and(|last| last <= address)
+                })
                 .ok_or(OutOfMemory)?;
 
             let frame_range =
@@ -212,7 +232,8 @@ pub fn allocate_frames(count: u64, policy: AllocationPolicy) -> Result<FrameRang
 
             let descriptor = MemoryDescriptor {
                 range: frame_range.address_range(),
-                region_type: MemoryType::BootloaderReclaimable,
+                region_type,
+                attributes: descriptor.attributes,
             };
             match allocator.try_insert_region(descriptor) {
                 Ok(()) => break Ok(frame_range),
@@ -232,6 +253,7 @@ pub unsafe fn deallocate_frames(range: FrameRange) {
         let descriptor = MemoryDescriptor {
             range: range.address_range(),
             region_type: MemoryType::Free,
+            attributes: allocator.attributes_at(range.start_address()),
         };
         match allocator.try_insert_region(descriptor) {
             Ok(()) => break,
@@ -248,12 +270,14 @@ pub fn memory_map<'buffer>(
 ) -> Result<MemoryMap<'buffer>, BufferTooSmall> {
     let frame_allocator = FRAME_ALLOCATOR.lock();
 
-    if usize_to_u64(buffer.len()) < frame_allocator.range_count {
+    let range_count = u64_to_usize_strict(frame_allocator.range_count);
+    if buffer.len() < range_count {
         return Err(BufferTooSmall {
-            required_count: u64_to_usize_strict(frame_allocator.range_count),
+            required_count: range_count,
         });
     }
 
+    let buffer = &mut buffer[..range_count];
     for (write_loc, descriptor) in buffer.iter_mut().zip(frame_allocator.descriptors()) {
         *write_loc = descriptor;
     }
@@ -271,6 +295,7 @@ pub fn memory_map_key() -> u64 {
         descriptor.range.start().value().hash(&mut state);
         descriptor.range.count().hash(&mut state);
         (descriptor.region_type as u32).hash(&mut state);
+        descriptor.attributes.hash(&mut state);
     }
 
     state.finish()
@@ -351,9 +376,11 @@ impl FrameAllocator {
                 // Any sublink descriptors that reach this point must be adjacent to or overlap
                 // with the target descriptor.
 
-                // Any overlapping or adjacent regions that share [`MemoryType`]s with the target
-                // descriptor are merged.
-                if sublink_descriptor.region_type == descriptor.region_type {
+                // Any overlapping or adjacent regions that share [`MemoryType`]s and
+                // [`MemoryAttributes`] with the target descriptor are merged.
+                if sublink_descriptor.region_type == descriptor.region_type
+                    && sublink_descriptor.attributes == descriptor.attributes
+                {
                     subsuming_overlap_location =
                         subsuming_overlap_location.or(Some((current_link, sublink_index)));
                     subsuming_overlap_count += 1;
@@ -398,7 +425,7 @@ impl FrameAllocator {
 
                         let lower_descriptor = MemoryDescriptor {
                             range: lower,
-                            region_type: sublink_descriptor.region_type,
+                            ..sublink_descriptor
                         };
                         write_descriptor(current_link, sublink_index, lower_descriptor);
 
@@ -412,7 +439,7 @@ impl FrameAllocator {
 
                         let upper_descriptor = MemoryDescriptor {
                             range: upper,
-                            region_type: sublink_descriptor.region_type,
+                            ..sublink_descriptor
                         };
                         write_descriptor(current_link, sublink_index, upper_descriptor);
 
@@ -493,7 +520,7 @@ impl FrameAllocator {
 
             let descriptor = MemoryDescriptor {
                 range,
-                region_type: descriptor.region_type,
+                ..descriptor
             };
             write_descriptor(link, index, descriptor);
 
@@ -572,6 +599,7 @@ impl FrameAllocator {
                 let memory_descriptor = MemoryDescriptor {
                     range: frame.address_range(),
                     region_type: MemoryType::BootloaderReclaimable,
+                    attributes: descriptor.attributes,
                 };
                 self.try_insert_region(memory_descriptor)
                     .expect("link failed to add additonal storage");
@@ -592,6 +620,7 @@ impl FrameAllocator {
                 MemoryDescriptor {
                     range: PhysicalAddressRange::empty(),
                     region_type: MemoryType::Reserved,
+                    attributes: MemoryAttributes::NONE,
                 },
             )
         }
@@ -677,6 +706,13 @@ impl FrameAllocator {
         None
     }
 
+    /// Returns the [`MemoryAttributes`] of the region that contains `address`.
+    fn attributes_at(&self, address: PhysicalAddress) -> MemoryAttributes {
+        self.descriptors()
+            .find(|descriptor| descriptor.range.contains(address))
+            .map_or(MemoryAttributes::NONE, |descriptor| descriptor.attributes)
+    }
+
     /// Returns an iterator over the links in the [`FrameAllocator`]'s linked list.
     pub fn links(&self) -> LinkIter<'_> {
         LinkIter {
@@ -854,6 +890,7 @@ fn read_descriptor_at(physical_address: PhysicalAddress) -> MemoryDescriptor {
     let start_offset = 0;
     let count_offset = usize_to_u64(mem::size_of::<PhysicalAddress>());
     let region_type_offset = count_offset.strict_add(usize_to_u64(mem::size_of::<u64>()));
+    let attributes_offset = region_type_offset.strict_add(1);
 
     let start = read_u64_at(physical_address.strict_add(start_offset))
         .expect("address region is unreachable");
@@ -861,6 +898,8 @@ fn read_descriptor_at(physical_address: PhysicalAddress) -> MemoryDescriptor {
         .expect("address region is unreachable");
     let region_type = read_u8_at(physical_address.strict_add(region_type_offset))
         .expect("address region is unreachable");
+    let attributes = read_u8_at(physical_address.strict_add(attributes_offset))
+        .expect("address region is unreachable");
 
     let region_type = match region_type {
         0 => MemoryType::Free,
@@ -869,12 +908,21 @@ fn read_descriptor_at(physical_address: PhysicalAddress) -> MemoryDescriptor {
         3 => MemoryType::Reserved,
         4 => MemoryType::AcpiReclaimable,
         5 => MemoryType::AcpiNonVolatile,
+        6 => MemoryType::RuntimeServicesCode,
+        7 => MemoryType::RuntimeServicesData,
+        8 => MemoryType::Mmio,
+        9 => MemoryType::Persistent,
+        10 => MemoryType::Framebuffer,
+        11 => MemoryType::Executable,
+        12 => MemoryType::ProtocolTable,
+        13 => MemoryType::LogRing,
         _ => unreachable!(),
     };
 
     MemoryDescriptor {
         range: PhysicalAddressRange::new(PhysicalAddress::new(start), count),
         region_type,
+        attributes: MemoryAttributes::from_bits(attributes),
     }
 }
 
@@ -883,6 +931,7 @@ fn write_descriptor_at(physical_address: PhysicalAddress, descriptor: MemoryDesc
     let start_offset = 0;
     let count_offset = usize_to_u64(mem::size_of::<PhysicalAddress>());
     let region_type_offset = count_offset.strict_add(usize_to_u64(mem::size_of::<u64>()));
+    let attributes_offset = region_type_offset.strict_add(1);
 
     if !write_u64_at(
         physical_address.strict_add(start_offset),
@@ -904,6 +953,13 @@ fn write_descriptor_at(physical_address: PhysicalAddress, descriptor: MemoryDesc
     ) {
         panic!("address region is unreachable")
     }
+
+    if !write_u8_at(
+        physical_address.strict_add(attributes_offset),
+        descriptor.attributes.to_bits(),
+    ) {
+        panic!("address region is unreachable")
+    }
 }
 
 /// [`Hasher`] implementing `FNV-1a`.
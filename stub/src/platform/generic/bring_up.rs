@@ -0,0 +1,60 @@
+//! Platform agnostic stages of platform bring-up.
+//!
+//! Each platform expresses its bring-up sequence as inactive [`initgraph`] tasks, activates them
+//! once it knows that it was used to boot the stub, and then calls [`bring_up()`]. A task takes
+//! part in a stage by listing the stage among its dependents.
+
+use crate::arch::arch_specific::read_counter;
+
+initgraph::define_stage!(CONSOLE_STAGE, "platform.console", active);
+initgraph::define_stage!(MEMORY_STAGE, "platform.memory", active);
+initgraph::define_stage!(COMMAND_LINE_STAGE, "platform.command_line", active);
+initgraph::define_stage!(LOG_RING_STAGE, "platform.log_ring", active);
+initgraph::define_stage!(PROCESSORS_STAGE, "platform.processors", active);
+initgraph::define_stage!(TABLES_STAGE, "platform.tables", active);
+initgraph::define_stage!(FRAMEBUFFER_STAGE, "platform.framebuffer", active);
+initgraph::define_stage!(MODULES_STAGE, "platform.modules", active);
+
+/// Runs every active platform bring-up task, then logs the order in which the tasks ran and the
+/// number of counter ticks each of them took.
+pub fn bring_up() {
+    // SAFETY:
+    //
+    // Platform bring-up runs before the stub starts any other processor.
+    #[cfg(feature = "lock-diagnostics")]
+    unsafe {
+        crate::platform::install_lock_diagnostics()
+    }
+
+    initgraph::set_clock(read_counter);
+
+    for stage in [
+        &CONSOLE_STAGE,
+        &MEMORY_STAGE,
+        &COMMAND_LINE_STAGE,
+        &LOG_RING_STAGE,
+        &PROCESSORS_STAGE,
+        &TABLES_STAGE,
+        &FRAMEBUFFER_STAGE,
+        &MODULES_STAGE,
+    ] {
+        initgraph::run(stage);
+    }
+
+    crate::debug!("Platform bring-up:");
+    for node in initgraph::executed().filter(|node| !node.is_stage()) {
+        crate::debug!(
+            "    {:<24} {:>12} ticks",
+            node.name(),
+            node.elapsed_ticks().unwrap_or_default()
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn graph_is_acyclic() {
+        initgraph::verify();
+    }
+}
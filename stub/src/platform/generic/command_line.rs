@@ -0,0 +1,33 @@
+//! Definitions and interfaces that platforms use to provide the command line of the stub in a
+//! platform agnostic manner.
+
+use sync::ControlledModificationCell;
+
+use crate::platform::configure_log_filter;
+
+/// The command line passed to the stub.
+static COMMAND_LINE: ControlledModificationCell<&'static str> = ControlledModificationCell::new("");
+
+/// Sets the command line passed to the stub and applies the log filter that it configures.
+///
+/// # Safety
+///
+/// There must be zero overlapping calls to [`set_command_line()`], [`command_line()`], or any
+/// logging function.
+pub unsafe fn set_command_line(command_line: &'static str) {
+    // SAFETY:
+    //
+    // The invariants of `set_command_line()` ensure that this operation is safe.
+    unsafe { *COMMAND_LINE.get_mut() = command_line }
+
+    // SAFETY:
+    //
+    // The invariants of `set_command_line()` ensure that no logging occurs concurrently.
+    unsafe { configure_log_filter(command_line) }
+}
+
+/// Returns the command line passed to the stub, which is empty if the platform did not provide
+/// one.
+pub fn command_line() -> &'static str {
+    COMMAND_LINE.get()
+}
@@ -0,0 +1,55 @@
+//! Definitions and interfaces that platforms use to provide information related to the primary
+//! linear framebuffer in a platform agnostic manner.
+
+use sync::ControlledModificationCell;
+
+use crate::platform::PhysicalAddress;
+
+/// The platform's primary linear framebuffer.
+static FRAMEBUFFER: ControlledModificationCell<Option<Framebuffer>> =
+    ControlledModificationCell::new(None);
+
+/// Sets the platform's primary linear [`Framebuffer`].
+///
+/// # Safety
+///
+/// There must be zero overlapping calls to [`set_framebuffer()`] or [`framebuffer()`].
+pub unsafe fn set_framebuffer(framebuffer: Framebuffer) {
+    // SAFETY:
+    //
+    // The invariants of `set_framebuffer()` ensure that this operation is safe.
+    unsafe { *FRAMEBUFFER.get_mut() = Some(framebuffer) }
+}
+
+/// Returns the platform's primary linear [`Framebuffer`].
+pub fn framebuffer() -> Option<Framebuffer> {
+    *FRAMEBUFFER.get()
+}
+
+/// Description of a linear framebuffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Framebuffer {
+    /// The [`PhysicalAddress`] of the start of the framebuffer.
+    pub address: PhysicalAddress,
+    /// The width, in pixels, of the framebuffer.
+    pub width: u64,
+    /// The height, in pixels, of the framebuffer.
+    pub height: u64,
+    /// The number of bytes between the start of one row of pixels and the start of the next.
+    pub pitch: u64,
+
+    /// The number of bits that make up a single pixel.
+    pub bpp: u16,
+    /// The number of bits that make up the red component of a pixel.
+    pub red_mask_size: u8,
+    /// The offset, in bits, of the red component of a pixel.
+    pub red_mask_shift: u8,
+    /// The number of bits that make up the green component of a pixel.
+    pub green_mask_size: u8,
+    /// The offset, in bits, of the green component of a pixel.
+    pub green_mask_shift: u8,
+    /// The number of bits that make up the blue component of a pixel.
+    pub blue_mask_size: u8,
+    /// The offset, in bits, of the blue component of a pixel.
+    pub blue_mask_shift: u8,
+}
@@ -0,0 +1,32 @@
+//! Reporting of the lock diagnostics provided by the `sync` crate.
+
+use sync::LockDiagnostic;
+
+use crate::platform::{LogLevel, log_unbuffered};
+
+/// The number of spins after which an acquisition of a lock is reported.
+const SPIN_THRESHOLD: usize = 1 << 24;
+
+/// Installs a handler that reports lock diagnostics through the logging subsystem.
+///
+/// # Safety
+///
+/// This function must be called before any other processor is started.
+pub unsafe fn install_lock_diagnostics() {
+    // SAFETY:
+    //
+    // The invariants of `install_lock_diagnostics()` ensure that no other processor is acquiring a
+    // lock.
+    unsafe { sync::set_lock_diagnostics_handler(report, SPIN_THRESHOLD) }
+}
+
+/// Logs `diagnostic` without acquiring the lock of the shared print buffer, which may be the lock
+/// being diagnosed.
+fn report(diagnostic: &LockDiagnostic) {
+    let level = match diagnostic {
+        LockDiagnostic::Reacquired { .. } => LogLevel::Error,
+        _ => LogLevel::Warn,
+    };
+
+    log_unbuffered(level, format_args!("{diagnostic}"));
+}
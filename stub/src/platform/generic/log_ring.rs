@@ -0,0 +1,216 @@
+//! Persistent log ring shared between the stub and the executable.
+//!
+//! The ring is placed at a fixed physical address when possible so that the next boot can find it
+//! and report the records left behind by a crash or reset before formatting it again.
+
+use core::{mem, ptr::NonNull, str};
+
+use conversion::u64_to_usize_strict;
+use stub_api::log_ring::{LogRecordFlags, LogRecordLevel, LogRing};
+use sync::ControlledModificationCell;
+
+use crate::{
+    arch::arch_specific::read_counter,
+    platform::{
+        AllocationPolicy, FrameRange, LogLevel, MemoryType, Permissions, allocate_frames_aligned,
+        frame_size, map,
+    },
+};
+
+/// The size, in bytes, of the log ring.
+const LOG_RING_SIZE: u64 = 256 * 1024;
+/// The alignment, in bytes, of the physical address of the log ring.
+const LOG_RING_ALIGNMENT: u64 = 64 * 1024;
+
+/// The physical address at which the log ring is placed when that memory is free.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const PREFERRED_ADDRESS: u64 = 0x0800_0000;
+/// The physical address at which the log ring is placed when that memory is free.
+#[cfg(target_arch = "aarch64")]
+const PREFERRED_ADDRESS: u64 = 0x4800_0000;
+
+/// The log ring and the frames in which it resides.
+static LOG_RING: ControlledModificationCell<Option<(LogRing, FrameRange)>> =
+    ControlledModificationCell::new(None);
+
+initgraph::define_task!(
+    LOG_RING_TASK,
+    "generic.log_ring",
+    func = initialize_log_ring,
+    active,
+    requires = ["platform.memory"],
+    dependents = ["platform.log_ring"],
+    after = [],
+    before = [],
+);
+
+/// Allocates the log ring, reports the records of a log ring left by the previous boot, and then
+/// formats the log ring for use by this boot.
+fn initialize_log_ring() {
+    let count = LOG_RING_SIZE.div_ceil(frame_size());
+    let (allocation, preferred) = match allocate_frames_aligned(
+        count,
+        LOG_RING_ALIGNMENT,
+        AllocationPolicy::At(PREFERRED_ADDRESS),
+        MemoryType::LogRing,
+    ) {
+        Ok(allocation) => (allocation, true),
+        Err(_) => match allocate_frames_aligned(
+            count,
+            LOG_RING_ALIGNMENT,
+            AllocationPolicy::InclusiveMax(u64::from(u32::MAX)),
+            MemoryType::LogRing,
+        ) {
+            Ok(allocation) => (allocation, false),
+            Err(error) => {
+                crate::warn!("failed to allocate log ring: {error}");
+                return;
+            }
+        },
+    };
+
+    let mapping = match map(allocation.range(), Permissions::ReadWrite) {
+        Ok(mapping) => mapping,
+        Err(error) => {
+            crate::warn!("failed to map log ring: {error}");
+            return;
+        }
+    };
+    let size = u64_to_usize_strict(LOG_RING_SIZE);
+    let Some(base) = NonNull::new(core::ptr::with_exposed_provenance_mut::<u8>(
+        mapping.range().start_address().value(),
+    )) else {
+        return;
+    };
+
+    // Only memory at the preferred address can hold a log ring written by the previous boot.
+    if preferred {
+        // SAFETY:
+        //
+        // The frames backing the log ring are allocated and mapped at `base`, and nothing else
+        // accesses them.
+        if let Some(previous) = unsafe { LogRing::recover(base, size) } {
+            report_previous_boot(&previous);
+        }
+    }
+
+    // SAFETY:
+    //
+    // The frames backing the log ring are allocated and mapped at `base`, and are never freed or
+    // unmapped.
+    let ring = unsafe { LogRing::initialize(base, size) };
+    let Some(ring) = ring else {
+        return;
+    };
+
+    let range = allocation.range();
+    mem::forget(allocation);
+    mem::forget(mapping);
+
+    // SAFETY:
+    //
+    // Platform bring-up runs before the stub starts any other processor, and logging only reads
+    // `LOG_RING`.
+    unsafe { *LOG_RING.get_mut() = Some((ring, range)) }
+
+    crate::debug!("Log ring at {}", range.start_address());
+}
+
+/// Logs the records in `previous`, which was left by the previous boot.
+fn report_previous_boot(previous: &LogRing) {
+    let frequency = previous.counter_frequency();
+
+    let mut count = 0u64;
+    previous.for_each(|_, _| count += 1);
+    crate::info!("Recovered {count} log records from the previous boot");
+
+    previous.for_each(|record, message| {
+        let message = match str::from_utf8(message) {
+            Ok(message) => message,
+            Err(error) => str::from_utf8(&message[..error.valid_up_to()]).unwrap_or_default(),
+        };
+        let origin = if record.flags.contains(LogRecordFlags::EXECUTABLE) {
+            "revm"
+        } else {
+            "stub"
+        };
+
+        if frequency == 0 {
+            crate::debug!(
+                "previous boot: [{:>20}] cpu {} {origin} {:?}: {message}",
+                record.timestamp,
+                record.cpu,
+                record.level,
+            );
+        } else {
+            let micros = u128::from(record.timestamp) * 1_000_000 / u128::from(frequency);
+            crate::debug!(
+                "previous boot: [{:>5}.{:06}] cpu {} {origin} {:?}: {message}",
+                micros / 1_000_000,
+                micros % 1_000_000,
+                record.cpu,
+                record.level,
+            );
+        }
+    });
+}
+
+/// Returns the [`FrameRange`] that holds the log ring, if one was set up.
+pub fn log_ring_frames() -> Option<FrameRange> {
+    (*LOG_RING.get()).map(|(_, range)| range)
+}
+
+/// Appends `message`, logged by the stub at `level`, to the log ring.
+pub(in crate::platform) fn append_to_log_ring(level: LogLevel, message: &str) {
+    let Some((ring, _)) = *LOG_RING.get() else {
+        return;
+    };
+
+    let level = match level {
+        LogLevel::Trace => LogRecordLevel::TRACE,
+        LogLevel::Debug => LogRecordLevel::DEBUG,
+        LogLevel::Info => LogRecordLevel::INFO,
+        LogLevel::Warn => LogRecordLevel::WARN,
+        LogLevel::Error => LogRecordLevel::ERROR,
+    };
+    // Logging must work at every point of bring-up, including before a platform can identify the
+    // current processor and while handling a panic, so the stub leaves its records unattributed.
+    ring.append(
+        level,
+        LogRecordFlags(0),
+        u32::MAX,
+        read_counter(),
+        message.trim_end_matches('\n').as_bytes(),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use crate::platform::{LogLevel, mock};
+
+    use super::{LOG_RING, append_to_log_ring, initialize_log_ring};
+
+    #[test]
+    fn logging_does_not_query_the_processor_id() {
+        let _guard = mock::acquire();
+        if LOG_RING.get().is_none() {
+            initialize_log_ring();
+        }
+        let (ring, _) = LOG_RING.get().expect("failed to set up the log ring");
+
+        // SAFETY:
+        //
+        // The mock platform does not start any other processor, and the registered source always
+        // identifies processor 0 of 1.
+        unsafe { sync::set_processor_id_source(|| 0, 1) }
+        append_to_log_ring(LogLevel::Info, "logged after registration\n");
+
+        let mut cpu = None;
+        ring.for_each(|record, message| {
+            if message == b"logged after registration" {
+                cpu = Some(record.cpu);
+            }
+        });
+        assert_eq!(cpu, Some(u32::MAX));
+    }
+}
@@ -7,8 +7,17 @@ use core::{
     sync::atomic::{AtomicPtr, Ordering},
 };
 
+use log_filter::{Filter, LevelFilter};
 use sync::Spinlock;
 
+use crate::platform::append_to_log_ring;
+
+pub use log_filter::LogLevel;
+
+/// The [`LevelFilter`] of modules that no `log=` directive matches, so that the stub logs every
+/// message unless told otherwise.
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Trace;
+
 /// The head of the [`Console`] list.
 static CONSOLE_HEAD: AtomicPtr<Console> = AtomicPtr::new(ptr::null_mut());
 /// The print buffer.
@@ -95,12 +104,43 @@ pub unsafe fn deregister_console(console: NonNull<Console>) {
     panic!("attempted to deregister an unregistered console")
 }
 
+/// Applies the log filter configured by the `log=` arguments of `command_line`.
+///
+/// # Safety
+///
+/// There must be zero overlapping calls to any logging function.
+pub(in crate::platform) unsafe fn configure_log_filter(command_line: &str) {
+    match Filter::from_command_line(command_line, DEFAULT_LEVEL) {
+        Ok(filter) => {
+            // SAFETY:
+            //
+            // The invariants of this function ensure that no logging occurs concurrently.
+            unsafe { log_filter::set_filter(filter) }
+            crate::debug!("Log filter: {filter}");
+        }
+        Err(error) => crate::warn!("ignoring log filter: {error}"),
+    }
+}
+
 #[doc(hidden)]
-pub fn _log(level: LogLevel, args: fmt::Arguments) {
-    if level < LogLevel::Trace {
+pub fn _log(level: LogLevel, module_path: &str, args: fmt::Arguments) {
+    if !log_filter::enabled(level, module_path) {
         return;
     }
 
+    log(level, args, true)
+}
+
+/// Logs output that the executable wrote through the protocol table.
+///
+/// The executable filters its own output and appends its own records to the log ring, so the
+/// output is only written to the registered [`Console`]s.
+pub fn log_executable_output(level: LogLevel, args: fmt::Arguments) {
+    log(level, args, false)
+}
+
+/// Logs a message, appending it to the log ring if `append` is `true`.
+fn log(level: LogLevel, args: fmt::Arguments, append: bool) {
     let mut buffer = BUFFER.lock();
     buffer.written = 0;
 
@@ -111,7 +151,37 @@ pub fn _log(level: LogLevel, args: fmt::Arguments) {
     // WriteBuffer ensures that the bytes in the range `0..buffer.written` have been initialized to
     // UTF-8.
     let message = unsafe { core::str::from_utf8_unchecked(&buffer.buffer[..buffer.written]) };
+    if append {
+        append_to_log_ring(level, message);
+    }
+    write_consoles(metadata, message);
+
+    drop(buffer)
+}
+
+/// Logs a message without acquiring the shared print buffer.
+///
+/// This allows problems with the lock protecting the shared print buffer to be reported.
+#[cfg(feature = "lock-diagnostics")]
+pub(in crate::platform) fn log_unbuffered(level: LogLevel, args: fmt::Arguments) {
+    let mut buffer = WriteBuffer {
+        buffer: [0; 4096],
+        written: 0,
+    };
+
+    let metadata = Metadata { level };
+    let _ = writeln!(&mut buffer, "{args}");
+    // SAFETY:
+    //
+    // WriteBuffer ensures that the bytes in the range `0..buffer.written` have been initialized to
+    // UTF-8.
+    let message = unsafe { core::str::from_utf8_unchecked(&buffer.buffer[..buffer.written]) };
+    append_to_log_ring(level, message);
+    write_consoles(metadata, message);
+}
 
+/// Writes `message` to every registered [`Console`].
+fn write_consoles(metadata: Metadata, message: &str) {
     let mut console_ptr = NonNull::new(CONSOLE_HEAD.load(Ordering::Acquire));
     while let Some(console) = console_ptr {
         // SAFETY:
@@ -124,8 +194,6 @@ pub fn _log(level: LogLevel, args: fmt::Arguments) {
 
         console_ptr = next;
     }
-
-    drop(buffer)
 }
 
 /// Logs a message with [`LogLevel::Trace`].
@@ -133,6 +201,7 @@ pub fn _log(level: LogLevel, args: fmt::Arguments) {
 macro_rules! trace {
     ($($arg:tt)*) => ($crate::platform::_log(
         $crate::platform::LogLevel::Trace,
+        module_path!(),
         format_args!($($arg)*))
     );
 }
@@ -142,6 +211,7 @@ macro_rules! trace {
 macro_rules! debug {
     ($($arg:tt)*) => ($crate::platform::_log(
         $crate::platform::LogLevel::Debug,
+        module_path!(),
         format_args!($($arg)*))
     );
 }
@@ -151,6 +221,7 @@ macro_rules! debug {
 macro_rules! info {
     ($($arg:tt)*) => ($crate::platform::_log(
         $crate::platform::LogLevel::Info,
+        module_path!(),
         format_args!($($arg)*))
     );
 }
@@ -160,6 +231,7 @@ macro_rules! info {
 macro_rules! warn {
     ($($arg:tt)*) => ($crate::platform::_log(
         $crate::platform::LogLevel::Warn,
+        module_path!(),
         format_args!($($arg)*))
     );
 }
@@ -169,6 +241,7 @@ macro_rules! warn {
 macro_rules! error {
     ($($arg:tt)*) => ($crate::platform::_log(
         $crate::platform::LogLevel::Error,
+        module_path!(),
         format_args!($($arg)*))
     );
 }
@@ -180,21 +253,6 @@ pub struct Metadata {
     pub level: LogLevel,
 }
 
-/// Various levels to determine the priority of information.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub enum LogLevel {
-    /// Designates very low priority information.
-    Trace,
-    /// Designates lower priority information.
-    Debug,
-    /// Designates informatory logs.
-    Info,
-    /// Designates hazardous logs.
-    Warn,
-    /// Designates very serious logs.
-    Error,
-}
-
 /// The interface for a text-based output device.
 pub struct Console {
     /// The function to output the provided string onto the device.
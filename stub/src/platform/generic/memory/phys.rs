@@ -1,7 +1,10 @@
 //! Definitions and interfaces that platforms utilize to provide physical memory management services
 //! for use by the rest of the executable.
 
-use core::{error, fmt};
+use core::{
+    error, fmt,
+    ops::{BitOr, BitOrAssign},
+};
 
 use sync::ControlledModificationCell;
 
@@ -45,24 +48,42 @@ pub fn allocate_frames(
     policy: AllocationPolicy,
 ) -> Result<FrameAllocation, OutOfMemory> {
     physical_memory_manager()
-        .allocate_frames(count, policy)
+        .allocate_frames(count, policy, MemoryType::BootloaderReclaimable)
         .map(FrameAllocation)
 }
 
-/// Allocates a region of `count` frames with a starting physical address being a multiple of
-/// `alignment` and the entire region in accordance with the provided [`AllocationPolicy`].
+/// Allocates a region of `count` frames of `region_type` with a starting physical address being a
+/// multiple of `alignment` and the entire region in accordance with the provided
+/// [`AllocationPolicy`].
 ///
 /// # Errors
 ///
 /// Returns [`OutOfMemory`] if the system cannot allocate the requested frames. This may not
 /// indicate memory exhaustion if [`AllocationPolicy::Any`] is not in use.
+///
+/// # Panics
+///
+/// Panics if `region_type` is not [`MemoryType::BootloaderReclaimable`],
+/// [`MemoryType::Executable`], [`MemoryType::ProtocolTable`], or [`MemoryType::LogRing`].
 pub fn allocate_frames_aligned(
     count: u64,
     alignment: u64,
     policy: AllocationPolicy,
+    region_type: MemoryType,
 ) -> Result<FrameAllocation, OutOfMemory> {
+    assert!(
+        matches!(
+            region_type,
+            MemoryType::BootloaderReclaimable
+                | MemoryType::Executable
+                | MemoryType::ProtocolTable
+                | MemoryType::LogRing
+        ),
+        "frames cannot be allocated as {region_type:?}"
+    );
+
     physical_memory_manager()
-        .allocate_frames_aligned(count, alignment, policy)
+        .allocate_frames_aligned(count, alignment, policy, region_type)
         .map(FrameAllocation)
 }
 
@@ -79,7 +100,12 @@ pub fn allocate_physical(
     policy: AllocationPolicy,
 ) -> Result<FrameAllocation, OutOfMemory> {
     let frame_count = byte_count.div_ceil(frame_size());
-    allocate_frames_aligned(frame_count, alignment, policy)
+    allocate_frames_aligned(
+        frame_count,
+        alignment,
+        policy,
+        MemoryType::BootloaderReclaimable,
+    )
 }
 
 /// Deallocates the provided physical [`FrameRange`].
@@ -149,7 +175,11 @@ impl Drop for FrameAllocation {
 
 /// Trait representing a platform-independent mechanism for physical memory management.
 pub(in crate::platform) trait PhysicalMemoryManager: Send + Sync {
-    /// Allocates a region of `count` frames in accordance with the provided [`AllocationPolicy`].
+    /// Allocates a region of `count` frames of `region_type` in accordance with the provided
+    /// [`AllocationPolicy`].
+    ///
+    /// `region_type` is one of [`MemoryType::BootloaderReclaimable`], [`MemoryType::Executable`],
+    /// [`MemoryType::ProtocolTable`], or [`MemoryType::LogRing`].
     ///
     /// # Errors
     ///
@@ -159,10 +189,15 @@ pub(in crate::platform) trait PhysicalMemoryManager: Send + Sync {
         &self,
         count: u64,
         policy: AllocationPolicy,
+        region_type: MemoryType,
     ) -> Result<FrameRange, OutOfMemory>;
 
-    /// Allocates a region of `count` frames with a starting physical address being a multiple of
-    /// `alignment` and the entire region in accordance with the provided [`AllocationPolicy`].
+    /// Allocates a region of `count` frames of `region_type` with a starting physical address being
+    /// a multiple of `alignment` and the entire region in accordance with the provided
+    /// [`AllocationPolicy`].
+    ///
+    /// `region_type` is one of [`MemoryType::BootloaderReclaimable`], [`MemoryType::Executable`],
+    /// [`MemoryType::ProtocolTable`], or [`MemoryType::LogRing`].
     ///
     /// # Errors
     ///
@@ -173,18 +208,19 @@ pub(in crate::platform) trait PhysicalMemoryManager: Send + Sync {
         count: u64,
         alignment: u64,
         policy: AllocationPolicy,
+        region_type: MemoryType,
     ) -> Result<FrameRange, OutOfMemory> {
         assert!(alignment.is_power_of_two());
 
         if frame_size() >= alignment {
-            self.allocate_frames(count, policy)
+            self.allocate_frames(count, policy, region_type)
         } else {
             let total_count = alignment
                 .div_ceil(frame_size())
                 .checked_add(count)
                 .ok_or(OutOfMemory)?;
 
-            let chunk_range = self.allocate_frames(total_count, policy)?;
+            let chunk_range = self.allocate_frames(total_count, policy, region_type)?;
 
             let returned_chunk = chunk_range
                 .start()
@@ -290,6 +326,8 @@ pub struct MemoryDescriptor {
     pub range: PhysicalAddressRange,
     /// The type of the memory region.
     pub region_type: MemoryType,
+    /// The caching and runtime attributes of the memory region.
+    pub attributes: MemoryAttributes,
 }
 
 impl MemoryDescriptor {
@@ -316,6 +354,73 @@ pub enum MemoryType {
     AcpiReclaimable,
     /// Memory that holds non-volatile ACPI data.
     AcpiNonVolatile,
+    /// Memory that holds the code of the firmware's runtime services.
+    RuntimeServicesCode,
+    /// Memory that holds the data of the firmware's runtime services.
+    RuntimeServicesData,
+    /// Memory-mapped I/O regions reported by the firmware.
+    Mmio,
+    /// Byte-addressable memory that retains its contents across resets.
+    Persistent,
+    /// Memory that backs a framebuffer.
+    Framebuffer,
+    /// Memory that holds the loaded image of the executable.
+    Executable,
+    /// Memory that holds the protocol table and the structures that it references.
+    ProtocolTable,
+    /// Memory that holds the persistent log ring.
+    LogRing,
+}
+
+/// Attributes of a memory region.
+///
+/// The caching attributes describe the memory types that the region supports. A region without
+/// any caching attributes has unknown caching capabilities.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct MemoryAttributes(u8);
+
+impl MemoryAttributes {
+    /// No attributes are known for the region.
+    pub const NONE: Self = Self(0);
+    /// The region supports being mapped as uncacheable.
+    pub const UNCACHEABLE: Self = Self(1 << 0);
+    /// The region supports being mapped as write-combining.
+    pub const WRITE_COMBINING: Self = Self(1 << 1);
+    /// The region supports being mapped as write-through.
+    pub const WRITE_THROUGH: Self = Self(1 << 2);
+    /// The region supports being mapped as write-back.
+    pub const WRITE_BACK: Self = Self(1 << 3);
+    /// The region is used by the firmware's runtime services.
+    pub const RUNTIME: Self = Self(1 << 7);
+
+    /// Creates a [`MemoryAttributes`] from its raw value.
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the [`MemoryAttributes`].
+    pub const fn to_bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns `true` if the flags in `other` are set in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for MemoryAttributes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for MemoryAttributes {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
 }
 
 /// Indicates that there were no frame regions that were free and complied with the provided flags.
@@ -1,12 +1,26 @@
 //! Definitions and interfaces that platforms utilize to provide services for use by the rest of
 //! the executable.
 
+mod bring_up;
+mod command_line;
+mod framebuffer;
+#[cfg(feature = "lock-diagnostics")]
+mod lock_diagnostics;
+mod log_ring;
 mod logging;
 mod memory;
+mod modules;
 mod platform_tables;
 mod processor;
 
+pub use bring_up::*;
+pub use command_line::*;
+pub use framebuffer::*;
+#[cfg(feature = "lock-diagnostics")]
+pub use lock_diagnostics::*;
+pub use log_ring::*;
 pub use logging::*;
 pub use memory::*;
+pub use modules::*;
 pub use platform_tables::*;
 pub use processor::*;
@@ -0,0 +1,72 @@
+//! Definitions and interfaces that platforms use to provide the files loaded on behalf of the
+//! executable in a platform agnostic manner.
+
+use sync::Spinlock;
+
+use crate::platform::PhysicalAddressRange;
+
+/// The maximum number of [`Module`]s that can be registered.
+const MAX_MODULES: usize = 32;
+
+/// The registered [`Module`]s.
+static MODULES: Spinlock<ModuleList> = Spinlock::new(ModuleList {
+    modules: [None; MAX_MODULES],
+    count: 0,
+});
+
+/// Registers a [`Module`] to be passed to the executable.
+///
+/// Returns `false` if the maximum number of [`Module`]s has already been registered.
+pub fn register_module(module: Module) -> bool {
+    let mut list = MODULES.lock();
+    let count = list.count;
+    let Some(slot) = list.modules.get_mut(count) else {
+        return false;
+    };
+
+    *slot = Some(module);
+    list.count += 1;
+    true
+}
+
+/// Returns a [`ModuleList`] containing all registered [`Module`]s.
+pub fn modules() -> ModuleList {
+    *MODULES.lock()
+}
+
+/// A list of [`Module`]s.
+#[derive(Clone, Copy, Debug)]
+pub struct ModuleList {
+    /// The storage for the [`Module`]s.
+    modules: [Option<Module>; MAX_MODULES],
+    /// The number of [`Module`]s in the list.
+    count: usize,
+}
+
+impl ModuleList {
+    /// Returns the number of [`Module`]s in the list.
+    pub const fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the list contains no [`Module`]s.
+    pub const fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns an [`Iterator`] over the [`Module`]s in the list.
+    pub fn iter(&self) -> impl Iterator<Item = Module> + '_ {
+        self.modules[..self.count].iter().flatten().copied()
+    }
+}
+
+/// Description of a file loaded into memory on behalf of the executable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Module {
+    /// The [`PhysicalAddressRange`] containing the contents of the module.
+    pub range: PhysicalAddressRange,
+    /// The name of the module.
+    pub name: &'static str,
+    /// The command line associated with the module.
+    pub command_line: &'static str,
+}
@@ -3,6 +3,7 @@
 
 use core::ptr;
 
+use conversion::u64_to_usize_strict;
 use sync::ControlledModificationCell;
 
 /// The current [`ProcessorManager`].
@@ -21,6 +22,15 @@ pub(in crate::platform) unsafe fn initialize_processor_management(
     //
     // The invariants of [`initialize_processor_management`] ensure that this operation is safe.
     unsafe { *PROCESSOR_MANAGER.get_mut() = Some(manager) }
+
+    // A platform that has not discovered any other processors still runs on the main processor.
+    let count = u64_to_usize_strict(processor_count()).max(1);
+    // SAFETY:
+    //
+    // Processor management is initialized before the stub hands any work to another processor, and
+    // [`ProcessorManager::current_processor_id()`] returns a distinct, fixed ID that is less than
+    // [`ProcessorManager::processor_count()`] on each processor without panicking.
+    unsafe { sync::set_processor_id_source(|| u64_to_usize_strict(current_processor_id()), count) }
 }
 
 /// Returns the currently active [`ProcessorManager`].
@@ -46,6 +56,20 @@ pub fn processor_count() -> u64 {
     processor_manager().processor_count()
 }
 
+/// Returns the [`ProcessorDescriptor`] of the processor with the given `processor_id`.
+///
+/// # Panics
+///
+/// Panics if `processor_id` is not less than [`processor_count()`].
+pub fn processor_descriptor(processor_id: u64) -> ProcessorDescriptor {
+    assert!(
+        processor_id < processor_count(),
+        "processor ID {processor_id} is out of range"
+    );
+
+    processor_manager().processor_descriptor(processor_id)
+}
+
 /// Executes the provided function on all processors.
 ///
 /// All processors will return from `procedure` before this function returns.
@@ -67,6 +91,11 @@ pub(in crate::platform) trait ProcessorManager: Send + Sync {
     fn main_processor_id(&self) -> u64;
 
     /// Returns the processor ID of the processor on which this function was called.
+    ///
+    /// # Implementors
+    ///
+    /// This function must not panic, and must return a distinct ID that is less than
+    /// [`ProcessorManager::processor_count()`] on each processor.
     fn current_processor_id(&self) -> u64;
 
     /// Returns the total number of active processors.
@@ -76,6 +105,13 @@ pub(in crate::platform) trait ProcessorManager: Send + Sync {
     /// The value returned by this function must not change.
     fn processor_count(&self) -> u64;
 
+    /// Returns the [`ProcessorDescriptor`] of the processor with the given `processor_id`.
+    ///
+    /// # Implementors
+    ///
+    /// `processor_id` is guaranteed to be less than [`ProcessorManager::processor_count()`].
+    fn processor_descriptor(&self, processor_id: u64) -> ProcessorDescriptor;
+
     /// Executes the provided function on all processors. All CPUs must return before this function
     /// returns.
     ///
@@ -85,3 +121,15 @@ pub(in crate::platform) trait ProcessorManager: Send + Sync {
 
 /// The function prototype required for [`ProcessorManager::run_on_all_processors`]
 pub type Procedure = extern "C" fn(cpu_id: u64, arg: *mut ());
+
+/// Platform-independent description of a single processor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProcessorDescriptor {
+    /// The architectural identifier of the processor (the x2APIC ID or initial APIC ID on `x86`
+    /// and the affinity fields of `MPIDR_EL1` on `aarch64`).
+    pub hardware_id: u64,
+    /// Whether the processor is enabled and usable.
+    pub enabled: bool,
+    /// The NUMA proximity domain to which the processor belongs, if known.
+    pub proximity_domain: Option<u32>,
+}
@@ -1,11 +1,50 @@
 //! Implementation of an output-only graphical console to be used for logging.
+//!
+//! The console interprets the subset of the VT100 and xterm escape sequences that is useful for
+//! colored logging and simple status displays:
+//!
+//! - `CSI n m`: select graphic rendition, including 16, 256 and 24-bit colors.
+//! - `CSI n A`, `B`, `C`, `D`, `E`, `F`, `G`, `d`, and `CSI row;column H` or `f`: cursor movement.
+//! - `CSI n J` and `CSI n K`: clear the screen and clear the line.
+//! - `ESC 7`, `ESC 8`, `CSI s` and `CSI u`: save and restore the cursor.
+//! - `ESC c`: reset the console.
+//!
+//! Unsupported sequences are consumed without effect.
 
-use core::fmt;
+use core::fmt::{self, Write};
 
-use conversion::u32_to_usize;
+use conversion::{u8_to_usize, u32_to_usize};
 use font::{font_map::FontMap, glyph::GlyphArray};
 
-use crate::platform::graphics::surface::{Point, Region, Surface};
+use crate::platform::{
+    LogLevel, Metadata,
+    graphics::surface::{Point, Region, Surface},
+};
+
+/// The maximum number of parameters recorded for a single control sequence.
+const MAX_PARAMETERS: usize = 16;
+/// The distance, in characters, between tab stops.
+const TAB_WIDTH: usize = 8;
+
+/// The 16 standard colors, in the order selected by SGR parameters `30..=37` and `90..=97`.
+const PALETTE: [u32; 16] = [
+    rgb(0, 0, 0),
+    rgb(205, 0, 0),
+    rgb(0, 205, 0),
+    rgb(205, 205, 0),
+    rgb(0, 0, 238),
+    rgb(205, 0, 205),
+    rgb(0, 205, 205),
+    rgb(229, 229, 229),
+    rgb(127, 127, 127),
+    rgb(255, 0, 0),
+    rgb(0, 255, 0),
+    rgb(255, 255, 0),
+    rgb(92, 92, 255),
+    rgb(255, 0, 255),
+    rgb(0, 255, 255),
+    rgb(255, 255, 255),
+];
 
 /// Text-based graphical output device.
 pub struct TextConsole<'font, S: Surface> {
@@ -23,6 +62,17 @@ pub struct TextConsole<'font, S: Surface> {
     foreground: u32,
     /// The color of the background.
     background: u32,
+    /// The color of the foreground when no color has been selected.
+    default_foreground: u32,
+    /// The color of the background when no color has been selected.
+    default_background: u32,
+    /// If `true`, the foreground and background colors are swapped when drawing.
+    reverse: bool,
+
+    /// The cursor position stored by the most recent save cursor sequence.
+    saved_cursor: (usize, usize),
+    /// The escape sequence parser.
+    parser: Parser,
 
     /// The [`Surface`] on which this console writes.
     surface: S,
@@ -55,6 +105,12 @@ impl<'font, S: Surface> TextConsole<'font, S> {
 
             foreground,
             background,
+            default_foreground: foreground,
+            default_background: background,
+            reverse: false,
+
+            saved_cursor: (0, 0),
+            parser: Parser::new(),
 
             surface,
 
@@ -63,50 +119,278 @@ impl<'font, S: Surface> TextConsole<'font, S> {
         }
     }
 
-    /// Writes the given [`char`] to the [`Surface`].
+    /// Writes `message`, logged with the provided [`Metadata`], with its [`LogLevel`] colored.
+    pub fn write_log(&mut self, metadata: Metadata, message: &str) {
+        let color = match metadata.level {
+            LogLevel::Trace => 90,
+            LogLevel::Debug => 36,
+            LogLevel::Info => 32,
+            LogLevel::Warn => 33,
+            LogLevel::Error => 31,
+        };
+
+        let _ = write!(
+            self,
+            "\x1b[{color}m[{:?}]\x1b[39m: {message}",
+            metadata.level
+        );
+    }
+
+    /// Writes the given [`char`] to the [`Surface`], interpreting escape sequences.
     pub fn write_char(&mut self, c: char) {
+        match self.parser.state {
+            ParserState::Ground => self.execute(c),
+            ParserState::Escape => self.escape(c),
+            ParserState::ControlSequence => self.control_sequence(c),
+        }
+    }
+
+    /// Handles `c` outside of an escape sequence.
+    fn execute(&mut self, c: char) {
         match c {
+            '\x1b' => self.parser.state = ParserState::Escape,
             '\n' => self.new_line(),
             '\r' => self.carriage_return(),
-            c => {
-                if self.x + 1 >= self.text_width {
-                    self.new_line();
+            '\x08' => self.set_cursor(self.x.saturating_sub(1), self.y),
+            '\t' => self.set_cursor((self.x / TAB_WIDTH + 1) * TAB_WIDTH, self.y),
+            c if c.is_control() => {}
+            c => self.draw_char(c),
+        }
+    }
+
+    /// Handles `c` immediately after an `ESC`.
+    fn escape(&mut self, c: char) {
+        self.parser.state = ParserState::Ground;
+        match c {
+            '[' => self.parser.start_control_sequence(),
+            '7' => self.saved_cursor = (self.x, self.y),
+            '8' => (self.x, self.y) = self.saved_cursor,
+            'c' => self.reset(),
+            _ => {}
+        }
+    }
+
+    /// Handles `c` inside of a control sequence.
+    fn control_sequence(&mut self, c: char) {
+        match c {
+            '0'..='9' => self.parser.push_digit(c),
+            ';' | ':' => self.parser.next_parameter(),
+            '<' | '=' | '>' | '?' => self.parser.private = true,
+            '\x1b' => self.parser.state = ParserState::Escape,
+            '\x18' | '\x1a' => self.parser.state = ParserState::Ground,
+            c if c.is_control() => self.execute(c),
+            // Intermediate bytes select variants of sequences that are not supported.
+            '\x20'..='\x2f' => self.parser.private = true,
+            '\x40'..='\x7e' => {
+                self.parser.state = ParserState::Ground;
+                if !self.parser.private {
+                    self.dispatch(c);
                 }
+            }
+            _ => self.parser.state = ParserState::Ground,
+        }
+    }
 
-                let Some(glyph_index) = self.font_map.get(c) else {
-                    self.x += 1;
-                    return;
-                };
-                let Some(glyph) = self.glyph_array.get(u32_to_usize(glyph_index)) else {
-                    return;
-                };
+    /// Carries out the control sequence terminated by `c`.
+    fn dispatch(&mut self, c: char) {
+        let count = usize::from(self.parser.parameter(0).max(1));
+        match c {
+            'A' => self.set_cursor(self.x, self.y.saturating_sub(count)),
+            'B' => self.set_cursor(self.x, self.y.saturating_add(count)),
+            'C' => self.set_cursor(self.x.saturating_add(count), self.y),
+            'D' => self.set_cursor(self.x.min(self.max_x()).saturating_sub(count), self.y),
+            'E' => self.set_cursor(0, self.y.saturating_add(count)),
+            'F' => self.set_cursor(0, self.y.saturating_sub(count)),
+            'G' => self.set_cursor(count - 1, self.y),
+            'd' => self.set_cursor(self.x, count - 1),
+            'H' | 'f' => {
+                let column = usize::from(self.parser.parameter(1).max(1));
+                self.set_cursor(column - 1, count - 1);
+            }
+            'J' => self.erase_display(self.parser.parameter(0)),
+            'K' => self.erase_line(self.parser.parameter(0)),
+            'm' => self.select_graphic_rendition(),
+            's' => self.saved_cursor = (self.x, self.y),
+            'u' => (self.x, self.y) = self.saved_cursor,
+            _ => {}
+        }
+    }
+
+    /// Applies the graphic rendition parameters of the current control sequence.
+    fn select_graphic_rendition(&mut self) {
+        let count = self.parser.parameter_count().max(1);
+
+        let mut index = 0;
+        while index < count {
+            let parameter = self.parser.parameter(index);
+            index += 1;
 
-                let x_base = self.x * usize::from(self.glyph_array.width());
-                let y_base = self.y * usize::from(self.glyph_array.height());
-
-                for (y_offset, row) in glyph.into_iter().enumerate() {
-                    for (x_offset, pixel_on) in row.into_iter().enumerate() {
-                        let color = if pixel_on {
-                            self.foreground
-                        } else {
-                            self.background
-                        };
-
-                        self.surface
-                            .write_pixel(
-                                Point {
-                                    x: x_base + x_offset,
-                                    y: y_base + y_offset,
-                                },
-                                color,
-                            )
-                            .unwrap();
-                    }
+            match parameter {
+                0 => {
+                    self.foreground = self.default_foreground;
+                    self.background = self.default_background;
+                    self.reverse = false;
                 }
+                7 => self.reverse = true,
+                27 => self.reverse = false,
+                30..=37 => self.foreground = PALETTE[usize::from(parameter - 30)],
+                38 => {
+                    let (color, used) = self.parser.extended_color(index);
+                    self.foreground = color.unwrap_or(self.foreground);
+                    index += used;
+                }
+                39 => self.foreground = self.default_foreground,
+                40..=47 => self.background = PALETTE[usize::from(parameter - 40)],
+                48 => {
+                    let (color, used) = self.parser.extended_color(index);
+                    self.background = color.unwrap_or(self.background);
+                    index += used;
+                }
+                49 => self.background = self.default_background,
+                90..=97 => self.foreground = PALETTE[usize::from(parameter - 90 + 8)],
+                100..=107 => self.background = PALETTE[usize::from(parameter - 100 + 8)],
+                _ => {}
+            }
+        }
+    }
+
+    /// Clears part of the screen as selected by `mode`.
+    ///
+    /// `0` clears from the cursor to the end of the screen, `1` clears from the start of the
+    /// screen to the cursor, and `2` or `3` clear the entire screen.
+    fn erase_display(&mut self, mode: u16) {
+        match mode {
+            0 => {
+                self.erase_line(0);
+                self.clear_rows(self.y + 1, self.text_height);
+            }
+            1 => {
+                self.clear_rows(0, self.y);
+                self.erase_line(1);
+            }
+            2 | 3 => self.clear_rows(0, self.text_height),
+            _ => {}
+        }
+    }
+
+    /// Clears part of the current line as selected by `mode`.
+    ///
+    /// `0` clears from the cursor to the end of the line, `1` clears from the start of the line to
+    /// the cursor, and `2` clears the entire line.
+    fn erase_line(&mut self, mode: u16) {
+        let (start, end) = match mode {
+            0 => (self.x, self.text_width),
+            1 => (0, self.x.saturating_add(1)),
+            2 => (0, self.text_width),
+            _ => return,
+        };
+
+        let glyph_width = usize::from(self.glyph_array.width());
+        let glyph_height = usize::from(self.glyph_array.height());
+        let start = start.min(self.text_width);
+        let end = end.min(self.text_width);
+        if start >= end || self.y >= self.text_height {
+            return;
+        }
+
+        let region = Region {
+            point: Point {
+                x: start * glyph_width,
+                y: self.y * glyph_height,
+            },
+            width: (end - start) * glyph_width,
+            height: glyph_height,
+        };
+        let (_, background) = self.colors();
+        let _ = self.surface.fill(region, background);
+    }
+
+    /// Clears the rows from `start` up to, but not including, `end`.
+    fn clear_rows(&mut self, start: usize, end: usize) {
+        let glyph_height = usize::from(self.glyph_array.height());
+        let end = end.min(self.text_height);
+        if start >= end {
+            return;
+        }
+
+        let region = Region {
+            point: Point {
+                x: 0,
+                y: start * glyph_height,
+            },
+            width: self.surface.width(),
+            height: (end - start) * glyph_height,
+        };
+        let (_, background) = self.colors();
+        let _ = self.surface.fill(region, background);
+    }
 
-                self.x += 1;
+    /// Restores the initial colors, clears the screen, and moves the cursor to the top left.
+    fn reset(&mut self) {
+        self.foreground = self.default_foreground;
+        self.background = self.default_background;
+        self.reverse = false;
+        self.saved_cursor = (0, 0);
+
+        self.clear_rows(0, self.text_height);
+        self.set_cursor(0, 0);
+    }
+
+    /// Draws `c` at the cursor and advances the cursor.
+    fn draw_char(&mut self, c: char) {
+        if self.x >= self.text_width {
+            self.new_line();
+        }
+
+        let Some(glyph_index) = self.font_map.get(c) else {
+            self.x += 1;
+            return;
+        };
+        let Some(glyph) = self.glyph_array.get(u32_to_usize(glyph_index)) else {
+            return;
+        };
+
+        let (foreground, background) = self.colors();
+        let x_base = self.x * usize::from(self.glyph_array.width());
+        let y_base = self.y * usize::from(self.glyph_array.height());
+
+        for (y_offset, row) in glyph.into_iter().enumerate() {
+            for (x_offset, pixel_on) in row.into_iter().enumerate() {
+                let color = if pixel_on { foreground } else { background };
+
+                self.surface
+                    .write_pixel(
+                        Point {
+                            x: x_base + x_offset,
+                            y: y_base + y_offset,
+                        },
+                        color,
+                    )
+                    .unwrap();
             }
         }
+
+        self.x += 1;
+    }
+
+    /// Returns the foreground and background colors with which characters are drawn.
+    const fn colors(&self) -> (u32, u32) {
+        if self.reverse {
+            (self.background, self.foreground)
+        } else {
+            (self.foreground, self.background)
+        }
+    }
+
+    /// Moves the cursor to (`x`, `y`), clamped to the bounds of the character array.
+    fn set_cursor(&mut self, x: usize, y: usize) {
+        self.x = x.min(self.max_x());
+        self.y = y.min(self.text_height.saturating_sub(1));
+    }
+
+    /// Returns the largest x position at which the cursor can be placed.
+    const fn max_x(&self) -> usize {
+        self.text_width.saturating_sub(1)
     }
 
     /// Scrolls the [`TextConsole`] a single line.
@@ -127,15 +411,7 @@ impl<'font, S: Surface> TextConsole<'font, S> {
 
         self.surface.copy_within(write, source_point).unwrap();
 
-        let fill_region = Region {
-            point: Point {
-                x: 0,
-                y: copy_height,
-            },
-            width: self.surface.width(),
-            height: usize::from(self.glyph_array.height()),
-        };
-        self.surface.fill(fill_region, self.background).unwrap();
+        self.clear_rows(self.text_height - 1, self.text_height);
     }
 
     /// Handles new lines (with an included carriage return).
@@ -145,7 +421,7 @@ impl<'font, S: Surface> TextConsole<'font, S> {
         self.carriage_return();
         self.y += 1;
 
-        if self.y + 1 >= self.text_height {
+        if self.y >= self.text_height {
             self.y -= 1;
             self.scroll();
         }
@@ -166,3 +442,272 @@ impl<S: Surface> fmt::Write for TextConsole<'_, S> {
         Ok(())
     }
 }
+
+/// The state of the escape sequence parser of a [`TextConsole`].
+struct Parser {
+    /// The kind of input the parser expects next.
+    state: ParserState,
+    /// The numeric parameters of the current control sequence.
+    parameters: [u16; MAX_PARAMETERS],
+    /// The number of parameters that have been started in the current control sequence.
+    count: usize,
+    /// If `true`, the current control sequence is not supported and is ignored.
+    private: bool,
+}
+
+impl Parser {
+    /// Creates a new [`Parser`] that is not inside an escape sequence.
+    const fn new() -> Self {
+        Self {
+            state: ParserState::Ground,
+            parameters: [0; MAX_PARAMETERS],
+            count: 0,
+            private: false,
+        }
+    }
+
+    /// Begins parsing a control sequence.
+    const fn start_control_sequence(&mut self) {
+        self.state = ParserState::ControlSequence;
+        self.parameters = [0; MAX_PARAMETERS];
+        self.count = 0;
+        self.private = false;
+    }
+
+    /// Appends the decimal digit `c` to the current parameter.
+    fn push_digit(&mut self, c: char) {
+        if self.count == 0 {
+            self.count = 1;
+        }
+
+        if let Some(parameter) = self.parameters.get_mut(self.count - 1)
+            && let Some(digit) = c.to_digit(10)
+        {
+            let digit = u16::try_from(digit).unwrap_or_default();
+            *parameter = parameter.saturating_mul(10).saturating_add(digit);
+        }
+    }
+
+    /// Ends the current parameter and starts the next one.
+    const fn next_parameter(&mut self) {
+        if self.count == 0 {
+            self.count = 1;
+        }
+
+        if self.count <= MAX_PARAMETERS {
+            self.count += 1;
+        }
+    }
+
+    /// Returns the number of parameters recorded for the current control sequence.
+    fn parameter_count(&self) -> usize {
+        self.count.min(MAX_PARAMETERS)
+    }
+
+    /// Returns the parameter at `index`, which is `0` if it was omitted.
+    fn parameter(&self, index: usize) -> u16 {
+        if index < self.parameter_count() {
+            self.parameters[index]
+        } else {
+            0
+        }
+    }
+
+    /// Decodes the extended color that starts with the parameter at `index`.
+    ///
+    /// Returns the color, if valid, and the number of parameters it occupies.
+    fn extended_color(&self, index: usize) -> (Option<u32>, usize) {
+        let component = |offset| u8::try_from(self.parameter(index + offset)).ok();
+
+        match self.parameter(index) {
+            2 => {
+                let color = match (component(1), component(2), component(3)) {
+                    (Some(red), Some(green), Some(blue)) => Some(rgb(red, green, blue)),
+                    _ => None,
+                };
+                (color, 4)
+            }
+            5 => (component(1).map(indexed_color), 2),
+            _ => (None, 1),
+        }
+    }
+}
+
+/// The kind of input a [`Parser`] expects next.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ParserState {
+    /// Characters are printed or executed as control characters.
+    Ground,
+    /// An `ESC` has been received.
+    Escape,
+    /// A control sequence introducer (`ESC [`) has been received.
+    ControlSequence,
+}
+
+/// Returns the RGBA8 value of the opaque color made up of `red`, `green`, and `blue`.
+const fn rgb(red: u8, green: u8, blue: u8) -> u32 {
+    u32::from_le_bytes([red, green, blue, 0xFF])
+}
+
+/// Returns the color at `index` in the xterm 256 color palette.
+const fn indexed_color(index: u8) -> u32 {
+    /// Returns the intensity of `level` on the axis of the 6x6x6 color cube.
+    const fn cube_level(level: u8) -> u8 {
+        if level == 0 { 0 } else { 55 + level * 40 }
+    }
+
+    match index {
+        0..=15 => PALETTE[u8_to_usize(index)],
+        16..=231 => {
+            let index = index - 16;
+            rgb(
+                cube_level(index / 36),
+                cube_level(index / 6 % 6),
+                cube_level(index % 6),
+            )
+        }
+        232..=255 => {
+            let gray = 8 + (index - 232) * 10;
+            rgb(gray, gray, gray)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fmt::Write, vec, vec::Vec};
+
+    use super::{PALETTE, TextConsole, rgb};
+    use crate::platform::graphics::{
+        font::{FONT_MAP, GLYPH_ARRAY},
+        surface::{Point, Surface},
+    };
+
+    /// The width, in characters, of the test console.
+    const COLUMNS: usize = 10;
+    /// The height, in characters, of the test console.
+    const ROWS: usize = 4;
+    /// The foreground color with which the test console is created.
+    const FOREGROUND: u32 = 0xFF_FF_FF_FF;
+    /// The background color with which the test console is created.
+    const BACKGROUND: u32 = 0;
+
+    /// A [`Surface`] backed by host memory.
+    struct TestSurface {
+        /// The pixels of the surface, in row-major order.
+        pixels: Vec<u32>,
+        /// The width of the surface in pixels.
+        width: usize,
+        /// The height of the surface in pixels.
+        height: usize,
+    }
+
+    // SAFETY:
+    //
+    // Every in-bounds point refers to an element of `pixels`.
+    unsafe impl Surface for TestSurface {
+        fn width(&self) -> usize {
+            self.width
+        }
+
+        fn height(&self) -> usize {
+            self.height
+        }
+
+        unsafe fn write_pixel_unchecked(&mut self, point: Point, value: u32) {
+            self.pixels[point.y * self.width + point.x] = value;
+        }
+
+        unsafe fn read_pixel_unchecked(&self, point: Point) -> u32 {
+            self.pixels[point.y * self.width + point.x]
+        }
+    }
+
+    /// Creates a [`TextConsole`] that is [`COLUMNS`] characters wide and [`ROWS`] characters tall.
+    fn console() -> TextConsole<'static, TestSurface> {
+        let width = COLUMNS * usize::from(GLYPH_ARRAY.width());
+        let height = ROWS * usize::from(GLYPH_ARRAY.height());
+        let surface = TestSurface {
+            pixels: vec![BACKGROUND; width * height],
+            width,
+            height,
+        };
+
+        TextConsole::new(surface, GLYPH_ARRAY, FONT_MAP, FOREGROUND, BACKGROUND)
+    }
+
+    /// Returns `true` if every pixel of the character cell at (`x`, `y`) is `color`.
+    fn cell_is(console: &TextConsole<'_, TestSurface>, x: usize, y: usize, color: u32) -> bool {
+        let glyph_width = usize::from(GLYPH_ARRAY.width());
+        let glyph_height = usize::from(GLYPH_ARRAY.height());
+
+        (0..glyph_height).all(|y_offset| {
+            (0..glyph_width).all(|x_offset| {
+                let pixel_x = x * glyph_width + x_offset;
+                let pixel_y = y * glyph_height + y_offset;
+                console.surface.pixels[pixel_y * console.surface.width + pixel_x] == color
+            })
+        })
+    }
+
+    #[test]
+    fn cursor_movement() {
+        let mut console = console();
+
+        write!(console, "\x1b[3;5H").unwrap();
+        assert_eq!((console.x, console.y), (4, 2));
+        write!(console, "\x1b[2A\x1b[3D").unwrap();
+        assert_eq!((console.x, console.y), (1, 0));
+        write!(console, "\x1b[100C\x1b[100B").unwrap();
+        assert_eq!((console.x, console.y), (COLUMNS - 1, ROWS - 1));
+        write!(console, "\x1b[2F\x1b[4G").unwrap();
+        assert_eq!((console.x, console.y), (3, 1));
+
+        write!(console, "\x1b[s\x1b[H").unwrap();
+        assert_eq!((console.x, console.y), (0, 0));
+        write!(console, "\x1b[u").unwrap();
+        assert_eq!((console.x, console.y), (3, 1));
+        write!(console, "\x1b7\x1b[1;1H\x1b8").unwrap();
+        assert_eq!((console.x, console.y), (3, 1));
+    }
+
+    #[test]
+    fn graphic_rendition() {
+        let mut console = console();
+
+        write!(console, "\x1b[31;104m").unwrap();
+        assert_eq!(console.foreground, PALETTE[1]);
+        assert_eq!(console.background, PALETTE[12]);
+        write!(console, "\x1b[38;5;196;48;2;1;2;3m").unwrap();
+        assert_eq!(console.foreground, rgb(255, 0, 0));
+        assert_eq!(console.background, rgb(1, 2, 3));
+        write!(console, "\x1b[38;5;244m").unwrap();
+        assert_eq!(console.foreground, rgb(128, 128, 128));
+        write!(console, "\x1b[7m").unwrap();
+        assert_eq!(console.colors(), (rgb(1, 2, 3), rgb(128, 128, 128)));
+        write!(console, "\x1b[m").unwrap();
+        assert_eq!(console.colors(), (FOREGROUND, BACKGROUND));
+
+        // Unsupported sequences are consumed without printing anything.
+        write!(console, "\x1b[?25l\x1b[1 q").unwrap();
+        assert_eq!((console.x, console.y), (0, 0));
+    }
+
+    #[test]
+    fn erase() {
+        let mut console = console();
+        let red = PALETTE[1];
+
+        write!(console, "\x1b[41m\x1b[2J").unwrap();
+        assert!((0..ROWS).all(|y| (0..COLUMNS).all(|x| cell_is(&console, x, y, red))));
+
+        write!(console, "\x1b[49m\x1b[2;4H\x1b[K").unwrap();
+        assert!((0..3).all(|x| cell_is(&console, x, 1, red)));
+        assert!((3..COLUMNS).all(|x| cell_is(&console, x, 1, BACKGROUND)));
+        assert!(cell_is(&console, 3, 0, red));
+
+        write!(console, "\x1b[1J").unwrap();
+        assert!((0..COLUMNS).all(|x| cell_is(&console, x, 0, BACKGROUND)));
+        assert!((0..COLUMNS).all(|x| cell_is(&console, x, 2, red)));
+    }
+}
@@ -5,7 +5,7 @@ use core::{alloc::Layout, mem, ptr::NonNull};
 use conversion::usize_to_u64;
 
 use crate::platform::{
-    AllocationPolicy, Frame, FrameRange, Page, PageRange, Permissions, VirtualAddress,
+    AllocationPolicy, Frame, FrameRange, MemoryType, Page, PageRange, Permissions, VirtualAddress,
     allocate_frames_aligned, deallocate_frames, frame_size, map, page_size, translate_virt, unmap,
 };
 
@@ -16,7 +16,13 @@ pub fn allocate(layout: Layout) -> Option<NonNull<u8>> {
 
     let count = usize_to_u64(layout.size()).div_ceil(frame_size());
     let alignment = usize_to_u64(page_size()).max(frame_size());
-    let frames = allocate_frames_aligned(count, alignment, AllocationPolicy::Any).ok()?;
+    let frames = allocate_frames_aligned(
+        count,
+        alignment,
+        AllocationPolicy::Any,
+        MemoryType::BootloaderReclaimable,
+    )
+    .ok()?;
 
     let mapping = map(frames.range(), Permissions::ReadWrite).ok()?;
 
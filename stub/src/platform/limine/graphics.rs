@@ -1,6 +1,6 @@
 //! Graphical logging implementation for Limine provided [`GenericSurface`]s.
 
-use core::{fmt::Write, mem, ptr::NonNull};
+use core::{mem, ptr::NonNull};
 
 use crate::platform::{
     Console as LogConsole, Metadata,
@@ -74,7 +74,7 @@ fn write(console: NonNull<LogConsole>, metadata: Metadata, message: &str) {
     let console = unsafe { console.cast::<Console>().as_ref() };
 
     let mut text_console = console.text_console.lock();
-    let _ = write!(text_console, "[{:?}]: {message}", metadata.level);
+    text_console.write_log(metadata, message);
 }
 
 /// Creates a new [`GenericSurface`] as specified by [`FramebufferV0`].
@@ -2,6 +2,7 @@
 
 use core::{
     alloc::Layout,
+    ffi::CStr,
     fmt::Write,
     ptr::{self, NonNull},
     slice,
@@ -10,6 +11,8 @@ use core::{
 
 use conversion::{u64_to_usize, u64_to_usize_strict, usize_to_u64};
 #[cfg(target_arch = "aarch64")]
+use device_tree::Fdt;
+#[cfg(target_arch = "aarch64")]
 use limine::mp::aarch64::{MpInfo, MpResponse};
 #[cfg(target_arch = "x86_64")]
 use limine::mp::x86_64::{MpInfo, MpResponse};
@@ -18,9 +21,11 @@ use limine::{
     device_tree::{DEVICE_TREE_REQUEST_MAGIC, DeviceTreeRequest},
     efi_sys_table::{EFI_SYSTEM_TABLE_REQUEST_MAGIC, EfiSystemTableRequest},
     executable_addr::{EXECUTABLE_ADDRESS_REQUEST_MAGIC, ExecutableAddressRequest},
+    executable_cmdline::{EXECUTABLE_CMD_LINE_REQUEST_MAGIC, ExecutableCmdLineRequest},
     framebuffer::{FRAMEBUFFER_REQUEST_MAGIC, FramebufferRequest, FramebufferV0},
     hhdm::{HHDM_REQUEST_MAGIC, HhdmRequest},
     memory_map::{MEMORY_MAP_REQUEST_MAGIC, MemoryMapEntry, MemoryMapRequest, MemoryType},
+    module::{MODULE_REQUEST_MAGIC, ModuleRequestV0},
     mp::{MP_REQUEST_MAGIC, MpRequest, MpRequestFlags},
     rsdp::{RSDP_REQUEST_MAGIC, RsdpRequest},
     smbios::{SMBIOS_REQUEST_MAGIC, SmbiosRequest},
@@ -30,6 +35,7 @@ use sync::{ControlledModificationCell, Spinlock};
 use crate::{
     PANIC_HANDLER,
     arch::{
+        arch_specific::{current_hardware_id, install_exception_handlers},
         generic::memory::paging::{
             ExternalFrame, ExternalFrameRange, ExternalPage, ExternalPageRange,
             ExternalPhysicalAddress, ExternalVirtualAddress, SearchStrategy, TranslationScheme,
@@ -37,10 +43,11 @@ use crate::{
         memory::{ArchTranslationScheme, physical_bits},
     },
     platform::{
-        AllocationPolicy, Allocator, BufferTooSmall, FrameRange, MapError, MappingType,
-        MemoryDescriptor, MemoryMap, OutOfMemory, Page, PageRange, Permissions, PhysicalAddress,
-        PhysicalAddressRange, PhysicalMemoryManager, Procedure, ProcessorManager, VirtualAddress,
-        VirtualMemoryManager, frame_size,
+        AllocationPolicy, Allocator, BufferTooSmall, FrameRange, Framebuffer, MapError,
+        MappingType, MemoryAttributes, MemoryDescriptor, MemoryMap, Module, OutOfMemory, Page,
+        PageRange, Permissions, PhysicalAddress, PhysicalAddressRange, PhysicalMemoryManager,
+        Procedure, ProcessorDescriptor, ProcessorManager, VirtualAddress, VirtualMemoryManager,
+        bring_up, command_line, frame_size,
         graphics::{
             console::TextConsole,
             font::{FONT_MAP, GLYPH_ARRAY},
@@ -50,10 +57,14 @@ use crate::{
         limine::graphics::{
             create_surface, initialize_primary_framebuffer, primary_framebuffer_initialized,
         },
-        page_size, set_device_tree, set_rsdp, set_smbios_32, set_smbios_64, set_uefi_system_table,
-        set_xsdp,
+        page_size, register_module, set_command_line, set_device_tree, set_framebuffer, set_rsdp,
+        set_smbios_32, set_smbios_64, set_uefi_system_table, set_xsdp,
     },
 };
+#[cfg(target_arch = "aarch64")]
+use crate::{
+    arch::arch_specific::MPIDR_AFFINITY_MASK, platform::shared::device_tree::device_tree_processor,
+};
 
 mod graphics;
 
@@ -159,6 +170,26 @@ static SMBIOS_REQUEST: ControlledModificationCell<SmbiosRequest> =
         response: ptr::null_mut(),
     });
 
+/// Request for the modules loaded alongside the executable.
+#[used]
+#[unsafe(link_section = ".limine.requests")]
+static MODULE_REQUEST: ControlledModificationCell<ModuleRequestV0> =
+    ControlledModificationCell::new(ModuleRequestV0 {
+        id: MODULE_REQUEST_MAGIC,
+        revision: 0,
+        response: ptr::null_mut(),
+    });
+
+/// Request for the command line associated with the executable.
+#[used]
+#[unsafe(link_section = ".limine.requests")]
+static EXECUTABLE_CMD_LINE_REQUEST: ControlledModificationCell<ExecutableCmdLineRequest> =
+    ControlledModificationCell::new(ExecutableCmdLineRequest {
+        id: EXECUTABLE_CMD_LINE_REQUEST_MAGIC,
+        revision: 0,
+        response: ptr::null_mut(),
+    });
+
 /// Indicates the end of the Limine boot protocol request zone.
 #[used]
 #[unsafe(link_section = ".limine.end")]
@@ -179,6 +210,10 @@ static EXECUTABLE_PHYSICAL_BASE: ControlledModificationCell<u64> =
 static CPUS: ControlledModificationCell<&[&MpInfo]> = ControlledModificationCell::new(&[]);
 /// The [`MpInfo`] associated with the primary processor.
 static BSP: ControlledModificationCell<Option<&MpInfo>> = ControlledModificationCell::new(None);
+/// The [`Fdt`] provided by [`DEVICE_TREE_REQUEST`].
+#[cfg(target_arch = "aarch64")]
+static DEVICE_TREE: ControlledModificationCell<Option<Fdt<'static>>> =
+    ControlledModificationCell::new(None);
 /// The [`FramebufferV0`]s provided by [`FRAMEBUFFER_REQUEST`].
 static FRAMEBUFFERS: ControlledModificationCell<&[&FramebufferV0]> =
     ControlledModificationCell::new(&[]);
@@ -186,10 +221,123 @@ static FRAMEBUFFERS: ControlledModificationCell<&[&FramebufferV0]> =
 /// The [`TranslationScheme`] used to implement page mapping and unmapping.
 static TRANSLATION_SCHEME: Spinlock<Option<ArchTranslationScheme>> = Spinlock::new(None);
 
+initgraph::define_task!(
+    RESPONSES_TASK,
+    "limine.responses",
+    func = store_responses,
+    inactive,
+    requires = [],
+    dependents = [],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    CONSOLE_TASK,
+    "limine.console",
+    func = initialize_console,
+    inactive,
+    requires = ["limine.responses"],
+    dependents = ["platform.console"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    MEMORY_TASK,
+    "limine.memory",
+    func = initialize_memory,
+    inactive,
+    requires = ["limine.responses"],
+    dependents = ["platform.memory"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    PROCESSORS_TASK,
+    "limine.processors",
+    func = initialize_processors,
+    inactive,
+    requires = ["limine.responses"],
+    dependents = ["platform.processors"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    TABLES_TASK,
+    "limine.tables",
+    func = record_tables,
+    inactive,
+    requires = ["limine.responses"],
+    dependents = ["platform.tables"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    FRAMEBUFFER_TASK,
+    "limine.framebuffer",
+    func = record_framebuffer,
+    inactive,
+    requires = ["limine.responses"],
+    dependents = ["platform.framebuffer"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    COMMAND_LINE_TASK,
+    "limine.command_line",
+    func = record_command_line,
+    inactive,
+    requires = ["limine.responses"],
+    dependents = ["platform.command_line"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    MODULES_TASK,
+    "limine.modules",
+    func = register_modules,
+    inactive,
+    requires = ["limine.responses"],
+    dependents = ["platform.modules"],
+    after = [],
+    before = [],
+);
+
 /// Rust entrypoint for the Limine boot protocol.
 pub extern "C" fn limine_main() -> ! {
     *PANIC_HANDLER.lock() = panic_handler;
+    // SAFETY:
+    //
+    // The code segment provided by Limine remains in use for the lifetime of the stub, while the
+    // Limine boot protocol leaves the IDT and exception vector table unspecified.
+    unsafe { install_exception_handlers(false) }
+
+    for task in [
+        &RESPONSES_TASK,
+        &CONSOLE_TASK,
+        &MEMORY_TASK,
+        &PROCESSORS_TASK,
+        &TABLES_TASK,
+        &FRAMEBUFFER_TASK,
+        &COMMAND_LINE_TASK,
+        &MODULES_TASK,
+    ] {
+        task.activate();
+    }
+    bring_up();
+
+    crate::debug!("Image Start: {:#x}", crate::util::image_start());
+    match crate::stub_main(command_line()) {
+        Ok(()) => {}
+        Err(error) => crate::error!("{error}"),
+    };
+
+    loop {
+        core::hint::spin_loop()
+    }
+}
 
+/// Validates the Limine responses and stores their contents.
+fn store_responses() {
     let (
         memory_map_entries,
         hhdm_offset,
@@ -211,12 +359,18 @@ pub extern "C" fn limine_main() -> ! {
         *FRAMEBUFFERS.get_mut() = framebuffers;
         *CPUS.get_mut() = cpu_info_buffer;
     }
+}
 
+/// Initializes the console on the primary framebuffer.
+fn initialize_console() {
     // SAFETY:
     //
     // This function occurs before processor bring-up.
     unsafe { initialize_primary_framebuffer() }
+}
 
+/// Takes over the active address space and initializes memory management.
+fn initialize_memory() {
     let mut address_impl = TRANSLATION_SCHEME.lock();
 
     let chunk_size = {
@@ -245,10 +399,9 @@ pub extern "C" fn limine_main() -> ! {
         initialize_physical_memory_manager(&LimineImpl);
         initialize_virtual_memory_manager(&LimineImpl);
         initialize_allocator(&LimineImpl);
-        initialize_processor_management(&LimineImpl);
     }
 
-    crate::platform::frame_allocator::initialize(memory_map_entries.iter().map(|entry| {
+    crate::platform::frame_allocator::initialize(MEMORY_MAP_ENTRIES.get().iter().map(|entry| {
         let start = PhysicalAddress::new(entry.base);
         let range = PhysicalAddressRange::new(start, entry.length);
 
@@ -264,14 +417,43 @@ pub extern "C" fn limine_main() -> ! {
             MemoryType::BAD_MEMORY => crate::platform::MemoryType::Bad,
             MemoryType::ACPI_RECLAIMABLE => crate::platform::MemoryType::AcpiReclaimable,
             MemoryType::ACPI_NVS => crate::platform::MemoryType::AcpiNonVolatile,
+            MemoryType::FRAMEBUFFER => crate::platform::MemoryType::Framebuffer,
             MemoryType::RESERVED_MAPPED => crate::platform::MemoryType::Reserved,
             _ => crate::platform::MemoryType::Reserved,
         };
-        MemoryDescriptor { range, region_type }
+
+        // Limine does not report caching capabilities, but every type other than the reserved
+        // ones and the framebuffer describes RAM.
+        let attributes = match entry.mem_type {
+            MemoryType::USABLE
+            | MemoryType::BOOTLOADER_RECLAIMABLE
+            | MemoryType::EXECUTABLE_AND_MODULES
+            | MemoryType::ACPI_RECLAIMABLE
+            | MemoryType::ACPI_NVS => MemoryAttributes::WRITE_BACK,
+            MemoryType::FRAMEBUFFER => {
+                MemoryAttributes::UNCACHEABLE | MemoryAttributes::WRITE_COMBINING
+            }
+            _ => MemoryAttributes::NONE,
+        };
+
+        MemoryDescriptor {
+            range,
+            region_type,
+            attributes,
+        }
     }));
+}
+
+/// Parks the application processors in [`ap_loop()`] and initializes processor management.
+fn initialize_processors() {
+    // SAFETY:
+    //
+    // This initialization occurs before any calls to the processor management subsystem.
+    unsafe { initialize_processor_management(&LimineImpl) }
 
-    if !cpu_info_buffer.is_empty() {
-        for (index, cpu_info) in cpu_info_buffer.iter().enumerate() {
+    let cpus = CPUS.get();
+    if !cpus.is_empty() {
+        for (index, cpu_info) in cpus.iter().enumerate() {
             cpu_info
                 .extra_argument
                 .store(usize_to_u64(index), Ordering::Relaxed);
@@ -311,7 +493,10 @@ pub extern "C" fn limine_main() -> ! {
             unreachable!()
         }
     }
+}
 
+/// Records the locations of the firmware tables provided by Limine.
+fn record_tables() {
     'uefi_system_table: {
         let uefi_system_table_response_ptr = UEFI_SYSTEM_TABLE_REQUEST.get().response;
 
@@ -324,15 +509,9 @@ pub extern "C" fn limine_main() -> ! {
             break 'uefi_system_table;
         };
 
-        // SAFETY:
-        //
-        // No other cores are active at this time and thus no calls to [`set_uefi_system_table()`] or
-        // [`uefi_system_table()`] can overlap.
-        unsafe {
-            set_uefi_system_table(PhysicalAddress::new(
-                uefi_system_table_response.address - HHDM_OFFSET.get(),
-            ))
-        }
+        set_uefi_system_table(PhysicalAddress::new(
+            uefi_system_table_response.address - HHDM_OFFSET.get(),
+        ));
     }
 
     'rsdp: {
@@ -347,15 +526,8 @@ pub extern "C" fn limine_main() -> ! {
         };
 
         let address = PhysicalAddress::new(rsdp_response.address as u64 - HHDM_OFFSET.get());
-        // SAFETY:
-        //
-        // No other cores are active at this time and thus no calls to [`set_rsdp()`],
-        // [`set_xsdp()`], [`rsdp()`], or [`xsdp()`] can overlap.
-        #[expect(clippy::multiple_unsafe_ops_per_block)]
-        unsafe {
-            set_rsdp(address);
-            set_xsdp(address);
-        }
+        set_rsdp(address);
+        set_xsdp(address);
     }
 
     'device_tree: {
@@ -369,14 +541,22 @@ pub extern "C" fn limine_main() -> ! {
             break 'device_tree;
         };
 
-        // SAFETY:
-        //
-        // No other cores are active at this time and thus no calls to [`set_device_tree()`] or
-        // [`device_tree()`] can overlap.
-        unsafe {
-            set_device_tree(PhysicalAddress::new(
-                device_tree_response.dtb_ptr as u64 - HHDM_OFFSET.get(),
-            ))
+        set_device_tree(PhysicalAddress::new(
+            device_tree_response.dtb_ptr as u64 - HHDM_OFFSET.get(),
+        ));
+
+        #[cfg(target_arch = "aarch64")]
+        {
+            // SAFETY:
+            //
+            // The Limine bootloader specification states that the device tree response points to
+            // a valid flattened device tree.
+            let fdt = unsafe { Fdt::from_ptr(device_tree_response.dtb_ptr.cast()) };
+            // SAFETY:
+            //
+            // No other cores are active at this time and thus no accesses to [`DEVICE_TREE`] can
+            // overlap.
+            unsafe { *DEVICE_TREE.get_mut() = fdt }
         }
     }
 
@@ -391,29 +571,131 @@ pub extern "C" fn limine_main() -> ! {
             break 'smbios;
         };
 
+        set_smbios_32(PhysicalAddress::new(
+            (smbios_response.entry_32 as u64).saturating_sub(*HHDM_OFFSET.get()),
+        ));
+        set_smbios_64(PhysicalAddress::new(
+            (smbios_response.entry_64 as u64).saturating_sub(*HHDM_OFFSET.get()),
+        ));
+    }
+}
+
+/// Records the primary framebuffer provided by Limine.
+fn record_framebuffer() {
+    if let [framebuffer, ..] = FRAMEBUFFERS.get() {
+        let framebuffer = Framebuffer {
+            address: PhysicalAddress::new(
+                usize_to_u64(framebuffer.address.addr()).saturating_sub(*HHDM_OFFSET.get()),
+            ),
+            width: framebuffer.width,
+            height: framebuffer.height,
+            pitch: framebuffer.pitch,
+            bpp: framebuffer.bpp,
+            red_mask_size: framebuffer.red_mask_size,
+            red_mask_shift: framebuffer.red_mask_shift,
+            green_mask_size: framebuffer.green_mask_size,
+            green_mask_shift: framebuffer.green_mask_shift,
+            blue_mask_size: framebuffer.blue_mask_size,
+            blue_mask_shift: framebuffer.blue_mask_shift,
+        };
+
         // SAFETY:
         //
-        // No other cores are active at this time and thus no calls to [`set_smbios_32()`],
-        // [`set_smbios_64()`], [`smbios_32()`], or [`smbios_64()`] can overlap.
-        #[expect(clippy::multiple_unsafe_ops_per_block)]
-        unsafe {
-            set_smbios_32(PhysicalAddress::new(
-                (smbios_response.entry_32 as u64).saturating_sub(*HHDM_OFFSET.get()),
-            ));
-            set_smbios_64(PhysicalAddress::new(
-                (smbios_response.entry_64 as u64).saturating_sub(*HHDM_OFFSET.get()),
-            ));
-        }
+        // No other cores are active at this time and thus no calls to [`set_framebuffer()`] or
+        // [`framebuffer()`] can overlap.
+        unsafe { set_framebuffer(framebuffer) }
     }
+}
 
-    crate::debug!("Image Start: {:#x}", crate::util::image_start());
-    match crate::stub_main() {
-        Ok(()) => {}
-        Err(error) => crate::error!("{error}"),
+/// Records the command line associated with the executable.
+fn record_command_line() {
+    let response_ptr = EXECUTABLE_CMD_LINE_REQUEST.get().response;
+
+    // SAFETY:
+    //
+    // The Limine bootloader specification states that if the response pointer has changed (and it
+    // has if it isn't NULL), then the executable command line response is valid.
+    let Some(response) = (unsafe { response_ptr.as_ref() }) else {
+        return;
     };
+    if response.cmd_line.is_null() {
+        return;
+    }
 
-    loop {
-        core::hint::spin_loop()
+    // SAFETY:
+    //
+    // The Limine bootloader specification states that `cmd_line` is a valid NUL-terminated
+    // string.
+    let command_line = unsafe { CStr::from_ptr(response.cmd_line) };
+    if let Ok(command_line) = command_line.to_str() {
+        // SAFETY:
+        //
+        // Platform bring-up runs before the stub starts any other processor.
+        unsafe { set_command_line(command_line) }
+    }
+}
+
+/// Registers the modules loaded alongside the executable.
+fn register_modules() {
+    'modules: {
+        let module_response_ptr = MODULE_REQUEST.get().response;
+
+        // SAFETY:
+        //
+        // The Limine bootloader specification states that if the response pointer has changed (and it
+        // has if it isn't NULL), then the module response is valid.
+        let Some(module_response) = (unsafe { module_response_ptr.as_ref() }) else {
+            break 'modules;
+        };
+        if module_response.modules.is_null() {
+            break 'modules;
+        }
+
+        // SAFETY:
+        //
+        // The Limine bootloader specification states that `modules` points to an array of
+        // `module_count` pointers to valid [`File`][f] structures.
+        //
+        // [f]: limine::executable::File
+        let files = unsafe {
+            slice::from_raw_parts(
+                module_response.modules,
+                u64_to_usize_strict(module_response.module_count),
+            )
+        };
+        for &file in files {
+            // SAFETY:
+            //
+            // The Limine bootloader specification states that each pointer in `modules` is valid.
+            let Some(file) = (unsafe { file.as_ref() }) else {
+                continue;
+            };
+
+            // SAFETY:
+            //
+            // The Limine bootloader specification states that `path` is a valid NUL-terminated
+            // string.
+            let name = unsafe { CStr::from_ptr(file.path) };
+            // SAFETY:
+            //
+            // The Limine bootloader specification states that `command_line` is a valid
+            // NUL-terminated string.
+            let command_line = unsafe { CStr::from_ptr(file.command_line) };
+
+            let module = Module {
+                range: PhysicalAddressRange::new(
+                    PhysicalAddress::new(
+                        usize_to_u64(file.address.addr()).saturating_sub(*HHDM_OFFSET.get()),
+                    ),
+                    file.size,
+                ),
+                name: name.to_str().unwrap_or_default(),
+                command_line: command_line.to_str().unwrap_or_default(),
+            };
+            if !register_module(module) {
+                crate::warn!("too many modules: ignoring {:?}", module.name);
+            }
+        }
     }
 }
 
@@ -425,8 +707,9 @@ impl PhysicalMemoryManager for LimineImpl {
         &self,
         count: u64,
         policy: AllocationPolicy,
+        region_type: crate::platform::MemoryType,
     ) -> Result<FrameRange, OutOfMemory> {
-        crate::platform::frame_allocator::allocate_frames(count, policy)
+        crate::platform::frame_allocator::allocate_frames(count, policy, region_type)
     }
 
     unsafe fn deallocate_frames(&self, range: FrameRange) {
@@ -647,7 +930,7 @@ impl VirtualMemoryManager for LimineImpl {
         //
         // The invariants of [`VirtualMemoryManager::unmap()`] fulfill the invariants of
         // [`ArchTranslationScheme::unmap()`].
-        unsafe { scheme.unmap(input) }
+        unsafe { scheme.unmap(input) }.expect("failed to allocate table to split stub mapping");
 
         #[cfg(target_arch = "x86_64")]
         for page in range.iter() {
@@ -677,13 +960,39 @@ impl ProcessorManager for LimineImpl {
     }
 
     fn current_processor_id(&self) -> u64 {
-        todo!()
+        // Processor IDs are indices into the Limine MP response, which describes every processor
+        // (including the main processor) exactly once. Without an MP response, the main processor
+        // is the only processor.
+        let hardware_id = current_hardware_id();
+        CPUS.get()
+            .iter()
+            .position(|cpu| mp_info_hardware_id(cpu) == hardware_id)
+            .map_or(0, usize_to_u64)
     }
 
     fn processor_count(&self) -> u64 {
         usize_to_u64(CPUS.get().len())
     }
 
+    fn processor_descriptor(&self, processor_id: u64) -> ProcessorDescriptor {
+        let cpu = CPUS.get()[u64_to_usize_strict(processor_id)];
+
+        let hardware_id = mp_info_hardware_id(cpu);
+        #[cfg(target_arch = "aarch64")]
+        let proximity_domain = DEVICE_TREE
+            .get()
+            .and_then(|fdt| device_tree_processor(fdt.root(), hardware_id))
+            .and_then(|descriptor| descriptor.proximity_domain);
+        #[cfg(target_arch = "x86_64")]
+        let proximity_domain = None;
+
+        ProcessorDescriptor {
+            hardware_id,
+            enabled: true,
+            proximity_domain,
+        }
+    }
+
     fn run_on_all_processors(&self, procedure: Procedure, argument: *mut ()) {
         if CPUS.get().len() > 1 {
             let proc = BSP.get().expect("failure in initialization");
@@ -729,6 +1038,17 @@ impl ProcessorManager for LimineImpl {
     }
 }
 
+/// Returns the architectural identifier of the processor described by `cpu`, in the form returned
+/// by [`current_hardware_id()`].
+fn mp_info_hardware_id(cpu: &MpInfo) -> u64 {
+    #[cfg(target_arch = "aarch64")]
+    let hardware_id = cpu.mpidr & MPIDR_AFFINITY_MASK;
+    #[cfg(target_arch = "x86_64")]
+    let hardware_id = u64::from(cpu.lapic_id);
+
+    hardware_id
+}
+
 /// Validates that the required Limine requests have been fulfilled and returns the contents of
 /// those responses.
 fn validate_required_tables() -> (
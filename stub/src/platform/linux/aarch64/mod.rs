@@ -11,22 +11,92 @@ use core::{
 use conversion::{u32_to_usize, u64_to_usize, u64_to_usize_strict, usize_to_u64};
 use device_tree::{Fdt, raw::FdtHeader};
 use pe::raw::{DosHeader, NtHeaders64, SectionHeader};
+use sync::ControlledModificationCell;
 use uefi::table::{config, system::SystemTable};
 
 use crate::{
     PANIC_HANDLER,
-    arch::memory::physical_bits,
+    arch::{
+        arch_specific::{current_hardware_id, install_exception_handlers},
+        memory::physical_bits,
+    },
     platform::{
         AllocationPolicy, Allocator, BufferTooSmall, Frame, FrameRange, MapError, MappingType,
-        MemoryDescriptor, MemoryMap, MemoryType, OutOfMemory, Page, PageRange, Permissions,
-        PhysicalAddress, PhysicalAddressRange, PhysicalMemoryManager, Procedure, ProcessorManager,
-        VirtualAddress, VirtualAddressRange, VirtualMemoryManager, frame_allocator,
-        initialize_allocator, initialize_memory_config, initialize_physical_memory_manager,
-        initialize_processor_management, initialize_virtual_memory_manager, page_size,
-        set_device_tree, set_rsdp, set_smbios_32, set_smbios_64, set_uefi_system_table, set_xsdp,
+        MemoryAttributes, MemoryDescriptor, MemoryMap, MemoryType, Module, OutOfMemory, Page,
+        PageRange, Permissions, PhysicalAddress, PhysicalAddressRange, PhysicalMemoryManager,
+        Procedure, ProcessorDescriptor, ProcessorManager, VirtualAddress, VirtualAddressRange,
+        VirtualMemoryManager, bring_up, command_line, frame_allocator, initialize_allocator,
+        initialize_memory_config, initialize_physical_memory_manager,
+        initialize_processor_management, initialize_virtual_memory_manager,
+        linux::{ENTRY_ARGUMENTS, EntryArguments},
+        page_size, register_module, set_command_line, set_device_tree, set_rsdp, set_smbios_32,
+        set_smbios_64, set_uefi_system_table, set_xsdp,
+        shared::{
+            device_tree::{chosen_bootargs, chosen_initrd, device_tree_processor},
+            uefi::uefi_memory_descriptor,
+        },
     },
 };
 
+/// The [`ProcessorDescriptor`] of the boot processor.
+static BOOT_PROCESSOR: ControlledModificationCell<ProcessorDescriptor> =
+    ControlledModificationCell::new(ProcessorDescriptor {
+        hardware_id: 0,
+        enabled: true,
+        proximity_domain: None,
+    });
+
+initgraph::define_task!(
+    MEMORY_TASK,
+    "linux.memory",
+    func = initialize_memory,
+    inactive,
+    requires = [],
+    dependents = ["platform.memory"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    PROCESSORS_TASK,
+    "linux.processors",
+    func = initialize_processors,
+    inactive,
+    requires = [],
+    dependents = ["platform.processors"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    TABLES_TASK,
+    "linux.tables",
+    func = record_tables,
+    inactive,
+    requires = [],
+    dependents = ["platform.tables"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    COMMAND_LINE_TASK,
+    "linux.command_line",
+    func = record_command_line,
+    inactive,
+    requires = ["linux.memory"],
+    dependents = ["platform.command_line"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    MODULES_TASK,
+    "linux.modules",
+    func = register_initrd,
+    inactive,
+    requires = ["linux.memory"],
+    dependents = ["platform.modules"],
+    after = [],
+    before = [],
+);
+
 /// Rust entry point for the Linux boot protocol on `aarch64`.
 pub extern "C" fn linux_main(
     dtb_ptr: *mut FdtHeader,
@@ -36,41 +106,82 @@ pub extern "C" fn linux_main(
     stack_size: u64,
 ) -> ! {
     *PANIC_HANDLER.lock() = panic_handler;
-
-    initialize_memory_config(512, physical_bits(), 512);
-    let fdt_phys_addr = PhysicalAddress::new(dtb_ptr as u64);
-
     // SAFETY:
     //
-    // This function is called before any call to the matching [`device_tree()`].
-    unsafe {
-        set_device_tree(fdt_phys_addr);
-    }
+    // The Linux boot protocol leaves the exception vector table unspecified.
+    unsafe { install_exception_handlers(false) }
 
     // SAFETY:
     //
-    // These functions are called before any calls to their respective subsystems.
-    #[expect(clippy::multiple_unsafe_ops_per_block)]
+    // This occurs before any of the bring-up tasks access [`ENTRY_ARGUMENTS`].
     unsafe {
-        initialize_virtual_memory_manager(&LinuxImpl);
-        initialize_processor_management(&LinuxImpl);
+        *ENTRY_ARGUMENTS.get_mut() = EntryArguments {
+            boot_information: dtb_ptr as u64,
+            image_start,
+            image_size,
+            stack_start,
+            stack_size,
+        };
+    }
+
+    for task in [
+        &MEMORY_TASK,
+        &PROCESSORS_TASK,
+        &TABLES_TASK,
+        &COMMAND_LINE_TASK,
+        &MODULES_TASK,
+    ] {
+        task.activate();
+    }
+    bring_up();
+
+    match crate::stub_main(command_line()) {
+        Ok(()) => {}
+        Err(error) => crate::error!("{error}"),
     }
 
+    loop {
+        core::hint::spin_loop()
+    }
+}
+
+/// Initializes memory management from the UEFI memory map or the `/memory` nodes of the
+/// flattened device tree.
+fn initialize_memory() {
+    let arguments = ENTRY_ARGUMENTS.copy();
+
+    initialize_memory_config(512, physical_bits(), 512);
+
     // SAFETY:
     //
-    // The `linux` boot protocol requires that the FDT is valid, while `revm-stub` properly manages
-    // memory to ensure that `fdt` has exclusive access.
-    let fdt = unsafe { Fdt::from_ptr(dtb_ptr).expect("flattened device tree must be valid") };
+    // [`initialize_virtual_memory_manager()`] is called before any call to its subsystem.
+    unsafe { initialize_virtual_memory_manager(&LinuxImpl) }
+
+    let fdt = boot_device_tree();
     let root = fdt.root();
     let chosen = root.find_node(c"chosen").expect("`chosen` node must exist");
 
     let image_iter = iter::once_with(|| MemoryDescriptor {
-        range: PhysicalAddressRange::new(PhysicalAddress::new(image_start), image_size),
+        range: PhysicalAddressRange::new(
+            PhysicalAddress::new(arguments.image_start),
+            arguments.image_size,
+        ),
         region_type: MemoryType::BootloaderReclaimable,
+        attributes: MemoryAttributes::NONE,
     });
     let stack_iter = iter::once_with(|| MemoryDescriptor {
-        range: PhysicalAddressRange::new(PhysicalAddress::new(stack_start), stack_size),
+        range: PhysicalAddressRange::new(
+            PhysicalAddress::new(arguments.stack_start),
+            arguments.stack_size,
+        ),
+        region_type: MemoryType::BootloaderReclaimable,
+        attributes: MemoryAttributes::NONE,
+    });
+    let initrd = chosen_initrd(chosen);
+    let initrd_iter = initrd.map(|range| MemoryDescriptor {
+        range,
         region_type: MemoryType::BootloaderReclaimable,
+        attributes: MemoryAttributes::NONE,
     });
     if let Some(mmap_start) = chosen.find_property(c"linux,uefi-mmap-start") {
         let mmap_start = mmap_start
@@ -104,28 +215,15 @@ pub extern "C" fn linux_main(
             //
             // The `linux` boot protocol requires that the FDT is valid.
             let desc = unsafe { ptr.cast::<uefi::memory::MemoryDescriptor>().read() };
-            let region_type = match desc.region_type {
-                uefi::memory::MemoryType::CONVENTIONAL => MemoryType::Free,
-                uefi::memory::MemoryType::LOADER_CODE => MemoryType::BootloaderReclaimable,
-                uefi::memory::MemoryType::LOADER_DATA => MemoryType::BootloaderReclaimable,
-                uefi::memory::MemoryType::BOOT_SERVICES_CODE => MemoryType::BootloaderReclaimable,
-                uefi::memory::MemoryType::BOOT_SERVICES_DATA => MemoryType::BootloaderReclaimable,
-                uefi::memory::MemoryType::UNUSABLE => MemoryType::Bad,
-                uefi::memory::MemoryType::ACPI_RECLAIM => MemoryType::AcpiReclaimable,
-                uefi::memory::MemoryType::ACPI_NVS => MemoryType::AcpiNonVolatile,
-                _ => MemoryType::Reserved,
-            };
-
-            MemoryDescriptor {
-                range: PhysicalAddressRange::new(
-                    PhysicalAddress::new(desc.physical_start),
-                    desc.number_of_pages.strict_mul(4096),
-                ),
-                region_type,
-            }
+            uefi_memory_descriptor(&desc)
         });
 
-        frame_allocator::initialize(entry_iter.chain(image_iter).chain(stack_iter));
+        frame_allocator::initialize(
+            entry_iter
+                .chain(image_iter)
+                .chain(stack_iter)
+                .chain(initrd_iter),
+        );
     } else {
         let address_cells = root
             .find_property(c"#address-cells")
@@ -175,9 +273,11 @@ pub extern "C" fn linux_main(
 
                     let range = PhysicalAddressRange::new(PhysicalAddress::new(address), size);
 
+                    // `/memory` nodes describe RAM, which is always write-back cacheable.
                     Some(MemoryDescriptor {
                         range,
                         region_type: MemoryType::Free,
+                        attributes: MemoryAttributes::WRITE_BACK,
                     })
                 }))
             })
@@ -217,6 +317,7 @@ pub extern "C" fn linux_main(
         let fdt_iter = iter::once(MemoryDescriptor {
             range: fdt_range,
             region_type: fdt_range_type,
+            attributes: MemoryAttributes::NONE,
         });
 
         let rsvmap_iter = fdt.reserve_entries().map(|entry| {
@@ -225,6 +326,7 @@ pub extern "C" fn linux_main(
             MemoryDescriptor {
                 range,
                 region_type: MemoryType::Reserved,
+                attributes: MemoryAttributes::NONE,
             }
         });
 
@@ -233,7 +335,8 @@ pub extern "C" fn linux_main(
                 .chain(fdt_iter)
                 .chain(rsvmap_iter)
                 .chain(image_iter)
-                .chain(stack_iter),
+                .chain(stack_iter)
+                .chain(initrd_iter),
         );
     }
 
@@ -245,6 +348,41 @@ pub extern "C" fn linux_main(
         initialize_physical_memory_manager(&LinuxImpl);
         initialize_allocator(&LinuxImpl);
     }
+}
+
+/// Initializes processor management and records the [`ProcessorDescriptor`] of the boot
+/// processor.
+fn initialize_processors() {
+    // SAFETY:
+    //
+    // [`initialize_processor_management()`] is called before any call to its subsystem.
+    unsafe { initialize_processor_management(&LinuxImpl) }
+
+    let fdt = boot_device_tree();
+    let root = fdt.root();
+
+    let hardware_id = current_hardware_id();
+    let boot_processor = device_tree_processor(root, hardware_id).unwrap_or(ProcessorDescriptor {
+        hardware_id,
+        enabled: true,
+        proximity_domain: None,
+    });
+    // SAFETY:
+    //
+    // No other cores are active at this time and thus no accesses to [`BOOT_PROCESSOR`] can
+    // overlap.
+    unsafe { *BOOT_PROCESSOR.get_mut() = boot_processor }
+}
+
+/// Records the flattened device tree and the tables provided by the UEFI system table.
+fn record_tables() {
+    set_device_tree(PhysicalAddress::new(ENTRY_ARGUMENTS.get().boot_information));
+
+    let fdt = boot_device_tree();
+    let chosen = fdt
+        .root()
+        .find_node(c"chosen")
+        .expect("`chosen` node must exist");
 
     'uefi_system_table: {
         let Some(uefi_system_table) = chosen.find_property(c"linux,uefi-system-table") else {
@@ -258,7 +396,7 @@ pub extern "C" fn linux_main(
         let uefi_system_table =
             ptr::without_provenance::<SystemTable>(u64_to_usize(uefi_system_table_address));
 
-        let tables: [(::uefi::data_type::Guid, unsafe fn(PhysicalAddress)); 5] = [
+        let tables: [(::uefi::data_type::Guid, fn(PhysicalAddress)); 5] = [
             (config::ACPI, set_rsdp),
             (config::ACPI_2, set_xsdp),
             (config::DEVICE_TREE, set_device_tree),
@@ -283,32 +421,61 @@ pub extern "C" fn linux_main(
         for table in config_tables {
             for (guid, set) in tables {
                 if table.vendor_guid == guid {
-                    // SAFETY:
-                    //
-                    // There are zero overlapping calls to the getter and setter functions.
-                    unsafe {
-                        set(PhysicalAddress::new(usize_to_u64(
-                            table.vendor_table.addr(),
-                        )));
-                    }
+                    set(PhysicalAddress::new(usize_to_u64(
+                        table.vendor_table.addr(),
+                    )));
                 }
             }
         }
 
+        set_uefi_system_table(PhysicalAddress::new(uefi_system_table_address));
+    }
+}
+
+/// Records the `bootargs` property of the `/chosen` node as the command line.
+fn record_command_line() {
+    let fdt = boot_device_tree();
+    let chosen = fdt
+        .root()
+        .find_node(c"chosen")
+        .expect("`chosen` node must exist");
+
+    if let Some(command_line) = chosen_bootargs(chosen) {
         // SAFETY:
         //
-        // This function is called before any calls to the matching [`uefi_system_table()`].
-        unsafe { set_uefi_system_table(PhysicalAddress::new(uefi_system_table_address)) }
+        // Platform bring-up runs before the stub starts any other processor.
+        unsafe { set_command_line(command_line) }
     }
+}
 
-    match crate::stub_main() {
-        Ok(()) => {}
-        Err(error) => crate::error!("{error}"),
+/// Registers the initial ramdisk as a module, if one was loaded.
+fn register_initrd() {
+    let fdt = boot_device_tree();
+    let chosen = fdt
+        .root()
+        .find_node(c"chosen")
+        .expect("`chosen` node must exist");
+
+    if let Some(range) = chosen_initrd(chosen) {
+        register_module(Module {
+            range,
+            name: "initrd",
+            command_line: "",
+        });
     }
+}
 
-    loop {
-        core::hint::spin_loop()
-    }
+/// Returns the [`Fdt`] passed to [`linux_main()`].
+fn boot_device_tree() -> Fdt<'static> {
+    let dtb_ptr = ptr::with_exposed_provenance_mut::<FdtHeader>(u64_to_usize_strict(
+        ENTRY_ARGUMENTS.get().boot_information,
+    ));
+
+    // SAFETY:
+    //
+    // The `linux` boot protocol requires that the FDT is valid, while `revm-stub` properly manages
+    // memory to ensure that `fdt` has exclusive access.
+    unsafe { Fdt::from_ptr(dtb_ptr).expect("flattened device tree must be valid") }
 }
 
 /// Zero-sized implementation of most platform abstractions.
@@ -319,8 +486,9 @@ impl PhysicalMemoryManager for LinuxImpl {
         &self,
         count: u64,
         policy: AllocationPolicy,
+        region_type: MemoryType,
     ) -> Result<FrameRange, OutOfMemory> {
-        crate::platform::frame_allocator::allocate_frames(count, policy)
+        crate::platform::frame_allocator::allocate_frames(count, policy, region_type)
     }
 
     unsafe fn deallocate_frames(&self, range: FrameRange) {
@@ -424,6 +592,10 @@ impl ProcessorManager for LinuxImpl {
         1
     }
 
+    fn processor_descriptor(&self, _: u64) -> ProcessorDescriptor {
+        *BOOT_PROCESSOR.get()
+    }
+
     fn run_on_all_processors(&self, procedure: Procedure, argument: *mut ()) {
         if self.processor_count() != 1 {
             todo!("implement processor bring-up")
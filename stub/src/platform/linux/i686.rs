@@ -3,7 +3,6 @@
 use core::{
     alloc::Layout,
     arch::global_asm,
-    fmt::Write,
     iter, mem,
     ptr::{self, NonNull},
 };
@@ -15,21 +14,30 @@ use sync::Spinlock;
 
 use crate::{
     PANIC_HANDLER,
-    arch::{arch_specific::load_gdt, memory::physical_bits},
+    arch::{
+        arch_specific::{current_hardware_id, install_exception_handlers, load_gdt},
+        memory::physical_bits,
+    },
     platform::{
         AllocationPolicy, Allocator, BufferTooSmall, Console, Frame, FrameRange, MapError,
-        MappingType, MemoryDescriptor, MemoryMap, MemoryType, Metadata, OutOfMemory, Page,
-        PageRange, Permissions, PhysicalAddress, PhysicalAddressRange, PhysicalMemoryManager,
-        Procedure, ProcessorManager, VirtualAddress, VirtualAddressRange, VirtualMemoryManager,
+        MappingType, MemoryAttributes, MemoryDescriptor, MemoryMap, MemoryType, Metadata, Module,
+        OutOfMemory, Page, PageRange, Permissions, PhysicalAddress, PhysicalAddressRange,
+        PhysicalMemoryManager, Procedure, ProcessorDescriptor, ProcessorManager, VirtualAddress,
+        VirtualAddressRange, VirtualMemoryManager, bring_up, command_line,
         graphics::{
             console::TextConsole,
             font::{FONT_MAP, GLYPH_ARRAY},
             surface::GenericSurface,
         },
         initialize_allocator, initialize_memory_config, initialize_physical_memory_manager,
-        initialize_processor_management, initialize_virtual_memory_manager, page_size,
-        register_console, set_rsdp, set_uefi_system_table, set_xsdp,
-        shared::linux::E820Iter,
+        initialize_processor_management, initialize_virtual_memory_manager,
+        linux::{ENTRY_ARGUMENTS, EntryArguments},
+        page_size, register_console, register_module, set_command_line, set_framebuffer, set_rsdp,
+        set_uefi_system_table, set_xsdp,
+        shared::linux::{
+            E820Iter, boot_params_command_line, boot_params_initrd, e820_memory_descriptor,
+            read_command_line, screen_info_framebuffer,
+        },
     },
 };
 
@@ -39,6 +47,77 @@ static CONSOLE: Console = Console::new(write);
 /// Location of the [`TextConsole`] that utilizes [`ScreenInfo`].
 static TEXT_CONSOLE: Spinlock<Option<TextConsole<GenericSurface>>> = Spinlock::new(None);
 
+initgraph::define_task!(
+    CONSOLE_TASK,
+    "linux.console",
+    func = initialize_console,
+    inactive,
+    requires = [],
+    dependents = ["platform.console"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    MEMORY_TASK,
+    "linux.memory",
+    func = initialize_memory,
+    inactive,
+    requires = [],
+    dependents = ["platform.memory"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    PROCESSORS_TASK,
+    "linux.processors",
+    func = initialize_processors,
+    inactive,
+    requires = [],
+    dependents = ["platform.processors"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    TABLES_TASK,
+    "linux.tables",
+    func = record_tables,
+    inactive,
+    requires = [],
+    dependents = ["platform.tables"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    FRAMEBUFFER_TASK,
+    "linux.framebuffer",
+    func = record_framebuffer,
+    inactive,
+    requires = [],
+    dependents = ["platform.framebuffer"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    COMMAND_LINE_TASK,
+    "linux.command_line",
+    func = record_command_line,
+    inactive,
+    requires = ["linux.memory"],
+    dependents = ["platform.command_line"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    MODULES_TASK,
+    "linux.modules",
+    func = register_initrd,
+    inactive,
+    requires = ["linux.memory"],
+    dependents = ["platform.modules"],
+    after = [],
+    before = [],
+);
+
 /// Rust entrypoint for the Linux boot protocol.
 pub extern "C" fn linux_main(
     boot_params_ptr: *mut BootParams,
@@ -51,19 +130,57 @@ pub extern "C" fn linux_main(
     //
     // This system has exclusive control over its system state.
     unsafe { load_gdt() }
+    // SAFETY:
+    //
+    // The GDT loaded above remains in use for the lifetime of the stub, while the Linux boot
+    // protocol leaves the IDT unspecified.
+    unsafe { install_exception_handlers(false) }
 
     *PANIC_HANDLER.lock() = panic_handler;
 
     // SAFETY:
     //
-    // The linux boot protocol ensure that we have exclusive control over the system and that the
-    // provided pointer is non-zero and properly aligned.
-    let boot_params = unsafe { &mut *boot_params_ptr };
+    // This occurs before any of the bring-up tasks access [`ENTRY_ARGUMENTS`].
+    unsafe {
+        *ENTRY_ARGUMENTS.get_mut() = EntryArguments {
+            boot_information: boot_params_ptr as u64,
+            image_start: u64::from(image_start),
+            image_size: u64::from(image_size),
+            stack_start: u64::from(stack_start),
+            stack_size: u64::from(stack_size),
+        };
+    }
+
+    for task in [
+        &CONSOLE_TASK,
+        &MEMORY_TASK,
+        &PROCESSORS_TASK,
+        &TABLES_TASK,
+        &FRAMEBUFFER_TASK,
+        &COMMAND_LINE_TASK,
+        &MODULES_TASK,
+    ] {
+        task.activate();
+    }
+    bring_up();
 
+    crate::debug!("Image Start: {:#x}", crate::util::image_start());
+    match crate::stub_main(command_line()) {
+        Ok(()) => {}
+        Err(error) => crate::error!("{error}"),
+    }
+
+    loop {
+        core::hint::spin_loop()
+    }
+}
+
+/// Initializes the console on the framebuffer described by the [`ScreenInfo`].
+fn initialize_console() {
     // SAFETY:
     //
     // This occurs before any accesses to [`SURFACE`] and provides exclusive access to [`CONSOLE`].
-    if let Some(surface) = unsafe { screen_surface(boot_params.screen_info) } {
+    if let Some(surface) = unsafe { screen_surface(boot_params().screen_info) } {
         let console = TextConsole::new(surface, GLYPH_ARRAY, FONT_MAP, 0xFF_FF_FF_FF, 0);
         *TEXT_CONSOLE.lock() = Some(console);
 
@@ -73,8 +190,12 @@ pub extern "C" fn linux_main(
         // operations.
         unsafe { register_console(NonNull::from_ref(&CONSOLE)) }
     }
+}
+
+/// Initializes memory management from the E820 memory map.
+fn initialize_memory() {
+    let arguments = ENTRY_ARGUMENTS.copy();
 
-    crate::debug!("Image Start: {:#x}", crate::util::image_start());
     initialize_memory_config(4096, physical_bits(), 4096);
 
     // SAFETY:
@@ -85,74 +206,113 @@ pub extern "C" fn linux_main(
         initialize_physical_memory_manager(&LinuxImpl);
         initialize_virtual_memory_manager(&LinuxImpl);
         initialize_allocator(&LinuxImpl);
-        initialize_processor_management(&LinuxImpl);
     }
 
-    let e820_iter = E820Iter::new(PhysicalAddress::new(boot_params_ptr as u64));
+    let boot_params = PhysicalAddress::new(arguments.boot_information);
+    let initrd = boot_params_initrd(boot_params);
+    let command_line = boot_params_command_line(boot_params);
+    let e820_iter = E820Iter::new(boot_params);
     crate::platform::frame_allocator::initialize(
         e820_iter
-            .map(|entry| {
-                let start = PhysicalAddress::new(entry.addr);
-                let range = PhysicalAddressRange::new(start, entry.size);
-
-                let region_type = match entry.entry_type {
-                    1 => MemoryType::Free,
-                    2 => MemoryType::Reserved,
-                    3 => MemoryType::AcpiReclaimable,
-                    4 => MemoryType::AcpiNonVolatile,
-                    5 => MemoryType::Bad,
-                    _ => MemoryType::Reserved,
-                };
-
-                MemoryDescriptor { range, region_type }
-            })
+            .map(e820_memory_descriptor)
             .chain(iter::once_with(|| MemoryDescriptor {
                 range: PhysicalAddressRange::new(
-                    PhysicalAddress::new(u64::from(image_start)),
-                    u64::from(image_size),
+                    PhysicalAddress::new(arguments.image_start),
+                    arguments.image_size,
                 ),
                 region_type: MemoryType::BootloaderReclaimable,
+                attributes: MemoryAttributes::NONE,
             }))
             .chain(iter::once_with(|| MemoryDescriptor {
                 range: PhysicalAddressRange::new(
-                    PhysicalAddress::new(u64::from(stack_start)),
-                    u64::from(stack_size),
+                    PhysicalAddress::new(arguments.stack_start),
+                    arguments.stack_size,
                 ),
                 region_type: MemoryType::BootloaderReclaimable,
+                attributes: MemoryAttributes::NONE,
+            }))
+            .chain(initrd.map(|range| MemoryDescriptor {
+                range,
+                region_type: MemoryType::BootloaderReclaimable,
+                attributes: MemoryAttributes::NONE,
+            }))
+            .chain(command_line.map(|range| MemoryDescriptor {
+                range,
+                region_type: MemoryType::BootloaderReclaimable,
+                attributes: MemoryAttributes::NONE,
             })),
     );
+}
 
-    let rsdp_xsdp = PhysicalAddress::new(boot_params.acpi_rsdp_addr);
-    // SAFETY:
-    //
-    // There exist zero overlapping calls to [`set_rsdp()`] and [`rsdp()`].
-    unsafe { set_rsdp(rsdp_xsdp) };
+/// Initializes processor management.
+fn initialize_processors() {
     // SAFETY:
     //
-    // There exist zero overlapping calls to [`set_rsdp()`] and [`rsdp()`].
-    unsafe { set_xsdp(rsdp_xsdp) };
+    // [`initialize_processor_management()`] is called before any call to its subsystem.
+    unsafe { initialize_processor_management(&LinuxImpl) }
+}
+
+/// Records the ACPI and UEFI tables provided in the [`BootParams`].
+fn record_tables() {
+    let boot_params = boot_params();
+
+    let rsdp_xsdp = PhysicalAddress::new(boot_params.acpi_rsdp_addr);
+    set_rsdp(rsdp_xsdp);
+    set_xsdp(rsdp_xsdp);
 
     if boot_params.efi_info.system_table != 0 || boot_params.efi_info.system_table_high != 0 {
         let address = u64::from(boot_params.efi_info.system_table)
             | (u64::from(boot_params.efi_info.system_table_high) << 32);
 
+        set_uefi_system_table(PhysicalAddress::new(address));
+    }
+}
+
+/// Records the framebuffer described by the [`ScreenInfo`].
+fn record_framebuffer() {
+    if let Some(framebuffer) = screen_info_framebuffer(boot_params().screen_info) {
         // SAFETY:
         //
-        // There exist zero overlapping calls to [`set_uefi_system_table()`] and
-        // [`uefi_system_table()`]
-        unsafe { set_uefi_system_table(PhysicalAddress::new(address)) };
+        // No other cores are active at this time and thus no calls to [`set_framebuffer()`] or
+        // [`framebuffer()`] can overlap.
+        unsafe { set_framebuffer(framebuffer) }
     }
+}
 
-    match crate::stub_main() {
-        Ok(()) => {}
-        Err(error) => crate::error!("{error}"),
+/// Records the command line provided in the [`BootParams`].
+fn record_command_line() {
+    let boot_params = PhysicalAddress::new(ENTRY_ARGUMENTS.get().boot_information);
+    if let Some(command_line) = boot_params_command_line(boot_params).and_then(read_command_line) {
+        // SAFETY:
+        //
+        // Platform bring-up runs before the stub starts any other processor.
+        unsafe { set_command_line(command_line) }
     }
+}
 
-    loop {
-        core::hint::spin_loop()
+/// Registers the initial ramdisk as a module, if one was loaded.
+fn register_initrd() {
+    let boot_params = PhysicalAddress::new(ENTRY_ARGUMENTS.get().boot_information);
+    if let Some(range) = boot_params_initrd(boot_params) {
+        register_module(Module {
+            range,
+            name: "initrd",
+            command_line: "",
+        });
     }
 }
 
+/// Returns the [`BootParams`] passed to [`linux_main()`].
+fn boot_params() -> &'static BootParams {
+    let address = u64_to_usize_strict(ENTRY_ARGUMENTS.get().boot_information);
+
+    // SAFETY:
+    //
+    // The Linux boot protocol ensures that the [`BootParams`] are non-null, properly aligned and
+    // identity mapped, and the stub never modifies them.
+    unsafe { &*ptr::with_exposed_provenance::<BootParams>(address) }
+}
+
 /// Zero-sized implementation of most platform abstractions.
 struct LinuxImpl;
 
@@ -161,8 +321,9 @@ impl PhysicalMemoryManager for LinuxImpl {
         &self,
         count: u64,
         policy: AllocationPolicy,
+        region_type: MemoryType,
     ) -> Result<FrameRange, OutOfMemory> {
-        crate::platform::frame_allocator::allocate_frames(count, policy)
+        crate::platform::frame_allocator::allocate_frames(count, policy, region_type)
     }
 
     unsafe fn deallocate_frames(&self, range: FrameRange) {
@@ -266,6 +427,14 @@ impl ProcessorManager for LinuxImpl {
         1
     }
 
+    fn processor_descriptor(&self, _: u64) -> ProcessorDescriptor {
+        ProcessorDescriptor {
+            hardware_id: current_hardware_id(),
+            enabled: true,
+            proximity_domain: None,
+        }
+    }
+
     fn run_on_all_processors(&self, procedure: Procedure, argument: *mut ()) {
         if self.processor_count() != 1 {
             todo!("implement processor bring-up")
@@ -323,7 +492,7 @@ fn write(_: NonNull<Console>, metadata: Metadata, message: &str) {
     let Some(console) = console.as_mut() else {
         return;
     };
-    let _ = write!(console, "[{:?}]: {message}", metadata.level);
+    console.write_log(metadata, message);
 }
 
 /// Linux boot protocol-specific panic handler.
@@ -373,7 +542,7 @@ global_asm! {
     ".byte 1",           // relocatable_kernel
     ".byte 21",          // min_alignment (2 MiB)
     ".2byte 0",          // xloadflags
-    ".4byte 0",          // cmdline_size
+    ".4byte 2047",       // cmdline_size
     ".4byte 0",          // hardware_subarch
     ".4byte 0",          // hardware_subarch_data
     ".4byte 0",          // payload_offset
@@ -1,5 +1,7 @@
 //! Support for booting using the Linux boot protocol.
 
+use sync::ControlledModificationCell;
+
 #[cfg(target_arch = "aarch64")]
 mod aarch64;
 #[cfg(target_arch = "x86")]
@@ -13,3 +15,29 @@ pub use aarch64::linux_main;
 pub use i686::linux_main;
 #[cfg(target_arch = "x86_64")]
 pub use x86_64::linux_main;
+
+/// The arguments passed to `linux_main()`, stored for the bring-up tasks.
+static ENTRY_ARGUMENTS: ControlledModificationCell<EntryArguments> =
+    ControlledModificationCell::new(EntryArguments {
+        boot_information: 0,
+        image_start: 0,
+        image_size: 0,
+        stack_start: 0,
+        stack_size: 0,
+    });
+
+/// The arguments passed to `linux_main()` by the Linux boot protocol entry code.
+#[derive(Clone, Copy, Debug)]
+struct EntryArguments {
+    /// The physical address of the boot information: the `BootParams` on `x86` and the flattened
+    /// device tree on `aarch64`.
+    boot_information: u64,
+    /// The physical address of the start of the stub's image.
+    image_start: u64,
+    /// The size, in bytes, of the stub's image.
+    image_size: u64,
+    /// The physical address of the start of the stub's stack.
+    stack_start: u64,
+    /// The size, in bytes, of the stub's stack.
+    stack_size: u64,
+}
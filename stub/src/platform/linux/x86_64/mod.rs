@@ -3,7 +3,6 @@
 use core::{
     alloc::Layout,
     arch::global_asm,
-    fmt::Write,
     iter, mem,
     ptr::{self, NonNull},
 };
@@ -15,11 +14,15 @@ use sync::Spinlock;
 
 use crate::{
     PANIC_HANDLER,
-    arch::{arch_specific::load_gdt, memory::physical_bits},
+    arch::{
+        arch_specific::{current_hardware_id, install_exception_handlers, load_gdt},
+        memory::physical_bits,
+    },
     platform::{
-        AllocationPolicy, Allocator, BufferTooSmall, Console, Frame, FrameRange, MemoryDescriptor,
-        MemoryMap, MemoryType, Metadata, OutOfMemory, Permissions, PhysicalAddress,
-        PhysicalAddressRange, PhysicalMemoryManager, Procedure, ProcessorManager, frame_size,
+        AllocationPolicy, Allocator, BufferTooSmall, Console, Frame, FrameRange, MemoryAttributes,
+        MemoryDescriptor, MemoryMap, MemoryType, Metadata, Module, OutOfMemory, Permissions,
+        PhysicalAddress, PhysicalAddressRange, PhysicalMemoryManager, Procedure,
+        ProcessorDescriptor, ProcessorManager, bring_up, command_line, frame_size,
         graphics::{
             console::TextConsole,
             font::{FONT_MAP, GLYPH_ARRAY},
@@ -27,9 +30,12 @@ use crate::{
         },
         initialize_allocator, initialize_memory_config, initialize_physical_memory_manager,
         initialize_processor_management, initialize_virtual_memory_manager,
-        linux::x86_64::virt::setup_initial_mappings,
-        map, register_console,
-        shared::linux::E820Iter,
+        linux::{ENTRY_ARGUMENTS, EntryArguments, x86_64::virt::setup_initial_mappings},
+        map, register_console, register_module, set_command_line, set_framebuffer,
+        shared::linux::{
+            E820Iter, boot_params_command_line, boot_params_initrd, e820_memory_descriptor,
+            read_command_line, screen_info_framebuffer,
+        },
     },
 };
 
@@ -41,6 +47,67 @@ static TEXT_CONSOLE: Spinlock<Option<TextConsole<GenericSurface>>> = Spinlock::n
 
 mod virt;
 
+initgraph::define_task!(
+    MEMORY_TASK,
+    "linux.memory",
+    func = initialize_memory,
+    inactive,
+    requires = [],
+    dependents = ["platform.memory"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    CONSOLE_TASK,
+    "linux.console",
+    func = initialize_console,
+    inactive,
+    requires = ["linux.memory"],
+    dependents = ["platform.console"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    PROCESSORS_TASK,
+    "linux.processors",
+    func = initialize_processors,
+    inactive,
+    requires = [],
+    dependents = ["platform.processors"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    FRAMEBUFFER_TASK,
+    "linux.framebuffer",
+    func = record_framebuffer,
+    inactive,
+    requires = ["linux.memory"],
+    dependents = ["platform.framebuffer"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    COMMAND_LINE_TASK,
+    "linux.command_line",
+    func = record_command_line,
+    inactive,
+    requires = ["linux.memory"],
+    dependents = ["platform.command_line"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    MODULES_TASK,
+    "linux.modules",
+    func = register_initrd,
+    inactive,
+    requires = ["linux.memory"],
+    dependents = ["platform.modules"],
+    after = [],
+    before = [],
+);
+
 /// Rust entrypoint for the Linux boot protocol.
 pub extern "C" fn linux_main(
     boot_params: *mut BootParams,
@@ -53,10 +120,60 @@ pub extern "C" fn linux_main(
     //
     // This system has exclusive control over its system state.
     unsafe { load_gdt() }
+    // SAFETY:
+    //
+    // The GDT loaded above remains in use for the lifetime of the stub, while the Linux boot
+    // protocol leaves the IDT unspecified.
+    unsafe { install_exception_handlers(false) }
     *PANIC_HANDLER.lock() = panic_handler;
 
+    // SAFETY:
+    //
+    // This occurs before any of the bring-up tasks access [`ENTRY_ARGUMENTS`].
+    unsafe {
+        *ENTRY_ARGUMENTS.get_mut() = EntryArguments {
+            boot_information: boot_params as u64,
+            image_start,
+            image_size,
+            stack_start,
+            stack_size,
+        };
+    }
+
+    for task in [
+        &MEMORY_TASK,
+        &CONSOLE_TASK,
+        &PROCESSORS_TASK,
+        &FRAMEBUFFER_TASK,
+        &COMMAND_LINE_TASK,
+        &MODULES_TASK,
+    ] {
+        task.activate();
+    }
+    bring_up();
+
+    crate::debug!("Image Start: {:#x}", crate::util::image_start());
+    match crate::stub_main(command_line()) {
+        Ok(()) => {}
+        Err(error) => crate::error!("{error}"),
+    }
+
+    loop {
+        core::hint::spin_loop()
+    }
+}
+
+/// Builds the initial mappings and initializes memory management from the E820 memory map.
+fn initialize_memory() {
+    let arguments = ENTRY_ARGUMENTS.copy();
+
     initialize_memory_config(4096, physical_bits(), 4096);
-    setup_initial_mappings(image_start, image_size, stack_start, stack_size);
+    setup_initial_mappings(
+        arguments.image_start,
+        arguments.image_size,
+        arguments.stack_start,
+        arguments.stack_size,
+    );
 
     // SAFETY:
     //
@@ -66,44 +183,120 @@ pub extern "C" fn linux_main(
     }
 
     // Initialize physical memory management.
-    let e820_iter = E820Iter::new(PhysicalAddress::new(boot_params as u64));
+    let boot_params = PhysicalAddress::new(arguments.boot_information);
+    let initrd = boot_params_initrd(boot_params);
+    let command_line = boot_params_command_line(boot_params);
+    let e820_iter = E820Iter::new(boot_params);
     crate::platform::frame_allocator::initialize(
         e820_iter
-            .map(|entry| {
-                let start = PhysicalAddress::new(entry.addr);
-                let range = PhysicalAddressRange::new(start, entry.size);
-
-                let region_type = match entry.entry_type {
-                    1 => MemoryType::Free,
-                    2 => MemoryType::Reserved,
-                    3 => MemoryType::AcpiReclaimable,
-                    4 => MemoryType::AcpiNonVolatile,
-                    5 => MemoryType::Bad,
-                    _ => MemoryType::Reserved,
-                };
-
-                MemoryDescriptor { range, region_type }
-            })
+            .map(e820_memory_descriptor)
             .chain(iter::once_with(|| MemoryDescriptor {
-                range: PhysicalAddressRange::new(PhysicalAddress::new(image_start), image_size),
+                range: PhysicalAddressRange::new(
+                    PhysicalAddress::new(arguments.image_start),
+                    arguments.image_size,
+                ),
                 region_type: MemoryType::BootloaderReclaimable,
+                attributes: MemoryAttributes::NONE,
             }))
             .chain(iter::once_with(|| MemoryDescriptor {
-                range: PhysicalAddressRange::new(PhysicalAddress::new(stack_start), stack_size),
+                range: PhysicalAddressRange::new(
+                    PhysicalAddress::new(arguments.stack_start),
+                    arguments.stack_size,
+                ),
+                region_type: MemoryType::BootloaderReclaimable,
+                attributes: MemoryAttributes::NONE,
+            }))
+            .chain(initrd.map(|range| MemoryDescriptor {
+                range,
+                region_type: MemoryType::BootloaderReclaimable,
+                attributes: MemoryAttributes::NONE,
+            }))
+            .chain(command_line.map(|range| MemoryDescriptor {
+                range,
                 region_type: MemoryType::BootloaderReclaimable,
+                attributes: MemoryAttributes::NONE,
             })),
     );
 
     // SAFETY:
     //
-    // [`initialize_physical_memory_manager()`] is called before any call to its subsystem.
+    // These functions are called before any calls to their respective subsystems.
+    #[expect(clippy::multiple_unsafe_ops_per_block)]
     unsafe {
         initialize_physical_memory_manager(&LinuxImpl);
+        initialize_allocator(&LinuxImpl);
     }
+}
+
+/// Initializes the console on the framebuffer described by the [`ScreenInfo`].
+fn initialize_console() {
+    with_boot_params(|boot_params| {
+        // SAFETY:
+        //
+        // This occurs before any accesses to [`SURFACE`] and provides exclusive access to
+        // [`CONSOLE`].
+        if let Some(surface) = unsafe { screen_surface(boot_params.screen_info) } {
+            let console = TextConsole::new(surface, GLYPH_ARRAY, FONT_MAP, 0xFF_FF_FF_FF, 0);
+            *TEXT_CONSOLE.lock() = Some(console);
+
+            // SAFETY:
+            //
+            // This registration occurs before SMP and thus cannot overlap with other logging
+            // subsystem operations.
+            unsafe { register_console(NonNull::from_ref(&CONSOLE)) }
+        }
+    })
+}
 
+/// Initializes processor management.
+fn initialize_processors() {
+    // SAFETY:
+    //
+    // [`initialize_processor_management()`] is called before any call to its subsystem.
+    unsafe { initialize_processor_management(&LinuxImpl) }
+}
+
+/// Records the framebuffer described by the [`ScreenInfo`].
+fn record_framebuffer() {
+    with_boot_params(|boot_params| {
+        if let Some(framebuffer) = screen_info_framebuffer(boot_params.screen_info) {
+            // SAFETY:
+            //
+            // No other cores are active at this time and thus no calls to [`set_framebuffer()`]
+            // or [`framebuffer()`] can overlap.
+            unsafe { set_framebuffer(framebuffer) }
+        }
+    })
+}
+
+/// Records the command line provided in the [`BootParams`].
+fn record_command_line() {
+    let boot_params = PhysicalAddress::new(ENTRY_ARGUMENTS.get().boot_information);
+    if let Some(command_line) = boot_params_command_line(boot_params).and_then(read_command_line) {
+        // SAFETY:
+        //
+        // Platform bring-up runs before the stub starts any other processor.
+        unsafe { set_command_line(command_line) }
+    }
+}
+
+/// Registers the initial ramdisk as a module, if one was loaded.
+fn register_initrd() {
+    let boot_params = PhysicalAddress::new(ENTRY_ARGUMENTS.get().boot_information);
+    if let Some(range) = boot_params_initrd(boot_params) {
+        register_module(Module {
+            range,
+            name: "initrd",
+            command_line: "",
+        });
+    }
+}
+
+/// Maps the [`BootParams`] passed to [`linux_main()`] and calls `func` with them.
+fn with_boot_params<R>(func: impl FnOnce(&mut BootParams) -> R) -> R {
     let boot_params_mapping = map(
         FrameRange::new(
-            Frame::containing_address(PhysicalAddress::new(boot_params as u64)),
+            Frame::containing_address(PhysicalAddress::new(ENTRY_ARGUMENTS.get().boot_information)),
             1,
         ),
         Permissions::ReadWrite,
@@ -118,40 +311,7 @@ pub extern "C" fn linux_main(
         )
     };
 
-    // SAFETY:
-    //
-    // This occurs before any accesses to [`SURFACE`] and provides exclusive access to [`CONSOLE`].
-    if let Some(surface) = unsafe { screen_surface(boot_params.screen_info) } {
-        let console = TextConsole::new(surface, GLYPH_ARRAY, FONT_MAP, 0xFF_FF_FF_FF, 0);
-        *TEXT_CONSOLE.lock() = Some(console);
-
-        // SAFETY:
-        //
-        // This registration occurs before SMP and thus cannot overlap with other logging subsystem
-        // operations.
-        unsafe { register_console(NonNull::from_ref(&CONSOLE)) }
-    }
-
-    drop(boot_params_mapping);
-
-    // SAFETY:
-    //
-    // These functions are called before any calls to their respective subsystems.
-    #[expect(clippy::multiple_unsafe_ops_per_block)]
-    unsafe {
-        initialize_allocator(&LinuxImpl);
-        initialize_processor_management(&LinuxImpl);
-    }
-
-    crate::debug!("Image Start: {:#x}", crate::util::image_start());
-    match crate::stub_main() {
-        Ok(()) => {}
-        Err(error) => crate::error!("{error}"),
-    }
-
-    loop {
-        core::hint::spin_loop()
-    }
+    func(boot_params)
 }
 
 /// Zero-sized implementation of most platform abstractions.
@@ -162,8 +322,9 @@ impl PhysicalMemoryManager for LinuxImpl {
         &self,
         count: u64,
         policy: AllocationPolicy,
+        region_type: MemoryType,
     ) -> Result<FrameRange, OutOfMemory> {
-        crate::platform::frame_allocator::allocate_frames(count, policy)
+        crate::platform::frame_allocator::allocate_frames(count, policy, region_type)
     }
 
     unsafe fn deallocate_frames(&self, range: FrameRange) {
@@ -209,6 +370,14 @@ impl ProcessorManager for LinuxImpl {
         1
     }
 
+    fn processor_descriptor(&self, _: u64) -> ProcessorDescriptor {
+        ProcessorDescriptor {
+            hardware_id: current_hardware_id(),
+            enabled: true,
+            proximity_domain: None,
+        }
+    }
+
     fn run_on_all_processors(&self, procedure: Procedure, argument: *mut ()) {
         if self.processor_count() != 1 {
             todo!("implement processor bring-up")
@@ -280,7 +449,7 @@ fn write(_: NonNull<Console>, metadata: Metadata, message: &str) {
     let Some(console) = console.as_mut() else {
         return;
     };
-    let _ = write!(console, "[{:?}]: {message}", metadata.level);
+    console.write_log(metadata, message);
 }
 
 /// Linux boot protocol-specific panic handler.
@@ -330,7 +499,7 @@ global_asm! {
     ".byte 1",                     // relocatable_kernel
     ".byte 21",                    // min_alignment (2 MiB)
     ".2byte (1 << 0) | (1 << 1)",  // xloadflags
-    ".4byte 0",                    // cmdline_size
+    ".4byte 2047",                 // cmdline_size
     ".4byte 0",                    // hardware_subarch
     ".4byte 0",                    // hardware_subarch_data
     ".4byte 0",                    // payload_offset
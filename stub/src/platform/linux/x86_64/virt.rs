@@ -181,7 +181,7 @@ impl VirtualMemoryManager for LinuxImpl {
         //
         // The invariants of [`VirtualMemoryManager::unmap()`] fulfill the invariants of
         // [`ArchTranslationScheme::unmap()`].
-        unsafe { scheme.unmap(input) }
+        unsafe { scheme.unmap(input) }.expect("failed to allocate table to split stub mapping");
 
         for page in range.iter() {
             x86::paging::tlb::invalidate_page(page.start_address().value());
@@ -0,0 +1,225 @@
+//! Host-side mock platform backed by an in-memory model of physical memory.
+//!
+//! This platform is only available to host tests and allows platform-independent code (such as the
+//! frame allocator, the [`TranslationScheme`][ts] implementations, and the cross address space call
+//! handlers) to be exercised without booting.
+//!
+//! [ts]: crate::arch::generic::memory::paging::TranslationScheme
+
+use std::{
+    alloc::{self, Layout},
+    ptr::{self, NonNull},
+    sync::{
+        Mutex, MutexGuard, Once, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use conversion::{u64_to_usize_strict, usize_to_u64};
+
+use crate::platform::{
+    AllocationPolicy, Allocator, BufferTooSmall, FrameRange, MapError, MappingType,
+    MemoryAttributes, MemoryDescriptor, MemoryMap, MemoryType, OutOfMemory, Page, PageRange,
+    Permissions, PhysicalAddress, PhysicalAddressRange, PhysicalMemoryManager, VirtualAddress,
+    VirtualMemoryManager, initialize_allocator, initialize_memory_config,
+    initialize_physical_memory_manager, initialize_virtual_memory_manager,
+};
+
+/// The size, in bytes, of the modeled physical memory.
+pub const PHYSICAL_MEMORY_SIZE: u64 = 8 * 1024 * 1024;
+/// The size, in bytes, of the [`MemoryType::Reserved`] region at the start of the modeled physical
+/// memory.
+pub const RESERVED_SIZE: u64 = 0x10000;
+/// The size, in bytes, of frames and pages on the mock platform.
+const GRANULE_SIZE: u64 = 4096;
+
+/// The address of the host allocation that backs the modeled physical memory.
+static PHYSICAL_MEMORY: AtomicUsize = AtomicUsize::new(0);
+/// Serializes access to the mock platform between concurrently running tests.
+static PLATFORM_LOCK: Mutex<()> = Mutex::new(());
+
+/// Initializes the mock platform if necessary and returns a guard that provides exclusive access to
+/// it.
+///
+/// The mock platform is shared by every test in the process, so any test that relies on the
+/// platform's state must hold the returned guard for its entire duration.
+pub fn acquire() -> MutexGuard<'static, ()> {
+    /// Guard ensuring that the mock platform is only initialized once.
+    static INITIALIZE: Once = Once::new();
+
+    let guard = PLATFORM_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    INITIALIZE.call_once(initialize);
+    guard
+}
+
+/// Initializes the memory configuration, the memory management subsystems, and the system frame
+/// allocator of the mock platform.
+fn initialize() {
+    let layout = Layout::from_size_align(
+        u64_to_usize_strict(PHYSICAL_MEMORY_SIZE),
+        u64_to_usize_strict(GRANULE_SIZE),
+    )
+    .expect("invalid physical memory layout");
+    // SAFETY:
+    //
+    // `layout` has a non-zero size.
+    let memory = unsafe { alloc::alloc_zeroed(layout) };
+    assert!(!memory.is_null(), "failed to allocate physical memory");
+    PHYSICAL_MEMORY.store(memory.expose_provenance(), Ordering::Release);
+
+    initialize_memory_config(GRANULE_SIZE, 32, u64_to_usize_strict(GRANULE_SIZE));
+
+    // SAFETY:
+    //
+    // The mock platform is initialized exactly once and before any memory management call.
+    unsafe { initialize_virtual_memory_manager(&MockImpl) }
+
+    crate::platform::frame_allocator::initialize(
+        [
+            MemoryDescriptor {
+                range: PhysicalAddressRange::new(PhysicalAddress::zero(), RESERVED_SIZE),
+                region_type: MemoryType::Reserved,
+                attributes: MemoryAttributes::NONE,
+            },
+            MemoryDescriptor {
+                range: PhysicalAddressRange::new(
+                    PhysicalAddress::new(RESERVED_SIZE),
+                    PHYSICAL_MEMORY_SIZE - RESERVED_SIZE,
+                ),
+                region_type: MemoryType::Free,
+                attributes: MemoryAttributes::WRITE_BACK,
+            },
+        ]
+        .into_iter(),
+    );
+
+    // SAFETY:
+    //
+    // The mock platform is initialized exactly once and before any physical memory management
+    // call.
+    unsafe { initialize_physical_memory_manager(&MockImpl) }
+    // SAFETY:
+    //
+    // The mock platform is initialized exactly once and before any allocation call.
+    unsafe { initialize_allocator(&MockImpl) }
+}
+
+/// Returns the address of the host allocation that backs the modeled physical memory.
+fn physical_memory_base() -> usize {
+    PHYSICAL_MEMORY.load(Ordering::Acquire)
+}
+
+/// Zero-sized implementation of the mock platform abstractions.
+struct MockImpl;
+
+impl PhysicalMemoryManager for MockImpl {
+    fn allocate_frames(
+        &self,
+        count: u64,
+        policy: AllocationPolicy,
+        region_type: MemoryType,
+    ) -> Result<FrameRange, OutOfMemory> {
+        crate::platform::frame_allocator::allocate_frames(count, policy, region_type)
+    }
+
+    unsafe fn deallocate_frames(&self, range: FrameRange) {
+        // SAFETY:
+        //
+        // The invariants of [`PhysicalMemoryManager::deallocate_frames()`] fulfill the invariants
+        // of [`deallocate_frames()`].
+        unsafe { crate::platform::frame_allocator::deallocate_frames(range) }
+    }
+
+    fn memory_map<'buffer>(
+        &self,
+        buffer: &'buffer mut [MemoryDescriptor],
+    ) -> Result<MemoryMap<'buffer>, BufferTooSmall> {
+        crate::platform::frame_allocator::memory_map(buffer)
+    }
+}
+
+impl VirtualMemoryManager for MockImpl {
+    fn max_physical_address(&self) -> PhysicalAddress {
+        PhysicalAddress::new(PHYSICAL_MEMORY_SIZE - 1)
+    }
+
+    fn map(
+        &self,
+        frames: FrameRange,
+        _: Permissions,
+        _: MappingType,
+    ) -> Result<PageRange, MapError> {
+        if frames.is_empty() || frames.end_address_exclusive().value() > PHYSICAL_MEMORY_SIZE {
+            return Err(MapError::FindFreeRegionError);
+        }
+
+        // The modeled physical memory is linearly mapped into the host's address space.
+        let start = VirtualAddress::new(
+            physical_memory_base() + u64_to_usize_strict(frames.start_address().value()),
+        );
+        let start = Page::containing_address(start);
+        let end = start.strict_add(u64_to_usize_strict(frames.count() - 1));
+        Ok(PageRange::new(start, end))
+    }
+
+    fn map_identity(&self, _: FrameRange, _: Permissions) -> Result<PageRange, MapError> {
+        // The host's address space cannot accommodate identity mappings of the modeled physical
+        // memory.
+        Err(MapError::FindFreeRegionError)
+    }
+
+    fn map_temporary(&self, address: PhysicalAddress) -> Option<VirtualAddress> {
+        if address > self.max_physical_address() {
+            return None;
+        }
+
+        Some(VirtualAddress::new(
+            physical_memory_base() + u64_to_usize_strict(address.value()),
+        ))
+    }
+
+    fn translate_virtual(
+        &self,
+        address: VirtualAddress,
+    ) -> Option<(Permissions, MappingType, PhysicalAddress)> {
+        let offset = usize_to_u64(address.value().checked_sub(physical_memory_base())?);
+        if offset >= PHYSICAL_MEMORY_SIZE {
+            return None;
+        }
+
+        Some((
+            Permissions::ReadWrite,
+            MappingType::Normal,
+            PhysicalAddress::new(offset),
+        ))
+    }
+
+    unsafe fn unmap(&self, _: PageRange) {
+        // The linear mapping of the modeled physical memory is never removed.
+    }
+}
+
+impl Allocator for MockImpl {
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        if layout.size() == 0 {
+            return NonNull::new(ptr::without_provenance_mut(layout.align()));
+        }
+
+        // SAFETY:
+        //
+        // `layout` has a non-zero size.
+        NonNull::new(unsafe { alloc::alloc(layout) })
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() == 0 {
+            return;
+        }
+
+        // SAFETY:
+        //
+        // The invariants of [`Allocator::deallocate()`] ensure that `ptr` was allocated by
+        // [`Allocator::allocate()`] with `layout`.
+        unsafe { alloc::dealloc(ptr.as_ptr(), layout) }
+    }
+}
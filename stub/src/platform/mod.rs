@@ -1,15 +1,23 @@
 //! A collection of supported platforms and various utilities provided by said platforms that are
 //! required to carry out `revm-stub`'s goal.
 
-// Platform support modules.
+// Platform support modules. Their entry points are only referenced by the boot assembly, which is
+// not linked into host tests.
 #[cfg(any(target_arch = "aarch64", target_arch = "x86_64"))]
+#[cfg_attr(test, allow(dead_code, unused_imports))]
 mod limine;
+#[cfg_attr(test, allow(dead_code, unused_imports))]
 mod linux;
+#[cfg(test)]
+pub mod mock;
+#[cfg_attr(test, allow(dead_code, unused_imports))]
 mod uefi;
 
-#[cfg(any(target_arch = "aarch64", target_arch = "x86_64"))]
+#[cfg(all(any(target_arch = "aarch64", target_arch = "x86_64"), not(test)))]
 use limine::limine_main;
+#[cfg(not(test))]
 use linux::linux_main;
+#[cfg(not(test))]
 use uefi::uefi_main;
 
 // Other support modules.
@@ -23,7 +31,7 @@ mod shared;
 
 pub use generic::*;
 
-#[cfg(target_arch = "aarch64")]
+#[cfg(all(target_arch = "aarch64", not(test)))]
 core::arch::global_asm! {
     ".global main",
     "main:",
@@ -68,7 +76,7 @@ core::arch::global_asm! {
     uefi_main = sym uefi_main,
 }
 
-#[cfg(target_arch = "x86")]
+#[cfg(all(target_arch = "x86", not(test)))]
 core::arch::global_asm! {
     ".global main",
     "main:",
@@ -94,7 +102,7 @@ core::arch::global_asm! {
     uefi_main = sym uefi_main,
 }
 
-#[cfg(target_arch = "x86_64")]
+#[cfg(all(target_arch = "x86_64", not(test)))]
 core::arch::global_asm! {
     ".global main",
     "main:",
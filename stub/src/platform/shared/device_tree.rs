@@ -0,0 +1,81 @@
+//! Code shared between protocols for purposes of interacting with the flattened device tree.
+
+use device_tree::Node;
+
+use crate::platform::{PhysicalAddress, PhysicalAddressRange, ProcessorDescriptor};
+
+/// Returns the [`ProcessorDescriptor`] described by the `/cpus` node of the device tree rooted at
+/// `root` whose hardware ID matches `hardware_id`.
+pub fn device_tree_processor(root: Node, hardware_id: u64) -> Option<ProcessorDescriptor> {
+    let cpus = root.find_node(c"cpus")?;
+    let address_cells = cpus
+        .find_property(c"#address-cells")
+        .and_then(|property| property.read_u32_at(0))
+        .unwrap_or(1);
+
+    cpus.nodes()
+        .filter(|node| {
+            node.find_property(c"device_type")
+                .and_then(|device_type| device_type.read_cstr(0))
+                .is_some_and(|device_type| device_type == c"cpu")
+        })
+        .find(|node| {
+            let Some(reg) = node.find_property(c"reg") else {
+                return false;
+            };
+
+            let node_hardware_id = match address_cells {
+                1 => reg.read_u32_at(0).map(u64::from),
+                2 => reg.read_u64_at(0),
+                _ => None,
+            };
+            node_hardware_id == Some(hardware_id)
+        })
+        .map(|node| {
+            let enabled = node
+                .find_property(c"status")
+                .and_then(|status| status.read_cstr(0))
+                .is_none_or(|status| status == c"okay" || status == c"ok");
+            let proximity_domain = node
+                .find_property(c"numa-node-id")
+                .and_then(|property| property.read_u32_at(0));
+
+            ProcessorDescriptor {
+                hardware_id,
+                enabled,
+                proximity_domain,
+            }
+        })
+}
+
+/// Returns the command line stored in the `bootargs` property of the `/chosen` node.
+///
+/// This returns [`None`] if no command line was provided.
+pub fn chosen_bootargs<'a>(chosen: Node<'a>) -> Option<&'a str> {
+    chosen
+        .find_property(c"bootargs")
+        .and_then(|property| property.read_cstr(0))
+        .and_then(|bootargs| bootargs.to_str().ok())
+        .filter(|bootargs| !bootargs.is_empty())
+}
+
+/// Returns the [`PhysicalAddressRange`] of the initial ramdisk described by the
+/// `linux,initrd-start` and `linux,initrd-end` properties of the `/chosen` node.
+///
+/// This returns [`None`] if no initial ramdisk was loaded.
+pub fn chosen_initrd(chosen: Node) -> Option<PhysicalAddressRange> {
+    let read_address = |name| {
+        let property = chosen.find_property(name)?;
+        match property.data().len() {
+            4 => property.read_u32_at(0).map(u64::from),
+            8 => property.read_u64_at(0),
+            _ => None,
+        }
+    };
+
+    let start = read_address(c"linux,initrd-start")?;
+    let end = read_address(c"linux,initrd-end")?;
+    let size = end.checked_sub(start).filter(|&size| size != 0)?;
+
+    Some(PhysicalAddressRange::new(PhysicalAddress::new(start), size))
+}
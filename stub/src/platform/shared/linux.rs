@@ -1,18 +1,157 @@
 //! Code shared between protocols for purposes of interacting with the `linux` boot protocol.
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-use core::{mem, ptr};
+use core::{alloc::Layout, mem, ptr, slice, str};
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use conversion::{u8_to_usize, u64_to_usize_strict, usize_to_u64};
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-use linux::x86::{BootParams, E820Entry, SetupData, SetupDataIndirect, SetupType};
+use linux::x86::{
+    BootParams, E820Entry, ScreenInfo, SetupData, SetupDataIndirect, SetupType, VideoCapabilities,
+};
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use crate::platform::{
-    PhysicalAddress, PhysicalAddressRange, map_temporary, page_size, read_u32_at, read_u64_at,
+    Framebuffer, MemoryAttributes, MemoryDescriptor, MemoryType, PhysicalAddress,
+    PhysicalAddressRange, allocate, map_temporary, page_size, read_bytes_at, read_u8_at,
+    read_u32_at, read_u64_at,
 };
 
+/// Returns the [`Framebuffer`] described by the provided [`ScreenInfo`].
+///
+/// This returns [`None`] if [`ScreenInfo`] does not describe a linear framebuffer.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn screen_info_framebuffer(screen_info: ScreenInfo) -> Option<Framebuffer> {
+    if screen_info.lfb_base == 0 {
+        return None;
+    }
+
+    let address = if screen_info.capabilities.0 & VideoCapabilities::BASE_64_BIT.0 != 0 {
+        (u64::from(screen_info.ext_lfb_base) << 32) | u64::from(screen_info.lfb_base)
+    } else {
+        u64::from(screen_info.lfb_base)
+    };
+
+    Some(Framebuffer {
+        address: PhysicalAddress::new(address),
+        width: u64::from(screen_info.lfb_width),
+        height: u64::from(screen_info.lfb_height),
+        pitch: u64::from(screen_info.lfb_line_length),
+        bpp: screen_info.lfb_depth,
+        red_mask_size: screen_info.red_size,
+        red_mask_shift: screen_info.red_pos,
+        green_mask_size: screen_info.green_size,
+        green_mask_shift: screen_info.green_pos,
+        blue_mask_size: screen_info.blue_size,
+        blue_mask_shift: screen_info.blue_pos,
+    })
+}
+
+/// Returns the [`PhysicalAddressRange`] of the initial ramdisk described by the [`BootParams`]
+/// located at `boot_params`.
+///
+/// This returns [`None`] if no initial ramdisk was loaded.
+///
+/// This function uses [`map_temporary()`].
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn boot_params_initrd(boot_params: PhysicalAddress) -> Option<PhysicalAddressRange> {
+    let mapping = map_temporary(boot_params)?;
+    // SAFETY:
+    //
+    // The `linux` boot protocol ensures that this operation is safe.
+    let boot_params = unsafe { &*ptr::with_exposed_provenance::<BootParams>(mapping.value()) };
+
+    let address =
+        (u64::from(boot_params.ext_ramdisk_image) << 32) | u64::from(boot_params.hdr.ramdisk_image);
+    let size =
+        (u64::from(boot_params.ext_ramdisk_size) << 32) | u64::from(boot_params.hdr.ramdisk_size);
+    if address == 0 || size == 0 {
+        return None;
+    }
+
+    Some(PhysicalAddressRange::new(
+        PhysicalAddress::new(address),
+        size,
+    ))
+}
+
+/// Returns the [`PhysicalAddressRange`] of the command line described by the [`BootParams`]
+/// located at `boot_params`, excluding its NUL terminator.
+///
+/// This returns [`None`] if no command line was provided.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn boot_params_command_line(boot_params: PhysicalAddress) -> Option<PhysicalAddressRange> {
+    let mapping = map_temporary(boot_params)?;
+    // SAFETY:
+    //
+    // The `linux` boot protocol ensures that this operation is safe.
+    let boot_params = unsafe { &*ptr::with_exposed_provenance::<BootParams>(mapping.value()) };
+
+    let address =
+        (u64::from(boot_params.ext_cmd_line_ptr) << 32) | u64::from(boot_params.hdr.cmd_line_ptr);
+    let max_length = u64::from(boot_params.hdr.cmdline_size);
+    if address == 0 {
+        return None;
+    }
+
+    let address = PhysicalAddress::new(address);
+    let length = (0..max_length)
+        .find(|&offset| read_u8_at(address.strict_add(offset)).is_none_or(|byte| byte == 0))
+        .unwrap_or(max_length);
+    if length == 0 {
+        return None;
+    }
+
+    Some(PhysicalAddressRange::new(address, length))
+}
+
+/// Copies the command line located in `range` into memory that lives for the rest of the program.
+///
+/// The command line is truncated at the first invalid UTF-8 sequence.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn read_command_line(range: PhysicalAddressRange) -> Option<&'static str> {
+    let layout = Layout::array::<u8>(u64_to_usize_strict(range.count())).ok()?;
+    let buffer = allocate(layout)?;
+    // SAFETY:
+    //
+    // `buffer` was allocated with `layout` and is never deallocated.
+    let buffer = unsafe { slice::from_raw_parts_mut(buffer.as_ptr(), layout.size()) };
+    if !read_bytes_at(range.start(), buffer) {
+        return None;
+    }
+
+    match str::from_utf8(buffer) {
+        Ok(command_line) => Some(command_line),
+        Err(error) => str::from_utf8(&buffer[..error.valid_up_to()]).ok(),
+    }
+}
+
+/// Returns the [`MemoryDescriptor`] that describes the provided [`E820Entry`].
+///
+/// The E820 memory map does not report caching capabilities, so only the entry types that
+/// describe RAM are marked as [`MemoryAttributes::WRITE_BACK`].
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn e820_memory_descriptor(entry: E820Entry) -> MemoryDescriptor {
+    let range = PhysicalAddressRange::new(PhysicalAddress::new(entry.addr), entry.size);
+
+    let (region_type, attributes) = match entry.entry_type {
+        1 => (MemoryType::Free, MemoryAttributes::WRITE_BACK),
+        2 => (MemoryType::Reserved, MemoryAttributes::NONE),
+        3 => (MemoryType::AcpiReclaimable, MemoryAttributes::WRITE_BACK),
+        4 => (MemoryType::AcpiNonVolatile, MemoryAttributes::WRITE_BACK),
+        5 => (MemoryType::Bad, MemoryAttributes::NONE),
+        // Both the ACPI persistent memory type and the legacy protected memory type.
+        7 | 12 => (MemoryType::Persistent, MemoryAttributes::WRITE_BACK),
+        _ => (MemoryType::Reserved, MemoryAttributes::NONE),
+    };
+
+    MemoryDescriptor {
+        range,
+        region_type,
+        attributes,
+    }
+}
+
 /// [`Iterator`] over the [`E820Entry`]s that the [`BootParams`]s table provides.
 ///
 /// This [`Iterator`] uses [`map_temporary()`].
@@ -1,3 +1,6 @@
 //! Code shared between protocols.
 
+#[cfg(target_arch = "aarch64")]
+pub mod device_tree;
 pub mod linux;
+pub mod uefi;
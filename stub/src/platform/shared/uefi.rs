@@ -1 +1,69 @@
 //! Code shared between protocols for purposes of interacting with the UEFI environment.
+
+use uefi::memory::{
+    MemoryAttribute, MemoryDescriptor as UefiMemoryDescriptor, MemoryType as UefiMemoryType,
+};
+
+use crate::platform::{
+    MemoryAttributes, MemoryDescriptor, MemoryType, PhysicalAddress, PhysicalAddressRange,
+};
+
+/// The OS loader defined UEFI memory type of memory that holds the image of the executable.
+pub const EXECUTABLE_MEMORY_TYPE: UefiMemoryType = UefiMemoryType(0x8000_0000);
+/// The OS loader defined UEFI memory type of memory that holds the protocol table.
+pub const PROTOCOL_TABLE_MEMORY_TYPE: UefiMemoryType = UefiMemoryType(0x8000_0001);
+/// The OS loader defined UEFI memory type of memory that holds the persistent log ring.
+pub const LOG_RING_MEMORY_TYPE: UefiMemoryType = UefiMemoryType(0x8000_0002);
+
+/// The size, in bytes, of a UEFI page.
+const UEFI_PAGE_SIZE: u64 = 4096;
+
+/// Returns the [`MemoryDescriptor`] that describes the provided UEFI memory descriptor.
+pub fn uefi_memory_descriptor(descriptor: &UefiMemoryDescriptor) -> MemoryDescriptor {
+    let region_type = match descriptor.region_type {
+        UefiMemoryType::CONVENTIONAL => MemoryType::Free,
+        UefiMemoryType::LOADER_CODE
+        | UefiMemoryType::LOADER_DATA
+        | UefiMemoryType::BOOT_SERVICES_CODE
+        | UefiMemoryType::BOOT_SERVICES_DATA => MemoryType::BootloaderReclaimable,
+        UefiMemoryType::RUNTIME_SERVICES_CODE => MemoryType::RuntimeServicesCode,
+        UefiMemoryType::RUNTIME_SERVICES_DATA => MemoryType::RuntimeServicesData,
+        UefiMemoryType::UNUSABLE => MemoryType::Bad,
+        UefiMemoryType::ACPI_RECLAIM => MemoryType::AcpiReclaimable,
+        UefiMemoryType::ACPI_NVS => MemoryType::AcpiNonVolatile,
+        UefiMemoryType::MMIO | UefiMemoryType::MMIO_PORT_SPACE => MemoryType::Mmio,
+        UefiMemoryType::PERSISTENT => MemoryType::Persistent,
+        EXECUTABLE_MEMORY_TYPE => MemoryType::Executable,
+        PROTOCOL_TABLE_MEMORY_TYPE => MemoryType::ProtocolTable,
+        LOG_RING_MEMORY_TYPE => MemoryType::LogRing,
+        _ => MemoryType::Reserved,
+    };
+
+    let mut attributes = MemoryAttributes::NONE;
+    for (uefi_attribute, attribute) in [
+        (MemoryAttribute::UNCACHEABLE, MemoryAttributes::UNCACHEABLE),
+        (
+            MemoryAttribute::WRITE_COMBINING,
+            MemoryAttributes::WRITE_COMBINING,
+        ),
+        (
+            MemoryAttribute::WRITE_THROUGH,
+            MemoryAttributes::WRITE_THROUGH,
+        ),
+        (MemoryAttribute::WRITE_BACK, MemoryAttributes::WRITE_BACK),
+        (MemoryAttribute::RUNTIME, MemoryAttributes::RUNTIME),
+    ] {
+        if descriptor.attribute & uefi_attribute.0 != 0 {
+            attributes |= attribute;
+        }
+    }
+
+    MemoryDescriptor {
+        range: PhysicalAddressRange::new(
+            PhysicalAddress::new(descriptor.physical_start),
+            descriptor.number_of_pages.strict_mul(UEFI_PAGE_SIZE),
+        ),
+        region_type,
+        attributes,
+    }
+}
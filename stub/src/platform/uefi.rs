@@ -4,22 +4,30 @@ use core::{
     alloc::Layout,
     ffi,
     fmt::{self, Write},
-    mem,
+    iter, mem,
     ptr::{self, NonNull},
     slice,
     sync::atomic::{AtomicPtr, Ordering},
 };
 
-use conversion::{u64_to_usize_strict, usize_to_u64};
+use conversion::{u32_to_usize, u64_to_usize_strict, usize_to_u64};
 use sync::{ControlledModificationCell, Spinlock};
 use uefi::{
-    data_type::{Boolean, Event, Handle, Status, TaskPriorityLevel},
+    data_type::{Boolean, Char16, Event, Handle, Status, TaskPriorityLevel},
     protocol::{
-        console::simple_text::output::SimpleTextOutputProtocol,
+        console::{
+            graphics_output::{GraphicsOutputProtocol, GraphicsPixelFormat},
+            simple_text::output::SimpleTextOutputProtocol,
+        },
+        loaded_image::LoadedImageProtocol,
+        media::{
+            file::{FileAttributes, FileInfo, FileProtocol, OpenMode},
+            simple_file_system::SimpleFileSystemProtocol,
+        },
         mp::{MpServicesProtocol, ProcessorInformation, StatusFlag},
     },
     table::{
-        boot::{AllocateType, BootServices2_0, EventType},
+        boot::{AllocateType, BootServices1_0, BootServices2_0, EventType},
         config,
         system::SystemTable,
     },
@@ -27,16 +35,25 @@ use uefi::{
 
 use crate::{
     PANIC_HANDLER,
-    arch::memory::physical_bits,
+    arch::{
+        arch_specific::{current_hardware_id, install_exception_handlers},
+        memory::physical_bits,
+    },
     platform::{
-        AllocationPolicy, Allocator, BufferTooSmall, Console, Frame, FrameRange, MapError,
-        MappingType, MemoryDescriptor, MemoryMap, MemoryType, Metadata, OutOfMemory, Page,
-        PageRange, Permissions, PhysicalAddress, PhysicalMemoryManager, Procedure,
-        ProcessorManager, VirtualAddress, VirtualAddressRange, VirtualMemoryManager, allocate,
+        AllocationPolicy, Allocator, BufferTooSmall, Console, Frame, FrameRange, Framebuffer,
+        MapError, MappingType, MemoryDescriptor, MemoryMap, MemoryType, Metadata, Module,
+        OutOfMemory, Page, PageRange, Permissions, PhysicalAddress, PhysicalAddressRange,
+        PhysicalMemoryManager, Procedure, ProcessorDescriptor, ProcessorManager, VirtualAddress,
+        VirtualAddressRange, VirtualMemoryManager, allocate, bring_up, command_line,
         current_processor_id, deallocate, initialize_allocator, initialize_memory_config,
         initialize_physical_memory_manager, initialize_processor_management,
-        initialize_virtual_memory_manager, page_size, register_console, set_device_tree, set_rsdp,
-        set_smbios_32, set_smbios_64, set_uefi_system_table, set_xsdp,
+        initialize_virtual_memory_manager, page_size, register_console, register_module,
+        set_command_line, set_device_tree, set_framebuffer, set_rsdp, set_smbios_32, set_smbios_64,
+        set_uefi_system_table, set_xsdp,
+        shared::uefi::{
+            EXECUTABLE_MEMORY_TYPE, LOG_RING_MEMORY_TYPE, PROTOCOL_TABLE_MEMORY_TYPE,
+            uefi_memory_descriptor,
+        },
     },
 };
 
@@ -56,6 +73,82 @@ static MEMORY_MAP: Spinlock<UefiMemoryMap> = Spinlock::new(UefiMemoryMap::new())
 static MP_SERVICES: ControlledModificationCell<Option<&'static MpServicesProtocol>> =
     ControlledModificationCell::new(None);
 
+/// The size, in bytes, of a UEFI page.
+const UEFI_PAGE_SIZE: u64 = 4096;
+/// The maximum length, in UCS-2 characters, of the path of a module listed on the command line.
+const MAX_MODULE_PATH_LENGTH: usize = 512;
+
+initgraph::define_task!(
+    CONSOLE_TASK,
+    "uefi.console",
+    func = initialize_console,
+    inactive,
+    requires = [],
+    dependents = ["platform.console"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    PROCESSORS_TASK,
+    "uefi.processors",
+    func = initialize_processors,
+    inactive,
+    requires = [],
+    dependents = ["platform.processors"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    MEMORY_TASK,
+    "uefi.memory",
+    func = initialize_memory,
+    inactive,
+    requires = ["uefi.processors"],
+    dependents = ["platform.memory"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    COMMAND_LINE_TASK,
+    "uefi.command_line",
+    func = record_command_line,
+    inactive,
+    requires = ["uefi.memory"],
+    dependents = ["platform.command_line"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    TABLES_TASK,
+    "uefi.tables",
+    func = record_tables,
+    inactive,
+    requires = [],
+    dependents = ["platform.tables"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    FRAMEBUFFER_TASK,
+    "uefi.framebuffer",
+    func = record_framebuffer,
+    inactive,
+    requires = [],
+    dependents = ["platform.framebuffer"],
+    after = [],
+    before = [],
+);
+initgraph::define_task!(
+    MODULES_TASK,
+    "uefi.modules",
+    func = load_modules,
+    inactive,
+    requires = ["uefi.memory", "uefi.command_line"],
+    dependents = ["platform.modules"],
+    after = [],
+    before = [],
+);
+
 /// Rust entrypoint for the UEFI environment.
 pub extern "efiapi" fn uefi_main(
     image_handle: Handle,
@@ -67,8 +160,45 @@ pub extern "efiapi" fn uefi_main(
 
     // SAFETY:
     //
-    // This registration occurs first thing and thus cannot overlap with other printers.
+    // The firmware's interrupt handlers and code segment remain valid while boot services are
+    // active, and boot services are only exited when the stub hands control to the executable.
+    unsafe { install_exception_handlers(true) }
+
+    for task in [
+        &CONSOLE_TASK,
+        &PROCESSORS_TASK,
+        &MEMORY_TASK,
+        &COMMAND_LINE_TASK,
+        &TABLES_TASK,
+        &FRAMEBUFFER_TASK,
+        &MODULES_TASK,
+    ] {
+        task.activate();
+    }
+    bring_up();
+
+    crate::debug!("Image Start: {:#x}", crate::util::image_start());
+    match crate::stub_main(command_line()) {
+        Ok(()) => Status::SUCCESS,
+        Err(error) => {
+            crate::warn!("{error}");
+            Status::LOAD_ERROR
+        }
+    }
+}
+
+/// Registers the UEFI standard output as a [`Console`].
+fn initialize_console() {
+    // SAFETY:
+    //
+    // This registration occurs before any other console is registered and thus cannot overlap
+    // with other printers.
     unsafe { register_console(NonNull::from_ref(&UEFI_CONSOLE)) };
+}
+
+/// Locates the [`MpServicesProtocol`] and records the processors it reports.
+fn initialize_processors() {
+    let system_table_ptr = system_table_ptr();
 
     let (main_processor_id, processor_count) = 'mp: {
         // SAFETY:
@@ -171,7 +301,10 @@ pub extern "efiapi" fn uefi_main(
         uefi_impl.main_processor_id = main_processor_id;
         uefi_impl.processor_count = processor_count;
     }
+}
 
+/// Initializes memory management on top of the UEFI boot services.
+fn initialize_memory() {
     // The UEFI specification states that frame sizes are always 4096 bytes, and as an identity
     // mapped platform, so too are page sizes.
     initialize_memory_config(4096, physical_bits(), 4096);
@@ -186,8 +319,13 @@ pub extern "efiapi" fn uefi_main(
         initialize_allocator(UEFI_IMPL.get());
         initialize_processor_management(UEFI_IMPL.get());
     }
+}
+
+/// Records the locations of the firmware tables listed in the UEFI configuration table.
+fn record_tables() {
+    let system_table_ptr = system_table_ptr();
 
-    let tables: [(::uefi::data_type::Guid, unsafe fn(PhysicalAddress)); 5] = [
+    let tables: [(::uefi::data_type::Guid, fn(PhysicalAddress)); 5] = [
         (config::ACPI, set_rsdp),
         (config::ACPI_2, set_xsdp),
         (config::DEVICE_TREE, set_device_tree),
@@ -212,31 +350,545 @@ pub extern "efiapi" fn uefi_main(
     for table in config_tables {
         for (guid, set) in tables {
             if table.vendor_guid == guid {
-                // SAFETY:
-                //
-                // There are zero overlapping calls to the getter and setter functions.
-                unsafe {
-                    set(PhysicalAddress::new(usize_to_u64(
-                        table.vendor_table.addr(),
-                    )));
+                set(PhysicalAddress::new(usize_to_u64(
+                    table.vendor_table.addr(),
+                )));
+            }
+        }
+    }
+
+    set_uefi_system_table(PhysicalAddress::new(usize_to_u64(system_table_ptr.addr())));
+}
+
+/// Records the framebuffer of the graphics output protocol.
+fn record_framebuffer() {
+    let system_table_ptr = system_table_ptr();
+
+    // SAFETY:
+    //
+    // `system_table_ptr` was provided by the `efi_main` entry point and so according to the UEFI
+    // specification, the pointer must be valid.
+    if let Some(framebuffer) = unsafe { graphics_output_framebuffer(system_table_ptr) } {
+        // SAFETY:
+        //
+        // No other cores are active at this time and thus no calls to [`set_framebuffer()`] or
+        // [`framebuffer()`] can overlap.
+        unsafe { set_framebuffer(framebuffer) }
+    }
+}
+
+/// Records the load options of the image as the command line of the stub.
+fn record_command_line() {
+    let image_handle = Handle(IMAGE_HANDLE.load(Ordering::Relaxed));
+
+    // SAFETY:
+    //
+    // `system_table_ptr` was provided by the `efi_main` entry point and so according to the UEFI
+    // specification, the pointer must be valid.
+    let loaded_image = unsafe { loaded_image(image_handle, system_table_ptr()) };
+    let Some(loaded_image) = loaded_image else {
+        return;
+    };
+    if loaded_image.load_options.is_null() {
+        return;
+    }
+
+    // SAFETY:
+    //
+    // The UEFI specification states that `load_options` points to `load_options_size` bytes.
+    let load_options = unsafe {
+        slice::from_raw_parts(
+            loaded_image.load_options.cast::<u16>(),
+            u32_to_usize(loaded_image.load_options_size) / mem::size_of::<u16>(),
+        )
+    };
+    let Some(command_line) = decode_load_options(load_options) else {
+        return;
+    };
+
+    // SAFETY:
+    //
+    // Platform bring-up runs before the stub starts any other processor.
+    unsafe { set_command_line(command_line) }
+}
+
+/// Loads the modules listed on the command line of the image.
+fn load_modules() {
+    let image_handle = Handle(IMAGE_HANDLE.load(Ordering::Relaxed));
+    let system_table_ptr = system_table_ptr();
+
+    // SAFETY:
+    //
+    // `system_table_ptr` was provided by the `efi_main` entry point and so according to the UEFI
+    // specification, the pointer must be valid.
+    unsafe { load_command_line_modules(image_handle, system_table_ptr) }
+}
+
+/// Returns the pointer to the [`SystemTable`] provided to [`uefi_main()`].
+fn system_table_ptr() -> *mut SystemTable {
+    (*UEFI_SYSTEM_TABLE.lock())
+        .expect("UEFI system table was not provided")
+        .0
+        .as_ptr()
+}
+
+/// Returns the [`LoadedImageProtocol`] of the image identified by `image_handle`.
+///
+/// # Safety
+///
+/// `system_table_ptr` must point to a valid UEFI [`SystemTable`] and boot services must not have
+/// been exited.
+unsafe fn loaded_image(
+    image_handle: Handle,
+    system_table_ptr: *mut SystemTable,
+) -> Option<&'static LoadedImageProtocol> {
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `system_table_ptr` is valid.
+    let boot_services_ptr = unsafe { (*system_table_ptr).boot_services };
+    // SAFETY:
+    //
+    // `boot_services_ptr` must point to a valid [`BootServices`] table and that must contain a
+    // `handle_protocol` function pointer.
+    let handle_protocol_ptr = unsafe { (*boot_services_ptr).handle_protocol };
+
+    let guid = LoadedImageProtocol::GUID;
+    let mut interface = ptr::null_mut();
+    // SAFETY:
+    //
+    // The invariants of this function fulfill the invariants of `handle_protocol`.
+    let result = unsafe { handle_protocol_ptr(image_handle, &guid, &mut interface) };
+    if result != Status::SUCCESS {
+        return None;
+    }
+
+    // SAFETY:
+    //
+    // [`LoadedImageProtocol`] will be active until `exit_boot_services()` is called.
+    Some(unsafe { &*interface.cast::<LoadedImageProtocol>() })
+}
+
+/// Loads every file listed as a `module=<path> [command line]` argument in the load options of the
+/// image into memory and registers it as a [`Module`].
+///
+/// Arguments are separated by whitespace, so an argument that contains a command line must be
+/// surrounded by double quotes (e.g. `"module=\guest\kernel console=ttyS0"`).
+///
+/// # Safety
+///
+/// `system_table_ptr` must point to a valid UEFI [`SystemTable`] and boot services must not have
+/// been exited.
+unsafe fn load_command_line_modules(image_handle: Handle, system_table_ptr: *mut SystemTable) {
+    let mut module_arguments = command_line_arguments(command_line())
+        .filter_map(|argument| argument.strip_prefix("module="))
+        .peekable();
+    if module_arguments.peek().is_none() {
+        return;
+    }
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `system_table_ptr` is valid.
+    let loaded_image = unsafe { loaded_image(image_handle, system_table_ptr) };
+    let Some(loaded_image) = loaded_image else {
+        return;
+    };
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `system_table_ptr` is valid.
+    let boot_services_ptr = unsafe { (*system_table_ptr).boot_services };
+    // SAFETY:
+    //
+    // `boot_services_ptr` must point to a valid [`BootServices`] table and that must contain a
+    // `handle_protocol` function pointer.
+    let handle_protocol_ptr = unsafe { (*boot_services_ptr).handle_protocol };
+
+    let guid = SimpleFileSystemProtocol::GUID;
+    let mut interface = ptr::null_mut();
+    // SAFETY:
+    //
+    // The invariants of this function fulfill the invariants of `handle_protocol`.
+    let result = unsafe { handle_protocol_ptr(loaded_image.device_handle, &guid, &mut interface) };
+    if result != Status::SUCCESS {
+        crate::warn!("failed to load modules: boot volume does not support file access");
+        return;
+    }
+    let file_system = interface.cast::<SimpleFileSystemProtocol>();
+
+    let mut root = ptr::null_mut();
+    // SAFETY:
+    //
+    // [`SimpleFileSystemProtocol`] will be active until `exit_boot_services()` is called.
+    let open_volume = unsafe { (*file_system).open_volume };
+    // SAFETY:
+    //
+    // The arguments to `open_volume` are valid according to the UEFI specification.
+    let result = unsafe { open_volume(file_system, &mut root) };
+    if result != Status::SUCCESS {
+        crate::warn!("failed to load modules: error opening boot volume: {result:?}");
+        return;
+    }
+
+    for argument in module_arguments {
+        let (path, command_line) = argument
+            .split_once(char::is_whitespace)
+            .map_or((argument, ""), |(path, command_line)| {
+                (path, command_line.trim_start())
+            });
+
+        // SAFETY:
+        //
+        // `root` is a valid [`FileProtocol`] and `boot_services_ptr` is a valid [`BootServices`]
+        // table.
+        match unsafe { load_file(boot_services_ptr, root, path) } {
+            Ok(range) => {
+                let module = Module {
+                    range,
+                    name: path,
+                    command_line,
+                };
+                if !register_module(module) {
+                    crate::warn!("too many modules: ignoring {path:?}");
                 }
             }
+            Err(status) => crate::warn!("failed to load module {path:?}: {status:?}"),
         }
     }
 
     // SAFETY:
     //
-    // There are zero overlapping calls to [`set_uefi_system_table()`] and [`uefi_system_table()`].
-    unsafe { set_uefi_system_table(PhysicalAddress::new(usize_to_u64(system_table_ptr.addr()))) }
+    // `root` is a valid [`FileProtocol`].
+    let close = unsafe { (*root).close };
+    // SAFETY:
+    //
+    // `root` was opened by `open_volume` and is not used after this call.
+    let _ = unsafe { close(root) };
+}
 
-    crate::debug!("Image Start: {:#x}", crate::util::image_start());
-    match crate::stub_main() {
-        Ok(()) => Status::SUCCESS,
-        Err(error) => {
-            crate::warn!("{error}");
-            Status::LOAD_ERROR
+/// Loads the file located at `path` relative to `directory` into newly allocated pages and
+/// returns the [`PhysicalAddressRange`] that contains it.
+///
+/// # Safety
+///
+/// `boot_services_ptr` must point to a valid UEFI [`BootServices`] table and `directory` must be
+/// a valid [`FileProtocol`].
+unsafe fn load_file(
+    boot_services_ptr: *mut BootServices1_0,
+    directory: *mut FileProtocol,
+    path: &str,
+) -> Result<PhysicalAddressRange, Status> {
+    let mut path_buffer = [Char16(0); MAX_MODULE_PATH_LENGTH];
+    for (index, character) in path.encode_utf16().enumerate() {
+        // Leave space for the NUL terminator.
+        if index + 1 >= path_buffer.len() {
+            return Err(Status::BAD_BUFFER_SIZE);
         }
+
+        path_buffer[index] = Char16(character);
     }
+
+    // SAFETY:
+    //
+    // `directory` is a valid [`FileProtocol`].
+    let open = unsafe { (*directory).open };
+    let mut file = ptr::null_mut();
+    // SAFETY:
+    //
+    // The arguments to `open` are valid according to the UEFI specification.
+    let result = unsafe {
+        open(
+            directory,
+            &mut file,
+            path_buffer.as_ptr(),
+            OpenMode::READ,
+            FileAttributes(0),
+        )
+    };
+    if result != Status::SUCCESS {
+        return Err(result);
+    }
+
+    // SAFETY:
+    //
+    // `file` is a valid [`FileProtocol`] and `boot_services_ptr` is a valid [`BootServices`] table.
+    let result = unsafe { read_file(boot_services_ptr, file) };
+
+    // SAFETY:
+    //
+    // `file` is a valid [`FileProtocol`].
+    let close = unsafe { (*file).close };
+    // SAFETY:
+    //
+    // `file` was opened by `open` and is not used after this call.
+    let _ = unsafe { close(file) };
+
+    result
+}
+
+/// Reads the entirety of `file` into newly allocated pages and returns the
+/// [`PhysicalAddressRange`] that contains it.
+///
+/// # Safety
+///
+/// `boot_services_ptr` must point to a valid UEFI [`BootServices`] table and `file` must be a valid
+/// [`FileProtocol`].
+unsafe fn read_file(
+    boot_services_ptr: *mut BootServices1_0,
+    file: *mut FileProtocol,
+) -> Result<PhysicalAddressRange, Status> {
+    // SAFETY:
+    //
+    // `file` is a valid [`FileProtocol`].
+    let get_info = unsafe { (*file).get_info };
+    // SAFETY:
+    //
+    // `file` is a valid [`FileProtocol`].
+    let read = unsafe { (*file).read };
+
+    let guid = FileInfo::GUID;
+    let mut info_size = 0;
+    // SAFETY:
+    //
+    // The arguments to `get_info` are valid according to the UEFI specification.
+    let result = unsafe { get_info(file, &guid, &mut info_size, ptr::null_mut()) };
+    if result != Status::BUFFER_TOO_SMALL {
+        return Err(result);
+    }
+
+    let layout = Layout::from_size_align(info_size, mem::align_of::<FileInfo>())
+        .map_err(|_| Status::BAD_BUFFER_SIZE)?;
+    let info_buffer = allocate(layout).ok_or(Status::OUT_OF_RESOURCES)?;
+    // SAFETY:
+    //
+    // The arguments to `get_info` are valid according to the UEFI specification.
+    let result = unsafe {
+        get_info(
+            file,
+            &guid,
+            &mut info_size,
+            info_buffer.as_ptr().cast::<ffi::c_void>(),
+        )
+    };
+    // SAFETY:
+    //
+    // `get_info` succeeded, so `info_buffer` contains a valid [`FileInfo`].
+    let file_size = (result == Status::SUCCESS)
+        .then(|| unsafe { info_buffer.cast::<FileInfo>().as_ref().file_size });
+    // SAFETY:
+    //
+    // `info_buffer` was allocated by `allocate()` with `layout`.
+    unsafe { deallocate(info_buffer, layout) }
+    let file_size = file_size.ok_or(result)?;
+
+    // SAFETY:
+    //
+    // `boot_services_ptr` must point to a valid [`BootServices`] table and that must contain an
+    // `allocate_pages` function pointer.
+    let allocate_pages_ptr = unsafe { (*boot_services_ptr).allocate_pages };
+    // SAFETY:
+    //
+    // `boot_services_ptr` must point to a valid [`BootServices`] table and that must contain a
+    // `free_pages` function pointer.
+    let free_pages_ptr = unsafe { (*boot_services_ptr).free_pages };
+
+    let page_count = u64_to_usize_strict(file_size.div_ceil(UEFI_PAGE_SIZE).max(1));
+    let mut physical_address = 0;
+    // SAFETY:
+    //
+    // The arguments to `allocate_pages` are valid according to the UEFI specification.
+    let result = unsafe {
+        allocate_pages_ptr(
+            AllocateType::ANY_PAGES,
+            ::uefi::memory::MemoryType::LOADER_DATA,
+            page_count,
+            &mut physical_address,
+        )
+    };
+    if result != Status::SUCCESS {
+        return Err(result);
+    }
+
+    let mut read_size = u64_to_usize_strict(file_size);
+    // SAFETY:
+    //
+    // The arguments to `read` are valid according to the UEFI specification and the allocated
+    // pages are large enough to hold `read_size` bytes.
+    let result = unsafe {
+        read(
+            file,
+            &mut read_size,
+            ptr::with_exposed_provenance_mut::<ffi::c_void>(u64_to_usize_strict(physical_address)),
+        )
+    };
+    if result != Status::SUCCESS || usize_to_u64(read_size) != file_size {
+        // SAFETY:
+        //
+        // The pages were allocated by `allocate_pages` and are not used after this call.
+        let _ = unsafe { free_pages_ptr(physical_address, page_count) };
+        return Err(if result == Status::SUCCESS {
+            Status::END_OF_FILE
+        } else {
+            result
+        });
+    }
+
+    Ok(PhysicalAddressRange::new(
+        PhysicalAddress::new(physical_address),
+        file_size,
+    ))
+}
+
+/// Decodes the UCS-2 `load_options` of the image into a UTF-8 string that lives for the rest of
+/// the program.
+///
+/// The load options end at the first NUL character and invalid characters are replaced with
+/// [`char::REPLACEMENT_CHARACTER`].
+fn decode_load_options(load_options: &[u16]) -> Option<&'static str> {
+    let load_options = load_options
+        .split(|&character| character == 0)
+        .next()
+        .unwrap_or_default();
+    if load_options.is_empty() {
+        return None;
+    }
+
+    // Every UCS-2 character is encoded as at most 3 UTF-8 bytes.
+    let layout = Layout::array::<u8>(load_options.len().strict_mul(3)).ok()?;
+    let buffer = allocate(layout)?;
+    // SAFETY:
+    //
+    // `buffer` was allocated with `layout` and is never deallocated.
+    let buffer = unsafe { slice::from_raw_parts_mut(buffer.as_ptr(), layout.size()) };
+
+    let mut length = 0;
+    for character in char::decode_utf16(load_options.iter().copied()) {
+        let character = character.unwrap_or(char::REPLACEMENT_CHARACTER);
+        length += character.encode_utf8(&mut buffer[length..]).len();
+    }
+
+    core::str::from_utf8(&buffer[..length]).ok()
+}
+
+/// Returns an [`Iterator`] over the whitespace-separated arguments of `command_line`.
+///
+/// An argument that starts with a double quote extends until the next double quote, which allows
+/// it to contain whitespace.
+fn command_line_arguments(mut command_line: &str) -> impl Iterator<Item = &str> {
+    iter::from_fn(move || {
+        command_line = command_line.trim_start();
+        if command_line.is_empty() {
+            return None;
+        }
+
+        let (argument, remaining) = match command_line.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => command_line
+                .split_once(char::is_whitespace)
+                .unwrap_or((command_line, "")),
+        };
+        command_line = remaining;
+
+        Some(argument)
+    })
+}
+
+/// Returns the [`Framebuffer`] associated with the current mode of the first
+/// [`GraphicsOutputProtocol`] instance.
+///
+/// # Safety
+///
+/// `system_table_ptr` must point to a valid UEFI [`SystemTable`] and boot services must be active.
+unsafe fn graphics_output_framebuffer(system_table_ptr: *mut SystemTable) -> Option<Framebuffer> {
+    /// Returns the size and shift of the provided color component `mask`.
+    fn mask_layout(mask: u32) -> Option<(u8, u8)> {
+        let size = u8::try_from(mask.count_ones()).ok()?;
+        let shift = u8::try_from(mask.trailing_zeros() % u32::BITS).ok()?;
+
+        Some((size, shift))
+    }
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `system_table_ptr` is valid.
+    let boot_services_ptr = unsafe { (*system_table_ptr).boot_services };
+
+    // SAFETY:
+    //
+    // The invariants of this function ensure that `system_table_ptr` contains a valid UEFI
+    // [`BootServices`] table.
+    let boot_services_revision = unsafe { (*boot_services_ptr).header.revision };
+    if boot_services_revision.major() != 2 {
+        return None;
+    }
+
+    let boot_services_ptr = boot_services_ptr.cast::<BootServices2_0>();
+
+    // SAFETY:
+    //
+    // `boot_services_ptr` must point to a valid [`BootServices2_0`] table and that must contain a
+    // `locate_protocol` function pointer, since the revision check was successful.
+    let locate_protocol_ptr = unsafe { (*boot_services_ptr).v1_1.locate_protocol };
+
+    let guid = GraphicsOutputProtocol::GUID;
+    let mut interface = ptr::null_mut();
+    // SAFETY:
+    //
+    // The invariants of this function fulfill the invariants of `locate_protocol`.
+    let result = unsafe { locate_protocol_ptr(&guid, ptr::null_mut(), &mut interface) };
+    if result != Status::SUCCESS {
+        return None;
+    }
+
+    let interface = interface.cast::<GraphicsOutputProtocol>();
+    // SAFETY:
+    //
+    // [`GraphicsOutputProtocol`] will be active until `exit_boot_services()` is called.
+    let mode_ptr = unsafe { (*interface).mode };
+    // SAFETY:
+    //
+    // [`GraphicsOutputProtocol::mode`] is valid as long as the protocol is active.
+    let mode = unsafe { mode_ptr.as_ref()? };
+    // SAFETY:
+    //
+    // [`GraphicsOutputProtocolMode::info`] is valid as long as the mode is not changed.
+    let info = unsafe { mode.info.as_ref()? };
+
+    let (bpp, red, green, blue) = match info.pixel_format {
+        GraphicsPixelFormat::RED_GREEN_BLUE_RESERVED_8_BIT_PER_COLOR => {
+            (32, (8, 0), (8, 8), (8, 16))
+        }
+        GraphicsPixelFormat::BLUE_GREEN_RED_RESERVED_8_BIT_PER_COLOR => {
+            (32, (8, 16), (8, 8), (8, 0))
+        }
+        GraphicsPixelFormat::BIT_MASK => {
+            let masks = info.pixel_information;
+            let all = masks.red_mask | masks.green_mask | masks.blue_mask | masks.reserved_mask;
+            let bpp = u16::try_from(u32::BITS - all.leading_zeros()).ok()?;
+
+            (
+                bpp.next_multiple_of(8),
+                mask_layout(masks.red_mask)?,
+                mask_layout(masks.green_mask)?,
+                mask_layout(masks.blue_mask)?,
+            )
+        }
+        _ => return None,
+    };
+
+    Some(Framebuffer {
+        address: PhysicalAddress::new(mode.frame_buffer_base),
+        width: u64::from(info.horizontal_resolution),
+        height: u64::from(info.vertical_resolution),
+        pitch: u64::from(info.pixels_per_scan_line) * u64::from(bpp / 8),
+        bpp,
+        red_mask_size: red.0,
+        red_mask_shift: red.1,
+        green_mask_size: green.0,
+        green_mask_shift: green.1,
+        blue_mask_size: blue.0,
+        blue_mask_shift: blue.1,
+    })
 }
 
 /// Wrapper around the UEFI [`SystemTable`] to ensure its [`Sync`] and [`Send`] properties.
@@ -270,11 +922,22 @@ impl UefiImpl {
     }
 }
 
+/// Returns the UEFI memory type with which frames of `region_type` are allocated.
+fn uefi_memory_type(region_type: MemoryType) -> ::uefi::memory::MemoryType {
+    match region_type {
+        MemoryType::Executable => EXECUTABLE_MEMORY_TYPE,
+        MemoryType::ProtocolTable => PROTOCOL_TABLE_MEMORY_TYPE,
+        MemoryType::LogRing => LOG_RING_MEMORY_TYPE,
+        _ => ::uefi::memory::MemoryType::LOADER_CODE,
+    }
+}
+
 impl PhysicalMemoryManager for UefiImpl {
     fn allocate_frames(
         &self,
         count: u64,
         policy: AllocationPolicy,
+        region_type: MemoryType,
     ) -> Result<FrameRange, OutOfMemory> {
         let (allocation_type, mut physical_address) = match policy {
             AllocationPolicy::Any => (AllocateType::ANY_PAGES, 0),
@@ -305,7 +968,7 @@ impl PhysicalMemoryManager for UefiImpl {
         let status = unsafe {
             allocate_pages_ptr(
                 allocation_type,
-                ::uefi::memory::MemoryType::LOADER_CODE,
+                uefi_memory_type(region_type),
                 count,
                 &mut physical_address,
             )
@@ -358,8 +1021,6 @@ impl PhysicalMemoryManager for UefiImpl {
         &self,
         buffer: &'buffer mut [MemoryDescriptor],
     ) -> Result<MemoryMap<'buffer>, BufferTooSmall> {
-        use uefi::memory::MemoryType as UefiMemoryType;
-
         let mut memory_map = MEMORY_MAP.lock();
 
         memory_map.update();
@@ -370,30 +1031,9 @@ impl PhysicalMemoryManager for UefiImpl {
             });
         }
 
+        let buffer = &mut buffer[..total_entries_required];
         for (index, descriptor) in memory_map.descriptors().enumerate() {
-            let region_type = match descriptor.region_type {
-                UefiMemoryType::CONVENTIONAL => MemoryType::Free,
-                UefiMemoryType::LOADER_CODE => MemoryType::BootloaderReclaimable,
-                UefiMemoryType::LOADER_DATA => MemoryType::BootloaderReclaimable,
-                UefiMemoryType::BOOT_SERVICES_CODE => MemoryType::BootloaderReclaimable,
-                UefiMemoryType::BOOT_SERVICES_DATA => MemoryType::BootloaderReclaimable,
-                UefiMemoryType::UNUSABLE => MemoryType::Bad,
-                UefiMemoryType::ACPI_RECLAIM => MemoryType::AcpiReclaimable,
-                UefiMemoryType::ACPI_NVS => MemoryType::AcpiNonVolatile,
-                _ => MemoryType::Reserved,
-            };
-
-            let start = descriptor.physical_start;
-            let length = descriptor.number_of_pages;
-            let frame_range = FrameRange::new(
-                Frame::containing_address(PhysicalAddress::new(start)),
-                length,
-            );
-
-            buffer[index] = MemoryDescriptor {
-                range: frame_range.address_range(),
-                region_type,
-            };
+            buffer[index] = uefi_memory_descriptor(&descriptor);
         }
 
         Ok(MemoryMap::new(buffer, usize_to_u64(memory_map.key)))
@@ -565,6 +1205,47 @@ impl ProcessorManager for UefiImpl {
         usize_to_u64(self.processor_count)
     }
 
+    fn processor_descriptor(&self, processor_id: u64) -> ProcessorDescriptor {
+        let Some(mp_services) = MP_SERVICES.get() else {
+            return ProcessorDescriptor {
+                hardware_id: current_hardware_id(),
+                enabled: true,
+                proximity_domain: None,
+            };
+        };
+
+        let mut processor_number = u64_to_usize_strict(processor_id);
+        let mut processor_info = ProcessorInformation::default();
+        // SAFETY:
+        //
+        // The invariants of [`MpServicesProtocol::get_processor_info()`] have been fulfilled.
+        let result = unsafe {
+            (mp_services.get_processor_info)(
+                ptr::from_ref(*mp_services).cast_mut(),
+                &mut processor_number,
+                &mut processor_info,
+            )
+        };
+        assert!(
+            result == Status::SUCCESS,
+            "failed to retrieve information about processor {processor_id}"
+        );
+
+        // The UEFI Platform Initialization specification states that the processor ID is the
+        // APIC ID on `x86` and the value of `MPIDR_EL1` on `aarch64`.
+        #[cfg(target_arch = "aarch64")]
+        let hardware_id =
+            processor_info.processor_id & crate::arch::arch_specific::MPIDR_AFFINITY_MASK;
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        let hardware_id = processor_info.processor_id;
+
+        ProcessorDescriptor {
+            hardware_id,
+            enabled: (processor_info.status_flag.0 & StatusFlag::ENABLED.0) != 0,
+            proximity_domain: None,
+        }
+    }
+
     fn run_on_all_processors(&self, procedure: Procedure, argument: *mut ()) {
         if self.processor_count() == 1 {
             procedure(self.main_processor_id(), argument);
@@ -22,7 +22,9 @@ const DEPENDENCIES: &[&str] = &[
     "memory",
     // Architecture Support.
     "aarch64",
+    "gic",
     "x86",
+    "x86_decoder",
     // File Formats.
     "elf",
     "pe",
@@ -34,6 +36,8 @@ const DEPENDENCIES: &[&str] = &[
     "device_tree",
     // Utilities.
     "font",
+    "initgraph",
+    "log_filter",
     "stub_api",
     "sync",
     // Tools.